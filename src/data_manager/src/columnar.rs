@@ -0,0 +1,70 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Row, Values};
+use representation::{unpack_raw, Binary, Datum};
+
+/// name of the storage object that keeps values of the column at `index`
+pub(crate) fn column_object(table_name: &str, index: usize) -> String {
+    format!("{}#{}", table_name, index)
+}
+
+/// splits rows into `columns` vectors of records, each of them holds values of a single column
+pub(crate) fn split(rows: Vec<Row>, columns: usize) -> Vec<Vec<Row>> {
+    let mut split = vec![Vec::with_capacity(rows.len()); columns];
+    for (key, values) in rows {
+        let mut data = unpack_raw(values.to_bytes()).into_iter();
+        for column in split.iter_mut() {
+            let datum = data.next().unwrap_or(Datum::Null);
+            column.push((key.clone(), Binary::pack(&[datum])));
+        }
+    }
+    split
+}
+
+/// stitches records of separately stored columns back into rows, columns are expected
+/// to have records with the same keys in the same order
+pub(crate) fn assemble(columns: Vec<Vec<Row>>) -> Vec<Row> {
+    let mut columns = columns.into_iter();
+    let keys = match columns.next() {
+        Some(first) => first,
+        None => return vec![],
+    };
+    let rest = columns.collect::<Vec<_>>();
+    keys.into_iter()
+        .enumerate()
+        .map(|(index, (key, values))| {
+            let size = rest.iter().fold(values.to_bytes().len(), |acc, column| {
+                acc + column[index].1.to_bytes().len()
+            });
+            let mut data = Vec::with_capacity(size);
+            data.extend_from_slice(values.to_bytes());
+            for column in rest.iter() {
+                data.extend_from_slice(column[index].1.to_bytes());
+            }
+            (key, Values::with_data(data))
+        })
+        .collect()
+}
+
+/// keeps only values of `columns` in the given order
+pub(crate) fn project(values: &Values, columns: &[usize]) -> Values {
+    let data = values.unpack();
+    Binary::pack(
+        &columns
+            .iter()
+            .map(|index| data.get(*index).cloned().unwrap_or(Datum::Null))
+            .collect::<Vec<_>>(),
+    )
+}
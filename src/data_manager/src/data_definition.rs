@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{ColumnDefinition, Database, DropSchemaError, DropStrategy, InitStatus, PersistentDatabase};
+use crate::{ColumnDefinition, Database, DropSchemaError, DropStrategy, InitStatus, PersistentDatabase, StorageLayout};
 use kernel::{SystemError, SystemResult};
 use representation::{Binary, Datum};
+use serde::{de::DeserializeOwned, Serialize};
 use sql_types::SqlType;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -25,10 +26,10 @@ use std::{
     },
 };
 
-const SYSTEM_CATALOG: &'_ str = "system";
+pub(crate) const SYSTEM_CATALOG: &'_ str = "system";
 // CREATE SCHEMA DEFINITION_SCHEMA
 //      AUTHORIZATION DEFINITION_SCHEMA
-const DEFINITION_SCHEMA: &'_ str = "DEFINITION_SCHEMA";
+pub(crate) const DEFINITION_SCHEMA: &'_ str = "DEFINITION_SCHEMA";
 //CREATE TABLE CATALOG_NAMES (
 //     CATALOG_NAME    INFORMATION_SCHEMA.SQL_IDENTIFIER,
 //                     CONSTRAINT CATALOG_NAMES_PRIMARY_KEY
@@ -262,6 +263,22 @@ const TABLES_TABLE: &'_ str = "TABLES";
 //         )
 // )
 const COLUMNS_TABLE: &'_ str = "COLUMNS";
+// definitions that the data manager keeps in memory are stored serialized, one record
+// for each object that they belong to
+pub(crate) const FOREIGN_KEYS_TABLE: &'_ str = "FOREIGN_KEYS";
+pub(crate) const USERS_TABLE: &'_ str = "USERS";
+pub(crate) const FUNCTIONS_TABLE: &'_ str = "FUNCTIONS";
+pub(crate) const PARTITION_KEYS_TABLE: &'_ str = "PARTITION_KEYS";
+pub(crate) const PARTITIONS_TABLE: &'_ str = "PARTITIONS";
+pub(crate) const TRIGGERS_TABLE: &'_ str = "TRIGGERS";
+pub(crate) const DEFINITION_TABLES: [&'_ str; 6] = [
+    FOREIGN_KEYS_TABLE,
+    USERS_TABLE,
+    FUNCTIONS_TABLE,
+    PARTITION_KEYS_TABLE,
+    PARTITIONS_TABLE,
+    TRIGGERS_TABLE,
+];
 
 #[allow(dead_code)]
 fn catalog_names_types() -> [ColumnDefinition; 1] {
@@ -283,12 +300,14 @@ fn schemata_table_types() -> [ColumnDefinition; 2] {
 /// TABLE_CATALOG   varchar(255)
 /// TABLE_SCHEMA    varchar(255)
 /// TABLE_NAME      varchar(255)
+/// STORAGE_LAYOUT  varchar(255)
 #[allow(dead_code)]
-fn tables_table_types() -> [ColumnDefinition; 3] {
+fn tables_table_types() -> [ColumnDefinition; 4] {
     [
         ColumnDefinition::new("TABLE_CATALOG", SqlType::VarChar(255)),
         ColumnDefinition::new("TABLE_SCHEMA", SqlType::VarChar(255)),
         ColumnDefinition::new("TABLE_NAME", SqlType::VarChar(255)),
+        ColumnDefinition::new("STORAGE_LAYOUT", SqlType::VarChar(255)),
     ]
 }
 
@@ -309,6 +328,39 @@ fn columns_table_types() -> [ColumnDefinition; 5] {
     ]
 }
 
+fn column_definition(data: &[Datum]) -> ColumnDefinition {
+    let name = data[3].as_str();
+    let sql_type = data[4].as_sql_type();
+    let column = match data.get(6) {
+        Some(unique) if unique.as_bool() => ColumnDefinition::unique(name, sql_type),
+        _ => ColumnDefinition::new(name, sql_type),
+    };
+    let column = column.with_ordinal_position(data[5].as_u64() as u16);
+    let column = match data.get(7) {
+        Some(Datum::Null) | None => column,
+        Some(expression) => column.with_generated(expression.as_str().to_owned()),
+    };
+    let column = match data.get(8) {
+        Some(Datum::Null) | None => column,
+        Some(expression) => column.with_default(expression.as_str().to_owned()),
+    };
+    match data.get(9) {
+        Some(not_null) if not_null.as_bool() => column.with_not_null(),
+        _ => column,
+    }
+}
+
+// tables that were created before layouts were introduced do not have it stored
+fn storage_layout(data: &[Datum]) -> StorageLayout {
+    match data.get(3) {
+        Some(layout) if layout.as_str() == COLUMNAR_LAYOUT => StorageLayout::Columnar,
+        _ => StorageLayout::Row,
+    }
+}
+
+const ROW_LAYOUT: &'_ str = "ROW";
+const COLUMNAR_LAYOUT: &'_ str = "COLUMNAR";
+
 type InnerId = u64;
 type InnerCatalogId = Option<InnerId>;
 type InnerFullSchemaId = Option<(InnerId, Option<InnerId>)>;
@@ -352,6 +404,8 @@ impl Catalog {
     }
 
     fn add_schema(&self, schema_id: InnerId, schema_name: &str) -> Arc<Schema> {
+        // schemas created after restart should not reuse ids of the restored ones
+        self.schema_id_generator.fetch_max(schema_id + 1, Ordering::SeqCst);
         let schema = Arc::new(Schema::new(schema_id));
         self.schemas
             .write()
@@ -368,6 +422,13 @@ impl Catalog {
             .map(|schema| schema.id())
     }
 
+    fn rename_schema(&self, schema_name: &str, new_name: &str) -> Option<Arc<Schema>> {
+        let mut schemas = self.schemas.write().expect("to acquire write lock");
+        let schema = schemas.remove(schema_name)?;
+        schemas.insert(new_name.to_owned(), schema.clone());
+        Some(schema)
+    }
+
     fn schema(&self, schema_name: &str) -> Option<Arc<Schema>> {
         self.schemas
             .read()
@@ -409,9 +470,14 @@ impl Schema {
         self.id
     }
 
-    fn create_table(&self, table_name: &str, column_definitions: &[ColumnDefinition]) -> Arc<Table> {
+    fn create_table(
+        &self,
+        table_name: &str,
+        column_definitions: &[ColumnDefinition],
+        layout: StorageLayout,
+    ) -> Arc<Table> {
         let table_id = self.table_id_generator.fetch_add(1, Ordering::SeqCst);
-        let table = Arc::new(Table::new(table_id, column_definitions));
+        let table = Arc::new(Table::new(table_id, column_definitions, layout));
         self.tables
             .write()
             .expect("to acquire write lock")
@@ -425,10 +491,12 @@ impl Schema {
         table_name: &str,
         column_definitions: BTreeMap<InnerId, ColumnDefinition>,
         max_id: InnerId,
+        layout: StorageLayout,
     ) {
+        self.table_id_generator.fetch_max(table_id + 1, Ordering::SeqCst);
         self.tables.write().expect("to acquire write lock").insert(
             table_name.to_owned(),
-            Arc::new(Table::restore(table_id, column_definitions, max_id)),
+            Arc::new(Table::restore(table_id, column_definitions, max_id, layout)),
         );
     }
 
@@ -474,14 +542,16 @@ struct Table {
     id: InnerId,
     columns: RwLock<BTreeMap<InnerId, ColumnDefinition>>,
     column_id_generator: AtomicU64,
+    layout: StorageLayout,
 }
 
 impl Table {
-    fn new(id: InnerId, column_definitions: &[ColumnDefinition]) -> Table {
+    fn new(id: InnerId, column_definitions: &[ColumnDefinition], layout: StorageLayout) -> Table {
         let table = Table {
             id,
             columns: RwLock::default(),
             column_id_generator: AtomicU64::default(),
+            layout,
         };
         for column_definition in column_definitions.to_vec().into_iter() {
             table.add_column(column_definition)
@@ -489,11 +559,17 @@ impl Table {
         table
     }
 
-    fn restore(id: InnerId, column_definitions: BTreeMap<InnerId, ColumnDefinition>, max_id: InnerId) -> Table {
+    fn restore(
+        id: InnerId,
+        column_definitions: BTreeMap<InnerId, ColumnDefinition>,
+        max_id: InnerId,
+        layout: StorageLayout,
+    ) -> Table {
         Table {
             id,
             columns: RwLock::new(column_definitions),
             column_id_generator: AtomicU64::new(max_id),
+            layout,
         }
     }
 
@@ -501,21 +577,40 @@ impl Table {
         self.id
     }
 
+    fn layout(&self) -> StorageLayout {
+        self.layout
+    }
+
     fn add_column(&self, column_definition: ColumnDefinition) {
         let column_id = self.column_id_generator.fetch_add(1, Ordering::SeqCst);
-        self.columns
-            .write()
-            .expect("to acquire write lock")
-            .insert(column_id, column_definition);
+        let mut columns = self.columns.write().expect("to acquire write lock");
+        let column_definition = if column_definition.ordinal_position() == 0 {
+            let last = columns.values().map(ColumnDefinition::ordinal_position).max();
+            column_definition.with_ordinal_position(last.unwrap_or(0) + 1)
+        } else {
+            column_definition
+        };
+        columns.insert(column_id, column_definition);
+    }
+
+    fn rename_column(&self, column_name: &str, new_name: &str) -> Option<(InnerId, ColumnDefinition)> {
+        let mut columns = self.columns.write().expect("to acquire write lock");
+        let (id, column) = columns.iter_mut().find(|(_id, column)| column.has_name(column_name))?;
+        *column = column.clone().with_name(new_name);
+        Some((*id, column.clone()))
     }
 
+    // columns are ordered by their ordinal position, not by the order they were stored in
     fn columns(&self) -> Vec<(InnerId, ColumnDefinition)> {
-        self.columns
+        let mut columns = self
+            .columns
             .read()
             .expect("to acquire read lock")
             .iter()
             .map(|(id, definition)| (*id, definition.clone()))
-            .collect()
+            .collect::<Vec<_>>();
+        columns.sort_by_key(|(_id, definition)| definition.ordinal_position());
+        columns
     }
 }
 
@@ -582,6 +677,13 @@ impl DataDefinition {
                     .expect("no io error")
                     .expect("no platform error")
                     .expect("table COLUMNS is created");
+                for table_name in DEFINITION_TABLES.iter() {
+                    system_catalog
+                        .create_object(DEFINITION_SCHEMA, table_name)
+                        .expect("no io error")
+                        .expect("no platform error")
+                        .expect("definition table is created");
+                }
                 (HashMap::new(), 0)
             }
             _ => {
@@ -619,6 +721,55 @@ impl DataDefinition {
         }
     }
 
+    /// replaces definition stored under the key, nothing is stored in memory
+    pub(crate) fn save_definition<T: Serialize>(&self, table_name: &str, key: &[Datum], definition: &T) {
+        if let Some(system_catalog) = self.system_catalog.as_ref() {
+            system_catalog
+                .write(
+                    DEFINITION_SCHEMA,
+                    table_name,
+                    vec![(
+                        Binary::pack(key),
+                        Binary::with_data(bincode::serialize(definition).expect("To serialize definition")),
+                    )],
+                )
+                .expect("no io error")
+                .expect("no platform error")
+                .expect("to save definition");
+        }
+    }
+
+    pub(crate) fn remove_definition(&self, table_name: &str, key: &[Datum]) {
+        if let Some(system_catalog) = self.system_catalog.as_ref() {
+            system_catalog
+                .delete(DEFINITION_SCHEMA, table_name, vec![Binary::pack(key)])
+                .expect("no io error")
+                .expect("no platform error")
+                .expect("to remove definition");
+        }
+    }
+
+    /// stored definitions with their keys
+    pub(crate) fn definitions<T: DeserializeOwned>(&self, table_name: &str) -> Vec<(Binary, T)> {
+        match self.system_catalog.as_ref() {
+            None => vec![],
+            Some(system_catalog) => system_catalog
+                .read(DEFINITION_SCHEMA, table_name)
+                .expect("no io error")
+                .expect("no platform error")
+                .expect("to have definition table")
+                .map(Result::unwrap)
+                .map(Result::unwrap)
+                .map(|(key, definition)| {
+                    (
+                        key,
+                        bincode::deserialize(definition.to_bytes()).expect("To deserialize definition"),
+                    )
+                })
+                .collect(),
+        }
+    }
+
     #[allow(dead_code)]
     pub(crate) fn catalog_exists(&self, catalog_name: &str) -> InnerCatalogId {
         self.catalogs
@@ -815,16 +966,21 @@ impl DataDefinition {
         catalog_name: &str,
         schema_name: &str,
         strategy: DropStrategy,
-    ) -> Result<(), DropSchemaError> {
+    ) -> SystemResult<Result<(), DropSchemaError>> {
         let catalog = match self.catalog(catalog_name) {
             Some(catalog) => catalog,
-            None => return Err(DropSchemaError::CatalogDoesNotExist),
+            None => {
+                return Err(SystemError::catalog_corruption(format!(
+                    "catalog {:?} does not exist",
+                    catalog_name
+                )))
+            }
         };
         let schema = match catalog.schema(schema_name) {
             Some(schema) => schema,
-            None => return Err(DropSchemaError::DoesNotExist),
+            None => return Ok(Err(DropSchemaError::DoesNotExist)),
         };
-        match strategy {
+        Ok(match strategy {
             DropStrategy::Restrict => {
                 if schema.empty() {
                     let schema_id = catalog.remove_schema(schema_name);
@@ -921,7 +1077,47 @@ impl DataDefinition {
                     }
                 }
             }
+        })
+    }
+
+    /// `false` when the schema does not exist
+    pub(crate) fn rename_schema(&self, catalog_name: &str, schema_name: &str, new_name: &str) -> bool {
+        let catalog = match self.catalog(catalog_name) {
+            Some(catalog) => catalog,
+            None => return false,
+        };
+        let schema = match catalog.rename_schema(schema_name, new_name) {
+            Some(schema) => schema,
+            None => return false,
+        };
+        if let Some(system_catalog) = self.system_catalog.as_ref() {
+            // name of the schema is the second value of SCHEMATA, TABLES and COLUMNS records
+            for table in &[SCHEMATA_TABLE, TABLES_TABLE, COLUMNS_TABLE] {
+                let renamed = system_catalog
+                    .read(DEFINITION_SCHEMA, table)
+                    .expect("no io error")
+                    .expect("no platform error")
+                    .expect("to have system table")
+                    .map(Result::unwrap)
+                    .map(Result::unwrap)
+                    .filter(|(record_id, _data)| {
+                        let ids = record_id.unpack();
+                        ids[0].as_u64() == catalog.id() && ids[1].as_u64() == schema.id()
+                    })
+                    .map(|(record_id, data)| {
+                        let mut data = data.unpack();
+                        data[1] = Datum::from_str(new_name);
+                        (record_id, Binary::pack(&data))
+                    })
+                    .collect();
+                system_catalog
+                    .write(DEFINITION_SCHEMA, table, renamed)
+                    .expect("no io error")
+                    .expect("no platform error")
+                    .expect("to rename schema");
+            }
         }
+        true
     }
 
     pub(crate) fn schemas(&self, catalog_name: &str) -> Vec<(InnerId, String)> {
@@ -970,7 +1166,7 @@ impl DataDefinition {
                         .map(Result::unwrap)
                         .map(Result::unwrap)
                         .map(|(record_id, columns)| {
-                            let id = record_id.unpack()[0].as_u64();
+                            let id = record_id.unpack()[1].as_u64();
                             let name = columns.unpack()[1].as_str().to_owned();
                             (id, name)
                         })
@@ -997,17 +1193,17 @@ impl DataDefinition {
                         .map(Result::unwrap)
                         .map(Result::unwrap)
                         .map(|(record_id, data)| {
-                            let id = record_id.unpack()[0].as_u64();
+                            let id = record_id.unpack()[2].as_u64();
                             let data = data.unpack();
                             let schema = data[1].as_str().to_owned();
                             let table = data[2].as_str().to_owned();
-                            (id, schema, table)
+                            (id, schema, table, storage_layout(&data))
                         })
-                        .filter(|(_id, schema, table)| schema == schema_name && table == table_name)
-                        .map(|(id, _schema, _table)| id)
+                        .filter(|(_id, schema, table, _layout)| schema == schema_name && table == table_name)
+                        .map(|(id, _schema, _table, layout)| (id, layout))
                         .next();
                     match table_info {
-                        Some(table_id) => {
+                        Some((table_id, layout)) => {
                             let mut max_id = 0;
                             let table_columns = system_catalog
                                 .read(DEFINITION_SCHEMA, COLUMNS_TABLE)
@@ -1021,19 +1217,14 @@ impl DataDefinition {
                                     let data = data.unpack();
                                     let schema = data[1].as_str().to_owned();
                                     let table = data[2].as_str().to_owned();
-                                    let column = data[3].as_str().to_owned();
-                                    let sql_type = data[4].as_sql_type();
+                                    let column = column_definition(&data);
                                     max_id = max_id.max(id);
-                                    (id, schema, table, column, sql_type)
-                                })
-                                .filter(|(_id, schema, table, _column, _sql_type)| {
-                                    schema == schema_name && table == table_name
-                                })
-                                .map(|(id, _schema, _table, column, sql_type)| {
-                                    (id, ColumnDefinition::new(column.as_str(), sql_type))
+                                    (id, schema, table, column)
                                 })
+                                .filter(|(_id, schema, table, _column)| schema == schema_name && table == table_name)
+                                .map(|(id, _schema, _table, column)| (id, column))
                                 .collect::<BTreeMap<_, _>>();
-                            schema.add_table(table_id, table_name, table_columns, max_id);
+                            schema.add_table(table_id, table_name, table_columns, max_id, layout);
                             Some(table_id)
                         }
                         None => None,
@@ -1049,12 +1240,30 @@ impl DataDefinition {
         result
     }
 
+    #[cfg(test)]
     pub(crate) fn create_table(
         &self,
         catalog_name: &str,
         schema_name: &str,
         table_name: &str,
         column_definitions: &[ColumnDefinition],
+    ) -> InnerFullTableId {
+        self.create_table_with_layout(
+            catalog_name,
+            schema_name,
+            table_name,
+            column_definitions,
+            StorageLayout::Row,
+        )
+    }
+
+    pub(crate) fn create_table_with_layout(
+        &self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+        column_definitions: &[ColumnDefinition],
+        layout: StorageLayout,
     ) -> InnerFullTableId {
         let catalog = match self.catalog(catalog_name) {
             Some(catalog) => catalog,
@@ -1064,7 +1273,7 @@ impl DataDefinition {
             Some(schema) => schema,
             None => return Some((catalog.id(), None)),
         };
-        let created_table = schema.create_table(table_name, column_definitions);
+        let created_table = schema.create_table(table_name, column_definitions, layout);
         if let Some(system_catalog) = self.system_catalog.as_ref() {
             system_catalog
                 .write(
@@ -1080,6 +1289,10 @@ impl DataDefinition {
                             Datum::from_str(catalog_name),
                             Datum::from_str(schema_name),
                             Datum::from_str(table_name),
+                            Datum::from_str(match layout {
+                                StorageLayout::Row => ROW_LAYOUT,
+                                StorageLayout::Columnar => COLUMNAR_LAYOUT,
+                            }),
                         ]),
                     )],
                 )
@@ -1104,7 +1317,17 @@ impl DataDefinition {
                                 Datum::from_str(table_name),
                                 Datum::from_str(column.name().as_str()),
                                 Datum::from_sql_type(column.sql_type()),
-                                Datum::UInt64(id),
+                                Datum::UInt64(column.ordinal_position() as u64),
+                                Datum::from_bool(column.is_unique()),
+                                match column.generated() {
+                                    Some(expression) => Datum::from_str(expression),
+                                    None => Datum::from_null(),
+                                },
+                                match column.default_expression() {
+                                    Some(expression) => Datum::from_str(expression),
+                                    None => Datum::from_null(),
+                                },
+                                Datum::from_bool(column.is_not_null()),
                             ]),
                         )],
                     )
@@ -1145,6 +1368,70 @@ impl DataDefinition {
         }
     }
 
+    /// `false` when the table or its column does not exist
+    pub(crate) fn rename_column(
+        &self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+        column_name: &str,
+        new_name: &str,
+    ) -> bool {
+        let catalog = match self.catalog(catalog_name) {
+            Some(catalog) => catalog,
+            None => return false,
+        };
+        let schema = match catalog.schema(schema_name) {
+            Some(schema) => schema,
+            None => return false,
+        };
+        let table = match schema.table(table_name) {
+            Some(table) => table,
+            None => return false,
+        };
+        let (column_id, column) = match table.rename_column(column_name, new_name) {
+            Some(renamed) => renamed,
+            None => return false,
+        };
+        if let Some(system_catalog) = self.system_catalog.as_ref() {
+            system_catalog
+                .write(
+                    DEFINITION_SCHEMA,
+                    COLUMNS_TABLE,
+                    vec![(
+                        Binary::pack(&[
+                            Datum::from_u64(catalog.id()),
+                            Datum::from_u64(schema.id()),
+                            Datum::from_u64(table.id()),
+                            Datum::from_u64(column_id),
+                        ]),
+                        Binary::pack(&[
+                            Datum::from_str(catalog_name),
+                            Datum::from_str(schema_name),
+                            Datum::from_str(table_name),
+                            Datum::from_str(column.name().as_str()),
+                            Datum::from_sql_type(column.sql_type()),
+                            Datum::UInt64(column.ordinal_position() as u64),
+                            Datum::from_bool(column.is_unique()),
+                            match column.generated() {
+                                Some(expression) => Datum::from_str(expression),
+                                None => Datum::from_null(),
+                            },
+                            match column.default_expression() {
+                                Some(expression) => Datum::from_str(expression),
+                                None => Datum::from_null(),
+                            },
+                            Datum::from_bool(column.is_not_null()),
+                        ]),
+                    )],
+                )
+                .expect("no io error")
+                .expect("no platform error")
+                .expect("to save column");
+        }
+        true
+    }
+
     pub(crate) fn tables(&self, catalog_name: &str, schema_name: &str) -> Vec<(InnerId, String)> {
         let catalog = match self.catalog(catalog_name) {
             Some(catalog) => catalog,
@@ -1155,7 +1442,7 @@ impl DataDefinition {
             None => return vec![],
         };
         if let Some(system_catalog) = self.system_catalog.as_ref() {
-            for (table_id, _catalog, _schema, table) in system_catalog
+            for (table_id, _catalog, _schema, table, layout) in system_catalog
                 .read(DEFINITION_SCHEMA, TABLES_TABLE)
                 .expect("no io error")
                 .expect("no platform error")
@@ -1163,14 +1450,14 @@ impl DataDefinition {
                 .map(Result::unwrap)
                 .map(Result::unwrap)
                 .map(|(record_id, columns)| {
-                    let id = record_id.unpack()[1].as_u64();
+                    let id = record_id.unpack()[2].as_u64();
                     let columns = columns.unpack();
                     let catalog = columns[0].as_str().to_owned();
                     let schema = columns[1].as_str().to_owned();
                     let table = columns[2].as_str().to_owned();
-                    (id, catalog, schema, table)
+                    (id, catalog, schema, table, storage_layout(&columns))
                 })
-                .filter(|(_id, catalog, schema, _table)| catalog == catalog_name && schema == schema_name)
+                .filter(|(_id, catalog, schema, _table, _layout)| catalog == catalog_name && schema == schema_name)
             {
                 let mut max_id = 0;
                 let table_columns = system_catalog
@@ -1185,17 +1472,16 @@ impl DataDefinition {
                         let data = data.unpack();
                         let schema = data[1].as_str().to_owned();
                         let table = data[2].as_str().to_owned();
-                        let column = data[3].as_str().to_owned();
-                        let sql_type = data[4].as_sql_type();
+                        let column = column_definition(&data);
                         max_id = max_id.max(id);
-                        (id, schema, table, column, sql_type)
+                        (id, schema, table, column)
                     })
-                    .filter(|(_id, schema, _table, _column, _sql_type)| schema == schema_name)
-                    .map(|(id, _schema, _table, column, sql_type)| {
-                        (id, ColumnDefinition::new(column.as_str(), sql_type))
+                    .filter(|(_id, column_schema, column_table, _column)| {
+                        column_schema == schema_name && column_table == &table
                     })
+                    .map(|(id, _schema, _table, column)| (id, column))
                     .collect::<BTreeMap<_, _>>();
-                schema.add_table(table_id, table.as_str(), table_columns, max_id);
+                schema.add_table(table_id, table.as_str(), table_columns, max_id, layout);
             }
         }
         schema.tables()
@@ -1227,6 +1513,15 @@ impl DataDefinition {
         }
     }
 
+    pub(crate) fn table_layout(&self, catalog_name: &str, schema_name: &str, table_name: &str) -> StorageLayout {
+        self.table_exists(catalog_name, schema_name, table_name);
+        self.catalog(catalog_name)
+            .and_then(|catalog| catalog.schema(schema_name))
+            .and_then(|schema| schema.table(table_name))
+            .map(|table| table.layout())
+            .unwrap_or(StorageLayout::Row)
+    }
+
     fn catalog(&self, catalog_name: &str) -> Option<Arc<Catalog>> {
         self.catalogs
             .read()
@@ -1337,11 +1632,15 @@ mod tests {
 
         assert_eq!(
             data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Restrict),
-            Err(DropSchemaError::CatalogDoesNotExist)
+            Err(SystemError::catalog_corruption(
+                "catalog \"catalog_name\" does not exist".to_owned()
+            ))
         );
         assert_eq!(
             data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Cascade),
-            Err(DropSchemaError::CatalogDoesNotExist)
+            Err(SystemError::catalog_corruption(
+                "catalog \"catalog_name\" does not exist".to_owned()
+            ))
         );
     }
 
@@ -1352,11 +1651,11 @@ mod tests {
         data_definition.create_catalog("catalog_name");
         assert_eq!(
             data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Restrict),
-            Err(DropSchemaError::DoesNotExist)
+            Ok(Err(DropSchemaError::DoesNotExist))
         );
         assert_eq!(
             data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Cascade),
-            Err(DropSchemaError::DoesNotExist)
+            Ok(Err(DropSchemaError::DoesNotExist))
         );
     }
 
@@ -1381,7 +1680,7 @@ mod tests {
 
         assert_eq!(
             data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Restrict),
-            Ok(())
+            Ok(Ok(()))
         );
 
         assert!(matches!(
@@ -1400,7 +1699,7 @@ mod tests {
 
         assert_eq!(
             data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Restrict),
-            Err(DropSchemaError::HasDependentObjects)
+            Ok(Err(DropSchemaError::HasDependentObjects))
         );
 
         assert!(matches!(
@@ -1423,7 +1722,7 @@ mod tests {
 
         assert_eq!(
             data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Cascade),
-            Ok(())
+            Ok(Ok(()))
         );
 
         assert!(matches!(
@@ -1462,6 +1761,38 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn added_column_is_the_last_one() {
+        let data_definition = DataDefinition::in_memory();
+
+        data_definition.create_catalog("catalog_name");
+        data_definition.create_schema("catalog_name", "schema_name");
+        data_definition.create_table(
+            "catalog_name",
+            "schema_name",
+            "table_name",
+            &[
+                ColumnDefinition::new("col_1", SqlType::Bool),
+                ColumnDefinition::new("col_2", SqlType::Bool),
+            ],
+        );
+        data_definition
+            .catalog("catalog_name")
+            .and_then(|catalog| catalog.schema("schema_name"))
+            .and_then(|schema| schema.table("table_name"))
+            .expect("table exists")
+            .add_column(ColumnDefinition::new("col_3", SqlType::Bool));
+
+        assert_eq!(
+            data_definition.table_columns("catalog_name", "schema_name", "table_name"),
+            vec![
+                ColumnDefinition::new("col_1", SqlType::Bool).with_ordinal_position(1),
+                ColumnDefinition::new("col_2", SqlType::Bool).with_ordinal_position(2),
+                ColumnDefinition::new("col_3", SqlType::Bool).with_ordinal_position(3),
+            ]
+        );
+    }
+
     #[test]
     fn drop_table() {
         let data_definition = DataDefinition::in_memory();
@@ -1593,7 +1924,7 @@ mod tests {
             ));
             assert_eq!(
                 data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Restrict),
-                Ok(())
+                Ok(Ok(()))
             );
             assert!(matches!(
                 data_definition.schema_exists("catalog_name", "schema_name"),
@@ -1653,11 +1984,11 @@ mod tests {
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name", "schema_name_1", "table_name"),
-                vec![ColumnDefinition::new("col_1", SqlType::Integer(0))]
+                vec![ColumnDefinition::new("col_1", SqlType::Integer(0)).with_ordinal_position(1)]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name", "schema_name_2", "table_name"),
-                vec![ColumnDefinition::new("col_1", SqlType::SmallInt(0))]
+                vec![ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1)]
             );
             drop(data_definition);
 
@@ -1665,11 +1996,71 @@ mod tests {
 
             assert_eq!(
                 data_definition.table_columns("catalog_name", "schema_name_1", "table_name"),
-                vec![ColumnDefinition::new("col_1", SqlType::Integer(0))]
+                vec![ColumnDefinition::new("col_1", SqlType::Integer(0)).with_ordinal_position(1)]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name", "schema_name_2", "table_name"),
-                vec![ColumnDefinition::new("col_1", SqlType::SmallInt(0))]
+                vec![ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1)]
+            );
+        }
+
+        #[rstest::rstest]
+        fn storage_preserve_generated_column_after_restart(storage_path: (DataDefinition, PathBuf)) {
+            let (data_definition, path) = storage_path;
+            data_definition.create_catalog("catalog_name");
+            data_definition.create_schema("catalog_name", "schema_name");
+            data_definition.create_table(
+                "catalog_name",
+                "schema_name",
+                "table_name",
+                &[
+                    ColumnDefinition::new("col_1", SqlType::Integer(0)),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_generated("col_1 * 2".to_owned()),
+                ],
+            );
+            drop(data_definition);
+
+            let data_definition = DataDefinition::persistent(&path).expect("create persistent data definition");
+
+            assert_eq!(
+                data_definition.table_columns("catalog_name", "schema_name", "table_name"),
+                vec![
+                    ColumnDefinition::new("col_1", SqlType::Integer(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0))
+                        .with_generated("col_1 * 2".to_owned())
+                        .with_ordinal_position(2)
+                ]
+            );
+        }
+
+        #[rstest::rstest]
+        fn storage_preserve_column_default_and_not_null_after_restart(storage_path: (DataDefinition, PathBuf)) {
+            let (data_definition, path) = storage_path;
+            data_definition.create_catalog("catalog_name");
+            data_definition.create_schema("catalog_name", "schema_name");
+            data_definition.create_table(
+                "catalog_name",
+                "schema_name",
+                "table_name",
+                &[
+                    ColumnDefinition::new("col_1", SqlType::Integer(0)).with_default("7".to_owned()),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_not_null(),
+                ],
+            );
+            drop(data_definition);
+
+            let data_definition = DataDefinition::persistent(&path).expect("create persistent data definition");
+
+            assert_eq!(
+                data_definition.table_columns("catalog_name", "schema_name", "table_name"),
+                vec![
+                    ColumnDefinition::new("col_1", SqlType::Integer(0))
+                        .with_default("7".to_owned())
+                        .with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0))
+                        .with_not_null()
+                        .with_ordinal_position(2)
+                ]
             );
         }
 
@@ -1816,9 +2207,9 @@ mod tests {
             assert_eq!(
                 data_definition.table_columns("catalog_name", "schema_name", "table_name"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             drop(data_definition);
@@ -1827,9 +2218,9 @@ mod tests {
             assert_eq!(
                 data_definition.table_columns("catalog_name", "schema_name", "table_name"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
         }
@@ -1930,68 +2321,68 @@ mod tests {
             assert_eq!(
                 data_definition.table_columns("catalog_name_1", "schema_name_1", "table_name_1"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name_1", "schema_name_1", "table_name_2"),
                 vec![
-                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_5", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_6", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_5", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_6", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
 
             assert_eq!(
                 data_definition.table_columns("catalog_name_1", "schema_name_2", "table_name_3"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name_1", "schema_name_2", "table_name_4"),
                 vec![
-                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_5", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_6", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_5", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_6", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
 
             assert_eq!(
                 data_definition.table_columns("catalog_name_2", "schema_name_3", "table_name_5"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name_2", "schema_name_3", "table_name_6"),
                 vec![
-                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_5", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_6", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_5", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_6", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
 
             assert_eq!(
                 data_definition.table_columns("catalog_name_2", "schema_name_4", "table_name_7"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name_2", "schema_name_4", "table_name_8"),
                 vec![
-                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_5", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_6", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_5", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_6", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
 
@@ -2002,68 +2393,68 @@ mod tests {
             assert_eq!(
                 data_definition.table_columns("catalog_name_1", "schema_name_1", "table_name_1"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name_1", "schema_name_1", "table_name_2"),
                 vec![
-                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_5", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_6", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_5", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_6", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
 
             assert_eq!(
                 data_definition.table_columns("catalog_name_1", "schema_name_2", "table_name_3"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name_1", "schema_name_2", "table_name_4"),
                 vec![
-                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_5", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_6", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_5", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_6", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
 
             assert_eq!(
                 data_definition.table_columns("catalog_name_2", "schema_name_3", "table_name_5"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name_2", "schema_name_3", "table_name_6"),
                 vec![
-                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_5", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_6", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_5", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_6", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
 
             assert_eq!(
                 data_definition.table_columns("catalog_name_2", "schema_name_4", "table_name_7"),
                 vec![
-                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_2", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_3", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_2", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_3", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
             assert_eq!(
                 data_definition.table_columns("catalog_name_2", "schema_name_4", "table_name_8"),
                 vec![
-                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)),
-                    ColumnDefinition::new("col_5", SqlType::Integer(0)),
-                    ColumnDefinition::new("col_6", SqlType::BigInt(0))
+                    ColumnDefinition::new("col_4", SqlType::SmallInt(0)).with_ordinal_position(1),
+                    ColumnDefinition::new("col_5", SqlType::Integer(0)).with_ordinal_position(2),
+                    ColumnDefinition::new("col_6", SqlType::BigInt(0)).with_ordinal_position(3)
                 ]
             );
         }
@@ -2142,7 +2533,7 @@ mod tests {
 
             assert_eq!(
                 data_definition.drop_schema("catalog_name", "schema_name", DropStrategy::Cascade),
-                Ok(())
+                Ok(Ok(()))
             );
             assert!(matches!(
                 data_definition.schema_exists("catalog_name", "schema_name"),
@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Database, DefinitionError, Key, ObjectId, ReadCursor, RowResult, SchemaId, StorageError, Values};
+use crate::{Database, DefinitionError, Key, ObjectId, ReadCursor, Row, RowResult, SchemaId, StorageError, Values};
 use std::{
     collections::{BTreeMap, HashMap},
     io::{self},
+    ops::Bound,
     sync::RwLock,
 };
 
@@ -57,7 +58,25 @@ impl Database for InMemoryDatabase {
     fn drop_schema(&self, schema_name: SchemaId) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
         match self.schemas.write().expect("to acquire write lock").remove(schema_name) {
             Some(_namespace) => Ok(Ok(Ok(()))),
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
+        }
+    }
+
+    fn rename_schema(
+        &self,
+        schema_name: SchemaId,
+        new_schema_name: SchemaId,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        let mut schemas = self.schemas.write().expect("to acquire write lock");
+        if schemas.contains_key(new_schema_name) {
+            return Ok(Ok(Err(DefinitionError::SchemaAlreadyExists)));
+        }
+        match schemas.remove(schema_name) {
+            Some(schema) => {
+                schemas.insert(new_schema_name.to_owned(), schema);
+                Ok(Ok(Ok(())))
+            }
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
@@ -80,7 +99,7 @@ impl Database for InMemoryDatabase {
                     Ok(Ok(Ok(())))
                 }
             }
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
@@ -97,9 +116,9 @@ impl Database for InMemoryDatabase {
         {
             Some(schema) => match schema.objects.remove(object_name) {
                 Some(_) => Ok(Ok(Ok(()))),
-                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
             },
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
@@ -123,9 +142,9 @@ impl Database for InMemoryDatabase {
                     }
                     Ok(Ok(Ok(len)))
                 }
-                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
             },
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
@@ -145,9 +164,57 @@ impl Database for InMemoryDatabase {
                         .collect::<Vec<RowResult>>()
                         .into_iter(),
                 )))),
-                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
+            },
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
+        }
+    }
+
+    fn visit(
+        &self,
+        schema_name: SchemaId,
+        object_name: ObjectId,
+        after: Option<&Key>,
+        limit: usize,
+        visit: &mut dyn FnMut(&Values),
+    ) -> io::Result<Result<Result<Option<Key>, DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => match schema.objects.get(object_name) {
+                Some(object) => {
+                    let start = match after {
+                        Some(after) => Bound::Excluded(after),
+                        None => Bound::Unbounded,
+                    };
+                    let mut last = None;
+                    for (key, values) in object.records.range((start, Bound::Unbounded)).take(limit) {
+                        visit(values);
+                        last = Some(key);
+                    }
+                    Ok(Ok(Ok(last.cloned())))
+                }
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
+            },
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
+        }
+    }
+
+    fn scan(
+        &self,
+        schema_name: SchemaId,
+        object_name: ObjectId,
+        predicate: &(dyn Fn(&Values) -> bool + Sync),
+    ) -> io::Result<Result<Result<Vec<Row>, DefinitionError>, StorageError>> {
+        match self.schemas.read().expect("to acquire read lock").get(schema_name) {
+            Some(schema) => match schema.objects.get(object_name) {
+                Some(object) => Ok(Ok(Ok(object
+                    .records
+                    .iter()
+                    .filter(|(_key, values)| predicate(values))
+                    .map(|(key, values)| (key.clone(), values.clone()))
+                    .collect()))),
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
             },
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
@@ -173,9 +240,9 @@ impl Database for InMemoryDatabase {
                         .collect();
                     Ok(Ok(Ok(keys.len())))
                 }
-                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+                None => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
             },
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 }
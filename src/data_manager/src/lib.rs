@@ -12,15 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use representation::Binary;
+use representation::{unpack_column, unpack_raw, Binary, Datum};
 use std::io::{self};
 
-use crate::data_definition::DataDefinition;
+use crate::data_definition::{
+    DataDefinition, FOREIGN_KEYS_TABLE, FUNCTIONS_TABLE, PARTITIONS_TABLE, PARTITION_KEYS_TABLE, TRIGGERS_TABLE,
+    USERS_TABLE,
+};
 use kernel::{Object, Operation, SystemError, SystemResult};
 use serde::{Deserialize, Serialize};
 use sql_types::SqlType;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    mem::discriminant,
     path::PathBuf,
     sync::{
         atomic::{AtomicU64, Ordering},
@@ -28,8 +32,12 @@ use std::{
     },
 };
 
+mod columnar;
 mod data_definition;
 mod in_memory;
+mod lock_manager;
+mod migration;
+mod mvcc;
 pub mod persistent;
 
 pub type Row = (Key, Values);
@@ -38,7 +46,10 @@ pub type Values = Binary;
 pub type RowResult = io::Result<Result<Row, StorageError>>;
 pub type ReadCursor = Box<dyn Iterator<Item = RowResult>>;
 
-use crate::{in_memory::InMemoryDatabase, persistent::PersistentDatabase};
+use crate::{in_memory::InMemoryDatabase, lock_manager::LockManager, mvcc::Versions, persistent::PersistentDatabase};
+pub use lock_manager::LockMode;
+pub use migration::{MigrateFn, Migrator, SCHEMA_VERSION};
+pub use persistent::ParallelScanConfig;
 
 pub enum InitStatus {
     Created,
@@ -50,14 +61,16 @@ pub enum StorageError {
     Io,
     CascadeIo(Vec<String>),
     Storage,
+    /// stored row does not match its checksum
+    Corruption(String),
 }
 
 #[derive(Debug, PartialEq)]
 pub enum DefinitionError {
     SchemaAlreadyExists,
-    SchemaDoesNotExist,
+    SchemaDoesNotExist(String),
     ObjectAlreadyExists,
-    ObjectDoesNotExist,
+    ObjectDoesNotExist(String),
 }
 
 pub type SchemaId<'s> = &'s str;
@@ -68,6 +81,13 @@ pub trait Database {
 
     fn drop_schema(&self, schema_name: SchemaId) -> io::Result<Result<Result<(), DefinitionError>, StorageError>>;
 
+    /// objects of the schema with all their rows become available under the new name
+    fn rename_schema(
+        &self,
+        schema_name: SchemaId,
+        new_schema_name: SchemaId,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>>;
+
     fn create_object(
         &self,
         schema_name: SchemaId,
@@ -99,16 +119,102 @@ pub trait Database {
         object_name: ObjectId,
         keys: Vec<Key>,
     ) -> io::Result<Result<Result<usize, DefinitionError>, StorageError>>;
+
+    /// rows that satisfy `predicate` in the order of their keys
+    fn scan(
+        &self,
+        schema_name: SchemaId,
+        object_name: ObjectId,
+        predicate: &(dyn Fn(&Values) -> bool + Sync),
+    ) -> io::Result<Result<Result<Vec<Row>, DefinitionError>, StorageError>> {
+        match self.read(schema_name, object_name)? {
+            Ok(Ok(cursor)) => filter_rows(cursor, predicate).map(|rows| rows.map(Ok)),
+            Ok(Err(error)) => Ok(Ok(Err(error))),
+            Err(error) => Ok(Err(error)),
+        }
+    }
+
+    /// passes values of at most `limit` records that follow the `after` key to `visit` in the order of their keys,
+    /// returns the key of the last visited record
+    fn visit(
+        &self,
+        schema_name: SchemaId,
+        object_name: ObjectId,
+        after: Option<&Key>,
+        limit: usize,
+        visit: &mut dyn FnMut(&Values),
+    ) -> io::Result<Result<Result<Option<Key>, DefinitionError>, StorageError>> {
+        let cursor = match self.read(schema_name, object_name)? {
+            Ok(Ok(cursor)) => cursor,
+            Ok(Err(error)) => return Ok(Ok(Err(error))),
+            Err(error) => return Ok(Err(error)),
+        };
+        visit_rows(
+            cursor.filter(|row| match (row, after) {
+                (Ok(Ok((key, _values))), Some(after)) => key > after,
+                _ => true,
+            }),
+            limit,
+            visit,
+        )
+        .map(|last| last.map(Ok))
+    }
+}
+
+pub(crate) fn visit_rows(
+    cursor: impl Iterator<Item = RowResult>,
+    limit: usize,
+    visit: &mut dyn FnMut(&Values),
+) -> io::Result<Result<Option<Key>, StorageError>> {
+    let mut last = None;
+    for row in cursor.take(limit) {
+        match row? {
+            Ok((key, values)) => {
+                visit(&values);
+                last = Some(key);
+            }
+            Err(error) => return Ok(Err(error)),
+        }
+    }
+    Ok(Ok(last))
+}
+
+pub(crate) fn filter_rows(
+    cursor: impl Iterator<Item = RowResult>,
+    predicate: &(dyn Fn(&Values) -> bool + Sync),
+) -> io::Result<Result<Vec<Row>, StorageError>> {
+    let mut rows = vec![];
+    for row in cursor {
+        match row? {
+            Ok((key, values)) if predicate(&values) => rows.push((key, values)),
+            Ok(_) => {}
+            Err(error) => return Ok(Err(error)),
+        }
+    }
+    Ok(Ok(rows))
 }
 
 pub type RecordId = u64;
+pub type SessionId = u64;
 pub type FullSchemaId = Option<RecordId>;
 pub type FullTableId = Option<(RecordId, Option<RecordId>)>;
+/// foreign key together with values of its columns that are missing in the referenced table
+pub type ForeignKeyViolation = (ForeignKeyDefinition, Vec<String>);
+/// ids of the referencing table, its foreign key and values of the key that are still referenced
+pub type ReferencedKeyViolation = (RecordId, RecordId, ForeignKeyDefinition, Vec<String>);
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ColumnDefinition {
     name: String,
     sql_type: SqlType,
+    unique: bool,
+    ordinal_position: u16,
+    #[serde(default)]
+    generated: Option<String>,
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    not_null: bool,
 }
 
 impl ColumnDefinition {
@@ -116,11 +222,64 @@ impl ColumnDefinition {
         Self {
             name: name.to_string(),
             sql_type,
+            unique: false,
+            ordinal_position: 0,
+            generated: None,
+            default: None,
+            not_null: false,
+        }
+    }
+
+    pub fn unique(name: &str, sql_type: SqlType) -> Self {
+        Self {
+            name: name.to_string(),
+            sql_type,
+            unique: true,
+            ordinal_position: 0,
+            generated: None,
+            default: None,
+            not_null: false,
+        }
+    }
+
+    /// one based position of the column in the table, column without position is placed after the last one
+    pub fn with_ordinal_position(self, ordinal_position: u16) -> Self {
+        Self {
+            ordinal_position,
+            ..self
+        }
+    }
+
+    pub fn with_name(self, name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..self
+        }
+    }
+
+    /// value of the column is computed from the other columns of a row by the expression
+    pub fn with_generated(self, expression: String) -> Self {
+        Self {
+            generated: Some(expression),
+            ..self
+        }
+    }
+
+    /// value of the column when a row is written without it or with `DEFAULT` keyword
+    pub fn with_default(self, expression: String) -> Self {
+        Self {
+            default: Some(expression),
+            ..self
         }
     }
 
+    /// NULL could not be stored in the column
+    pub fn with_not_null(self) -> Self {
+        Self { not_null: true, ..self }
+    }
+
     pub fn sql_type(&self) -> SqlType {
-        self.sql_type
+        self.sql_type.clone()
     }
 
     pub fn has_name(&self, other_name: &str) -> bool {
@@ -130,6 +289,560 @@ impl ColumnDefinition {
     pub fn name(&self) -> String {
         self.name.clone()
     }
+
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+
+    pub fn ordinal_position(&self) -> u16 {
+        self.ordinal_position
+    }
+
+    pub fn generated(&self) -> Option<&str> {
+        self.generated.as_deref()
+    }
+
+    pub fn default_expression(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn is_not_null(&self) -> bool {
+        self.not_null
+    }
+}
+
+/// Type of values that a function returns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReturnType {
+    Value(SqlType),
+    /// function is executed by triggers, its body is a statement over `NEW` and `OLD` rows
+    Trigger,
+}
+
+/// Function that is defined by a user, its body is an SQL expression over the arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionDefinition {
+    name: String,
+    args: Vec<(String, SqlType)>,
+    return_type: ReturnType,
+    body: String,
+}
+
+impl FunctionDefinition {
+    pub fn new(name: &str, args: Vec<(String, SqlType)>, return_type: ReturnType, body: String) -> Self {
+        Self {
+            name: name.to_owned(),
+            args,
+            return_type,
+            body,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn args(&self) -> &[(String, SqlType)] {
+        &self.args
+    }
+
+    pub fn return_type(&self) -> ReturnType {
+        self.return_type.clone()
+    }
+
+    pub fn body(&self) -> &str {
+        self.body.as_str()
+    }
+}
+
+/// Whether a trigger fires before or after a row is modified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerTiming {
+    Before,
+    After,
+}
+
+/// Modification of a row that fires a trigger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TriggerEvent {
+    Insert,
+    /// names of columns that have to be assigned by UPDATE, any UPDATE fires the trigger if empty
+    Update(Vec<String>),
+    Delete,
+}
+
+/// Row level trigger of a table that executes a function of `function_schema_id` schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerDefinition {
+    name: String,
+    timing: TriggerTiming,
+    events: Vec<TriggerEvent>,
+    function_schema_id: RecordId,
+    function_name: String,
+    enabled: bool,
+}
+
+impl TriggerDefinition {
+    pub fn new(
+        name: &str,
+        timing: TriggerTiming,
+        events: Vec<TriggerEvent>,
+        function_schema_id: RecordId,
+        function_name: &str,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            timing,
+            events,
+            function_schema_id,
+            function_name: function_name.to_owned(),
+            enabled: true,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn timing(&self) -> TriggerTiming {
+        self.timing
+    }
+
+    pub fn events(&self) -> &[TriggerEvent] {
+        &self.events
+    }
+
+    pub fn function(&self) -> (RecordId, &str) {
+        (self.function_schema_id, self.function_name.as_str())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Referential constraint, values of `columns` of a row have to be stored in `referenced_columns`
+/// of the referenced table unless one of them is NULL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignKeyDefinition {
+    name: String,
+    columns: Vec<String>,
+    referenced_schema_id: RecordId,
+    referenced_table_id: RecordId,
+    referenced_columns: Vec<String>,
+    deferrable: bool,
+    initially_deferred: bool,
+}
+
+impl ForeignKeyDefinition {
+    pub fn new(
+        name: &str,
+        columns: Vec<String>,
+        referenced_schema_id: RecordId,
+        referenced_table_id: RecordId,
+        referenced_columns: Vec<String>,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            columns,
+            referenced_schema_id,
+            referenced_table_id,
+            referenced_columns,
+            deferrable: false,
+            initially_deferred: false,
+        }
+    }
+
+    /// checks of a deferrable foreign key could be postponed until a transaction is committed
+    pub fn with_deferrable(mut self, initially_deferred: bool) -> Self {
+        self.deferrable = true;
+        self.initially_deferred = initially_deferred;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn is_deferrable(&self) -> bool {
+        self.deferrable
+    }
+
+    pub fn is_initially_deferred(&self) -> bool {
+        self.initially_deferred
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn referenced_table(&self) -> (RecordId, RecordId) {
+        (self.referenced_schema_id, self.referenced_table_id)
+    }
+
+    pub fn referenced_columns(&self) -> &[String] {
+        &self.referenced_columns
+    }
+}
+
+/// How rows of a partitioned table are distributed among its partitions by values of the partition key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartitionStrategy {
+    Range,
+    List,
+}
+
+/// Values of the partition key of rows that are stored in a partition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PartitionBounds {
+    /// greater than or equal to the lower bound and less than the upper bound
+    Range {
+        #[serde(with = "packed_datum")]
+        lower: Datum<'static>,
+        #[serde(with = "packed_datum")]
+        upper: Datum<'static>,
+    },
+    /// equal to one of the values
+    List(#[serde(with = "packed_datums")] Vec<Datum<'static>>),
+    /// any value that no other partition of the table accepts
+    Default,
+}
+
+/// Partition of a partitioned table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionDefinition {
+    schema_id: RecordId,
+    table_id: RecordId,
+    bounds: PartitionBounds,
+}
+
+impl PartitionDefinition {
+    pub fn new(schema_id: RecordId, table_id: RecordId, bounds: PartitionBounds) -> Self {
+        Self {
+            schema_id,
+            table_id,
+            bounds,
+        }
+    }
+
+    pub fn table(&self) -> (RecordId, RecordId) {
+        (self.schema_id, self.table_id)
+    }
+
+    pub fn bounds(&self) -> &PartitionBounds {
+        &self.bounds
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.bounds == PartitionBounds::Default
+    }
+
+    /// NULL is not contained in any bounds, the default partition contains only values that are out of
+    /// bounds of the other partitions, see `DataManager::partition_of`
+    pub fn contains(&self, value: &Datum) -> bool {
+        match &self.bounds {
+            PartitionBounds::Range { lower, upper } => {
+                matches!(
+                    compare_keys(lower, value),
+                    Some(std::cmp::Ordering::Less) | Some(std::cmp::Ordering::Equal)
+                ) && compare_keys(value, upper) == Some(std::cmp::Ordering::Less)
+            }
+            PartitionBounds::List(values) => values
+                .iter()
+                .any(|item| compare_keys(item, value) == Some(std::cmp::Ordering::Equal)),
+            PartitionBounds::Default => false,
+        }
+    }
+
+    /// a table can have only one default partition
+    pub fn overlaps(&self, other: &PartitionDefinition) -> bool {
+        match (&self.bounds, &other.bounds) {
+            (
+                PartitionBounds::Range { lower, upper },
+                PartitionBounds::Range {
+                    lower: other_lower,
+                    upper: other_upper,
+                },
+            ) => {
+                compare_keys(lower, other_upper) == Some(std::cmp::Ordering::Less)
+                    && compare_keys(other_lower, upper) == Some(std::cmp::Ordering::Less)
+            }
+            (PartitionBounds::List(values), _) => values.iter().any(|value| other.contains(value)),
+            (PartitionBounds::Default, PartitionBounds::Default) => true,
+            _ => false,
+        }
+    }
+}
+
+// bounds are stored in the catalog in the same format as rows of tables
+mod packed_datums {
+    use representation::{Binary, Datum};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(datums: &[Datum<'static>], serializer: S) -> Result<S::Ok, S::Error> {
+        Binary::pack(datums).to_bytes().serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Datum<'static>>, D::Error> {
+        let data = Vec::<u8>::deserialize(deserializer)?;
+        Ok(Binary::with_data(data).unpack().into_iter().map(owned).collect())
+    }
+
+    fn owned(datum: Datum) -> Datum<'static> {
+        match datum {
+            Datum::Null => Datum::Null,
+            Datum::True => Datum::True,
+            Datum::False => Datum::False,
+            Datum::Int16(value) => Datum::Int16(value),
+            Datum::Int32(value) => Datum::Int32(value),
+            Datum::Int64(value) => Datum::Int64(value),
+            Datum::UInt64(value) => Datum::UInt64(value),
+            Datum::Float32(value) => Datum::Float32(value),
+            Datum::Float64(value) => Datum::Float64(value),
+            Datum::String(value) => Datum::OwnedString(value.to_owned()),
+            Datum::OwnedString(value) => Datum::OwnedString(value),
+            Datum::SqlType(value) => Datum::SqlType(value),
+        }
+    }
+}
+
+mod packed_datum {
+    use representation::Datum;
+    use serde::{de::Error, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(datum: &Datum<'static>, serializer: S) -> Result<S::Ok, S::Error> {
+        super::packed_datums::serialize(std::slice::from_ref(datum), serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Datum<'static>, D::Error> {
+        super::packed_datums::deserialize(deserializer)?
+            .pop()
+            .ok_or_else(|| D::Error::custom("bound value is missing"))
+    }
+}
+
+/// values of a partition key are compared as numbers or as text, `None` when they can't be compared
+pub fn compare_keys(left: &Datum, right: &Datum) -> Option<std::cmp::Ordering> {
+    fn number(datum: &Datum) -> Option<i64> {
+        match datum {
+            Datum::Int16(value) => Some(i64::from(*value)),
+            Datum::Int32(value) => Some(i64::from(*value)),
+            Datum::Int64(value) => Some(*value),
+            _ => None,
+        }
+    }
+    fn text<'d>(datum: &'d Datum) -> Option<&'d str> {
+        match datum {
+            Datum::String(value) => Some(value),
+            Datum::OwnedString(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+    match (number(left), number(right)) {
+        (Some(left), Some(right)) => Some(left.cmp(&right)),
+        _ => match (text(left), text(right)) {
+            (Some(left), Some(right)) => Some(left.cmp(right)),
+            _ => None,
+        },
+    }
+}
+
+/// number of records that a select scan reads from a storage object at once
+const SCAN_PAGE_SIZE: usize = 1024;
+
+// rows of a table with text values of the selected columns, see `DataManager::select_scan`
+struct SelectScan<'s> {
+    data_manager: &'s DataManager,
+    table: (RecordId, RecordId),
+    source: ScanSource,
+    page: VecDeque<Vec<Option<String>>>,
+    rows: usize,
+}
+
+enum ScanSource {
+    // storage objects that are read page by page, either the table object with positions of the selected columns
+    // in its rows or objects of the selected columns of a columnar table
+    Objects {
+        schema_name: String,
+        objects: Vec<String>,
+        columns: Vec<usize>,
+        after: Option<Key>,
+        finished: bool,
+    },
+    // rows that are visible to the session, when the table has changes that it does not see
+    Visible(std::vec::IntoIter<Row>),
+}
+
+fn text(datum: Option<Datum>) -> Option<String> {
+    match datum {
+        None | Some(Datum::Null) => None,
+        Some(datum) => Some(datum.to_string()),
+    }
+}
+
+fn read_error<T>(
+    table: (RecordId, RecordId),
+    result: io::Result<Result<Result<T, DefinitionError>, StorageError>>,
+) -> SystemResult<T> {
+    match result {
+        Ok(Ok(Ok(value))) => Ok(value),
+        Ok(Ok(Err(error))) => Err(SystemError::unrecoverable(format!(
+            "{:?} while table {:?} is read",
+            error, table
+        ))),
+        Ok(Err(StorageError::Corruption(message))) => Err(SystemError::data_corruption(message)),
+        Ok(Err(error)) => Err(SystemError::unrecoverable(format!(
+            "{:?} while table {:?} is read",
+            error, table
+        ))),
+        Err(io_error) => Err(SystemError::io(io_error)),
+    }
+}
+
+impl SelectScan<'_> {
+    fn read_page(&mut self) -> SystemResult<()> {
+        let data_storage = &self.data_manager.data_storage;
+        let page = &mut self.page;
+        let visited = match &mut self.source {
+            ScanSource::Objects { finished: true, .. } => return Ok(()),
+            ScanSource::Objects {
+                schema_name,
+                objects,
+                columns,
+                after,
+                ..
+            } => {
+                let mut visited = Vec::with_capacity(objects.len());
+                for (cursor, object_name) in objects.iter().enumerate() {
+                    let mut row = 0;
+                    visited.push(data_storage.visit(
+                        schema_name.as_str(),
+                        object_name.as_str(),
+                        after.as_ref(),
+                        SCAN_PAGE_SIZE,
+                        &mut |values| {
+                            if objects.len() == 1 {
+                                let data = unpack_raw(values.to_bytes());
+                                page.push_back(columns.iter().map(|index| text(data.get(*index).cloned())).collect());
+                            } else if cursor == 0 {
+                                let mut values_of_row = Vec::with_capacity(objects.len());
+                                values_of_row.push(text(unpack_column(values.to_bytes(), 0)));
+                                page.push_back(values_of_row);
+                            } else if let Some(values_of_row) = page.get_mut(row) {
+                                // a record of a columnar table holds the single value of its column
+                                values_of_row.push(text(unpack_column(values.to_bytes(), 0)));
+                            }
+                            row += 1;
+                        },
+                    ));
+                }
+                visited
+            }
+            ScanSource::Visible(rows) => {
+                for (_key, values) in rows.take(SCAN_PAGE_SIZE) {
+                    page.push_back(unpack_raw(values.to_bytes()).into_iter().map(Some).map(text).collect());
+                }
+                return Ok(());
+            }
+        };
+        let mut last = None;
+        for result in visited {
+            let key = read_error(self.table, result)?;
+            last.get_or_insert(key);
+        }
+        if let ScanSource::Objects { after, finished, .. } = &mut self.source {
+            *finished = self.page.len() < SCAN_PAGE_SIZE;
+            *after = last.flatten();
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for SelectScan<'_> {
+    type Item = SystemResult<Vec<Option<String>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.page.is_empty() {
+            if let Err(error) = self.read_page() {
+                // the scan is not continued after a failure
+                self.source = ScanSource::Visible(vec![].into_iter());
+                return Some(Err(error));
+            }
+        }
+        let row = self.page.pop_front()?;
+        self.rows += 1;
+        Some(Ok(row))
+    }
+}
+
+// rows that were read before the scan is finished or abandoned are counted as read by a sequential scan
+impl Drop for SelectScan<'_> {
+    fn drop(&mut self) {
+        self.data_manager.count_scan(self.table, self.rows);
+    }
+}
+
+#[derive(Default)]
+struct DeferredChecks {
+    /// mode set by `SET CONSTRAINTS ALL`, otherwise foreign keys are checked as they were declared
+    all_deferred: Option<bool>,
+    /// foreign keys by their table whose checks were postponed
+    pending: BTreeSet<(RecordId, RecordId, String)>,
+}
+
+/// Privilege on a table that is checked before its data is read or modified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Statistics of a column collected by ANALYZE, `min` and `max` are `None` when all values are NULLs
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStatistics {
+    pub distinct: usize,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub null_fraction: f64,
+}
+
+/// Numbers of sequential scans of a table and of rows read and changed since it was created,
+/// `live_rows` is the number of rows stored in the table right now
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TableActivity {
+    pub live_rows: u64,
+    pub sequential_scans: u64,
+    pub rows_read: u64,
+    pub rows_inserted: u64,
+    pub rows_updated: u64,
+    pub rows_deleted: u64,
+}
+
+/// Kind of modification of table rows that is counted in `TableActivity`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableChange {
+    Inserted,
+    Updated,
+    Deleted,
+}
+
+/// Definition of a table as it is shown to users, e.g. by `\d` of psql
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDescription {
+    pub columns: Vec<ColumnDefinition>,
+    /// names of columns with UNIQUE constraint, PRIMARY KEY columns are stored as unique ones
+    pub unique_columns: Vec<String>,
+    pub layout: StorageLayout,
+    pub triggers: Vec<TriggerDefinition>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct User {
+    superuser: bool,
+    // `true` when a privilege was granted with grant option
+    privileges: HashMap<(RecordId, RecordId), HashMap<Privilege, bool>>,
 }
 
 pub enum DropStrategy {
@@ -137,19 +850,71 @@ pub enum DropStrategy {
     Cascade,
 }
 
+/// errors of DROP SCHEMA that are reported to the client, failures of the storage are `SystemError`s
 #[derive(Debug, PartialEq)]
 pub enum DropSchemaError {
-    CatalogDoesNotExist,
     DoesNotExist,
     HasDependentObjects,
 }
 
+/// errors of ALTER SCHEMA RENAME that are reported to the client, failures of the storage are `SystemError`s
+#[derive(Debug, PartialEq)]
+pub enum RenameSchemaError {
+    DoesNotExist,
+    AlreadyExists,
+}
+
+/// errors of copying a table that are reported to the client, failures of the storage are `SystemError`s
+#[derive(Debug, PartialEq)]
+pub enum OperationOnTableError {
+    SchemaDoesNotExist(String),
+    TableDoesNotExist(String),
+    TableAlreadyExists(String),
+}
+
+/// how rows of a table are laid out in the storage
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StorageLayout {
+    /// all values of a row are stored as a single record
+    #[default]
+    Row,
+    /// values of each column are stored separately, reading a subset of columns
+    /// does not touch the others
+    Columnar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageBackend {
+    InMemory,
+    Persistent(PathBuf),
+}
+
 pub struct DataManager {
     data_storage: Box<dyn Database>,
     data_definition: DataDefinition,
     schemas: RwLock<HashMap<RecordId, String>>,
     tables: RwLock<HashMap<(RecordId, RecordId), Vec<String>>>,
     record_id_generators: RwLock<HashMap<(RecordId, RecordId), AtomicU64>>,
+    session_id_generator: AtomicU64,
+    /// open sessions with a flag whether the query that a session runs is requested to be canceled
+    sessions: RwLock<HashMap<SessionId, bool>>,
+    users: RwLock<HashMap<String, User>>,
+    functions: RwLock<HashMap<(RecordId, String), FunctionDefinition>>,
+    triggers: RwLock<HashMap<(RecordId, RecordId), Vec<TriggerDefinition>>>,
+    foreign_keys: RwLock<HashMap<(RecordId, RecordId), Vec<ForeignKeyDefinition>>>,
+    /// index of the partition key column of tables that are partitioned by range
+    partition_keys: RwLock<HashMap<(RecordId, RecordId), (PartitionStrategy, usize)>>,
+    partitions: RwLock<HashMap<(RecordId, RecordId), Vec<PartitionDefinition>>>,
+    /// foreign key checks postponed by sessions inside of a transaction block
+    deferred_checks: RwLock<HashMap<SessionId, DeferredChecks>>,
+    session_users: RwLock<HashMap<SessionId, String>>,
+    locks: LockManager,
+    statistics: RwLock<HashMap<(RecordId, RecordId), Vec<ColumnStatistics>>>,
+    activity: RwLock<HashMap<(RecordId, RecordId), TableActivity>>,
+    schema_version: AtomicU64,
+    modifications: AtomicU64,
+    table_versions: RwLock<HashMap<(RecordId, RecordId), u64>>,
+    row_versions: RwLock<Versions>,
 }
 
 impl Default for DataManager {
@@ -162,8 +927,75 @@ unsafe impl Send for DataManager {}
 unsafe impl Sync for DataManager {}
 
 const DEFAULT_CATALOG: &'_ str = "public";
+// temporary tables of a session are kept in its own schema, name of a table there
+// is prefixed with the schema of the table that it shadows
+const TEMP_SCHEMA_PREFIX: &'_ str = "pg_temp_";
+
+fn temp_schema_name(session_id: SessionId) -> String {
+    format!("{}{}", TEMP_SCHEMA_PREFIX, session_id)
+}
+
+fn temp_table_name(schema_name: &str, table_name: &str) -> String {
+    format!("{}.{}", schema_name, table_name)
+}
+
+// stored strings are decoded as borrowed ones, thus they are compared by their content
+fn column_equals(values: &Values, column: usize, value: &Datum) -> bool {
+    match (unpack_column(values.to_bytes(), column), value) {
+        (Some(Datum::String(stored)), Datum::OwnedString(value)) => stored == value,
+        (Some(stored), value) if discriminant(&stored) == discriminant(value) => &stored == value,
+        // a column could hold datums of different kinds, e.g. a number inserted into VARCHAR column
+        (Some(stored), value) => stored.to_string() == value.to_string(),
+        (None, _) => false,
+    }
+}
+
+// values are compared as text, thus integers of different widths are equal
+fn key_values(values: &Values, indexes: &[usize]) -> Option<Vec<String>> {
+    let data = values.unpack();
+    indexes
+        .iter()
+        .map(|index| match data.get(*index) {
+            None | Some(Datum::Null) => None,
+            Some(datum) => Some(datum.to_string()),
+        })
+        .collect()
+}
+
+// definitions of a table are stored under ids of its schema and the table
+fn table_key(key: &Binary) -> (RecordId, RecordId) {
+    let key = key.unpack();
+    (key[0].as_u64(), key[1].as_u64())
+}
+
+// positions of the named columns in the table
+fn column_indexes(columns: &[ColumnDefinition], names: &[String]) -> Vec<usize> {
+    names
+        .iter()
+        .filter_map(|name| columns.iter().position(|column| column.has_name(name)))
+        .collect()
+}
+
+// columnar table is stored as a set of objects, one for each of its columns
+fn table_objects(data_definition: &DataDefinition, schema_name: &str, table_name: &str) -> Vec<String> {
+    match data_definition.table_layout(DEFAULT_CATALOG, schema_name, table_name) {
+        StorageLayout::Row => vec![table_name.to_owned()],
+        StorageLayout::Columnar => (0..data_definition
+            .table_columns(DEFAULT_CATALOG, schema_name, table_name)
+            .len())
+            .map(|index| columnar::column_object(table_name, index))
+            .collect(),
+    }
+}
 
 impl DataManager {
+    pub fn new(backend: StorageBackend) -> SystemResult<DataManager> {
+        match backend {
+            StorageBackend::InMemory => Self::in_memory(),
+            StorageBackend::Persistent(path) => Self::persistent(path),
+        }
+    }
+
     pub fn in_memory() -> SystemResult<DataManager> {
         let data_definition = DataDefinition::in_memory();
         data_definition.create_catalog(DEFAULT_CATALOG);
@@ -173,10 +1005,33 @@ impl DataManager {
             schemas: RwLock::default(),
             tables: RwLock::default(),
             record_id_generators: RwLock::default(),
+            session_id_generator: AtomicU64::default(),
+            sessions: RwLock::default(),
+            users: RwLock::default(),
+            functions: RwLock::default(),
+            triggers: RwLock::default(),
+            foreign_keys: RwLock::default(),
+            partition_keys: RwLock::default(),
+            partitions: RwLock::default(),
+            deferred_checks: RwLock::default(),
+            session_users: RwLock::default(),
+            locks: LockManager::default(),
+            statistics: RwLock::default(),
+            activity: RwLock::default(),
+            schema_version: AtomicU64::default(),
+            modifications: AtomicU64::default(),
+            table_versions: RwLock::default(),
+            row_versions: RwLock::default(),
         })
     }
 
     pub fn persistent(path: PathBuf) -> SystemResult<DataManager> {
+        Self::persistent_with_migrator(path, &Migrator::default())
+    }
+
+    /// opens storage under the path migrating it to the latest version of the migrator
+    pub fn persistent_with_migrator(path: PathBuf, migrator: &Migrator) -> SystemResult<DataManager> {
+        migrator.migrate(&path)?;
         let data_definition = DataDefinition::persistent(&path)?;
         let catalog = PersistentDatabase::new(path.join(DEFAULT_CATALOG));
         let schemas = RwLock::new(HashMap::new());
@@ -196,7 +1051,9 @@ impl DataManager {
                                     .write()
                                     .expect("to acquire write lock")
                                     .insert((schema_id, table_id), vec![schema_name.clone(), table_name.clone()]);
-                                catalog.open_object(schema_name.as_str(), table_name.as_str());
+                                for object_name in table_objects(&data_definition, &schema_name, &table_name) {
+                                    catalog.open_object(schema_name.as_str(), object_name.as_str());
+                                }
                             }
                         }
                         Ok(Ok(InitStatus::Created)) => {
@@ -221,22 +1078,985 @@ impl DataManager {
                 data_definition.create_catalog(DEFAULT_CATALOG);
             }
         }
-        Ok(Self {
+        let foreign_keys = data_definition
+            .definitions(FOREIGN_KEYS_TABLE)
+            .into_iter()
+            .map(|(key, table_foreign_keys)| (table_key(&key), table_foreign_keys))
+            .collect();
+        let users = data_definition
+            .definitions(USERS_TABLE)
+            .into_iter()
+            .map(|(key, user)| (key.unpack()[0].as_str().to_owned(), user))
+            .collect();
+        let functions = data_definition
+            .definitions(FUNCTIONS_TABLE)
+            .into_iter()
+            .map(|(key, function)| {
+                let key = key.unpack();
+                ((key[0].as_u64(), key[1].as_str().to_owned()), function)
+            })
+            .collect();
+        let triggers = data_definition
+            .definitions(TRIGGERS_TABLE)
+            .into_iter()
+            .map(|(key, table_triggers)| (table_key(&key), table_triggers))
+            .collect();
+        let partition_keys = data_definition
+            .definitions(PARTITION_KEYS_TABLE)
+            .into_iter()
+            .map(|(key, partition_key)| (table_key(&key), partition_key))
+            .collect();
+        let partitions = data_definition
+            .definitions(PARTITIONS_TABLE)
+            .into_iter()
+            .map(|(key, table_partitions)| (table_key(&key), table_partitions))
+            .collect();
+        let data_manager = Self {
             data_storage: Box::new(catalog),
             data_definition,
             schemas,
             tables,
             record_id_generators: RwLock::default(),
-        })
-    }
-
-    pub fn next_key_id(&self, schema_id: RecordId, table_id: RecordId) -> RecordId {
-        match self
-            .record_id_generators
+            session_id_generator: AtomicU64::default(),
+            sessions: RwLock::default(),
+            users: RwLock::new(users),
+            functions: RwLock::new(functions),
+            triggers: RwLock::new(triggers),
+            foreign_keys: RwLock::new(foreign_keys),
+            partition_keys: RwLock::new(partition_keys),
+            partitions: RwLock::new(partitions),
+            deferred_checks: RwLock::default(),
+            session_users: RwLock::default(),
+            locks: LockManager::default(),
+            statistics: RwLock::default(),
+            activity: RwLock::default(),
+            schema_version: AtomicU64::default(),
+            modifications: AtomicU64::default(),
+            table_versions: RwLock::default(),
+            row_versions: RwLock::default(),
+        };
+        // sessions that created temporary tables were not closed properly
+        let temp_schemas = data_manager
+            .schemas
             .read()
             .expect("to acquire read lock")
-            .get(&(schema_id, table_id))
-        {
+            .iter()
+            .filter(|(_schema_id, schema_name)| schema_name.starts_with(TEMP_SCHEMA_PREFIX))
+            .map(|(schema_id, _schema_name)| *schema_id)
+            .collect::<Vec<_>>();
+        for schema_id in temp_schemas {
+            data_manager.drop_schema(schema_id, DropStrategy::Cascade)?.ok();
+        }
+        Ok(data_manager)
+    }
+
+    pub fn open_session(&self) -> SessionId {
+        let session_id = self.session_id_generator.fetch_add(1, Ordering::SeqCst);
+        self.sessions
+            .write()
+            .expect("to acquire write lock")
+            .insert(session_id, false);
+        session_id
+    }
+
+    /// drops all temporary tables created by the session
+    pub fn close_session(&self, session_id: SessionId) -> SystemResult<()> {
+        self.sessions
+            .write()
+            .expect("to acquire write lock")
+            .remove(&session_id);
+        self.session_users
+            .write()
+            .expect("to acquire write lock")
+            .remove(&session_id);
+        self.commit_transaction(session_id);
+        self.release_locks(session_id);
+        match self.schema_exists(&temp_schema_name(session_id)) {
+            Some(schema_id) => self.drop_schema(schema_id, DropStrategy::Cascade).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// requests the query that the session runs to stop, `false` when the session is not open
+    pub fn cancel_session(&self, session_id: SessionId) -> bool {
+        match self
+            .sessions
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(&session_id)
+        {
+            Some(canceled) => {
+                *canceled = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// whether the query that the session runs is requested to stop
+    pub fn is_canceled(&self, session_id: SessionId) -> bool {
+        self.sessions
+            .read()
+            .expect("to acquire read lock")
+            .get(&session_id)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// requests to cancel queries that the session finished are not applied to the next one
+    pub fn start_query(&self, session_id: SessionId) {
+        if let Some(canceled) = self
+            .sessions
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(&session_id)
+        {
+            *canceled = false;
+        }
+    }
+
+    /// waits until other sessions release conflicting locks on the table
+    pub fn lock_table(&self, session_id: SessionId, schema_id: RecordId, table_id: RecordId, mode: LockMode) {
+        self.locks.lock(session_id, (schema_id, table_id), mode)
+    }
+
+    /// `false` when the lock can't be acquired immediately
+    pub fn try_lock_table(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        mode: LockMode,
+    ) -> bool {
+        self.locks.try_lock(session_id, (schema_id, table_id), mode)
+    }
+
+    pub fn release_locks(&self, session_id: SessionId) {
+        self.locks.release(session_id)
+    }
+
+    /// reads of the session see only rows committed before the transaction started
+    /// and changes of the transaction itself until it is committed
+    pub fn begin_transaction(&self, session_id: SessionId) {
+        self.row_versions
+            .write()
+            .expect("to acquire write lock")
+            .begin(session_id);
+        self.deferred_checks
+            .write()
+            .expect("to acquire write lock")
+            .entry(session_id)
+            .or_default();
+    }
+
+    /// changes of the transaction become visible to other sessions
+    pub fn commit_transaction(&self, session_id: SessionId) {
+        self.deferred_checks
+            .write()
+            .expect("to acquire write lock")
+            .remove(&session_id);
+        let pruned = self
+            .row_versions
+            .write()
+            .expect("to acquire write lock")
+            .commit(session_id);
+        // results read while the changes were not visible to everyone are outdated
+        for (schema_id, table_id) in pruned {
+            self.table_modified(schema_id, table_id);
+        }
+    }
+
+    /// `true` when all sessions read the same rows of the table
+    pub fn is_latest(&self, schema_id: RecordId, table_id: RecordId) -> bool {
+        self.row_versions
+            .read()
+            .expect("to acquire read lock")
+            .is_latest((schema_id, table_id))
+    }
+
+    pub fn set_session_user(&self, session_id: SessionId, user_name: &str) {
+        self.session_users
+            .write()
+            .expect("to acquire write lock")
+            .insert(session_id, user_name.to_owned());
+    }
+
+    /// users that were not created by `create_user` or were dropped have no privileges.
+    /// Sessions without a user are opened by the application that owns the storage
+    pub fn is_superuser(&self, session_id: SessionId) -> bool {
+        match self
+            .session_users
+            .read()
+            .expect("to acquire read lock")
+            .get(&session_id)
+        {
+            Some(user_name) => self
+                .users
+                .read()
+                .expect("to acquire read lock")
+                .get(user_name)
+                .map(|user| user.superuser)
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+
+    pub fn user_exists(&self, user_name: &str) -> bool {
+        self.users.read().expect("to acquire read lock").contains_key(user_name)
+    }
+
+    /// users and their privileges are stored in the system catalog of a persistent storage
+    pub fn create_user(&self, user_name: &str, superuser: bool) {
+        let mut users = self.users.write().expect("to acquire write lock");
+        users.insert(
+            user_name.to_owned(),
+            User {
+                superuser,
+                privileges: HashMap::new(),
+            },
+        );
+        self.store_user(&users, user_name);
+    }
+
+    pub fn drop_user(&self, user_name: &str) {
+        let mut users = self.users.write().expect("to acquire write lock");
+        users.remove(user_name);
+        self.store_user(&users, user_name);
+    }
+
+    fn store_user(&self, users: &HashMap<String, User>, user_name: &str) {
+        let key = [Datum::from_str(user_name)];
+        match users.get(user_name) {
+            Some(user) => self.data_definition.save_definition(USERS_TABLE, &key, user),
+            None => self.data_definition.remove_definition(USERS_TABLE, &key),
+        }
+    }
+
+    pub fn function_exists(&self, schema_id: RecordId, function_name: &str) -> bool {
+        self.functions
+            .read()
+            .expect("to acquire read lock")
+            .contains_key(&(schema_id, function_name.to_owned()))
+    }
+
+    /// functions are stored in the system catalog of a persistent storage, queries that were
+    /// cached before a function is created or dropped are not reused
+    pub fn create_function(&self, schema_id: RecordId, function: FunctionDefinition) {
+        self.data_definition.save_definition(
+            FUNCTIONS_TABLE,
+            &[Datum::from_u64(schema_id), Datum::from_str(function.name())],
+            &function,
+        );
+        self.functions
+            .write()
+            .expect("to acquire write lock")
+            .insert((schema_id, function.name().to_owned()), function);
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn drop_function(&self, schema_id: RecordId, function_name: &str) {
+        self.functions
+            .write()
+            .expect("to acquire write lock")
+            .remove(&(schema_id, function_name.to_owned()));
+        self.data_definition.remove_definition(
+            FUNCTIONS_TABLE,
+            &[Datum::from_u64(schema_id), Datum::from_str(function_name)],
+        );
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn function(&self, schema_id: RecordId, function_name: &str) -> Option<FunctionDefinition> {
+        self.functions
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, function_name.to_owned()))
+            .cloned()
+    }
+
+    /// all functions with names of their schemas
+    pub fn functions(&self) -> Vec<(String, FunctionDefinition)> {
+        let schemas = self.schemas.read().expect("to acquire read lock");
+        self.functions
+            .read()
+            .expect("to acquire read lock")
+            .iter()
+            .filter_map(|((schema_id, _name), function)| {
+                schemas
+                    .get(schema_id)
+                    .map(|schema_name| (schema_name.clone(), function.clone()))
+            })
+            .collect()
+    }
+
+    /// triggers of the table in the order they were created
+    pub fn triggers(&self, schema_id: RecordId, table_id: RecordId) -> Vec<TriggerDefinition> {
+        self.triggers
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn trigger_exists(&self, schema_id: RecordId, table_id: RecordId, trigger_name: &str) -> bool {
+        self.triggers(schema_id, table_id)
+            .iter()
+            .any(|trigger| trigger.name() == trigger_name)
+    }
+
+    pub fn create_trigger(&self, schema_id: RecordId, table_id: RecordId, trigger: TriggerDefinition) {
+        let mut triggers = self.triggers.write().expect("to acquire write lock");
+        triggers.entry((schema_id, table_id)).or_default().push(trigger);
+        self.store_triggers(&triggers, (schema_id, table_id));
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn drop_trigger(&self, schema_id: RecordId, table_id: RecordId, trigger_name: &str) {
+        let mut triggers = self.triggers.write().expect("to acquire write lock");
+        if let Some(table_triggers) = triggers.get_mut(&(schema_id, table_id)) {
+            table_triggers.retain(|trigger| trigger.name() != trigger_name);
+        }
+        self.store_triggers(&triggers, (schema_id, table_id));
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// disabled triggers are kept, however they don't fire
+    pub fn enable_trigger(&self, schema_id: RecordId, table_id: RecordId, trigger_name: &str, enabled: bool) {
+        let mut triggers = self.triggers.write().expect("to acquire write lock");
+        if let Some(trigger) = triggers
+            .get_mut(&(schema_id, table_id))
+            .and_then(|triggers| triggers.iter_mut().find(|trigger| trigger.name() == trigger_name))
+        {
+            trigger.enabled = enabled;
+        }
+        self.store_triggers(&triggers, (schema_id, table_id));
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn store_triggers(
+        &self,
+        triggers: &HashMap<(RecordId, RecordId), Vec<TriggerDefinition>>,
+        table: (RecordId, RecordId),
+    ) {
+        let key = [Datum::from_u64(table.0), Datum::from_u64(table.1)];
+        match triggers.get(&table) {
+            Some(table_triggers) if !table_triggers.is_empty() => {
+                self.data_definition
+                    .save_definition(TRIGGERS_TABLE, &key, table_triggers)
+            }
+            _ => self.data_definition.remove_definition(TRIGGERS_TABLE, &key),
+        }
+    }
+
+    /// foreign keys of the table in the order they were added
+    pub fn foreign_keys(&self, schema_id: RecordId, table_id: RecordId) -> Vec<ForeignKeyDefinition> {
+        self.foreign_keys
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn foreign_key_exists(&self, schema_id: RecordId, table_id: RecordId, name: &str) -> bool {
+        self.foreign_keys(schema_id, table_id)
+            .iter()
+            .any(|foreign_key| foreign_key.name() == name)
+    }
+
+    /// foreign keys of all tables, including the table itself, that reference the table with ids of their tables
+    pub fn referencing_foreign_keys(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+    ) -> Vec<(RecordId, RecordId, ForeignKeyDefinition)> {
+        self.foreign_keys
+            .read()
+            .expect("to acquire read lock")
+            .iter()
+            .flat_map(|((referencing_schema_id, referencing_table_id), foreign_keys)| {
+                foreign_keys
+                    .iter()
+                    .filter(|foreign_key| foreign_key.referenced_table() == (schema_id, table_id))
+                    .map(move |foreign_key| (*referencing_schema_id, *referencing_table_id, foreign_key.clone()))
+            })
+            .collect()
+    }
+
+    /// foreign keys are stored in the system catalog of a persistent storage
+    pub fn add_foreign_key(&self, schema_id: RecordId, table_id: RecordId, foreign_key: ForeignKeyDefinition) {
+        let mut foreign_keys = self.foreign_keys.write().expect("to acquire write lock");
+        foreign_keys.entry((schema_id, table_id)).or_default().push(foreign_key);
+        self.store_foreign_keys(&foreign_keys, (schema_id, table_id));
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn drop_foreign_key(&self, schema_id: RecordId, table_id: RecordId, name: &str) {
+        let mut foreign_keys = self.foreign_keys.write().expect("to acquire write lock");
+        if let Some(table_foreign_keys) = foreign_keys.get_mut(&(schema_id, table_id)) {
+            table_foreign_keys.retain(|foreign_key| foreign_key.name() != name);
+        }
+        self.store_foreign_keys(&foreign_keys, (schema_id, table_id));
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    // all foreign keys of a table are stored as a single definition
+    fn store_foreign_keys(
+        &self,
+        foreign_keys: &HashMap<(RecordId, RecordId), Vec<ForeignKeyDefinition>>,
+        table: (RecordId, RecordId),
+    ) {
+        let key = [Datum::from_u64(table.0), Datum::from_u64(table.1)];
+        match foreign_keys.get(&table) {
+            Some(table_foreign_keys) if !table_foreign_keys.is_empty() => {
+                self.data_definition
+                    .save_definition(FOREIGN_KEYS_TABLE, &key, table_foreign_keys)
+            }
+            _ => self.data_definition.remove_definition(FOREIGN_KEYS_TABLE, &key),
+        }
+    }
+
+    // foreign keys of the tables that satisfy the predicate and the ones that reference them are removed
+    fn drop_foreign_keys(&self, predicate: impl Fn(&(RecordId, RecordId)) -> bool) {
+        let mut foreign_keys = self.foreign_keys.write().expect("to acquire write lock");
+        let mut changed = foreign_keys
+            .keys()
+            .filter(|table| predicate(table))
+            .cloned()
+            .collect::<Vec<_>>();
+        foreign_keys.retain(|table, _| !predicate(table));
+        for (table, table_foreign_keys) in foreign_keys.iter_mut() {
+            let count = table_foreign_keys.len();
+            table_foreign_keys.retain(|foreign_key| !predicate(&foreign_key.referenced_table()));
+            if table_foreign_keys.len() != count {
+                changed.push(*table);
+            }
+        }
+        for table in changed {
+            self.store_foreign_keys(&foreign_keys, table);
+        }
+    }
+
+    /// rows of the table are stored in its partitions by values of the `key` column
+    pub fn partition_by(&self, schema_id: RecordId, table_id: RecordId, strategy: PartitionStrategy, key: usize) {
+        self.partition_keys
+            .write()
+            .expect("to acquire write lock")
+            .insert((schema_id, table_id), (strategy, key));
+        self.data_definition.save_definition(
+            PARTITION_KEYS_TABLE,
+            &[Datum::from_u64(schema_id), Datum::from_u64(table_id)],
+            &(strategy, key),
+        );
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// index of the partition key column, `None` if the table is not partitioned
+    pub fn partition_key(&self, schema_id: RecordId, table_id: RecordId) -> Option<usize> {
+        self.partition_keys
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+            .map(|(_strategy, key)| *key)
+    }
+
+    /// `None` if the table is not partitioned
+    pub fn partition_strategy(&self, schema_id: RecordId, table_id: RecordId) -> Option<PartitionStrategy> {
+        self.partition_keys
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+            .map(|(strategy, _key)| *strategy)
+    }
+
+    /// partitions of the table in the order they were attached
+    pub fn partitions(&self, schema_id: RecordId, table_id: RecordId) -> Vec<PartitionDefinition> {
+        self.partitions
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// partitioned table that the table is a partition of
+    pub fn partition_parent(&self, schema_id: RecordId, table_id: RecordId) -> Option<(RecordId, RecordId)> {
+        self.partitions
+            .read()
+            .expect("to acquire read lock")
+            .iter()
+            .find(|(_parent, partitions)| {
+                partitions
+                    .iter()
+                    .any(|partition| partition.table() == (schema_id, table_id))
+            })
+            .map(|(parent, _partitions)| *parent)
+    }
+
+    /// the partition is not attached if its bounds overlap with bounds of one of existing partitions,
+    /// that partition is returned
+    pub fn attach_partition(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+        partition: PartitionDefinition,
+    ) -> Result<(), PartitionDefinition> {
+        let mut partitions = self.partitions.write().expect("to acquire write lock");
+        let table_partitions = partitions.entry((schema_id, table_id)).or_default();
+        if let Some(existing) = table_partitions.iter().find(|existing| existing.overlaps(&partition)) {
+            return Err(existing.clone());
+        }
+        table_partitions.push(partition);
+        self.store_partitions(&partitions, (schema_id, table_id));
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+        self.table_modified(schema_id, table_id);
+        Ok(())
+    }
+
+    /// the partition becomes a regular table that keeps its rows, `false` if it is not a partition of the table
+    pub fn detach_partition(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+        partition_schema_id: RecordId,
+        partition_table_id: RecordId,
+    ) -> bool {
+        let mut partitions = self.partitions.write().expect("to acquire write lock");
+        let table_partitions = match partitions.get_mut(&(schema_id, table_id)) {
+            Some(table_partitions) => table_partitions,
+            None => return false,
+        };
+        let len = table_partitions.len();
+        table_partitions.retain(|partition| partition.table() != (partition_schema_id, partition_table_id));
+        if table_partitions.len() == len {
+            return false;
+        }
+        self.store_partitions(&partitions, (schema_id, table_id));
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+        self.table_modified(schema_id, table_id);
+        true
+    }
+
+    fn store_partitions(
+        &self,
+        partitions: &HashMap<(RecordId, RecordId), Vec<PartitionDefinition>>,
+        table: (RecordId, RecordId),
+    ) {
+        let key = [Datum::from_u64(table.0), Datum::from_u64(table.1)];
+        match partitions.get(&table) {
+            Some(table_partitions) if !table_partitions.is_empty() => {
+                self.data_definition
+                    .save_definition(PARTITIONS_TABLE, &key, table_partitions)
+            }
+            _ => self.data_definition.remove_definition(PARTITIONS_TABLE, &key),
+        }
+    }
+
+    /// partition of the partitioned table that the row has to be stored in, the default partition if the
+    /// partition key of the row is out of bounds of all other partitions
+    pub fn partition_of(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+        values: &Values,
+    ) -> Option<(RecordId, RecordId)> {
+        let key = unpack_column(values.to_bytes(), self.partition_key(schema_id, table_id)?)?;
+        let partitions = self.partitions.read().expect("to acquire read lock");
+        let table_partitions = partitions.get(&(schema_id, table_id))?;
+        table_partitions
+            .iter()
+            .find(|partition| partition.contains(&key))
+            .or_else(|| table_partitions.iter().find(|partition| partition.is_default()))
+            .map(PartitionDefinition::table)
+    }
+
+    /// for each row returns the first foreign key whose values are not stored in the referenced
+    /// table together with these values. Foreign keys that are deferred by the session are not
+    /// checked, they are returned by `deferred_foreign_keys` until the transaction is committed
+    pub fn foreign_key_violations(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        rows: &[Row],
+    ) -> SystemResult<Vec<Option<ForeignKeyViolation>>> {
+        let mut violations = vec![None; rows.len()];
+        for foreign_key in self.foreign_keys(schema_id, table_id) {
+            if self.is_deferred(session_id, &foreign_key) {
+                if !rows.is_empty() {
+                    self.deferred_checks
+                        .write()
+                        .expect("to acquire write lock")
+                        .entry(session_id)
+                        .or_default()
+                        .pending
+                        .insert((schema_id, table_id, foreign_key.name().to_owned()));
+                }
+                continue;
+            }
+            let missing = self.missing_references(schema_id, table_id, &foreign_key, rows)?;
+            for (violation, key) in violations.iter_mut().zip(missing) {
+                if let (None, Some(key)) = (&violation, key) {
+                    *violation = Some((foreign_key.clone(), key));
+                }
+            }
+        }
+        Ok(violations)
+    }
+
+    /// `SET CONSTRAINTS ALL` changes when deferrable foreign keys are checked until the end
+    /// of the transaction block, it has no effect outside of it
+    pub fn set_constraints_deferred(&self, session_id: SessionId, deferred: bool) {
+        if let Some(checks) = self
+            .deferred_checks
+            .write()
+            .expect("to acquire write lock")
+            .get_mut(&session_id)
+        {
+            checks.all_deferred = Some(deferred);
+        }
+    }
+
+    fn is_deferred(&self, session_id: SessionId, foreign_key: &ForeignKeyDefinition) -> bool {
+        foreign_key.is_deferrable()
+            && self
+                .deferred_checks
+                .read()
+                .expect("to acquire read lock")
+                .get(&session_id)
+                .map(|checks| {
+                    checks
+                        .all_deferred
+                        .unwrap_or_else(|| foreign_key.is_initially_deferred())
+                })
+                .unwrap_or(false)
+    }
+
+    /// foreign keys with tables they belong to whose checks were postponed in the transaction
+    pub fn deferred_foreign_keys(&self, session_id: SessionId) -> Vec<(RecordId, RecordId, ForeignKeyDefinition)> {
+        let pending = match self
+            .deferred_checks
+            .read()
+            .expect("to acquire read lock")
+            .get(&session_id)
+        {
+            Some(checks) => checks.pending.clone(),
+            None => return vec![],
+        };
+        // foreign keys that were dropped in the meantime are not checked
+        pending
+            .into_iter()
+            .filter_map(|(schema_id, table_id, name)| {
+                self.foreign_keys(schema_id, table_id)
+                    .into_iter()
+                    .find(|foreign_key| foreign_key.name() == name)
+                    .map(|foreign_key| (schema_id, table_id, foreign_key))
+            })
+            .collect()
+    }
+
+    /// for each row returns values of the foreign key columns that are not stored in the referenced
+    /// table, values of rows that are not committed yet are taken too
+    pub fn missing_references(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+        foreign_key: &ForeignKeyDefinition,
+        rows: &[Row],
+    ) -> SystemResult<Vec<Option<Vec<String>>>> {
+        let (referenced_schema_id, referenced_table_id) = foreign_key.referenced_table();
+        let indexes = column_indexes(&self.table_columns(schema_id, table_id)?, foreign_key.columns());
+        let referenced_indexes = column_indexes(
+            &self.table_columns(referenced_schema_id, referenced_table_id)?,
+            foreign_key.referenced_columns(),
+        );
+        let referenced = self
+            .latest_rows(referenced_schema_id, referenced_table_id)?
+            .iter()
+            .filter_map(|(_key, values)| key_values(values, &referenced_indexes))
+            .collect::<BTreeSet<_>>();
+        Ok(rows
+            .iter()
+            .map(|(_key, values)| key_values(values, &indexes).filter(|key| !referenced.contains(key)))
+            .collect())
+    }
+
+    /// returns the first foreign key that references a key of `removed` rows that is not stored in the table
+    /// anymore once they are replaced by `added` rows. Foreign keys that are deferred by the session are
+    /// checked against the referencing table when the transaction is committed
+    pub fn referenced_key_violation(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        removed: &[Row],
+        added: &[Row],
+    ) -> SystemResult<Option<ReferencedKeyViolation>> {
+        let referencing = self.referencing_foreign_keys(schema_id, table_id);
+        if removed.is_empty() || referencing.is_empty() {
+            return Ok(None);
+        }
+        let removed_keys = removed.iter().map(|(key, _values)| key).collect::<BTreeSet<_>>();
+        // rows of the table as they will be stored after the change
+        let rows = self
+            .latest_rows(schema_id, table_id)?
+            .into_iter()
+            .filter(|(key, _values)| !removed_keys.contains(key))
+            .chain(added.iter().cloned())
+            .collect::<Vec<_>>();
+        let columns = self.table_columns(schema_id, table_id)?;
+        for (referencing_schema_id, referencing_table_id, foreign_key) in referencing {
+            if self.is_deferred(session_id, &foreign_key) {
+                let pending = (
+                    referencing_schema_id,
+                    referencing_table_id,
+                    foreign_key.name().to_owned(),
+                );
+                self.deferred_checks
+                    .write()
+                    .expect("to acquire write lock")
+                    .entry(session_id)
+                    .or_default()
+                    .pending
+                    .insert(pending);
+                continue;
+            }
+            let indexes = column_indexes(&columns, foreign_key.referenced_columns());
+            let remaining = rows
+                .iter()
+                .filter_map(|(_key, values)| key_values(values, &indexes))
+                .collect::<BTreeSet<_>>();
+            let lost = removed
+                .iter()
+                .filter_map(|(_key, values)| key_values(values, &indexes))
+                .filter(|key| !remaining.contains(key))
+                .collect::<BTreeSet<_>>();
+            if lost.is_empty() {
+                continue;
+            }
+            let referencing_indexes = column_indexes(
+                &self.table_columns(referencing_schema_id, referencing_table_id)?,
+                foreign_key.columns(),
+            );
+            // rows of a table that references itself are checked as they will be stored
+            let referencing_rows = if (referencing_schema_id, referencing_table_id) == (schema_id, table_id) {
+                rows.clone()
+            } else {
+                self.latest_rows(referencing_schema_id, referencing_table_id)?
+            };
+            let referenced = referencing_rows
+                .iter()
+                .filter_map(|(_key, values)| key_values(values, &referencing_indexes))
+                .find(|key| lost.contains(key));
+            if let Some(key) = referenced {
+                return Ok(Some((referencing_schema_id, referencing_table_id, foreign_key, key)));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn has_privilege(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        privilege: Privilege,
+    ) -> bool {
+        self.is_superuser(session_id)
+            || self
+                .session_privilege(session_id, schema_id, table_id, privilege)
+                .is_some()
+    }
+
+    /// superuser or a user that has privilege with grant option can grant or revoke it
+    pub fn can_grant(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        privilege: Privilege,
+    ) -> bool {
+        self.is_superuser(session_id)
+            || self.session_privilege(session_id, schema_id, table_id, privilege) == Some(true)
+    }
+
+    fn session_privilege(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        privilege: Privilege,
+    ) -> Option<bool> {
+        let session_users = self.session_users.read().expect("to acquire read lock");
+        let users = self.users.read().expect("to acquire read lock");
+        users
+            .get(session_users.get(&session_id)?)?
+            .privileges
+            .get(&(schema_id, table_id))?
+            .get(&privilege)
+            .copied()
+    }
+
+    pub fn grant(
+        &self,
+        user_name: &str,
+        schema_id: RecordId,
+        table_id: RecordId,
+        privilege: Privilege,
+        with_grant_option: bool,
+    ) {
+        let mut users = self.users.write().expect("to acquire write lock");
+        if let Some(user) = users.get_mut(user_name) {
+            let grant_option = user
+                .privileges
+                .entry((schema_id, table_id))
+                .or_default()
+                .entry(privilege)
+                .or_default();
+            *grant_option = *grant_option || with_grant_option;
+            self.store_user(&users, user_name);
+        }
+    }
+
+    pub fn revoke(&self, user_name: &str, schema_id: RecordId, table_id: RecordId, privilege: Privilege) {
+        let mut users = self.users.write().expect("to acquire write lock");
+        if let Some(user) = users.get_mut(user_name) {
+            if let Some(privileges) = user.privileges.get_mut(&(schema_id, table_id)) {
+                privileges.remove(&privilege);
+            }
+            self.store_user(&users, user_name);
+        }
+    }
+
+    /// user that created a table has all privileges on it with grant option
+    pub fn grant_to_owner(&self, session_id: SessionId, schema_id: RecordId, table_id: RecordId) {
+        let user_name = match self
+            .session_users
+            .read()
+            .expect("to acquire read lock")
+            .get(&session_id)
+        {
+            Some(user_name) => user_name.clone(),
+            None => return,
+        };
+        for privilege in &[
+            Privilege::Select,
+            Privilege::Insert,
+            Privilege::Update,
+            Privilege::Delete,
+        ] {
+            self.grant(&user_name, schema_id, table_id, *privilege, true);
+        }
+    }
+
+    fn drop_privileges(&self, dropped: impl Fn(&(RecordId, RecordId)) -> bool) {
+        let mut users = self.users.write().expect("to acquire write lock");
+        let mut changed = vec![];
+        for (user_name, user) in users.iter_mut() {
+            let count = user.privileges.len();
+            user.privileges.retain(|table, _| !dropped(table));
+            if user.privileges.len() != count {
+                changed.push(user_name.clone());
+            }
+        }
+        for user_name in changed {
+            self.store_user(&users, &user_name);
+        }
+    }
+
+    pub fn set_table_statistics(&self, schema_id: RecordId, table_id: RecordId, statistics: Vec<ColumnStatistics>) {
+        self.statistics
+            .write()
+            .expect("to acquire write lock")
+            .insert((schema_id, table_id), statistics);
+    }
+
+    pub fn table_statistics(&self, schema_id: RecordId, table_id: RecordId) -> Option<Vec<ColumnStatistics>> {
+        self.statistics
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+            .cloned()
+    }
+
+    /// counts `rows` of the table changed by INSERT, UPDATE or DELETE
+    pub fn count_changes(&self, schema_id: RecordId, table_id: RecordId, change: TableChange, rows: usize) {
+        let mut activity = self.activity.write().expect("to acquire write lock");
+        let activity = activity.entry((schema_id, table_id)).or_default();
+        let counter = match change {
+            TableChange::Inserted => &mut activity.rows_inserted,
+            TableChange::Updated => &mut activity.rows_updated,
+            TableChange::Deleted => &mut activity.rows_deleted,
+        };
+        *counter += rows as u64;
+    }
+
+    /// activity of tables ordered by schema and table names, temporary tables are not included
+    pub fn tables_activity(&self) -> SystemResult<Vec<(String, String, TableActivity)>> {
+        let mut tables = self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .iter()
+            .filter(|(_table, full_name)| !full_name[0].starts_with(TEMP_SCHEMA_PREFIX))
+            .map(|(table, full_name)| (full_name[0].clone(), full_name[1].clone(), *table))
+            .collect::<Vec<_>>();
+        tables.sort();
+        let mut activities = vec![];
+        for (schema_name, table_name, (schema_id, table_id)) in tables {
+            let mut activity = self
+                .activity
+                .read()
+                .expect("to acquire read lock")
+                .get(&(schema_id, table_id))
+                .copied()
+                .unwrap_or_default();
+            activity.live_rows = self.latest_rows(schema_id, table_id)?.len() as u64;
+            activities.push((schema_name, table_name, activity));
+        }
+        Ok(activities)
+    }
+
+    // every read of the table is a sequential scan as tables do not have indexes
+    fn count_scan(&self, table: (RecordId, RecordId), rows: usize) {
+        let mut activity = self.activity.write().expect("to acquire write lock");
+        let activity = activity.entry(table).or_default();
+        activity.sequential_scans += 1;
+        activity.rows_read += rows as u64;
+    }
+
+    /// changed by every creation or removal of a schema or a table
+    pub fn schema_version(&self) -> u64 {
+        self.schema_version.load(Ordering::SeqCst)
+    }
+
+    /// changed by every write into or deletion from the table, it is changed after the data is
+    /// modified, thus rows read after the version was taken are not older than the version
+    pub fn table_version(&self, schema_id: RecordId, table_id: RecordId) -> u64 {
+        self.table_versions
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn table_modified(&self, schema_id: RecordId, table_id: RecordId) {
+        let version = self.modifications.fetch_add(1, Ordering::SeqCst) + 1;
+        self.table_versions
+            .write()
+            .expect("to acquire write lock")
+            .insert((schema_id, table_id), version);
+    }
+
+    pub fn next_key_id(&self, schema_id: RecordId, table_id: RecordId) -> RecordId {
+        match self
+            .record_id_generators
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+        {
             Some(id_generator) => id_generator.fetch_add(1, Ordering::SeqCst),
             None => panic!(),
         }
@@ -249,6 +2069,7 @@ impl DataManager {
                     .write()
                     .expect("to acquire write lock")
                     .insert(schema_id, schema_name.to_owned());
+                self.schema_version.fetch_add(1, Ordering::SeqCst);
                 match self.data_storage.create_schema(schema_name) {
                     Ok(Ok(Ok(()))) => Ok(schema_id),
                     _ => Err(SystemError::bug_in_sql_engine(
@@ -276,9 +2097,72 @@ impl DataManager {
         match self.schemas.write().expect("to acquire write lock").remove(&schema_id) {
             None => Ok(Err(DropSchemaError::DoesNotExist)),
             Some(schema_name) => {
+                self.schema_version.fetch_add(1, Ordering::SeqCst);
+                self.drop_privileges(|(table_schema_id, _)| *table_schema_id == schema_id);
+                let dropped_functions = self
+                    .functions
+                    .read()
+                    .expect("to acquire read lock")
+                    .keys()
+                    .filter(|(function_schema_id, _)| *function_schema_id == schema_id)
+                    .map(|(_, function_name)| function_name.clone())
+                    .collect::<Vec<_>>();
+                for function_name in dropped_functions {
+                    self.drop_function(schema_id, &function_name);
+                }
+                let mut triggers = self.triggers.write().expect("to acquire write lock");
+                let dropped_triggers = triggers
+                    .keys()
+                    .filter(|(table_schema_id, _)| *table_schema_id == schema_id)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for table in dropped_triggers {
+                    triggers.remove(&table);
+                    self.store_triggers(&triggers, table);
+                }
+                drop(triggers);
+                self.drop_foreign_keys(|(table_schema_id, _)| *table_schema_id == schema_id);
+                self.row_versions
+                    .write()
+                    .expect("to acquire write lock")
+                    .drop_tables(|(table_schema_id, _)| *table_schema_id == schema_id);
+                self.statistics
+                    .write()
+                    .expect("to acquire write lock")
+                    .retain(|(table_schema_id, _), _| *table_schema_id != schema_id);
+                self.partition_keys
+                    .write()
+                    .expect("to acquire write lock")
+                    .retain(|(table_schema_id, table_id), _| {
+                        if *table_schema_id == schema_id {
+                            self.data_definition.remove_definition(
+                                PARTITION_KEYS_TABLE,
+                                &[Datum::from_u64(*table_schema_id), Datum::from_u64(*table_id)],
+                            );
+                        }
+                        *table_schema_id != schema_id
+                    });
+                let mut partitions = self.partitions.write().expect("to acquire write lock");
+                let changed = partitions
+                    .iter_mut()
+                    .filter_map(|(table, table_partitions)| {
+                        let len = table_partitions.len();
+                        table_partitions.retain(|partition| partition.table().0 != schema_id);
+                        if table.0 == schema_id || table_partitions.len() != len {
+                            Some(*table)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                partitions.retain(|(table_schema_id, _), _| *table_schema_id != schema_id);
+                for table in changed {
+                    self.store_partitions(&partitions, table);
+                }
+                drop(partitions);
                 match self
                     .data_definition
-                    .drop_schema(DEFAULT_CATALOG, schema_name.as_str(), strategy)
+                    .drop_schema(DEFAULT_CATALOG, schema_name.as_str(), strategy)?
                 {
                     Ok(()) => match self.data_storage.drop_schema(schema_name.as_str()) {
                         Ok(Ok(Ok(()))) => Ok(Ok(())),
@@ -293,18 +2177,71 @@ impl DataManager {
         }
     }
 
+    /// tables of the schema keep their ids, thus they stay accessible under the new name of the schema
+    pub fn rename_schema(&self, old_name: &str, new_name: &str) -> SystemResult<Result<(), RenameSchemaError>> {
+        let schema_id = match self.schema_exists(old_name) {
+            Some(schema_id) => schema_id,
+            None => return Ok(Err(RenameSchemaError::DoesNotExist)),
+        };
+        if self.schema_exists(new_name).is_some() {
+            return Ok(Err(RenameSchemaError::AlreadyExists));
+        }
+        let mut schemas = self.schemas.write().expect("to acquire write lock");
+        match self.data_storage.rename_schema(old_name, new_name) {
+            Ok(Ok(Ok(()))) => {}
+            Ok(Ok(Err(DefinitionError::SchemaAlreadyExists))) => return Ok(Err(RenameSchemaError::AlreadyExists)),
+            Ok(Ok(Err(_))) => return Ok(Err(RenameSchemaError::DoesNotExist)),
+            Ok(Err(_)) => {
+                return Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Schema(old_name),
+                ))
+            }
+            Err(io_error) => return Err(SystemError::io(io_error)),
+        }
+        if !self.data_definition.rename_schema(DEFAULT_CATALOG, old_name, new_name) {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Schema(old_name),
+            ));
+        }
+        schemas.insert(schema_id, new_name.to_owned());
+        for ((table_schema_id, _table_id), full_name) in self.tables.write().expect("to acquire write lock").iter_mut()
+        {
+            if *table_schema_id == schema_id {
+                full_name[0] = new_name.to_owned();
+            }
+        }
+        drop(schemas);
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+        Ok(Ok(()))
+    }
+
     pub fn create_table(
         &self,
         schema_id: RecordId,
         table_name: &str,
         column_definitions: &[ColumnDefinition],
+    ) -> SystemResult<RecordId> {
+        self.create_table_with_layout(schema_id, table_name, column_definitions, StorageLayout::Row)
+    }
+
+    pub fn create_table_with_layout(
+        &self,
+        schema_id: RecordId,
+        table_name: &str,
+        column_definitions: &[ColumnDefinition],
+        layout: StorageLayout,
     ) -> SystemResult<RecordId> {
         match self.schemas.read().expect("to acquire read lock").get(&schema_id) {
             Some(schema_name) => {
-                match self
-                    .data_definition
-                    .create_table(DEFAULT_CATALOG, schema_name, table_name, column_definitions)
-                {
+                match self.data_definition.create_table_with_layout(
+                    DEFAULT_CATALOG,
+                    schema_name,
+                    table_name,
+                    column_definitions,
+                    layout,
+                ) {
                     Some((_, Some((_, Some(table_id))))) => {
                         self.tables.write().expect("to acquire write lock").insert(
                             (schema_id, table_id),
@@ -314,13 +2251,19 @@ impl DataManager {
                             .write()
                             .expect("to acquire write lock")
                             .insert((schema_id, table_id), AtomicU64::default());
-                        match self.data_storage.create_object(schema_name, table_name) {
-                            Ok(Ok(Ok(()))) => Ok(table_id),
-                            _ => Err(SystemError::bug_in_sql_engine(
-                                Operation::Create,
-                                Object::Table(schema_name, table_name),
-                            )),
+                        self.schema_version.fetch_add(1, Ordering::SeqCst);
+                        for object_name in table_objects(&self.data_definition, schema_name, table_name) {
+                            match self.data_storage.create_object(schema_name, object_name.as_str()) {
+                                Ok(Ok(Ok(()))) => {}
+                                _ => {
+                                    return Err(SystemError::bug_in_sql_engine(
+                                        Operation::Create,
+                                        Object::Table(schema_name, table_name),
+                                    ))
+                                }
+                            }
                         }
+                        Ok(table_id)
                     }
                     _ => Err(SystemError::bug_in_sql_engine(
                         Operation::Create,
@@ -335,6 +2278,27 @@ impl DataManager {
         }
     }
 
+    pub fn create_temp_table(
+        &self,
+        session_id: SessionId,
+        schema_name: &str,
+        table_name: &str,
+        column_definitions: &[ColumnDefinition],
+        layout: StorageLayout,
+    ) -> SystemResult<RecordId> {
+        let temp_schema_name = temp_schema_name(session_id);
+        let schema_id = match self.schema_exists(&temp_schema_name) {
+            Some(schema_id) => schema_id,
+            None => self.create_schema(&temp_schema_name)?,
+        };
+        self.create_table_with_layout(
+            schema_id,
+            &temp_table_name(schema_name, table_name),
+            column_definitions,
+            layout,
+        )
+    }
+
     pub fn table_columns(&self, schema_id: RecordId, table_id: RecordId) -> SystemResult<Vec<ColumnDefinition>> {
         match self
             .tables
@@ -354,7 +2318,204 @@ impl DataManager {
         }
     }
 
+    /// creates `dst_table` with columns and layout of `src_table` and copies rows of `src_table`
+    /// that are visible to the session, copied rows get keys of the created table
+    pub fn copy_table(
+        &self,
+        session_id: SessionId,
+        src_schema: &str,
+        src_table: &str,
+        dst_schema: &str,
+        dst_table: &str,
+    ) -> SystemResult<Result<(), OperationOnTableError>> {
+        let (src_schema_id, src_table_id) = match self.table_exists(src_schema, src_table) {
+            None => return Ok(Err(OperationOnTableError::SchemaDoesNotExist(src_schema.to_owned()))),
+            Some((_, None)) => {
+                return Ok(Err(OperationOnTableError::TableDoesNotExist(format!(
+                    "{}.{}",
+                    src_schema, src_table
+                ))))
+            }
+            Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        };
+        let dst_schema_id = match self.table_exists(dst_schema, dst_table) {
+            None => return Ok(Err(OperationOnTableError::SchemaDoesNotExist(dst_schema.to_owned()))),
+            Some((_, Some(_))) => {
+                return Ok(Err(OperationOnTableError::TableAlreadyExists(format!(
+                    "{}.{}",
+                    dst_schema, dst_table
+                ))))
+            }
+            Some((schema_id, None)) => schema_id,
+        };
+        let columns = self.table_columns(src_schema_id, src_table_id)?;
+        let layout = self
+            .data_definition
+            .table_layout(DEFAULT_CATALOG, src_schema, src_table);
+        let mut rows = vec![];
+        for row in self.full_scan(session_id, src_schema_id, src_table_id)? {
+            match row {
+                Ok(Ok((_key, values))) => rows.push(values),
+                Ok(Err(_)) => {
+                    return Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(src_schema, src_table),
+                    ))
+                }
+                Err(io_error) => return Err(SystemError::io(io_error)),
+            }
+        }
+        let dst_table_id = self.create_table_with_layout(dst_schema_id, dst_table, &columns, layout)?;
+        let rows = rows
+            .into_iter()
+            .map(|values| {
+                let key = self.next_key_id(dst_schema_id, dst_table_id).to_be_bytes().to_vec();
+                (Binary::with_data(key), values)
+            })
+            .collect();
+        self.write_into(session_id, dst_schema_id, dst_table_id, rows)?;
+        Ok(Ok(()))
+    }
+
+    /// renames the column together with foreign keys that reference it, unique column keeps
+    /// to be looked up by its value under the new name
+    pub fn rename_column(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+        column_name: &str,
+        new_name: &str,
+    ) -> SystemResult<()> {
+        let full_name = match self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+        {
+            Some(full_name) => full_name.clone(),
+            None => {
+                return Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                ))
+            }
+        };
+        // foreign keys are not checked against the table while its column is renamed
+        let mut foreign_keys = self.foreign_keys.write().expect("to acquire write lock");
+        if !self.data_definition.rename_column(
+            DEFAULT_CATALOG,
+            full_name[0].as_str(),
+            full_name[1].as_str(),
+            column_name,
+            new_name,
+        ) {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+            ));
+        }
+        let rename = |columns: &mut Vec<String>| {
+            for column in columns.iter_mut().filter(|column| *column == column_name) {
+                *column = new_name.to_owned();
+            }
+        };
+        let mut changed = vec![];
+        for (table, table_foreign_keys) in foreign_keys.iter_mut() {
+            for foreign_key in table_foreign_keys.iter_mut() {
+                if *table == (schema_id, table_id) {
+                    rename(&mut foreign_key.columns);
+                    changed.push(*table);
+                }
+                if foreign_key.referenced_table() == (schema_id, table_id) {
+                    rename(&mut foreign_key.referenced_columns);
+                    changed.push(*table);
+                }
+            }
+        }
+        changed.dedup();
+        for table in changed {
+            self.store_foreign_keys(&foreign_keys, table);
+        }
+        drop(foreign_keys);
+        self.schema_version.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// declared names and types of table columns, `None` if there is no such table
+    pub fn column_types(&self, schema_name: &str, table_name: &str) -> Option<Vec<(String, SqlType)>> {
+        self.table_exists(schema_name, table_name)?.1?;
+        Some(
+            self.data_definition
+                .table_columns(DEFAULT_CATALOG, schema_name, table_name)
+                .into_iter()
+                .map(|column| (column.name(), column.sql_type()))
+                .collect(),
+        )
+    }
+
+    /// columns, constraints and triggers of the table, `None` if there is no such table
+    pub fn describe_table(&self, schema_name: &str, table_name: &str) -> Option<TableDescription> {
+        let (schema_id, table_id) = self.table_exists(schema_name, table_name)?;
+        let table_id = table_id?;
+        let columns = self
+            .data_definition
+            .table_columns(DEFAULT_CATALOG, schema_name, table_name);
+        let unique_columns = columns
+            .iter()
+            .filter(|column| column.is_unique())
+            .map(ColumnDefinition::name)
+            .collect();
+        Some(TableDescription {
+            columns,
+            unique_columns,
+            layout: self
+                .data_definition
+                .table_layout(DEFAULT_CATALOG, schema_name, table_name),
+            triggers: self.triggers(schema_id, table_id),
+        })
+    }
+
     pub fn drop_table(&self, schema_id: RecordId, table_id: RecordId) -> SystemResult<()> {
+        // partitions are dropped together with the partitioned table
+        let partitions = self
+            .partitions
+            .write()
+            .expect("to acquire write lock")
+            .remove(&(schema_id, table_id))
+            .unwrap_or_default();
+        for partition in partitions {
+            let (partition_schema_id, partition_table_id) = partition.table();
+            self.drop_table(partition_schema_id, partition_table_id)?;
+        }
+        self.partition_keys
+            .write()
+            .expect("to acquire write lock")
+            .remove(&(schema_id, table_id));
+        self.data_definition.remove_definition(
+            PARTITION_KEYS_TABLE,
+            &[Datum::from_u64(schema_id), Datum::from_u64(table_id)],
+        );
+        self.data_definition.remove_definition(
+            PARTITIONS_TABLE,
+            &[Datum::from_u64(schema_id), Datum::from_u64(table_id)],
+        );
+        let mut partitions = self.partitions.write().expect("to acquire write lock");
+        let changed = partitions
+            .iter_mut()
+            .filter_map(|(table, table_partitions)| {
+                let len = table_partitions.len();
+                table_partitions.retain(|partition| partition.table() != (schema_id, table_id));
+                if table_partitions.len() != len {
+                    Some(*table)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        for table in changed {
+            self.store_partitions(&partitions, table);
+        }
+        drop(partitions);
         match self
             .tables
             .write()
@@ -366,24 +2527,52 @@ impl DataManager {
                 Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
             )),
             Some(full_name) => {
+                self.schema_version.fetch_add(1, Ordering::SeqCst);
+                self.table_modified(schema_id, table_id);
+                self.drop_privileges(|table| *table == (schema_id, table_id));
+                let mut triggers = self.triggers.write().expect("to acquire write lock");
+                triggers.remove(&(schema_id, table_id));
+                self.store_triggers(&triggers, (schema_id, table_id));
+                drop(triggers);
+                // foreign keys that reference the table are dropped with it
+                self.drop_foreign_keys(|table| *table == (schema_id, table_id));
+                self.row_versions
+                    .write()
+                    .expect("to acquire write lock")
+                    .drop_tables(|table| *table == (schema_id, table_id));
+                self.statistics
+                    .write()
+                    .expect("to acquire write lock")
+                    .remove(&(schema_id, table_id));
+                self.activity
+                    .write()
+                    .expect("to acquire write lock")
+                    .remove(&(schema_id, table_id));
+                let objects = table_objects(&self.data_definition, full_name[0].as_str(), full_name[1].as_str());
                 self.data_definition
                     .drop_table(DEFAULT_CATALOG, full_name[0].as_str(), full_name[1].as_str());
-                match self
-                    .data_storage
-                    .drop_object(full_name[0].as_str(), full_name[1].as_str())
-                {
-                    Ok(Ok(Ok(()))) => Ok(()),
-                    _ => Err(SystemError::bug_in_sql_engine(
-                        Operation::Drop,
-                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
-                    )),
+                for object_name in objects {
+                    match self
+                        .data_storage
+                        .drop_object(full_name[0].as_str(), object_name.as_str())
+                    {
+                        Ok(Ok(Ok(()))) => {}
+                        _ => {
+                            return Err(SystemError::bug_in_sql_engine(
+                                Operation::Drop,
+                                Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                            ))
+                        }
+                    }
                 }
+                Ok(())
             }
         }
     }
 
     pub fn write_into(
         &self,
+        session_id: SessionId,
         schema_id: RecordId,
         table_id: RecordId,
         values: Vec<(Key, Values)>,
@@ -396,11 +2585,20 @@ impl DataManager {
         {
             Some(full_name) => {
                 log::debug!("{:#?}", values);
-                match self
-                    .data_storage
-                    .write(full_name[0].as_str(), full_name[1].as_str(), values)
-                {
-                    Ok(Ok(Ok(size))) => Ok(size),
+                let mut row_versions = self.row_versions.write().expect("to acquire write lock");
+                let keys = values.iter().map(|(key, _values)| key.clone()).collect::<Vec<_>>();
+                self.keep_versions(&mut row_versions, session_id, (schema_id, table_id), full_name, keys)?;
+                let written = match self.layout(full_name) {
+                    StorageLayout::Row => self
+                        .data_storage
+                        .write(full_name[0].as_str(), full_name[1].as_str(), values),
+                    StorageLayout::Columnar => self.write_columns(full_name, values),
+                };
+                match written {
+                    Ok(Ok(Ok(size))) => {
+                        self.table_modified(schema_id, table_id);
+                        Ok(size)
+                    }
                     _ => Err(SystemError::bug_in_sql_engine(
                         Operation::Access,
                         Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
@@ -414,15 +2612,95 @@ impl DataManager {
         }
     }
 
-    pub fn full_scan(&self, schema_id: RecordId, table_id: RecordId) -> SystemResult<ReadCursor> {
+    pub fn insert_or_ignore(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        rows: Vec<Row>,
+    ) -> SystemResult<usize> {
+        let conflicts = self.unique_conflicts(schema_id, table_id, &rows)?;
+        let to_write = rows
+            .into_iter()
+            .zip(conflicts)
+            .filter(|(_row, conflict)| conflict.is_none())
+            .map(|(row, _conflict)| row)
+            .collect();
+        self.write_into(session_id, schema_id, table_id, to_write)
+    }
+
+    /// for each row returns the name of the first unique column whose value is
+    /// already stored in the table or used by one of the preceding rows, values of rows
+    /// that are not committed yet are taken too
+    pub fn unique_conflicts(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+        rows: &[Row],
+    ) -> SystemResult<Vec<Option<String>>> {
+        let unique_columns = self
+            .table_columns(schema_id, table_id)?
+            .into_iter()
+            .enumerate()
+            .filter(|(_index, column)| column.is_unique())
+            .collect::<Vec<_>>();
+        if unique_columns.is_empty() {
+            return Ok(vec![None; rows.len()]);
+        }
+        let unique_values = |values: &Values| {
+            let data = values.unpack();
+            unique_columns
+                .iter()
+                .filter_map(|(index, column)| match data.get(*index) {
+                    None | Some(Datum::Null) => None,
+                    Some(datum) => Some((*index, column.name(), Binary::pack(std::slice::from_ref(datum)))),
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut taken = BTreeSet::new();
+        for (_key, values) in self.latest_rows(schema_id, table_id)? {
+            for (index, _name, value) in unique_values(&values) {
+                taken.insert((index, value));
+            }
+        }
+        let mut conflicts = vec![];
+        for (_key, values) in rows {
+            let values = unique_values(values);
+            let conflict = values
+                .iter()
+                .find(|(index, _name, value)| taken.contains(&(*index, value.clone())))
+                .map(|(_index, name, _value)| name.clone());
+            if conflict.is_none() {
+                for (index, _name, value) in values {
+                    taken.insert((index, value));
+                }
+            }
+            conflicts.push(conflict);
+        }
+        Ok(conflicts)
+    }
+
+    /// rows visible to the session
+    pub fn full_scan(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+    ) -> SystemResult<ReadCursor> {
         match self
             .tables
             .read()
             .expect("to acquire read lock")
             .get(&(schema_id, table_id))
         {
-            Some(full_name) => match self.data_storage.read(full_name[0].as_str(), full_name[1].as_str()) {
-                Ok(Ok(Ok(read))) => Ok(read),
+            Some(full_name) => match self.visible_rows(session_id, (schema_id, table_id), full_name, None, None) {
+                Ok(Ok(Ok(rows))) => {
+                    self.count_scan((schema_id, table_id), rows.len());
+                    Ok(Box::new(rows.into_iter().map(|row| Ok(Ok(row)))))
+                }
+                Ok(Err(StorageError::Corruption(message))) => Err(SystemError::data_corruption(message)),
+                Ok(Err(error)) => Ok(Box::new(std::iter::once(Ok(Err(error))))),
+                Err(error) => Ok(Box::new(std::iter::once(Err(error)))),
                 _ => Err(SystemError::bug_in_sql_engine(
                     Operation::Access,
                     Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
@@ -435,18 +2713,250 @@ impl DataManager {
         }
     }
 
-    pub fn delete_from(&self, schema_id: RecordId, table_id: RecordId, keys: Vec<Key>) -> SystemResult<usize> {
+    /// text values of the `columns` of the table, NULLs are `None`. Rows are read from the storage page by page
+    /// as the iterator is advanced, so a table larger than memory can be sent row by row. Rows of a table
+    /// that has changes which not every session sees are read in advance
+    pub fn select_scan<'s>(
+        &'s self,
+        session_id: SessionId,
+        schema_name: &str,
+        table_name: &str,
+        columns: &[String],
+    ) -> SystemResult<impl Iterator<Item = SystemResult<Vec<Option<String>>>> + 's> {
+        let table = match self.session_table_exists(session_id, schema_name, table_name) {
+            Some((schema_id, Some(table_id))) => (schema_id, table_id),
+            _ => {
+                return Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_name, table_name),
+                ))
+            }
+        };
+        let indexes = column_indexes(&self.table_columns(table.0, table.1)?, columns);
+        let full_name = self.tables.read().expect("to acquire read lock").get(&table).cloned();
+        let full_name = match full_name {
+            Some(full_name) if indexes.len() == columns.len() => full_name,
+            _ => {
+                return Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(schema_name, table_name),
+                ))
+            }
+        };
+        let source = if self.is_latest(table.0, table.1) {
+            let (objects, columns) = match self.layout(&full_name) {
+                StorageLayout::Row => (vec![full_name[1].clone()], indexes),
+                // storage objects of the columns that are not selected are not read
+                StorageLayout::Columnar => {
+                    let objects = table_objects(&self.data_definition, full_name[0].as_str(), full_name[1].as_str());
+                    (indexes.iter().map(|index| objects[*index].clone()).collect(), vec![0])
+                }
+            };
+            ScanSource::Objects {
+                schema_name: full_name[0].clone(),
+                objects,
+                columns,
+                after: None,
+                finished: false,
+            }
+        } else {
+            ScanSource::Visible(
+                read_error(
+                    table,
+                    self.visible_rows(session_id, table, &full_name, Some(&indexes), None),
+                )?
+                .into_iter(),
+            )
+        };
+        let mut scan = SelectScan {
+            data_manager: self,
+            table,
+            source,
+            page: VecDeque::new(),
+            rows: 0,
+        };
+        // failures of reading the first page are reported before any row is sent
+        scan.read_page()?;
+        Ok(scan)
+    }
+
+    /// rows with values of only `columns` in the given order, for a columnar table
+    /// storage objects of the other columns are not read
+    pub fn scan_columns(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        columns: &[usize],
+    ) -> SystemResult<ReadCursor> {
+        let table_columns = self.table_columns(schema_id, table_id)?.len();
+        match self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+        {
+            Some(full_name) if columns.iter().all(|index| *index < table_columns) => {
+                match self.visible_rows(session_id, (schema_id, table_id), full_name, Some(columns), None) {
+                    Ok(Ok(Ok(rows))) => {
+                        self.count_scan((schema_id, table_id), rows.len());
+                        Ok(Box::new(rows.into_iter().map(|row| Ok(Ok(row)))))
+                    }
+                    Ok(Err(StorageError::Corruption(message))) => Err(SystemError::data_corruption(message)),
+                    Ok(Err(error)) => Ok(Box::new(std::iter::once(Ok(Err(error))))),
+                    Err(error) => Ok(Box::new(std::iter::once(Err(error)))),
+                    _ => Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    )),
+                }
+            }
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    /// rows which value of `column` is equal to `value`, only values of that column
+    /// are decoded for rows that do not match
+    pub fn scan_where(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        column: usize,
+        value: &Datum,
+    ) -> SystemResult<ReadCursor> {
+        let table_columns = self.table_columns(schema_id, table_id)?.len();
         match self
             .tables
             .read()
             .expect("to acquire read lock")
             .get(&(schema_id, table_id))
         {
-            Some(full_name) => match self
-                .data_storage
-                .delete(full_name[0].as_str(), full_name[1].as_str(), keys)
-            {
-                Ok(Ok(Ok(len))) => Ok(len),
+            Some(full_name) if column < table_columns => {
+                match self.visible_rows(
+                    session_id,
+                    (schema_id, table_id),
+                    full_name,
+                    None,
+                    Some((column, value)),
+                ) {
+                    Ok(Ok(Ok(rows))) => {
+                        self.count_scan((schema_id, table_id), rows.len());
+                        Ok(Box::new(rows.into_iter().map(|row| Ok(Ok(row)))))
+                    }
+                    Ok(Err(StorageError::Corruption(message))) => Err(SystemError::data_corruption(message)),
+                    Ok(Err(error)) => Ok(Box::new(std::iter::once(Ok(Err(error))))),
+                    Err(error) => Ok(Box::new(std::iter::once(Err(error)))),
+                    _ => Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    )),
+                }
+            }
+            _ => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    /// replaces values of columns in `updates` for rows visible to the session that satisfy
+    /// `predicate`, changed rows are written in one batch after the scan, returns their number
+    pub fn bulk_update_where(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        updates: &[(usize, Datum)],
+        predicate: &dyn Fn(&[Datum]) -> bool,
+    ) -> SystemResult<usize> {
+        let table_columns = self.table_columns(schema_id, table_id)?.len();
+        if updates.iter().any(|(index, _value)| *index >= table_columns) {
+            return Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+            ));
+        }
+        let mut changed = vec![];
+        for row in self.full_scan(session_id, schema_id, table_id)? {
+            let (key, values) = match row {
+                Ok(Ok(row)) => row,
+                Ok(Err(_)) => {
+                    return Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    ))
+                }
+                Err(error) => return Err(SystemError::io(error)),
+            };
+            let mut data = values.unpack();
+            if predicate(&data) {
+                for (index, value) in updates {
+                    data[*index] = value.clone();
+                }
+                changed.push((key, Binary::pack(&data)));
+            }
+        }
+        if changed.is_empty() {
+            return Ok(0);
+        }
+        self.write_into(session_id, schema_id, table_id, changed)
+    }
+
+    pub fn delete_from(
+        &self,
+        session_id: SessionId,
+        schema_id: RecordId,
+        table_id: RecordId,
+        keys: Vec<Key>,
+    ) -> SystemResult<usize> {
+        match self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+        {
+            Some(full_name) => {
+                let mut row_versions = self.row_versions.write().expect("to acquire write lock");
+                self.keep_versions(
+                    &mut row_versions,
+                    session_id,
+                    (schema_id, table_id),
+                    full_name,
+                    keys.clone(),
+                )?;
+                match self.delete_rows(full_name, keys) {
+                    Ok(Ok(Ok(len))) => {
+                        self.table_modified(schema_id, table_id);
+                        Ok(len)
+                    }
+                    _ => Err(SystemError::bug_in_sql_engine(
+                        Operation::Access,
+                        Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+                    )),
+                }
+            }
+            None => Err(SystemError::bug_in_sql_engine(
+                Operation::Access,
+                Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
+            )),
+        }
+    }
+
+    // the latest versions of rows including changes of transactions that are not committed yet
+    fn latest_rows(&self, schema_id: RecordId, table_id: RecordId) -> SystemResult<Vec<Row>> {
+        match self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+        {
+            Some(full_name) => match self.read_rows(full_name, None, None) {
+                Ok(Ok(Ok(rows))) => Ok(rows),
+                Ok(Err(StorageError::Corruption(message))) => Err(SystemError::data_corruption(message)),
                 _ => Err(SystemError::bug_in_sql_engine(
                     Operation::Access,
                     Object::Table(schema_id.to_string().as_str(), table_id.to_string().as_str()),
@@ -459,6 +2969,186 @@ impl DataManager {
         }
     }
 
+    // stored versions of rows are kept before they are replaced or deleted, the caller holds
+    // the lock of row versions until the storage is changed
+    fn keep_versions(
+        &self,
+        row_versions: &mut Versions,
+        session_id: SessionId,
+        table: (RecordId, RecordId),
+        full_name: &[String],
+        keys: Vec<Key>,
+    ) -> SystemResult<()> {
+        if !row_versions.is_tracked() {
+            return Ok(());
+        }
+        let mut stored = match self.read_rows(full_name, None, None) {
+            Ok(Ok(Ok(rows))) => rows.into_iter().collect::<BTreeMap<_, _>>(),
+            Ok(Err(StorageError::Corruption(message))) => return Err(SystemError::data_corruption(message)),
+            _ => {
+                return Err(SystemError::bug_in_sql_engine(
+                    Operation::Access,
+                    Object::Table(table.0.to_string().as_str(), table.1.to_string().as_str()),
+                ))
+            }
+        };
+        let transaction = row_versions.writer(session_id);
+        for key in keys {
+            let previous = stored.remove(&key);
+            row_versions.record(table, transaction, key, previous);
+        }
+        Ok(())
+    }
+
+    // changes of transactions that are not visible to the session are reverted before
+    // rows are filtered and projected
+    fn visible_rows(
+        &self,
+        session_id: SessionId,
+        table: (RecordId, RecordId),
+        full_name: &[String],
+        columns: Option<&[usize]>,
+        filter: Option<(usize, &Datum)>,
+    ) -> io::Result<Result<Result<Vec<Row>, DefinitionError>, StorageError>> {
+        let row_versions = self.row_versions.read().expect("to acquire read lock");
+        if row_versions.is_latest(table) {
+            return self.read_rows(full_name, columns, filter);
+        }
+        let rows = match self.read_rows(full_name, None, None)? {
+            Ok(Ok(rows)) => row_versions.visible(table, &row_versions.snapshot(session_id), rows),
+            Ok(Err(error)) => return Ok(Ok(Err(error))),
+            Err(error) => return Ok(Err(error)),
+        };
+        Ok(Ok(Ok(rows
+            .into_iter()
+            .filter(|(_key, values)| match filter {
+                None => true,
+                Some((column, value)) => column_equals(values, column, value),
+            })
+            .map(|(key, values)| match columns {
+                None => (key, values),
+                Some(columns) => (key, columnar::project(&values, columns)),
+            })
+            .collect())))
+    }
+
+    fn layout(&self, full_name: &[String]) -> StorageLayout {
+        self.data_definition
+            .table_layout(DEFAULT_CATALOG, full_name[0].as_str(), full_name[1].as_str())
+    }
+
+    fn write_columns(
+        &self,
+        full_name: &[String],
+        rows: Vec<Row>,
+    ) -> io::Result<Result<Result<usize, DefinitionError>, StorageError>> {
+        let len = rows.len();
+        let objects = table_objects(&self.data_definition, full_name[0].as_str(), full_name[1].as_str());
+        let columns = columnar::split(rows, objects.len());
+        for (object_name, records) in objects.iter().zip(columns) {
+            match self
+                .data_storage
+                .write(full_name[0].as_str(), object_name.as_str(), records)?
+            {
+                Ok(Ok(_)) => {}
+                written => return Ok(written),
+            }
+        }
+        Ok(Ok(Ok(len)))
+    }
+
+    // rows are filtered by `filter` before they are assembled or projected
+    fn read_rows(
+        &self,
+        full_name: &[String],
+        columns: Option<&[usize]>,
+        filter: Option<(usize, &Datum)>,
+    ) -> io::Result<Result<Result<Vec<Row>, DefinitionError>, StorageError>> {
+        match self.layout(full_name) {
+            StorageLayout::Row => {
+                let rows = match filter {
+                    None => self
+                        .data_storage
+                        .scan(full_name[0].as_str(), full_name[1].as_str(), &|_values| true),
+                    Some((column, value)) => {
+                        self.data_storage
+                            .scan(full_name[0].as_str(), full_name[1].as_str(), &|values| {
+                                column_equals(values, column, value)
+                            })
+                    }
+                };
+                match columns {
+                    None => rows,
+                    Some(columns) => rows.map(|rows| {
+                        rows.map(|rows| {
+                            rows.map(|rows| {
+                                rows.into_iter()
+                                    .map(|(key, values)| (key, columnar::project(&values, columns)))
+                                    .collect()
+                            })
+                        })
+                    }),
+                }
+            }
+            StorageLayout::Columnar => {
+                let objects = table_objects(&self.data_definition, full_name[0].as_str(), full_name[1].as_str());
+                let keys =
+                    match filter {
+                        None => None,
+                        Some((column, value)) => match self.data_storage.scan(
+                            full_name[0].as_str(),
+                            objects[column].as_str(),
+                            &|values| column_equals(values, 0, value),
+                        )? {
+                            Ok(Ok(records)) => {
+                                Some(records.into_iter().map(|(key, _values)| key).collect::<BTreeSet<_>>())
+                            }
+                            Ok(Err(error)) => return Ok(Ok(Err(error))),
+                            Err(error) => return Ok(Err(error)),
+                        },
+                    };
+                let objects = match columns {
+                    None => objects,
+                    Some(columns) => columns.iter().map(|index| objects[*index].clone()).collect(),
+                };
+                let mut records = Vec::with_capacity(objects.len());
+                for object_name in objects {
+                    match self
+                        .data_storage
+                        .scan(full_name[0].as_str(), object_name.as_str(), &|_values| true)?
+                    {
+                        Ok(Ok(mut column)) => {
+                            if let Some(keys) = keys.as_ref() {
+                                column.retain(|(key, _values)| keys.contains(key));
+                            }
+                            records.push(column)
+                        }
+                        Ok(Err(error)) => return Ok(Ok(Err(error))),
+                        Err(error) => return Ok(Err(error)),
+                    }
+                }
+                Ok(Ok(Ok(columnar::assemble(records))))
+            }
+        }
+    }
+
+    fn delete_rows(
+        &self,
+        full_name: &[String],
+        keys: Vec<Key>,
+    ) -> io::Result<Result<Result<usize, DefinitionError>, StorageError>> {
+        let mut deleted = Ok(Ok(0));
+        for object_name in table_objects(&self.data_definition, full_name[0].as_str(), full_name[1].as_str()) {
+            deleted = self
+                .data_storage
+                .delete(full_name[0].as_str(), object_name.as_str(), keys.clone())?;
+            if !matches!(deleted, Ok(Ok(_))) {
+                break;
+            }
+        }
+        Ok(deleted)
+    }
+
     pub fn schema_exists(&self, schema_name: &str) -> FullSchemaId {
         self.data_definition
             .schema_exists(DEFAULT_CATALOG, schema_name)
@@ -470,6 +3160,88 @@ impl DataManager {
             .table_exists(DEFAULT_CATALOG, schema_name, table_name)
             .and_then(|(_catalog, full_table)| full_table)
     }
+
+    /// index of the column among columns of the table, `None` if the schema,
+    /// the table or the column does not exist
+    pub fn column_exists(&self, schema_name: &str, table_name: &str, column_name: &str) -> Option<usize> {
+        self.table_exists(schema_name, table_name)?.1?;
+        self.data_definition
+            .table_columns(DEFAULT_CATALOG, schema_name, table_name)
+            .iter()
+            .position(|column| column.has_name(column_name))
+    }
+
+    pub fn temp_table_exists(
+        &self,
+        session_id: SessionId,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Option<(RecordId, RecordId)> {
+        match self.table_exists(&temp_schema_name(session_id), &temp_table_name(schema_name, table_name)) {
+            Some((schema_id, Some(table_id))) => Some((schema_id, table_id)),
+            _ => None,
+        }
+    }
+
+    /// the same as `table_exists` but temporary table of the session shadows
+    /// a permanent table with the same name
+    pub fn session_table_exists(&self, session_id: SessionId, schema_name: &str, table_name: &str) -> FullTableId {
+        match self.temp_table_exists(session_id, schema_name, table_name) {
+            Some((schema_id, table_id)) => Some((schema_id, Some(table_id))),
+            None => self.table_exists(schema_name, table_name),
+        }
+    }
+
+    /// names of all schemas in alphabetical order, schemas of temporary tables are not listed
+    pub fn schemas(&self) -> Vec<String> {
+        let mut schemas: Vec<String> = self
+            .schemas
+            .read()
+            .expect("to acquire read lock")
+            .values()
+            .filter(|schema_name| !schema_name.starts_with(TEMP_SCHEMA_PREFIX))
+            .cloned()
+            .collect();
+        schemas.sort();
+        schemas
+    }
+
+    pub fn schema_name(&self, schema_id: RecordId) -> Option<String> {
+        self.schemas
+            .read()
+            .expect("to acquire read lock")
+            .get(&schema_id)
+            .cloned()
+    }
+
+    pub fn table_name(&self, schema_id: RecordId, table_id: RecordId) -> Option<String> {
+        self.tables
+            .read()
+            .expect("to acquire read lock")
+            .get(&(schema_id, table_id))
+            .map(|full_name| full_name[1].clone())
+    }
+
+    /// names of tables of the schema in alphabetical order, `None` if the schema does not exist
+    pub fn tables(&self, schema_name: &str) -> Option<Vec<String>> {
+        let schema_id = self
+            .schemas
+            .read()
+            .expect("to acquire read lock")
+            .iter()
+            .find(|(_schema_id, name)| name.as_str() == schema_name)
+            .map(|(schema_id, _name)| *schema_id)?;
+        let mut tables: Vec<String> = self
+            .tables
+            .read()
+            .expect("to acquire read lock")
+            .iter()
+            .filter(|((table_schema_id, _table_id), _full_name)| *table_schema_id == schema_id)
+            .map(|(_ids, full_name)| full_name[1].clone())
+            .collect();
+        tables.sort();
+        Some(tables)
+    }
 }
 
 #[cfg(test)]
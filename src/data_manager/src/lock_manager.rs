@@ -0,0 +1,125 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{RecordId, SessionId};
+use std::{
+    collections::HashMap,
+    sync::{Condvar, Mutex},
+};
+
+/// Mode of a table lock, a lock can't be acquired while other session holds a conflicting one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockMode {
+    /// acquired by SELECT, conflicts only with `AccessExclusive`
+    AccessShare,
+    /// acquired by INSERT, UPDATE and DELETE
+    RowExclusive,
+    /// allows only concurrent reads of a table
+    Exclusive,
+    /// the only lock on a table, default mode of `LOCK TABLE`
+    AccessExclusive,
+}
+
+impl LockMode {
+    fn conflicts_with(self, other: LockMode) -> bool {
+        match (self, other) {
+            (LockMode::AccessExclusive, _) | (_, LockMode::AccessExclusive) => true,
+            (LockMode::AccessShare, _) | (_, LockMode::AccessShare) => false,
+            (LockMode::RowExclusive, LockMode::RowExclusive) => false,
+            _ => true,
+        }
+    }
+}
+
+type Locks = HashMap<(RecordId, RecordId), Vec<(SessionId, LockMode)>>;
+
+/// table locks held by sessions, a session doesn't conflict with its own locks
+#[derive(Default)]
+pub(crate) struct LockManager {
+    locks: Mutex<Locks>,
+    released: Condvar,
+}
+
+impl LockManager {
+    /// waits until conflicting locks of other sessions are released
+    pub(crate) fn lock(&self, session_id: SessionId, table: (RecordId, RecordId), mode: LockMode) {
+        let mut locks = self.locks.lock().expect("to acquire lock");
+        while Self::conflicts(&locks, session_id, table, mode) {
+            locks = self.released.wait(locks).expect("to acquire lock");
+        }
+        locks.entry(table).or_default().push((session_id, mode));
+    }
+
+    /// `false` when other session holds a conflicting lock
+    pub(crate) fn try_lock(&self, session_id: SessionId, table: (RecordId, RecordId), mode: LockMode) -> bool {
+        let mut locks = self.locks.lock().expect("to acquire lock");
+        if Self::conflicts(&locks, session_id, table, mode) {
+            false
+        } else {
+            locks.entry(table).or_default().push((session_id, mode));
+            true
+        }
+    }
+
+    pub(crate) fn release(&self, session_id: SessionId) {
+        let mut locks = self.locks.lock().expect("to acquire lock");
+        for held in locks.values_mut() {
+            held.retain(|(holder, _)| *holder != session_id);
+        }
+        locks.retain(|_, held| !held.is_empty());
+        self.released.notify_all();
+    }
+
+    fn conflicts(locks: &Locks, session_id: SessionId, table: (RecordId, RecordId), mode: LockMode) -> bool {
+        locks
+            .get(&table)
+            .into_iter()
+            .flatten()
+            .any(|(holder, held_mode)| *holder != session_id && mode.conflicts_with(*held_mode))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: (RecordId, RecordId) = (0, 0);
+
+    #[test]
+    fn conflicting_modes() {
+        assert!(!LockMode::AccessShare.conflicts_with(LockMode::Exclusive));
+        assert!(!LockMode::RowExclusive.conflicts_with(LockMode::RowExclusive));
+        assert!(LockMode::RowExclusive.conflicts_with(LockMode::Exclusive));
+        assert!(LockMode::Exclusive.conflicts_with(LockMode::Exclusive));
+        assert!(LockMode::AccessShare.conflicts_with(LockMode::AccessExclusive));
+    }
+
+    #[test]
+    fn session_does_not_conflict_with_itself() {
+        let manager = LockManager::default();
+        manager.lock(1, TABLE, LockMode::Exclusive);
+
+        assert!(manager.try_lock(1, TABLE, LockMode::AccessExclusive));
+        assert!(!manager.try_lock(2, TABLE, LockMode::RowExclusive));
+    }
+
+    #[test]
+    fn released_lock() {
+        let manager = LockManager::default();
+        manager.lock(1, TABLE, LockMode::Exclusive);
+        manager.release(1);
+
+        assert!(manager.try_lock(2, TABLE, LockMode::Exclusive));
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    data_definition::{DEFINITION_SCHEMA, DEFINITION_TABLES, SYSTEM_CATALOG},
+    persistent, DEFAULT_CATALOG,
+};
+use kernel::{SystemError, SystemResult};
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// version of the storage layout that is written by the current code
+pub const SCHEMA_VERSION: u32 = 3;
+/// storage created before the version was tracked has the first layout
+const INITIAL_VERSION: u32 = 1;
+const SCHEMA_VERSION_FILE: &'_ str = "SCHEMA_VERSION";
+
+/// upgrades the storage layout under the root path from one version to another
+pub type MigrateFn = fn(&Path) -> io::Result<()>;
+
+/// Runs migrations of an existing storage when it was written by older code.
+/// The storage is backed up before migrations run and restored if one of them fails,
+/// thus the storage is either fully migrated or left untouched.
+pub struct Migrator {
+    latest: u32,
+    migrations: BTreeMap<u32, (u32, MigrateFn)>,
+}
+
+impl Default for Migrator {
+    fn default() -> Migrator {
+        Migrator::new(SCHEMA_VERSION)
+            .register(1, 2, row_checksums)
+            .register(2, 3, definition_tables)
+    }
+}
+
+impl Migrator {
+    pub fn new(latest: u32) -> Migrator {
+        Migrator {
+            latest,
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    pub fn register(mut self, from: u32, to: u32, migrate: MigrateFn) -> Migrator {
+        assert!(from < to, "migration from {} to {} does not upgrade storage", from, to);
+        self.migrations.insert(from, (to, migrate));
+        self
+    }
+
+    /// version of the storage under the root path, `None` when there is no storage yet
+    pub fn stored_version(path: &Path) -> SystemResult<Option<u32>> {
+        let system_catalog = path.join(SYSTEM_CATALOG);
+        if !system_catalog.exists() {
+            return Ok(None);
+        }
+        match fs::read_to_string(system_catalog.join(SCHEMA_VERSION_FILE)) {
+            Ok(version) => match version.trim().parse() {
+                Ok(version) => Ok(Some(version)),
+                Err(_) => Err(SystemError::unrecoverable(format!(
+                    "storage schema version {:?} is corrupted",
+                    version
+                ))),
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Some(INITIAL_VERSION)),
+            Err(error) => Err(SystemError::io(error)),
+        }
+    }
+
+    /// brings the storage under the root path to the latest version
+    pub(crate) fn migrate(&self, path: &Path) -> SystemResult<()> {
+        let stored = match Self::stored_version(path)? {
+            None => {
+                fs::create_dir_all(path.join(SYSTEM_CATALOG)).map_err(SystemError::io)?;
+                return write_version(path, self.latest).map_err(SystemError::io);
+            }
+            Some(stored) => stored,
+        };
+        if stored > self.latest {
+            return Err(SystemError::runtime_check_failure(format!(
+                "storage schema version {} is newer than supported version {}",
+                stored, self.latest
+            )));
+        }
+        if stored == self.latest {
+            return Ok(());
+        }
+
+        let backup = backup_path(path);
+        if backup.exists() {
+            fs::remove_dir_all(&backup).map_err(SystemError::io)?;
+        }
+        copy_dir(path, &backup).map_err(SystemError::io)?;
+        match self.run(path, stored) {
+            Ok(()) => fs::remove_dir_all(&backup).map_err(SystemError::io),
+            Err(error) => {
+                fs::remove_dir_all(path).map_err(SystemError::io)?;
+                fs::rename(&backup, path).map_err(SystemError::io)?;
+                Err(error)
+            }
+        }
+    }
+
+    fn run(&self, path: &Path, stored: u32) -> SystemResult<()> {
+        let mut version = stored;
+        while version < self.latest {
+            let (to, migrate) = match self.migrations.get(&version) {
+                Some(migration) => *migration,
+                None => {
+                    return Err(SystemError::runtime_check_failure(format!(
+                        "there is no migration of storage schema from version {}",
+                        version
+                    )))
+                }
+            };
+            if let Err(error) = migrate(path) {
+                log::error!("migration from {} to {} has failed {:?}", version, to, error);
+                return Err(SystemError::io(error));
+            }
+            write_version(path, to).map_err(SystemError::io)?;
+            version = to;
+        }
+        Ok(())
+    }
+}
+
+// rows of the first version are stored without checksums
+fn row_checksums(path: &Path) -> io::Result<()> {
+    for catalog in &[SYSTEM_CATALOG, DEFAULT_CATALOG] {
+        let catalog = path.join(catalog);
+        if !catalog.exists() {
+            continue;
+        }
+        for entry in fs::read_dir(catalog)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                persistent::add_checksums(&entry.path())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// definitions that were kept only in memory by the second version are stored in their own tables
+fn definition_tables(path: &Path) -> io::Result<()> {
+    persistent::create_objects(&path.join(SYSTEM_CATALOG).join(DEFINITION_SCHEMA), &DEFINITION_TABLES)
+}
+
+fn write_version(path: &Path, version: u32) -> io::Result<()> {
+    let system_catalog = path.join(SYSTEM_CATALOG);
+    let temporary = system_catalog.join(format!("{}.tmp", SCHEMA_VERSION_FILE));
+    fs::write(&temporary, version.to_string())?;
+    fs::rename(temporary, system_catalog.join(SCHEMA_VERSION_FILE))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = OsString::from(path.as_os_str());
+    backup.push(".backup");
+    PathBuf::from(backup)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
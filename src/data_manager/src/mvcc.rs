@@ -0,0 +1,245 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Key, RecordId, Row, SessionId, Values};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+pub(crate) type TransactionId = u64;
+
+/// Transactions whose changes are seen by a reader
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Snapshot {
+    /// transaction of the reader, it sees its own changes
+    own: Option<TransactionId>,
+    /// the first transaction that was not started when the snapshot was taken
+    next: TransactionId,
+    /// transactions that were not committed when the snapshot was taken
+    in_progress: BTreeSet<TransactionId>,
+}
+
+impl Snapshot {
+    fn sees(&self, transaction: TransactionId) -> bool {
+        self.own == Some(transaction) || (transaction < self.next && !self.in_progress.contains(&transaction))
+    }
+}
+
+// version of a row before it was changed by the transaction, `None` when the row was inserted
+struct RowVersion {
+    transaction: TransactionId,
+    key: Key,
+    previous: Option<Values>,
+}
+
+/// Storage keeps only the latest version of a row. Previous versions are kept in memory
+/// while there is a snapshot that doesn't see the transaction that replaced them, a reader
+/// reverts changes of the transactions that are not visible to its snapshot.
+/// Statements executed outside of a transaction block run in their own transaction that is
+/// committed right away.
+#[derive(Default)]
+pub(crate) struct Versions {
+    next_transaction: TransactionId,
+    /// transactions and snapshots of sessions inside of a transaction block
+    transactions: HashMap<SessionId, (TransactionId, Snapshot)>,
+    /// previous versions of rows in the order they were replaced
+    tables: HashMap<(RecordId, RecordId), Vec<RowVersion>>,
+}
+
+impl Versions {
+    /// the snapshot is taken when the transaction starts, thus all reads of the
+    /// transaction see the same committed rows
+    pub(crate) fn begin(&mut self, session_id: SessionId) {
+        if self.transactions.contains_key(&session_id) {
+            return;
+        }
+        let transaction = self.next_transaction;
+        self.next_transaction += 1;
+        let snapshot = Snapshot {
+            own: Some(transaction),
+            next: self.next_transaction,
+            in_progress: self.in_progress(),
+        };
+        self.transactions.insert(session_id, (transaction, snapshot));
+    }
+
+    /// returns tables whose previous versions of rows are not kept anymore
+    pub(crate) fn commit(&mut self, session_id: SessionId) -> Vec<(RecordId, RecordId)> {
+        match self.transactions.remove(&session_id) {
+            Some(_) => self.prune(),
+            None => vec![],
+        }
+    }
+
+    /// transaction of the session's transaction block or a new one that is committed right away
+    pub(crate) fn writer(&mut self, session_id: SessionId) -> TransactionId {
+        match self.transactions.get(&session_id) {
+            Some((transaction, _snapshot)) => *transaction,
+            None => {
+                let transaction = self.next_transaction;
+                self.next_transaction += 1;
+                transaction
+            }
+        }
+    }
+
+    /// previous versions are needed only while some session is inside of a transaction block
+    pub(crate) fn is_tracked(&self) -> bool {
+        !self.transactions.is_empty()
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        table: (RecordId, RecordId),
+        transaction: TransactionId,
+        key: Key,
+        previous: Option<Values>,
+    ) {
+        self.tables.entry(table).or_default().push(RowVersion {
+            transaction,
+            key,
+            previous,
+        });
+    }
+
+    /// `true` when readers of all sessions see the latest versions of the table rows
+    pub(crate) fn is_latest(&self, table: (RecordId, RecordId)) -> bool {
+        !self.tables.contains_key(&table)
+    }
+
+    pub(crate) fn snapshot(&self, session_id: SessionId) -> Snapshot {
+        match self.transactions.get(&session_id) {
+            Some((_transaction, snapshot)) => snapshot.clone(),
+            None => Snapshot {
+                own: None,
+                next: self.next_transaction,
+                in_progress: self.in_progress(),
+            },
+        }
+    }
+
+    /// versions of the latest `rows` that are visible to the snapshot, in the order of their keys
+    pub(crate) fn visible(&self, table: (RecordId, RecordId), snapshot: &Snapshot, rows: Vec<Row>) -> Vec<Row> {
+        let versions = match self.tables.get(&table) {
+            Some(versions) => versions,
+            None => return rows,
+        };
+        let mut rows = rows.into_iter().collect::<BTreeMap<_, _>>();
+        for version in versions.iter().rev() {
+            if snapshot.sees(version.transaction) {
+                continue;
+            }
+            match &version.previous {
+                Some(previous) => rows.insert(version.key.clone(), previous.clone()),
+                None => rows.remove(&version.key),
+            };
+        }
+        rows.into_iter().collect()
+    }
+
+    pub(crate) fn drop_tables(&mut self, dropped: impl Fn(&(RecordId, RecordId)) -> bool) {
+        self.tables.retain(|table, _versions| !dropped(table));
+    }
+
+    /// removes versions that are seen by all snapshots and returns tables that have no more versions
+    fn prune(&mut self) -> Vec<(RecordId, RecordId)> {
+        let in_progress = self.in_progress();
+        let snapshots = self
+            .transactions
+            .values()
+            .map(|(_transaction, snapshot)| snapshot)
+            .collect::<Vec<_>>();
+        let mut pruned = vec![];
+        for (table, versions) in self.tables.iter_mut() {
+            versions.retain(|version| {
+                in_progress.contains(&version.transaction)
+                    || snapshots.iter().any(|snapshot| !snapshot.sees(version.transaction))
+            });
+            if versions.is_empty() {
+                pruned.push(*table);
+            }
+        }
+        self.tables.retain(|_table, versions| !versions.is_empty());
+        pruned
+    }
+
+    fn in_progress(&self) -> BTreeSet<TransactionId> {
+        self.transactions
+            .values()
+            .map(|(transaction, _snapshot)| *transaction)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Binary;
+
+    const TABLE: (RecordId, RecordId) = (0, 0);
+
+    fn row(key: u8, value: u8) -> Row {
+        (Binary::with_data(vec![key]), Binary::with_data(vec![value]))
+    }
+
+    #[test]
+    fn snapshot_does_not_see_later_changes() {
+        let mut versions = Versions::default();
+        versions.begin(1);
+        let snapshot = versions.snapshot(1);
+
+        let transaction = versions.writer(2);
+        versions.record(
+            TABLE,
+            transaction,
+            Binary::with_data(vec![1]),
+            Some(Binary::with_data(vec![10])),
+        );
+        versions.record(TABLE, transaction, Binary::with_data(vec![2]), None);
+
+        assert_eq!(
+            versions.visible(TABLE, &snapshot, vec![row(1, 11), row(2, 20)]),
+            vec![row(1, 10)]
+        );
+        assert_eq!(
+            versions.visible(TABLE, &versions.snapshot(2), vec![row(1, 11), row(2, 20)]),
+            vec![row(1, 11), row(2, 20)]
+        );
+    }
+
+    #[test]
+    fn uncommitted_changes_are_seen_only_by_own_transaction() {
+        let mut versions = Versions::default();
+        versions.begin(1);
+        let transaction = versions.writer(1);
+        versions.record(TABLE, transaction, Binary::with_data(vec![1]), None);
+
+        assert_eq!(
+            versions.visible(TABLE, &versions.snapshot(1), vec![row(1, 10)]),
+            vec![row(1, 10)]
+        );
+        assert_eq!(versions.visible(TABLE, &versions.snapshot(2), vec![row(1, 10)]), vec![]);
+    }
+
+    #[test]
+    fn versions_are_pruned_when_seen_by_all_snapshots() {
+        let mut versions = Versions::default();
+        versions.begin(1);
+        let transaction = versions.writer(1);
+        versions.record(TABLE, transaction, Binary::with_data(vec![1]), None);
+
+        assert!(!versions.is_latest(TABLE));
+        assert_eq!(versions.commit(1), vec![TABLE]);
+        assert!(versions.is_latest(TABLE));
+        assert!(!versions.is_tracked());
+    }
+}
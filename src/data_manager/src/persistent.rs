@@ -13,20 +13,44 @@
 // limitations under the License.
 
 use crate::{
-    Database, DefinitionError, InitStatus, Key, ObjectId, ReadCursor, RowResult, SchemaId, StorageError, Values,
+    filter_rows, visit_rows, Database, DefinitionError, InitStatus, Key, ObjectId, ReadCursor, Row, RowResult,
+    SchemaId, StorageError, Values,
 };
 use representation::Binary;
 use sled::{Db as Schema, DiskPtr, Error as SledError, IVec, Tree};
 use std::{
     collections::HashMap,
+    convert::TryInto,
     io::{self, ErrorKind},
-    path::PathBuf,
+    ops::Bound,
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
+    thread,
 };
 
+const CHECKSUM_LENGTH: usize = 4;
+
+/// Objects with at least `min_rows_for_parallel` rows are scanned by `worker_threads` threads,
+/// each of them scans its own range of row keys
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParallelScanConfig {
+    pub min_rows_for_parallel: usize,
+    pub worker_threads: usize,
+}
+
+impl Default for ParallelScanConfig {
+    fn default() -> ParallelScanConfig {
+        ParallelScanConfig {
+            min_rows_for_parallel: 10_000,
+            worker_threads: 4,
+        }
+    }
+}
+
 pub struct PersistentDatabase {
     path: PathBuf,
     schemas: RwLock<HashMap<String, Arc<Schema>>>,
+    parallel_scan: ParallelScanConfig,
 }
 
 impl PersistentDatabase {
@@ -34,9 +58,14 @@ impl PersistentDatabase {
         PersistentDatabase {
             path,
             schemas: RwLock::default(),
+            parallel_scan: ParallelScanConfig::default(),
         }
     }
 
+    pub fn with_parallel_scan(self, parallel_scan: ParallelScanConfig) -> PersistentDatabase {
+        PersistentDatabase { parallel_scan, ..self }
+    }
+
     pub fn init(&self, schema_name: SchemaId) -> io::Result<Result<InitStatus, StorageError>> {
         let path_to_schema = PathBuf::from(&self.path).join(schema_name);
         log::info!("path to schema {:?}", path_to_schema);
@@ -96,7 +125,9 @@ impl PersistentDatabase {
                 SledError::Corruption { .. } => Ok(Err(StorageError::Storage)),
                 SledError::ReportableBug(_) => Ok(Err(StorageError::Storage)),
                 SledError::Unsupported(_) => Ok(Err(StorageError::Storage)),
-                SledError::CollectionNotFound(_) => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+                SledError::CollectionNotFound(_) => {
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned()))))
+                }
             },
         }
     }
@@ -131,6 +162,31 @@ impl PersistentDatabase {
         }
     }
 
+    // sled database is bound to its directory, thus objects of a renamed schema are copied into
+    // the database under the new name
+    fn copy_database(&self, schema: &Schema, renamed: &Schema) -> io::Result<Result<(), StorageError>> {
+        let copied = || -> Result<(), SledError> {
+            for tree_name in schema.tree_names() {
+                if tree_name == b"__sled__default" {
+                    continue;
+                }
+                let source = schema.open_tree(&tree_name)?;
+                let target = renamed.open_tree(&tree_name)?;
+                for item in source.iter() {
+                    let (key, values) = item?;
+                    target.insert(key, values)?;
+                }
+                target.flush()?;
+            }
+            Ok(())
+        };
+        match copied() {
+            Ok(()) => Ok(Ok(())),
+            Err(SledError::Io(io_error)) => Err(io_error),
+            Err(_) => Ok(Err(StorageError::Storage)),
+        }
+    }
+
     fn drop_database_cascade_with_failpoint(&self, schema: Arc<Schema>, tree: IVec) -> Result<bool, SledError> {
         fail::fail_point!("sled-fail-to-drop-db", |kind| {
             if tree == b"__sled__default" {
@@ -154,12 +210,13 @@ impl PersistentDatabase {
         values: &Binary,
     ) -> Result<Option<IVec>, SledError> {
         fail::fail_point!("sled-fail-to-insert-into-tree", |kind| Err(sled_error(kind)));
-        tree.insert(key.to_bytes(), values.to_bytes())
+        tree.insert(key.to_bytes(), with_checksum(key.to_bytes(), values.to_bytes()))
     }
 
     fn tree_flush(
         &self,
         tree: Tree,
+        object_name: ObjectId,
         io_operations: usize,
     ) -> io::Result<Result<Result<usize, DefinitionError>, StorageError>> {
         match self.tree_flush_with_failpoint(tree) {
@@ -172,7 +229,9 @@ impl PersistentDatabase {
                 SledError::Corruption { .. } => Ok(Err(StorageError::Storage)),
                 SledError::ReportableBug(_) => Ok(Err(StorageError::Storage)),
                 SledError::Unsupported(_) => Ok(Err(StorageError::Storage)),
-                SledError::CollectionNotFound(_) => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+                SledError::CollectionNotFound(_) => {
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned()))))
+                }
             },
         }
     }
@@ -188,6 +247,18 @@ impl PersistentDatabase {
         ));
         Box::new(object.iter())
     }
+
+    fn range_over_tree_with_failpoint(
+        &self,
+        object: Tree,
+        start: Bound<Vec<u8>>,
+    ) -> Box<dyn Iterator<Item = sled::Result<(IVec, IVec)>>> {
+        fail::fail_point!("sled-fail-iterate-over-tree", |kind| Box::new(
+            vec![Err(sled_error(kind))].into_iter()
+        ));
+        Box::new(object.range((start, Bound::Unbounded)))
+    }
+
     fn remove_fro_tree_with_failpoint(&self, object: &Tree, key: Binary) -> Result<Option<IVec>, SledError> {
         fail::fail_point!("sled-fail-to-remove-from-tree", |kind| Err(sled_error(kind)));
         object.remove(key.to_bytes())
@@ -196,6 +267,105 @@ impl PersistentDatabase {
     fn empty_iterator(&self) -> Box<dyn Iterator<Item = RowResult>> {
         Box::new(std::iter::empty())
     }
+
+    // inclusive ranges of row keys scanned by separate threads, `None` if the object has to be scanned sequentially
+    fn partitions(&self, object: &Tree) -> Option<Vec<(u64, u64)>> {
+        let ParallelScanConfig {
+            min_rows_for_parallel,
+            worker_threads,
+        } = self.parallel_scan;
+        if worker_threads < 2 {
+            return None;
+        }
+        let first = record_id(object.iter().next()?.ok()?.0)?;
+        let last = record_id(object.iter().next_back()?.ok()?.0)?;
+        // keys are generated sequentially, so the key range estimates number of rows
+        let rows = last - first + 1;
+        if rows < min_rows_for_parallel as u64 {
+            return None;
+        }
+        let partition_size = rows.div_ceil(worker_threads as u64);
+        Some(
+            (0..worker_threads as u64)
+                .map(|partition| first + partition * partition_size)
+                .take_while(|start| *start <= last)
+                .map(|start| (start, last.min(start + (partition_size - 1))))
+                .collect(),
+        )
+    }
+}
+
+/// rewrites rows of the schema stored under the path with their checksums
+pub(crate) fn add_checksums(path_to_schema: &Path) -> io::Result<()> {
+    let schema = sled::open(path_to_schema)?;
+    for tree_name in schema.tree_names() {
+        if tree_name == b"__sled__default" {
+            continue;
+        }
+        let object = schema.open_tree(&tree_name)?;
+        for item in object.iter() {
+            let (key, values) = item?;
+            object.insert(&key, with_checksum(&key, &values))?;
+        }
+        object.flush()?;
+    }
+    Ok(())
+}
+
+/// creates objects of the schema stored under the path that do not exist yet
+pub(crate) fn create_objects(path_to_schema: &Path, object_names: &[&str]) -> io::Result<()> {
+    let schema = sled::open(path_to_schema)?;
+    for object_name in object_names {
+        schema.open_tree(object_name)?;
+    }
+    schema.flush()?;
+    Ok(())
+}
+
+fn record_id(key: IVec) -> Option<u64> {
+    key.as_ref().try_into().ok().map(u64::from_be_bytes)
+}
+
+// values are stored after checksum of the row, it covers the key as well
+// thus a row that is written under another key does not pass the check too
+fn checksum(key: &[u8], values: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(key);
+    hasher.update(values);
+    hasher.finalize().to_be_bytes()
+}
+
+fn with_checksum(key: &[u8], values: &[u8]) -> Vec<u8> {
+    let mut stored = Vec::with_capacity(CHECKSUM_LENGTH + values.len());
+    stored.extend_from_slice(&checksum(key, values));
+    stored.extend_from_slice(values);
+    stored
+}
+
+fn row_result(item: sled::Result<(IVec, IVec)>) -> RowResult {
+    match item {
+        Ok((key, stored)) => {
+            if stored.len() < CHECKSUM_LENGTH || stored[..CHECKSUM_LENGTH] != checksum(&key, &stored[CHECKSUM_LENGTH..])
+            {
+                log::error!("row with key {:?} does not match its checksum", key.as_ref());
+                return Ok(Err(StorageError::Corruption(format!(
+                    "row with key {:?} does not match its checksum",
+                    key.as_ref()
+                ))));
+            }
+            Ok(Ok((
+                Binary::with_data(key.to_vec()),
+                Binary::with_data(stored[CHECKSUM_LENGTH..].to_vec()),
+            )))
+        }
+        Err(error) => match error {
+            SledError::Io(io_error) => Err(io_error),
+            SledError::Corruption { .. } => Ok(Err(StorageError::Storage)),
+            SledError::ReportableBug(_) => Ok(Err(StorageError::Storage)),
+            SledError::Unsupported(_) => Ok(Err(StorageError::Storage)),
+            SledError::CollectionNotFound(_) => Ok(Err(StorageError::Storage)),
+        },
+    }
 }
 
 impl Database for PersistentDatabase {
@@ -225,10 +395,37 @@ impl Database for PersistentDatabase {
     fn drop_schema(&self, schema_name: SchemaId) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
         match self.schemas.write().expect("to acquire write lock").remove(schema_name) {
             Some(schema) => self.drop_database(schema),
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
+    fn rename_schema(
+        &self,
+        schema_name: SchemaId,
+        new_schema_name: SchemaId,
+    ) -> io::Result<Result<Result<(), DefinitionError>, StorageError>> {
+        let mut schemas = self.schemas.write().expect("to acquire write lock");
+        if schemas.contains_key(new_schema_name) {
+            return Ok(Ok(Err(DefinitionError::SchemaAlreadyExists)));
+        }
+        let schema = match schemas.get(schema_name) {
+            Some(schema) => schema.clone(),
+            None => return Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
+        };
+        let path_to_schema = PathBuf::from(&self.path).join(new_schema_name);
+        log::info!("path to schema {:?}", path_to_schema);
+        let renamed = match self.open_database(path_to_schema)? {
+            Ok(renamed) => Arc::new(renamed),
+            Err(error) => return Ok(Err(error)),
+        };
+        if let Err(error) = self.copy_database(&schema, &renamed)? {
+            return Ok(Err(error));
+        }
+        schemas.remove(schema_name);
+        schemas.insert(new_schema_name.to_owned(), renamed);
+        self.drop_database(schema)
+    }
+
     fn create_object(
         &self,
         schema_name: SchemaId,
@@ -243,7 +440,7 @@ impl Database for PersistentDatabase {
                         .map(|io| io.map(|storage| storage.map(|_object| ())))
                 }
             }
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
@@ -255,16 +452,18 @@ impl Database for PersistentDatabase {
         match self.schemas.read().expect("to acquire read lock").get(schema_name) {
             Some(schema) => match self.drop_tree_with_failpoint(schema.clone(), object_name.as_bytes().into()) {
                 Ok(true) => Ok(Ok(Ok(()))),
-                Ok(false) => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+                Ok(false) => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
                 Err(error) => match error {
                     SledError::Io(io_error) => Err(io_error),
                     SledError::Corruption { .. } => Ok(Err(StorageError::Storage)),
                     SledError::ReportableBug(_) => Ok(Err(StorageError::Storage)),
                     SledError::Unsupported(_) => Ok(Err(StorageError::Storage)),
-                    SledError::CollectionNotFound(_) => Ok(Ok(Err(DefinitionError::ObjectDoesNotExist))),
+                    SledError::CollectionNotFound(_) => {
+                        Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned()))))
+                    }
                 },
             },
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
@@ -289,20 +488,22 @@ impl Database for PersistentDatabase {
                                         SledError::ReportableBug(_) => return Ok(Err(StorageError::Storage)),
                                         SledError::Unsupported(_) => return Ok(Err(StorageError::Storage)),
                                         SledError::CollectionNotFound(_) => {
-                                            return Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                                            return Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(
+                                                object_name.to_owned(),
+                                            ))))
                                         }
                                     },
                                 }
                             }
-                            self.tree_flush(object, written_rows)
+                            self.tree_flush(object, object_name, written_rows)
                         }
                         otherwise => otherwise.map(|io| io.map(|storage| storage.map(|_object| 0))),
                     }
                 } else {
-                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned()))))
                 }
             }
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
         }
     }
 
@@ -315,21 +516,9 @@ impl Database for PersistentDatabase {
             Some(schema) => {
                 if schema.tree_names().contains(&(object_name.into())) {
                     match self.open_tree(schema.clone(), object_name) {
-                        Ok(Ok(Ok(object))) => Ok(Ok(Ok(Box::new(self.iterator_over_tree_with_failpoint(object).map(
-                            |item| match item {
-                                Ok((key, values)) => Ok(Ok((
-                                    Binary::with_data(key.to_vec()),
-                                    Binary::with_data(values.to_vec()),
-                                ))),
-                                Err(error) => match error {
-                                    SledError::Io(io_error) => Err(io_error),
-                                    SledError::Corruption { .. } => Ok(Err(StorageError::Storage)),
-                                    SledError::ReportableBug(_) => Ok(Err(StorageError::Storage)),
-                                    SledError::Unsupported(_) => Ok(Err(StorageError::Storage)),
-                                    SledError::CollectionNotFound(_) => Ok(Err(StorageError::Storage)),
-                                },
-                            },
-                        ))))),
+                        Ok(Ok(Ok(object))) => Ok(Ok(Ok(Box::new(
+                            self.iterator_over_tree_with_failpoint(object).map(row_result),
+                        )))),
                         otherwise => otherwise.map(|io| io.map(|storage| storage.map(|_object| self.empty_iterator()))),
                     }
                 } else {
@@ -338,12 +527,12 @@ impl Database for PersistentDatabase {
                         schema_name,
                         object_name
                     );
-                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned()))))
                 }
             }
             None => {
                 log::error!("No schema with {:?} name found", schema_name);
-                Ok(Ok(Err(DefinitionError::SchemaDoesNotExist)))
+                Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned()))))
             }
         }
     }
@@ -369,21 +558,115 @@ impl Database for PersistentDatabase {
                                         SledError::ReportableBug(_) => return Ok(Err(StorageError::Storage)),
                                         SledError::Unsupported(_) => return Ok(Err(StorageError::Storage)),
                                         SledError::CollectionNotFound(_) => {
-                                            return Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                                            return Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(
+                                                object_name.to_owned(),
+                                            ))))
                                         }
                                     },
                                 }
                             }
-                            self.tree_flush(object, deleted)
+                            self.tree_flush(object, object_name, deleted)
                         }
                         otherwise => otherwise.map(|io| io.map(|storage| storage.map(|_object| 0))),
                     }
                 } else {
-                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist)))
+                    Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned()))))
                 }
             }
-            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist))),
+            None => Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
+        }
+    }
+
+    fn visit(
+        &self,
+        schema_name: SchemaId,
+        object_name: ObjectId,
+        after: Option<&Key>,
+        limit: usize,
+        visit: &mut dyn FnMut(&Values),
+    ) -> io::Result<Result<Result<Option<Key>, DefinitionError>, StorageError>> {
+        let schema = self
+            .schemas
+            .read()
+            .expect("to acquire read lock")
+            .get(schema_name)
+            .cloned();
+        let object = match schema {
+            Some(schema) if schema.tree_names().contains(&(object_name.into())) => {
+                match self.open_tree(schema, object_name)? {
+                    Ok(Ok(object)) => object,
+                    Ok(Err(error)) => return Ok(Ok(Err(error))),
+                    Err(error) => return Ok(Err(error)),
+                }
+            }
+            Some(_) => return Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
+            None => return Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
+        };
+        let start = match after {
+            Some(after) => Bound::Excluded(after.to_bytes().to_vec()),
+            None => Bound::Unbounded,
+        };
+        visit_rows(
+            self.range_over_tree_with_failpoint(object, start).map(row_result),
+            limit,
+            visit,
+        )
+        .map(|last| last.map(Ok))
+    }
+
+    fn scan(
+        &self,
+        schema_name: SchemaId,
+        object_name: ObjectId,
+        predicate: &(dyn Fn(&Values) -> bool + Sync),
+    ) -> io::Result<Result<Result<Vec<Row>, DefinitionError>, StorageError>> {
+        let schema = self
+            .schemas
+            .read()
+            .expect("to acquire read lock")
+            .get(schema_name)
+            .cloned();
+        let object = match schema {
+            Some(schema) if schema.tree_names().contains(&(object_name.into())) => {
+                match self.open_tree(schema, object_name)? {
+                    Ok(Ok(object)) => object,
+                    Ok(Err(error)) => return Ok(Ok(Err(error))),
+                    Err(error) => return Ok(Err(error)),
+                }
+            }
+            Some(_) => return Ok(Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))),
+            None => return Ok(Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))),
+        };
+        let partitions = match self.partitions(&object) {
+            Some(partitions) => partitions,
+            None => return filter_rows(object.iter().map(row_result), predicate).map(|rows| rows.map(Ok)),
+        };
+
+        let object = &object;
+        let scanned = thread::scope(|scope| {
+            partitions
+                .into_iter()
+                .map(|(start, end)| {
+                    scope.spawn(move || {
+                        filter_rows(
+                            object.range(start.to_be_bytes()..=end.to_be_bytes()).map(row_result),
+                            predicate,
+                        )
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|worker| worker.join().expect("scan worker is not panicked"))
+                .collect::<Vec<_>>()
+        });
+        let mut rows = vec![];
+        for partition in scanned {
+            match partition? {
+                Ok(partition) => rows.extend(partition),
+                Err(error) => return Ok(Err(error)),
+            }
         }
+        Ok(Ok(Ok(rows)))
     }
 }
 
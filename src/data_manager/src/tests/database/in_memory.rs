@@ -99,7 +99,7 @@ mod schemas {
     fn drop_schema_that_does_not_exist(storage: Storage, schema_name: SchemaId) {
         assert_eq!(
             storage.drop_schema(schema_name).expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))
         )
     }
 }
@@ -144,7 +144,7 @@ mod create_object {
             storage
                 .create_object("does_not_exist", object_name)
                 .expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist("does_not_exist".to_owned())))
         )
     }
 
@@ -197,7 +197,7 @@ mod drop_object {
     fn drop_object_from_schema_that_does_not_exist(storage: Storage, object_name: ObjectId) {
         assert_eq!(
             storage.drop_object("does_not_exist", object_name).expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist("does_not_exist".to_owned())))
         );
     }
 
@@ -205,7 +205,7 @@ mod drop_object {
     fn drop_object_that_does_not_exist(with_schema: Storage, schema_name: SchemaId, object_name: ObjectId) {
         assert_eq!(
             with_schema.drop_object(schema_name, object_name).expect("no io error"),
-            Ok(Err(DefinitionError::ObjectDoesNotExist))
+            Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))
         );
     }
 }
@@ -220,7 +220,7 @@ mod operations_on_object {
             with_schema
                 .write(schema_name, object_name, as_rows(vec![(1u8, vec!["123"])]))
                 .expect("no io error"),
-            Ok(Err(DefinitionError::ObjectDoesNotExist))
+            Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))
         );
     }
 
@@ -234,7 +234,7 @@ mod operations_on_object {
             storage
                 .write(schema_name, object_name, as_rows(vec![(1u8, vec!["123"])]))
                 .expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))
         );
     }
 
@@ -294,7 +294,7 @@ mod operations_on_object {
             with_schema
                 .delete(schema_name, object_name, vec![])
                 .expect("no io error"),
-            Ok(Err(DefinitionError::ObjectDoesNotExist))
+            Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))
         );
     }
 
@@ -306,7 +306,7 @@ mod operations_on_object {
     ) {
         assert_eq!(
             storage.delete(schema_name, object_name, vec![]).expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))
         );
     }
 
@@ -347,7 +347,7 @@ mod operations_on_object {
     fn read_from_object_that_does_not_exist(with_schema: Storage, schema_name: SchemaId, object_name: ObjectId) {
         assert!(matches!(
             with_schema.read(schema_name, object_name).expect("no io error"),
-            Ok(Err(DefinitionError::ObjectDoesNotExist))
+            Ok(Err(DefinitionError::ObjectDoesNotExist(name))) if name == object_name
         ));
     }
 
@@ -359,7 +359,7 @@ mod operations_on_object {
     ) {
         assert!(matches!(
             storage.read(schema_name, object_name).expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist(name))) if name == schema_name
         ));
     }
 
@@ -418,4 +418,48 @@ mod operations_on_object {
             .collect()),
         );
     }
+
+    #[rstest::rstest]
+    fn visit_records_page_by_page(with_object: Storage, schema_name: SchemaId, object_name: ObjectId) {
+        with_object
+            .write(
+                schema_name,
+                object_name,
+                as_rows(vec![(1u8, vec!["123"]), (2u8, vec!["456"]), (3u8, vec!["789"])]),
+            )
+            .expect("no io error")
+            .expect("no platform error")
+            .expect("values are written");
+
+        let keys = as_keys(vec![2, 3]);
+        let mut visited = vec![];
+        assert_eq!(
+            with_object
+                .visit(schema_name, object_name, None, 2, &mut |values| visited
+                    .push(values.clone()))
+                .expect("no io error"),
+            Ok(Ok(Some(keys[0].clone())))
+        );
+        assert_eq!(
+            with_object
+                .visit(schema_name, object_name, Some(&keys[0]), 2, &mut |values| visited
+                    .push(values.clone()))
+                .expect("no io error"),
+            Ok(Ok(Some(keys[1].clone())))
+        );
+        assert_eq!(
+            with_object
+                .visit(schema_name, object_name, Some(&keys[1]), 2, &mut |values| visited
+                    .push(values.clone()))
+                .expect("no io error"),
+            Ok(Ok(None))
+        );
+        assert_eq!(
+            visited,
+            as_rows(vec![(1u8, vec!["123"]), (2u8, vec!["456"]), (3u8, vec!["789"])])
+                .into_iter()
+                .map(|(_key, values)| values)
+                .collect::<Vec<_>>()
+        );
+    }
 }
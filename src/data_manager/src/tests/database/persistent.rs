@@ -101,7 +101,7 @@ mod schemas {
     fn drop_schema_that_does_not_exist(storage: Storage, schema_name: SchemaId) {
         assert_eq!(
             storage.drop_schema(schema_name).expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))
         )
     }
 }
@@ -146,7 +146,7 @@ mod create_object {
             storage
                 .create_object("does_not_exist", object_name)
                 .expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist("does_not_exist".to_owned())))
         )
     }
 
@@ -199,7 +199,7 @@ mod drop_object {
     fn drop_object_from_schema_that_does_not_exist(storage: Storage, object_name: ObjectId) {
         assert_eq!(
             storage.drop_object("does_not_exist", object_name).expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist("does_not_exist".to_owned())))
         );
     }
 
@@ -207,7 +207,7 @@ mod drop_object {
     fn drop_object_that_does_not_exist(with_schema: Storage, schema_name: SchemaId, object_name: ObjectId) {
         assert_eq!(
             with_schema.drop_object(schema_name, object_name).expect("no io error"),
-            Ok(Err(DefinitionError::ObjectDoesNotExist))
+            Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))
         );
     }
 }
@@ -222,7 +222,7 @@ mod operations_on_object {
             with_schema
                 .write(schema_name, object_name, as_rows(vec![(1u8, vec!["123"])]))
                 .expect("no io error"),
-            Ok(Err(DefinitionError::ObjectDoesNotExist))
+            Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))
         );
     }
 
@@ -236,7 +236,7 @@ mod operations_on_object {
             storage
                 .write(schema_name, object_name, as_rows(vec![(1u8, vec!["123"])]))
                 .expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))
         );
     }
 
@@ -296,7 +296,7 @@ mod operations_on_object {
             with_schema
                 .delete(schema_name, object_name, vec![])
                 .expect("no io error"),
-            Ok(Err(DefinitionError::ObjectDoesNotExist))
+            Ok(Err(DefinitionError::ObjectDoesNotExist(object_name.to_owned())))
         );
     }
 
@@ -308,7 +308,7 @@ mod operations_on_object {
     ) {
         assert_eq!(
             storage.delete(schema_name, object_name, vec![]).expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist(schema_name.to_owned())))
         );
     }
 
@@ -349,7 +349,7 @@ mod operations_on_object {
     fn read_from_object_that_does_not_exist(with_schema: Storage, schema_name: SchemaId, object_name: ObjectId) {
         assert!(matches!(
             with_schema.read(schema_name, object_name).expect("no io error"),
-            Ok(Err(DefinitionError::ObjectDoesNotExist))
+            Ok(Err(DefinitionError::ObjectDoesNotExist(name))) if name == object_name
         ));
     }
 
@@ -361,7 +361,7 @@ mod operations_on_object {
     ) {
         assert!(matches!(
             storage.read(schema_name, object_name).expect("no io error"),
-            Ok(Err(DefinitionError::SchemaDoesNotExist))
+            Ok(Err(DefinitionError::SchemaDoesNotExist(name))) if name == schema_name
         ));
     }
 
@@ -420,4 +420,164 @@ mod operations_on_object {
             .collect()),
         );
     }
+    #[rstest::rstest]
+    fn visit_records_page_by_page(with_object: Storage, schema_name: SchemaId, object_name: ObjectId) {
+        with_object
+            .write(
+                schema_name,
+                object_name,
+                as_rows(vec![(1u8, vec!["123"]), (2u8, vec!["456"]), (3u8, vec!["789"])]),
+            )
+            .expect("no io error")
+            .expect("no platform error")
+            .expect("values are written");
+
+        let keys = as_keys(vec![2, 3]);
+        let mut visited = vec![];
+        assert_eq!(
+            with_object
+                .visit(schema_name, object_name, None, 2, &mut |values| visited
+                    .push(values.clone()))
+                .expect("no io error"),
+            Ok(Ok(Some(keys[0].clone())))
+        );
+        assert_eq!(
+            with_object
+                .visit(schema_name, object_name, Some(&keys[0]), 2, &mut |values| visited
+                    .push(values.clone()))
+                .expect("no io error"),
+            Ok(Ok(Some(keys[1].clone())))
+        );
+        assert_eq!(
+            with_object
+                .visit(schema_name, object_name, Some(&keys[1]), 2, &mut |values| visited
+                    .push(values.clone()))
+                .expect("no io error"),
+            Ok(Ok(None))
+        );
+        assert_eq!(
+            visited,
+            as_rows(vec![(1u8, vec!["123"]), (2u8, vec!["456"]), (3u8, vec!["789"])])
+                .into_iter()
+                .map(|(_key, values)| values)
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[cfg(test)]
+mod parallel_scan {
+    use super::*;
+    use crate::persistent::ParallelScanConfig;
+
+    const ROWS: u64 = 10_000;
+
+    fn with_rows(parallel_scan: ParallelScanConfig, schema_name: SchemaId, object_name: ObjectId) -> Storage {
+        let root_path = tempfile::tempdir().expect("to create temporary folder");
+        let storage = Storage::new(root_path.into_path()).with_parallel_scan(parallel_scan);
+        storage
+            .create_schema(schema_name)
+            .expect("no io error")
+            .expect("no platform errors")
+            .expect("schema created");
+        storage
+            .create_object(schema_name, object_name)
+            .expect("no io error")
+            .expect("no storage error")
+            .expect("object created");
+        // written in reverse order so that storage has to order them by key
+        let rows = (0..ROWS)
+            .rev()
+            .map(|id| {
+                (
+                    Binary::with_data(id.to_be_bytes().to_vec()),
+                    Binary::with_data(id.to_string().into_bytes()),
+                )
+            })
+            .collect();
+        storage
+            .write(schema_name, object_name, rows)
+            .expect("no io error")
+            .expect("no platform error")
+            .expect("values are written");
+        storage
+    }
+
+    fn parallel() -> ParallelScanConfig {
+        ParallelScanConfig {
+            min_rows_for_parallel: 1_000,
+            worker_threads: 4,
+        }
+    }
+
+    fn sequential() -> ParallelScanConfig {
+        ParallelScanConfig {
+            min_rows_for_parallel: usize::MAX,
+            worker_threads: 4,
+        }
+    }
+
+    fn scan(storage: &Storage, predicate: &(dyn Fn(&Values) -> bool + Sync)) -> Vec<Row> {
+        storage
+            .scan("schema_name", "object_name", predicate)
+            .expect("no io error")
+            .expect("no platform error")
+            .expect("object exists")
+    }
+
+    fn even(values: &Values) -> bool {
+        String::from_utf8(values.to_bytes().to_vec())
+            .expect("to be a number")
+            .parse::<u64>()
+            .expect("to be a number")
+            % 2
+            == 0
+    }
+
+    #[rstest::rstest]
+    fn parallel_scan_matches_sequential_scan(schema_name: SchemaId, object_name: ObjectId) {
+        let parallel = with_rows(parallel(), schema_name, object_name);
+        let sequential = with_rows(sequential(), schema_name, object_name);
+
+        let rows = scan(&parallel, &|_values| true);
+        assert_eq!(rows.len(), ROWS as usize);
+        assert_eq!(rows, scan(&sequential, &|_values| true));
+    }
+
+    #[rstest::rstest]
+    fn rows_are_ordered_by_key(schema_name: SchemaId, object_name: ObjectId) {
+        let storage = with_rows(parallel(), schema_name, object_name);
+
+        assert_eq!(
+            scan(&storage, &|_values| true)
+                .into_iter()
+                .map(|(key, _values)| key)
+                .collect::<Vec<_>>(),
+            (0..ROWS)
+                .map(|id| Binary::with_data(id.to_be_bytes().to_vec()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest::rstest]
+    fn predicate_filters_rows_of_each_partition(schema_name: SchemaId, object_name: ObjectId) {
+        let parallel = with_rows(parallel(), schema_name, object_name);
+        let sequential = with_rows(sequential(), schema_name, object_name);
+
+        let rows = scan(&parallel, &even);
+        assert_eq!(rows.len(), (ROWS / 2) as usize);
+        assert!(rows.iter().all(|(_key, values)| even(values)));
+        assert_eq!(rows, scan(&sequential, &even));
+    }
+
+    #[rstest::rstest]
+    fn scan_of_non_existent_object(with_schema: Storage, schema_name: SchemaId) {
+        assert_eq!(
+            with_schema
+                .scan(schema_name, "non_existent", &|_values| true)
+                .expect("no io error")
+                .expect("no platform error"),
+            Err(DefinitionError::ObjectDoesNotExist("non_existent".to_owned()))
+        );
+    }
 }
@@ -0,0 +1,83 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use representation::{Binary, Datum};
+use sql_types::SqlType;
+
+fn write_and_scan(data_manager: &DataManager) -> Vec<Row> {
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("col_test", SqlType::Bool)],
+        )
+        .expect("to create a table");
+    data_manager
+        .write_into(
+            SESSION,
+            schema_id,
+            table_id,
+            vec![(
+                Binary::pack(&[Datum::from_u64(0)]),
+                Binary::pack(&[Datum::from_bool(true)]),
+            )],
+        )
+        .expect("values are inserted");
+    data_manager
+        .full_scan(SESSION, schema_id, table_id)
+        .expect("to scan a table")
+        .map(|item| item.expect("no io error").expect("no platform error"))
+        .collect()
+}
+
+#[rstest::rstest]
+fn in_memory_backend() {
+    let data_manager = DataManager::new(StorageBackend::InMemory).expect("to create data manager");
+
+    assert_eq!(
+        write_and_scan(&data_manager),
+        vec![(
+            Binary::pack(&[Datum::from_u64(0)]),
+            Binary::pack(&[Datum::from_bool(true)]),
+        )]
+    );
+}
+
+#[rstest::rstest]
+fn persistent_backend() {
+    let root_path = tempfile::tempdir().expect("to create temp folder");
+    let data_manager =
+        DataManager::new(StorageBackend::Persistent(root_path.path().to_path_buf())).expect("to create data manager");
+
+    assert_eq!(
+        write_and_scan(&data_manager),
+        vec![(
+            Binary::pack(&[Datum::from_u64(0)]),
+            Binary::pack(&[Datum::from_bool(true)]),
+        )]
+    );
+}
+
+#[rstest::rstest]
+fn in_memory_backend_does_not_touch_disk() {
+    let data_manager = DataManager::new(StorageBackend::InMemory).expect("to create data manager");
+    data_manager.create_schema(SCHEMA).expect("to create a schema");
+    drop(data_manager);
+
+    let data_manager = DataManager::new(StorageBackend::InMemory).expect("to create data manager");
+
+    assert_eq!(data_manager.schema_exists(SCHEMA), None);
+}
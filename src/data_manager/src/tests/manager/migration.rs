@@ -0,0 +1,199 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use representation::{Binary, Datum};
+use sql_types::SqlType;
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+use tempfile::TempDir;
+
+const MIGRATIONS: &str = "migrations";
+
+fn log_migration(path: &Path, migration: &str) -> io::Result<()> {
+    let mut log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path.join(MIGRATIONS))?;
+    writeln!(log, "{}", migration)
+}
+
+fn from_1_to_2(path: &Path) -> io::Result<()> {
+    log_migration(path, "1 -> 2")
+}
+
+fn from_2_to_3(path: &Path) -> io::Result<()> {
+    log_migration(path, "2 -> 3")
+}
+
+fn failed(path: &Path) -> io::Result<()> {
+    log_migration(path, "2 -> 3")?;
+    Err(io::Error::other("migration failed"))
+}
+
+fn migrations(path: &Path) -> Vec<String> {
+    fs::read_to_string(path.join(MIGRATIONS))
+        .map(|log| log.lines().map(ToOwned::to_owned).collect())
+        .unwrap_or_default()
+}
+
+#[rstest::fixture]
+fn storage_v1() -> TempDir {
+    let root_path = tempfile::tempdir().expect("to create temp folder");
+    let data_manager = DataManager::persistent_with_migrator(root_path.path().to_path_buf(), &Migrator::new(1))
+        .expect("to create catalog manager");
+    data_manager.create_schema(SCHEMA).expect("to create a schema");
+    root_path
+}
+
+#[rstest::rstest]
+fn fresh_storage_has_latest_version() {
+    let root_path = tempfile::tempdir().expect("to create temp folder");
+
+    DataManager::persistent(root_path.path().to_path_buf()).expect("to create catalog manager");
+
+    assert_eq!(Migrator::stored_version(root_path.path()), Ok(Some(SCHEMA_VERSION)));
+}
+
+#[rstest::rstest]
+fn migrate_previous_version(storage_v1: TempDir) {
+    let migrator = Migrator::new(2).register(1, 2, from_1_to_2);
+
+    let data_manager = DataManager::persistent_with_migrator(storage_v1.path().to_path_buf(), &migrator)
+        .expect("to create catalog manager");
+
+    assert_eq!(Migrator::stored_version(storage_v1.path()), Ok(Some(2)));
+    assert_eq!(migrations(storage_v1.path()), vec!["1 -> 2"]);
+    assert!(data_manager.schema_exists(SCHEMA).is_some());
+}
+
+#[rstest::rstest]
+fn migrate_two_versions_in_sequence(storage_v1: TempDir) {
+    let migrator = Migrator::new(3).register(2, 3, from_2_to_3).register(1, 2, from_1_to_2);
+
+    let data_manager = DataManager::persistent_with_migrator(storage_v1.path().to_path_buf(), &migrator)
+        .expect("to create catalog manager");
+
+    assert_eq!(Migrator::stored_version(storage_v1.path()), Ok(Some(3)));
+    assert_eq!(migrations(storage_v1.path()), vec!["1 -> 2", "2 -> 3"]);
+    assert!(data_manager.schema_exists(SCHEMA).is_some());
+}
+
+#[rstest::rstest]
+fn failed_migration_is_rolled_back(storage_v1: TempDir) {
+    let migrator = Migrator::new(3).register(1, 2, from_1_to_2).register(2, 3, failed);
+
+    assert!(DataManager::persistent_with_migrator(storage_v1.path().to_path_buf(), &migrator).is_err());
+
+    assert_eq!(Migrator::stored_version(storage_v1.path()), Ok(Some(1)));
+    assert_eq!(migrations(storage_v1.path()), Vec::<String>::new());
+
+    let data_manager = DataManager::persistent_with_migrator(storage_v1.path().to_path_buf(), &Migrator::new(1))
+        .expect("to create catalog manager");
+    assert!(data_manager.schema_exists(SCHEMA).is_some());
+}
+
+#[rstest::rstest]
+fn rows_without_checksums_are_migrated() {
+    let root_path = tempfile::tempdir().expect("to create temp folder");
+    let data_manager = DataManager::persistent(root_path.path().to_path_buf()).expect("to create catalog manager");
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("col_test", SqlType::Bool)],
+        )
+        .expect("to create a table");
+    let row = (
+        Binary::pack(&[Datum::from_u64(0)]),
+        Binary::pack(&[Datum::from_bool(true)]),
+    );
+    data_manager
+        .write_into(SESSION, schema_id, table_id, vec![row.clone()])
+        .expect("values are inserted");
+    drop(data_manager);
+
+    // rows of the first version are written without checksums
+    for path_to_schema in &[
+        root_path.path().join("system").join("DEFINITION_SCHEMA"),
+        root_path.path().join("public").join(SCHEMA),
+    ] {
+        let schema = sled::open(path_to_schema).expect("to open schema storage");
+        for tree_name in schema.tree_names() {
+            if tree_name == b"__sled__default" {
+                continue;
+            }
+            let object = schema.open_tree(&tree_name).expect("to open object storage");
+            for item in object.iter() {
+                let (key, stored) = item.expect("to read a row");
+                object.insert(key, &stored[4..]).expect("to write a row");
+            }
+            object.flush().expect("to flush object storage");
+        }
+    }
+    fs::write(root_path.path().join("system").join("SCHEMA_VERSION"), "1").expect("to write version");
+
+    let data_manager = DataManager::persistent(root_path.path().to_path_buf()).expect("to create catalog manager");
+
+    assert_eq!(Migrator::stored_version(root_path.path()), Ok(Some(SCHEMA_VERSION)));
+    assert_eq!(
+        data_manager
+            .full_scan(SESSION, schema_id, table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![row])
+    );
+}
+
+#[rstest::rstest]
+fn definition_tables_are_created_by_migration() {
+    let root_path = tempfile::tempdir().expect("to create temp folder");
+    let data_manager = DataManager::persistent(root_path.path().to_path_buf()).expect("to create catalog manager");
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::unique("col_test", SqlType::Integer(i32::MIN))],
+        )
+        .expect("to create a table");
+    drop(data_manager);
+
+    // definitions of the second version are stored only in memory
+    let definition_schema =
+        sled::open(root_path.path().join("system").join("DEFINITION_SCHEMA")).expect("to open schema storage");
+    assert_eq!(definition_schema.drop_tree("FOREIGN_KEYS"), Ok(true));
+    definition_schema.flush().expect("to flush schema storage");
+    drop(definition_schema);
+    fs::write(root_path.path().join("system").join("SCHEMA_VERSION"), "2").expect("to write version");
+
+    let data_manager = DataManager::persistent(root_path.path().to_path_buf()).expect("to create catalog manager");
+    let foreign_key = ForeignKeyDefinition::new(
+        "table_name_col_test_fkey",
+        vec!["col_test".to_owned()],
+        schema_id,
+        table_id,
+        vec!["col_test".to_owned()],
+    );
+    data_manager.add_foreign_key(schema_id, table_id, foreign_key.clone());
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.path().to_path_buf()).expect("to create catalog manager");
+
+    assert_eq!(Migrator::stored_version(root_path.path()), Ok(Some(SCHEMA_VERSION)));
+    assert_eq!(data_manager.foreign_keys(schema_id, table_id), vec![foreign_key]);
+}
@@ -14,6 +14,10 @@
 
 use super::*;
 
+#[cfg(test)]
+mod backend;
+#[cfg(test)]
+mod migration;
 #[cfg(test)]
 mod persistence;
 #[cfg(test)]
@@ -26,6 +30,7 @@ mod table;
 const SCHEMA: &str = "schema_name";
 const SCHEMA_1: &str = "schema_name_1";
 const SCHEMA_2: &str = "schema_name_2";
+const SESSION: SessionId = 0;
 
 #[rstest::fixture]
 fn data_manager() -> DataManager {
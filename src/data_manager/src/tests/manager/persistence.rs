@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use super::*;
+use kernel::SystemErrorKind;
 use representation::{Binary, Datum};
 use sql_types::SqlType;
 use std::path::PathBuf;
@@ -68,10 +69,120 @@ fn created_table_is_preserved_after_restart(persistent: (DataManager, TempDir))
         data_manager
             .table_columns(schema_id, table_id)
             .expect("to have a columns"),
-        vec![ColumnDefinition::new("col_test", SqlType::Bool)]
+        vec![ColumnDefinition::new("col_test", SqlType::Bool).with_ordinal_position(1)]
     )
 }
 
+#[rstest::rstest]
+fn renamed_column_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::unique("col_test", SqlType::Bool)],
+        )
+        .expect("to create a table");
+    data_manager
+        .rename_column(schema_id, table_id, "col_test", "col_renamed")
+        .expect("to rename a column");
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert!(matches!(
+        data_manager.table_exists(SCHEMA, "table_name"),
+        Some((_, Some(_)))
+    ));
+    assert_eq!(
+        data_manager
+            .table_columns(schema_id, table_id)
+            .expect("to have a columns"),
+        vec![ColumnDefinition::unique("col_renamed", SqlType::Bool).with_ordinal_position(1)]
+    )
+}
+
+#[rstest::rstest]
+fn renamed_schema_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("col_test", SqlType::Bool)],
+        )
+        .expect("to create a table");
+    let row = (
+        Binary::pack(&[Datum::from_u64(0)]),
+        Binary::pack(&[Datum::from_bool(true)]),
+    );
+    data_manager
+        .write_into(SESSION, schema_id, table_id, vec![row.clone()])
+        .expect("values are inserted");
+    data_manager
+        .rename_schema(SCHEMA, "schema_renamed")
+        .expect("no system errors")
+        .expect("to rename a schema");
+
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(data_manager.schema_exists(SCHEMA), None);
+    assert_eq!(
+        data_manager.table_exists("schema_renamed", "table_name"),
+        Some((schema_id, Some(table_id)))
+    );
+    assert_eq!(
+        data_manager
+            .full_scan(SESSION, schema_id, table_id)
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .collect::<Vec<Row>>(),
+        vec![row],
+    );
+}
+
+#[rstest::rstest]
+fn schema_renamed_back_keeps_its_rows(persistent: (DataManager, TempDir)) {
+    let (data_manager, _root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("col_test", SqlType::Bool)],
+        )
+        .expect("to create a table");
+    let row = (
+        Binary::pack(&[Datum::from_u64(0)]),
+        Binary::pack(&[Datum::from_bool(true)]),
+    );
+    data_manager
+        .write_into(SESSION, schema_id, table_id, vec![row.clone()])
+        .expect("values are inserted");
+    data_manager
+        .rename_schema(SCHEMA, "schema_renamed")
+        .expect("no system errors")
+        .expect("to rename a schema");
+    data_manager
+        .rename_schema("schema_renamed", SCHEMA)
+        .expect("no system errors")
+        .expect("to rename a schema");
+
+    assert_eq!(
+        data_manager
+            .full_scan(SESSION, schema_id, table_id)
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .collect::<Vec<Row>>(),
+        vec![row],
+    );
+}
+
 #[rstest::rstest]
 fn stored_data_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
     let (data_manager, root_path) = persistent;
@@ -85,6 +196,7 @@ fn stored_data_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
         .expect("to create a table");
     data_manager
         .write_into(
+            SESSION,
             schema_id,
             table_id,
             vec![(
@@ -96,7 +208,7 @@ fn stored_data_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
 
     assert_eq!(
         data_manager
-            .full_scan(schema_id, table_id)
+            .full_scan(SESSION, schema_id, table_id)
             .expect("to scan a table")
             .map(|item| item.expect("no io error").expect("no platform error"))
             .collect::<Vec<Row>>(),
@@ -111,7 +223,7 @@ fn stored_data_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
 
     assert_eq!(
         data_manager
-            .full_scan(schema_id, table_id)
+            .full_scan(SESSION, schema_id, table_id)
             .expect("to scan a table")
             .map(|item| item.expect("no io error").expect("no platform error"))
             .collect::<Vec<Row>>(),
@@ -121,3 +233,303 @@ fn stored_data_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
         )],
     );
 }
+
+#[rstest::rstest]
+fn corrupted_row_is_reported_on_read(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("col_test", SqlType::Integer(i32::MIN))],
+        )
+        .expect("to create a table");
+    data_manager
+        .write_into(
+            SESSION,
+            schema_id,
+            table_id,
+            vec![(
+                Binary::pack(&[Datum::from_u64(0)]),
+                Binary::pack(&[Datum::from_i32(123)]),
+            )],
+        )
+        .expect("values are inserted");
+    drop(data_manager);
+
+    {
+        let schema = sled::open(root_path.path().join("public").join(SCHEMA)).expect("to open schema storage");
+        let object = schema.open_tree("table_name").expect("to open table storage");
+        let (key, stored) = object.iter().next().expect("to have a row").expect("to read a row");
+        let mut corrupted = stored.to_vec();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        object.insert(key, corrupted).expect("to write a row");
+        object.flush().expect("to flush table storage");
+    }
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert!(matches!(
+        data_manager.full_scan(SESSION, schema_id, table_id),
+        Err(error) if error.kind() == &SystemErrorKind::DataCorruption
+    ));
+}
+
+#[rstest::rstest]
+fn columnar_table_data_is_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let table_id = data_manager
+        .create_table_with_layout(
+            schema_id,
+            "table_name",
+            &[
+                ColumnDefinition::new("col_1", SqlType::Bool),
+                ColumnDefinition::new("col_2", SqlType::Integer(i32::MIN)),
+            ],
+            StorageLayout::Columnar,
+        )
+        .expect("to create a table");
+    data_manager
+        .write_into(
+            SESSION,
+            schema_id,
+            table_id,
+            vec![(
+                Binary::pack(&[Datum::from_u64(0)]),
+                Binary::pack(&[Datum::from_bool(true), Datum::from_i32(10)]),
+            )],
+        )
+        .expect("values are inserted");
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(
+        data_manager
+            .scan_columns(SESSION, schema_id, table_id, &[1])
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .collect::<Vec<Row>>(),
+        vec![(
+            Binary::pack(&[Datum::from_u64(0)]),
+            Binary::pack(&[Datum::from_i32(10)]),
+        )],
+    );
+    assert_eq!(
+        data_manager
+            .full_scan(SESSION, schema_id, table_id)
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .collect::<Vec<Row>>(),
+        vec![(
+            Binary::pack(&[Datum::from_u64(0)]),
+            Binary::pack(&[Datum::from_bool(true), Datum::from_i32(10)]),
+        )],
+    );
+}
+
+#[rstest::rstest]
+fn columns_of_multiple_tables_are_preserved_in_declaration_order_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let first_table_id = data_manager
+        .create_table(
+            schema_id,
+            "first_table",
+            &[
+                ColumnDefinition::new("z_column", SqlType::Bool),
+                ColumnDefinition::new("a_column", SqlType::Integer(i32::MIN)),
+            ],
+        )
+        .expect("to create a table");
+    let second_table_id = data_manager
+        .create_table(
+            schema_id,
+            "second_table",
+            &[
+                ColumnDefinition::new("m_column", SqlType::BigInt(i64::MIN)),
+                ColumnDefinition::new("b_column", SqlType::Bool),
+                ColumnDefinition::new("y_column", SqlType::SmallInt(i16::MIN)),
+            ],
+        )
+        .expect("to create a table");
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(
+        data_manager
+            .table_columns(schema_id, first_table_id)
+            .expect("to have a columns"),
+        vec![
+            ColumnDefinition::new("z_column", SqlType::Bool).with_ordinal_position(1),
+            ColumnDefinition::new("a_column", SqlType::Integer(i32::MIN)).with_ordinal_position(2),
+        ]
+    );
+    assert_eq!(
+        data_manager
+            .table_columns(schema_id, second_table_id)
+            .expect("to have a columns"),
+        vec![
+            ColumnDefinition::new("m_column", SqlType::BigInt(i64::MIN)).with_ordinal_position(1),
+            ColumnDefinition::new("b_column", SqlType::Bool).with_ordinal_position(2),
+            ColumnDefinition::new("y_column", SqlType::SmallInt(i16::MIN)).with_ordinal_position(3),
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn table_created_after_restart_does_not_change_columns_of_restored_ones(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let first_table_id = data_manager
+        .create_table(
+            schema_id,
+            "first_table",
+            &[
+                ColumnDefinition::new("z_column", SqlType::Bool),
+                ColumnDefinition::new("a_column", SqlType::Integer(i32::MIN)),
+            ],
+        )
+        .expect("to create a table");
+    drop(data_manager);
+
+    let path = root_path.into_path();
+    let data_manager = DataManager::persistent(path.clone()).expect("to create catalog manager");
+    let second_table_id = data_manager
+        .create_table(
+            schema_id,
+            "second_table",
+            &[ColumnDefinition::new("m_column", SqlType::BigInt(i64::MIN))],
+        )
+        .expect("to create a table");
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(path).expect("to create catalog manager");
+
+    assert_ne!(first_table_id, second_table_id);
+    assert_eq!(
+        data_manager
+            .table_columns(schema_id, first_table_id)
+            .expect("to have a columns"),
+        vec![
+            ColumnDefinition::new("z_column", SqlType::Bool).with_ordinal_position(1),
+            ColumnDefinition::new("a_column", SqlType::Integer(i32::MIN)).with_ordinal_position(2),
+        ]
+    );
+    assert_eq!(
+        data_manager
+            .table_columns(schema_id, second_table_id)
+            .expect("to have a columns"),
+        vec![ColumnDefinition::new("m_column", SqlType::BigInt(i64::MIN)).with_ordinal_position(1)]
+    );
+}
+
+#[rstest::rstest]
+fn foreign_keys_are_preserved_after_restart(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let customers_id = data_manager
+        .create_table(
+            schema_id,
+            "customers",
+            &[ColumnDefinition::unique("id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("to create a table");
+    let orders_id = data_manager
+        .create_table(
+            schema_id,
+            "orders",
+            &[ColumnDefinition::new("customer_id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("to create a table");
+    let foreign_key = ForeignKeyDefinition::new(
+        "orders_customer_id_fkey",
+        vec!["customer_id".to_owned()],
+        schema_id,
+        customers_id,
+        vec!["id".to_owned()],
+    );
+    data_manager.add_foreign_key(schema_id, orders_id, foreign_key.clone());
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(data_manager.foreign_keys(schema_id, orders_id), vec![foreign_key]);
+}
+
+#[rstest::rstest]
+fn dropped_foreign_keys_are_not_restored(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let customers_id = data_manager
+        .create_table(
+            schema_id,
+            "customers",
+            &[ColumnDefinition::unique("id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("to create a table");
+    let orders_id = data_manager
+        .create_table(
+            schema_id,
+            "orders",
+            &[ColumnDefinition::new("customer_id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("to create a table");
+    let foreign_key = ForeignKeyDefinition::new(
+        "orders_customer_id_fkey",
+        vec!["customer_id".to_owned()],
+        schema_id,
+        customers_id,
+        vec!["id".to_owned()],
+    );
+    data_manager.add_foreign_key(schema_id, orders_id, foreign_key);
+    data_manager.drop_foreign_key(schema_id, orders_id, "orders_customer_id_fkey");
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(data_manager.foreign_keys(schema_id, orders_id), vec![]);
+}
+
+#[rstest::rstest]
+fn foreign_keys_of_dropped_table_are_not_restored(persistent: (DataManager, TempDir)) {
+    let (data_manager, root_path) = persistent;
+    let schema_id = data_manager.create_schema(SCHEMA).expect("to create a schema");
+    let customers_id = data_manager
+        .create_table(
+            schema_id,
+            "customers",
+            &[ColumnDefinition::unique("id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("to create a table");
+    let orders_id = data_manager
+        .create_table(
+            schema_id,
+            "orders",
+            &[ColumnDefinition::new("customer_id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("to create a table");
+    data_manager.add_foreign_key(
+        schema_id,
+        orders_id,
+        ForeignKeyDefinition::new(
+            "orders_customer_id_fkey",
+            vec!["customer_id".to_owned()],
+            schema_id,
+            customers_id,
+            vec!["id".to_owned()],
+        ),
+    );
+    data_manager
+        .drop_table(schema_id, customers_id)
+        .expect("to drop a table");
+    drop(data_manager);
+
+    let data_manager = DataManager::persistent(root_path.into_path()).expect("to create catalog manager");
+
+    assert_eq!(data_manager.foreign_keys(schema_id, orders_id), vec![]);
+}
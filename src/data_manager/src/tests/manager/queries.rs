@@ -32,6 +32,7 @@ fn delete_all_from_table(data_manager_with_schema: DataManager) {
 
     data_manager_with_schema
         .write_into(
+            SESSION,
             schema_id,
             table_id,
             vec![(
@@ -42,6 +43,7 @@ fn delete_all_from_table(data_manager_with_schema: DataManager) {
         .expect("values are inserted");
     data_manager_with_schema
         .write_into(
+            SESSION,
             schema_id,
             table_id,
             vec![(
@@ -52,6 +54,7 @@ fn delete_all_from_table(data_manager_with_schema: DataManager) {
         .expect("values are inserted");
     data_manager_with_schema
         .write_into(
+            SESSION,
             table_id,
             schema_id,
             vec![(
@@ -63,6 +66,7 @@ fn delete_all_from_table(data_manager_with_schema: DataManager) {
 
     assert_eq!(
         data_manager_with_schema.delete_from(
+            SESSION,
             schema_id,
             table_id,
             vec![
@@ -76,7 +80,7 @@ fn delete_all_from_table(data_manager_with_schema: DataManager) {
 
     assert_eq!(
         data_manager_with_schema
-            .full_scan(schema_id, table_id)
+            .full_scan(SESSION, schema_id, table_id)
             .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
         Ok(vec![])
     );
@@ -108,6 +112,7 @@ fn select_all_from_table_with_many_columns(with_small_ints_table: DataManager) {
     let table_id = full_table_id.1.expect("table exist");
     with_small_ints_table
         .write_into(
+            SESSION,
             schema_id,
             table_id,
             vec![(
@@ -118,11 +123,13 @@ fn select_all_from_table_with_many_columns(with_small_ints_table: DataManager) {
         .expect("values are inserted");
 
     assert_eq!(
-        with_small_ints_table.full_scan(schema_id, table_id).map(|read| read
-            .map(Result::unwrap)
-            .map(Result::unwrap)
-            .map(|(_key, values)| values)
-            .collect()),
+        with_small_ints_table
+            .full_scan(SESSION, schema_id, table_id)
+            .map(|read| read
+                .map(Result::unwrap)
+                .map(Result::unwrap)
+                .map(|(_key, values)| values)
+                .collect()),
         Ok(vec![Binary::pack(&[
             Datum::from_i16(1),
             Datum::from_i16(2),
@@ -130,3 +137,577 @@ fn select_all_from_table_with_many_columns(with_small_ints_table: DataManager) {
         ])])
     );
 }
+
+#[rstest::rstest]
+fn insert_or_ignore_skips_rows_with_duplicate_unique_values(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::unique("column_test", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+
+    data_manager_with_schema
+        .write_into(
+            SESSION,
+            schema_id,
+            table_id,
+            vec![(
+                Binary::pack(&[Datum::from_u64(1)]),
+                Binary::pack(&[Datum::from_i16(123)]),
+            )],
+        )
+        .expect("values are inserted");
+
+    assert_eq!(
+        data_manager_with_schema.insert_or_ignore(
+            SESSION,
+            schema_id,
+            table_id,
+            vec![
+                (
+                    Binary::pack(&[Datum::from_u64(2)]),
+                    Binary::pack(&[Datum::from_i16(123)])
+                ),
+                (
+                    Binary::pack(&[Datum::from_u64(3)]),
+                    Binary::pack(&[Datum::from_i16(456)])
+                ),
+                (
+                    Binary::pack(&[Datum::from_u64(4)]),
+                    Binary::pack(&[Datum::from_i16(456)])
+                ),
+            ]
+        ),
+        Ok(1)
+    );
+
+    assert_eq!(
+        data_manager_with_schema
+            .full_scan(SESSION, schema_id, table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![
+            (
+                Binary::pack(&[Datum::from_u64(1)]),
+                Binary::pack(&[Datum::from_i16(123)])
+            ),
+            (
+                Binary::pack(&[Datum::from_u64(3)]),
+                Binary::pack(&[Datum::from_i16(456)])
+            ),
+        ])
+    );
+}
+
+#[rstest::rstest]
+fn write_and_delete_nothing(with_small_ints_table: DataManager) {
+    let schema_id = with_small_ints_table.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = with_small_ints_table
+        .table_exists(SCHEMA, "table_name")
+        .and_then(|(_, table_id)| table_id)
+        .expect("table exists");
+
+    assert_eq!(
+        with_small_ints_table.write_into(SESSION, schema_id, table_id, vec![]),
+        Ok(0)
+    );
+    assert_eq!(
+        with_small_ints_table.delete_from(SESSION, schema_id, table_id, vec![]),
+        Ok(0)
+    );
+}
+
+#[cfg(test)]
+mod columnar {
+    use super::*;
+
+    fn rows() -> Vec<Row> {
+        vec![
+            (
+                Binary::pack(&[Datum::from_u64(0)]),
+                Binary::pack(&[Datum::from_i16(1), Datum::from_str("one"), Datum::from_bool(true)]),
+            ),
+            (
+                Binary::pack(&[Datum::from_u64(1)]),
+                Binary::pack(&[Datum::from_i16(2), Datum::Null, Datum::from_bool(false)]),
+            ),
+        ]
+    }
+
+    fn create_table(data_manager: &DataManager, table_name: &str, layout: StorageLayout) -> (RecordId, RecordId) {
+        let schema_id = data_manager.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = data_manager
+            .create_table_with_layout(
+                schema_id,
+                table_name,
+                &[
+                    ColumnDefinition::new("col_1", SqlType::SmallInt(i16::MIN)),
+                    ColumnDefinition::new("col_2", SqlType::VarChar(10)),
+                    ColumnDefinition::new("col_3", SqlType::Bool),
+                ],
+                layout,
+            )
+            .expect("table is created");
+        (schema_id, table_id)
+    }
+
+    fn scan(data_manager: &DataManager, schema_id: RecordId, table_id: RecordId) -> Vec<Row> {
+        data_manager
+            .full_scan(SESSION, schema_id, table_id)
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .collect()
+    }
+
+    #[rstest::rstest]
+    fn stores_the_same_rows_as_row_layout(data_manager_with_schema: DataManager) {
+        let (schema_id, row_table_id) = create_table(&data_manager_with_schema, "row_table", StorageLayout::Row);
+        let (_, columnar_table_id) = create_table(&data_manager_with_schema, "columnar_table", StorageLayout::Columnar);
+
+        data_manager_with_schema
+            .write_into(SESSION, schema_id, row_table_id, rows())
+            .expect("values are inserted");
+        data_manager_with_schema
+            .write_into(SESSION, schema_id, columnar_table_id, rows())
+            .expect("values are inserted");
+
+        assert_eq!(scan(&data_manager_with_schema, schema_id, columnar_table_id), rows());
+        assert_eq!(
+            scan(&data_manager_with_schema, schema_id, columnar_table_id),
+            scan(&data_manager_with_schema, schema_id, row_table_id)
+        );
+    }
+
+    #[rstest::rstest]
+    fn scan_subset_of_columns(data_manager_with_schema: DataManager) {
+        let expected = vec![
+            (
+                Binary::pack(&[Datum::from_u64(0)]),
+                Binary::pack(&[Datum::from_bool(true), Datum::from_i16(1)]),
+            ),
+            (
+                Binary::pack(&[Datum::from_u64(1)]),
+                Binary::pack(&[Datum::from_bool(false), Datum::from_i16(2)]),
+            ),
+        ];
+        for (table_name, layout) in &[
+            ("row_table", StorageLayout::Row),
+            ("columnar_table", StorageLayout::Columnar),
+        ] {
+            let (schema_id, table_id) = create_table(&data_manager_with_schema, table_name, *layout);
+            data_manager_with_schema
+                .write_into(SESSION, schema_id, table_id, rows())
+                .expect("values are inserted");
+
+            assert_eq!(
+                data_manager_with_schema
+                    .scan_columns(SESSION, schema_id, table_id, &[2, 0])
+                    .expect("to scan a table")
+                    .map(|item| item.expect("no io error").expect("no platform error"))
+                    .collect::<Vec<Row>>(),
+                expected
+            );
+        }
+    }
+
+    #[rstest::rstest]
+    fn scan_of_unknown_column(data_manager_with_schema: DataManager) {
+        let (schema_id, table_id) = create_table(&data_manager_with_schema, "columnar_table", StorageLayout::Columnar);
+
+        assert!(data_manager_with_schema
+            .scan_columns(SESSION, schema_id, table_id, &[3])
+            .is_err());
+    }
+
+    #[rstest::rstest]
+    fn update_and_delete(data_manager_with_schema: DataManager) {
+        let (schema_id, table_id) = create_table(&data_manager_with_schema, "columnar_table", StorageLayout::Columnar);
+        data_manager_with_schema
+            .write_into(SESSION, schema_id, table_id, rows())
+            .expect("values are inserted");
+
+        let updated = (
+            Binary::pack(&[Datum::from_u64(1)]),
+            Binary::pack(&[Datum::from_i16(3), Datum::from_str("three"), Datum::from_bool(true)]),
+        );
+        data_manager_with_schema
+            .write_into(SESSION, schema_id, table_id, vec![updated.clone()])
+            .expect("values are updated");
+        assert_eq!(
+            data_manager_with_schema.delete_from(
+                SESSION,
+                schema_id,
+                table_id,
+                vec![Binary::pack(&[Datum::from_u64(0)])]
+            ),
+            Ok(1)
+        );
+
+        assert_eq!(scan(&data_manager_with_schema, schema_id, table_id), vec![updated]);
+    }
+
+    #[rstest::rstest]
+    fn drop_table(data_manager_with_schema: DataManager) {
+        let (schema_id, table_id) = create_table(&data_manager_with_schema, "columnar_table", StorageLayout::Columnar);
+
+        assert_eq!(data_manager_with_schema.drop_table(schema_id, table_id), Ok(()));
+        assert_eq!(
+            data_manager_with_schema.table_exists(SCHEMA, "columnar_table"),
+            Some((schema_id, None))
+        );
+    }
+}
+
+#[cfg(test)]
+mod select_scan {
+    use super::*;
+
+    const READER: SessionId = 1;
+    // more rows than a single page of a scan
+    const ROWS: u64 = 2500;
+
+    fn with_rows(data_manager: &DataManager, table_name: &str, layout: StorageLayout) -> (RecordId, RecordId) {
+        let schema_id = data_manager.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = data_manager
+            .create_table_with_layout(
+                schema_id,
+                table_name,
+                &[
+                    ColumnDefinition::new("col_1", SqlType::Integer(i32::MIN)),
+                    ColumnDefinition::new("col_2", SqlType::VarChar(10)),
+                ],
+                layout,
+            )
+            .expect("table is created");
+        data_manager
+            .write_into(
+                SESSION,
+                schema_id,
+                table_id,
+                (0..ROWS)
+                    .map(|id| {
+                        let text = if id % 2 == 0 {
+                            Datum::Null
+                        } else {
+                            Datum::from_str("odd")
+                        };
+                        (
+                            Binary::pack(&[Datum::from_u64(id)]),
+                            Binary::pack(&[Datum::from_i32(id as i32), text]),
+                        )
+                    })
+                    .collect(),
+            )
+            .expect("values are inserted");
+        (schema_id, table_id)
+    }
+
+    fn select(data_manager: &DataManager, session_id: SessionId, table_name: &str) -> Vec<Vec<Option<String>>> {
+        let mut rows = data_manager
+            .select_scan(
+                session_id,
+                SCHEMA,
+                table_name,
+                &["col_2".to_owned(), "col_1".to_owned()],
+            )
+            .expect("to scan a table")
+            .map(|row| row.expect("row is read"))
+            .collect::<Vec<_>>();
+        // rows follow the order of their stored keys
+        rows.sort();
+        rows
+    }
+
+    fn expected(ids: std::ops::Range<u64>) -> Vec<Vec<Option<String>>> {
+        let mut rows = ids
+            .map(|id| {
+                vec![
+                    if id % 2 == 0 { None } else { Some("odd".to_owned()) },
+                    Some(id.to_string()),
+                ]
+            })
+            .collect::<Vec<_>>();
+        rows.sort();
+        rows
+    }
+
+    #[rstest::rstest]
+    fn rows_of_both_layouts(data_manager_with_schema: DataManager) {
+        for (table_name, layout) in &[
+            ("row_table", StorageLayout::Row),
+            ("columnar_table", StorageLayout::Columnar),
+        ] {
+            with_rows(&data_manager_with_schema, table_name, *layout);
+
+            assert_eq!(
+                select(&data_manager_with_schema, SESSION, table_name),
+                expected(0..ROWS)
+            );
+        }
+    }
+
+    #[rstest::rstest]
+    fn abandoned_scan_counts_rows_that_were_read(data_manager_with_schema: DataManager) {
+        with_rows(&data_manager_with_schema, "table_name", StorageLayout::Row);
+
+        assert_eq!(
+            data_manager_with_schema
+                .select_scan(SESSION, SCHEMA, "table_name", &["col_1".to_owned()])
+                .expect("to scan a table")
+                .take(3)
+                .count(),
+            3
+        );
+        assert_eq!(
+            data_manager_with_schema
+                .tables_activity()
+                .expect("activity is read")
+                .into_iter()
+                .map(|(_schema, _table, activity)| (activity.sequential_scans, activity.rows_read))
+                .collect::<Vec<_>>(),
+            vec![(1, 3)]
+        );
+    }
+
+    #[rstest::rstest]
+    fn reader_in_transaction_sees_snapshot(data_manager_with_schema: DataManager) {
+        let (schema_id, table_id) = with_rows(&data_manager_with_schema, "table_name", StorageLayout::Row);
+        data_manager_with_schema.begin_transaction(READER);
+        data_manager_with_schema.begin_transaction(SESSION);
+        assert_eq!(
+            data_manager_with_schema.delete_from(
+                SESSION,
+                schema_id,
+                table_id,
+                (0..ROWS).map(|id| Binary::pack(&[Datum::from_u64(id)])).collect()
+            ),
+            Ok(ROWS as usize)
+        );
+        data_manager_with_schema.commit_transaction(SESSION);
+
+        assert_eq!(
+            select(&data_manager_with_schema, READER, "table_name"),
+            expected(0..ROWS)
+        );
+        assert_eq!(select(&data_manager_with_schema, SESSION, "table_name"), expected(0..0));
+    }
+
+    #[rstest::rstest]
+    fn unknown_column(data_manager_with_schema: DataManager) {
+        with_rows(&data_manager_with_schema, "table_name", StorageLayout::Row);
+
+        assert!(data_manager_with_schema
+            .select_scan(SESSION, SCHEMA, "table_name", &["col_3".to_owned()])
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod scan_where {
+    use super::*;
+
+    fn rows() -> Vec<Row> {
+        (0..4)
+            .map(|id| {
+                (
+                    Binary::pack(&[Datum::from_u64(id)]),
+                    Binary::pack(&[
+                        Datum::from_i32(id as i32 % 2),
+                        Datum::from_string(format!("name_{}", id)),
+                    ]),
+                )
+            })
+            .collect()
+    }
+
+    fn scan_where(data_manager: &DataManager, layout: StorageLayout, column: usize, value: &Datum) -> Vec<Row> {
+        let schema_id = data_manager.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = data_manager
+            .create_table_with_layout(
+                schema_id,
+                "table_name",
+                &[
+                    ColumnDefinition::new("col_1", SqlType::Integer(i32::MIN)),
+                    ColumnDefinition::new("col_2", SqlType::VarChar(10)),
+                ],
+                layout,
+            )
+            .expect("table is created");
+        data_manager
+            .write_into(SESSION, schema_id, table_id, rows())
+            .expect("values are inserted");
+        data_manager
+            .scan_where(SESSION, schema_id, table_id, column, value)
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .collect()
+    }
+
+    #[rstest::rstest]
+    fn leading_column(data_manager_with_schema: DataManager) {
+        let rows = rows();
+        assert_eq!(
+            scan_where(&data_manager_with_schema, StorageLayout::Row, 0, &Datum::from_i32(1)),
+            vec![rows[1].clone(), rows[3].clone()]
+        );
+    }
+
+    #[rstest::rstest]
+    fn string_column(data_manager_with_schema: DataManager) {
+        assert_eq!(
+            scan_where(
+                &data_manager_with_schema,
+                StorageLayout::Row,
+                1,
+                &Datum::from_string("name_2".to_owned())
+            ),
+            vec![rows()[2].clone()]
+        );
+    }
+
+    #[rstest::rstest]
+    fn columnar_table(data_manager_with_schema: DataManager) {
+        assert_eq!(
+            scan_where(
+                &data_manager_with_schema,
+                StorageLayout::Columnar,
+                1,
+                &Datum::from_string("name_3".to_owned())
+            ),
+            vec![rows()[3].clone()]
+        );
+    }
+
+    #[rstest::rstest]
+    fn no_matches(data_manager_with_schema: DataManager) {
+        assert_eq!(
+            scan_where(&data_manager_with_schema, StorageLayout::Row, 0, &Datum::from_i32(5)),
+            vec![]
+        );
+    }
+}
+
+#[cfg(test)]
+mod bulk_update_where {
+    use super::*;
+
+    const READER: SessionId = 1;
+
+    fn with_rows(data_manager: &DataManager) -> (RecordId, RecordId) {
+        let schema_id = data_manager.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = data_manager
+            .create_table(
+                schema_id,
+                "table_name",
+                &[
+                    ColumnDefinition::new("col_1", SqlType::Integer(i32::MIN)),
+                    ColumnDefinition::new("col_2", SqlType::Integer(i32::MIN)),
+                ],
+            )
+            .expect("table is created");
+        let rows = (0..4)
+            .map(|id| {
+                (
+                    Binary::pack(&[Datum::from_u64(id)]),
+                    Binary::pack(&[Datum::from_i32(id as i32), Datum::from_i32(0)]),
+                )
+            })
+            .collect();
+        data_manager
+            .write_into(SESSION, schema_id, table_id, rows)
+            .expect("values are inserted");
+        (schema_id, table_id)
+    }
+
+    fn second_column(data_manager: &DataManager, session_id: SessionId, table: (RecordId, RecordId)) -> Vec<Values> {
+        data_manager
+            .full_scan(session_id, table.0, table.1)
+            .expect("to scan a table")
+            .map(|item| item.expect("no io error").expect("no platform error"))
+            .map(|(_key, values)| Binary::pack(&values.unpack()[1..]))
+            .collect()
+    }
+
+    fn packed(values: &[i32]) -> Vec<Values> {
+        values
+            .iter()
+            .map(|value| Binary::pack(&[Datum::from_i32(*value)]))
+            .collect()
+    }
+
+    #[rstest::rstest]
+    fn subset_of_rows(data_manager_with_schema: DataManager) {
+        let table = with_rows(&data_manager_with_schema);
+        assert_eq!(
+            data_manager_with_schema.bulk_update_where(
+                SESSION,
+                table.0,
+                table.1,
+                &[(1, Datum::from_i32(7))],
+                &|values| values[0] >= Datum::from_i32(2)
+            ),
+            Ok(2)
+        );
+        assert_eq!(
+            second_column(&data_manager_with_schema, SESSION, table),
+            packed(&[0, 0, 7, 7])
+        );
+    }
+
+    #[rstest::rstest]
+    fn no_matching_rows(data_manager_with_schema: DataManager) {
+        let table = with_rows(&data_manager_with_schema);
+        assert_eq!(
+            data_manager_with_schema
+                .bulk_update_where(SESSION, table.0, table.1, &[(1, Datum::from_i32(7))], &|_| false),
+            Ok(0)
+        );
+        assert_eq!(
+            second_column(&data_manager_with_schema, SESSION, table),
+            packed(&[0; 4])
+        );
+    }
+
+    #[rstest::rstest]
+    fn all_rows(data_manager_with_schema: DataManager) {
+        let table = with_rows(&data_manager_with_schema);
+        assert_eq!(
+            data_manager_with_schema
+                .bulk_update_where(SESSION, table.0, table.1, &[(1, Datum::from_i32(7))], &|_| true),
+            Ok(4)
+        );
+        assert_eq!(
+            second_column(&data_manager_with_schema, SESSION, table),
+            packed(&[7; 4])
+        );
+    }
+
+    #[rstest::rstest]
+    fn reader_in_transaction_sees_snapshot(data_manager_with_schema: DataManager) {
+        let table = with_rows(&data_manager_with_schema);
+        data_manager_with_schema.begin_transaction(READER);
+        data_manager_with_schema.begin_transaction(SESSION);
+        assert_eq!(
+            data_manager_with_schema
+                .bulk_update_where(SESSION, table.0, table.1, &[(1, Datum::from_i32(7))], &|_| true),
+            Ok(4)
+        );
+        data_manager_with_schema.commit_transaction(SESSION);
+
+        assert_eq!(second_column(&data_manager_with_schema, READER, table), packed(&[0; 4]));
+        assert_eq!(
+            second_column(&data_manager_with_schema, SESSION, table),
+            packed(&[7; 4])
+        );
+    }
+
+    #[rstest::rstest]
+    fn unknown_column(data_manager_with_schema: DataManager) {
+        let table = with_rows(&data_manager_with_schema);
+        assert!(data_manager_with_schema
+            .bulk_update_where(SESSION, table.0, table.1, &[(2, Datum::from_i32(7))], &|_| true)
+            .is_err());
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use super::*;
+use representation::{Binary, Datum};
 use sql_types::SqlType;
 
 #[rstest::rstest]
@@ -50,14 +51,64 @@ fn same_table_names_with_different_columns_in_different_schemas(data_manager: Da
         Ok(vec![ColumnDefinition::new(
             "sn_1_column",
             SqlType::SmallInt(i16::min_value())
-        )])
+        )
+        .with_ordinal_position(1)])
     );
     assert_eq!(
         data_manager.table_columns(schema_2_id, table_2_id),
         Ok(vec![ColumnDefinition::new(
             "sn_2_column",
             SqlType::BigInt(i64::min_value())
-        )])
+        )
+        .with_ordinal_position(1)])
+    );
+}
+
+#[rstest::rstest]
+fn same_table_names_in_different_schemas_keep_their_own_rows(data_manager: DataManager) {
+    let schema_1_id = data_manager.create_schema(SCHEMA_1).expect("schema is created");
+    let schema_2_id = data_manager.create_schema(SCHEMA_2).expect("schema is created");
+    let columns = [ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))];
+    let table_1_id = data_manager
+        .create_table(schema_1_id, "table_name", &columns)
+        .expect("table is created");
+    let table_2_id = data_manager
+        .create_table(schema_2_id, "table_name", &columns)
+        .expect("table is created");
+
+    let row = |key: u64, value: i16| {
+        (
+            Binary::pack(&[Datum::from_u64(key)]),
+            Binary::pack(&[Datum::from_i16(value)]),
+        )
+    };
+    data_manager
+        .write_into(SESSION, schema_1_id, table_1_id, vec![row(1, 123)])
+        .expect("values are inserted");
+    data_manager
+        .write_into(SESSION, schema_2_id, table_2_id, vec![row(1, 456)])
+        .expect("values are inserted");
+
+    assert_eq!(
+        data_manager
+            .full_scan(SESSION, schema_1_id, table_1_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![row(1, 123)])
+    );
+
+    data_manager
+        .drop_table(schema_1_id, table_1_id)
+        .expect("table is dropped");
+
+    assert_eq!(
+        data_manager.table_exists(SCHEMA_1, "table_name"),
+        Some((schema_1_id, None))
+    );
+    assert_eq!(
+        data_manager
+            .full_scan(SESSION, schema_2_id, table_2_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![row(1, 456)])
     );
 }
 
@@ -142,3 +193,90 @@ fn cascade_drop_schema_drops_tables_in_it(data_manager_with_schema: DataManager)
         Ok(_)
     ));
 }
+
+#[rstest::rstest]
+fn list_schemas(data_manager: DataManager) {
+    data_manager.create_schema(SCHEMA_2).expect("schema is created");
+    data_manager.create_schema(SCHEMA_1).expect("schema is created");
+    let session_id = data_manager.open_session();
+    data_manager
+        .create_temp_table(
+            session_id,
+            SCHEMA_1,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+            StorageLayout::Row,
+        )
+        .expect("temporary table is created");
+
+    assert_eq!(data_manager.schemas(), vec![SCHEMA_1.to_owned(), SCHEMA_2.to_owned()]);
+}
+
+#[rstest::rstest]
+fn dropped_schema_is_not_listed(data_manager: DataManager) {
+    let schema_id = data_manager.create_schema(SCHEMA_1).expect("schema is created");
+    data_manager.create_schema(SCHEMA_2).expect("schema is created");
+
+    assert_eq!(
+        data_manager
+            .drop_schema(schema_id, DropStrategy::Restrict)
+            .expect("no system errors"),
+        Ok(())
+    );
+    assert_eq!(data_manager.schemas(), vec![SCHEMA_2.to_owned()]);
+}
+
+#[rstest::rstest]
+fn renamed_schema_keeps_its_tables(data_manager: DataManager) {
+    let schema_id = data_manager.create_schema(SCHEMA_1).expect("schema is created");
+    let table_id = data_manager
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+    let row = (
+        Binary::pack(&[Datum::from_u64(1)]),
+        Binary::pack(&[Datum::from_i16(123)]),
+    );
+    data_manager
+        .write_into(SESSION, schema_id, table_id, vec![row.clone()])
+        .expect("values are inserted");
+
+    assert_eq!(data_manager.rename_schema(SCHEMA_1, SCHEMA_2), Ok(Ok(())));
+
+    assert_eq!(data_manager.schema_exists(SCHEMA_1), None);
+    assert_eq!(data_manager.schema_exists(SCHEMA_2), Some(schema_id));
+    assert_eq!(
+        data_manager.table_exists(SCHEMA_2, "table_name"),
+        Some((schema_id, Some(table_id)))
+    );
+    assert_eq!(data_manager.schemas(), vec![SCHEMA_2.to_owned()]);
+    assert_eq!(
+        data_manager
+            .full_scan(SESSION, schema_id, table_id)
+            .map(|iter| iter.map(Result::unwrap).map(Result::unwrap).collect()),
+        Ok(vec![row])
+    );
+}
+
+#[rstest::rstest]
+fn rename_schema_to_existing_name(data_manager: DataManager) {
+    data_manager.create_schema(SCHEMA_1).expect("schema is created");
+    data_manager.create_schema(SCHEMA_2).expect("schema is created");
+
+    assert_eq!(
+        data_manager.rename_schema(SCHEMA_1, SCHEMA_2),
+        Ok(Err(RenameSchemaError::AlreadyExists))
+    );
+    assert_eq!(data_manager.schemas(), vec![SCHEMA_1.to_owned(), SCHEMA_2.to_owned()]);
+}
+
+#[rstest::rstest]
+fn rename_non_existent_schema(data_manager: DataManager) {
+    assert_eq!(
+        data_manager.rename_schema(SCHEMA_1, SCHEMA_2),
+        Ok(Err(RenameSchemaError::DoesNotExist))
+    );
+}
@@ -115,3 +115,873 @@ fn table_columns_on_empty_table(data_manager_with_schema: DataManager) {
         vec![]
     );
 }
+
+#[rstest::rstest]
+fn table_columns_in_creation_order(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[
+                ColumnDefinition::new("d", SqlType::SmallInt(i16::MIN)),
+                ColumnDefinition::new("c", SqlType::Integer(i32::MIN)),
+                ColumnDefinition::new("b", SqlType::BigInt(i64::MIN)),
+                ColumnDefinition::new("a", SqlType::Bool),
+            ],
+        )
+        .expect("table is created");
+
+    assert_eq!(
+        data_manager_with_schema
+            .table_columns(schema_id, table_id)
+            .expect("no system errors"),
+        vec![
+            ColumnDefinition::new("d", SqlType::SmallInt(i16::MIN)).with_ordinal_position(1),
+            ColumnDefinition::new("c", SqlType::Integer(i32::MIN)).with_ordinal_position(2),
+            ColumnDefinition::new("b", SqlType::BigInt(i64::MIN)).with_ordinal_position(3),
+            ColumnDefinition::new("a", SqlType::Bool).with_ordinal_position(4),
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn table_columns_ordered_by_ordinal_position(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[
+                ColumnDefinition::new("third", SqlType::Bool).with_ordinal_position(3),
+                ColumnDefinition::new("first", SqlType::Bool).with_ordinal_position(1),
+                ColumnDefinition::new("second", SqlType::Bool).with_ordinal_position(2),
+            ],
+        )
+        .expect("table is created");
+
+    assert_eq!(
+        data_manager_with_schema
+            .table_columns(schema_id, table_id)
+            .expect("no system errors")
+            .iter()
+            .map(ColumnDefinition::name)
+            .collect::<Vec<_>>(),
+        vec!["first", "second", "third"]
+    );
+}
+
+#[rstest::rstest]
+fn column_types_of_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[
+                ColumnDefinition::new("id", SqlType::SmallInt(i16::MIN)),
+                ColumnDefinition::new("name", SqlType::VarChar(10)),
+                ColumnDefinition::new("active", SqlType::Bool),
+            ],
+        )
+        .expect("table is created");
+
+    assert_eq!(
+        data_manager_with_schema.column_types(SCHEMA, "table_name"),
+        Some(vec![
+            ("id".to_owned(), SqlType::SmallInt(i16::MIN)),
+            ("name".to_owned(), SqlType::VarChar(10)),
+            ("active".to_owned(), SqlType::Bool),
+        ])
+    );
+}
+
+#[rstest::rstest]
+fn column_types_of_non_existent_table(data_manager_with_schema: DataManager) {
+    assert_eq!(data_manager_with_schema.column_types(SCHEMA, "non_existent"), None);
+    assert_eq!(
+        data_manager_with_schema.column_types("non_existent", "table_name"),
+        None
+    );
+}
+
+#[rstest::rstest]
+fn table_and_column_exist(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[
+                ColumnDefinition::new("id", SqlType::SmallInt(i16::MIN)),
+                ColumnDefinition::new("name", SqlType::VarChar(10)),
+            ],
+        )
+        .expect("table is created");
+
+    assert_eq!(
+        data_manager_with_schema.table_exists(SCHEMA, "table_name"),
+        Some((schema_id, Some(table_id)))
+    );
+    assert_eq!(
+        data_manager_with_schema.column_exists(SCHEMA, "table_name", "name"),
+        Some(1)
+    );
+}
+
+#[rstest::rstest]
+fn non_existent_column(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("id", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+
+    assert_eq!(
+        data_manager_with_schema.column_exists(SCHEMA, "table_name", "non_existent"),
+        None
+    );
+}
+
+#[rstest::rstest]
+fn column_of_non_existent_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    assert_eq!(
+        data_manager_with_schema.table_exists(SCHEMA, "non_existent"),
+        Some((schema_id, None))
+    );
+    assert_eq!(
+        data_manager_with_schema.column_exists(SCHEMA, "non_existent", "id"),
+        None
+    );
+}
+
+#[rstest::rstest]
+fn column_in_non_existent_schema(data_manager: DataManager) {
+    assert_eq!(data_manager.table_exists("non_existent", "table_name"), None);
+    assert_eq!(data_manager.column_exists("non_existent", "table_name", "id"), None);
+}
+
+#[rstest::rstest]
+fn temporary_table_shadows_permanent_only_in_its_session(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+    let session_id = data_manager_with_schema.open_session();
+    let other_session_id = data_manager_with_schema.open_session();
+    let temp_table_id = data_manager_with_schema
+        .create_temp_table(
+            session_id,
+            SCHEMA,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+            StorageLayout::Row,
+        )
+        .expect("temporary table is created");
+    let temp_schema_id = data_manager_with_schema
+        .temp_table_exists(session_id, SCHEMA, "table_name")
+        .map(|(schema_id, _)| schema_id)
+        .expect("temporary table exists");
+
+    assert_eq!(
+        data_manager_with_schema.session_table_exists(session_id, SCHEMA, "table_name"),
+        Some((temp_schema_id, Some(temp_table_id)))
+    );
+    assert_eq!(
+        data_manager_with_schema.session_table_exists(other_session_id, SCHEMA, "table_name"),
+        Some((schema_id, Some(table_id)))
+    );
+
+    data_manager_with_schema
+        .close_session(session_id)
+        .expect("session is closed");
+
+    assert_eq!(
+        data_manager_with_schema.session_table_exists(session_id, SCHEMA, "table_name"),
+        Some((schema_id, Some(table_id)))
+    );
+}
+
+#[rstest::rstest]
+fn statistics_are_dropped_with_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+    let statistics = vec![ColumnStatistics {
+        distinct: 1,
+        min: Some("1".to_owned()),
+        max: Some("1".to_owned()),
+        null_fraction: 0.0,
+    }];
+
+    data_manager_with_schema.set_table_statistics(schema_id, table_id, statistics.clone());
+    assert_eq!(
+        data_manager_with_schema.table_statistics(schema_id, table_id),
+        Some(statistics)
+    );
+
+    data_manager_with_schema
+        .drop_table(schema_id, table_id)
+        .expect("table is dropped");
+    assert_eq!(data_manager_with_schema.table_statistics(schema_id, table_id), None);
+}
+
+#[rstest::rstest]
+fn activity_is_dropped_with_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+
+    data_manager_with_schema.count_changes(schema_id, table_id, TableChange::Inserted, 2);
+    data_manager_with_schema.count_changes(schema_id, table_id, TableChange::Deleted, 1);
+    assert_eq!(
+        data_manager_with_schema
+            .full_scan(SESSION, schema_id, table_id)
+            .expect("table is scanned")
+            .count(),
+        0
+    );
+    assert_eq!(
+        data_manager_with_schema.tables_activity().expect("activity is read"),
+        vec![(
+            SCHEMA.to_owned(),
+            "table_name".to_owned(),
+            TableActivity {
+                live_rows: 0,
+                sequential_scans: 1,
+                rows_read: 0,
+                rows_inserted: 2,
+                rows_updated: 0,
+                rows_deleted: 1,
+            }
+        )]
+    );
+
+    data_manager_with_schema
+        .drop_table(schema_id, table_id)
+        .expect("table is dropped");
+    assert_eq!(
+        data_manager_with_schema.tables_activity().expect("activity is read"),
+        vec![]
+    );
+}
+
+#[rstest::rstest]
+fn list_tables_of_schema(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    for table_name in &["table_name_2", "table_name_1", "table_name_3"] {
+        data_manager_with_schema
+            .create_table(
+                schema_id,
+                table_name,
+                &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+            )
+            .expect("table is created");
+    }
+    let table_id = data_manager_with_schema
+        .table_exists(SCHEMA, "table_name_3")
+        .and_then(|(_, table_id)| table_id)
+        .expect("table exists");
+    data_manager_with_schema
+        .drop_table(schema_id, table_id)
+        .expect("table is dropped");
+
+    assert_eq!(
+        data_manager_with_schema.tables(SCHEMA),
+        Some(vec!["table_name_1".to_owned(), "table_name_2".to_owned()])
+    );
+}
+
+#[rstest::rstest]
+fn list_tables_of_empty_schema(data_manager_with_schema: DataManager) {
+    assert_eq!(data_manager_with_schema.tables(SCHEMA), Some(vec![]));
+}
+
+#[rstest::rstest]
+fn list_tables_of_non_existent_schema(data_manager: DataManager) {
+    assert_eq!(data_manager.tables("non_existent"), None);
+}
+
+#[rstest::rstest]
+fn disable_and_drop_trigger(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+    data_manager_with_schema.create_trigger(
+        schema_id,
+        table_id,
+        TriggerDefinition::new(
+            "trigger_name",
+            TriggerTiming::Before,
+            vec![TriggerEvent::Insert],
+            schema_id,
+            "function_name",
+        ),
+    );
+    data_manager_with_schema.enable_trigger(schema_id, table_id, "trigger_name", false);
+
+    let triggers = data_manager_with_schema.triggers(schema_id, table_id);
+    assert_eq!(triggers.len(), 1);
+    assert!(!triggers[0].is_enabled());
+
+    data_manager_with_schema.drop_trigger(schema_id, table_id, "trigger_name");
+
+    assert!(!data_manager_with_schema.trigger_exists(schema_id, table_id, "trigger_name"));
+}
+
+#[rstest::rstest]
+fn triggers_are_dropped_with_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+        )
+        .expect("table is created");
+    data_manager_with_schema.create_trigger(
+        schema_id,
+        table_id,
+        TriggerDefinition::new(
+            "trigger_name",
+            TriggerTiming::After,
+            vec![TriggerEvent::Delete],
+            schema_id,
+            "function_name",
+        ),
+    );
+    data_manager_with_schema
+        .drop_table(schema_id, table_id)
+        .expect("table is dropped");
+
+    assert_eq!(data_manager_with_schema.triggers(schema_id, table_id), vec![]);
+}
+
+#[rstest::rstest]
+fn foreign_keys_are_dropped_with_referenced_table(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let referenced_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "referenced",
+            &[ColumnDefinition::unique("id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("table is created");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("referenced_id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("table is created");
+    data_manager_with_schema.add_foreign_key(
+        schema_id,
+        table_id,
+        ForeignKeyDefinition::new(
+            "fk_name",
+            vec!["referenced_id".to_owned()],
+            schema_id,
+            referenced_id,
+            vec!["id".to_owned()],
+        ),
+    );
+    assert!(data_manager_with_schema.foreign_key_exists(schema_id, table_id, "fk_name"));
+
+    data_manager_with_schema
+        .drop_table(schema_id, referenced_id)
+        .expect("table is dropped");
+
+    assert_eq!(data_manager_with_schema.foreign_keys(schema_id, table_id), vec![]);
+}
+
+#[rstest::rstest]
+fn foreign_keys_follow_renamed_columns(data_manager_with_schema: DataManager) {
+    let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+    let referenced_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "referenced",
+            &[ColumnDefinition::unique("id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("table is created");
+    let table_id = data_manager_with_schema
+        .create_table(
+            schema_id,
+            "table_name",
+            &[ColumnDefinition::new("referenced_id", SqlType::Integer(i32::MIN))],
+        )
+        .expect("table is created");
+    data_manager_with_schema.add_foreign_key(
+        schema_id,
+        table_id,
+        ForeignKeyDefinition::new(
+            "fk_name",
+            vec!["referenced_id".to_owned()],
+            schema_id,
+            referenced_id,
+            vec!["id".to_owned()],
+        ),
+    );
+
+    data_manager_with_schema
+        .rename_column(schema_id, referenced_id, "id", "key")
+        .expect("column is renamed");
+    data_manager_with_schema
+        .rename_column(schema_id, table_id, "referenced_id", "referenced_key")
+        .expect("column is renamed");
+
+    assert_eq!(
+        data_manager_with_schema.foreign_keys(schema_id, table_id),
+        vec![ForeignKeyDefinition::new(
+            "fk_name",
+            vec!["referenced_key".to_owned()],
+            schema_id,
+            referenced_id,
+            vec!["key".to_owned()],
+        )]
+    );
+}
+
+#[cfg(test)]
+mod describe_table {
+    use super::*;
+
+    fn trigger() -> TriggerDefinition {
+        TriggerDefinition::new(
+            "trigger_name",
+            TriggerTiming::After,
+            vec![TriggerEvent::Insert, TriggerEvent::Delete],
+            0,
+            "function_name",
+        )
+    }
+
+    #[rstest::rstest]
+    fn table_with_constraints(data_manager_with_schema: DataManager) {
+        let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = data_manager_with_schema
+            .create_table_with_layout(
+                schema_id,
+                "table_name",
+                &[
+                    ColumnDefinition::unique("id", SqlType::Integer(i32::MIN)),
+                    ColumnDefinition::unique("code", SqlType::VarChar(5)),
+                    ColumnDefinition::new("amount", SqlType::SmallInt(i16::MIN)),
+                    ColumnDefinition::new("total", SqlType::SmallInt(i16::MIN)).with_generated("amount * 2".to_owned()),
+                ],
+                StorageLayout::Columnar,
+            )
+            .expect("table is created");
+        data_manager_with_schema.create_trigger(schema_id, table_id, trigger());
+
+        let description = data_manager_with_schema
+            .describe_table(SCHEMA, "table_name")
+            .expect("table exists");
+        assert_eq!(
+            description
+                .columns
+                .iter()
+                .map(|column| (column.name(), column.sql_type(), column.generated()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("id".to_owned(), SqlType::Integer(i32::MIN), None),
+                ("code".to_owned(), SqlType::VarChar(5), None),
+                ("amount".to_owned(), SqlType::SmallInt(i16::MIN), None),
+                ("total".to_owned(), SqlType::SmallInt(i16::MIN), Some("amount * 2")),
+            ]
+        );
+        assert_eq!(description.unique_columns, vec!["id".to_owned(), "code".to_owned()]);
+        assert_eq!(description.layout, StorageLayout::Columnar);
+        assert_eq!(description.triggers, vec![trigger()]);
+    }
+
+    #[rstest::rstest]
+    fn table_without_constraints(data_manager_with_schema: DataManager) {
+        let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = data_manager_with_schema
+            .create_table(
+                schema_id,
+                "table_name",
+                &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+            )
+            .expect("table is created");
+
+        assert_eq!(
+            data_manager_with_schema.describe_table(SCHEMA, "table_name"),
+            Some(TableDescription {
+                columns: data_manager_with_schema
+                    .table_columns(schema_id, table_id)
+                    .expect("table exists"),
+                unique_columns: vec![],
+                layout: StorageLayout::Row,
+                triggers: vec![],
+            })
+        );
+    }
+
+    #[rstest::rstest]
+    fn non_existent_table(data_manager_with_schema: DataManager) {
+        assert_eq!(data_manager_with_schema.describe_table(SCHEMA, "non_existent"), None);
+        assert_eq!(
+            data_manager_with_schema.describe_table("non_existent", "table_name"),
+            None
+        );
+    }
+
+    #[rstest::rstest]
+    fn reflects_altered_trigger(data_manager_with_schema: DataManager) {
+        let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = data_manager_with_schema
+            .create_table(
+                schema_id,
+                "table_name",
+                &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+            )
+            .expect("table is created");
+        data_manager_with_schema.create_trigger(schema_id, table_id, trigger());
+        data_manager_with_schema.enable_trigger(schema_id, table_id, "trigger_name", false);
+
+        let description = data_manager_with_schema
+            .describe_table(SCHEMA, "table_name")
+            .expect("table exists");
+        assert_eq!(description.triggers.len(), 1);
+        assert!(!description.triggers[0].is_enabled());
+    }
+}
+
+#[cfg(test)]
+mod copy_table {
+    use super::*;
+    use representation::{Binary, Datum};
+
+    fn row(key: u64, value: i16) -> (Binary, Binary) {
+        (
+            Binary::with_data(key.to_be_bytes().to_vec()),
+            Binary::pack(&[Datum::from_i16(value)]),
+        )
+    }
+
+    fn values(data_manager: &DataManager, schema_id: RecordId, table_id: RecordId) -> Vec<Binary> {
+        data_manager
+            .full_scan(SESSION, schema_id, table_id)
+            .expect("table is scanned")
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .map(|(_key, values)| values)
+            .collect()
+    }
+
+    #[rstest::fixture]
+    fn with_source(data_manager_with_schema: DataManager) -> (DataManager, RecordId, RecordId) {
+        let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = data_manager_with_schema
+            .create_table(
+                schema_id,
+                "source",
+                &[ColumnDefinition::unique("column_test", SqlType::SmallInt(i16::MIN))],
+            )
+            .expect("table is created");
+        data_manager_with_schema
+            .write_into(SESSION, schema_id, table_id, vec![row(0, 123), row(1, 456)])
+            .expect("rows are written");
+        (data_manager_with_schema, schema_id, table_id)
+    }
+
+    #[rstest::rstest]
+    fn copied_table_has_the_same_columns(with_source: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, table_id) = with_source;
+
+        assert_eq!(
+            data_manager.copy_table(SESSION, SCHEMA, "source", SCHEMA, "target"),
+            Ok(Ok(()))
+        );
+        let copy_id = data_manager
+            .table_exists(SCHEMA, "target")
+            .and_then(|(_, table_id)| table_id)
+            .expect("table is copied");
+        assert_eq!(
+            data_manager.table_columns(schema_id, copy_id),
+            data_manager.table_columns(schema_id, table_id)
+        );
+    }
+
+    #[rstest::rstest]
+    fn copied_table_has_the_same_rows(with_source: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, table_id) = with_source;
+
+        data_manager
+            .copy_table(SESSION, SCHEMA, "source", SCHEMA, "target")
+            .expect("no system errors")
+            .expect("table is copied");
+        let copy_id = data_manager
+            .table_exists(SCHEMA, "target")
+            .and_then(|(_, table_id)| table_id)
+            .expect("table is copied");
+        assert_eq!(
+            values(&data_manager, schema_id, copy_id),
+            values(&data_manager, schema_id, table_id)
+        );
+    }
+
+    #[rstest::rstest]
+    fn modifying_copy_does_not_affect_source(with_source: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, table_id) = with_source;
+
+        data_manager
+            .copy_table(SESSION, SCHEMA, "source", SCHEMA, "target")
+            .expect("no system errors")
+            .expect("table is copied");
+        let copy_id = data_manager
+            .table_exists(SCHEMA, "target")
+            .and_then(|(_, table_id)| table_id)
+            .expect("table is copied");
+        let key = data_manager.next_key_id(schema_id, copy_id);
+        data_manager
+            .write_into(SESSION, schema_id, copy_id, vec![row(key, 789)])
+            .expect("row is written");
+
+        assert_eq!(values(&data_manager, schema_id, copy_id).len(), 3);
+        assert_eq!(
+            values(&data_manager, schema_id, table_id),
+            vec![row(0, 123).1, row(1, 456).1]
+        );
+    }
+
+    #[rstest::rstest]
+    fn copy_to_existing_table(with_source: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, _table_id) = with_source;
+        data_manager
+            .create_table(
+                schema_id,
+                "target",
+                &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+            )
+            .expect("table is created");
+
+        assert_eq!(
+            data_manager.copy_table(SESSION, SCHEMA, "source", SCHEMA, "target"),
+            Ok(Err(OperationOnTableError::TableAlreadyExists(format!(
+                "{}.target",
+                SCHEMA
+            ))))
+        );
+    }
+
+    #[rstest::rstest]
+    fn copy_of_non_existent_table(data_manager_with_schema: DataManager) {
+        assert_eq!(
+            data_manager_with_schema.copy_table(SESSION, SCHEMA, "source", SCHEMA, "target"),
+            Ok(Err(OperationOnTableError::TableDoesNotExist(format!(
+                "{}.source",
+                SCHEMA
+            ))))
+        );
+    }
+
+    #[rstest::rstest]
+    fn copy_across_schemas(with_source: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, table_id) = with_source;
+        let other_schema_id = data_manager.create_schema(SCHEMA_1).expect("schema is created");
+
+        assert_eq!(
+            data_manager.copy_table(SESSION, SCHEMA, "source", SCHEMA_2, "target"),
+            Ok(Err(OperationOnTableError::SchemaDoesNotExist(SCHEMA_2.to_owned())))
+        );
+        data_manager
+            .copy_table(SESSION, SCHEMA, "source", SCHEMA_1, "source")
+            .expect("no system errors")
+            .expect("table is copied");
+        let copy_id = data_manager
+            .table_exists(SCHEMA_1, "source")
+            .and_then(|(_, table_id)| table_id)
+            .expect("table is copied");
+        assert_eq!(
+            values(&data_manager, other_schema_id, copy_id),
+            values(&data_manager, schema_id, table_id)
+        );
+    }
+}
+
+mod partitions {
+    use super::*;
+    use representation::{Binary, Datum};
+
+    fn create_table(data_manager: &DataManager, schema_id: RecordId, table_name: &str) -> RecordId {
+        data_manager
+            .create_table(
+                schema_id,
+                table_name,
+                &[ColumnDefinition::new("column_test", SqlType::SmallInt(i16::MIN))],
+            )
+            .expect("table is created")
+    }
+
+    fn partition(schema_id: RecordId, table_id: RecordId, lower: i16, upper: i16) -> PartitionDefinition {
+        PartitionDefinition::new(
+            schema_id,
+            table_id,
+            PartitionBounds::Range {
+                lower: Datum::from_i16(lower),
+                upper: Datum::from_i16(upper),
+            },
+        )
+    }
+
+    #[rstest::fixture]
+    fn with_partitions(data_manager_with_schema: DataManager) -> (DataManager, RecordId, RecordId) {
+        let schema_id = data_manager_with_schema.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = create_table(&data_manager_with_schema, schema_id, "parent");
+        data_manager_with_schema.partition_by(schema_id, table_id, PartitionStrategy::Range, 0);
+        for &(name, lower, upper) in &[("low", 0, 10), ("high", 10, 20)] {
+            let partition_id = create_table(&data_manager_with_schema, schema_id, name);
+            data_manager_with_schema
+                .attach_partition(schema_id, table_id, partition(schema_id, partition_id, lower, upper))
+                .expect("partition is attached");
+        }
+        (data_manager_with_schema, schema_id, table_id)
+    }
+
+    #[rstest::rstest]
+    fn rows_are_routed_by_ranges(with_partitions: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, table_id) = with_partitions;
+        let low = data_manager
+            .table_exists(SCHEMA, "low")
+            .and_then(|(_, table_id)| table_id);
+        let high = data_manager
+            .table_exists(SCHEMA, "high")
+            .and_then(|(_, table_id)| table_id);
+
+        let partition_of = |value: i16| {
+            data_manager
+                .partition_of(schema_id, table_id, &Binary::pack(&[Datum::from_i16(value)]))
+                .map(|(_, partition_id)| partition_id)
+        };
+        assert_eq!(partition_of(0), low);
+        assert_eq!(partition_of(9), low);
+        assert_eq!(partition_of(10), high);
+        assert_eq!(partition_of(20), None);
+        assert_eq!(partition_of(-1), None);
+    }
+
+    #[rstest::rstest]
+    fn overlapping_partition_is_not_attached(with_partitions: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, table_id) = with_partitions;
+        let partition_id = create_table(&data_manager, schema_id, "middle");
+        let high = data_manager
+            .table_exists(SCHEMA, "high")
+            .and_then(|(_, table_id)| table_id)
+            .expect("partition exists");
+
+        assert_eq!(
+            data_manager.attach_partition(schema_id, table_id, partition(schema_id, partition_id, 15, 25)),
+            Err(partition(schema_id, high, 10, 20))
+        );
+        assert_eq!(data_manager.partitions(schema_id, table_id).len(), 2);
+    }
+
+    #[rstest::rstest]
+    fn partitions_are_dropped_with_table(with_partitions: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, table_id) = with_partitions;
+
+        data_manager.drop_table(schema_id, table_id).expect("table is dropped");
+
+        assert_eq!(data_manager.table_exists(SCHEMA, "low"), Some((schema_id, None)));
+        assert_eq!(data_manager.table_exists(SCHEMA, "high"), Some((schema_id, None)));
+        assert_eq!(data_manager.partition_key(schema_id, table_id), None);
+    }
+
+    #[rstest::rstest]
+    fn detached_partition_is_not_routed_to(with_partitions: (DataManager, RecordId, RecordId)) {
+        let (data_manager, schema_id, table_id) = with_partitions;
+        let low = data_manager
+            .table_exists(SCHEMA, "low")
+            .and_then(|(_, table_id)| table_id)
+            .expect("partition exists");
+
+        assert_eq!(
+            data_manager.partition_parent(schema_id, low),
+            Some((schema_id, table_id))
+        );
+        assert!(data_manager.detach_partition(schema_id, table_id, schema_id, low));
+        assert!(!data_manager.detach_partition(schema_id, table_id, schema_id, low));
+
+        assert_eq!(data_manager.partition_parent(schema_id, low), None);
+        assert_eq!(
+            data_manager.partition_of(schema_id, table_id, &Binary::pack(&[Datum::from_i16(5)])),
+            None
+        );
+        assert_eq!(data_manager.table_exists(SCHEMA, "low"), Some((schema_id, Some(low))));
+    }
+
+    #[rstest::rstest]
+    fn rows_are_routed_by_lists_and_default(data_manager_with_schema: DataManager) {
+        let data_manager = data_manager_with_schema;
+        let schema_id = data_manager.schema_exists(SCHEMA).expect("schema exists");
+        let table_id = create_table(&data_manager, schema_id, "parent");
+        data_manager.partition_by(schema_id, table_id, PartitionStrategy::List, 0);
+        let odd = create_table(&data_manager, schema_id, "odd");
+        data_manager
+            .attach_partition(
+                schema_id,
+                table_id,
+                PartitionDefinition::new(
+                    schema_id,
+                    odd,
+                    PartitionBounds::List(vec![Datum::from_i16(1), Datum::from_i16(3)]),
+                ),
+            )
+            .expect("partition is attached");
+
+        let partition_of = |value: i16| {
+            data_manager
+                .partition_of(schema_id, table_id, &Binary::pack(&[Datum::from_i16(value)]))
+                .map(|(_, partition_id)| partition_id)
+        };
+        assert_eq!(partition_of(3), Some(odd));
+        assert_eq!(partition_of(2), None);
+
+        let rest = create_table(&data_manager, schema_id, "rest");
+        data_manager
+            .attach_partition(
+                schema_id,
+                table_id,
+                PartitionDefinition::new(schema_id, rest, PartitionBounds::Default),
+            )
+            .expect("partition is attached");
+        assert_eq!(partition_of(1), Some(odd));
+        assert_eq!(partition_of(2), Some(rest));
+
+        let another = create_table(&data_manager, schema_id, "another");
+        assert_eq!(
+            data_manager.attach_partition(
+                schema_id,
+                table_id,
+                PartitionDefinition::new(schema_id, another, PartitionBounds::Default),
+            ),
+            Err(PartitionDefinition::new(schema_id, rest, PartitionBounds::Default))
+        );
+    }
+}
@@ -119,7 +119,7 @@ fn collection_not_found(database: PersistentDatabase, scenario: FailScenario) {
                 vec![(Binary::with_data(vec![]), Binary::with_data(vec![]))]
             )
             .expect("no io error"),
-        Ok(Err(DefinitionError::ObjectDoesNotExist))
+        Ok(Err(DefinitionError::ObjectDoesNotExist(OBJECT.to_owned())))
     );
 
     scenario.teardown();
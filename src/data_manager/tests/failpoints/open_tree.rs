@@ -82,7 +82,7 @@ fn collection_not_found(database: PersistentDatabase, scenario: FailScenario) {
 
     assert_eq!(
         database.create_object(SCHEMA, OBJECT).expect("no io error"),
-        Ok(Err(DefinitionError::ObjectDoesNotExist))
+        Ok(Err(DefinitionError::ObjectDoesNotExist(OBJECT.to_owned())))
     );
 
     scenario.teardown();
@@ -14,8 +14,13 @@
 
 use std::fmt::{self, Display, Formatter};
 
+/// Errors are reported through two channels. Errors of a query, e.g. a table does not exist
+/// or a value violates a constraint, are sent to the client as `QueryError` and the session
+/// continues to serve next queries. `SystemError` is returned as `Err` of `SystemResult` when
+/// the database itself fails, e.g. the storage can't be read or the catalog is corrupted.
 pub type SystemResult<T> = std::result::Result<T, SystemError>;
 
+/// failure of the database itself, its `kind` tells whether an operation could be retried
 #[derive(Debug)]
 pub struct SystemError {
     message: String,
@@ -71,6 +76,36 @@ impl SystemError {
             kind: SystemErrorKind::Io(io_error),
         }
     }
+
+    pub fn catalog_corruption(message: String) -> SystemError {
+        SystemError {
+            message,
+            backtrace: backtrace::Backtrace::new(),
+            kind: SystemErrorKind::CatalogCorruption,
+        }
+    }
+
+    pub fn data_corruption(message: String) -> SystemError {
+        SystemError {
+            message,
+            backtrace: backtrace::Backtrace::new(),
+            kind: SystemErrorKind::DataCorruption,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+
+    pub fn kind(&self) -> &SystemErrorKind {
+        &self.kind
+    }
+
+    /// only failures of the storage I/O could disappear when an operation is repeated,
+    /// other failures need the database to be fixed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.kind, SystemErrorKind::Io(_))
+    }
 }
 
 impl PartialEq for SystemError {
@@ -81,9 +116,17 @@ impl PartialEq for SystemError {
 
 #[derive(Debug)]
 pub enum SystemErrorKind {
+    /// the database could not continue to work, e.g. its files were created by an unknown version
     Unrecoverable,
+    /// invariant that the engine relies on does not hold
     RuntimeCheckFailure,
+    /// the engine skipped a check that it has to do before accessing the storage
     SqlEngineBug,
+    /// definitions of schemas and tables are missing or inconsistent with each other
+    CatalogCorruption,
+    /// stored rows do not match their checksums
+    DataCorruption,
+    /// reading or writing of the storage failed
     Io(std::io::Error),
 }
 
@@ -114,7 +157,43 @@ impl PartialEq for SystemErrorKind {
             (SystemErrorKind::Io(_), SystemErrorKind::Io(_)) => true,
             (SystemErrorKind::Unrecoverable, SystemErrorKind::Unrecoverable) => true,
             (SystemErrorKind::RuntimeCheckFailure, SystemErrorKind::RuntimeCheckFailure) => true,
+            (SystemErrorKind::SqlEngineBug, SystemErrorKind::SqlEngineBug) => true,
+            (SystemErrorKind::CatalogCorruption, SystemErrorKind::CatalogCorruption) => true,
+            (SystemErrorKind::DataCorruption, SystemErrorKind::DataCorruption) => true,
             _ => false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn io_errors_are_retryable() {
+        assert!(SystemError::io(io::Error::new(io::ErrorKind::Interrupted, "interrupted")).is_retryable());
+    }
+
+    #[test]
+    fn catalog_corruption_is_not_retryable() {
+        let error = SystemError::catalog_corruption("catalog \"catalog_name\" does not exist".to_owned());
+
+        assert!(!error.is_retryable());
+        assert_eq!(error.kind(), &SystemErrorKind::CatalogCorruption);
+        assert_eq!(error.message(), "catalog \"catalog_name\" does not exist");
+    }
+
+    #[test]
+    fn data_corruption_is_not_retryable() {
+        let error = SystemError::data_corruption("row does not match its checksum".to_owned());
+
+        assert!(!error.is_retryable());
+        assert_eq!(error.kind(), &SystemErrorKind::DataCorruption);
+    }
+
+    #[test]
+    fn bugs_in_sql_engine_are_not_retryable() {
+        assert!(!SystemError::bug_in_sql_engine(Operation::Access, Object::Schema("schema_name")).is_retryable());
+    }
+}
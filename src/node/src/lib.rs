@@ -17,4 +17,6 @@ extern crate kernel;
 extern crate log;
 extern crate protocol;
 
+pub mod metrics;
 pub mod node;
+pub mod pool;
@@ -0,0 +1,362 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::{
+    results::{Description, QueryEvent, QueryResult, SelectedRow},
+    Sender,
+};
+use std::{
+    fs, io,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// Counters of the server activity exposed in Prometheus text format
+#[derive(Default)]
+pub struct MetricsCollector {
+    queries_executed: AtomicU64,
+    queries_failed: AtomicU64,
+    rows_inserted: AtomicU64,
+    rows_deleted: AtomicU64,
+    rows_updated: AtomicU64,
+    rows_selected: AtomicU64,
+    active_connections: AtomicU64,
+    storage_path: Option<PathBuf>,
+}
+
+impl MetricsCollector {
+    /// `storage_path` is the directory of the persistent storage, storage size is 0 when it is `None`
+    pub fn new(storage_path: Option<PathBuf>) -> MetricsCollector {
+        MetricsCollector {
+            storage_path,
+            ..MetricsCollector::default()
+        }
+    }
+
+    pub fn record(&self, query_result: &QueryResult) {
+        let (counter, increment) = match query_result {
+            Ok(QueryEvent::QueryComplete) => (&self.queries_executed, 1),
+            Err(_) => (&self.queries_failed, 1),
+            Ok(QueryEvent::RecordsInserted(records)) => (&self.rows_inserted, *records),
+            Ok(QueryEvent::RecordsDeleted(records)) => (&self.rows_deleted, *records),
+            Ok(QueryEvent::RecordsDeletedReturning((_, rows))) => (&self.rows_deleted, rows.len()),
+            Ok(QueryEvent::RecordsUpdated(records)) => (&self.rows_updated, *records),
+            Ok(QueryEvent::RecordsSelected((_, rows))) => (&self.rows_selected, rows.len()),
+            Ok(_) => return,
+        };
+        counter.fetch_add(increment as u64, Ordering::SeqCst);
+    }
+
+    /// the connection is counted as active until the returned value is dropped
+    pub fn open_connection(self: &Arc<Self>) -> ActiveConnection {
+        self.active_connections.fetch_add(1, Ordering::SeqCst);
+        ActiveConnection { metrics: self.clone() }
+    }
+
+    pub fn render(&self) -> String {
+        let storage_size = self.storage_path.as_deref().map_or(0, directory_size);
+        let metrics = [
+            (
+                "queries_executed_total",
+                "counter",
+                "Number of executed queries",
+                self.queries_executed.load(Ordering::SeqCst),
+            ),
+            (
+                "queries_failed_total",
+                "counter",
+                "Number of queries completed with an error",
+                self.queries_failed.load(Ordering::SeqCst),
+            ),
+            (
+                "rows_inserted_total",
+                "counter",
+                "Number of inserted rows",
+                self.rows_inserted.load(Ordering::SeqCst),
+            ),
+            (
+                "rows_deleted_total",
+                "counter",
+                "Number of deleted rows",
+                self.rows_deleted.load(Ordering::SeqCst),
+            ),
+            (
+                "rows_updated_total",
+                "counter",
+                "Number of updated rows",
+                self.rows_updated.load(Ordering::SeqCst),
+            ),
+            (
+                "rows_selected_total",
+                "counter",
+                "Number of rows sent to clients",
+                self.rows_selected.load(Ordering::SeqCst),
+            ),
+            (
+                "active_connections",
+                "gauge",
+                "Number of connected clients",
+                self.active_connections.load(Ordering::SeqCst),
+            ),
+            (
+                "storage_file_size_bytes",
+                "gauge",
+                "Size of the storage files",
+                storage_size,
+            ),
+        ];
+        metrics
+            .iter()
+            .map(|(name, kind, help, value)| {
+                format!(
+                    "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}\n",
+                    name = name,
+                    help = help,
+                    kind = kind,
+                    value = value
+                )
+            })
+            .collect()
+    }
+}
+
+pub struct ActiveConnection {
+    metrics: Arc<MetricsCollector>,
+}
+
+impl Drop for ActiveConnection {
+    fn drop(&mut self) {
+        self.metrics.active_connections.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn directory_size(path: &Path) -> u64 {
+    match fs::read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+                Ok(metadata) => metadata.len(),
+                Err(_) => 0,
+            })
+            .sum(),
+        Err(_) => 0,
+    }
+}
+
+/// Records results of the queries before sending them to the client
+pub struct MetricsSender {
+    sender: Arc<dyn Sender>,
+    metrics: Arc<MetricsCollector>,
+}
+
+impl MetricsSender {
+    pub fn new(sender: Arc<dyn Sender>, metrics: Arc<MetricsCollector>) -> MetricsSender {
+        MetricsSender { sender, metrics }
+    }
+}
+
+impl Sender for MetricsSender {
+    fn flush(&self) -> io::Result<()> {
+        self.sender.flush()
+    }
+
+    fn send(&self, query_result: QueryResult) -> io::Result<()> {
+        self.metrics.record(&query_result);
+        self.sender.send(query_result)
+    }
+
+    fn send_rows(&self, description: Description, rows: &mut dyn Iterator<Item = SelectedRow>) -> io::Result<()> {
+        let mut selected = 0;
+        let mut counted = rows.inspect(|row| match row {
+            Ok(_) => selected += 1,
+            Err(error) => self.metrics.record(&Err(error.clone())),
+        });
+        let result = self.sender.send_rows(description, &mut counted);
+        self.metrics.rows_selected.fetch_add(selected, Ordering::SeqCst);
+        result
+    }
+}
+
+/// Serves `GET /metrics` on its own thread, so scraping does not wait for queries executed by the engine
+pub fn serve(listener: TcpListener, metrics: Arc<MetricsCollector>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(error) = respond(stream, &metrics) {
+                        log::error!("metrics request error: {:?}", error);
+                    }
+                }
+                Err(error) => log::error!("metrics connection error: {:?}", error),
+            }
+        }
+    })
+}
+
+fn respond(stream: TcpStream, metrics: &MetricsCollector) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut stream = stream;
+    match request_line.split_whitespace().take(2).collect::<Vec<_>>().as_slice() {
+        ["GET", "/metrics"] => {
+            let body = metrics.render();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        }
+        _ => write!(
+            stream,
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_manager::DataManager;
+    use io::Read;
+    use sql_engine::QueryExecutor;
+
+    struct Discard;
+
+    impl Sender for Discard {
+        fn flush(&self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn send(&self, _query_result: QueryResult) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn scrape(address: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).expect("to connect");
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).expect("to send request");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("to read response");
+        response
+    }
+
+    #[test]
+    fn counts_executed_queries() {
+        let metrics = Arc::new(MetricsCollector::new(None));
+        let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+        let sender = Arc::new(MetricsSender::new(Arc::new(Discard), metrics.clone()));
+        let mut executor = QueryExecutor::new(storage, sender);
+        executor
+            .execute("create schema schema_name;")
+            .expect("no system errors");
+        executor
+            .execute("create table schema_name.table_name (column_test smallint);")
+            .expect("no system errors");
+        executor
+            .execute("insert into schema_name.table_name values (1), (2), (3);")
+            .expect("no system errors");
+        executor
+            .execute("update schema_name.table_name set column_test = 4 where column_test = 1;")
+            .expect("no system errors");
+        executor
+            .execute("delete from schema_name.table_name where column_test = 2;")
+            .expect("no system errors");
+        executor
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+        executor
+            .execute("select * from schema_name.non_existent;")
+            .expect("no system errors");
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("\nqueries_executed_total 7\n"), "{}", rendered);
+        assert!(rendered.contains("\nqueries_failed_total 1\n"), "{}", rendered);
+        assert!(rendered.contains("\nrows_inserted_total 3\n"), "{}", rendered);
+        assert!(rendered.contains("\nrows_updated_total 1\n"), "{}", rendered);
+        assert!(rendered.contains("\nrows_deleted_total 1\n"), "{}", rendered);
+        assert!(rendered.contains("\nrows_selected_total 2\n"), "{}", rendered);
+        assert!(rendered.contains("\nstorage_file_size_bytes 0\n"), "{}", rendered);
+    }
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let metrics = Arc::new(MetricsCollector::new(None));
+        let threads = (0..8)
+            .map(|_| {
+                let metrics = metrics.clone();
+                thread::spawn(move || {
+                    (0..1000)
+                        .map(|_| {
+                            metrics.record(&Ok(QueryEvent::RecordsInserted(1)));
+                            metrics.open_connection()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut connections = vec![];
+        for thread in threads {
+            connections.extend(thread.join().expect("to finish"));
+        }
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("\nrows_inserted_total 8000\n"), "{}", rendered);
+        assert!(rendered.contains("\nactive_connections 8000\n"), "{}", rendered);
+
+        connections.truncate(10);
+        assert!(metrics.render().contains("\nactive_connections 10\n"));
+    }
+
+    #[test]
+    fn endpoint_is_available_under_load() {
+        let metrics = Arc::new(MetricsCollector::new(None));
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("to bind");
+        let address = listener.local_addr().expect("to get address");
+        serve(listener, metrics.clone());
+
+        let load = {
+            let metrics = metrics.clone();
+            thread::spawn(move || {
+                for _ in 0..100_000 {
+                    metrics.record(&Ok(QueryEvent::QueryComplete));
+                }
+            })
+        };
+        let response = scrape(address, "/metrics");
+        load.join().expect("to finish");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(
+            response.contains("# TYPE queries_executed_total counter\n"),
+            "{}",
+            response
+        );
+        assert!(scrape(address, "/metrics").contains("\nqueries_executed_total 100000\n"));
+        assert!(scrape(address, "/other").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
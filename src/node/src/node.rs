@@ -12,40 +12,80 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::{
+    metrics::{self, MetricsCollector, MetricsSender},
+    pool::ConnectionPool,
+};
 use async_dup::Arc as AsyncArc;
 use async_io::Async;
-use data_manager::DataManager;
-use protocol::{Command, ProtocolConfiguration, Receiver};
-use sql_engine::QueryExecutor;
+use data_manager::{DataManager, StorageBackend};
+use protocol::{
+    auth::AuthConfig, results::QueryError, Command, ProtocolConfiguration, Receiver, Sender, ServerTlsConfig,
+};
+use sql_engine::{Notifications, QueryCache, QueryExecutor};
 use std::{
     env,
-    net::TcpListener,
+    net::{SocketAddr, TcpListener},
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicU8, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 const PORT: u16 = 5432;
+const METRICS_PORT: u16 = 9187;
 const HOST: [u8; 4] = [0, 0, 0, 0];
+const MAX_CONNECTIONS: usize = 100;
+const CONNECTION_TIMEOUT_MILLIS: u64 = 30_000;
+const QUERY_CACHE_TTL_MILLIS: u64 = 60_000;
+// queries are not canceled by default
+const QUERY_TIMEOUT_MILLIS: u64 = 0;
 
 pub const RUNNING: u8 = 0;
 pub const STOPPED: u8 = 1;
 
 pub fn start() {
-    let persistent = env::var("PERSISTENT").is_ok();
-    let root_path = env::var("ROOT_PATH").map(PathBuf::from).unwrap_or_default();
+    let backend = if env::var("PERSISTENT").is_ok() {
+        let root_path = env::var("ROOT_PATH").map(PathBuf::from).unwrap_or_default();
+        StorageBackend::Persistent(root_path.join("root_directory"))
+    } else {
+        StorageBackend::InMemory
+    };
+    let metrics = Arc::new(MetricsCollector::new(match &backend {
+        StorageBackend::Persistent(path) => Some(path.clone()),
+        StorageBackend::InMemory => None,
+    }));
+    let metrics_port = env::var("METRICS_PORT")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(METRICS_PORT);
+    metrics::serve(
+        TcpListener::bind(SocketAddr::from((HOST, metrics_port))).expect("OK"),
+        metrics.clone(),
+    );
     smol::block_on(async {
-        let storage = if persistent {
-            Arc::new(DataManager::persistent(root_path.join("root_directory")).unwrap())
-        } else {
-            Arc::new(DataManager::in_memory().unwrap())
-        };
+        let storage = Arc::new(DataManager::new(backend).unwrap());
+        // when the server has an owner only its sessions act as a superuser and other users have privileges
+        // that were granted to them, otherwise users that were not created by `CREATE USER` act as a superuser
+        let owner = env::var("OWNER").ok();
+        if let Some(owner) = &owner {
+            if !storage.user_exists(owner) {
+                storage.create_user(owner, true);
+            }
+        }
+        let check_unknown_users = owner.is_some();
         let listener = Async::<TcpListener>::bind((HOST, PORT)).expect("OK");
 
         let state = Arc::new(AtomicU8::new(RUNNING));
         let config = protocol_configuration();
+        let pool = connection_pool();
+        let cache = query_cache();
+        let notifications = Arc::new(Notifications::default());
+        // server files could be copied into tables only from this directory
+        let copy_directory = env::var("COPY_DIRECTORY").ok().map(|_| file_path("COPY_DIRECTORY"));
+        let query_timeout = query_timeout();
 
         while let Ok((tcp_stream, address)) = listener.accept().await {
             let tcp_stream = AsyncArc::new(tcp_stream);
@@ -58,12 +98,44 @@ pub fn start() {
                 }
                 let state = state.clone();
                 let storage = storage.clone();
-                let sender = Arc::new(sender);
-                let s = sender.clone();
-                let mut query_executor = QueryExecutor::new(storage.clone(), s);
-                log::debug!("ready to handle query");
+                let pool = pool.clone();
+                let metrics = metrics.clone();
+                let cache = cache.clone();
+                let notifications = notifications.clone();
+                let copy_directory = copy_directory.clone();
+                let sender = Arc::new(MetricsSender::new(Arc::new(sender), metrics.clone()));
 
                 smol::spawn(async move {
+                    // the slot is released when the connection is closed and the task is finished
+                    let _connection = match pool.acquire().await {
+                        Some(connection) => connection,
+                        None => {
+                            sender
+                                .send(Err(QueryError::too_many_connections()))
+                                .expect("To Send Error to Client");
+                            if let Err(error) = sender.flush() {
+                                log::error!("Flush error: {:?}", error);
+                            }
+                            return;
+                        }
+                    };
+                    let _active = metrics.open_connection();
+                    let mut query_executor = QueryExecutor::new(storage.clone(), sender.clone());
+                    if let Some(cache) = cache {
+                        query_executor.set_query_cache(cache);
+                    }
+                    query_executor.set_notifications(notifications);
+                    if let Some(directory) = copy_directory {
+                        query_executor.set_copy_directory(directory);
+                    }
+                    query_executor.set_query_timeout(query_timeout);
+                    if let Some((_, user)) = receiver.properties().1.iter().find(|(key, _)| key == "user") {
+                        if check_unknown_users || storage.user_exists(user) {
+                            query_executor.set_session_user(user);
+                        }
+                    }
+                    log::debug!("ready to handle query");
+
                     loop {
                         match receiver.receive().await {
                             Err(e) => {
@@ -76,62 +148,11 @@ pub fn start() {
                                 state.store(STOPPED, Ordering::SeqCst);
                                 return;
                             }
-                            Ok(Ok(Command::Bind {
-                                portal_name,
-                                statement_name,
-                                param_formats,
-                                raw_params,
-                                result_formats,
-                            })) => {
-                                match query_executor.bind_prepared_statement_to_portal(
-                                    portal_name.as_str(),
-                                    statement_name.as_str(),
-                                    param_formats.as_ref(),
-                                    raw_params.as_ref(),
-                                    result_formats.as_ref(),
-                                ) {
-                                    Ok(()) => {}
-                                    Err(error) => log::error!("{:?}", error),
-                                }
-                            }
-                            Ok(Ok(Command::Continue)) => {}
-                            Ok(Ok(Command::DescribeStatement { name })) => {
-                                match query_executor.describe_prepared_statement(name.as_str()) {
-                                    Ok(()) => {}
-                                    Err(error) => log::error!("{:?}", error),
-                                }
-                            }
-                            Ok(Ok(Command::Execute { portal_name, max_rows })) => {
-                                match query_executor.execute_portal(portal_name.as_str(), max_rows) {
-                                    Ok(()) => {}
-                                    Err(error) => log::error!("{:?}", error),
-                                }
-                            }
-                            Ok(Ok(Command::Flush)) => query_executor.flush(),
-                            Ok(Ok(Command::Parse {
-                                statement_name,
-                                sql,
-                                param_types,
-                            })) => {
-                                match query_executor.parse_prepared_statement(
-                                    statement_name.as_str(),
-                                    sql.as_str(),
-                                    param_types.as_ref(),
-                                ) {
-                                    Ok(()) => {}
-                                    Err(error) => log::error!("{:?}", error),
-                                }
-                            }
-                            Ok(Ok(Command::Query { sql })) => match query_executor.execute(sql.as_str()) {
-                                Ok(()) => {
-                                    query_executor.flush();
-                                }
-                                Err(error) => log::error!("{:?}", error),
-                            },
                             Ok(Ok(Command::Terminate)) => {
                                 log::debug!("Closing connection with client");
                                 break;
                             }
+                            Ok(Ok(command)) => query_executor = handle_command(query_executor, command).await,
                         }
                     }
                 })
@@ -141,8 +162,83 @@ pub fn start() {
     });
 }
 
-fn pfx_certificate_path() -> PathBuf {
-    let file = env::var("PFX_CERTIFICATE_FILE").unwrap();
+/// commands are executed on a thread pool, queries could wait for locks that other sessions hold
+/// and the executor has to keep serving their connections meanwhile
+async fn handle_command(mut query_executor: QueryExecutor, command: Command) -> QueryExecutor {
+    smol::unblock(move || {
+        match command {
+            Command::Bind {
+                portal_name,
+                statement_name,
+                param_formats,
+                raw_params,
+                result_formats,
+            } => {
+                match query_executor.bind_prepared_statement_to_portal(
+                    portal_name.as_str(),
+                    statement_name.as_str(),
+                    param_formats.as_ref(),
+                    raw_params.as_ref(),
+                    result_formats.as_ref(),
+                ) {
+                    Ok(()) => {}
+                    Err(error) => log::error!("{:?}", error),
+                }
+            }
+            Command::Continue => {}
+            Command::CopyData { data } => query_executor.copy_data(data.as_ref()),
+            Command::CopyDone => match query_executor.copy_done() {
+                Ok(()) => {
+                    query_executor.flush();
+                }
+                Err(error) => log::error!("{:?}", error),
+            },
+            Command::CopyFail { message } => match query_executor.copy_fail(message.as_str()) {
+                Ok(()) => {
+                    query_executor.flush();
+                }
+                Err(error) => log::error!("{:?}", error),
+            },
+            Command::DescribeStatement { name } => match query_executor.describe_prepared_statement(name.as_str()) {
+                Ok(()) => {}
+                Err(error) => log::error!("{:?}", error),
+            },
+            Command::Execute { portal_name, max_rows } => {
+                match query_executor.execute_portal(portal_name.as_str(), max_rows) {
+                    Ok(()) => {}
+                    Err(error) => log::error!("{:?}", error),
+                }
+            }
+            Command::Flush => query_executor.flush(),
+            Command::Parse {
+                statement_name,
+                sql,
+                param_types,
+            } => {
+                match query_executor.parse_prepared_statement(
+                    statement_name.as_str(),
+                    sql.as_str(),
+                    param_types.as_ref(),
+                ) {
+                    Ok(()) => {}
+                    Err(error) => log::error!("{:?}", error),
+                }
+            }
+            Command::Query { sql } => match query_executor.execute_batch(sql.as_str()) {
+                Ok(()) => {
+                    query_executor.flush();
+                }
+                Err(error) => log::error!("{:?}", error),
+            },
+            Command::Terminate => {}
+        }
+        query_executor
+    })
+    .await
+}
+
+fn file_path(variable: &str) -> PathBuf {
+    let file = env::var(variable).unwrap();
     let path = Path::new(&file);
     if path.is_absolute() {
         return path.to_path_buf();
@@ -152,16 +248,119 @@ fn pfx_certificate_path() -> PathBuf {
     current_dir.as_path().join(path)
 }
 
-fn pfx_certificate_password() -> String {
-    env::var("PFX_CERTIFICATE_PASSWORD").unwrap()
+fn connection_pool() -> ConnectionPool {
+    let max_connections = env::var("MAX_CONNECTIONS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(MAX_CONNECTIONS);
+    let timeout = env::var("CONNECTION_TIMEOUT_MILLIS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(CONNECTION_TIMEOUT_MILLIS);
+    ConnectionPool::new(max_connections, Duration::from_millis(timeout))
+}
+
+fn query_timeout() -> Duration {
+    let timeout = env::var("QUERY_TIMEOUT_MILLIS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(QUERY_TIMEOUT_MILLIS);
+    Duration::from_millis(timeout)
+}
+
+// results of queries are cached only if the size of the cache is set
+fn query_cache() -> Option<Arc<QueryCache>> {
+    let cache_size_bytes = env::var("QUERY_CACHE_SIZE_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())?;
+    let ttl = env::var("QUERY_CACHE_TTL_MILLIS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(QUERY_CACHE_TTL_MILLIS);
+    Some(Arc::new(QueryCache::new(cache_size_bytes, Duration::from_millis(ttl))))
 }
 
 fn protocol_configuration() -> ProtocolConfiguration {
-    match env::var("SECURE") {
+    let config = match env::var("SECURE") {
         Ok(s) => match s.to_lowercase().as_str() {
-            "ssl_only" => ProtocolConfiguration::with_ssl(pfx_certificate_path(), pfx_certificate_password()),
+            "ssl_only" => ProtocolConfiguration::with_ssl(ServerTlsConfig::new(
+                file_path("CERTIFICATE_FILE"),
+                file_path("PRIVATE_KEY_FILE"),
+            )),
             _ => ProtocolConfiguration::none(),
         },
         _ => ProtocolConfiguration::none(),
+    };
+    match env::var("AUTH_CONFIG_FILE") {
+        Ok(_) => config.with_auth(AuthConfig::from_file(&file_path("AUTH_CONFIG_FILE")).expect("auth config loaded")),
+        Err(_) => config,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::results::{QueryEvent, QueryResult};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Collector(Mutex<Vec<QueryResult>>);
+
+    impl Sender for Collector {
+        fn flush(&self) -> std::io::Result<()> {
+            Ok(())
+        }
+
+        fn send(&self, query_result: QueryResult) -> std::io::Result<()> {
+            self.0.lock().expect("to acquire lock").push(query_result);
+            Ok(())
+        }
+    }
+
+    fn query(sql: &str) -> Command {
+        Command::Query { sql: sql.to_owned() }
+    }
+
+    #[test]
+    fn session_waits_for_lock_without_blocking_other_sessions() {
+        let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+        let first_collector = Arc::new(Collector::default());
+        let second_collector = Arc::new(Collector::default());
+        let first = QueryExecutor::new(storage.clone(), first_collector.clone());
+        let second = QueryExecutor::new(storage, second_collector.clone());
+
+        let finished = smol::block_on(async {
+            let mut first = first;
+            for sql in &[
+                "create schema schema_name;",
+                "create table schema_name.table_name (column_test smallint);",
+                "begin;",
+                "lock table schema_name.table_name;",
+            ] {
+                first = smol::spawn(handle_command(first, query(sql))).await;
+            }
+            let waiting = smol::spawn(handle_command(
+                second,
+                query("insert into schema_name.table_name values (1);"),
+            ));
+            smol::Timer::after(Duration::from_millis(100)).await;
+            assert!(second_collector.0.lock().expect("to acquire lock").is_empty());
+
+            let committed = async {
+                let first = smol::spawn(handle_command(first, query("commit;"))).await;
+                Some((first, waiting.await))
+            };
+            let timeout = async {
+                smol::Timer::after(Duration::from_secs(5)).await;
+                None
+            };
+            smol::future::race(committed, timeout).await
+        });
+
+        assert!(finished.is_some(), "sessions are blocked by the waiting one");
+        assert_eq!(
+            second_collector.0.lock().expect("to acquire lock").clone(),
+            vec![Ok(QueryEvent::RecordsInserted(1)), Ok(QueryEvent::QueryComplete)]
+        );
     }
 }
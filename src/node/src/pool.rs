@@ -0,0 +1,204 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_channel::{Receiver, Sender};
+use async_io::Timer;
+use futures_lite::future::FutureExt;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Limits number of concurrently served client connections.
+/// A slot of a closed connection becomes idle and is reused by the next connection,
+/// when all slots are active a new connection waits until one is released or timeout expires
+#[derive(Clone)]
+pub struct ConnectionPool {
+    max_connections: usize,
+    timeout: Duration,
+    opened: Arc<AtomicUsize>,
+    idle_sender: Sender<usize>,
+    idle_receiver: Receiver<usize>,
+}
+
+impl ConnectionPool {
+    pub fn new(max_connections: usize, timeout: Duration) -> ConnectionPool {
+        let (idle_sender, idle_receiver) = async_channel::unbounded();
+        ConnectionPool {
+            max_connections,
+            timeout,
+            opened: Arc::new(AtomicUsize::new(0)),
+            idle_sender,
+            idle_receiver,
+        }
+    }
+
+    /// returns `None` if no slot was released during the timeout
+    pub async fn acquire(&self) -> Option<Connection> {
+        let slot = match self.idle_receiver.try_recv() {
+            Ok(slot) => Some(slot),
+            Err(_) => match self.open_slot() {
+                Some(slot) => Some(slot),
+                None => {
+                    log::debug!("all {} connection slots are active, waiting", self.max_connections);
+                    let timeout = self.timeout;
+                    async { self.idle_receiver.recv().await.ok() }
+                        .or(async {
+                            Timer::after(timeout).await;
+                            None
+                        })
+                        .await
+                }
+            },
+        };
+
+        match slot {
+            Some(slot) => {
+                let connection = Connection {
+                    slot,
+                    pool: self.clone(),
+                };
+                self.log_metrics();
+                Some(connection)
+            }
+            None => {
+                log::warn!("no connection slot was released within {:?}", self.timeout);
+                None
+            }
+        }
+    }
+
+    pub fn active(&self) -> usize {
+        self.opened.load(Ordering::SeqCst) - self.idle()
+    }
+
+    pub fn idle(&self) -> usize {
+        self.idle_receiver.len()
+    }
+
+    fn open_slot(&self) -> Option<usize> {
+        let max_connections = self.max_connections;
+        self.opened
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |opened| {
+                if opened < max_connections {
+                    Some(opened + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()
+    }
+
+    fn release(&self, slot: usize) {
+        self.idle_sender
+            .try_send(slot)
+            .expect("idle slots channel is unbounded and never closed");
+        self.log_metrics();
+    }
+
+    fn log_metrics(&self) {
+        log::info!("connections active: {}, idle: {}", self.active(), self.idle());
+    }
+}
+
+/// Slot of the pool occupied by a client connection, it is released when dropped
+pub struct Connection {
+    slot: usize,
+    pool: ConnectionPool,
+}
+
+impl Connection {
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.pool.release(self.slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future;
+
+    const TIMEOUT: Duration = Duration::from_millis(50);
+
+    #[test]
+    fn third_connection_is_queued_until_slot_is_released() {
+        let pool = ConnectionPool::new(2, Duration::from_secs(5));
+        smol::block_on(async {
+            let first = pool.acquire().await.expect("first connection");
+            let _second = pool.acquire().await.expect("second connection");
+            let first_slot = first.slot();
+            assert_eq!(pool.active(), 2);
+
+            let (third, ()) = future::zip(pool.acquire(), async move {
+                Timer::after(TIMEOUT).await;
+                drop(first);
+            })
+            .await;
+
+            assert_eq!(third.map(|connection| connection.slot()), Some(first_slot));
+        });
+    }
+
+    #[test]
+    fn queued_connection_is_rejected_after_timeout() {
+        let pool = ConnectionPool::new(2, TIMEOUT);
+        smol::block_on(async {
+            let _first = pool.acquire().await.expect("first connection");
+            let _second = pool.acquire().await.expect("second connection");
+
+            assert!(pool.acquire().await.is_none());
+            assert_eq!(pool.active(), 2);
+        });
+    }
+
+    #[test]
+    fn idle_connection_is_reused() {
+        let pool = ConnectionPool::new(2, TIMEOUT);
+        smol::block_on(async {
+            let first = pool.acquire().await.expect("first connection");
+            let slot = first.slot();
+            drop(first);
+            assert_eq!(pool.idle(), 1);
+
+            let second = pool.acquire().await.expect("second connection");
+            assert_eq!(second.slot(), slot);
+            assert_eq!(pool.idle(), 0);
+            assert_eq!(pool.active(), 1);
+        });
+    }
+
+    #[test]
+    fn dropped_connection_frees_slot() {
+        let pool = ConnectionPool::new(1, TIMEOUT);
+        smol::block_on(async {
+            let connection = pool.acquire().await.expect("connection");
+            assert_eq!(pool.active(), 1);
+
+            drop(connection);
+            assert_eq!(pool.active(), 0);
+            assert_eq!(pool.idle(), 1);
+
+            assert!(pool.acquire().await.is_some());
+        });
+    }
+}
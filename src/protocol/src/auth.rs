@@ -0,0 +1,517 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hmac::{Hmac, Mac, NewMac};
+use rand::Rng;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, convert::TryFrom, fs, io, net::IpAddr, path::Path};
+
+const ALL: &str = "all";
+const SCRAM_ITERATIONS: u32 = 4096;
+const CLIENT_KEY: &[u8] = b"Client Key";
+const SERVER_KEY: &[u8] = b"Server Key";
+
+/// Name of the only `SASL` mechanism supported by server
+pub(crate) const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+/// Authentication method that a client has to pass to open a connection
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    /// Allows connection unconditionally
+    Trust,
+    /// Rejects connection unconditionally
+    Reject,
+    /// Client has to send password in clear-text form
+    Password,
+    /// Client has to send password encrypted via MD5 with random salt
+    Md5,
+    /// Client has to pass `SCRAM-SHA-256` challenge-response authentication
+    #[serde(rename = "scram-sha-256")]
+    ScramSha256,
+}
+
+/// `pg_hba.conf`-like authentication configuration.
+///
+/// Rules are checked in order, the first one that matches client address,
+/// database and user defines authentication method. A connection that does
+/// not match any rule is rejected.
+///
+/// Passwords of `users` are kept in plain text, thus the file has to be
+/// readable only by the server.
+///
+/// ```toml
+/// [[rules]]
+/// address = "127.0.0.1/32"
+/// database = "all"
+/// user = "all"
+/// method = "md5"
+///
+/// [users]
+/// postgres = "password"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct AuthConfig {
+    #[serde(default)]
+    rules: Vec<Rule>,
+    #[serde(default)]
+    users: HashMap<String, String>,
+}
+
+impl AuthConfig {
+    /// Loads configuration from `toml` file
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses configuration from `toml` formatted string
+    pub fn from_toml(content: &str) -> io::Result<Self> {
+        toml::from_str(content).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    /// Authentication method of the first rule that matches connection parameters
+    pub fn method(&self, address: IpAddr, database: &str, user: &str) -> Method {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(address, database, user))
+            .map(|rule| rule.method)
+            .unwrap_or(Method::Reject)
+    }
+
+    pub(crate) fn password(&self, user: &str) -> Option<&str> {
+        self.users.get(user).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Rule {
+    address: Address,
+    database: String,
+    user: String,
+    method: Method,
+}
+
+impl Rule {
+    fn matches(&self, address: IpAddr, database: &str, user: &str) -> bool {
+        self.address.contains(address)
+            && (self.database == ALL || self.database == database)
+            && (self.user == ALL || self.user == user)
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+enum Address {
+    All,
+    Range(IpAddr, u32),
+}
+
+impl Address {
+    fn contains(&self, address: IpAddr) -> bool {
+        let address = match address {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(address),
+            v4 => v4,
+        };
+        match (self, address) {
+            (Address::All, _) => true,
+            (Address::Range(IpAddr::V4(network), prefix), IpAddr::V4(address)) => {
+                let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+                u32::from(*network) & mask == u32::from(address) & mask
+            }
+            (Address::Range(IpAddr::V6(network), prefix), IpAddr::V6(address)) => {
+                let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+                u128::from(*network) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value == ALL {
+            return Ok(Address::All);
+        }
+        let invalid = || format!("invalid address \"{}\"", value);
+        let mut parts = value.splitn(2, '/');
+        let address = parts
+            .next()
+            .and_then(|address| address.parse::<IpAddr>().ok())
+            .ok_or_else(invalid)?;
+        let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = match parts.next() {
+            Some(prefix) => prefix
+                .parse::<u32>()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(invalid)?,
+            None => max_prefix,
+        };
+        Ok(Address::Range(address, prefix))
+    }
+}
+
+/// Value that client has to send as a response on `AuthenticationMD5Password`
+pub(crate) fn md5_password(user: &str, password: &str, salt: &[u8; 4]) -> String {
+    let mut salted = format!("{:x}", md5::compute(format!("{}{}", password, user))).into_bytes();
+    salted.extend_from_slice(salt);
+    format!("md5{:x}", md5::compute(salted))
+}
+
+/// Server side of `SCRAM-SHA-256` authentication exchange, see RFC 5802 and RFC 7677
+pub(crate) struct ScramSha256 {
+    salted_password: Vec<u8>,
+    salt: Vec<u8>,
+    iterations: u32,
+    nonce: String,
+    gs2_header: String,
+    client_first_bare: String,
+    server_first: String,
+}
+
+impl ScramSha256 {
+    pub(crate) fn new(password: &str) -> Self {
+        let mut rng = rand::thread_rng();
+        let salt = rng.gen::<[u8; 16]>();
+        let nonce = rng.gen::<[u8; 18]>();
+        Self::with(password, &salt, base64::encode(nonce), SCRAM_ITERATIONS)
+    }
+
+    fn with(password: &str, salt: &[u8], nonce: String, iterations: u32) -> Self {
+        let mut salted_password = vec![0; 32];
+        pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, iterations, &mut salted_password);
+        Self {
+            salted_password,
+            salt: salt.to_vec(),
+            iterations,
+            nonce,
+            gs2_header: String::new(),
+            client_first_bare: String::new(),
+            server_first: String::new(),
+        }
+    }
+
+    /// handles `client-first-message` and returns `server-first-message`
+    pub(crate) fn server_first(&mut self, client_first: &str) -> Option<String> {
+        // channel binding is not supported
+        if !client_first.starts_with("n,") && !client_first.starts_with("y,") {
+            return None;
+        }
+        let bare_start = client_first[2..].find(',')? + 3;
+        let client_first_bare = &client_first[bare_start..];
+        let client_nonce = attribute(client_first_bare, 'r')?;
+        self.nonce = format!("{}{}", client_nonce, self.nonce);
+        self.gs2_header = client_first[..bare_start].to_owned();
+        self.client_first_bare = client_first_bare.to_owned();
+        self.server_first = format!(
+            "r={},s={},i={}",
+            self.nonce,
+            base64::encode(&self.salt),
+            self.iterations
+        );
+        Some(self.server_first.clone())
+    }
+
+    /// verifies client proof from `client-final-message` and returns `server-final-message`
+    pub(crate) fn server_final(&self, client_final: &str) -> Option<String> {
+        let proof_start = client_final.rfind(",p=")?;
+        let without_proof = &client_final[..proof_start];
+        let proof = base64::decode(&client_final[proof_start + 3..]).ok()?;
+        if attribute(without_proof, 'c')? != base64::encode(&self.gs2_header)
+            || attribute(without_proof, 'r')? != self.nonce
+        {
+            return None;
+        }
+
+        let auth_message = format!("{},{},{}", self.client_first_bare, self.server_first, without_proof);
+        let stored_key = Sha256::digest(&hmac(&self.salted_password, CLIENT_KEY));
+        let client_signature = hmac(&stored_key, auth_message.as_bytes());
+        if proof.len() != client_signature.len() {
+            return None;
+        }
+        let client_key = proof
+            .iter()
+            .zip(client_signature.iter())
+            .map(|(proof, signature)| proof ^ signature)
+            .collect::<Vec<u8>>();
+        if !constant_time_eq(&Sha256::digest(&client_key), &stored_key) {
+            return None;
+        }
+
+        let server_key = hmac(&self.salted_password, SERVER_KEY);
+        Some(format!(
+            "v={}",
+            base64::encode(hmac(&server_key, auth_message.as_bytes()))
+        ))
+    }
+}
+
+/// compares secrets in time that does not depend on position of the first different byte
+pub(crate) fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right.iter())
+            .fold(0, |difference, (left, right)| difference | (left ^ right))
+            == 0
+}
+
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_varkey(key).expect("HMAC accepts key of any size");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn attribute(message: &str, name: char) -> Option<&str> {
+    message
+        .split(',')
+        .find(|part| part.len() > 1 && part.starts_with(name) && part[1..].starts_with('='))
+        .map(|part| &part[2..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    const LOCALHOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    #[test]
+    fn connection_is_rejected_without_rules() {
+        let config = AuthConfig::from_toml("").expect("config parsed");
+
+        assert_eq!(config.method(LOCALHOST, "database", "user"), Method::Reject);
+    }
+
+    #[test]
+    fn first_matched_rule_is_used() {
+        let config = AuthConfig::from_toml(
+            r#"
+            [[rules]]
+            address = "all"
+            database = "all"
+            user = "admin"
+            method = "scram-sha-256"
+
+            [[rules]]
+            address = "all"
+            database = "all"
+            user = "all"
+            method = "trust"
+            "#,
+        )
+        .expect("config parsed");
+
+        assert_eq!(config.method(LOCALHOST, "database", "admin"), Method::ScramSha256);
+        assert_eq!(config.method(LOCALHOST, "database", "user"), Method::Trust);
+    }
+
+    #[test]
+    fn specific_database() {
+        let config = AuthConfig::from_toml(
+            r#"
+            [[rules]]
+            address = "all"
+            database = "public_db"
+            user = "all"
+            method = "trust"
+            "#,
+        )
+        .expect("config parsed");
+
+        assert_eq!(config.method(LOCALHOST, "public_db", "user"), Method::Trust);
+        assert_eq!(config.method(LOCALHOST, "private_db", "user"), Method::Reject);
+    }
+
+    #[test]
+    fn ip_v4_range() {
+        let config = AuthConfig::from_toml(
+            r#"
+            [[rules]]
+            address = "192.168.0.0/16"
+            database = "all"
+            user = "all"
+            method = "md5"
+            "#,
+        )
+        .expect("config parsed");
+
+        assert_eq!(
+            config.method(IpAddr::V4(Ipv4Addr::new(192, 168, 10, 1)), "database", "user"),
+            Method::Md5
+        );
+        assert_eq!(
+            config.method(IpAddr::V4(Ipv4Addr::new(192, 169, 0, 1)), "database", "user"),
+            Method::Reject
+        );
+        assert_eq!(
+            config.method(
+                IpAddr::V6(Ipv4Addr::new(192, 168, 0, 1).to_ipv6_mapped()),
+                "database",
+                "user"
+            ),
+            Method::Md5
+        );
+    }
+
+    #[test]
+    fn ip_v6_address() {
+        let config = AuthConfig::from_toml(
+            r#"
+            [[rules]]
+            address = "::1"
+            database = "all"
+            user = "all"
+            method = "password"
+            "#,
+        )
+        .expect("config parsed");
+
+        assert_eq!(
+            config.method(IpAddr::V6(Ipv6Addr::LOCALHOST), "database", "user"),
+            Method::Password
+        );
+        assert_eq!(config.method(LOCALHOST, "database", "user"), Method::Reject);
+    }
+
+    #[test]
+    fn invalid_address() {
+        let config = AuthConfig::from_toml(
+            r#"
+            [[rules]]
+            address = "127.0.0.1/33"
+            database = "all"
+            user = "all"
+            method = "trust"
+            "#,
+        );
+
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn unknown_method() {
+        let config = AuthConfig::from_toml(
+            r#"
+            [[rules]]
+            address = "all"
+            database = "all"
+            user = "all"
+            method = "ident"
+            "#,
+        );
+
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn user_password() {
+        let config = AuthConfig::from_toml(
+            r#"
+            [users]
+            postgres = "secret"
+            "#,
+        )
+        .expect("config parsed");
+
+        assert_eq!(config.password("postgres"), Some("secret"));
+        assert_eq!(config.password("admin"), None);
+    }
+
+    #[test]
+    fn compare_secrets() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret!"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn md5_password_is_salted() {
+        assert_eq!(
+            md5_password("postgres", "password", &[1, 2, 3, 4]),
+            md5_password("postgres", "password", &[1, 2, 3, 4])
+        );
+        assert_ne!(
+            md5_password("postgres", "password", &[1, 2, 3, 4]),
+            md5_password("postgres", "password", &[4, 3, 2, 1])
+        );
+        assert!(md5_password("postgres", "password", &[1, 2, 3, 4]).starts_with("md5"));
+    }
+
+    // test vector from RFC 7677
+    fn rfc_scram() -> ScramSha256 {
+        ScramSha256::with(
+            "pencil",
+            &base64::decode("W22ZaJ0SNY7soEsUEjb6gQ==").unwrap(),
+            "%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0".to_owned(),
+            4096,
+        )
+    }
+
+    #[test]
+    fn scram_sha_256_exchange() {
+        let mut scram = rfc_scram();
+
+        assert_eq!(
+            scram.server_first("n,,n=user,r=rOprNGfwEbeRWgbNEkqO"),
+            Some("r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096".to_owned())
+        );
+        assert_eq!(
+            scram.server_final(
+                "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+            ),
+            Some("v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=".to_owned())
+        );
+    }
+
+    #[test]
+    fn scram_sha_256_invalid_proof() {
+        let mut scram = rfc_scram();
+
+        scram.server_first("n,,n=user,r=rOprNGfwEbeRWgbNEkqO");
+
+        assert_eq!(
+            scram.server_final(
+                "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=AAAAAapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn scram_sha_256_nonce_mismatch() {
+        let mut scram = rfc_scram();
+
+        scram.server_first("n,,n=user,r=rOprNGfwEbeRWgbNEkqO");
+
+        assert_eq!(
+            scram.server_final("c=biws,r=rOprNGfwEbeRWgbNEkqO,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="),
+            None
+        );
+    }
+
+    #[test]
+    fn scram_sha_256_channel_binding_is_not_supported() {
+        let mut scram = rfc_scram();
+
+        assert_eq!(
+            scram.server_first("p=tls-server-end-point,,n=user,r=rOprNGfwEbeRWgbNEkqO"),
+            None
+        );
+    }
+}
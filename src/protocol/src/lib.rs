@@ -17,23 +17,32 @@
 extern crate log;
 
 use crate::{
+    auth::{AuthConfig, Method, ScramSha256, SCRAM_SHA_256},
     messages::{BackendMessage, Encryption, FrontendMessage},
-    results::QueryResult,
+    results::{Description, QueryEvent, QueryResult, SelectedRow},
     sql_formats::PostgreSqlFormat,
     sql_types::PostgreSqlType,
 };
 use async_mutex::Mutex as AsyncMutex;
-use async_native_tls::TlsStream;
 use async_trait::async_trait;
-use blocking::Unblock;
 use byteorder::{ByteOrder, NetworkEndian};
 use futures_lite::{
     future::block_on,
     io::{self, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ErrorKind},
 };
+use futures_rustls::{
+    rustls::{
+        internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys},
+        NoClientAuth, ServerConfig,
+    },
+    server::TlsStream,
+    TlsAcceptor,
+};
 use itertools::Itertools;
+use rand::Rng;
 use std::{
     fs::File,
+    io::BufReader,
     net::SocketAddr,
     path::PathBuf,
     pin::Pin,
@@ -41,6 +50,8 @@ use std::{
     task::{Context, Poll},
 };
 
+/// Module contains `pg_hba.conf`-like client authentication configuration
+pub mod auth;
 /// Module contains backend messages that could be send by server implementation
 /// to a client
 pub mod messages;
@@ -92,6 +103,8 @@ pub enum Error {
     UnsupportedRequest,
     /// Indicates that during handshake client sent unrecognized protocol version
     UnrecognizedVersion,
+    /// Indicates that client connection is rejected or client failed authentication
+    AuthenticationFailed,
 }
 
 /// Result of handling incoming bytes from a client
@@ -114,6 +127,18 @@ pub enum Command {
     },
     /// Nothing needs to handle on client, just to receive next message
     Continue,
+    /// Client sends data of `COPY FROM STDIN`
+    CopyData {
+        /// Rows in text format, a row could be split between messages.
+        data: Vec<u8>,
+    },
+    /// Client sent all data of `COPY FROM STDIN`
+    CopyDone,
+    /// Client aborts `COPY FROM STDIN`
+    CopyFail {
+        /// The reason of the failure.
+        message: String,
+    },
     /// Client commands to describe a prepared statement
     DescribeStatement {
         /// The name of the prepared statement to describe.
@@ -180,22 +205,9 @@ where
 
         match decode_startup(message) {
             Ok(ClientHandshake::Startup(version, params)) => {
-                channel
-                    .write_all(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice())
-                    .await?;
-                let mut buffer = [0u8; 1];
-                let tag = channel.read_exact(&mut buffer).await.map(|_| buffer[0]);
-                log::debug!("client message response tag {:?}", tag);
-                log::debug!("waiting for authentication response");
-                let mut buffer = [0u8; 4];
-                let len = channel
-                    .read_exact(&mut buffer)
-                    .await
-                    .map(|_| NetworkEndian::read_u32(&buffer) as usize)?;
-                let len = len - 4;
-                let mut buffer = Vec::with_capacity(len);
-                buffer.resize(len, b'0');
-                let _message = channel.read_exact(&mut buffer).await.map(|_| buffer)?;
+                if let Err(error) = authenticate(&mut channel, address, &params, config).await? {
+                    return Ok(Err(error));
+                }
                 channel
                     .write_all(BackendMessage::AuthenticationOk.as_vec().as_slice())
                     .await?;
@@ -228,6 +240,8 @@ where
                 channel
                     .write_all(BackendMessage::ReadyForQuery.as_vec().as_slice())
                     .await?;
+                // `tls` stream buffers written data until it is flushed
+                channel.flush().await?;
 
                 let channel = Arc::new(AsyncMutex::new(channel));
                 return Ok(Ok((
@@ -239,7 +253,7 @@ where
                 channel = match channel {
                     Channel::Plain(mut channel) if config.ssl_support() => {
                         channel.write_all(Encryption::AcceptSsl.into()).await?;
-                        Channel::Secure(tls_channel(channel, config).await?)
+                        Channel::Secure(Box::new(tls_channel(channel, config).await?))
                     }
                     _ => {
                         channel.write_all(Encryption::RejectSsl.into()).await?;
@@ -253,17 +267,200 @@ where
     }
 }
 
+async fn authenticate<RW>(
+    channel: &mut Channel<RW>,
+    address: SocketAddr,
+    params: &[(String, String)],
+    config: &ProtocolConfiguration,
+) -> io::Result<Result<()>>
+where
+    RW: AsyncRead + AsyncWrite + Unpin,
+{
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let user = param("user").unwrap_or_default();
+    let database = param("database").unwrap_or(user);
+
+    let auth_config = match config.auth_config() {
+        Some(auth_config) => auth_config,
+        None => {
+            // without authentication config any password is accepted
+            channel
+                .write_all(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice())
+                .await?;
+            channel.flush().await?;
+            read_password(channel).await?;
+            return Ok(Ok(()));
+        }
+    };
+
+    let authenticated = match auth_config.method(address.ip(), database, user) {
+        Method::Trust => true,
+        Method::Reject => {
+            log::debug!("connection from {:?} is rejected", address);
+            let message = format!(
+                "pg_hba.conf rejects connection for host \"{}\", user \"{}\", database \"{}\"",
+                address.ip(),
+                user,
+                database
+            );
+            channel
+                .write_all(
+                    BackendMessage::ErrorResponse(Some("FATAL"), Some("28000"), Some(message), None, None)
+                        .as_vec()
+                        .as_slice(),
+                )
+                .await?;
+            channel.flush().await?;
+            return Ok(Err(Error::AuthenticationFailed));
+        }
+        Method::Password => {
+            channel
+                .write_all(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice())
+                .await?;
+            channel.flush().await?;
+            let password = read_password(channel).await?;
+            auth_config
+                .password(user)
+                .is_some_and(|expected| auth::constant_time_eq(expected.as_bytes(), c_string(&password)))
+        }
+        Method::Md5 => {
+            let salt = rand::thread_rng().gen::<[u8; 4]>();
+            channel
+                .write_all(BackendMessage::AuthenticationMD5Password(salt).as_vec().as_slice())
+                .await?;
+            channel.flush().await?;
+            let password = read_password(channel).await?;
+            auth_config.password(user).is_some_and(|expected| {
+                auth::constant_time_eq(
+                    auth::md5_password(user, expected, &salt).as_bytes(),
+                    c_string(&password),
+                )
+            })
+        }
+        Method::ScramSha256 => scram_sha_256(channel, auth_config.password(user)).await?,
+    };
+
+    if authenticated {
+        Ok(Ok(()))
+    } else {
+        log::debug!("user {:?} failed authentication", user);
+        channel
+            .write_all(
+                BackendMessage::ErrorResponse(
+                    Some("FATAL"),
+                    Some("28P01"),
+                    Some(format!("password authentication failed for user \"{}\"", user)),
+                    None,
+                    None,
+                )
+                .as_vec()
+                .as_slice(),
+            )
+            .await?;
+        channel.flush().await?;
+        Ok(Err(Error::AuthenticationFailed))
+    }
+}
+
+async fn scram_sha_256<RW>(channel: &mut Channel<RW>, password: Option<&str>) -> io::Result<bool>
+where
+    RW: AsyncRead + AsyncWrite + Unpin,
+{
+    channel
+        .write_all(
+            BackendMessage::AuthenticationSASL(vec![SCRAM_SHA_256])
+                .as_vec()
+                .as_slice(),
+        )
+        .await?;
+    channel.flush().await?;
+
+    // SASLInitialResponse contains mechanism name and length prefixed client-first-message
+    let initial_response = read_password(channel).await?;
+    let mechanism = c_string(&initial_response);
+    if mechanism != SCRAM_SHA_256.as_bytes() || initial_response.len() < mechanism.len() + 5 {
+        return Ok(false);
+    }
+    let client_first = &initial_response[mechanism.len() + 5..];
+
+    // unknown user still fails the exchange the same way as a wrong password
+    let mut scram = ScramSha256::new(password.unwrap_or_default());
+    let server_first = match std::str::from_utf8(client_first)
+        .ok()
+        .and_then(|message| scram.server_first(message))
+    {
+        Some(server_first) => server_first,
+        None => return Ok(false),
+    };
+    channel
+        .write_all(
+            BackendMessage::AuthenticationSASLContinue(server_first)
+                .as_vec()
+                .as_slice(),
+        )
+        .await?;
+    channel.flush().await?;
+
+    let client_final = read_password(channel).await?;
+    match std::str::from_utf8(&client_final)
+        .ok()
+        .and_then(|message| scram.server_final(message))
+    {
+        Some(server_final) if password.is_some() => {
+            channel
+                .write_all(
+                    BackendMessage::AuthenticationSASLFinal(server_final)
+                        .as_vec()
+                        .as_slice(),
+                )
+                .await?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// reads body of `PasswordMessage`, `SASLInitialResponse` or `SASLResponse`
+async fn read_password<RW>(channel: &mut Channel<RW>) -> io::Result<Vec<u8>>
+where
+    RW: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut buffer = [0u8; 1];
+    let tag = channel.read_exact(&mut buffer).await.map(|_| buffer[0]);
+    log::debug!("client message response tag {:?}", tag);
+    log::debug!("waiting for authentication response");
+    let mut buffer = [0u8; 4];
+    let len = channel
+        .read_exact(&mut buffer)
+        .await
+        .map(|_| NetworkEndian::read_u32(&buffer) as usize)?;
+    let len = len.saturating_sub(4);
+    let mut buffer = Vec::with_capacity(len);
+    buffer.resize(len, b'0');
+    channel.read_exact(&mut buffer).await.map(|_| buffer)
+}
+
+fn c_string(bytes: &[u8]) -> &[u8] {
+    bytes.split(|b| *b == 0).next().unwrap_or_default()
+}
+
 async fn tls_channel<RW>(tcp_channel: RW, config: &ProtocolConfiguration) -> io::Result<TlsStream<RW>>
 where
     RW: AsyncRead + AsyncWrite + Unpin,
 {
     match config.ssl_config() {
-        Some((path, password)) => {
-            match async_native_tls::accept(Unblock::new(File::open(path)?), password, tcp_channel).await {
-                Ok(socket) => Ok(socket),
-                Err(_err) => Err(io::Error::from(ErrorKind::ConnectionAborted)),
+        Some(tls_config) => match tls_config.acceptor()?.accept(tcp_channel).await {
+            Ok(socket) => Ok(socket),
+            Err(error) => {
+                log::error!("TLS handshake failed: {:?}", error);
+                Err(io::Error::from(ErrorKind::ConnectionAborted))
             }
-        }
+        },
         None => Err(io::Error::from(io::ErrorKind::ConnectionAborted)),
     }
 }
@@ -301,15 +498,14 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> RequestReceiver<RW> {
     pub(crate) fn new(properties: (Version, Params), channel: Arc<AsyncMutex<Channel<RW>>>) -> RequestReceiver<RW> {
         RequestReceiver { properties, channel }
     }
-
-    /// connection properties tuple
-    pub fn properties(&self) -> &(Version, Params) {
-        &(self.properties)
-    }
 }
 
 #[async_trait]
 impl<RW: AsyncRead + AsyncWrite + Unpin> Receiver for RequestReceiver<RW> {
+    fn properties(&self) -> &(Version, Params) {
+        &(self.properties)
+    }
+
     async fn receive(&mut self) -> io::Result<Result<Command>> {
         // Parses the one-byte tag.
         let mut buffer = [0u8; 1];
@@ -370,6 +566,9 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> Receiver for RequestReceiver<RW> {
                 param_types,
             })),
             FrontendMessage::Query { sql } => Ok(Ok(Command::Query { sql })),
+            FrontendMessage::CopyData(data) => Ok(Ok(Command::CopyData { data })),
+            FrontendMessage::CopyDone => Ok(Ok(Command::CopyDone)),
+            FrontendMessage::CopyFail { message } => Ok(Ok(Command::CopyFail { message })),
             FrontendMessage::Terminate => Ok(Ok(Command::Terminate)),
             _ => Ok(Ok(Command::Continue)),
         }
@@ -379,6 +578,9 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> Receiver for RequestReceiver<RW> {
 /// Trait to handle client to server commands for PostgreSQL Wire Protocol connection
 #[async_trait]
 pub trait Receiver: Send + Sync {
+    /// connection properties tuple
+    fn properties(&self) -> &(Version, Params);
+
     /// receives and decodes a command from remote client
     async fn receive(&mut self) -> io::Result<Result<Command>>;
 }
@@ -402,8 +604,21 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> ResponseSender<RW> {
     pub(crate) fn new(properties: (Version, Params), channel: Arc<AsyncMutex<Channel<RW>>>) -> ResponseSender<RW> {
         ResponseSender { properties, channel }
     }
+
+    fn write_message(&self, message: BackendMessage) {
+        log::debug!("{:?}", message);
+        block_on(async {
+            self.channel
+                .lock()
+                .await
+                .write_all(message.as_vec().as_slice())
+                .await
+                .expect("OK");
+        });
+    }
 }
 
+#[async_trait]
 impl<RW: AsyncRead + AsyncWrite + Unpin> Sender for ResponseSender<RW> {
     fn flush(&self) -> io::Result<()> {
         block_on(async {
@@ -414,23 +629,15 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> Sender for ResponseSender<RW> {
     }
 
     fn send(&self, query_result: QueryResult) -> io::Result<()> {
+        block_on(self.send_async(query_result))
+    }
+
+    async fn send_async(&self, query_result: QueryResult) -> io::Result<()> {
         log::debug!("Sending {:?}", query_result);
-        block_on(async {
-            match query_result {
-                Ok(event) => {
-                    let messages: Vec<BackendMessage> = event.into();
-                    for message in messages {
-                        log::debug!("{:?}", message);
-                        self.channel
-                            .lock()
-                            .await
-                            .write_all(message.as_vec().as_slice())
-                            .await
-                            .expect("OK");
-                    }
-                }
-                Err(error) => {
-                    let message: BackendMessage = error.into();
+        match query_result {
+            Ok(event) => {
+                let messages: Vec<BackendMessage> = event.into();
+                for message in messages {
                     log::debug!("{:?}", message);
                     self.channel
                         .lock()
@@ -440,14 +647,42 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> Sender for ResponseSender<RW> {
                         .expect("OK");
                 }
             }
-            log::debug!("end of the command is sent");
-        });
+            Err(error) => {
+                let message: BackendMessage = error.into();
+                log::debug!("{:?}", message);
+                self.channel
+                    .lock()
+                    .await
+                    .write_all(message.as_vec().as_slice())
+                    .await
+                    .expect("OK");
+            }
+        }
+        log::debug!("end of the command is sent");
+        Ok(())
+    }
+
+    fn send_rows(&self, description: Description, rows: &mut dyn Iterator<Item = SelectedRow>) -> io::Result<()> {
+        // the channel is not locked while the next row is produced, it could send a notice
+        self.write_message(results::row_description(description));
+        let mut len = 0;
+        for row in rows {
+            match row {
+                Ok(row) => {
+                    self.write_message(BackendMessage::DataRow(row));
+                    len += 1;
+                }
+                Err(error) => return self.send(Err(error)),
+            }
+        }
+        self.write_message(BackendMessage::CommandComplete(format!("SELECT {}", len)));
         Ok(())
     }
 }
 
 /// Trait to handle server to client query results for PostgreSQL Wire Protocol
 /// connection
+#[async_trait]
 pub trait Sender: Send + Sync {
     /// Flushes the output stream.
     fn flush(&self) -> io::Result<()>;
@@ -455,6 +690,26 @@ pub trait Sender: Send + Sync {
     /// Sends response messages to client. Most of the time it is a single
     /// message, select result one of the exceptional situation
     fn send(&self, query_result: QueryResult) -> io::Result<()>;
+
+    /// Sends response messages to client without blocking current thread.
+    /// By default it falls back to `send`
+    async fn send_async(&self, query_result: QueryResult) -> io::Result<()> {
+        self.send(query_result)
+    }
+
+    /// Sends rows of SELECT as they are produced, the first error is sent
+    /// instead of the command completion. By default rows are collected and
+    /// sent by `send` as a single result
+    fn send_rows(&self, description: Description, rows: &mut dyn Iterator<Item = SelectedRow>) -> io::Result<()> {
+        let mut records = vec![];
+        for row in rows {
+            match row {
+                Ok(row) => records.push(row),
+                Err(error) => return self.send(Err(error)),
+            }
+        }
+        self.send(Ok(QueryEvent::RecordsSelected((description, records))))
+    }
 }
 
 impl<RW: AsyncRead + AsyncWrite + Unpin> PartialEq for RequestReceiver<RW> {
@@ -465,7 +720,7 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> PartialEq for RequestReceiver<RW> {
 
 pub(crate) enum Channel<RW: AsyncRead + AsyncWrite + Unpin> {
     Plain(RW),
-    Secure(TlsStream<RW>),
+    Secure(Box<TlsStream<RW>>),
 }
 
 unsafe impl<RW: AsyncRead + AsyncWrite + Unpin> Send for Channel<RW> {}
@@ -475,7 +730,7 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> AsyncRead for Channel<RW> {
     fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
         match self.get_mut() {
             Channel::Plain(tcp) => Pin::new(tcp).poll_read(cx, buf),
-            Channel::Secure(tls) => Pin::new(tls).poll_read(cx, buf),
+            Channel::Secure(tls) => Pin::new(tls.as_mut()).poll_read(cx, buf),
         }
     }
 }
@@ -484,42 +739,96 @@ impl<RW: AsyncRead + AsyncWrite + Unpin> AsyncWrite for Channel<RW> {
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
         match self.get_mut() {
             Channel::Plain(tcp) => Pin::new(tcp).poll_write(cx, buf),
-            Channel::Secure(tls) => Pin::new(tls).poll_write(cx, buf),
+            Channel::Secure(tls) => Pin::new(tls.as_mut()).poll_write(cx, buf),
         }
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         match self.get_mut() {
             Channel::Plain(tcp) => Pin::new(tcp).poll_flush(cx),
-            Channel::Secure(tls) => Pin::new(tls).poll_flush(cx),
+            Channel::Secure(tls) => Pin::new(tls.as_mut()).poll_flush(cx),
         }
     }
 
     fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         match self.get_mut() {
             Channel::Plain(tcp) => Pin::new(tcp).poll_close(cx),
-            Channel::Secure(tls) => Pin::new(tls).poll_close(cx),
+            Channel::Secure(tls) => Pin::new(tls.as_mut()).poll_close(cx),
         }
     }
 }
 
+/// Paths to PEM encoded certificate chain and private key that server uses for `tls` connections
+#[derive(Debug, Clone)]
+pub struct ServerTlsConfig {
+    certificate: PathBuf,
+    private_key: PathBuf,
+}
+
+impl ServerTlsConfig {
+    /// Creates configuration from certificate and private key (PKCS8 or RSA) files
+    pub fn new(certificate: PathBuf, private_key: PathBuf) -> Self {
+        Self {
+            certificate,
+            private_key,
+        }
+    }
+
+    /// files are read for every connection, thus a replaced certificate is used without restart
+    fn acceptor(&self) -> io::Result<TlsAcceptor> {
+        let invalid_data = |message: &str| io::Error::new(ErrorKind::InvalidData, message);
+        let certificates = certs(&mut BufReader::new(File::open(&self.certificate)?))
+            .map_err(|()| invalid_data("certificate file can't be parsed"))?;
+        let mut keys = pkcs8_private_keys(&mut BufReader::new(File::open(&self.private_key)?))
+            .map_err(|()| invalid_data("private key file can't be parsed"))?;
+        if keys.is_empty() {
+            keys = rsa_private_keys(&mut BufReader::new(File::open(&self.private_key)?))
+                .map_err(|()| invalid_data("private key file can't be parsed"))?;
+        }
+        let key = match keys.pop() {
+            Some(key) => key,
+            None => return Err(invalid_data("private key file does not contain any key")),
+        };
+
+        let mut config = ServerConfig::new(NoClientAuth::new());
+        config
+            .set_single_cert(certificates, key)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
 /// Struct to configure possible secure providers for client-server communication
 /// PostgreSQL Wire Protocol supports `ssl`/`tls` and `gss` encryption
 pub struct ProtocolConfiguration {
-    ssl_conf: Option<(PathBuf, String)>,
+    ssl_conf: Option<ServerTlsConfig>,
+    auth_conf: Option<AuthConfig>,
 }
 
 #[allow(dead_code)]
 impl ProtocolConfiguration {
     /// Creates configuration that support neither `ssl` nor `gss` encryption
     pub fn none() -> Self {
-        Self { ssl_conf: None }
+        Self {
+            ssl_conf: None,
+            auth_conf: None,
+        }
     }
 
     /// Creates configuration that support only `ssl`
-    pub fn with_ssl(cert: PathBuf, password: String) -> Self {
+    pub fn with_ssl(tls_config: ServerTlsConfig) -> Self {
         Self {
-            ssl_conf: Some((cert, password)),
+            ssl_conf: Some(tls_config),
+            auth_conf: None,
+        }
+    }
+
+    /// Requires clients to authenticate according to `auth_config` rules,
+    /// otherwise any client password is accepted
+    pub fn with_auth(self, auth_config: AuthConfig) -> Self {
+        Self {
+            auth_conf: Some(auth_config),
+            ..self
         }
     }
 
@@ -528,11 +837,16 @@ impl ProtocolConfiguration {
         self.ssl_conf.is_some()
     }
 
-    /// cert file and its password
-    fn ssl_config(&self) -> Option<&(PathBuf, String)> {
+    /// certificate and private key files
+    fn ssl_config(&self) -> Option<&ServerTlsConfig> {
         self.ssl_conf.as_ref()
     }
 
+    /// client authentication rules
+    fn auth_config(&self) -> Option<&AuthConfig> {
+        self.auth_conf.as_ref()
+    }
+
     /// returns `true` if support `gss` encrypted connection
     fn gssenc_support(&self) -> bool {
         false
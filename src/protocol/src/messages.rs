@@ -22,8 +22,11 @@ const ERROR_RESPONSE: u8 = b'E';
 const SEVERITY: u8 = b'S';
 const CODE: u8 = b'C';
 const MESSAGE: u8 = b'M';
+const DETAIL: u8 = b'D';
+const HINT: u8 = b'H';
 const EMPTY_QUERY_RESPONSE: u8 = b'I';
 const NOTICE_RESPONSE: u8 = b'N';
+const NOTIFICATION_RESPONSE: u8 = b'A';
 const AUTHENTICATION: u8 = b'R';
 const PARAMETER_STATUS: u8 = b'S';
 const ROW_DESCRIPTION: u8 = b'T';
@@ -33,6 +36,10 @@ const NO_DATA: u8 = b'n';
 const PARSE_COMPLETE: u8 = b'1';
 const BIND_COMPLETE: u8 = b'2';
 const CLOSE_COMPLETE: u8 = b'3';
+const COPY_IN_RESPONSE: u8 = b'G';
+const COPY_OUT_RESPONSE: u8 = b'H';
+const COPY_DATA: u8 = b'd';
+const COPY_DONE: u8 = b'c';
 
 pub(crate) enum Encryption {
     AcceptSsl,
@@ -146,6 +153,24 @@ pub enum FrontendMessage {
         name: String,
     },
 
+    /// Data of `COPY FROM STDIN`, rows could be split between messages.
+    ///
+    /// This is sent as part of the copy-in flow.
+    CopyData(Vec<u8>),
+
+    /// All data of `COPY FROM STDIN` is sent.
+    ///
+    /// This is sent as part of the copy-in flow.
+    CopyDone,
+
+    /// `COPY FROM STDIN` has to be aborted.
+    ///
+    /// This is sent as part of the copy-in flow.
+    CopyFail {
+        /// The reason of the failure.
+        message: String,
+    },
+
     /// Terminate a connection.
     Terminate,
 }
@@ -173,6 +198,11 @@ impl FrontendMessage {
             b'P' => decode_parse(cursor),
             b'S' => decode_sync(cursor),
 
+            // Copy-in flow.
+            b'd' => decode_copy_data(cursor),
+            b'c' => decode_copy_done(cursor),
+            b'f' => decode_copy_fail(cursor),
+
             // Termination.
             b'X' => decode_terminate(cursor),
 
@@ -192,7 +222,12 @@ impl FrontendMessage {
 pub enum BackendMessage {
     /// A warning message has been issued. The frontend should display the message
     /// but continue listening for ReadyForQuery or ErrorResponse.
-    NoticeResponse,
+    /// Contains severity and message of the notice
+    NoticeResponse(String, String),
+    /// A notification of a channel that the frontend listens to. It could be sent
+    /// at any time, even when the frontend is not engaged in a query.
+    /// Contains process id of the notifying backend, channel name and payload
+    NotificationResponse(u32, String, String),
     /// The frontend must now send a PasswordMessage containing the password in
     /// clear-text form. If this is the correct password, the server responds
     /// with an AuthenticationOk, otherwise it responds with an ErrorResponse.
@@ -205,14 +240,23 @@ pub enum BackendMessage {
     /// can be computed in SQL as concat('md5', md5(concat(md5(concat(password,
     /// username)), random-salt))). (Keep in mind the md5() function returns its
     /// result as a hex string.)
-    #[allow(dead_code)]
-    AuthenticationMD5Password,
+    AuthenticationMD5Password([u8; 4]),
+    /// The frontend must now initiate a SASL negotiation, using one of the SASL
+    /// mechanisms listed in the message.
+    AuthenticationSASL(Vec<&'static str>),
+    /// This message contains challenge data for the previous step of SASL
+    /// negotiation.
+    AuthenticationSASLContinue(String),
+    /// SASL authentication has completed with additional mechanism-specific
+    /// data for the client.
+    AuthenticationSASLFinal(String),
     /// The authentication exchange is successfully completed.
     AuthenticationOk,
     /// Start-up is completed. The frontend can now issue commands.
     ReadyForQuery,
     /// One of the set of rows returned by a SELECT, FETCH, etc query.
-    DataRow(Vec<String>),
+    /// NULL value is `None`.
+    DataRow(Vec<Option<String>>),
     /// Indicates that rows are about to be returned in response to a SELECT, FETCH,
     /// etc query. The contents of this message describe the column layout of
     /// the rows. This will be followed by a DataRow message for each row being
@@ -223,9 +267,15 @@ pub enum BackendMessage {
     /// An empty query string was recognized.
     #[allow(dead_code)]
     EmptyQueryResponse,
-    /// An error has occurred. Contains (`Severity`, `Error Code`, `Error Message`)
-    /// all of them are optional
-    ErrorResponse(Option<&'static str>, Option<&'static str>, Option<String>),
+    /// An error has occurred. Contains (`Severity`, `Error Code`, `Error Message`,
+    /// `Detail`, `Hint`) all of them are optional
+    ErrorResponse(
+        Option<&'static str>,
+        Option<&'static str>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    ),
     /// This message informs the frontend about the current (initial) setting of
     /// backend parameters, such as client_encoding or DateStyle
     ///
@@ -245,22 +295,78 @@ pub enum BackendMessage {
     /// This message informs the frontend about the previous `Close` frontend
     /// message is successful.
     CloseComplete,
+    /// The backend is ready to receive `COPY FROM STDIN` data in text format.
+    /// Contains number of copied columns
+    CopyInResponse(i16),
+    /// The backend starts to send `COPY TO STDOUT` data in text format.
+    /// Contains number of copied columns
+    CopyOutResponse(i16),
+    /// A row of `COPY TO STDOUT` data
+    CopyData(String),
+    /// The backend sent all rows of `COPY TO STDOUT` data
+    CopyDone,
 }
 
 impl BackendMessage {
     /// returns binary representation of a backend message
     pub fn as_vec(&self) -> Vec<u8> {
         match self {
-            BackendMessage::NoticeResponse => vec![NOTICE_RESPONSE],
+            BackendMessage::NoticeResponse(severity, message) => {
+                let mut notice_response_buff = Vec::new();
+                notice_response_buff.extend_from_slice(&[NOTICE_RESPONSE]);
+                let mut message_buff = Vec::new();
+                message_buff.extend_from_slice(&[SEVERITY]);
+                message_buff.extend_from_slice(severity.as_bytes());
+                message_buff.extend_from_slice(&[0]);
+                message_buff.extend_from_slice(&[MESSAGE]);
+                message_buff.extend_from_slice(message.as_bytes());
+                message_buff.extend_from_slice(&[0]);
+                notice_response_buff.extend_from_slice(&(message_buff.len() as i32 + 4 + 1).to_be_bytes());
+                notice_response_buff.extend_from_slice(message_buff.as_ref());
+                notice_response_buff.extend_from_slice(&[0]);
+                notice_response_buff
+            }
+            BackendMessage::NotificationResponse(pid, channel, payload) => {
+                let mut buff = Vec::new();
+                buff.extend_from_slice(&[NOTIFICATION_RESPONSE]);
+                buff.extend_from_slice(&(4 + 4 + channel.len() as i32 + 1 + payload.len() as i32 + 1).to_be_bytes());
+                buff.extend_from_slice(&pid.to_be_bytes());
+                buff.extend_from_slice(channel.as_bytes());
+                buff.extend_from_slice(&[0]);
+                buff.extend_from_slice(payload.as_bytes());
+                buff.extend_from_slice(&[0]);
+                buff
+            }
             BackendMessage::AuthenticationCleartextPassword => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 3],
-            BackendMessage::AuthenticationMD5Password => vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5, 1, 1, 1, 1],
+            BackendMessage::AuthenticationMD5Password(salt) => {
+                let mut buff = vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5];
+                buff.extend_from_slice(salt);
+                buff
+            }
+            BackendMessage::AuthenticationSASL(mechanisms) => {
+                let mut mechanisms_buff = Vec::new();
+                for mechanism in mechanisms.iter() {
+                    mechanisms_buff.extend_from_slice(mechanism.as_bytes());
+                    mechanisms_buff.extend_from_slice(&[0]);
+                }
+                mechanisms_buff.extend_from_slice(&[0]);
+                authentication(10, mechanisms_buff.as_slice())
+            }
+            BackendMessage::AuthenticationSASLContinue(data) => authentication(11, data.as_bytes()),
+            BackendMessage::AuthenticationSASLFinal(data) => authentication(12, data.as_bytes()),
             BackendMessage::AuthenticationOk => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 0],
             BackendMessage::ReadyForQuery => vec![READY_FOR_QUERY, 0, 0, 0, 5, EMPTY_QUERY_RESPONSE],
             BackendMessage::DataRow(row) => {
                 let mut row_buff = Vec::new();
                 for field in row.iter() {
-                    row_buff.extend_from_slice(&(field.len() as i32).to_be_bytes());
-                    row_buff.extend_from_slice(field.as_str().as_bytes());
+                    match field {
+                        // length of NULL value is -1, no bytes of the value follow
+                        None => row_buff.extend_from_slice(&(-1i32).to_be_bytes()),
+                        Some(field) => {
+                            row_buff.extend_from_slice(&(field.len() as i32).to_be_bytes());
+                            row_buff.extend_from_slice(field.as_bytes());
+                        }
+                    }
                 }
                 let mut len_buff = Vec::new();
                 len_buff.extend_from_slice(&[DATA_ROW]);
@@ -278,7 +384,7 @@ impl BackendMessage {
                     buff.extend_from_slice(&(0i16).to_be_bytes()); // column id
                     buff.extend_from_slice(&field.type_id.to_be_bytes());
                     buff.extend_from_slice(&field.type_size.to_be_bytes());
-                    buff.extend_from_slice(&(-1i32).to_be_bytes()); // type modifier
+                    buff.extend_from_slice(&field.type_modifier.to_be_bytes());
                     buff.extend_from_slice(&0i16.to_be_bytes());
                 }
                 let mut len_buff = Vec::new();
@@ -297,7 +403,7 @@ impl BackendMessage {
                 command_buff
             }
             BackendMessage::EmptyQueryResponse => vec![EMPTY_QUERY_RESPONSE, 0, 0, 0, 4],
-            BackendMessage::ErrorResponse(severity, code, message) => {
+            BackendMessage::ErrorResponse(severity, code, message, detail, hint) => {
                 let mut error_response_buff = Vec::new();
                 error_response_buff.extend_from_slice(&[ERROR_RESPONSE]);
                 let mut message_buff = Vec::new();
@@ -316,6 +422,16 @@ impl BackendMessage {
                     message_buff.extend_from_slice(message.as_bytes());
                     message_buff.extend_from_slice(&[0]);
                 }
+                if let Some(detail) = detail.as_ref() {
+                    message_buff.extend_from_slice(&[DETAIL]);
+                    message_buff.extend_from_slice(detail.as_bytes());
+                    message_buff.extend_from_slice(&[0]);
+                }
+                if let Some(hint) = hint.as_ref() {
+                    message_buff.extend_from_slice(&[HINT]);
+                    message_buff.extend_from_slice(hint.as_bytes());
+                    message_buff.extend_from_slice(&[0]);
+                }
                 error_response_buff.extend_from_slice(&(message_buff.len() as i32 + 4 + 1).to_be_bytes());
                 error_response_buff.extend_from_slice(message_buff.as_ref());
                 error_response_buff.extend_from_slice(&[0]);
@@ -349,10 +465,41 @@ impl BackendMessage {
             BackendMessage::ParseComplete => vec![PARSE_COMPLETE, 0, 0, 0, 4],
             BackendMessage::BindComplete => vec![BIND_COMPLETE, 0, 0, 0, 4],
             BackendMessage::CloseComplete => vec![CLOSE_COMPLETE, 0, 0, 0, 4],
+            BackendMessage::CopyInResponse(columns) => copy_response(COPY_IN_RESPONSE, *columns),
+            BackendMessage::CopyOutResponse(columns) => copy_response(COPY_OUT_RESPONSE, *columns),
+            BackendMessage::CopyData(data) => {
+                let mut buff = Vec::new();
+                buff.extend_from_slice(&[COPY_DATA]);
+                buff.extend_from_slice(&(4 + data.len() as i32).to_be_bytes());
+                buff.extend_from_slice(data.as_bytes());
+                buff
+            }
+            BackendMessage::CopyDone => vec![COPY_DONE, 0, 0, 0, 4],
         }
     }
 }
 
+fn copy_response(tag: u8, columns: i16) -> Vec<u8> {
+    let mut buff = Vec::new();
+    buff.extend_from_slice(&[tag]);
+    buff.extend_from_slice(&(7 + 2 * columns as i32).to_be_bytes());
+    buff.extend_from_slice(&[0]); // text format of all columns
+    buff.extend_from_slice(&columns.to_be_bytes());
+    for _ in 0..columns {
+        buff.extend_from_slice(&0i16.to_be_bytes());
+    }
+    buff
+}
+
+fn authentication(code: i32, data: &[u8]) -> Vec<u8> {
+    let mut buff = Vec::new();
+    buff.extend_from_slice(&[AUTHENTICATION]);
+    buff.extend_from_slice(&(8 + data.len() as i32).to_be_bytes());
+    buff.extend_from_slice(&code.to_be_bytes());
+    buff.extend_from_slice(data);
+    buff
+}
+
 /// Struct description of metadata that describes how client should interpret
 /// outgoing selected data
 #[derive(Clone, Debug, PartialEq)]
@@ -363,6 +510,8 @@ pub struct ColumnMetadata {
     pub type_id: u32,
     /// PostgreSQL data type size
     pub type_size: i16,
+    /// PostgreSQL type modifier, e.g. declared length of `varchar(n)`, -1 if there is none
+    pub type_modifier: i32,
 }
 
 impl ColumnMetadata {
@@ -372,8 +521,14 @@ impl ColumnMetadata {
             name,
             type_id,
             type_size,
+            type_modifier: -1,
         }
     }
+
+    /// Sets type modifier of the column
+    pub fn with_type_modifier(self, type_modifier: i32) -> Self {
+        Self { type_modifier, ..self }
+    }
 }
 
 /// Decodes data within messages.
@@ -570,6 +725,19 @@ fn decode_terminate(_cursor: Cursor) -> Result<FrontendMessage> {
     Ok(FrontendMessage::Terminate)
 }
 
+fn decode_copy_data(cursor: Cursor) -> Result<FrontendMessage> {
+    Ok(FrontendMessage::CopyData(cursor.buf.to_vec()))
+}
+
+fn decode_copy_done(_cursor: Cursor) -> Result<FrontendMessage> {
+    Ok(FrontendMessage::CopyDone)
+}
+
+fn decode_copy_fail(mut cursor: Cursor) -> Result<FrontendMessage> {
+    let message = cursor.read_cstr()?.to_owned();
+    Ok(FrontendMessage::CopyFail { message })
+}
+
 #[cfg(test)]
 mod decoding_frontend_messages {
     use super::*;
@@ -704,6 +872,29 @@ mod decoding_frontend_messages {
         let message = FrontendMessage::decode(b'X', &[]);
         assert_eq!(message, Ok(FrontendMessage::Terminate));
     }
+
+    #[test]
+    fn copy_data() {
+        let message = FrontendMessage::decode(b'd', &[49, 9, 50, 10]);
+        assert_eq!(message, Ok(FrontendMessage::CopyData(vec![49, 9, 50, 10])));
+    }
+
+    #[test]
+    fn copy_done() {
+        let message = FrontendMessage::decode(b'c', &[]);
+        assert_eq!(message, Ok(FrontendMessage::CopyDone));
+    }
+
+    #[test]
+    fn copy_fail() {
+        let message = FrontendMessage::decode(b'f', &[101, 114, 114, 111, 114, 0]);
+        assert_eq!(
+            message,
+            Ok(FrontendMessage::CopyFail {
+                message: "error".to_owned()
+            })
+        );
+    }
 }
 
 #[cfg(test)]
@@ -712,7 +903,59 @@ mod serializing_backend_messages {
 
     #[test]
     fn notice() {
-        assert_eq!(BackendMessage::NoticeResponse.as_vec(), vec![NOTICE_RESPONSE]);
+        assert_eq!(
+            BackendMessage::NoticeResponse("NOTICE".to_owned(), "message".to_owned()).as_vec(),
+            vec![
+                NOTICE_RESPONSE,
+                0,
+                0,
+                0,
+                22,
+                SEVERITY,
+                b'N',
+                b'O',
+                b'T',
+                b'I',
+                b'C',
+                b'E',
+                0,
+                MESSAGE,
+                b'm',
+                b'e',
+                b's',
+                b's',
+                b'a',
+                b'g',
+                b'e',
+                0,
+                0
+            ]
+        );
+    }
+
+    #[test]
+    fn notification() {
+        assert_eq!(
+            BackendMessage::NotificationResponse(1, "ch".to_owned(), "msg".to_owned()).as_vec(),
+            vec![
+                NOTIFICATION_RESPONSE,
+                0,
+                0,
+                0,
+                15,
+                0,
+                0,
+                0,
+                1,
+                b'c',
+                b'h',
+                0,
+                b'm',
+                b's',
+                b'g',
+                0
+            ]
+        );
     }
 
     #[test]
@@ -726,8 +969,57 @@ mod serializing_backend_messages {
     #[test]
     fn authentication_md5_password() {
         assert_eq!(
-            BackendMessage::AuthenticationMD5Password.as_vec(),
-            vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5, 1, 1, 1, 1]
+            BackendMessage::AuthenticationMD5Password([1, 2, 3, 4]).as_vec(),
+            vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5, 1, 2, 3, 4]
+        )
+    }
+
+    #[test]
+    fn authentication_sasl() {
+        assert_eq!(
+            BackendMessage::AuthenticationSASL(vec!["SCRAM-SHA-256"]).as_vec(),
+            vec![
+                AUTHENTICATION,
+                0,
+                0,
+                0,
+                23,
+                0,
+                0,
+                0,
+                10,
+                83,
+                67,
+                82,
+                65,
+                77,
+                45,
+                83,
+                72,
+                65,
+                45,
+                50,
+                53,
+                54,
+                0,
+                0
+            ]
+        )
+    }
+
+    #[test]
+    fn authentication_sasl_continue() {
+        assert_eq!(
+            BackendMessage::AuthenticationSASLContinue("r=1".to_owned()).as_vec(),
+            vec![AUTHENTICATION, 0, 0, 0, 11, 0, 0, 0, 11, 114, 61, 49]
+        )
+    }
+
+    #[test]
+    fn authentication_sasl_final() {
+        assert_eq!(
+            BackendMessage::AuthenticationSASLFinal("v=1".to_owned()).as_vec(),
+            vec![AUTHENTICATION, 0, 0, 0, 11, 0, 0, 0, 12, 118, 61, 49]
         )
     }
 
@@ -785,11 +1077,19 @@ mod serializing_backend_messages {
     #[test]
     fn data_row() {
         assert_eq!(
-            BackendMessage::DataRow(vec!["1".to_owned(), "2".to_owned(), "3".to_owned()]).as_vec(),
+            BackendMessage::DataRow(vec![Some("1".to_owned()), Some("2".to_owned()), Some("3".to_owned())]).as_vec(),
             vec![DATA_ROW, 0, 0, 0, 21, 0, 3, 0, 0, 0, 1, 49, 0, 0, 0, 1, 50, 0, 0, 0, 1, 51]
         )
     }
 
+    #[test]
+    fn data_row_with_null() {
+        assert_eq!(
+            BackendMessage::DataRow(vec![Some("".to_owned()), None]).as_vec(),
+            vec![DATA_ROW, 0, 0, 0, 14, 0, 2, 0, 0, 0, 0, 255, 255, 255, 255]
+        )
+    }
+
     #[test]
     fn row_description() {
         assert_eq!(
@@ -827,6 +1127,46 @@ mod serializing_backend_messages {
         );
     }
 
+    #[test]
+    fn row_description_with_type_modifier() {
+        assert_eq!(
+            BackendMessage::RowDescription(vec![
+                ColumnMetadata::new("c1".to_owned(), 1043, -1).with_type_modifier(14)
+            ])
+            .as_vec(),
+            vec![
+                ROW_DESCRIPTION,
+                0,
+                0,
+                0,
+                27,
+                0,
+                1,
+                99,
+                49,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                4,
+                19,
+                255,
+                255,
+                0,
+                0,
+                0,
+                14,
+                0,
+                0
+            ]
+        );
+    }
+
     #[test]
     fn command_complete() {
         assert_eq!(
@@ -846,11 +1186,74 @@ mod serializing_backend_messages {
     #[test]
     fn error_response() {
         assert_eq!(
-            BackendMessage::ErrorResponse(None, None, None).as_vec(),
+            BackendMessage::ErrorResponse(None, None, None, None, None).as_vec(),
             vec![ERROR_RESPONSE, 0, 0, 0, 5, 0]
         )
     }
 
+    #[test]
+    fn error_response_with_detail_and_hint() {
+        assert_eq!(
+            BackendMessage::ErrorResponse(
+                Some("ERROR"),
+                None,
+                Some("m".to_owned()),
+                Some("d".to_owned()),
+                Some("h".to_owned())
+            )
+            .as_vec(),
+            vec![
+                ERROR_RESPONSE,
+                0,
+                0,
+                0,
+                21,
+                SEVERITY,
+                69,
+                82,
+                82,
+                79,
+                82,
+                0,
+                MESSAGE,
+                109,
+                0,
+                DETAIL,
+                100,
+                0,
+                HINT,
+                104,
+                0,
+                0
+            ]
+        )
+    }
+
+    #[test]
+    fn error_response_without_detail() {
+        assert_eq!(
+            BackendMessage::ErrorResponse(Some("ERROR"), None, Some("m".to_owned()), None, None).as_vec(),
+            vec![
+                ERROR_RESPONSE,
+                0,
+                0,
+                0,
+                15,
+                SEVERITY,
+                69,
+                82,
+                82,
+                79,
+                82,
+                0,
+                MESSAGE,
+                109,
+                0,
+                0
+            ]
+        )
+    }
+
     #[test]
     fn parameter_description() {
         assert_eq!(
@@ -878,4 +1281,33 @@ mod serializing_backend_messages {
     fn close_complete() {
         assert_eq!(BackendMessage::CloseComplete.as_vec(), vec![CLOSE_COMPLETE, 0, 0, 0, 4])
     }
+
+    #[test]
+    fn copy_in_response() {
+        assert_eq!(
+            BackendMessage::CopyInResponse(2).as_vec(),
+            vec![COPY_IN_RESPONSE, 0, 0, 0, 11, 0, 0, 2, 0, 0, 0, 0]
+        )
+    }
+
+    #[test]
+    fn copy_out_response() {
+        assert_eq!(
+            BackendMessage::CopyOutResponse(2).as_vec(),
+            vec![COPY_OUT_RESPONSE, 0, 0, 0, 11, 0, 0, 2, 0, 0, 0, 0]
+        )
+    }
+
+    #[test]
+    fn copy_data() {
+        assert_eq!(
+            BackendMessage::CopyData("1\t2\n".to_owned()).as_vec(),
+            vec![COPY_DATA, 0, 0, 0, 8, 49, 9, 50, 10]
+        )
+    }
+
+    #[test]
+    fn copy_done() {
+        assert_eq!(BackendMessage::CopyDone.as_vec(), vec![COPY_DONE, 0, 0, 0, 4])
+    }
 }
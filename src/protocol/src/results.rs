@@ -20,10 +20,13 @@ use std::fmt::{self, Display, Formatter};
 
 /// Represents result of SQL query execution
 pub type QueryResult = std::result::Result<QueryEvent, QueryError>;
-/// Represents selected columns from tables
-pub type Description = Vec<(String, PostgreSqlType)>;
-/// Represents selected data from tables
-pub type Projection = (Description, Vec<Vec<String>>);
+/// Represents selected columns from tables as names, types and type modifiers,
+/// type modifier is -1 when a type has none
+pub type Description = Vec<(String, PostgreSqlType, i32)>;
+/// Represents selected data from tables, NULL values are `None`
+pub type Projection = (Description, Vec<Vec<Option<String>>>);
+/// Represents a row that is sent to the client as soon as it is selected or the error that ends the selection
+pub type SelectedRow = std::result::Result<Vec<Option<String>>, QueryError>;
 
 /// Represents successful events that can happen in server backend
 #[derive(Clone, Debug, PartialEq)]
@@ -32,24 +35,104 @@ pub enum QueryEvent {
     SchemaCreated,
     /// Schema successfully dropped
     SchemaDropped,
+    /// Schema successfully renamed
+    SchemaAltered,
     /// Table successfully created
     TableCreated,
     /// Table successfully dropped
     TableDropped,
+    /// User successfully created
+    UserCreated,
+    /// User successfully dropped
+    UserDropped,
+    /// Function successfully created
+    FunctionCreated,
+    /// Function successfully dropped
+    FunctionDropped,
+    /// Trigger successfully created
+    TriggerCreated,
+    /// Trigger successfully dropped
+    TriggerDropped,
+    /// Table successfully altered, e.g. its trigger was disabled
+    TableAltered,
+    /// Privileges successfully granted
+    PrivilegesGranted,
+    /// Privileges successfully revoked
+    PrivilegesRevoked,
     /// Variable successfully set
     VariableSet,
     /// Transaction is started
     TransactionStarted,
+    /// Transaction is committed
+    TransactionCommitted,
+    /// Mode of deferrable constraints is changed for the current transaction
+    ConstraintsSet,
+    /// Table lock is acquired
+    TableLocked,
+    /// Session started to listen to a notification channel
+    ChannelListened,
+    /// Session stopped to listen to notification channels
+    ChannelUnlistened,
+    /// Notification is sent to listeners of a channel
+    NotificationSent,
+    /// Cursor is opened by `DECLARE`
+    CursorDeclared,
+    /// Cursor is closed by `CLOSE`
+    CursorClosed,
+    /// Notification of a channel that the session listens to
+    Notification {
+        /// id of the session that sent the notification
+        pid: u32,
+        /// name of the channel
+        channel: String,
+        /// payload of the notification, empty if it was not specified
+        payload: String,
+    },
+    /// Statistics of table columns are collected
+    AnalyzeComplete,
+    /// Query of `EXPLAIN SYNTAX` could be executed, it has no syntax or semantic errors
+    SyntaxValid,
+    /// Rows of a table are reordered by an index
+    TableClustered,
+    /// Dead rows of a table are removed
+    VacuumComplete {
+        /// number of removed dead rows
+        rows_removed: u64,
+    },
+    /// Server is ready to receive rows of `COPY FROM STDIN` with the number of columns
+    CopyInStarted(usize),
+    /// Rows of `COPY TO STDOUT` formatted as lines of text
+    RecordsCopied {
+        /// number of copied columns
+        columns: usize,
+        /// formatted rows, each ends with a new line
+        rows: Vec<String>,
+    },
     /// Number of records inserted into a table
     RecordsInserted(usize),
     /// Records selected from database
     RecordsSelected(Projection),
+    /// Records returned by `FETCH` from a cursor
+    RecordsFetched(Projection),
     /// Number of records updated into a table
     RecordsUpdated(usize),
     /// Number of records deleted into a table
     RecordsDeleted(usize),
+    /// Records deleted from a table projected by RETURNING clause
+    RecordsDeletedReturning(Projection),
+    /// Number of records inserted, updated or deleted by MERGE
+    RecordsMerged(usize),
     /// Parameters described needed by a prepared statement
     PreparedStatementDescribed(Vec<PostgreSqlType>, Description),
+    /// Non-fatal warning, e.g. value was truncated to fit a column
+    Notice {
+        /// severity of the notice, e.g. NOTICE or WARNING
+        severity: String,
+        /// human readable message of the notice
+        message: String,
+    },
+    /// Query string contains no statements
+    EmptyQueryResponse,
     /// Processing of the query is complete
     QueryComplete,
     /// Parsing the exteneded query is complete
@@ -63,37 +146,65 @@ impl Into<Vec<BackendMessage>> for QueryEvent {
         match self {
             QueryEvent::SchemaCreated => vec![BackendMessage::CommandComplete("CREATE SCHEMA".to_owned())],
             QueryEvent::SchemaDropped => vec![BackendMessage::CommandComplete("DROP SCHEMA".to_owned())],
+            QueryEvent::SchemaAltered => vec![BackendMessage::CommandComplete("ALTER SCHEMA".to_owned())],
             QueryEvent::TableCreated => vec![BackendMessage::CommandComplete("CREATE TABLE".to_owned())],
             QueryEvent::TableDropped => vec![BackendMessage::CommandComplete("DROP TABLE".to_owned())],
+            QueryEvent::UserCreated => vec![BackendMessage::CommandComplete("CREATE ROLE".to_owned())],
+            QueryEvent::UserDropped => vec![BackendMessage::CommandComplete("DROP ROLE".to_owned())],
+            QueryEvent::FunctionCreated => vec![BackendMessage::CommandComplete("CREATE FUNCTION".to_owned())],
+            QueryEvent::FunctionDropped => vec![BackendMessage::CommandComplete("DROP FUNCTION".to_owned())],
+            QueryEvent::TriggerCreated => vec![BackendMessage::CommandComplete("CREATE TRIGGER".to_owned())],
+            QueryEvent::TriggerDropped => vec![BackendMessage::CommandComplete("DROP TRIGGER".to_owned())],
+            QueryEvent::TableAltered => vec![BackendMessage::CommandComplete("ALTER TABLE".to_owned())],
+            QueryEvent::PrivilegesGranted => vec![BackendMessage::CommandComplete("GRANT".to_owned())],
+            QueryEvent::PrivilegesRevoked => vec![BackendMessage::CommandComplete("REVOKE".to_owned())],
             QueryEvent::VariableSet => vec![BackendMessage::CommandComplete("SET".to_owned())],
             QueryEvent::TransactionStarted => vec![BackendMessage::CommandComplete("BEGIN".to_owned())],
-            QueryEvent::RecordsInserted(records) => {
-                vec![BackendMessage::CommandComplete(format!("INSERT 0 {}", records))]
+            QueryEvent::TransactionCommitted => vec![BackendMessage::CommandComplete("COMMIT".to_owned())],
+            QueryEvent::ConstraintsSet => vec![BackendMessage::CommandComplete("SET CONSTRAINTS".to_owned())],
+            QueryEvent::TableLocked => vec![BackendMessage::CommandComplete("LOCK TABLE".to_owned())],
+            QueryEvent::ChannelListened => vec![BackendMessage::CommandComplete("LISTEN".to_owned())],
+            QueryEvent::ChannelUnlistened => vec![BackendMessage::CommandComplete("UNLISTEN".to_owned())],
+            QueryEvent::NotificationSent => vec![BackendMessage::CommandComplete("NOTIFY".to_owned())],
+            QueryEvent::CursorDeclared => vec![BackendMessage::CommandComplete("DECLARE CURSOR".to_owned())],
+            QueryEvent::CursorClosed => vec![BackendMessage::CommandComplete("CLOSE CURSOR".to_owned())],
+            QueryEvent::Notification { pid, channel, payload } => {
+                vec![BackendMessage::NotificationResponse(pid, channel, payload)]
             }
-            QueryEvent::RecordsSelected(projection) => {
-                let definition = projection.0;
-                let description: Vec<ColumnMetadata> = definition
-                    .into_iter()
-                    .map(|(name, sql_type)| ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len()))
-                    .collect();
-                let records = projection.1;
-                let len = records.len();
-                let mut messages = vec![BackendMessage::RowDescription(description)];
-                for record in records {
-                    messages.push(BackendMessage::DataRow(record));
+            QueryEvent::AnalyzeComplete => vec![BackendMessage::CommandComplete("ANALYZE".to_owned())],
+            QueryEvent::SyntaxValid => vec![BackendMessage::CommandComplete("EXPLAIN".to_owned())],
+            QueryEvent::TableClustered => vec![BackendMessage::CommandComplete("CLUSTER".to_owned())],
+            QueryEvent::VacuumComplete { .. } => vec![BackendMessage::CommandComplete("VACUUM".to_owned())],
+            QueryEvent::CopyInStarted(columns) => vec![BackendMessage::CopyInResponse(columns as i16)],
+            QueryEvent::RecordsCopied { columns, rows } => {
+                let len = rows.len();
+                let mut messages = vec![BackendMessage::CopyOutResponse(columns as i16)];
+                for row in rows {
+                    messages.push(BackendMessage::CopyData(row));
                 }
-                messages.push(BackendMessage::CommandComplete(format!("SELECT {}", len)));
+                messages.push(BackendMessage::CopyDone);
+                messages.push(BackendMessage::CommandComplete(format!("COPY {}", len)));
                 messages
             }
+            QueryEvent::RecordsInserted(records) => {
+                vec![BackendMessage::CommandComplete(format!("INSERT 0 {}", records))]
+            }
+            QueryEvent::RecordsSelected(projection) => rows(projection, "SELECT"),
+            QueryEvent::RecordsFetched(projection) => rows(projection, "FETCH"),
             QueryEvent::RecordsUpdated(records) => vec![BackendMessage::CommandComplete(format!("UPDATE {}", records))],
             QueryEvent::RecordsDeleted(records) => vec![BackendMessage::CommandComplete(format!("DELETE {}", records))],
+            QueryEvent::RecordsDeletedReturning(projection) => rows(projection, "DELETE"),
+            QueryEvent::RecordsMerged(records) => vec![BackendMessage::CommandComplete(format!("MERGE {}", records))],
             QueryEvent::PreparedStatementDescribed(param_types, description) => {
                 let desc_message = if description.is_empty() {
                     BackendMessage::NoData
                 } else {
                     let columns: Vec<ColumnMetadata> = description
                         .into_iter()
-                        .map(|(name, sql_type)| ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len()))
+                        .map(|(name, sql_type, type_modifier)| {
+                            ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len())
+                                .with_type_modifier(type_modifier)
+                        })
                         .collect();
                     BackendMessage::RowDescription(columns)
                 };
@@ -101,6 +212,8 @@ impl Into<Vec<BackendMessage>> for QueryEvent {
                 let type_ids = param_types.iter().map(PostgreSqlType::pg_oid).collect();
                 vec![BackendMessage::ParameterDescription(type_ids), desc_message]
             }
+            QueryEvent::Notice { severity, message } => vec![BackendMessage::NoticeResponse(severity, message)],
+            QueryEvent::EmptyQueryResponse => vec![BackendMessage::EmptyQueryResponse],
             QueryEvent::QueryComplete => vec![BackendMessage::ReadyForQuery],
             QueryEvent::ParseComplete => vec![BackendMessage::ParseComplete],
             QueryEvent::BindComplete => vec![BackendMessage::BindComplete],
@@ -108,6 +221,29 @@ impl Into<Vec<BackendMessage>> for QueryEvent {
     }
 }
 
+// row description and data rows followed by command tag with number of rows
+pub(crate) fn row_description(definition: Description) -> BackendMessage {
+    BackendMessage::RowDescription(
+        definition
+            .into_iter()
+            .map(|(name, sql_type, type_modifier)| {
+                ColumnMetadata::new(name, sql_type.pg_oid(), sql_type.pg_len()).with_type_modifier(type_modifier)
+            })
+            .collect(),
+    )
+}
+
+fn rows(projection: Projection, command: &str) -> Vec<BackendMessage> {
+    let (definition, records) = projection;
+    let len = records.len();
+    let mut messages = vec![row_description(definition)];
+    for record in records {
+        messages.push(BackendMessage::DataRow(record));
+    }
+    messages.push(BackendMessage::CommandComplete(format!("{} {}", command, len)));
+    messages
+}
+
 /// Message severities
 /// Reference: defined in https://www.postgresql.org/docs/12/protocol-error-fields.html
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -144,14 +280,81 @@ pub(crate) enum QueryErrorKind {
     TableAlreadyExists(String),
     SchemaDoesNotExist(String),
     SchemaHasDependentObjects(String),
+    TableHasDependentObjects(String),
     TableDoesNotExist(String),
     ColumnDoesNotExist(Vec<String>),
+    AmbiguousColumn(String),
+    UniqueViolation(String),
+    ForeignKeyViolation {
+        table_name: String,
+        constraint: String,
+    },
+    ReferencedKeyViolation {
+        table_name: String,
+        constraint: String,
+        referencing_table: String,
+    },
+    InvalidForeignKey(String),
+    GroupingError(String),
+    InvalidColumnReference(String),
+    InvalidIdentifier(String),
+    CardinalityViolation(String),
     InvalidParameterValue(String),
     PreparedStatementDoesNotExist(String),
     PortalDoesNotExist(String),
+    CursorAlreadyExists(String),
+    CursorDoesNotExist(String),
     ProtocolViolation(String),
     FeatureNotSupported(String),
+    TooManyConnections,
+    ProgramLimitExceeded(String),
+    StackDepthLimitExceeded,
+    PermissionDenied(String),
+    UserAlreadyExists(String),
+    UserDoesNotExist(String),
+    FunctionAlreadyExists(String),
+    FunctionDoesNotExist(String),
+    TriggerAlreadyExists {
+        trigger_name: String,
+        table_name: String,
+    },
+    TriggerDoesNotExist {
+        trigger_name: String,
+        table_name: String,
+    },
+    InvalidObjectDefinition(String),
+    TableIsNotPartitioned(String),
+    NoPartitionForRow(String),
+    NotAPartition {
+        partition: String,
+        table_name: String,
+    },
+    AlreadyAPartition(String),
+    CheckViolation(String),
+    NotNullViolation(String),
+    IndexDoesNotExist {
+        index_name: String,
+        table_name: String,
+    },
+    ConstraintAlreadyExists {
+        constraint: String,
+        table_name: String,
+    },
+    ConstraintDoesNotExist {
+        constraint: String,
+        table_name: String,
+    },
+    ColumnAlreadyExists {
+        column_name: String,
+        table_name: String,
+    },
+    LockNotAvailable(String),
     TooManyInsertExpressions,
+    GeneratedAlways(String),
+    BadCopyFileFormat(String),
+    UndefinedFile(String),
+    IoError(String),
+    QueryCanceled(String),
     NumericTypeOutOfRange {
         pg_type: PostgreSqlType,
         column_name: String,
@@ -163,6 +366,11 @@ pub(crate) enum QueryErrorKind {
         column_name: String,
         row_index: usize, // TODO make it optional - does not make sense for update query
     },
+    ValueOutOfRange(PostgreSqlType),
+    InvalidTextRepresentation {
+        pg_type: PostgreSqlType,
+        value: String,
+    },
     StringTypeLengthMismatch {
         pg_type: PostgreSqlType,
         len: u64,
@@ -175,6 +383,12 @@ pub(crate) enum QueryErrorKind {
         right_type: String,
     },
     SyntaxError(String),
+    InvalidRowCountInLimitClause,
+    InvalidRowCountInResultOffsetClause,
+    NonIntegerRowCount {
+        clause: String,
+        value: String,
+    },
 }
 
 impl QueryErrorKind {
@@ -184,19 +398,63 @@ impl QueryErrorKind {
             Self::TableAlreadyExists(_) => "42P07",
             Self::SchemaDoesNotExist(_) => "3F000",
             Self::SchemaHasDependentObjects(_) => "2BP01",
+            Self::TableHasDependentObjects(_) => "2BP01",
             Self::TableDoesNotExist(_) => "42P01",
             Self::ColumnDoesNotExist(_) => "42703",
+            Self::AmbiguousColumn(_) => "42702",
+            Self::UniqueViolation(_) => "23505",
+            Self::ForeignKeyViolation { .. } => "23503",
+            Self::ReferencedKeyViolation { .. } => "23503",
+            Self::InvalidForeignKey(_) => "42830",
+            Self::GroupingError(_) => "42803",
+            Self::InvalidColumnReference(_) => "42P10",
+            Self::InvalidIdentifier(_) => "42602",
+            Self::CardinalityViolation(_) => "21000",
             Self::InvalidParameterValue(_) => "22023",
             Self::PreparedStatementDoesNotExist(_) => "26000",
             Self::PortalDoesNotExist(_) => "26000",
+            Self::CursorAlreadyExists(_) => "42P03",
+            Self::CursorDoesNotExist(_) => "34000",
             Self::ProtocolViolation(_) => "08P01",
             Self::FeatureNotSupported(_) => "0A000",
+            Self::TooManyConnections => "53300",
+            Self::ProgramLimitExceeded(_) => "54000",
+            Self::StackDepthLimitExceeded => "54001",
+            Self::PermissionDenied(_) => "42501",
+            Self::UserAlreadyExists(_) => "42710",
+            Self::UserDoesNotExist(_) => "42704",
+            Self::FunctionAlreadyExists(_) => "42723",
+            Self::FunctionDoesNotExist(_) => "42883",
+            Self::TriggerAlreadyExists { .. } => "42710",
+            Self::TriggerDoesNotExist { .. } => "42704",
+            Self::InvalidObjectDefinition(_) => "42P17",
+            Self::TableIsNotPartitioned(_) => "42809",
+            Self::NoPartitionForRow(_) => "23514",
+            Self::NotAPartition { .. } => "42P01",
+            Self::AlreadyAPartition(_) => "42809",
+            Self::CheckViolation(_) => "23514",
+            Self::NotNullViolation(_) => "23502",
+            Self::IndexDoesNotExist { .. } => "42704",
+            Self::ConstraintAlreadyExists { .. } => "42710",
+            Self::ConstraintDoesNotExist { .. } => "42704",
+            Self::ColumnAlreadyExists { .. } => "42701",
+            Self::LockNotAvailable(_) => "55P03",
             Self::TooManyInsertExpressions => "42601",
+            Self::GeneratedAlways(_) => "428C9",
+            Self::BadCopyFileFormat(_) => "22P04",
+            Self::UndefinedFile(_) => "58P01",
+            Self::IoError(_) => "58030",
+            Self::QueryCanceled(_) => "57014",
             Self::NumericTypeOutOfRange { .. } => "22003",
             Self::DataTypeMismatch { .. } => "2200G",
+            Self::ValueOutOfRange(_) => "22003",
+            Self::InvalidTextRepresentation { .. } => "22P02",
             Self::StringTypeLengthMismatch { .. } => "22026",
             Self::UndefinedFunction { .. } => "42883",
             Self::SyntaxError(_) => "42601",
+            Self::InvalidRowCountInLimitClause => "2201W",
+            Self::InvalidRowCountInResultOffsetClause => "2201X",
+            Self::NonIntegerRowCount { .. } => "42804",
         }
     }
 }
@@ -210,6 +468,9 @@ impl Display for QueryErrorKind {
             Self::SchemaHasDependentObjects(schema_name) => {
                 write!(f, "schema \"{}\" has dependent objects", schema_name)
             }
+            Self::TableHasDependentObjects(table_name) => {
+                write!(f, "cannot drop table {} because other objects depend on it", table_name)
+            }
             Self::TableDoesNotExist(table_name) => write!(f, "table \"{}\" does not exist", table_name),
             Self::ColumnDoesNotExist(columns) => {
                 if columns.len() > 1 {
@@ -218,16 +479,121 @@ impl Display for QueryErrorKind {
                     write!(f, "column {} does not exist", columns[0])
                 }
             }
+            Self::AmbiguousColumn(column) => write!(f, "column reference \"{}\" is ambiguous", column),
+            Self::UniqueViolation(constraint) => {
+                write!(f, "duplicate key value violates unique constraint \"{}\"", constraint)
+            }
+            Self::ForeignKeyViolation { table_name, constraint } => write!(
+                f,
+                "insert or update on table \"{}\" violates foreign key constraint \"{}\"",
+                table_name, constraint
+            ),
+            Self::ReferencedKeyViolation {
+                table_name,
+                constraint,
+                referencing_table,
+            } => write!(
+                f,
+                "update or delete on table \"{}\" violates foreign key constraint \"{}\" on table \"{}\"",
+                table_name, constraint, referencing_table
+            ),
+            Self::InvalidForeignKey(message) => write!(f, "{}", message),
+            Self::GroupingError(column) => write!(
+                f,
+                "column \"{}\" must appear in the GROUP BY clause or be used in an aggregate function",
+                column
+            ),
+            Self::InvalidColumnReference(message) => write!(f, "{}", message),
+            Self::InvalidIdentifier(name) => write!(f, "\"{}\" is not a valid identifier", name),
+            Self::CardinalityViolation(message) => write!(f, "{}", message),
             Self::InvalidParameterValue(message) => write!(f, "{}", message),
             Self::PreparedStatementDoesNotExist(statement_name) => {
                 write!(f, "prepared statement {} does not exist", statement_name)
             }
             Self::PortalDoesNotExist(portal_name) => write!(f, "portal {} does not exist", portal_name),
+            Self::CursorAlreadyExists(cursor_name) => write!(f, "cursor \"{}\" already exists", cursor_name),
+            Self::CursorDoesNotExist(cursor_name) => write!(f, "cursor \"{}\" does not exist", cursor_name),
             Self::ProtocolViolation(message) => write!(f, "{}", message),
             Self::FeatureNotSupported(raw_sql_query) => {
                 write!(f, "Currently, Query '{}' can't be executed", raw_sql_query)
             }
+            Self::TooManyConnections => write!(f, "sorry, too many clients already"),
+            Self::ProgramLimitExceeded(message) => write!(f, "{}", message),
+            Self::StackDepthLimitExceeded => write!(f, "stack depth limit exceeded"),
+            Self::PermissionDenied(message) => write!(f, "{}", message),
+            Self::UserAlreadyExists(user_name) => write!(f, "role \"{}\" already exists", user_name),
+            Self::UserDoesNotExist(user_name) => write!(f, "role \"{}\" does not exist", user_name),
+            Self::FunctionAlreadyExists(function) => write!(f, "function \"{}\" already exists", function),
+            Self::FunctionDoesNotExist(function) => write!(f, "function {} does not exist", function),
+            Self::TriggerAlreadyExists {
+                trigger_name,
+                table_name,
+            } => write!(
+                f,
+                "trigger \"{}\" for relation \"{}\" already exists",
+                trigger_name, table_name
+            ),
+            Self::TriggerDoesNotExist {
+                trigger_name,
+                table_name,
+            } => write!(
+                f,
+                "trigger \"{}\" for table \"{}\" does not exist",
+                trigger_name, table_name
+            ),
+            Self::InvalidObjectDefinition(message) => write!(f, "{}", message),
+            Self::TableIsNotPartitioned(table_name) => write!(f, "table \"{}\" is not partitioned", table_name),
+            Self::NoPartitionForRow(table_name) => {
+                write!(f, "no partition of relation \"{}\" found for row", table_name)
+            }
+            Self::NotAPartition { partition, table_name } => write!(
+                f,
+                "relation \"{}\" is not a partition of relation \"{}\"",
+                partition, table_name
+            ),
+            Self::AlreadyAPartition(table_name) => write!(f, "\"{}\" is already a partition", table_name),
+            Self::CheckViolation(message) => write!(f, "{}", message),
+            Self::NotNullViolation(column_name) => write!(
+                f,
+                "null value in column \"{}\" violates not-null constraint",
+                column_name
+            ),
+            Self::IndexDoesNotExist { index_name, table_name } => {
+                write!(
+                    f,
+                    "index \"{}\" for table \"{}\" does not exist",
+                    index_name, table_name
+                )
+            }
+            Self::ConstraintAlreadyExists { constraint, table_name } => write!(
+                f,
+                "constraint \"{}\" for relation \"{}\" already exists",
+                constraint, table_name
+            ),
+            Self::ConstraintDoesNotExist { constraint, table_name } => write!(
+                f,
+                "constraint \"{}\" of relation \"{}\" does not exist",
+                constraint, table_name
+            ),
+            Self::ColumnAlreadyExists {
+                column_name,
+                table_name,
+            } => write!(
+                f,
+                "column \"{}\" of relation \"{}\" already exists",
+                column_name, table_name
+            ),
+            Self::LockNotAvailable(table_name) => write!(f, "could not obtain lock on relation \"{}\"", table_name),
             Self::TooManyInsertExpressions => write!(f, "INSERT has more expressions than target columns"),
+            Self::GeneratedAlways(column) => write!(f, "column \"{}\" is a generated column", column),
+            Self::BadCopyFileFormat(message) => write!(f, "{}", message),
+            Self::UndefinedFile(file_name) => write!(
+                f,
+                "could not open file \"{}\" for reading: No such file or directory",
+                file_name
+            ),
+            Self::IoError(message) => write!(f, "{}", message),
+            Self::QueryCanceled(message) => write!(f, "{}", message),
             Self::NumericTypeOutOfRange {
                 pg_type,
                 column_name,
@@ -247,6 +613,10 @@ impl Display for QueryErrorKind {
                 "invalid input syntax for type {} for column '{}' at row {}: \"{}\"",
                 pg_type, column_name, row_index, value
             ),
+            Self::ValueOutOfRange(pg_type) => write!(f, "{} out of range", pg_type),
+            Self::InvalidTextRepresentation { pg_type, value } => {
+                write!(f, "invalid input syntax for type {}: \"{}\"", pg_type, value)
+            }
             Self::StringTypeLengthMismatch {
                 pg_type,
                 len,
@@ -267,6 +637,11 @@ impl Display for QueryErrorKind {
                 left_type, operator, right_type
             ),
             Self::SyntaxError(expression) => write!(f, "syntax error in {}", expression),
+            Self::InvalidRowCountInLimitClause => write!(f, "LIMIT must not be negative"),
+            Self::InvalidRowCountInResultOffsetClause => write!(f, "OFFSET must not be negative"),
+            Self::NonIntegerRowCount { clause, value } => {
+                write!(f, "argument of {} must be an integer, not {}", clause, value)
+            }
         }
     }
 }
@@ -276,6 +651,9 @@ impl Display for QueryErrorKind {
 pub struct QueryError {
     severity: Severity,
     kind: QueryErrorKind,
+    // boxed to keep the size of `Result<_, QueryError>` small
+    detail: Option<Box<str>>,
+    hint: Option<Box<str>>,
 }
 
 impl QueryError {
@@ -291,11 +669,44 @@ impl QueryError {
     fn message(&self) -> Option<String> {
         Some(format!("{}", self.kind))
     }
+
+    /// secondary message that carries details about the problem
+    pub fn with_detail(mut self, detail: String) -> QueryError {
+        self.detail = Some(detail.into_boxed_str());
+        self
+    }
+
+    /// suggestion what to do about the problem
+    pub fn with_hint(mut self, hint: String) -> QueryError {
+        self.hint = Some(hint.into_boxed_str());
+        self
+    }
+
+    /// reports the error to the client as a warning that does not fail the query
+    pub fn into_warning(self) -> QueryEvent {
+        let severity: &'static str = Severity::Warning.into();
+        QueryEvent::Notice {
+            severity: severity.to_owned(),
+            message: format!("{}", self.kind),
+        }
+    }
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
 }
 
 impl Into<BackendMessage> for QueryError {
     fn into(self) -> BackendMessage {
-        BackendMessage::ErrorResponse(self.severity(), self.code(), self.message())
+        BackendMessage::ErrorResponse(
+            self.severity(),
+            self.code(),
+            self.message(),
+            self.detail.map(String::from),
+            self.hint.map(String::from),
+        )
     }
 }
 
@@ -305,6 +716,8 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::SchemaAlreadyExists(schema_name),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -313,6 +726,8 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::SchemaDoesNotExist(schema_name),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -321,6 +736,18 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::SchemaHasDependentObjects(schema_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// table is referenced by foreign keys of other tables error constructor
+    pub fn table_has_dependent_objects(table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::TableHasDependentObjects(table_name),
+            detail: None,
+            hint: Some("Use DROP ... CASCADE to drop the dependent objects too.".into()),
         }
     }
 
@@ -329,6 +756,8 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::TableAlreadyExists(table_name),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -337,6 +766,8 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::TableDoesNotExist(table_name),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -345,6 +776,103 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::ColumnDoesNotExist(non_existing_columns),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// column reference matches more than one column error constructor
+    pub fn ambiguous_column(column: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::AmbiguousColumn(column),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// unique constraint violation error constructor
+    pub fn unique_violation(constraint: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::UniqueViolation(constraint),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// row references a key that is missing in the referenced table error constructor,
+    /// the missing key is reported as the detail
+    pub fn foreign_key_violation(table_name: String, constraint: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::ForeignKeyViolation { table_name, constraint },
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// deleted or updated key of a row is still referenced by a foreign key error constructor
+    pub fn referenced_key_violation(table_name: String, constraint: String, referencing_table: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::ReferencedKeyViolation {
+                table_name,
+                constraint,
+                referencing_table,
+            },
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// columns of a foreign key can't reference the columns of the table error constructor
+    pub fn invalid_foreign_key(message: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::InvalidForeignKey(message),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// column is neither grouped nor aggregated error constructor
+    pub fn grouping_error(column: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::GroupingError(column),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// invalid column reference error constructor
+    pub fn invalid_column_reference(message: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::InvalidColumnReference(message),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// name that can't be used as an identifier error constructor
+    pub fn invalid_identifier(name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::InvalidIdentifier(name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// the same row is affected more than once error constructor
+    pub fn cardinality_violation(message: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::CardinalityViolation(message),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -353,6 +881,8 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::InvalidParameterValue(message),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -361,6 +891,8 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::PreparedStatementDoesNotExist(statement_name),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -369,6 +901,28 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::PortalDoesNotExist(portal_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// cursor with the same name is already declared error constructor
+    pub fn cursor_already_exists(cursor_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::CursorAlreadyExists(cursor_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// cursor does not exist error constructor
+    pub fn cursor_does_not_exist(cursor_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::CursorDoesNotExist(cursor_name),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -377,6 +931,8 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::ProtocolViolation(message),
+            detail: None,
+            hint: None,
         }
     }
 
@@ -385,365 +941,1695 @@ impl QueryError {
         QueryError {
             severity: Severity::Error,
             kind: QueryErrorKind::FeatureNotSupported(feature_description),
+            detail: None,
+            hint: None,
         }
     }
 
-    /// too many insert expressions errors constructors
-    pub fn too_many_insert_expressions() -> QueryError {
+    /// connection limit of the server is reached error constructor
+    pub fn too_many_connections() -> QueryError {
+        QueryError {
+            severity: Severity::Fatal,
+            kind: QueryErrorKind::TooManyConnections,
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// limit set for the session is exceeded error constructor
+    pub fn program_limit_exceeded(message: String) -> QueryError {
         QueryError {
             severity: Severity::Error,
-            kind: QueryErrorKind::TooManyInsertExpressions,
+            kind: QueryErrorKind::ProgramLimitExceeded(message),
+            detail: None,
+            hint: None,
         }
     }
 
-    /// syntax error in the expression as part of query
-    pub fn syntax_error(expression: String) -> QueryError {
+    /// recursion of function calls is too deep error constructor
+    pub fn stack_depth_limit_exceeded() -> QueryError {
         QueryError {
             severity: Severity::Error,
-            kind: QueryErrorKind::SyntaxError(expression),
+            kind: QueryErrorKind::StackDepthLimitExceeded,
+            detail: None,
+            hint: None,
         }
     }
 
-    /// operator or function is not found for operands
-    pub fn undefined_function(operator: String, left_type: String, right_type: String) -> QueryError {
+    /// insufficient privilege error constructor
+    pub fn permission_denied(message: String) -> QueryError {
         QueryError {
             severity: Severity::Error,
-            kind: QueryErrorKind::UndefinedFunction {
-                operator,
-                left_type,
-                right_type,
-            },
+            kind: QueryErrorKind::PermissionDenied(message),
+            detail: None,
+            hint: None,
         }
     }
 
-    /// numeric out of range constructor
-    pub fn out_of_range(pg_type: PostgreSqlType, column_name: String, row_index: usize) -> QueryError {
+    /// user already exists error constructor
+    pub fn user_already_exists(user_name: String) -> QueryError {
         QueryError {
             severity: Severity::Error,
-            kind: QueryErrorKind::NumericTypeOutOfRange {
-                pg_type,
-                column_name,
-                row_index,
-            },
+            kind: QueryErrorKind::UserAlreadyExists(user_name),
+            detail: None,
+            hint: None,
         }
     }
 
-    /// type mismatch constructor
-    pub fn type_mismatch(value: &str, pg_type: PostgreSqlType, column_name: String, row_index: usize) -> QueryError {
+    /// user does not exist error constructor
+    pub fn user_does_not_exist(user_name: String) -> QueryError {
         QueryError {
             severity: Severity::Error,
-            kind: QueryErrorKind::DataTypeMismatch {
-                pg_type,
-                value: value.to_owned(),
-                column_name,
-                row_index,
-            },
+            kind: QueryErrorKind::UserDoesNotExist(user_name),
+            detail: None,
+            hint: None,
         }
     }
 
-    /// length of string types do not match constructor
-    pub fn string_length_mismatch(
-        pg_type: PostgreSqlType,
-        len: u64,
-        column_name: String,
-        row_index: usize,
-    ) -> QueryError {
+    /// function already exists error constructor
+    pub fn function_already_exists(function_name: String) -> QueryError {
         QueryError {
             severity: Severity::Error,
-            kind: QueryErrorKind::StringTypeLengthMismatch {
-                pg_type,
-                len,
-                column_name,
-                row_index,
+            kind: QueryErrorKind::FunctionAlreadyExists(function_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// function does not exist or can't be called with the arguments error constructor
+    pub fn function_does_not_exist(function_signature: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::FunctionDoesNotExist(function_signature),
+            detail: None,
+            hint: Some(
+                "No function matches the given name and argument types. You might need to add explicit type casts."
+                    .into(),
+            ),
+        }
+    }
+
+    /// trigger with the same name is already defined on the table error constructor
+    pub fn trigger_already_exists(trigger_name: String, table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::TriggerAlreadyExists {
+                trigger_name,
+                table_name,
             },
+            detail: None,
+            hint: None,
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// trigger does not exist error constructor
+    pub fn trigger_does_not_exist(trigger_name: String, table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::TriggerDoesNotExist {
+                trigger_name,
+                table_name,
+            },
+            detail: None,
+            hint: None,
+        }
+    }
 
-    #[cfg(test)]
-    mod query_event {
-        use super::*;
+    /// index does not exist error constructor
+    pub fn index_does_not_exist(index_name: String, table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::IndexDoesNotExist { index_name, table_name },
+            detail: None,
+            hint: None,
+        }
+    }
 
-        #[test]
-        fn create_schema() {
-            let messages: Vec<BackendMessage> = QueryEvent::SchemaCreated.into();
-            assert_eq!(
-                messages,
-                vec![BackendMessage::CommandComplete("CREATE SCHEMA".to_owned())]
-            )
+    /// constraint with the same name is already defined on the table error constructor
+    pub fn constraint_already_exists(constraint: String, table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::ConstraintAlreadyExists { constraint, table_name },
+            detail: None,
+            hint: None,
         }
+    }
 
-        #[test]
-        fn drop_schema() {
-            let messages: Vec<BackendMessage> = QueryEvent::SchemaDropped.into();
-            assert_eq!(
-                messages,
-                vec![BackendMessage::CommandComplete("DROP SCHEMA".to_owned())]
-            )
+    /// constraint does not exist error constructor
+    pub fn constraint_does_not_exist(constraint: String, table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::ConstraintDoesNotExist { constraint, table_name },
+            detail: None,
+            hint: None,
         }
+    }
 
-        #[test]
-        fn create_table() {
-            let messages: Vec<BackendMessage> = QueryEvent::TableCreated.into();
-            assert_eq!(
-                messages,
-                vec![BackendMessage::CommandComplete("CREATE TABLE".to_owned())]
-            );
+    /// column with the same name is already defined in the table error constructor
+    pub fn column_already_exists(column_name: String, table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::ColumnAlreadyExists {
+                column_name,
+                table_name,
+            },
+            detail: None,
+            hint: None,
         }
+    }
 
-        #[test]
-        fn drop_table() {
-            let messages: Vec<BackendMessage> = QueryEvent::TableDropped.into();
-            assert_eq!(messages, vec![BackendMessage::CommandComplete("DROP TABLE".to_owned())]);
+    /// definition of a function or a trigger can't be used error constructor
+    pub fn invalid_object_definition(message: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::InvalidObjectDefinition(message),
+            detail: None,
+            hint: None,
         }
+    }
 
-        #[test]
-        fn insert_record() {
-            let records_number = 3;
-            let messages: Vec<BackendMessage> = QueryEvent::RecordsInserted(records_number).into();
-            assert_eq!(
-                messages,
-                vec![BackendMessage::CommandComplete(format!("INSERT 0 {}", records_number))]
-            )
+    /// partition is attached to a table that is not partitioned error constructor
+    pub fn table_is_not_partitioned(table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::TableIsNotPartitioned(table_name),
+            detail: None,
+            hint: None,
         }
+    }
+
+    /// value of the partition key is out of ranges of all partitions error constructor
+    pub fn no_partition_for_row(table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::NoPartitionForRow(table_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// detached table is not a partition of the partitioned table error constructor
+    pub fn not_a_partition(partition: String, table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::NotAPartition { partition, table_name },
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// attached table is already a partition of a partitioned table error constructor
+    pub fn already_a_partition(table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::AlreadyAPartition(table_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// rows violate a constraint, e.g. bounds of a partition, error constructor
+    pub fn check_violation(message: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::CheckViolation(message),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// NULL is assigned to a column with NOT NULL constraint error constructor
+    pub fn not_null_violation(column_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::NotNullViolation(column_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// lock on a table can't be acquired immediately error constructor
+    pub fn lock_not_available(table_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::LockNotAvailable(table_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// too many insert expressions errors constructors
+    pub fn too_many_insert_expressions() -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::TooManyInsertExpressions,
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// value is provided for a generated column error constructor
+    pub fn generated_column_cannot_be_written(column_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::GeneratedAlways(column_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// data of COPY can't be split into rows of table columns error constructor
+    pub fn bad_copy_file_format(message: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::BadCopyFileFormat(message),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// file on the server does not exist error constructor
+    pub fn undefined_file(file_name: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::UndefinedFile(file_name),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// file on the server can't be opened or read error constructor
+    pub fn io_error(message: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::IoError(message),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// execution is aborted on the client request error constructor
+    pub fn query_canceled(message: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::QueryCanceled(message),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// execution is aborted because the query runs longer than allowed error constructor
+    pub fn query_timeout() -> QueryError {
+        Self::query_canceled("canceling statement due to statement timeout".to_owned())
+    }
+
+    /// execution is aborted because another session requested to cancel it error constructor
+    pub fn canceled_by_user() -> QueryError {
+        Self::query_canceled("canceling statement due to user request".to_owned())
+    }
+
+    /// syntax error in the expression as part of query
+    pub fn syntax_error(expression: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::SyntaxError(expression),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// negative LIMIT error constructor
+    pub fn invalid_row_count_in_limit_clause() -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::InvalidRowCountInLimitClause,
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// negative OFFSET error constructor
+    pub fn invalid_row_count_in_result_offset_clause() -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::InvalidRowCountInResultOffsetClause,
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// LIMIT or OFFSET evaluated to not an integer value error constructor
+    pub fn non_integer_row_count(clause: String, value: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::NonIntegerRowCount { clause, value },
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// operator or function is not found for operands
+    pub fn undefined_function(operator: String, left_type: String, right_type: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::UndefinedFunction {
+                operator,
+                left_type,
+                right_type,
+            },
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// numeric out of range constructor
+    pub fn out_of_range(pg_type: PostgreSqlType, column_name: String, row_index: usize) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::NumericTypeOutOfRange {
+                pg_type,
+                column_name,
+                row_index,
+            },
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// type mismatch constructor
+    pub fn type_mismatch(value: &str, pg_type: PostgreSqlType, column_name: String, row_index: usize) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::DataTypeMismatch {
+                pg_type,
+                value: value.to_owned(),
+                column_name,
+                row_index,
+            },
+            detail: None,
+            hint: compatible_values(pg_type).map(|values| format!("{} accepts {}", pg_type, values).into_boxed_str()),
+        }
+    }
+
+    /// value converted to a numeric type is out of its range constructor
+    pub fn value_out_of_range(pg_type: PostgreSqlType) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::ValueOutOfRange(pg_type),
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// text that can't be converted to a value of the type constructor
+    pub fn invalid_text_representation(pg_type: PostgreSqlType, value: String) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::InvalidTextRepresentation { pg_type, value },
+            detail: None,
+            hint: None,
+        }
+    }
+
+    /// length of string types do not match constructor
+    pub fn string_length_mismatch(
+        pg_type: PostgreSqlType,
+        len: u64,
+        column_name: String,
+        row_index: usize,
+    ) -> QueryError {
+        QueryError {
+            severity: Severity::Error,
+            kind: QueryErrorKind::StringTypeLengthMismatch {
+                pg_type,
+                len,
+                column_name,
+                row_index,
+            },
+            detail: None,
+            hint: None,
+        }
+    }
+}
+
+// values that could be assigned to a column of the type, string types accept any value
+fn compatible_values(pg_type: PostgreSqlType) -> Option<&'static str> {
+    match pg_type {
+        PostgreSqlType::SmallInt | PostgreSqlType::Integer | PostgreSqlType::BigInt => Some("integer numbers"),
+        PostgreSqlType::Decimal | PostgreSqlType::Real | PostgreSqlType::DoublePrecision => Some("numbers"),
+        PostgreSqlType::Bool => Some("true, false, yes, no, on, off, 1 or 0"),
+        PostgreSqlType::Bytea => Some("hex or escaped strings"),
+        PostgreSqlType::Json => Some("JSON documents"),
+        PostgreSqlType::Date
+        | PostgreSqlType::Time
+        | PostgreSqlType::TimeWithTimeZone
+        | PostgreSqlType::Timestamp
+        | PostgreSqlType::TimestampWithTimeZone
+        | PostgreSqlType::Interval => Some("ISO 8601 formatted strings"),
+        PostgreSqlType::BoolArray
+        | PostgreSqlType::SmallIntArray
+        | PostgreSqlType::IntegerArray
+        | PostgreSqlType::BigIntArray
+        | PostgreSqlType::CharArray
+        | PostgreSqlType::VarCharArray => Some("array literals, e.g. '{1,2,3}'"),
+        PostgreSqlType::Char | PostgreSqlType::VarChar => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(test)]
+    mod query_event {
+        use super::*;
 
         #[test]
-        fn select_records() {
-            let projection = (
-                vec![
-                    ("column_name_1".to_owned(), PostgreSqlType::SmallInt),
-                    ("column_name_2".to_owned(), PostgreSqlType::SmallInt),
-                ],
-                vec![
-                    vec!["1".to_owned(), "2".to_owned()],
-                    vec!["3".to_owned(), "4".to_owned()],
-                ],
+        fn create_schema() {
+            let messages: Vec<BackendMessage> = QueryEvent::SchemaCreated.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("CREATE SCHEMA".to_owned())]
+            )
+        }
+
+        #[test]
+        fn drop_schema() {
+            let messages: Vec<BackendMessage> = QueryEvent::SchemaDropped.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("DROP SCHEMA".to_owned())]
+            )
+        }
+
+        #[test]
+        fn alter_schema() {
+            let messages: Vec<BackendMessage> = QueryEvent::SchemaAltered.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("ALTER SCHEMA".to_owned())]
+            )
+        }
+
+        #[test]
+        fn create_table() {
+            let messages: Vec<BackendMessage> = QueryEvent::TableCreated.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("CREATE TABLE".to_owned())]
             );
-            let messages: Vec<BackendMessage> = QueryEvent::RecordsSelected(projection).into();
+        }
+
+        #[test]
+        fn drop_table() {
+            let messages: Vec<BackendMessage> = QueryEvent::TableDropped.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("DROP TABLE".to_owned())]);
+        }
+
+        #[test]
+        fn create_user() {
+            let messages: Vec<BackendMessage> = QueryEvent::UserCreated.into();
             assert_eq!(
                 messages,
-                vec![
-                    BackendMessage::RowDescription(vec![
-                        ColumnMetadata::new("column_name_1".to_owned(), 21, 2),
-                        ColumnMetadata::new("column_name_2".to_owned(), 21, 2)
-                    ]),
-                    BackendMessage::DataRow(vec!["1".to_owned(), "2".to_owned()]),
-                    BackendMessage::DataRow(vec!["3".to_owned(), "4".to_owned()]),
-                    BackendMessage::CommandComplete("SELECT 2".to_owned())
-                ]
+                vec![BackendMessage::CommandComplete("CREATE ROLE".to_owned())]
             );
         }
 
         #[test]
-        fn update_records() {
-            let records_number = 3;
-            let messages: Vec<BackendMessage> = QueryEvent::RecordsUpdated(records_number).into();
+        fn drop_user() {
+            let messages: Vec<BackendMessage> = QueryEvent::UserDropped.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("DROP ROLE".to_owned())]);
+        }
+
+        #[test]
+        fn create_function() {
+            let messages: Vec<BackendMessage> = QueryEvent::FunctionCreated.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("CREATE FUNCTION".to_owned())]
+            );
+        }
+
+        #[test]
+        fn drop_function() {
+            let messages: Vec<BackendMessage> = QueryEvent::FunctionDropped.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("DROP FUNCTION".to_owned())]
+            );
+        }
+
+        #[test]
+        fn create_trigger() {
+            let messages: Vec<BackendMessage> = QueryEvent::TriggerCreated.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("CREATE TRIGGER".to_owned())]
+            );
+        }
+
+        #[test]
+        fn drop_trigger() {
+            let messages: Vec<BackendMessage> = QueryEvent::TriggerDropped.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("DROP TRIGGER".to_owned())]
+            );
+        }
+
+        #[test]
+        fn alter_table() {
+            let messages: Vec<BackendMessage> = QueryEvent::TableAltered.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("ALTER TABLE".to_owned())]
+            );
+        }
+
+        #[test]
+        fn grant() {
+            let messages: Vec<BackendMessage> = QueryEvent::PrivilegesGranted.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("GRANT".to_owned())]);
+        }
+
+        #[test]
+        fn revoke() {
+            let messages: Vec<BackendMessage> = QueryEvent::PrivilegesRevoked.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("REVOKE".to_owned())]);
+        }
+
+        #[test]
+        fn listen() {
+            let messages: Vec<BackendMessage> = QueryEvent::ChannelListened.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("LISTEN".to_owned())])
+        }
+
+        #[test]
+        fn declare_cursor() {
+            let messages: Vec<BackendMessage> = QueryEvent::CursorDeclared.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("DECLARE CURSOR".to_owned())]
+            )
+        }
+
+        #[test]
+        fn close_cursor() {
+            let messages: Vec<BackendMessage> = QueryEvent::CursorClosed.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("CLOSE CURSOR".to_owned())]
+            )
+        }
+
+        #[test]
+        fn notification() {
+            let messages: Vec<BackendMessage> = QueryEvent::Notification {
+                pid: 1,
+                channel: "channel".to_owned(),
+                payload: "payload".to_owned(),
+            }
+            .into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::NotificationResponse(
+                    1,
+                    "channel".to_owned(),
+                    "payload".to_owned()
+                )]
+            )
+        }
+
+        #[test]
+        fn notice() {
+            let messages: Vec<BackendMessage> = QueryEvent::Notice {
+                severity: "NOTICE".to_owned(),
+                message: "message".to_owned(),
+            }
+            .into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::NoticeResponse(
+                    "NOTICE".to_owned(),
+                    "message".to_owned()
+                )]
+            );
+        }
+
+        #[test]
+        fn error_as_warning() {
+            let messages: Vec<BackendMessage> = QueryError::table_does_not_exist("schema_name.table_name".to_owned())
+                .into_warning()
+                .into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::NoticeResponse(
+                    "WARNING".to_owned(),
+                    "table \"schema_name.table_name\" does not exist".to_owned()
+                )]
+            );
+        }
+
+        #[test]
+        fn commit_transaction() {
+            let messages: Vec<BackendMessage> = QueryEvent::TransactionCommitted.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("COMMIT".to_owned())]);
+        }
+
+        #[test]
+        fn set_constraints() {
+            let messages: Vec<BackendMessage> = QueryEvent::ConstraintsSet.into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete("SET CONSTRAINTS".to_owned())]
+            );
+        }
+
+        #[test]
+        fn lock_table() {
+            let messages: Vec<BackendMessage> = QueryEvent::TableLocked.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("LOCK TABLE".to_owned())]);
+        }
+
+        #[test]
+        fn analyze() {
+            let messages: Vec<BackendMessage> = QueryEvent::AnalyzeComplete.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("ANALYZE".to_owned())]);
+        }
+
+        #[test]
+        fn syntax_valid() {
+            let messages: Vec<BackendMessage> = QueryEvent::SyntaxValid.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("EXPLAIN".to_owned())]);
+        }
+
+        #[test]
+        fn cluster() {
+            let messages: Vec<BackendMessage> = QueryEvent::TableClustered.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("CLUSTER".to_owned())]);
+        }
+
+        #[test]
+        fn vacuum() {
+            let messages: Vec<BackendMessage> = QueryEvent::VacuumComplete { rows_removed: 3 }.into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("VACUUM".to_owned())]);
+        }
+
+        #[test]
+        fn copy_in_started() {
+            let messages: Vec<BackendMessage> = QueryEvent::CopyInStarted(2).into();
+            assert_eq!(messages, vec![BackendMessage::CopyInResponse(2)]);
+        }
+
+        #[test]
+        fn records_copied() {
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsCopied {
+                columns: 2,
+                rows: vec!["1\ta\n".to_owned(), "2\tb\n".to_owned()],
+            }
+            .into();
+            assert_eq!(
+                messages,
+                vec![
+                    BackendMessage::CopyOutResponse(2),
+                    BackendMessage::CopyData("1\ta\n".to_owned()),
+                    BackendMessage::CopyData("2\tb\n".to_owned()),
+                    BackendMessage::CopyDone,
+                    BackendMessage::CommandComplete("COPY 2".to_owned()),
+                ]
+            );
+        }
+
+        #[test]
+        fn insert_record() {
+            let records_number = 3;
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsInserted(records_number).into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete(format!("INSERT 0 {}", records_number))]
+            )
+        }
+
+        #[test]
+        fn select_records() {
+            let projection = (
+                vec![
+                    ("column_name_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("column_name_2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                vec![
+                    vec![Some("1".to_owned()), None],
+                    vec![Some("3".to_owned()), Some("4".to_owned())],
+                ],
+            );
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsSelected(projection).into();
+            assert_eq!(
+                messages,
+                vec![
+                    BackendMessage::RowDescription(vec![
+                        ColumnMetadata::new("column_name_1".to_owned(), 21, 2),
+                        ColumnMetadata::new("column_name_2".to_owned(), 21, 2)
+                    ]),
+                    BackendMessage::DataRow(vec![Some("1".to_owned()), None]),
+                    BackendMessage::DataRow(vec![Some("3".to_owned()), Some("4".to_owned())]),
+                    BackendMessage::CommandComplete("SELECT 2".to_owned())
+                ]
+            );
+        }
+
+        #[test]
+        fn select_records_completes_with_number_of_rows() {
+            let projection = (
+                vec![("column_name".to_owned(), PostgreSqlType::Integer, -1)],
+                vec![vec![Some("1".to_owned())], vec![None], vec![Some("3".to_owned())]],
+            );
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsSelected(projection).into();
+            assert_eq!(
+                messages.last(),
+                Some(&BackendMessage::CommandComplete("SELECT 3".to_owned()))
+            );
+        }
+
+        #[test]
+        fn fetch_records() {
+            let projection = (
+                vec![("column_name".to_owned(), PostgreSqlType::SmallInt, -1)],
+                vec![vec![Some("1".to_owned())]],
+            );
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsFetched(projection).into();
+            assert_eq!(
+                messages,
+                vec![
+                    BackendMessage::RowDescription(vec![ColumnMetadata::new("column_name".to_owned(), 21, 2)]),
+                    BackendMessage::DataRow(vec![Some("1".to_owned())]),
+                    BackendMessage::CommandComplete("FETCH 1".to_owned())
+                ]
+            );
+        }
+
+        #[test]
+        fn select_records_describes_columns_with_type_oids() {
+            let projection = (
+                vec![
+                    ("small".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("int".to_owned(), PostgreSqlType::Integer, -1),
+                    ("big".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("name".to_owned(), PostgreSqlType::VarChar, 14),
+                ],
+                vec![],
+            );
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsSelected(projection).into();
+            assert_eq!(
+                messages,
+                vec![
+                    BackendMessage::RowDescription(vec![
+                        ColumnMetadata::new("small".to_owned(), 21, 2),
+                        ColumnMetadata::new("int".to_owned(), 23, 4),
+                        ColumnMetadata::new("big".to_owned(), 20, 8),
+                        ColumnMetadata::new("name".to_owned(), 1043, -1).with_type_modifier(14),
+                    ]),
+                    BackendMessage::CommandComplete("SELECT 0".to_owned())
+                ]
+            );
+        }
+
+        #[test]
+        fn update_records() {
+            let records_number = 3;
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsUpdated(records_number).into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete(format!("UPDATE {}", records_number))]
+            );
+        }
+
+        #[test]
+        fn delete_records() {
+            let records_number = 3;
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsDeleted(records_number).into();
+            assert_eq!(
+                messages,
+                vec![BackendMessage::CommandComplete(format!("DELETE {}", records_number))]
+            )
+        }
+
+        #[test]
+        fn merge_records() {
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsMerged(3).into();
+            assert_eq!(messages, vec![BackendMessage::CommandComplete("MERGE 3".to_owned())])
+        }
+
+        #[test]
+        fn delete_records_returning() {
+            let projection = (
+                vec![("id".to_owned(), PostgreSqlType::SmallInt, -1)],
+                vec![vec![Some("1".to_owned())], vec![None]],
+            );
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsDeletedReturning(projection).into();
+            assert_eq!(
+                messages,
+                vec![
+                    BackendMessage::RowDescription(vec![ColumnMetadata::new("id".to_owned(), 21, 2)]),
+                    BackendMessage::DataRow(vec![Some("1".to_owned())]),
+                    BackendMessage::DataRow(vec![None]),
+                    BackendMessage::CommandComplete("DELETE 2".to_owned())
+                ]
+            )
+        }
+
+        #[test]
+        fn describe_prepared_statement() {
+            let messages: Vec<BackendMessage> = QueryEvent::PreparedStatementDescribed(
+                vec![PostgreSqlType::SmallInt],
+                vec![("si_column".to_owned(), PostgreSqlType::SmallInt, -1)],
+            )
+            .into();
+            assert_eq!(
+                messages,
+                [
+                    BackendMessage::ParameterDescription(vec![21]),
+                    BackendMessage::RowDescription(vec![ColumnMetadata {
+                        name: "si_column".to_owned(),
+                        type_id: 21,
+                        type_size: 2,
+                        type_modifier: -1
+                    }])
+                ]
+            )
+        }
+
+        #[test]
+        fn empty_query() {
+            let messages: Vec<BackendMessage> = QueryEvent::EmptyQueryResponse.into();
+            assert_eq!(messages, [BackendMessage::EmptyQueryResponse])
+        }
+
+        #[test]
+        fn complete_query() {
+            let messages: Vec<BackendMessage> = QueryEvent::QueryComplete.into();
+            assert_eq!(messages, [BackendMessage::ReadyForQuery])
+        }
+
+        #[test]
+        fn complete_parse() {
+            let messages: Vec<BackendMessage> = QueryEvent::ParseComplete.into();
+            assert_eq!(messages, [BackendMessage::ParseComplete])
+        }
+
+        #[test]
+        fn complete_bind() {
+            let messages: Vec<BackendMessage> = QueryEvent::BindComplete.into();
+            assert_eq!(messages, [BackendMessage::BindComplete])
+        }
+    }
+
+    #[cfg(test)]
+    mod query_error {
+        use super::*;
+
+        #[test]
+        fn schema_already_exists() {
+            let schema_name = "some_table_name".to_owned();
+            let message: BackendMessage = QueryError::schema_already_exists(schema_name.clone()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P06"),
+                    Some(format!("schema \"{}\" already exists", schema_name)),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn schema_does_not_exists() {
+            let schema_name = "some_table_name".to_owned();
+            let message: BackendMessage = QueryError::schema_does_not_exist(schema_name.clone()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("3F000"),
+                    Some(format!("schema \"{}\" does not exist", schema_name)),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn table_already_exists() {
+            let table_name = "some_table_name".to_owned();
+            let message: BackendMessage = QueryError::table_already_exists(table_name.clone()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P07"),
+                    Some(format!("table \"{}\" already exists", table_name)),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn table_does_not_exists() {
+            let table_name = "some_table_name".to_owned();
+            let message: BackendMessage = QueryError::table_does_not_exist(table_name.clone()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P01"),
+                    Some(format!("table \"{}\" does not exist", table_name)),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn one_column_does_not_exists() {
+            let message: BackendMessage =
+                QueryError::column_does_not_exist(vec!["column_not_in_table".to_owned()]).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42703"),
+                    Some("column column_not_in_table does not exist".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn multiple_columns_does_not_exists() {
+            let message: BackendMessage = QueryError::column_does_not_exist(vec![
+                "column_not_in_table1".to_owned(),
+                "column_not_in_table2".to_owned(),
+            ])
+            .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42703"),
+                    Some("columns column_not_in_table1, column_not_in_table2 do not exist".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn ambiguous_column() {
+            let message: BackendMessage = QueryError::ambiguous_column("id".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42702"),
+                    Some("column reference \"id\" is ambiguous".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn unique_violation() {
+            let message: BackendMessage = QueryError::unique_violation("t_id_key".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("23505"),
+                    Some("duplicate key value violates unique constraint \"t_id_key\"".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn grouping_error() {
+            let message: BackendMessage = QueryError::grouping_error("val".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42803"),
+                    Some(
+                        "column \"val\" must appear in the GROUP BY clause or be used in an aggregate function"
+                            .to_owned()
+                    ),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_column_reference() {
+            let message: BackendMessage = QueryError::invalid_column_reference(
+                "there is no unique or exclusion constraint matching the ON CONFLICT specification".to_owned(),
+            )
+            .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P10"),
+                    Some(
+                        "there is no unique or exclusion constraint matching the ON CONFLICT specification".to_owned()
+                    ),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_identifier() {
+            let message: BackendMessage = QueryError::invalid_identifier("../etc".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42602"),
+                    Some("\"../etc\" is not a valid identifier".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn cardinality_violation() {
+            let message: BackendMessage = QueryError::cardinality_violation(
+                "ON CONFLICT DO UPDATE command cannot affect row a second time".to_owned(),
+            )
+            .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("21000"),
+                    Some("ON CONFLICT DO UPDATE command cannot affect row a second time".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_row_count_in_limit_clause() {
+            let message: BackendMessage = QueryError::invalid_row_count_in_limit_clause().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2201W"),
+                    Some("LIMIT must not be negative".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_row_count_in_result_offset_clause() {
+            let message: BackendMessage = QueryError::invalid_row_count_in_result_offset_clause().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2201X"),
+                    Some("OFFSET must not be negative".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn non_integer_row_count() {
+            let message: BackendMessage =
+                QueryError::non_integer_row_count("LIMIT".to_owned(), "2.5".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42804"),
+                    Some("argument of LIMIT must be an integer, not 2.5".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_parameter_value() {
+            let messages: BackendMessage =
+                QueryError::invalid_parameter_value("Wrong parameter value".to_owned()).into();
+            assert_eq!(
+                messages,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22023"),
+                    Some("Wrong parameter value".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn prepared_statement_does_not_exists() {
+            let messages: BackendMessage =
+                QueryError::prepared_statement_does_not_exist("statement_name".to_owned()).into();
+            assert_eq!(
+                messages,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("26000"),
+                    Some("prepared statement statement_name does not exist".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn portal_does_not_exists() {
+            let messages: BackendMessage = QueryError::portal_does_not_exist("portal_name".to_owned()).into();
+            assert_eq!(
+                messages,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("26000"),
+                    Some("portal portal_name does not exist".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn cursor_already_exists() {
+            let message: BackendMessage = QueryError::cursor_already_exists("cur".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42P03"),
+                    Some("cursor \"cur\" already exists".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn cursor_does_not_exist() {
+            let message: BackendMessage = QueryError::cursor_does_not_exist("cur".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("34000"),
+                    Some("cursor \"cur\" does not exist".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn protocol_violation() {
+            let messages: BackendMessage = QueryError::protocol_violation("Wrong protocol data".to_owned()).into();
+            assert_eq!(
+                messages,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("08P01"),
+                    Some("Wrong protocol data".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn feature_not_supported() {
+            let raw_sql_query = "some SQL query".to_owned();
+            let message: BackendMessage = QueryError::feature_not_supported(raw_sql_query.clone()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("0A000"),
+                    Some(format!("Currently, Query '{}' can't be executed", raw_sql_query)),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn too_many_connections() {
+            let message: BackendMessage = QueryError::too_many_connections().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("FATAL"),
+                    Some("53300"),
+                    Some("sorry, too many clients already".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn program_limit_exceeded() {
+            let message: BackendMessage = QueryError::program_limit_exceeded(
+                "query returns more than 10 rows allowed by statement_max_rows".to_owned(),
+            )
+            .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("54000"),
+                    Some("query returns more than 10 rows allowed by statement_max_rows".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn stack_depth_limit_exceeded() {
+            let message: BackendMessage = QueryError::stack_depth_limit_exceeded().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("54001"),
+                    Some("stack depth limit exceeded".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn permission_denied() {
+            let message: BackendMessage =
+                QueryError::permission_denied("permission denied for table table_name".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42501"),
+                    Some("permission denied for table table_name".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn lock_not_available() {
+            let message: BackendMessage = QueryError::lock_not_available("table_name".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("55P03"),
+                    Some("could not obtain lock on relation \"table_name\"".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn user_already_exists() {
+            let message: BackendMessage = QueryError::user_already_exists("user_name".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42710"),
+                    Some("role \"user_name\" already exists".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn user_does_not_exist() {
+            let message: BackendMessage = QueryError::user_does_not_exist("user_name".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42704"),
+                    Some("role \"user_name\" does not exist".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn function_already_exists() {
+            let message: BackendMessage =
+                QueryError::function_already_exists("schema_name.function_name".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42723"),
+                    Some("function \"schema_name.function_name\" already exists".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn function_does_not_exist() {
+            let message: BackendMessage =
+                QueryError::function_does_not_exist("schema_name.function_name(integer)".to_owned()).into();
             assert_eq!(
-                messages,
-                vec![BackendMessage::CommandComplete(format!("UPDATE {}", records_number))]
-            );
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42883"),
+                    Some("function schema_name.function_name(integer) does not exist".to_owned()),
+                    None,
+                    Some(
+                        "No function matches the given name and argument types. \
+                         You might need to add explicit type casts."
+                            .to_owned()
+                    ),
+                )
+            )
         }
 
         #[test]
-        fn delete_records() {
-            let records_number = 3;
-            let messages: Vec<BackendMessage> = QueryEvent::RecordsDeleted(records_number).into();
+        fn foreign_key_violation() {
+            let message: BackendMessage =
+                QueryError::foreign_key_violation("orders".to_owned(), "fk_customer".to_owned())
+                    .with_detail("Key (customer_id)=(5) is not present in table \"customers\".".to_owned())
+                    .into();
             assert_eq!(
-                messages,
-                vec![BackendMessage::CommandComplete(format!("DELETE {}", records_number))]
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("23503"),
+                    Some(
+                        "insert or update on table \"orders\" violates foreign key constraint \"fk_customer\""
+                            .to_owned()
+                    ),
+                    Some("Key (customer_id)=(5) is not present in table \"customers\".".to_owned()),
+                    None,
+                )
             )
         }
 
         #[test]
-        fn describe_prepared_statement() {
-            let messages: Vec<BackendMessage> = QueryEvent::PreparedStatementDescribed(
-                vec![PostgreSqlType::SmallInt],
-                vec![("si_column".to_owned(), PostgreSqlType::SmallInt)],
+        fn referenced_key_violation() {
+            let message: BackendMessage = QueryError::referenced_key_violation(
+                "customers".to_owned(),
+                "fk_customer".to_owned(),
+                "orders".to_owned(),
             )
+            .with_detail("Key (id)=(5) is still referenced from table \"orders\".".to_owned())
             .into();
             assert_eq!(
-                messages,
-                [
-                    BackendMessage::ParameterDescription(vec![21]),
-                    BackendMessage::RowDescription(vec![ColumnMetadata {
-                        name: "si_column".to_owned(),
-                        type_id: 21,
-                        type_size: 2
-                    }])
-                ]
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("23503"),
+                    Some(
+                        "update or delete on table \"customers\" violates foreign key constraint \"fk_customer\" on table \"orders\""
+                            .to_owned()
+                    ),
+                    Some("Key (id)=(5) is still referenced from table \"orders\".".to_owned()),
+                    None,
+                )
             )
         }
 
         #[test]
-        fn complete_query() {
-            let messages: Vec<BackendMessage> = QueryEvent::QueryComplete.into();
-            assert_eq!(messages, [BackendMessage::ReadyForQuery])
+        fn table_has_dependent_objects() {
+            let message: BackendMessage = QueryError::table_has_dependent_objects("customers".to_owned())
+                .with_detail("constraint fk_customer on table orders depends on table customers".to_owned())
+                .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2BP01"),
+                    Some("cannot drop table customers because other objects depend on it".to_owned()),
+                    Some("constraint fk_customer on table orders depends on table customers".to_owned()),
+                    Some("Use DROP ... CASCADE to drop the dependent objects too.".to_owned()),
+                )
+            )
         }
 
         #[test]
-        fn complete_parse() {
-            let messages: Vec<BackendMessage> = QueryEvent::ParseComplete.into();
-            assert_eq!(messages, [BackendMessage::ParseComplete])
+        fn invalid_foreign_key() {
+            let message: BackendMessage = QueryError::invalid_foreign_key(
+                "there is no unique constraint matching given keys for referenced table \"customers\"".to_owned(),
+            )
+            .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42830"),
+                    Some(
+                        "there is no unique constraint matching given keys for referenced table \"customers\""
+                            .to_owned()
+                    ),
+                    None,
+                    None,
+                )
+            )
         }
 
         #[test]
-        fn complete_bind() {
-            let messages: Vec<BackendMessage> = QueryEvent::BindComplete.into();
-            assert_eq!(messages, [BackendMessage::BindComplete])
+        fn constraint_already_exists() {
+            let message: BackendMessage =
+                QueryError::constraint_already_exists("fk_customer".to_owned(), "orders".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42710"),
+                    Some("constraint \"fk_customer\" for relation \"orders\" already exists".to_owned()),
+                    None,
+                    None,
+                )
+            )
         }
-    }
 
-    #[cfg(test)]
-    mod query_error {
-        use super::*;
+        #[test]
+        fn column_already_exists() {
+            let message: BackendMessage =
+                QueryError::column_already_exists("customer_id".to_owned(), "orders".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("42701"),
+                    Some("column \"customer_id\" of relation \"orders\" already exists".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
 
         #[test]
-        fn schema_already_exists() {
-            let schema_name = "some_table_name".to_owned();
-            let message: BackendMessage = QueryError::schema_already_exists(schema_name.clone()).into();
+        fn constraint_does_not_exist() {
+            let message: BackendMessage =
+                QueryError::constraint_does_not_exist("fk_customer".to_owned(), "orders".to_owned()).into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("42P06"),
-                    Some(format!("schema \"{}\" already exists", schema_name)),
+                    Some("42704"),
+                    Some("constraint \"fk_customer\" of relation \"orders\" does not exist".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
 
         #[test]
-        fn schema_does_not_exists() {
-            let schema_name = "some_table_name".to_owned();
-            let message: BackendMessage = QueryError::schema_does_not_exist(schema_name.clone()).into();
+        fn trigger_already_exists() {
+            let message: BackendMessage =
+                QueryError::trigger_already_exists("trigger_name".to_owned(), "table_name".to_owned()).into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("3F000"),
-                    Some(format!("schema \"{}\" does not exist", schema_name)),
+                    Some("42710"),
+                    Some("trigger \"trigger_name\" for relation \"table_name\" already exists".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
 
         #[test]
-        fn table_already_exists() {
-            let table_name = "some_table_name".to_owned();
-            let message: BackendMessage = QueryError::table_already_exists(table_name.clone()).into();
+        fn trigger_does_not_exist() {
+            let message: BackendMessage =
+                QueryError::trigger_does_not_exist("trigger_name".to_owned(), "table_name".to_owned()).into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("42P07"),
-                    Some(format!("table \"{}\" already exists", table_name)),
+                    Some("42704"),
+                    Some("trigger \"trigger_name\" for table \"table_name\" does not exist".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
 
         #[test]
-        fn table_does_not_exists() {
-            let table_name = "some_table_name".to_owned();
-            let message: BackendMessage = QueryError::table_does_not_exist(table_name.clone()).into();
+        fn index_does_not_exist() {
+            let message: BackendMessage =
+                QueryError::index_does_not_exist("index_name".to_owned(), "table_name".to_owned()).into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("42P01"),
-                    Some(format!("table \"{}\" does not exist", table_name)),
+                    Some("42704"),
+                    Some("index \"index_name\" for table \"table_name\" does not exist".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
 
         #[test]
-        fn one_column_does_not_exists() {
+        fn invalid_object_definition() {
             let message: BackendMessage =
-                QueryError::column_does_not_exist(vec!["column_not_in_table".to_owned()]).into();
+                QueryError::invalid_object_definition("trigger functions can only be called as triggers".to_owned())
+                    .into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("42703"),
-                    Some("column column_not_in_table does not exist".to_owned()),
+                    Some("42P17"),
+                    Some("trigger functions can only be called as triggers".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
 
         #[test]
-        fn multiple_columns_does_not_exists() {
-            let message: BackendMessage = QueryError::column_does_not_exist(vec![
-                "column_not_in_table1".to_owned(),
-                "column_not_in_table2".to_owned(),
-            ])
-            .into();
+        fn table_is_not_partitioned() {
+            let message: BackendMessage = QueryError::table_is_not_partitioned("table_name".to_owned()).into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("42703"),
-                    Some("columns column_not_in_table1, column_not_in_table2 do not exist".to_owned()),
+                    Some("42809"),
+                    Some("table \"table_name\" is not partitioned".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
 
         #[test]
-        fn invalid_parameter_value() {
-            let messages: BackendMessage =
-                QueryError::invalid_parameter_value("Wrong parameter value".to_owned()).into();
+        fn no_partition_for_row() {
+            let message: BackendMessage = QueryError::no_partition_for_row("table_name".to_owned()).into();
             assert_eq!(
-                messages,
-                BackendMessage::ErrorResponse(Some("ERROR"), Some("22023"), Some("Wrong parameter value".to_owned()),)
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("23514"),
+                    Some("no partition of relation \"table_name\" found for row".to_owned()),
+                    None,
+                    None,
+                )
             )
         }
 
         #[test]
-        fn prepared_statement_does_not_exists() {
-            let messages: BackendMessage =
-                QueryError::prepared_statement_does_not_exist("statement_name".to_owned()).into();
+        fn not_a_partition() {
+            let message: BackendMessage =
+                QueryError::not_a_partition("partition_name".to_owned(), "table_name".to_owned()).into();
             assert_eq!(
-                messages,
+                message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("26000"),
-                    Some("prepared statement statement_name does not exist".to_owned()),
+                    Some("42P01"),
+                    Some("relation \"partition_name\" is not a partition of relation \"table_name\"".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
 
         #[test]
-        fn portal_does_not_exists() {
-            let messages: BackendMessage = QueryError::portal_does_not_exist("portal_name".to_owned()).into();
+        fn already_a_partition() {
+            let message: BackendMessage = QueryError::already_a_partition("table_name".to_owned()).into();
             assert_eq!(
-                messages,
+                message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("26000"),
-                    Some("portal portal_name does not exist".to_owned()),
+                    Some("42809"),
+                    Some("\"table_name\" is already a partition".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
 
         #[test]
-        fn protocol_violation() {
-            let messages: BackendMessage = QueryError::protocol_violation("Wrong protocol data".to_owned()).into();
+        fn check_violation() {
+            let message: BackendMessage =
+                QueryError::check_violation("partition constraint is violated by some row".to_owned()).into();
             assert_eq!(
-                messages,
-                BackendMessage::ErrorResponse(Some("ERROR"), Some("08P01"), Some("Wrong protocol data".to_owned()),)
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("23514"),
+                    Some("partition constraint is violated by some row".to_owned()),
+                    None,
+                    None,
+                )
             )
         }
 
         #[test]
-        fn feature_not_supported() {
-            let raw_sql_query = "some SQL query".to_owned();
-            let message: BackendMessage = QueryError::feature_not_supported(raw_sql_query.clone()).into();
+        fn not_null_violation() {
+            let message: BackendMessage = QueryError::not_null_violation("col1".to_owned()).into();
             assert_eq!(
                 message,
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
-                    Some("0A000"),
-                    Some(format!("Currently, Query '{}' can't be executed", raw_sql_query)),
+                    Some("23502"),
+                    Some("null value in column \"col1\" violates not-null constraint".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
@@ -757,6 +2643,116 @@ mod tests {
                     Some("ERROR"),
                     Some("42601"),
                     Some("INSERT has more expressions than target columns".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn generated_column_cannot_be_written() {
+            let message: BackendMessage = QueryError::generated_column_cannot_be_written("total".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("428C9"),
+                    Some("column \"total\" is a generated column".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn undefined_file() {
+            let message: BackendMessage = QueryError::undefined_file("/tmp/data.csv".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("58P01"),
+                    Some("could not open file \"/tmp/data.csv\" for reading: No such file or directory".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn io_error() {
+            let message: BackendMessage =
+                QueryError::io_error("could not read from file \"/tmp/data.csv\": Is a directory".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("58030"),
+                    Some("could not read from file \"/tmp/data.csv\": Is a directory".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn bad_copy_file_format() {
+            let message: BackendMessage =
+                QueryError::bad_copy_file_format("extra data after last expected column".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22P04"),
+                    Some("extra data after last expected column".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn query_canceled() {
+            let message: BackendMessage =
+                QueryError::query_canceled("COPY from stdin failed: aborted".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("57014"),
+                    Some("COPY from stdin failed: aborted".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn query_timeout() {
+            let message: BackendMessage = QueryError::query_timeout().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("57014"),
+                    Some("canceling statement due to statement timeout".to_owned()),
+                    None,
+                    None,
+                )
+            )
+        }
+
+        #[test]
+        fn canceled_by_user() {
+            let message: BackendMessage = QueryError::canceled_by_user().into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("57014"),
+                    Some("canceling statement due to user request".to_owned()),
+                    None,
+                    None,
                 )
             )
         }
@@ -770,7 +2766,40 @@ mod tests {
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
                     Some("22003"),
-                    Some("smallint is out of range for column 'col1' at row 1".to_owned())
+                    Some("smallint is out of range for column 'col1' at row 1".to_owned()),
+                    None,
+                    None
+                )
+            )
+        }
+
+        #[test]
+        fn value_out_of_range() {
+            let message: BackendMessage = QueryError::value_out_of_range(PostgreSqlType::Integer).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22003"),
+                    Some("integer out of range".to_owned()),
+                    None,
+                    None
+                )
+            )
+        }
+
+        #[test]
+        fn invalid_text_representation() {
+            let message: BackendMessage =
+                QueryError::invalid_text_representation(PostgreSqlType::SmallInt, "2.7".to_owned()).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("22P02"),
+                    Some("invalid input syntax for type smallint: \"2.7\"".to_owned()),
+                    None,
+                    None
                 )
             )
         }
@@ -784,7 +2813,46 @@ mod tests {
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
                     Some("2200G"),
-                    Some("invalid input syntax for type smallint for column 'col1' at row 1: \"abc\"".to_owned())
+                    Some("invalid input syntax for type smallint for column 'col1' at row 1: \"abc\"".to_owned()),
+                    None,
+                    Some("smallint accepts integer numbers".to_owned())
+                )
+            )
+        }
+
+        #[test]
+        fn type_mismatch_of_string_type_has_no_hint() {
+            let message: BackendMessage =
+                QueryError::type_mismatch("abc", PostgreSqlType::VarChar, "col1".to_string(), 1).into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("2200G"),
+                    Some(
+                        "invalid input syntax for type variable character for column 'col1' at row 1: \"abc\""
+                            .to_owned()
+                    ),
+                    None,
+                    None
+                )
+            )
+        }
+
+        #[test]
+        fn with_detail_and_hint() {
+            let message: BackendMessage = QueryError::unique_violation("t_id_key".to_owned())
+                .with_detail("Key (id)=(1) already exists.".to_owned())
+                .with_hint("Use ON CONFLICT to update the existing row.".to_owned())
+                .into();
+            assert_eq!(
+                message,
+                BackendMessage::ErrorResponse(
+                    Some("ERROR"),
+                    Some("23505"),
+                    Some("duplicate key value violates unique constraint \"t_id_key\"".to_owned()),
+                    Some("Key (id)=(1) already exists.".to_owned()),
+                    Some("Use ON CONFLICT to update the existing row.".to_owned()),
                 )
             )
         }
@@ -798,7 +2866,9 @@ mod tests {
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
                     Some("22026"),
-                    Some("value too long for type character(5) for column 'col1' at row 1".to_owned())
+                    Some("value too long for type character(5) for column 'col1' at row 1".to_owned()),
+                    None,
+                    None
                 )
             )
         }
@@ -812,7 +2882,9 @@ mod tests {
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
                     Some("42883"),
-                    Some("operator does not exist: (NUMBER || NUMBER)".to_owned())
+                    Some("operator does not exist: (NUMBER || NUMBER)".to_owned()),
+                    None,
+                    None
                 )
             )
         }
@@ -825,7 +2897,9 @@ mod tests {
                 BackendMessage::ErrorResponse(
                     Some("ERROR"),
                     Some("42601"),
-                    Some("syntax error in expression".to_owned())
+                    Some("syntax error in expression".to_owned()),
+                    None,
+                    None
                 )
             )
         }
@@ -14,9 +14,9 @@
 
 use crate::{
     sql_formats::PostgreSqlFormat,
-    statement::{Portal, PreparedStatement},
+    statement::{Cursor, Portal, PreparedStatement},
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 /// A `Session` holds SQL state that is attached to a session.
 #[derive(Clone, Debug)]
@@ -25,6 +25,14 @@ pub struct Session<S> {
     prepared_statements: HashMap<String, PreparedStatement<S>>,
     /// A map from statement names to bound statements
     portals: HashMap<String, Portal<S>>,
+    /// A map from cursor names to cursors opened by `DECLARE`
+    cursors: HashMap<String, Cursor>,
+    /// Maximum number of rows that a query could return, `None` if unlimited
+    statement_max_rows: Option<usize>,
+    /// Time after which a query is canceled, `None` if unlimited
+    statement_timeout: Option<Duration>,
+    /// `true` between BEGIN and COMMIT
+    in_transaction: bool,
 }
 
 impl<S> Default for Session<S> {
@@ -32,6 +40,10 @@ impl<S> Default for Session<S> {
         Session {
             prepared_statements: HashMap::default(),
             portals: HashMap::default(),
+            cursors: HashMap::default(),
+            statement_max_rows: None,
+            statement_timeout: None,
+            in_transaction: false,
         }
     }
 }
@@ -63,4 +75,58 @@ impl<S> Session<S> {
         let new_portal = Portal::new(statement_name, stmt, result_formats);
         self.portals.insert(portal_name, new_portal);
     }
+
+    /// get `Cursor` by its name to fetch its rows
+    pub fn get_cursor_mut(&mut self, name: &str) -> Option<&mut Cursor> {
+        self.cursors.get_mut(name)
+    }
+
+    /// save `Cursor` associated with a name, returns `false` if the name is already taken
+    pub fn declare_cursor(&mut self, name: String, cursor: Cursor) -> bool {
+        if self.cursors.contains_key(&name) {
+            return false;
+        }
+        self.cursors.insert(name, cursor);
+        true
+    }
+
+    /// remove `Cursor` by its name, returns `false` if there is no such cursor
+    pub fn close_cursor(&mut self, name: &str) -> bool {
+        self.cursors.remove(name).is_some()
+    }
+
+    /// remove all cursors of the session
+    pub fn close_all_cursors(&mut self) {
+        self.cursors.clear();
+    }
+
+    /// get maximum number of rows that a query could return
+    pub fn statement_max_rows(&self) -> Option<usize> {
+        self.statement_max_rows
+    }
+
+    /// limit number of rows that a query could return, `None` removes the limit
+    pub fn set_statement_max_rows(&mut self, max_rows: Option<usize>) {
+        self.statement_max_rows = max_rows;
+    }
+
+    /// get time after which a query is canceled
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    /// limit time that a query could run, `None` removes the limit
+    pub fn set_statement_timeout(&mut self, timeout: Option<Duration>) {
+        self.statement_timeout = timeout;
+    }
+
+    /// whether a transaction block is started
+    pub fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
+    /// starts or ends a transaction block
+    pub fn set_in_transaction(&mut self, in_transaction: bool) {
+        self.in_transaction = in_transaction;
+    }
 }
@@ -42,6 +42,14 @@ pub enum PostgreSqlType {
     TimestampWithTimeZone,
     Date,
     Interval,
+    Json,
+    Bytea,
+    BoolArray,
+    CharArray,
+    VarCharArray,
+    SmallIntArray,
+    IntegerArray,
+    BigIntArray,
 }
 
 impl TryFrom<Oid> for PostgreSqlType {
@@ -51,10 +59,12 @@ impl TryFrom<Oid> for PostgreSqlType {
     fn try_from(oid: Oid) -> Result<Self, Self::Error> {
         match oid {
             16 => Ok(PostgreSqlType::Bool),
+            17 => Ok(PostgreSqlType::Bytea),
             18 => Ok(PostgreSqlType::Char),
             20 => Ok(PostgreSqlType::BigInt),
             21 => Ok(PostgreSqlType::SmallInt),
             23 => Ok(PostgreSqlType::Integer),
+            114 => Ok(PostgreSqlType::Json),
             700 => Ok(PostgreSqlType::Real),
             701 => Ok(PostgreSqlType::DoublePrecision),
             1043 => Ok(PostgreSqlType::VarChar),
@@ -65,6 +75,12 @@ impl TryFrom<Oid> for PostgreSqlType {
             1186 => Ok(PostgreSqlType::Interval),
             1266 => Ok(PostgreSqlType::TimeWithTimeZone),
             1700 => Ok(PostgreSqlType::Decimal),
+            1000 => Ok(PostgreSqlType::BoolArray),
+            1005 => Ok(PostgreSqlType::SmallIntArray),
+            1007 => Ok(PostgreSqlType::IntegerArray),
+            1014 => Ok(PostgreSqlType::CharArray),
+            1015 => Ok(PostgreSqlType::VarCharArray),
+            1016 => Ok(PostgreSqlType::BigIntArray),
             _ => Err(()),
         }
     }
@@ -75,10 +91,12 @@ impl PostgreSqlType {
     pub fn pg_oid(&self) -> Oid {
         match self {
             Self::Bool => 16,
+            Self::Bytea => 17,
             Self::Char => 18,
-            Self::BigInt => 20,           // PG int8
-            Self::SmallInt => 21,         // PG int2
-            Self::Integer => 23,          // PG int4
+            Self::BigInt => 20,   // PG int8
+            Self::SmallInt => 21, // PG int2
+            Self::Integer => 23,  // PG int4
+            Self::Json => 114,
             Self::Real => 700,            // PG float4
             Self::DoublePrecision => 701, // PG float8
             Self::VarChar => 1043,
@@ -89,6 +107,12 @@ impl PostgreSqlType {
             Self::Interval => 1186,
             Self::TimeWithTimeZone => 1266, // PG Timetz
             Self::Decimal => 1700,          // PG Numeric & Decimal
+            Self::BoolArray => 1000,        // PG _bool
+            Self::SmallIntArray => 1005,    // PG _int2
+            Self::IntegerArray => 1007,     // PG _int4
+            Self::CharArray => 1014,        // PG _bpchar
+            Self::VarCharArray => 1015,     // PG _varchar
+            Self::BigIntArray => 1016,      // PG _int8
         }
     }
 
@@ -110,6 +134,14 @@ impl PostgreSqlType {
             Self::Interval => 16,
             Self::TimeWithTimeZone => 12,
             Self::Decimal => -1,
+            Self::Json => -1,
+            Self::Bytea => -1,
+            Self::BoolArray
+            | Self::CharArray
+            | Self::VarCharArray
+            | Self::SmallIntArray
+            | Self::IntegerArray
+            | Self::BigIntArray => -1,
         }
     }
 
@@ -169,6 +201,14 @@ impl Display for PostgreSqlType {
             Self::TimestampWithTimeZone => write!(f, "timestamp with timezone"),
             Self::Interval => write!(f, "interval"),
             Self::Decimal => write!(f, "decimal"),
+            Self::Json => write!(f, "json"),
+            Self::Bytea => write!(f, "bytea"),
+            Self::BoolArray => write!(f, "bool[]"),
+            Self::CharArray => write!(f, "character[]"),
+            Self::VarCharArray => write!(f, "variable character[]"),
+            Self::SmallIntArray => write!(f, "smallint[]"),
+            Self::IntegerArray => write!(f, "integer[]"),
+            Self::BigIntArray => write!(f, "bigint[]"),
         }
     }
 }
@@ -413,4 +453,43 @@ mod tests {
             );
         }
     }
+
+    #[cfg(test)]
+    mod arrays {
+        use super::*;
+
+        #[test]
+        fn integer_array_oid() {
+            assert_eq!(PostgreSqlType::IntegerArray.pg_oid(), 1007);
+            assert_eq!(PostgreSqlType::try_from(1007), Ok(PostgreSqlType::IntegerArray));
+        }
+
+        #[test]
+        fn variable_length() {
+            assert_eq!(PostgreSqlType::BoolArray.pg_len(), -1);
+            assert_eq!(PostgreSqlType::BigIntArray.pg_len(), -1);
+        }
+
+        #[test]
+        fn display() {
+            assert_eq!(PostgreSqlType::IntegerArray.to_string(), "integer[]");
+            assert_eq!(PostgreSqlType::VarCharArray.to_string(), "variable character[]");
+        }
+    }
+
+    #[cfg(test)]
+    mod bytea {
+        use super::*;
+
+        #[test]
+        fn oid() {
+            assert_eq!(PostgreSqlType::Bytea.pg_oid(), 17);
+            assert_eq!(PostgreSqlType::try_from(17), Ok(PostgreSqlType::Bytea));
+        }
+
+        #[test]
+        fn variable_length() {
+            assert_eq!(PostgreSqlType::Bytea.pg_len(), -1);
+        }
+    }
 }
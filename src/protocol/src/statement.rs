@@ -34,7 +34,11 @@
 //! 4. The client issues an `Execute` message with the name of a portal, causing
 //!    that portal to actually start scanning and returning results.
 
-use crate::{results::Description, sql_formats::PostgreSqlFormat, sql_types::PostgreSqlType};
+use crate::{
+    results::{Description, Projection},
+    sql_formats::PostgreSqlFormat,
+    sql_types::PostgreSqlType,
+};
 
 /// A prepared statement.
 #[derive(Clone, Debug)]
@@ -68,7 +72,7 @@ impl<S> PreparedStatement<S> {
     }
 
     /// Returns the type of the rows that will be returned.
-    pub fn description(&self) -> &[(String, PostgreSqlType)] {
+    pub fn description(&self) -> &[(String, PostgreSqlType, i32)] {
         self.description.as_ref()
     }
 }
@@ -99,3 +103,36 @@ impl<S> Portal<S> {
         &self.stmt
     }
 }
+
+/// A cursor opened by `DECLARE`, rows of its query are read when it is declared
+/// and then returned by `FETCH` in the same order.
+#[derive(Clone, Debug)]
+pub struct Cursor {
+    /// Rows of the query with their description.
+    projection: Projection,
+    /// Index of the row that the next `FETCH` returns first.
+    position: usize,
+}
+
+impl Cursor {
+    /// Constructs a new `Cursor` positioned before the first row.
+    pub fn new(projection: Projection) -> Cursor {
+        Cursor {
+            projection,
+            position: 0,
+        }
+    }
+
+    /// Returns at most `count` rows after the current position and moves past them,
+    /// all remaining rows are returned when `count` is `None`.
+    pub fn fetch(&mut self, count: Option<usize>) -> Projection {
+        let (description, rows) = &self.projection;
+        let remaining = &rows[self.position..];
+        let fetched = match count {
+            Some(count) if count < remaining.len() => &remaining[..count],
+            _ => remaining,
+        };
+        self.position += fetched.len();
+        (description.clone(), fetched.to_vec())
+    }
+}
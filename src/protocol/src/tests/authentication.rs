@@ -0,0 +1,208 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    auth::AuthConfig,
+    hand_shake,
+    messages::BackendMessage,
+    tests::{async_io::TestCase, pg_frontend},
+    Error, ProtocolConfiguration,
+};
+use futures_lite::future::block_on;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+const RULES: &str = r#"
+[[rules]]
+address = "10.0.0.0/8"
+database = "all"
+user = "all"
+method = "reject"
+
+[[rules]]
+address = "192.168.0.0/24"
+database = "all"
+user = "all"
+method = "trust"
+
+[[rules]]
+address = "all"
+database = "all"
+user = "admin"
+method = "password"
+
+[[rules]]
+address = "all"
+database = "public_db"
+user = "all"
+method = "trust"
+
+[users]
+admin = "secret"
+"#;
+
+fn config() -> ProtocolConfiguration {
+    ProtocolConfiguration::none().with_auth(AuthConfig::from_toml(RULES).expect("config parsed"))
+}
+
+fn client(address: [u8; 4]) -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::from(address)), 5432)
+}
+
+fn setup(user: &'static str, database: &'static str) -> Vec<u8> {
+    pg_frontend::Message::Setup(vec![("user", user), ("database", database)]).as_vec()
+}
+
+fn connection_established() -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(BackendMessage::AuthenticationOk.as_vec().as_slice());
+    content.extend_from_slice(
+        BackendMessage::ParameterStatus("client_encoding".to_owned(), "UTF8".to_owned())
+            .as_vec()
+            .as_slice(),
+    );
+    content.extend_from_slice(
+        BackendMessage::ParameterStatus("DateStyle".to_owned(), "ISO".to_owned())
+            .as_vec()
+            .as_slice(),
+    );
+    content.extend_from_slice(
+        BackendMessage::ParameterStatus("integer_datetimes".to_owned(), "off".to_owned())
+            .as_vec()
+            .as_slice(),
+    );
+    content.extend_from_slice(BackendMessage::ReadyForQuery.as_vec().as_slice());
+    content
+}
+
+#[test]
+fn trust_does_not_ask_password() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![setup("user", "database").as_slice(), &[]]);
+
+        let result = hand_shake(test_case.clone(), client([192, 168, 0, 15]), &config()).await;
+
+        assert!(matches!(result, Ok(Ok(_))));
+        assert_eq!(test_case.read_result().await, connection_established());
+    });
+}
+
+#[test]
+fn reject_blocks_connection() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![setup("admin", "database").as_slice(), &[]]);
+
+        let result = hand_shake(test_case.clone(), client([10, 1, 2, 3]), &config()).await;
+
+        assert!(matches!(result, Ok(Err(Error::AuthenticationFailed))));
+        assert_eq!(
+            test_case.read_result().await,
+            BackendMessage::ErrorResponse(
+                Some("FATAL"),
+                Some("28000"),
+                Some(
+                    "pg_hba.conf rejects connection for host \"10.1.2.3\", user \"admin\", database \"database\""
+                        .to_owned()
+                ),
+                None,
+                None
+            )
+            .as_vec()
+        );
+    });
+}
+
+#[test]
+fn connection_without_matched_rule_is_rejected() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![setup("user", "private_db").as_slice(), &[]]);
+
+        let result = hand_shake(test_case.clone(), client([172, 16, 0, 1]), &config()).await;
+
+        assert!(matches!(result, Ok(Err(Error::AuthenticationFailed))));
+    });
+}
+
+#[test]
+fn password_is_validated() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![
+            setup("admin", "database").as_slice(),
+            pg_frontend::Message::Password("secret").as_vec().as_slice(),
+            &[],
+        ]);
+
+        let result = hand_shake(test_case.clone(), client([172, 16, 0, 1]), &config()).await;
+
+        assert!(matches!(result, Ok(Ok(_))));
+        let mut expected_content = BackendMessage::AuthenticationCleartextPassword.as_vec();
+        expected_content.extend_from_slice(connection_established().as_slice());
+        assert_eq!(test_case.read_result().await, expected_content);
+    });
+}
+
+#[test]
+fn wrong_password() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![
+            setup("admin", "database").as_slice(),
+            pg_frontend::Message::Password("wrong").as_vec().as_slice(),
+            &[],
+        ]);
+
+        let result = hand_shake(test_case.clone(), client([172, 16, 0, 1]), &config()).await;
+
+        assert!(matches!(result, Ok(Err(Error::AuthenticationFailed))));
+        let mut expected_content = BackendMessage::AuthenticationCleartextPassword.as_vec();
+        expected_content.extend_from_slice(
+            BackendMessage::ErrorResponse(
+                Some("FATAL"),
+                Some("28P01"),
+                Some("password authentication failed for user \"admin\"".to_owned()),
+                None,
+                None,
+            )
+            .as_vec()
+            .as_slice(),
+        );
+        assert_eq!(test_case.read_result().await, expected_content);
+    });
+}
+
+#[test]
+fn wildcard_user_for_specific_database() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![setup("guest", "public_db").as_slice(), &[]]);
+
+        let result = hand_shake(test_case.clone(), client([172, 16, 0, 1]), &config()).await;
+
+        assert!(matches!(result, Ok(Ok(_))));
+        assert_eq!(test_case.read_result().await, connection_established());
+    });
+}
+
+#[test]
+fn database_defaults_to_user_name() {
+    block_on(async {
+        let test_case = TestCase::with_content(vec![
+            pg_frontend::Message::Setup(vec![("user", "public_db")])
+                .as_vec()
+                .as_slice(),
+            &[],
+        ]);
+
+        let result = hand_shake(test_case.clone(), client([172, 16, 0, 1]), &config()).await;
+
+        assert!(matches!(result, Ok(Ok(_))));
+    });
+}
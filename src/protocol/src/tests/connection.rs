@@ -86,3 +86,171 @@ mod read_query {
         });
     }
 }
+
+#[cfg(test)]
+mod send_response {
+    use super::*;
+    use crate::{
+        messages::BackendMessage,
+        results::{QueryError, QueryEvent},
+        sql_types::PostgreSqlType,
+        ResponseSender, Sender,
+    };
+    use futures_lite::io::AsyncWriteExt;
+
+    #[test]
+    fn send_query_results_asynchronously() {
+        block_on(async {
+            let test_case = TestCase::with_content(vec![]);
+            let channel = Arc::new(AsyncMutex::new(Channel::Plain(test_case.clone())));
+            let sender = ResponseSender::new((VERSION_3, vec![]), channel.clone());
+
+            sender
+                .send_async(Ok(QueryEvent::SchemaCreated))
+                .await
+                .expect("no io errors");
+            sender
+                .send_async(Err(QueryError::schema_does_not_exist("schema_name".to_owned())))
+                .await
+                .expect("no io errors");
+            channel.lock().await.flush().await.expect("no io errors");
+
+            let mut expected = vec![];
+            let messages: Vec<BackendMessage> = QueryEvent::SchemaCreated.into();
+            for message in messages {
+                expected.extend(message.as_vec());
+            }
+            let message: BackendMessage = QueryError::schema_does_not_exist("schema_name".to_owned()).into();
+            expected.extend(message.as_vec());
+
+            assert_eq!(test_case.read_result().await, expected);
+        });
+    }
+
+    #[test]
+    fn send_notice() {
+        block_on(async {
+            let test_case = TestCase::with_content(vec![]);
+            let channel = Arc::new(AsyncMutex::new(Channel::Plain(test_case.clone())));
+            let sender = ResponseSender::new((VERSION_3, vec![]), channel.clone());
+
+            sender
+                .send(Ok(QueryEvent::Notice {
+                    severity: "NOTICE".to_owned(),
+                    message: "message".to_owned(),
+                }))
+                .expect("no io errors");
+            channel.lock().await.flush().await.expect("no io errors");
+
+            assert_eq!(
+                test_case.read_result().await,
+                BackendMessage::NoticeResponse("NOTICE".to_owned(), "message".to_owned()).as_vec()
+            );
+        });
+    }
+
+    #[test]
+    fn send_error_with_hint() {
+        block_on(async {
+            let test_case = TestCase::with_content(vec![]);
+            let channel = Arc::new(AsyncMutex::new(Channel::Plain(test_case.clone())));
+            let sender = ResponseSender::new((VERSION_3, vec![]), channel.clone());
+
+            sender
+                .send(Err(QueryError::type_mismatch(
+                    "abc",
+                    PostgreSqlType::SmallInt,
+                    "col1".to_owned(),
+                    1,
+                )))
+                .expect("no io errors");
+            channel.lock().await.flush().await.expect("no io errors");
+
+            let mut expected = b"H".to_vec();
+            expected.extend_from_slice(b"smallint accepts integer numbers\0\0");
+            assert!(test_case.read_result().await.ends_with(&expected));
+        });
+    }
+
+    #[test]
+    fn send_rows_as_they_are_produced() {
+        block_on(async {
+            let test_case = TestCase::with_content(vec![]);
+            let channel = Arc::new(AsyncMutex::new(Channel::Plain(test_case.clone())));
+            let sender = ResponseSender::new((VERSION_3, vec![]), channel.clone());
+            let description = vec![("column".to_owned(), PostgreSqlType::Integer, 0)];
+            let rows = vec![vec![Some("1".to_owned())], vec![None]];
+
+            sender
+                .send_rows(description.clone(), &mut rows.clone().into_iter().map(Ok))
+                .expect("no io errors");
+            channel.lock().await.flush().await.expect("no io errors");
+
+            let mut expected = vec![];
+            let messages: Vec<BackendMessage> = QueryEvent::RecordsSelected((description, rows)).into();
+            for message in messages {
+                expected.extend(message.as_vec());
+            }
+            assert_eq!(test_case.read_result().await, expected);
+        });
+    }
+
+    #[test]
+    fn complete_streamed_rows_with_their_number() {
+        block_on(async {
+            let test_case = TestCase::with_content(vec![]);
+            let channel = Arc::new(AsyncMutex::new(Channel::Plain(test_case.clone())));
+            let sender = ResponseSender::new((VERSION_3, vec![]), channel.clone());
+
+            sender
+                .send_rows(vec![], &mut (0..3).map(|_| Ok(vec![])))
+                .expect("no io errors");
+            channel.lock().await.flush().await.expect("no io errors");
+
+            let mut expected = vec![];
+            for message in &[
+                crate::results::row_description(vec![]),
+                BackendMessage::DataRow(vec![]),
+                BackendMessage::DataRow(vec![]),
+                BackendMessage::DataRow(vec![]),
+                BackendMessage::CommandComplete("SELECT 3".to_owned()),
+            ] {
+                expected.extend(message.as_vec());
+            }
+            assert_eq!(test_case.read_result().await, expected);
+        });
+    }
+
+    #[test]
+    fn send_error_instead_of_rows_completion() {
+        block_on(async {
+            let test_case = TestCase::with_content(vec![]);
+            let channel = Arc::new(AsyncMutex::new(Channel::Plain(test_case.clone())));
+            let sender = ResponseSender::new((VERSION_3, vec![]), channel.clone());
+            let description = vec![("column".to_owned(), PostgreSqlType::Integer, 0)];
+
+            sender
+                .send_rows(
+                    description.clone(),
+                    &mut vec![
+                        Ok(vec![Some("1".to_owned())]),
+                        Err(QueryError::io_error("could not read table".to_owned())),
+                        Ok(vec![Some("2".to_owned())]),
+                    ]
+                    .into_iter(),
+                )
+                .expect("no io errors");
+            channel.lock().await.flush().await.expect("no io errors");
+
+            let mut expected = vec![];
+            for message in &[
+                crate::results::row_description(description),
+                BackendMessage::DataRow(vec![Some("1".to_owned())]),
+                QueryError::io_error("could not read table".to_owned()).into(),
+            ] {
+                expected.extend(message.as_vec());
+            }
+            assert_eq!(test_case.read_result().await, expected);
+        });
+    }
+}
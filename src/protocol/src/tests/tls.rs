@@ -0,0 +1,257 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    hand_shake,
+    messages::{BackendMessage, Encryption},
+    tests::{fixture, pg_frontend, tls_config},
+    ProtocolConfiguration, ServerTlsConfig,
+};
+use async_io::Async;
+use futures_lite::{
+    future::{self, block_on},
+    io::{self, AsyncReadExt, AsyncWriteExt},
+};
+use futures_rustls::{
+    rustls::{
+        internal::pemfile::certs, Certificate, ClientConfig, RootCertStore, ServerCertVerified, ServerCertVerifier,
+        TLSError,
+    },
+    webpki::DNSNameRef,
+    TlsConnector,
+};
+use std::{
+    fs::File,
+    io::BufReader,
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+};
+
+struct AcceptInvalidCerts;
+
+impl ServerCertVerifier for AcceptInvalidCerts {
+    fn verify_server_cert(
+        &self,
+        _roots: &RootCertStore,
+        _presented_certs: &[Certificate],
+        _dns_name: DNSNameRef<'_>,
+        _ocsp_response: &[u8],
+    ) -> Result<ServerCertVerified, TLSError> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+fn trusting(certificate: &str) -> ClientConfig {
+    let mut config = ClientConfig::new();
+    for certificate in certs(&mut BufReader::new(
+        File::open(fixture(certificate)).expect("certificate file"),
+    ))
+    .expect("certificate parsed")
+    {
+        config.root_store.add(&certificate).expect("certificate added");
+    }
+    config
+}
+
+fn accept_invalid_certs() -> ClientConfig {
+    let mut config = ClientConfig::new();
+    config
+        .dangerous()
+        .set_certificate_verifier(Arc::new(AcceptInvalidCerts));
+    config
+}
+
+// runs server side of hand shake and a client on a loopback connection,
+// returns whether the server completed the hand shake and what the client received
+fn connect(config: ProtocolConfiguration, client_config: Option<ClientConfig>) -> (bool, io::Result<Vec<u8>>) {
+    block_on(async {
+        let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0)).expect("listener bound");
+        let address = listener.get_ref().local_addr().expect("listener address");
+
+        let server = async {
+            let (stream, address) = listener.accept().await.expect("connection accepted");
+            matches!(hand_shake(stream, address, &config).await, Ok(Ok(_)))
+        };
+
+        let client = async {
+            let mut stream = Async::<TcpStream>::connect(address).await?;
+            stream
+                .write_all(pg_frontend::Message::SslRequired.as_vec().as_slice())
+                .await?;
+            let mut encryption = [0u8; 1];
+            stream.read_exact(&mut encryption).await?;
+
+            let client_config = match client_config {
+                Some(client_config) if encryption[0] == b'S' => client_config,
+                _ => return Ok(encryption.to_vec()),
+            };
+            let domain = DNSNameRef::try_from_ascii_str("localhost").expect("valid domain name");
+            let mut stream = TlsConnector::from(Arc::new(client_config))
+                .connect(domain, stream)
+                .await?;
+            stream
+                .write_all(
+                    pg_frontend::Message::Setup(vec![("user", "username"), ("database", "database_name")])
+                        .as_vec()
+                        .as_slice(),
+                )
+                .await?;
+            stream
+                .write_all(pg_frontend::Message::Password("123").as_vec().as_slice())
+                .await?;
+            stream.flush().await?;
+
+            let mut response = encryption.to_vec();
+            let mut buffer = vec![0u8; ready_for_query().len()];
+            stream.read_exact(&mut buffer).await?;
+            response.extend(buffer);
+            Ok(response)
+        };
+
+        future::zip(server, client).await
+    })
+}
+
+// runs server side of hand shake and a client that starts up without requesting encryption
+fn connect_plain(config: ProtocolConfiguration) -> (bool, io::Result<Vec<u8>>) {
+    block_on(async {
+        let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0)).expect("listener bound");
+        let address = listener.get_ref().local_addr().expect("listener address");
+
+        let server = async {
+            let (stream, address) = listener.accept().await.expect("connection accepted");
+            matches!(hand_shake(stream, address, &config).await, Ok(Ok(_)))
+        };
+
+        let client = async {
+            let mut stream = Async::<TcpStream>::connect(address).await?;
+            stream
+                .write_all(
+                    pg_frontend::Message::Setup(vec![("user", "username"), ("database", "database_name")])
+                        .as_vec()
+                        .as_slice(),
+                )
+                .await?;
+            stream
+                .write_all(pg_frontend::Message::Password("123").as_vec().as_slice())
+                .await?;
+
+            let mut response = vec![0u8; ready_for_query().len()];
+            stream.read_exact(&mut response).await?;
+            Ok(response)
+        };
+
+        future::zip(server, client).await
+    })
+}
+
+fn ready_for_query() -> Vec<u8> {
+    let mut expected_content = Vec::new();
+    expected_content.extend_from_slice(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice());
+    expected_content.extend_from_slice(BackendMessage::AuthenticationOk.as_vec().as_slice());
+    for (name, value) in &[
+        ("client_encoding", "UTF8"),
+        ("DateStyle", "ISO"),
+        ("integer_datetimes", "off"),
+    ] {
+        expected_content.extend_from_slice(
+            BackendMessage::ParameterStatus((*name).to_owned(), (*value).to_owned())
+                .as_vec()
+                .as_slice(),
+        );
+    }
+    expected_content.extend_from_slice(BackendMessage::ReadyForQuery.as_vec().as_slice());
+    expected_content
+}
+
+#[test]
+fn connect_with_tls_client() {
+    let (server_connected, client) = connect(
+        ProtocolConfiguration::with_ssl(tls_config()),
+        Some(trusting("certificate.pem")),
+    );
+
+    let mut expected_content = Vec::new();
+    expected_content.extend_from_slice(Encryption::AcceptSsl.into());
+    expected_content.extend(ready_for_query());
+    assert!(server_connected);
+    assert_eq!(client.expect("tls connection"), expected_content);
+}
+
+#[test]
+fn ssl_request_is_rejected_when_tls_is_not_configured() {
+    let (_, client) = connect(ProtocolConfiguration::none(), Some(trusting("certificate.pem")));
+
+    let mut expected_content = Vec::new();
+    expected_content.extend_from_slice(Encryption::RejectSsl.into());
+    assert_eq!(client.expect("plain connection"), expected_content);
+}
+
+#[test]
+fn client_declines_tls_when_tls_is_configured() {
+    let (server_connected, client) = connect_plain(ProtocolConfiguration::with_ssl(tls_config()));
+
+    assert!(server_connected);
+    assert_eq!(client.expect("plain connection"), ready_for_query());
+}
+
+#[test]
+fn expired_certificate_is_rejected() {
+    let (server_connected, client) = connect(
+        ProtocolConfiguration::with_ssl(ServerTlsConfig::new(
+            fixture("expired_certificate.pem"),
+            fixture("private_key.pem"),
+        )),
+        Some(trusting("expired_certificate.pem")),
+    );
+
+    assert!(!server_connected);
+    assert!(client.is_err());
+}
+
+#[test]
+fn self_signed_certificate_is_rejected_by_default() {
+    let (server_connected, client) = connect(ProtocolConfiguration::with_ssl(tls_config()), Some(ClientConfig::new()));
+
+    assert!(!server_connected);
+    assert!(client.is_err());
+}
+
+#[test]
+fn self_signed_certificate_with_accept_invalid_certs() {
+    let (server_connected, client) = connect(
+        ProtocolConfiguration::with_ssl(tls_config()),
+        Some(accept_invalid_certs()),
+    );
+
+    let mut expected_content = Vec::new();
+    expected_content.extend_from_slice(Encryption::AcceptSsl.into());
+    expected_content.extend(ready_for_query());
+    assert!(server_connected);
+    assert_eq!(client.expect("tls connection"), expected_content);
+}
+
+#[test]
+fn missing_certificate_file_aborts_connection() {
+    let (server_connected, client) = connect(
+        ProtocolConfiguration::with_ssl(ServerTlsConfig::new(
+            fixture("non_existent.pem"),
+            fixture("private_key.pem"),
+        )),
+        Some(accept_invalid_certs()),
+    );
+
+    assert!(!server_connected);
+    assert!(client.is_err());
+}
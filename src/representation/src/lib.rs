@@ -53,7 +53,9 @@ impl<'a> Datum<'a> {
             Self::Float64(_) => 1 + std::mem::size_of::<f64>(),
             Self::String(val) => 1 + std::mem::size_of::<usize>() + val.len(),
             Self::OwnedString(val) => 1 + std::mem::size_of::<usize>() + val.len(),
-            Self::SqlType(_) => 1 + std::mem::size_of::<SqlType>(),
+            Self::SqlType(val) => {
+                1 + std::mem::size_of::<usize>() + bincode::serialized_size(val).unwrap_or_default() as usize
+            }
         }
     }
 
@@ -172,7 +174,7 @@ impl<'a> Datum<'a> {
 
     pub fn as_sql_type(&self) -> SqlType {
         match self {
-            Self::SqlType(sql_type) => *sql_type,
+            Self::SqlType(sql_type) => sql_type.clone(),
             _ => panic!("invalid use of Datum::as_sql_type"),
         }
     }
@@ -355,8 +357,11 @@ impl Binary {
                 }
                 Datum::<'a>::Null => push_tag(&mut data, TypeTag::Null),
                 Datum::<'a>::SqlType(sql_type) => {
+                    // types could be nested, e.g. arrays, thus they are not copied as is
+                    let val = bincode::serialize(sql_type).expect("To serialize SqlType");
                     push_tag(&mut data, TypeTag::SqlType);
-                    push_copy!(&mut data, *sql_type, SqlType);
+                    data.extend_from_slice(&val.len().to_ne_bytes());
+                    data.extend_from_slice(&val);
                 }
             }
         }
@@ -373,49 +378,70 @@ pub fn unpack_raw(data: &[u8]) -> Vec<Datum> {
     let mut index = 0;
     let mut res = Vec::new();
     while index < data.len() {
-        let tag = read_tag(data, &mut index);
-        let datum = match tag {
-            TypeTag::Null => Datum::from_null(),
-            TypeTag::True => Datum::from_bool(true),
-            TypeTag::False => Datum::from_bool(false),
-            TypeTag::Str => {
-                let val = unsafe { read_string(data, &mut index) };
-                Datum::String(val)
-            }
-            TypeTag::I16 => {
-                let val = unsafe { read::<i16>(data, &mut index) };
-                Datum::from_i16(val)
-            }
-            TypeTag::I32 => {
-                let val = unsafe { read::<i32>(data, &mut index) };
-                Datum::from_i32(val)
-            }
-            TypeTag::I64 => {
-                let val = unsafe { read::<i64>(data, &mut index) };
-                Datum::from_i64(val)
-            }
-            TypeTag::U64 => {
-                let val = unsafe { read::<u64>(data, &mut index) };
-                Datum::from_u64(val)
-            }
-            TypeTag::F32 => {
-                let val = unsafe { read::<f32>(data, &mut index) };
-                Datum::from_f32(val)
-            }
-            TypeTag::F64 => {
-                let val = unsafe { read::<f64>(data, &mut index) };
-                Datum::from_f64(val)
-            }
-            TypeTag::SqlType => {
-                let val = unsafe { read::<SqlType>(data, &mut index) };
-                Datum::from_sql_type(val)
-            }
-        };
-        res.push(datum)
+        res.push(read_datum(data, &mut index))
     }
     res
 }
 
+/// datum of the column at `column` position, the following columns are not decoded
+pub fn unpack_column(data: &[u8], column: usize) -> Option<Datum> {
+    let mut index = 0;
+    for _ in 0..column {
+        if index >= data.len() {
+            return None;
+        }
+        read_datum(data, &mut index);
+    }
+    if index < data.len() {
+        Some(read_datum(data, &mut index))
+    } else {
+        None
+    }
+}
+
+fn read_datum<'d>(data: &'d [u8], index: &mut usize) -> Datum<'d> {
+    let tag = read_tag(data, index);
+    match tag {
+        TypeTag::Null => Datum::from_null(),
+        TypeTag::True => Datum::from_bool(true),
+        TypeTag::False => Datum::from_bool(false),
+        TypeTag::Str => {
+            let val = unsafe { read_string(data, index) };
+            Datum::String(val)
+        }
+        TypeTag::I16 => {
+            let val = unsafe { read::<i16>(data, index) };
+            Datum::from_i16(val)
+        }
+        TypeTag::I32 => {
+            let val = unsafe { read::<i32>(data, index) };
+            Datum::from_i32(val)
+        }
+        TypeTag::I64 => {
+            let val = unsafe { read::<i64>(data, index) };
+            Datum::from_i64(val)
+        }
+        TypeTag::U64 => {
+            let val = unsafe { read::<u64>(data, index) };
+            Datum::from_u64(val)
+        }
+        TypeTag::F32 => {
+            let val = unsafe { read::<f32>(data, index) };
+            Datum::from_f32(val)
+        }
+        TypeTag::F64 => {
+            let val = unsafe { read::<f64>(data, index) };
+            Datum::from_f64(val)
+        }
+        TypeTag::SqlType => {
+            let len = unsafe { read::<usize>(data, index) };
+            let val = bincode::deserialize(&data[*index..*index + len]).expect("To deserialize SqlType");
+            *index += len;
+            Datum::from_sql_type(val)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,5 +498,43 @@ mod tests {
             let row = Binary::pack(&data);
             assert_eq!(data, row.unpack());
         }
+
+        #[test]
+        fn array_sql_type() {
+            let data = vec![
+                Datum::from_sql_type(SqlType::Array(Box::new(SqlType::Integer(i32::MIN)))),
+                Datum::from_i16(1),
+            ];
+            let row = Binary::pack(&data);
+            assert_eq!(data, row.unpack());
+        }
+    }
+
+    #[cfg(test)]
+    mod unpack_column {
+        use super::*;
+
+        #[test]
+        fn leading_column() {
+            let row = Binary::pack(&[Datum::from_i32(1), Datum::from_str("one"), Datum::from_bool(true)]);
+            assert_eq!(unpack_column(row.to_bytes(), 0), Some(Datum::from_i32(1)));
+        }
+
+        #[test]
+        fn column_after_variable_length_ones() {
+            let row = Binary::pack(&[
+                Datum::from_str("one"),
+                Datum::from_sql_type(SqlType::VarChar(32)),
+                Datum::from_i64(2),
+            ]);
+            assert_eq!(unpack_column(row.to_bytes(), 2), Some(Datum::from_i64(2)));
+        }
+
+        #[test]
+        fn column_out_of_row() {
+            let row = Binary::pack(&[Datum::from_i32(1)]);
+            assert_eq!(unpack_column(row.to_bytes(), 1), None);
+            assert_eq!(unpack_column(row.to_bytes(), 5), None);
+        }
     }
 }
@@ -0,0 +1,210 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::RecordId;
+use protocol::{results::Projection, sql_types::PostgreSqlType};
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Table that a cached result was read from, the result is reused only while its name
+/// is resolved to the same table and data of the table has the same version
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ReadTable {
+    pub(crate) schema_name: String,
+    pub(crate) table_name: String,
+    pub(crate) schema_id: RecordId,
+    pub(crate) table_id: RecordId,
+    pub(crate) version: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    // text of the parsed query, thus it does not depend on whitespaces and comments of the raw one
+    sql: String,
+    schema_version: u64,
+}
+
+impl CacheKey {
+    pub(crate) fn new(sql: String, schema_version: u64) -> CacheKey {
+        CacheKey { sql, schema_version }
+    }
+}
+
+pub(crate) struct CachedResult {
+    id: u64,
+    pub(crate) tables: Vec<ReadTable>,
+    pub(crate) projection: Projection,
+}
+
+struct Entry {
+    id: u64,
+    tables: Vec<ReadTable>,
+    projection: Projection,
+    created: Instant,
+    size: usize,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_key: HashMap<CacheKey, Entry>,
+    // last usage of an entry to its key, the first one is the least recently used
+    by_usage: BTreeMap<u64, CacheKey>,
+    usages: u64,
+    size: usize,
+}
+
+impl Entries {
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.by_key.remove(key) {
+            self.by_usage.remove(&entry.last_used);
+            self.size -= entry.size;
+        }
+    }
+
+    fn next_usage(&mut self, key: &CacheKey) -> u64 {
+        self.usages += 1;
+        self.by_usage.insert(self.usages, key.clone());
+        self.usages
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        let usage = self.next_usage(key);
+        if let Some(entry) = self.by_key.get_mut(key) {
+            self.by_usage.remove(&entry.last_used);
+            entry.last_used = usage;
+        }
+    }
+}
+
+/// Results of read only queries shared by all sessions. Least recently used results are
+/// evicted when their total size exceeds `cache_size_bytes`, any result expires after `ttl`
+pub struct QueryCache {
+    cache_size_bytes: usize,
+    ttl: Duration,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+}
+
+impl QueryCache {
+    pub fn new(cache_size_bytes: usize, ttl: Duration) -> QueryCache {
+        QueryCache {
+            cache_size_bytes,
+            ttl,
+            entries: Mutex::default(),
+            hits: AtomicU64::default(),
+        }
+    }
+
+    /// number of queries that were answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::SeqCst)
+    }
+
+    /// number of cached results
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("to acquire lock").by_key.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// result is not checked against tables it was read from, it has to be `invalidate`d
+    /// if any of them were changed
+    pub(crate) fn lookup(&self, key: &CacheKey) -> Option<CachedResult> {
+        let mut entries = self.entries.lock().expect("to acquire lock");
+        let result = match entries.by_key.get(key) {
+            None => return None,
+            Some(entry) if entry.created.elapsed() > self.ttl => None,
+            Some(entry) => Some(CachedResult {
+                id: entry.id,
+                tables: entry.tables.clone(),
+                projection: entry.projection.clone(),
+            }),
+        };
+        match result {
+            None => entries.remove(key),
+            Some(_) => entries.touch(key),
+        }
+        result
+    }
+
+    pub(crate) fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// removes the result if it was not replaced since the lookup
+    pub(crate) fn invalidate(&self, key: &CacheKey, result: &CachedResult) {
+        let mut entries = self.entries.lock().expect("to acquire lock");
+        if entries.by_key.get(key).map(|entry| entry.id) == Some(result.id) {
+            entries.remove(key);
+        }
+    }
+
+    /// results that are larger than the cache itself are not stored
+    pub(crate) fn put(&self, key: CacheKey, tables: Vec<ReadTable>, projection: Projection) {
+        let size = entry_size(&key, &tables, &projection);
+        if size > self.cache_size_bytes {
+            return;
+        }
+        let mut entries = self.entries.lock().expect("to acquire lock");
+        entries.remove(&key);
+        while entries.size + size > self.cache_size_bytes {
+            let least_recently_used = match entries.by_usage.values().next() {
+                Some(key) => key.clone(),
+                None => break,
+            };
+            entries.remove(&least_recently_used);
+        }
+        let usage = entries.next_usage(&key);
+        entries.size += size;
+        entries.by_key.insert(
+            key,
+            Entry {
+                id: usage,
+                tables,
+                projection,
+                created: Instant::now(),
+                size,
+                last_used: usage,
+            },
+        );
+    }
+}
+
+fn entry_size(key: &CacheKey, tables: &[ReadTable], projection: &Projection) -> usize {
+    let (description, rows) = projection;
+    key.sql.len()
+        + tables
+            .iter()
+            .map(|table| mem::size_of::<ReadTable>() + table.schema_name.len() + table.table_name.len())
+            .sum::<usize>()
+        + description
+            .iter()
+            .map(|(name, _, _)| name.len() + mem::size_of::<(PostgreSqlType, i32)>())
+            .sum::<usize>()
+        + rows
+            .iter()
+            .flat_map(|row| row.iter())
+            .map(|value| value.as_ref().map_or(0, String::len) + mem::size_of::<Option<String>>())
+            .sum::<usize>()
+}
@@ -0,0 +1,67 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::{DataManager, SessionId};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use std::sync::Arc;
+
+pub(crate) struct DropUserCommand {
+    name: String,
+    if_exists: bool,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl DropUserCommand {
+    pub(crate) fn new(
+        name: String,
+        if_exists: bool,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> DropUserCommand {
+        DropUserCommand {
+            name,
+            if_exists,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        if !self.storage.is_superuser(self.session_id) {
+            self.sender
+                .send(Err(QueryError::permission_denied(
+                    "permission denied to drop role".to_owned(),
+                )))
+                .expect("To Send Query Result to Client");
+        } else if !self.storage.user_exists(&self.name) && !self.if_exists {
+            self.sender
+                .send(Err(QueryError::user_does_not_exist(self.name.clone())))
+                .expect("To Send Query Result to Client");
+        } else {
+            self.storage.drop_user(&self.name);
+            self.sender
+                .send(Ok(QueryEvent::UserDropped))
+                .expect("To Send Query Result to Client");
+        }
+        Ok(())
+    }
+}
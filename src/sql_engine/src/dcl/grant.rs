@@ -0,0 +1,72 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dcl::granted_table;
+use data_manager::{DataManager, Privilege, SessionId};
+use kernel::SystemResult;
+use protocol::{results::QueryEvent, Sender};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+
+pub(crate) struct GrantCommand {
+    privileges: Vec<Privilege>,
+    table_name: ObjectName,
+    grantee: String,
+    with_grant_option: bool,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl GrantCommand {
+    pub(crate) fn new(
+        privileges: Vec<Privilege>,
+        table_name: ObjectName,
+        grantee: String,
+        with_grant_option: bool,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> GrantCommand {
+        GrantCommand {
+            privileges,
+            table_name,
+            grantee,
+            with_grant_option,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        if let Some((schema_id, table_id)) = granted_table(
+            &self.table_name,
+            &self.privileges,
+            &self.grantee,
+            self.session_id,
+            &self.storage,
+            self.sender.as_ref(),
+        ) {
+            for privilege in self.privileges.iter() {
+                self.storage
+                    .grant(&self.grantee, schema_id, table_id, *privilege, self.with_grant_option);
+            }
+            self.sender
+                .send(Ok(QueryEvent::PrivilegesGranted))
+                .expect("To Send Query Result to Client");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(crate) mod create_user;
+pub(crate) mod drop_user;
+pub(crate) mod grant;
+pub(crate) mod revoke;
+
+use crate::query::{TableId, TableNamingError};
+use data_manager::{DataManager, Privilege, RecordId, SessionId};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::ObjectName;
+use std::convert::TryFrom;
+
+// only superuser or a user that has privileges with grant option can grant or revoke them,
+// `None` when an error was sent to the client
+fn granted_table(
+    table_name: &ObjectName,
+    privileges: &[Privilege],
+    grantee: &str,
+    session_id: SessionId,
+    storage: &DataManager,
+    sender: &dyn Sender,
+) -> Option<(RecordId, RecordId)> {
+    let table = match TableId::try_from(table_name.clone()) {
+        Ok(table) => table,
+        Err(TableNamingError(error)) => {
+            sender.send(Err(error)).expect("To Send Query Result to Client");
+            return None;
+        }
+    };
+    let schema_name = table.schema_name();
+    let table_name = table.name();
+    let (schema_id, table_id) = match storage.session_table_exists(session_id, schema_name, table_name) {
+        None => {
+            sender
+                .send(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
+                .expect("To Send Query Result to Client");
+            return None;
+        }
+        Some((_, None)) => {
+            sender
+                .send(Err(QueryError::table_does_not_exist(
+                    schema_name.to_owned() + "." + table_name,
+                )))
+                .expect("To Send Query Result to Client");
+            return None;
+        }
+        Some((schema_id, Some(table_id))) => (schema_id, table_id),
+    };
+    if !storage.user_exists(grantee) {
+        sender
+            .send(Err(QueryError::user_does_not_exist(grantee.to_owned())))
+            .expect("To Send Query Result to Client");
+        return None;
+    }
+    if privileges
+        .iter()
+        .any(|privilege| !storage.can_grant(session_id, schema_id, table_id, *privilege))
+    {
+        sender
+            .send(Err(QueryError::permission_denied(format!(
+                "permission denied for table {}",
+                table_name
+            ))))
+            .expect("To Send Query Result to Client");
+        return None;
+    }
+    Some((schema_id, table_id))
+}
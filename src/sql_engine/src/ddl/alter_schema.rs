@@ -0,0 +1,68 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::query::{SchemaId, SchemaNamingError};
+use data_manager::{DataManager, RenameSchemaError};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::ObjectName;
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct AlterSchemaCommand {
+    schema_name: ObjectName,
+    new_name: ObjectName,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl AlterSchemaCommand {
+    pub(crate) fn new(
+        schema_name: ObjectName,
+        new_name: ObjectName,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> AlterSchemaCommand {
+        AlterSchemaCommand {
+            schema_name,
+            new_name,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, new_schema_id) = match (
+            SchemaId::try_from(self.schema_name.clone()),
+            SchemaId::try_from(self.new_name.clone()),
+        ) {
+            (Ok(schema_id), Ok(new_schema_id)) => (schema_id, new_schema_id),
+            (Err(SchemaNamingError(error)), _) | (_, Err(SchemaNamingError(error))) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        };
+        let result = match self.storage.rename_schema(schema_id.name(), new_schema_id.name())? {
+            Ok(()) => Ok(QueryEvent::SchemaAltered),
+            Err(RenameSchemaError::DoesNotExist) => Err(QueryError::schema_does_not_exist(schema_id.name().to_owned())),
+            Err(RenameSchemaError::AlreadyExists) => {
+                Err(QueryError::schema_already_exists(new_schema_id.name().to_owned()))
+            }
+        };
+        self.sender.send(result).expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
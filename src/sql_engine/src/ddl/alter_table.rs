@@ -0,0 +1,258 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    ddl::existing_table,
+    dml::{
+        foreign_key_violation, generation_expressions,
+        relation::{Frame, RelationColumn, Scope},
+    },
+};
+use data_manager::{DataManager, ForeignKeyDefinition, RecordId, SessionId};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::{AlterTableOperation, Ident, ObjectName, TableConstraint};
+use std::sync::Arc;
+
+pub(crate) struct AlterTableCommand {
+    raw_sql_query: String,
+    table_name: ObjectName,
+    operation: AlterTableOperation,
+    /// whether an added foreign key is initially deferred when it is deferrable
+    deferrable: Option<bool>,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl AlterTableCommand {
+    pub(crate) fn new(
+        raw_sql_query: &str,
+        table_name: ObjectName,
+        operation: AlterTableOperation,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> AlterTableCommand {
+        AlterTableCommand {
+            raw_sql_query: raw_sql_query.to_owned(),
+            table_name,
+            operation,
+            deferrable: None,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn with_deferrable(mut self, initially_deferred: bool) -> AlterTableCommand {
+        self.deferrable = Some(initially_deferred);
+        self
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, table_id) =
+            match existing_table(&self.table_name, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        let altered = match &self.operation {
+            AlterTableOperation::AddConstraint(TableConstraint::ForeignKey {
+                name,
+                columns,
+                foreign_table,
+                referred_columns,
+            }) => self.add_foreign_key(
+                (schema_id, table_id),
+                name.as_ref(),
+                columns,
+                foreign_table,
+                referred_columns,
+            )?,
+            AlterTableOperation::DropConstraint { name } => self.drop_constraint((schema_id, table_id), name),
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => self.rename_column((schema_id, table_id), old_column_name, new_column_name)?,
+            _ => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(self.raw_sql_query.clone())))
+                    .expect("To Send Query Result to Client");
+                false
+            }
+        };
+        if altered {
+            self.sender
+                .send(Ok(QueryEvent::TableAltered))
+                .expect("To Send Query Result to Client");
+        }
+        Ok(())
+    }
+
+    // rows that are already stored have to reference existing keys, `false` when an error was sent
+    fn add_foreign_key(
+        &self,
+        (schema_id, table_id): (RecordId, RecordId),
+        name: Option<&Ident>,
+        columns: &[Ident],
+        foreign_table: &ObjectName,
+        referred_columns: &[Ident],
+    ) -> SystemResult<bool> {
+        let table_name = self.table_name.0[1].value.as_str();
+        let columns = columns.iter().map(|column| column.value.clone()).collect::<Vec<_>>();
+        // PostgreSQL names foreign keys after the table and its columns
+        let name = match name {
+            Some(name) => name.value.clone(),
+            None => format!("{}_{}_fkey", table_name, columns.join("_")),
+        };
+        if self.storage.foreign_key_exists(schema_id, table_id, &name) {
+            return Ok(self.report(QueryError::constraint_already_exists(name, table_name.to_owned())));
+        }
+        let table_columns = self.storage.table_columns(schema_id, table_id)?;
+        if let Some(missing) = columns
+            .iter()
+            .find(|column| !table_columns.iter().any(|definition| definition.has_name(column)))
+        {
+            return Ok(self.report(QueryError::column_does_not_exist(vec![missing.clone()])));
+        }
+
+        let (referenced_schema_id, referenced_table_id) =
+            match existing_table(foreign_table, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(false),
+            };
+        let referenced_table_name = foreign_table.0[1].value.as_str();
+        let referenced_table_columns = self.storage.table_columns(referenced_schema_id, referenced_table_id)?;
+        let referenced_columns = referred_columns
+            .iter()
+            .map(|column| column.value.clone())
+            .collect::<Vec<_>>();
+        if let Some(missing) = referenced_columns.iter().find(|column| {
+            !referenced_table_columns
+                .iter()
+                .any(|definition| definition.has_name(column))
+        }) {
+            return Ok(self.report(QueryError::column_does_not_exist(vec![missing.clone()])));
+        }
+        if columns.len() != referenced_columns.len() {
+            return Ok(self.report(QueryError::invalid_foreign_key(
+                "number of referencing and referenced columns for foreign key disagree".to_owned(),
+            )));
+        }
+        // only single columns are unique, thus a key could consist of one column
+        let unique = match referenced_columns.as_slice() {
+            [column] => referenced_table_columns
+                .iter()
+                .any(|definition| definition.has_name(column) && definition.is_unique()),
+            _ => false,
+        };
+        if !unique {
+            return Ok(self.report(QueryError::invalid_foreign_key(format!(
+                "there is no unique constraint matching given keys for referenced table \"{}\"",
+                referenced_table_name
+            ))));
+        }
+
+        let mut foreign_key = ForeignKeyDefinition::new(
+            &name,
+            columns,
+            referenced_schema_id,
+            referenced_table_id,
+            referenced_columns,
+        );
+        if let Some(initially_deferred) = self.deferrable {
+            foreign_key = foreign_key.with_deferrable(initially_deferred);
+        }
+        let rows = self
+            .storage
+            .full_scan(self.session_id, schema_id, table_id)?
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        let missing = self
+            .storage
+            .missing_references(schema_id, table_id, &foreign_key, &rows)?
+            .into_iter()
+            .flatten()
+            .next();
+        if let Some(key) = missing {
+            return Ok(self.report(foreign_key_violation(&self.storage, table_name, &foreign_key, &key)));
+        }
+        self.storage.add_foreign_key(schema_id, table_id, foreign_key);
+        Ok(true)
+    }
+
+    fn drop_constraint(&self, (schema_id, table_id): (RecordId, RecordId), name: &Ident) -> bool {
+        if !self.storage.foreign_key_exists(schema_id, table_id, &name.value) {
+            return self.report(QueryError::constraint_does_not_exist(
+                name.value.clone(),
+                self.table_name.0[1].value.clone(),
+            ));
+        }
+        self.storage.drop_foreign_key(schema_id, table_id, &name.value);
+        true
+    }
+
+    fn rename_column(
+        &self,
+        (schema_id, table_id): (RecordId, RecordId),
+        column_name: &Ident,
+        new_name: &Ident,
+    ) -> SystemResult<bool> {
+        let columns = self.storage.table_columns(schema_id, table_id)?;
+        if !columns.iter().any(|column| column.has_name(&column_name.value)) {
+            return Ok(self.report(QueryError::column_does_not_exist(vec![column_name.value.clone()])));
+        }
+        if columns.iter().any(|column| column.has_name(&new_name.value)) {
+            return Ok(self.report(QueryError::column_already_exists(
+                new_name.value.clone(),
+                self.table_name.0[1].value.clone(),
+            )));
+        }
+        // expressions of generated columns are kept as text, thus they can't follow the renamed column
+        let base_columns: Vec<RelationColumn> = columns
+            .iter()
+            .filter(|column| column.generated().is_none())
+            .map(|column| {
+                let name = if column.has_name(&column_name.value) {
+                    new_name.value.clone()
+                } else {
+                    column.name()
+                };
+                RelationColumn::new(name, (&column.sql_type()).into())
+            })
+            .collect();
+        let scope = Scope::new(Frame::header(&base_columns), None);
+        if generation_expressions(&columns)
+            .iter()
+            .any(|(_index, expr)| scope.check(expr).is_err())
+        {
+            return Ok(self.report(QueryError::feature_not_supported(format!(
+                "renaming of column \"{}\" that generated columns depend on",
+                column_name.value
+            ))));
+        }
+        self.storage
+            .rename_column(schema_id, table_id, &column_name.value, &new_name.value)?;
+        Ok(true)
+    }
+
+    fn report(&self, error: QueryError) -> bool {
+        self.sender.send(Err(error)).expect("To Send Query Result to Client");
+        false
+    }
+}
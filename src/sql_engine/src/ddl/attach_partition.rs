@@ -0,0 +1,179 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ddl::{
+    create_partition::{any_key, default_partition_conflict, overlap_error, partition_bounds, PartitionBound},
+    existing_table,
+};
+use data_manager::{DataManager, PartitionBounds, PartitionDefinition, SessionId};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+
+pub(crate) struct AttachPartitionCommand {
+    table_name: ObjectName,
+    partition: ObjectName,
+    bound: PartitionBound,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl AttachPartitionCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        partition: ObjectName,
+        bound: PartitionBound,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> AttachPartitionCommand {
+        AttachPartitionCommand {
+            table_name,
+            partition,
+            bound,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, table_id) =
+            match existing_table(&self.table_name, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        let (partition_schema_id, partition_table_id) =
+            match existing_table(&self.partition, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        let table_name = self.table_name.0[1].value.as_str();
+        let partition_name = self.partition.0[1].value.as_str();
+        let (strategy, key) = match (
+            self.storage.partition_strategy(schema_id, table_id),
+            self.storage.partition_key(schema_id, table_id),
+        ) {
+            (Some(strategy), Some(key)) => (strategy, key),
+            _ => return self.report(QueryError::table_is_not_partitioned(table_name.to_owned())),
+        };
+        if self
+            .storage
+            .partition_parent(partition_schema_id, partition_table_id)
+            .is_some()
+            || (partition_schema_id, partition_table_id) == (schema_id, table_id)
+        {
+            return self.report(QueryError::already_a_partition(partition_name.to_owned()));
+        }
+
+        // rows of partitions are stored as rows of the partitioned table
+        let columns = self.storage.table_columns(schema_id, table_id)?;
+        let partition_columns = self.storage.table_columns(partition_schema_id, partition_table_id)?;
+        if let Some(column) = partition_columns
+            .iter()
+            .find(|column| !columns.iter().any(|parent| parent.name() == column.name()))
+        {
+            return self.report(QueryError::invalid_object_definition(format!(
+                "table \"{}\" contains column \"{}\" not found in parent \"{}\"",
+                partition_name,
+                column.name(),
+                table_name
+            )));
+        }
+        if let Some(column) = columns
+            .iter()
+            .find(|column| !partition_columns.iter().any(|child| child.name() == column.name()))
+        {
+            return self.report(QueryError::invalid_object_definition(format!(
+                "child table is missing column \"{}\"",
+                column.name()
+            )));
+        }
+        if let Some((column, _)) = columns
+            .iter()
+            .zip(partition_columns.iter())
+            .find(|(column, child)| column.name() == child.name() && column.sql_type() != child.sql_type())
+        {
+            return self.report(QueryError::invalid_object_definition(format!(
+                "child table \"{}\" has different type for column \"{}\"",
+                partition_name,
+                column.name()
+            )));
+        }
+        if columns
+            .iter()
+            .zip(partition_columns.iter())
+            .any(|(column, child)| column.name() != child.name())
+        {
+            return self.report(QueryError::feature_not_supported(format!(
+                "partition \"{}\" with columns in a different order than partitioned table",
+                partition_name
+            )));
+        }
+
+        let bounds = match partition_bounds(&self.bound, strategy, &columns[key], partition_name) {
+            Ok(bounds) => bounds,
+            Err(error) => return self.report(error),
+        };
+        // rows of the default partition are rows that no other partition accepts
+        let partition = PartitionDefinition::new(partition_schema_id, partition_table_id, bounds.clone());
+        let existing = self.storage.partitions(schema_id, table_id);
+        let violated = match bounds {
+            PartitionBounds::Default => any_key(
+                &self.storage,
+                self.session_id,
+                partition_schema_id,
+                partition_table_id,
+                key,
+                |value| existing.iter().any(|other| other.contains(value)),
+            )?,
+            _ => any_key(
+                &self.storage,
+                self.session_id,
+                partition_schema_id,
+                partition_table_id,
+                key,
+                |value| !partition.contains(value),
+            )?,
+        };
+        if violated {
+            return self.report(QueryError::check_violation(format!(
+                "partition constraint of relation \"{}\" is violated by some row",
+                partition_name
+            )));
+        }
+        if let Some(error) =
+            default_partition_conflict(&self.storage, self.session_id, schema_id, table_id, key, &bounds)?
+        {
+            return self.report(error);
+        }
+        if let Err(existing) = self.storage.attach_partition(schema_id, table_id, partition) {
+            return self.report(overlap_error(&self.storage, partition_name, &existing));
+        }
+        self.sender
+            .send(Ok(QueryEvent::TableAltered))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    fn report(&self, error: QueryError) -> SystemResult<()> {
+        self.sender.send(Err(error)).expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
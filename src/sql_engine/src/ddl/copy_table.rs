@@ -0,0 +1,81 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::query::plan::TableCopyInfo;
+use data_manager::{DataManager, OperationOnTableError, SessionId};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use std::sync::Arc;
+
+pub(crate) struct CopyTableCommand {
+    copy_info: TableCopyInfo,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CopyTableCommand {
+    pub(crate) fn new(
+        copy_info: TableCopyInfo,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CopyTableCommand {
+        CopyTableCommand {
+            copy_info,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let source = &self.copy_info.source;
+        let target = &self.copy_info.target;
+        match self.storage.copy_table(
+            self.session_id,
+            source.schema_name(),
+            source.name(),
+            target.schema_name(),
+            target.name(),
+        )? {
+            Ok(()) => {
+                if let Some((schema_id, Some(table_id))) =
+                    self.storage.table_exists(target.schema_name(), target.name())
+                {
+                    self.storage.grant_to_owner(self.session_id, schema_id, table_id);
+                }
+                self.sender
+                    .send(Ok(QueryEvent::TableCreated))
+                    .expect("To Send Query Result to Client")
+            }
+            Err(OperationOnTableError::SchemaDoesNotExist(schema_name)) => self
+                .sender
+                .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                .expect("To Send Query Result to Client"),
+            Err(OperationOnTableError::TableDoesNotExist(table_name)) => self
+                .sender
+                .send(Err(QueryError::table_does_not_exist(table_name)))
+                .expect("To Send Query Result to Client"),
+            Err(OperationOnTableError::TableAlreadyExists(table_name)) => self
+                .sender
+                .send(Err(QueryError::table_already_exists(table_name)))
+                .expect("To Send Query Result to Client"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    ddl::function_schema,
+    dml::relation::{ColumnReferenceError, Frame, RelationColumn, Scope},
+    query::process::sql_type,
+};
+use data_manager::{DataManager, FunctionDefinition, RecordId, ReturnType};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::{DataType, Expr, Ident, ObjectName, Statement};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FunctionBody {
+    Expr(Expr),
+    /// statement of a function that `RETURNS TRIGGER`
+    Statement(Statement),
+}
+
+pub(crate) struct CreateFunctionCommand {
+    name: ObjectName,
+    args: Vec<(Ident, DataType)>,
+    return_type: DataType,
+    body: FunctionBody,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CreateFunctionCommand {
+    pub(crate) fn new(
+        name: ObjectName,
+        args: Vec<(Ident, DataType)>,
+        return_type: DataType,
+        body: FunctionBody,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CreateFunctionCommand {
+        CreateFunctionCommand {
+            name,
+            args,
+            return_type,
+            body,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, function_name) = match function_schema(&self.name, &self.storage, self.sender.as_ref()) {
+            Some(function) => function,
+            None => return Ok(()),
+        };
+        let body = match &self.body {
+            FunctionBody::Expr(body) => body,
+            FunctionBody::Statement(_) if !self.args.is_empty() => {
+                self.sender
+                    .send(Err(QueryError::invalid_object_definition(
+                        "trigger functions cannot have declared arguments".to_owned(),
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+            FunctionBody::Statement(statement) => {
+                return self.create(
+                    schema_id,
+                    FunctionDefinition::new(
+                        function_name.as_str(),
+                        vec![],
+                        ReturnType::Trigger,
+                        statement.to_string(),
+                    ),
+                )
+            }
+        };
+        let mut args = vec![];
+        for (name, data_type) in self.args.iter() {
+            match sql_type(data_type) {
+                Ok(sql_type) => args.push((name.value.clone(), sql_type)),
+                Err(error) => {
+                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                    return Ok(());
+                }
+            }
+        }
+        let return_type = match sql_type(&self.return_type) {
+            Ok(sql_type) => sql_type,
+            Err(error) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        };
+        // body could reference only arguments of the function
+        let columns: Vec<RelationColumn> = args
+            .iter()
+            .map(|(name, sql_type)| RelationColumn::new(name.clone(), sql_type.into()))
+            .collect();
+        let error = match Scope::new(Frame::header(&columns), None).check(body) {
+            Ok(()) => None,
+            Err(ColumnReferenceError::Missing(columns)) => Some(QueryError::column_does_not_exist(columns)),
+            Err(ColumnReferenceError::Ambiguous(column)) => Some(QueryError::ambiguous_column(column)),
+        };
+        if let Some(error) = error {
+            self.sender.send(Err(error)).expect("To Send Query Result to Client");
+            return Ok(());
+        }
+        self.create(
+            schema_id,
+            FunctionDefinition::new(
+                function_name.as_str(),
+                args,
+                ReturnType::Value(return_type),
+                body.to_string(),
+            ),
+        )
+    }
+
+    fn create(&self, schema_id: RecordId, function: FunctionDefinition) -> SystemResult<()> {
+        if self.storage.function_exists(schema_id, function.name()) {
+            self.sender
+                .send(Err(QueryError::function_already_exists(self.name.to_string())))
+                .expect("To Send Query Result to Client");
+            return Ok(());
+        }
+        self.storage.create_function(schema_id, function);
+        self.sender
+            .send(Ok(QueryEvent::FunctionCreated))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    dml::{column_datum, constraint_error},
+    query::{TableId, TableNamingError},
+};
+use data_manager::{
+    compare_keys, ColumnDefinition, DataManager, PartitionBounds, PartitionDefinition, PartitionStrategy, RecordId,
+    SessionId,
+};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use representation::{unpack_column, Datum};
+use sqlparser::ast::{Expr, ObjectName, UnaryOperator, Value};
+use std::{cmp::Ordering, convert::TryFrom, sync::Arc};
+
+/// Bound of a partition as it is written after `PARTITION OF <table>` or `ATTACH PARTITION <table>`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PartitionBound {
+    /// `FOR VALUES FROM (<lower>) TO (<upper>)`
+    Range { lower: Box<Expr>, upper: Box<Expr> },
+    /// `FOR VALUES IN (<values>)`
+    List(Vec<Expr>),
+    /// `DEFAULT`
+    Default,
+}
+
+pub(crate) struct CreatePartitionCommand {
+    table_name: ObjectName,
+    parent: ObjectName,
+    bound: PartitionBound,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CreatePartitionCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        parent: ObjectName,
+        bound: PartitionBound,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CreatePartitionCommand {
+        CreatePartitionCommand {
+            table_name,
+            parent,
+            bound,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (table, parent) = match (
+            TableId::try_from(self.table_name.clone()),
+            TableId::try_from(self.parent.clone()),
+        ) {
+            (Ok(table), Ok(parent)) => (table, parent),
+            (Err(TableNamingError(error)), _) | (_, Err(TableNamingError(error))) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        };
+        let (parent_schema_id, parent_table_id) = match self.storage.table_exists(parent.schema_name(), parent.name()) {
+            None => return self.report(QueryError::schema_does_not_exist(parent.schema_name().to_owned())),
+            Some((_, None)) => {
+                return self.report(QueryError::table_does_not_exist(format!(
+                    "{}.{}",
+                    parent.schema_name(),
+                    parent.name()
+                )))
+            }
+            Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        };
+        let (strategy, key) = match (
+            self.storage.partition_strategy(parent_schema_id, parent_table_id),
+            self.storage.partition_key(parent_schema_id, parent_table_id),
+        ) {
+            (Some(strategy), Some(key)) => (strategy, key),
+            _ => return self.report(QueryError::table_is_not_partitioned(parent.name().to_owned())),
+        };
+        let schema_id = match self.storage.table_exists(table.schema_name(), table.name()) {
+            None => return self.report(QueryError::schema_does_not_exist(table.schema_name().to_owned())),
+            Some((_, Some(_))) => {
+                return self.report(QueryError::table_already_exists(format!(
+                    "{}.{}",
+                    table.schema_name(),
+                    table.name()
+                )))
+            }
+            Some((schema_id, None)) => schema_id,
+        };
+        let description = match self.storage.describe_table(parent.schema_name(), parent.name()) {
+            Some(description) => description,
+            None => {
+                return self.report(QueryError::table_does_not_exist(format!(
+                    "{}.{}",
+                    parent.schema_name(),
+                    parent.name()
+                )))
+            }
+        };
+        let bounds = match partition_bounds(&self.bound, strategy, &description.columns[key], table.name()) {
+            Ok(bounds) => bounds,
+            Err(error) => return self.report(error),
+        };
+        if let Some(error) = default_partition_conflict(
+            &self.storage,
+            self.session_id,
+            parent_schema_id,
+            parent_table_id,
+            key,
+            &bounds,
+        )? {
+            return self.report(error);
+        }
+
+        let table_id =
+            self.storage
+                .create_table_with_layout(schema_id, table.name(), &description.columns, description.layout)?;
+        let partition = PartitionDefinition::new(schema_id, table_id, bounds);
+        if let Err(existing) = self
+            .storage
+            .attach_partition(parent_schema_id, parent_table_id, partition)
+        {
+            self.storage.drop_table(schema_id, table_id)?;
+            return self.report(overlap_error(&self.storage, table.name(), &existing));
+        }
+        self.storage.grant_to_owner(self.session_id, schema_id, table_id);
+        self.sender
+            .send(Ok(QueryEvent::TableCreated))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    fn report(&self, error: QueryError) -> SystemResult<()> {
+        self.sender.send(Err(error)).expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
+
+/// bounds of the partition `name` of a table that is partitioned with the `strategy` by the `key` column
+pub(crate) fn partition_bounds(
+    bound: &PartitionBound,
+    strategy: PartitionStrategy,
+    key: &ColumnDefinition,
+    name: &str,
+) -> Result<PartitionBounds, QueryError> {
+    match (bound, strategy) {
+        (PartitionBound::Default, _) => Ok(PartitionBounds::Default),
+        (PartitionBound::Range { lower, upper }, PartitionStrategy::Range) => {
+            let lower = bound_value(lower, key)?;
+            let upper = bound_value(upper, key)?;
+            if compare_keys(&lower, &upper) == Some(Ordering::Less) {
+                Ok(PartitionBounds::Range { lower, upper })
+            } else {
+                Err(QueryError::invalid_object_definition(format!(
+                    "empty range bound specified for partition \"{}\"",
+                    name
+                )))
+            }
+        }
+        (PartitionBound::List(values), PartitionStrategy::List) => values
+            .iter()
+            .map(|value| bound_value(value, key))
+            .collect::<Result<Vec<_>, _>>()
+            .map(PartitionBounds::List),
+        (_, PartitionStrategy::Range) => Err(QueryError::invalid_object_definition(
+            "invalid bound specification for a range partition".to_owned(),
+        )),
+        (_, PartitionStrategy::List) => Err(QueryError::invalid_object_definition(
+            "invalid bound specification for a list partition".to_owned(),
+        )),
+    }
+}
+
+// bounds are literals of the partition key type
+fn bound_value(bound: &Expr, key: &ColumnDefinition) -> Result<Datum<'static>, QueryError> {
+    let value = match bound {
+        Expr::Value(value @ Value::Number(_)) | Expr::Value(value @ Value::SingleQuotedString(_)) => value.clone(),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => match expr.as_ref() {
+            Expr::Value(Value::Number(number)) => Value::Number(-number.clone()),
+            _ => return Err(QueryError::feature_not_supported(format!("partition bound {}", bound))),
+        },
+        _ => return Err(QueryError::feature_not_supported(format!("partition bound {}", bound))),
+    };
+    let text = match &value {
+        Value::Number(number) => number.to_string(),
+        Value::SingleQuotedString(text) => text.clone(),
+        _ => unreachable!(),
+    };
+    match key.sql_type().constraint().validate(text.as_str()) {
+        Ok(()) => Ok(column_datum(&value, &key.sql_type())),
+        Err(error) => Err(constraint_error(error, key, 0)),
+    }
+}
+
+/// rows of the default partition that belong to a new partition with the `bounds` would be out of
+/// its bounds, such rows have to be moved before the partition is added
+pub(crate) fn default_partition_conflict(
+    storage: &DataManager,
+    session_id: SessionId,
+    schema_id: RecordId,
+    table_id: RecordId,
+    key: usize,
+    bounds: &PartitionBounds,
+) -> SystemResult<Option<QueryError>> {
+    let default = match storage
+        .partitions(schema_id, table_id)
+        .into_iter()
+        .find(PartitionDefinition::is_default)
+    {
+        Some(default) if *bounds != PartitionBounds::Default => default,
+        _ => return Ok(None),
+    };
+    let (default_schema_id, default_table_id) = default.table();
+    let partition = PartitionDefinition::new(default_schema_id, default_table_id, bounds.clone());
+    if any_key(storage, session_id, default_schema_id, default_table_id, key, |value| {
+        partition.contains(value)
+    })? {
+        Ok(Some(QueryError::check_violation(format!(
+            "updated partition constraint for default partition \"{}\" would be violated by some row",
+            storage
+                .table_name(default_schema_id, default_table_id)
+                .unwrap_or_default()
+        ))))
+    } else {
+        Ok(None)
+    }
+}
+
+/// whether the partition key of some row of the table satisfies the `predicate`
+pub(crate) fn any_key(
+    storage: &DataManager,
+    session_id: SessionId,
+    schema_id: RecordId,
+    table_id: RecordId,
+    key: usize,
+    predicate: impl Fn(&Datum) -> bool,
+) -> SystemResult<bool> {
+    Ok(storage
+        .full_scan(session_id, schema_id, table_id)?
+        .map(Result::unwrap)
+        .map(Result::unwrap)
+        .any(|(_key, values)| matches!(unpack_column(values.to_bytes(), key), Some(value) if predicate(&value))))
+}
+
+pub(crate) fn overlap_error(storage: &DataManager, name: &str, existing: &PartitionDefinition) -> QueryError {
+    let (existing_schema_id, existing_table_id) = existing.table();
+    QueryError::invalid_object_definition(format!(
+        "partition \"{}\" would overlap partition \"{}\"",
+        name,
+        storage
+            .table_name(existing_schema_id, existing_table_id)
+            .unwrap_or_default()
+    ))
+}
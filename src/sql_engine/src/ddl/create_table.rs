@@ -13,16 +13,19 @@
 // limitations under the License.
 
 use crate::query::plan::TableCreationInfo;
-use data_manager::DataManager;
+use data_manager::{DataManager, PartitionStrategy, SessionId};
 use kernel::SystemResult;
 use protocol::{
     results::{QueryError, QueryEvent},
     Sender,
 };
+use sqlparser::ast::Ident;
 use std::sync::Arc;
 
 pub(crate) struct CreateTableCommand {
     table_info: TableCreationInfo,
+    partition_key: Option<(PartitionStrategy, Ident)>,
+    session_id: SessionId,
     storage: Arc<DataManager>,
     sender: Arc<dyn Sender>,
 }
@@ -30,20 +33,33 @@ pub(crate) struct CreateTableCommand {
 impl CreateTableCommand {
     pub(crate) fn new(
         table_info: TableCreationInfo,
+        session_id: SessionId,
         storage: Arc<DataManager>,
         sender: Arc<dyn Sender>,
     ) -> CreateTableCommand {
         CreateTableCommand {
             table_info,
+            partition_key: None,
+            session_id,
             storage,
             sender,
         }
     }
 
+    /// rows of the table are stored in partitions by ranges or lists of the `key` column values
+    pub(crate) fn with_partition_key(mut self, strategy: PartitionStrategy, key: Ident) -> CreateTableCommand {
+        self.partition_key = Some((strategy, key));
+        self
+    }
+
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
         let table_name = self.table_info.table_name.as_str();
         let schema_name = self.table_info.schema_name.as_str();
 
+        if self.table_info.temporary {
+            return self.execute_temporary(schema_name, table_name);
+        }
+
         match self.storage.table_exists(schema_name, table_name) {
             None => self
                 .sender
@@ -54,18 +70,75 @@ impl CreateTableCommand {
                 .send(Err(QueryError::table_already_exists(table_name.to_owned())))
                 .expect("To Send Query Result to Client"),
             Some((schema_id, None)) => {
-                match self
-                    .storage
-                    .create_table(schema_id, table_name, self.table_info.columns.as_slice())
-                {
+                let partition_key = match &self.partition_key {
+                    None => None,
+                    Some((strategy, key)) => match self
+                        .table_info
+                        .columns
+                        .iter()
+                        .position(|column| column.has_name(&key.value))
+                    {
+                        Some(index) => Some((*strategy, index)),
+                        None => {
+                            self.sender
+                                .send(Err(QueryError::column_does_not_exist(vec![key.value.clone()])))
+                                .expect("To Send Query Result to Client");
+                            return Ok(());
+                        }
+                    },
+                };
+                match self.storage.create_table_with_layout(
+                    schema_id,
+                    table_name,
+                    self.table_info.columns.as_slice(),
+                    self.table_info.layout,
+                ) {
                     Err(error) => return Err(error),
-                    Ok(_table_id) => self
-                        .sender
-                        .send(Ok(QueryEvent::TableCreated))
-                        .expect("To Send Query Result to Client"),
+                    Ok(table_id) => {
+                        if let Some((strategy, key)) = partition_key {
+                            self.storage.partition_by(schema_id, table_id, strategy, key);
+                        }
+                        self.storage.grant_to_owner(self.session_id, schema_id, table_id);
+                        self.sender
+                            .send(Ok(QueryEvent::TableCreated))
+                            .expect("To Send Query Result to Client")
+                    }
                 }
             }
         }
         Ok(())
     }
+
+    fn execute_temporary(&self, schema_name: &str, table_name: &str) -> SystemResult<()> {
+        if self.storage.schema_exists(schema_name).is_none() {
+            self.sender
+                .send(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
+                .expect("To Send Query Result to Client");
+        } else if self
+            .storage
+            .temp_table_exists(self.session_id, schema_name, table_name)
+            .is_some()
+        {
+            self.sender
+                .send(Err(QueryError::table_already_exists(table_name.to_owned())))
+                .expect("To Send Query Result to Client");
+        } else {
+            self.storage.create_temp_table(
+                self.session_id,
+                schema_name,
+                table_name,
+                self.table_info.columns.as_slice(),
+                self.table_info.layout,
+            )?;
+            if let Some((schema_id, table_id)) =
+                self.storage.temp_table_exists(self.session_id, schema_name, table_name)
+            {
+                self.storage.grant_to_owner(self.session_id, schema_id, table_id);
+            }
+            self.sender
+                .send(Ok(QueryEvent::TableCreated))
+                .expect("To Send Query Result to Client");
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,135 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ddl::{existing_table, function_schema};
+use data_manager::{DataManager, ReturnType, SessionId, TriggerDefinition, TriggerEvent, TriggerTiming};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::{Ident, ObjectName};
+use std::sync::Arc;
+
+pub(crate) struct CreateTriggerCommand {
+    name: Ident,
+    timing: TriggerTiming,
+    events: Vec<TriggerEvent>,
+    table_name: ObjectName,
+    function: ObjectName,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CreateTriggerCommand {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        name: Ident,
+        timing: TriggerTiming,
+        events: Vec<TriggerEvent>,
+        table_name: ObjectName,
+        function: ObjectName,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CreateTriggerCommand {
+        CreateTriggerCommand {
+            name,
+            timing,
+            events,
+            table_name,
+            function,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, table_id) =
+            match existing_table(&self.table_name, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        // function of a trigger is looked up in the schema of the table if it is not qualified
+        let function = match self.function.0.as_slice() {
+            [name] => ObjectName(vec![self.table_name.0[0].clone(), name.clone()]),
+            _ => self.function.clone(),
+        };
+        let (function_schema_id, function_name) = match function_schema(&function, &self.storage, self.sender.as_ref())
+        {
+            Some(function) => function,
+            None => return Ok(()),
+        };
+        match self.storage.function(function_schema_id, function_name.as_str()) {
+            None => {
+                self.sender
+                    .send(Err(QueryError::function_does_not_exist(format!("{}()", function))))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+            Some(definition) if definition.return_type() != ReturnType::Trigger => {
+                self.sender
+                    .send(Err(QueryError::invalid_object_definition(format!(
+                        "function {} must return type trigger",
+                        function
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+            Some(_) => {}
+        }
+        let all_columns = self.storage.table_columns(schema_id, table_id)?;
+        let missing: Vec<String> = self
+            .events
+            .iter()
+            .flat_map(|event| match event {
+                TriggerEvent::Update(columns) => columns.clone(),
+                _ => vec![],
+            })
+            .filter(|column| !all_columns.iter().any(|definition| definition.has_name(column)))
+            .collect();
+        if !missing.is_empty() {
+            self.sender
+                .send(Err(QueryError::column_does_not_exist(missing)))
+                .expect("To Send Query Result to Client");
+            return Ok(());
+        }
+        if self.storage.trigger_exists(schema_id, table_id, &self.name.value) {
+            self.sender
+                .send(Err(QueryError::trigger_already_exists(
+                    self.name.value.clone(),
+                    self.table_name.0[1].value.clone(),
+                )))
+                .expect("To Send Query Result to Client");
+            return Ok(());
+        }
+        self.storage.create_trigger(
+            schema_id,
+            table_id,
+            TriggerDefinition::new(
+                &self.name.value,
+                self.timing,
+                self.events.clone(),
+                function_schema_id,
+                &function_name,
+            ),
+        );
+        self.sender
+            .send(Ok(QueryEvent::TriggerCreated))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
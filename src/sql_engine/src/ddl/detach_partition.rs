@@ -0,0 +1,78 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ddl::existing_table;
+use data_manager::{DataManager, SessionId};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+
+/// detached partition becomes a regular table with the rows it had
+pub(crate) struct DetachPartitionCommand {
+    table_name: ObjectName,
+    partition: ObjectName,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl DetachPartitionCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        partition: ObjectName,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> DetachPartitionCommand {
+        DetachPartitionCommand {
+            table_name,
+            partition,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, table_id) =
+            match existing_table(&self.table_name, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        let (partition_schema_id, partition_table_id) =
+            match existing_table(&self.partition, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        let result = if self.storage.partition_key(schema_id, table_id).is_none() {
+            Err(QueryError::table_is_not_partitioned(self.table_name.0[1].value.clone()))
+        } else if self
+            .storage
+            .detach_partition(schema_id, table_id, partition_schema_id, partition_table_id)
+        {
+            Ok(QueryEvent::TableAltered)
+        } else {
+            Err(QueryError::not_a_partition(
+                self.partition.0[1].value.clone(),
+                self.table_name.0[1].value.clone(),
+            ))
+        };
+        self.sender.send(result).expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
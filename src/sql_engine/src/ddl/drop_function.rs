@@ -0,0 +1,72 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ddl::function_schema;
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+
+pub(crate) struct DropFunctionCommand {
+    names: Vec<ObjectName>,
+    if_exists: bool,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl DropFunctionCommand {
+    pub(crate) fn new(
+        names: Vec<ObjectName>,
+        if_exists: bool,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> DropFunctionCommand {
+        DropFunctionCommand {
+            names,
+            if_exists,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        // nothing is dropped if any of the functions does not exist
+        let mut functions = vec![];
+        for name in self.names.iter() {
+            let (schema_id, function_name) = match function_schema(name, &self.storage, self.sender.as_ref()) {
+                Some(function) => function,
+                None => return Ok(()),
+            };
+            if self.storage.function_exists(schema_id, function_name.as_str()) {
+                functions.push((schema_id, function_name));
+            } else if !self.if_exists {
+                self.sender
+                    .send(Err(QueryError::function_does_not_exist(name.to_string())))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        }
+        for (schema_id, function_name) in functions {
+            self.storage.drop_function(schema_id, function_name.as_str());
+        }
+        self.sender
+            .send(Ok(QueryEvent::FunctionDropped))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
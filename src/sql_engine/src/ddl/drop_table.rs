@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::query::TableId;
-use data_manager::DataManager;
+use data_manager::{DataManager, SessionId};
 use kernel::SystemResult;
 use protocol::{
     results::{QueryError, QueryEvent},
@@ -23,19 +23,36 @@ use std::sync::Arc;
 
 pub(crate) struct DropTableCommand {
     name: TableId,
+    cascade: bool,
+    session_id: SessionId,
     storage: Arc<DataManager>,
     sender: Arc<dyn Sender>,
 }
 
 impl DropTableCommand {
-    pub(crate) fn new(name: TableId, storage: Arc<DataManager>, sender: Arc<dyn Sender>) -> DropTableCommand {
-        DropTableCommand { name, storage, sender }
+    pub(crate) fn new(
+        name: TableId,
+        cascade: bool,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> DropTableCommand {
+        DropTableCommand {
+            name,
+            cascade,
+            session_id,
+            storage,
+            sender,
+        }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
         let table_name = self.name.name();
         let schema_name = self.name.schema_name();
-        match self.storage.table_exists(schema_name, table_name) {
+        match self
+            .storage
+            .session_table_exists(self.session_id, schema_name, table_name)
+        {
             None => self
                 .sender
                 .send(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
@@ -46,13 +63,38 @@ impl DropTableCommand {
                     schema_name.to_owned() + "." + table_name,
                 )))
                 .expect("To Send Query Result to Client"),
-            Some((schema_id, Some(table_id))) => match self.storage.drop_table(schema_id, table_id) {
-                Err(error) => return Err(error),
-                Ok(()) => self
-                    .sender
+            Some((schema_id, Some(table_id))) => {
+                if !self.cascade {
+                    // foreign keys of other tables that reference the table are dropped only with CASCADE
+                    let dependent = self
+                        .storage
+                        .referencing_foreign_keys(schema_id, table_id)
+                        .into_iter()
+                        .find(|(referencing_schema_id, referencing_table_id, _)| {
+                            (*referencing_schema_id, *referencing_table_id) != (schema_id, table_id)
+                        });
+                    if let Some((referencing_schema_id, referencing_table_id, foreign_key)) = dependent {
+                        let referencing_table = self
+                            .storage
+                            .table_name(referencing_schema_id, referencing_table_id)
+                            .unwrap_or_default();
+                        self.sender
+                            .send(Err(QueryError::table_has_dependent_objects(table_name.to_owned())
+                                .with_detail(format!(
+                                    "constraint {} on table {} depends on table {}",
+                                    foreign_key.name(),
+                                    referencing_table,
+                                    table_name
+                                ))))
+                            .expect("To Send Query Result to Client");
+                        return Ok(());
+                    }
+                }
+                self.storage.drop_table(schema_id, table_id)?;
+                self.sender
                     .send(Ok(QueryEvent::TableDropped))
-                    .expect("To Send Query Result to Client"),
-            },
+                    .expect("To Send Query Result to Client");
+            }
         }
         Ok(())
     }
@@ -0,0 +1,75 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ddl::existing_table;
+use data_manager::{DataManager, SessionId};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::{Ident, ObjectName};
+use std::sync::Arc;
+
+pub(crate) struct EnableTriggerCommand {
+    table_name: ObjectName,
+    name: Ident,
+    enabled: bool,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl EnableTriggerCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        name: Ident,
+        enabled: bool,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> EnableTriggerCommand {
+        EnableTriggerCommand {
+            table_name,
+            name,
+            enabled,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, table_id) =
+            match existing_table(&self.table_name, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        if !self.storage.trigger_exists(schema_id, table_id, &self.name.value) {
+            self.sender
+                .send(Err(QueryError::trigger_does_not_exist(
+                    self.name.value.clone(),
+                    self.table_name.0[1].value.clone(),
+                )))
+                .expect("To Send Query Result to Client");
+            return Ok(());
+        }
+        self.storage
+            .enable_trigger(schema_id, table_id, &self.name.value, self.enabled);
+        self.sender
+            .send(Ok(QueryEvent::TableAltered))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
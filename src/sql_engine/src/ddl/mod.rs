@@ -12,7 +12,80 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod alter_schema;
+pub(crate) mod alter_table;
+pub(crate) mod attach_partition;
+pub(crate) mod copy_table;
+pub(crate) mod create_function;
+pub(crate) mod create_partition;
 pub(crate) mod create_schema;
 pub(crate) mod create_table;
+pub(crate) mod create_trigger;
+pub(crate) mod detach_partition;
+pub(crate) mod drop_function;
 pub(crate) mod drop_schema;
 pub(crate) mod drop_table;
+pub(crate) mod drop_trigger;
+pub(crate) mod enable_trigger;
+
+use crate::query::{TableId, TableNamingError};
+use data_manager::{DataManager, RecordId, SessionId};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::ObjectName;
+use std::convert::TryFrom;
+
+// functions are named as tables, e.g. `schema_name.function_name`. Returns id of the schema and
+// name of the function, `None` when an error was sent to the client
+fn function_schema(name: &ObjectName, storage: &DataManager, sender: &dyn Sender) -> Option<(RecordId, String)> {
+    let function = match TableId::try_from(name.clone()) {
+        Ok(function) => function,
+        Err(TableNamingError(error)) => {
+            sender.send(Err(error)).expect("To Send Query Result to Client");
+            return None;
+        }
+    };
+    match storage.schema_exists(function.schema_name()) {
+        Some(schema_id) => Some((schema_id, function.name().to_owned())),
+        None => {
+            sender
+                .send(Err(QueryError::schema_does_not_exist(
+                    function.schema_name().to_owned(),
+                )))
+                .expect("To Send Query Result to Client");
+            None
+        }
+    }
+}
+
+// table that is visible to the session, `None` when an error was sent to the client
+fn existing_table(
+    table_name: &ObjectName,
+    session_id: SessionId,
+    storage: &DataManager,
+    sender: &dyn Sender,
+) -> Option<(RecordId, RecordId)> {
+    let table = match TableId::try_from(table_name.clone()) {
+        Ok(table) => table,
+        Err(TableNamingError(error)) => {
+            sender.send(Err(error)).expect("To Send Query Result to Client");
+            return None;
+        }
+    };
+    match storage.session_table_exists(session_id, table.schema_name(), table.name()) {
+        None => {
+            sender
+                .send(Err(QueryError::schema_does_not_exist(table.schema_name().to_owned())))
+                .expect("To Send Query Result to Client");
+            None
+        }
+        Some((_, None)) => {
+            sender
+                .send(Err(QueryError::table_does_not_exist(
+                    table.schema_name().to_owned() + "." + table.name(),
+                )))
+                .expect("To Send Query Result to Client");
+            None
+        }
+        Some((schema_id, Some(table_id))) => Some((schema_id, table_id)),
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dml::{relation::RelationColumn, ExprResult};
+use bigdecimal::BigDecimal;
+use data_manager::DataManager;
+use kernel::SystemResult;
+use protocol::sql_types::{Oid, PostgreSqlType};
+use sqlparser::ast::{Ident, ObjectName};
+
+const CATALOG_SCHEMA: &str = "pg_catalog";
+/// oid of `pg_catalog` namespace in PostgreSQL
+const CATALOG_NAMESPACE: Oid = 11;
+/// oid of pseudo type of arguments that accept a value of any type
+const ANY: Oid = 2276;
+const INTEGER: Oid = 23;
+const VARCHAR: Oid = 1043;
+
+/// function that is evaluated by the engine, oids are the same as in PostgreSQL
+struct BuiltinFunction {
+    oid: Oid,
+    name: &'static str,
+    arg_types: &'static [Oid],
+    return_type: PostgreSqlType,
+}
+
+const BUILTIN_FUNCTIONS: &[BuiltinFunction] = &[
+    BuiltinFunction {
+        oid: 870,
+        name: "lower",
+        arg_types: &[VARCHAR],
+        return_type: PostgreSqlType::VarChar,
+    },
+    BuiltinFunction {
+        oid: 871,
+        name: "upper",
+        arg_types: &[VARCHAR],
+        return_type: PostgreSqlType::VarChar,
+    },
+    BuiltinFunction {
+        oid: 1066,
+        name: "generate_series",
+        arg_types: &[INTEGER, INTEGER, INTEGER],
+        return_type: PostgreSqlType::Integer,
+    },
+    BuiltinFunction {
+        oid: 1067,
+        name: "generate_series",
+        arg_types: &[INTEGER, INTEGER],
+        return_type: PostgreSqlType::Integer,
+    },
+    BuiltinFunction {
+        oid: 2026,
+        name: "pg_backend_pid",
+        arg_types: &[],
+        return_type: PostgreSqlType::Integer,
+    },
+    BuiltinFunction {
+        oid: 2147,
+        name: "count",
+        arg_types: &[ANY],
+        return_type: PostgreSqlType::BigInt,
+    },
+    BuiltinFunction {
+        oid: 2171,
+        name: "pg_cancel_backend",
+        arg_types: &[INTEGER],
+        return_type: PostgreSqlType::Bool,
+    },
+    BuiltinFunction {
+        oid: 2803,
+        name: "count",
+        arg_types: &[],
+        return_type: PostgreSqlType::BigInt,
+    },
+];
+
+const TYPES: &[PostgreSqlType] = &[
+    PostgreSqlType::Bool,
+    PostgreSqlType::Bytea,
+    PostgreSqlType::Char,
+    PostgreSqlType::BigInt,
+    PostgreSqlType::SmallInt,
+    PostgreSqlType::Integer,
+    PostgreSqlType::Json,
+    PostgreSqlType::Real,
+    PostgreSqlType::DoublePrecision,
+    PostgreSqlType::BoolArray,
+    PostgreSqlType::SmallIntArray,
+    PostgreSqlType::IntegerArray,
+    PostgreSqlType::CharArray,
+    PostgreSqlType::VarCharArray,
+    PostgreSqlType::BigIntArray,
+    PostgreSqlType::VarChar,
+    PostgreSqlType::Date,
+    PostgreSqlType::Time,
+    PostgreSqlType::Timestamp,
+    PostgreSqlType::TimestampWithTimeZone,
+    PostgreSqlType::Interval,
+    PostgreSqlType::TimeWithTimeZone,
+    PostgreSqlType::Decimal,
+];
+
+/// tables of `pg_catalog` that are computed from metadata of the engine instead of being stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CatalogTable {
+    /// functions that could be called in expressions
+    Proc,
+    /// data types of columns and values
+    Type,
+    /// numbers of scanned and changed rows of user tables
+    StatUserTables,
+}
+
+impl CatalogTable {
+    pub(crate) fn resolve(name: &ObjectName) -> Option<CatalogTable> {
+        match name.0.as_slice() {
+            [Ident { value: schema, .. }, Ident { value: table, .. }]
+                if schema.eq_ignore_ascii_case(CATALOG_SCHEMA) =>
+            {
+                match table.to_lowercase().as_str() {
+                    "pg_proc" => Some(CatalogTable::Proc),
+                    "pg_type" => Some(CatalogTable::Type),
+                    "pg_stat_user_tables" => Some(CatalogTable::StatUserTables),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            CatalogTable::Proc => "pg_proc",
+            CatalogTable::Type => "pg_type",
+            CatalogTable::StatUserTables => "pg_stat_user_tables",
+        }
+    }
+
+    pub(crate) fn columns(&self) -> Vec<RelationColumn> {
+        let columns: &[(&str, PostgreSqlType)] = match self {
+            CatalogTable::Proc => &[
+                ("oid", PostgreSqlType::Integer),
+                ("proname", PostgreSqlType::VarChar),
+                ("pronamespace", PostgreSqlType::Integer),
+                ("pronargs", PostgreSqlType::SmallInt),
+                ("proargtypes", PostgreSqlType::VarChar),
+                ("prorettype", PostgreSqlType::Integer),
+            ],
+            CatalogTable::Type => &[
+                ("oid", PostgreSqlType::Integer),
+                ("typname", PostgreSqlType::VarChar),
+                ("typnamespace", PostgreSqlType::Integer),
+                ("typlen", PostgreSqlType::SmallInt),
+            ],
+            CatalogTable::StatUserTables => &[
+                ("schemaname", PostgreSqlType::VarChar),
+                ("tablename", PostgreSqlType::VarChar),
+                ("n_live_tup", PostgreSqlType::BigInt),
+                ("n_dead_tup", PostgreSqlType::BigInt),
+                ("seq_scan", PostgreSqlType::BigInt),
+                ("seq_tup_read", PostgreSqlType::BigInt),
+                ("idx_scan", PostgreSqlType::BigInt),
+                ("idx_tup_fetch", PostgreSqlType::BigInt),
+                ("n_tup_ins", PostgreSqlType::BigInt),
+                ("n_tup_upd", PostgreSqlType::BigInt),
+                ("n_tup_del", PostgreSqlType::BigInt),
+            ],
+        };
+        columns
+            .iter()
+            .map(|(name, sql_type)| {
+                RelationColumn::qualified(
+                    Some(CATALOG_SCHEMA.to_owned()),
+                    Some(self.name().to_owned()),
+                    (*name).to_owned(),
+                    *sql_type,
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn rows(&self, storage: &DataManager) -> SystemResult<Vec<Vec<ExprResult>>> {
+        let rows = match self {
+            CatalogTable::Proc => BUILTIN_FUNCTIONS
+                .iter()
+                .map(|function| {
+                    vec![
+                        oid(function.oid),
+                        ExprResult::String(function.name.to_owned()),
+                        oid(CATALOG_NAMESPACE),
+                        ExprResult::Number(BigDecimal::from(function.arg_types.len() as u64)),
+                        // `oidvector` is represented as space separated oids
+                        ExprResult::String(
+                            function
+                                .arg_types
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(" "),
+                        ),
+                        oid(function.return_type.pg_oid()),
+                    ]
+                })
+                .collect(),
+            CatalogTable::Type => TYPES
+                .iter()
+                .map(|sql_type| {
+                    vec![
+                        oid(sql_type.pg_oid()),
+                        ExprResult::String(type_name(sql_type).to_owned()),
+                        oid(CATALOG_NAMESPACE),
+                        ExprResult::Number(BigDecimal::from(sql_type.pg_len())),
+                    ]
+                })
+                .collect(),
+            // tables do not have indexes thus index counters are NULLs as in PostgreSQL, DELETE removes rows
+            // from storage right away and there are no dead rows
+            CatalogTable::StatUserTables => storage
+                .tables_activity()?
+                .into_iter()
+                .map(|(schema_name, table_name, activity)| {
+                    vec![
+                        ExprResult::String(schema_name),
+                        ExprResult::String(table_name),
+                        counter(activity.live_rows),
+                        counter(0),
+                        counter(activity.sequential_scans),
+                        counter(activity.rows_read),
+                        ExprResult::Null,
+                        ExprResult::Null,
+                        counter(activity.rows_inserted),
+                        counter(activity.rows_updated),
+                        counter(activity.rows_deleted),
+                    ]
+                })
+                .collect(),
+        };
+        Ok(rows)
+    }
+}
+
+fn oid(oid: Oid) -> ExprResult {
+    ExprResult::Number(BigDecimal::from(oid))
+}
+
+fn counter(value: u64) -> ExprResult {
+    ExprResult::Number(BigDecimal::from(value))
+}
+
+// names that PostgreSQL uses internally for the types
+fn type_name(sql_type: &PostgreSqlType) -> &'static str {
+    match sql_type {
+        PostgreSqlType::Bool => "bool",
+        PostgreSqlType::Char => "char",
+        PostgreSqlType::VarChar => "varchar",
+        PostgreSqlType::Decimal => "numeric",
+        PostgreSqlType::SmallInt => "int2",
+        PostgreSqlType::Integer => "int4",
+        PostgreSqlType::BigInt => "int8",
+        PostgreSqlType::Real => "float4",
+        PostgreSqlType::DoublePrecision => "float8",
+        PostgreSqlType::Time => "time",
+        PostgreSqlType::TimeWithTimeZone => "timetz",
+        PostgreSqlType::Timestamp => "timestamp",
+        PostgreSqlType::TimestampWithTimeZone => "timestamptz",
+        PostgreSqlType::Date => "date",
+        PostgreSqlType::Interval => "interval",
+        PostgreSqlType::Json => "json",
+        PostgreSqlType::Bytea => "bytea",
+        PostgreSqlType::BoolArray => "_bool",
+        PostgreSqlType::CharArray => "_bpchar",
+        PostgreSqlType::VarCharArray => "_varchar",
+        PostgreSqlType::SmallIntArray => "_int2",
+        PostgreSqlType::IntegerArray => "_int4",
+        PostgreSqlType::BigIntArray => "_int8",
+    }
+}
@@ -0,0 +1,885 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    dml::{
+        column_datum, constraint_error, default_expressions, default_values, fit_char, generate_values,
+        generation_expressions, not_null_violation, ExprResult, ExpressionEvaluation,
+    },
+    query::{TableId, TableNamingError},
+};
+use bigdecimal::BigDecimal;
+use data_manager::{ColumnDefinition, DataManager, LockMode, Privilege, RecordId, SessionId, TableChange};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use representation::{unpack_raw, Binary, Datum};
+use sql_types::SqlType;
+use sqlparser::ast::{Ident, ObjectName, Value};
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    mem,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// the line that marks the end of data in text format
+const END_OF_DATA: &str = "\\.";
+const NULL: &str = "\\N";
+/// byte order mark that some editors write at the start of UTF-8 files
+const BYTE_ORDER_MARK: char = '\u{feff}';
+/// number of rows that are validated and written at once, rows of a file on the server are read
+/// batch by batch, data received from the client is kept in memory until the end of COPY
+const COPY_BATCH_SIZE: usize = 1024;
+
+/// format of rows that are copied from or to the client
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CopyFormat {
+    /// tab separated values, `\N` is NULL
+    Text,
+    /// comma separated values, unquoted empty value is NULL
+    Csv,
+}
+
+/// what happens with rows of `COPY FROM` that can't be inserted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OnError {
+    /// the first invalid row fails the whole COPY
+    Stop,
+    /// invalid rows are skipped and reported as notices
+    Ignore,
+}
+
+pub(crate) struct CopyFromCommand {
+    table_name: ObjectName,
+    columns: Vec<Ident>,
+    format: CopyFormat,
+    on_error: OnError,
+    header: bool,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CopyFromCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+        format: CopyFormat,
+        on_error: OnError,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CopyFromCommand {
+        CopyFromCommand {
+            table_name,
+            columns,
+            format,
+            on_error,
+            header: false,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    /// the first line of data is skipped when it is a header
+    pub(crate) fn with_header(mut self, header: bool) -> CopyFromCommand {
+        self.header = header;
+        self
+    }
+
+    // rows are received with CopyData messages, nothing is copied if the table or its columns don't exist
+    pub(crate) fn execute(&mut self) -> SystemResult<Option<CopyIn>> {
+        let copy_in = match self.copy_in()? {
+            Some(copy_in) => copy_in,
+            None => return Ok(None),
+        };
+        self.sender
+            .send(Ok(QueryEvent::CopyInStarted(copy_in.table.columns.len())))
+            .expect("To Send Query Result to Client");
+        Ok(Some(copy_in))
+    }
+
+    // rows are read from the file on the server line by line, only files inside of the `directory` could be read
+    pub(crate) fn execute_file(&mut self, file_name: &str, directory: Option<&Path>) -> SystemResult<()> {
+        let copy_in = match self.copy_in()? {
+            Some(copy_in) => copy_in,
+            None => return Ok(()),
+        };
+        match server_file(file_name, directory) {
+            Ok(file) => copy_in.insert(FileRows::new(file_name, BufReader::new(file), self.format)),
+            Err(error) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                Ok(())
+            }
+        }
+    }
+
+    fn copy_in(&self) -> SystemResult<Option<CopyIn>> {
+        let table = match self.target()? {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        self.storage
+            .lock_table(self.session_id, table.schema_id, table.table_id, LockMode::RowExclusive);
+        Ok(Some(CopyIn {
+            table,
+            format: self.format,
+            on_error: self.on_error,
+            header: self.header,
+            data: vec![],
+            session_id: self.session_id,
+            storage: self.storage.clone(),
+            sender: self.sender.clone(),
+        }))
+    }
+
+    // resolves the table and copied columns, the client is not asked for data
+    pub(crate) fn validate(&mut self) -> SystemResult<()> {
+        self.target().map(|_table| ())
+    }
+
+    fn target(&self) -> SystemResult<Option<CopiedTable>> {
+        CopiedTable::resolve(
+            &self.table_name,
+            &self.columns,
+            Privilege::Insert,
+            self.session_id,
+            &self.storage,
+            self.sender.as_ref(),
+        )
+    }
+}
+
+/// `COPY FROM STDIN` that receives data from the client
+pub(crate) struct CopyIn {
+    table: CopiedTable,
+    format: CopyFormat,
+    on_error: OnError,
+    header: bool,
+    data: Vec<u8>,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CopyIn {
+    pub(crate) fn receive(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+    }
+
+    // rows are written in batches, a single invalid row fails the whole COPY unless invalid rows
+    // are ignored, rows of the batches that were written before it are removed; batches are not
+    // written inside of a transaction, thus the table keeps rows of a failed COPY when they can't
+    // be removed or the server stops between batches
+    pub(crate) fn finish(mut self) -> SystemResult<()> {
+        let data = match String::from_utf8(mem::take(&mut self.data)) {
+            Ok(data) => data,
+            Err(_) => {
+                self.sender
+                    .send(Err(QueryError::bad_copy_file_format(
+                        "invalid byte sequence for encoding \"UTF8\"".to_owned(),
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        };
+        let rows = match self.format {
+            CopyFormat::Text => text_rows(&data),
+            CopyFormat::Csv => csv_rows(&data),
+        };
+        self.insert(rows.into_iter().map(Ok))
+    }
+
+    // rows that can't be read fail COPY even if invalid rows are ignored
+    fn insert(&self, rows: impl Iterator<Item = Result<Vec<Option<String>>, QueryError>>) -> SystemResult<()> {
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let generated = generation_expressions(&self.table.all_columns);
+        let defaults = default_expressions(&self.table.all_columns);
+        let mut skipped = 0;
+        let mut written = vec![];
+        let mut records = Vec::with_capacity(COPY_BATCH_SIZE);
+        for (row_index, fields) in rows.skip(if self.header { 1 } else { 0 }).enumerate() {
+            let fields = match fields {
+                Ok(fields) => fields,
+                Err(error) => {
+                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                    return self.undo(written);
+                }
+            };
+            match (self.record(row_index, fields), self.on_error) {
+                (Ok(mut record), _) => {
+                    let not_copied = |index: usize| {
+                        !self.table.columns.contains(&index) && self.table.all_columns[index].generated().is_none()
+                    };
+                    if default_values(
+                        &mut evaluation,
+                        &defaults,
+                        not_copied,
+                        &self.table.all_columns,
+                        &mut record,
+                        row_index,
+                        self.sender.as_ref(),
+                    )
+                    .is_err()
+                        || generate_values(
+                            &mut evaluation,
+                            &generated,
+                            &self.table.all_columns,
+                            &mut record,
+                            row_index,
+                            self.sender.as_ref(),
+                        )
+                        .is_err()
+                    {
+                        return self.undo(written);
+                    }
+                    match (not_null_violation(&self.table.all_columns, &record), self.on_error) {
+                        (None, _) => records.push((row_index, record)),
+                        (Some(error), OnError::Ignore) => {
+                            self.skip(row_index, error);
+                            skipped += 1;
+                        }
+                        (Some(error), OnError::Stop) => {
+                            self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                            return self.undo(written);
+                        }
+                    }
+                }
+                (Err(error), OnError::Ignore) => {
+                    self.skip(row_index, error);
+                    skipped += 1;
+                }
+                (Err(error), OnError::Stop) => {
+                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                    return self.undo(written);
+                }
+            }
+            if records.len() == COPY_BATCH_SIZE && !self.write(mem::take(&mut records), &mut written, &mut skipped)? {
+                return self.undo(written);
+            }
+        }
+        if !self.write(records, &mut written, &mut skipped)? {
+            return self.undo(written);
+        }
+
+        if skipped > 0 {
+            self.sender
+                .send(Ok(QueryEvent::Notice {
+                    severity: "NOTICE".to_owned(),
+                    message: if skipped == 1 {
+                        "1 row was skipped".to_owned()
+                    } else {
+                        format!("{} rows were skipped", skipped)
+                    },
+                }))
+                .expect("To Send Query Result to Client");
+        }
+        self.storage.count_changes(
+            self.table.schema_id,
+            self.table.table_id,
+            TableChange::Inserted,
+            written.len(),
+        );
+        self.sender
+            .send(Ok(QueryEvent::RecordsInserted(written.len())))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    // rows that violate unique constraints are not written, `false` when such a row fails COPY
+    fn write(
+        &self,
+        records: Vec<(usize, Vec<Datum<'static>>)>,
+        written: &mut Vec<RecordId>,
+        skipped: &mut usize,
+    ) -> SystemResult<bool> {
+        let (schema_id, table_id) = (self.table.schema_id, self.table.table_id);
+        let keys = records
+            .iter()
+            .map(|_record| self.storage.next_key_id(schema_id, table_id))
+            .collect::<Vec<_>>();
+        let rows = keys
+            .iter()
+            .zip(records.iter())
+            .map(|(key, (_row_index, record))| (Binary::with_data(key.to_be_bytes().to_vec()), Binary::pack(record)))
+            .collect::<Vec<_>>();
+        let conflicts = self.storage.unique_conflicts(schema_id, table_id, &rows)?;
+        let mut to_write = vec![];
+        let mut written_keys = vec![];
+        for (((row, conflict), (row_index, _record)), key) in rows.into_iter().zip(conflicts).zip(records).zip(keys) {
+            let column = match conflict {
+                None => {
+                    to_write.push(row);
+                    written_keys.push(key);
+                    continue;
+                }
+                Some(column) => column,
+            };
+            let error = QueryError::unique_violation(format!("{}_{}_key", self.table.name, column));
+            match self.on_error {
+                OnError::Ignore => {
+                    self.skip(row_index, error);
+                    *skipped += 1;
+                }
+                OnError::Stop => {
+                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                    return Ok(false);
+                }
+            }
+        }
+        if !to_write.is_empty() {
+            self.storage
+                .write_into(self.session_id, schema_id, table_id, to_write)?;
+        }
+        written.extend(written_keys);
+        Ok(true)
+    }
+
+    // COPY failed after its error was sent, rows that it has already written are removed,
+    // the rows stay in the table if removing them fails
+    fn undo(&self, written: Vec<RecordId>) -> SystemResult<()> {
+        if !written.is_empty() {
+            self.storage.delete_from(
+                self.session_id,
+                self.table.schema_id,
+                self.table.table_id,
+                written
+                    .into_iter()
+                    .map(|key| Binary::with_data(key.to_be_bytes().to_vec()))
+                    .collect(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn skip(&self, row_index: usize, error: QueryError) {
+        self.sender
+            .send(Ok(QueryEvent::Notice {
+                severity: "NOTICE".to_owned(),
+                message: format!("skipping row {}: {}", row_index + 1, error),
+            }))
+            .expect("To Send Query Result to Client");
+    }
+
+    // columns that are not copied are NULL until their default values are stored
+    fn record(&self, row_index: usize, fields: Vec<Option<String>>) -> Result<Vec<Datum<'static>>, QueryError> {
+        let all_columns = &self.table.all_columns;
+        if fields.len() > self.table.columns.len() {
+            return Err(QueryError::bad_copy_file_format(
+                "extra data after last expected column".to_owned(),
+            ));
+        }
+        if let Some(missing) = self.table.columns.get(fields.len()) {
+            return Err(QueryError::bad_copy_file_format(format!(
+                "missing data for column \"{}\"",
+                all_columns[*missing].name()
+            )));
+        }
+
+        let mut record = vec![Datum::from_null(); all_columns.len()];
+        for (field, index) in fields.into_iter().zip(self.table.columns.iter()) {
+            let column_definition = &all_columns[*index];
+            let text = match field {
+                Some(text) => text,
+                None => continue,
+            };
+            let text = match fit_char(
+                Value::SingleQuotedString(text),
+                column_definition,
+                row_index,
+                self.sender.as_ref(),
+            ) {
+                Value::SingleQuotedString(text) => text,
+                _ => unreachable!(),
+            };
+            let sql_type = column_definition.sql_type();
+            match sql_type.constraint().validate(text.as_str()) {
+                Ok(()) => record[*index] = column_datum(&text_value(text, &sql_type), &sql_type),
+                Err(error) => return Err(constraint_error(error, column_definition, row_index)),
+            }
+        }
+        Ok(record)
+    }
+}
+
+pub(crate) struct CopyToCommand {
+    table_name: ObjectName,
+    columns: Vec<Ident>,
+    format: CopyFormat,
+    header: bool,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl CopyToCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+        format: CopyFormat,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> CopyToCommand {
+        CopyToCommand {
+            table_name,
+            columns,
+            format,
+            header: false,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    /// names of copied columns are sent as the first line
+    pub(crate) fn with_header(mut self, header: bool) -> CopyToCommand {
+        self.header = header;
+        self
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let table = match self.target()? {
+            Some(table) => table,
+            None => return Ok(()),
+        };
+        self.storage
+            .lock_table(self.session_id, table.schema_id, table.table_id, LockMode::AccessShare);
+        let mut rows = vec![];
+        if self.header {
+            let names = table
+                .columns
+                .iter()
+                .map(|index| Some(table.all_columns[*index].name()))
+                .collect();
+            rows.push(match self.format {
+                CopyFormat::Text => text_row(names),
+                CopyFormat::Csv => csv_row(names),
+            });
+        }
+        let values = self
+            .storage
+            .full_scan(self.session_id, table.schema_id, table.table_id)?
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .map(|(_key, values)| {
+                let datums = unpack_raw(values.to_bytes());
+                let fields = table
+                    .columns
+                    .iter()
+                    .map(|index| match &datums[*index] {
+                        Datum::Null => None,
+                        datum => Some(ExprResult::from(datum.clone()).to_string()),
+                    })
+                    .collect::<Vec<_>>();
+                match self.format {
+                    CopyFormat::Text => text_row(fields),
+                    CopyFormat::Csv => csv_row(fields),
+                }
+            });
+        rows.extend(values);
+
+        self.sender
+            .send(Ok(QueryEvent::RecordsCopied {
+                columns: table.columns.len(),
+                rows,
+            }))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    // resolves the table and copied columns, rows are not read
+    pub(crate) fn validate(&mut self) -> SystemResult<()> {
+        self.target().map(|_table| ())
+    }
+
+    fn target(&self) -> SystemResult<Option<CopiedTable>> {
+        CopiedTable::resolve(
+            &self.table_name,
+            &self.columns,
+            Privilege::Select,
+            self.session_id,
+            &self.storage,
+            self.sender.as_ref(),
+        )
+    }
+}
+
+/// table with positions of copied columns in the order they are listed by COPY
+struct CopiedTable {
+    schema_id: RecordId,
+    table_id: RecordId,
+    name: String,
+    all_columns: Vec<ColumnDefinition>,
+    columns: Vec<usize>,
+}
+
+impl CopiedTable {
+    // sends an error when the table or one of the columns does not exist
+    fn resolve(
+        table_name: &ObjectName,
+        columns: &[Ident],
+        privilege: Privilege,
+        session_id: SessionId,
+        storage: &DataManager,
+        sender: &dyn Sender,
+    ) -> SystemResult<Option<CopiedTable>> {
+        let table = match TableId::try_from(table_name.clone()) {
+            Ok(table) => table,
+            Err(TableNamingError(error)) => {
+                sender.send(Err(error)).expect("To Send Query Result to Client");
+                return Ok(None);
+            }
+        };
+        let (schema_id, table_id) = match storage.session_table_exists(session_id, table.schema_name(), table.name()) {
+            None => {
+                sender
+                    .send(Err(QueryError::schema_does_not_exist(table.schema_name().to_owned())))
+                    .expect("To Send Query Result to Client");
+                return Ok(None);
+            }
+            Some((_, None)) => {
+                sender
+                    .send(Err(QueryError::table_does_not_exist(
+                        table.schema_name().to_owned() + "." + table.name(),
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Ok(None);
+            }
+            Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        };
+        if !storage.has_privilege(session_id, schema_id, table_id, privilege) {
+            sender
+                .send(Err(QueryError::permission_denied(format!(
+                    "permission denied for table {}",
+                    table.name()
+                ))))
+                .expect("To Send Query Result to Client");
+            return Ok(None);
+        }
+
+        let all_columns = storage.table_columns(schema_id, table_id)?;
+        // values of generated columns are computed, they are not copied into a table
+        let written = |index: &usize| privilege != Privilege::Insert || all_columns[*index].generated().is_none();
+        let columns = if columns.is_empty() {
+            (0..all_columns.len()).filter(written).collect()
+        } else {
+            let mut indices = vec![];
+            let mut non_existing_columns = vec![];
+            for Ident { value, .. } in columns.iter() {
+                match all_columns.iter().position(|column| column.has_name(value)) {
+                    Some(index) => indices.push(index),
+                    None => non_existing_columns.push(value.clone()),
+                }
+            }
+            if !non_existing_columns.is_empty() {
+                sender
+                    .send(Err(QueryError::column_does_not_exist(non_existing_columns)))
+                    .expect("To Send Query Result to Client");
+                return Ok(None);
+            }
+            if let Some(index) = indices.iter().find(|index| !written(index)) {
+                sender
+                    .send(Err(QueryError::generated_column_cannot_be_written(
+                        all_columns[*index].name(),
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Ok(None);
+            }
+            indices
+        };
+
+        Ok(Some(CopiedTable {
+            schema_id,
+            table_id,
+            name: table.name().to_owned(),
+            all_columns,
+            columns,
+        }))
+    }
+}
+
+// relative paths are resolved against the `directory`, a path outside of it is rejected before the file
+// is looked up, so an error does not tell whether such a file exists; links are followed afterwards
+fn server_file(file_name: &str, directory: Option<&Path>) -> Result<File, QueryError> {
+    let directory = match directory.map(Path::canonicalize) {
+        Some(Ok(directory)) => directory,
+        Some(Err(_)) | None => {
+            return Err(QueryError::permission_denied(
+                "permission denied to COPY from a file".to_owned(),
+            ))
+        }
+    };
+    let outside = || {
+        QueryError::permission_denied(format!(
+            "could not open file \"{}\" for reading: it is outside of the copy directory",
+            file_name
+        ))
+    };
+    let path = normalized(&directory.join(file_name));
+    if !path.starts_with(&directory) {
+        return Err(outside());
+    }
+    let path = match path.canonicalize() {
+        Ok(path) => path,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Err(QueryError::undefined_file(file_name.to_owned()))
+        }
+        Err(error) => return Err(file_error("open", file_name, error)),
+    };
+    if !path.starts_with(&directory) {
+        return Err(outside());
+    }
+    File::open(path).map_err(|error| file_error("open", file_name, error))
+}
+
+// `.` and `..` components are resolved without touching the filesystem
+fn normalized(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn file_error(action: &str, file_name: &str, error: io::Error) -> QueryError {
+    QueryError::io_error(format!(
+        "could not {} file \"{}\" for reading: {}",
+        action, file_name, error
+    ))
+}
+
+/// rows of a file that are read one by one, thus the whole file is never kept in memory
+struct FileRows<'f, R: BufRead> {
+    file_name: &'f str,
+    reader: R,
+    format: CopyFormat,
+    first_line: bool,
+    finished: bool,
+}
+
+impl<'f, R: BufRead> FileRows<'f, R> {
+    fn new(file_name: &'f str, reader: R, format: CopyFormat) -> FileRows<'f, R> {
+        FileRows {
+            file_name,
+            reader,
+            format,
+            first_line: true,
+            finished: false,
+        }
+    }
+
+    // a quoted field of a CSV row could span several lines
+    fn read_row(&mut self) -> Result<Option<String>, QueryError> {
+        let mut row = String::new();
+        loop {
+            let mut line = vec![];
+            let read = self
+                .reader
+                .read_until(b'\n', &mut line)
+                .map_err(|error| file_error("read", self.file_name, error))?;
+            if read == 0 {
+                return Ok(if row.is_empty() { None } else { Some(row) });
+            }
+            let line = String::from_utf8(line).map_err(|_| {
+                QueryError::bad_copy_file_format("invalid byte sequence for encoding \"UTF8\"".to_owned())
+            })?;
+            if mem::replace(&mut self.first_line, false) {
+                row.push_str(line.strip_prefix(BYTE_ORDER_MARK).unwrap_or(&line));
+            } else {
+                row.push_str(&line);
+            }
+            let open_quote = self.format == CopyFormat::Csv && row.matches('"').count() % 2 == 1;
+            if !open_quote {
+                return Ok(Some(row));
+            }
+        }
+    }
+}
+
+impl<'f, R: BufRead> Iterator for FileRows<'f, R> {
+    type Item = Result<Vec<Option<String>>, QueryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let row = match self.read_row() {
+            Ok(Some(row)) => row,
+            Ok(None) => return None,
+            Err(error) => {
+                self.finished = true;
+                return Some(Err(error));
+            }
+        };
+        let mut rows = match self.format {
+            CopyFormat::Text => text_rows(&row),
+            CopyFormat::Csv => csv_rows(&row),
+        };
+        // the end of data marker stops reading the file
+        if rows.is_empty() {
+            self.finished = true;
+            return None;
+        }
+        Some(Ok(rows.remove(0)))
+    }
+}
+
+// rows of tab separated fields in text format, `None` is NULL
+fn text_rows(data: &str) -> Vec<Vec<Option<String>>> {
+    data.lines()
+        .take_while(|line| *line != END_OF_DATA)
+        .map(|line| {
+            line.split('\t')
+                .map(|field| {
+                    if field == NULL {
+                        return None;
+                    }
+                    let mut text = String::with_capacity(field.len());
+                    let mut chars = field.chars();
+                    while let Some(c) = chars.next() {
+                        if c != '\\' {
+                            text.push(c);
+                            continue;
+                        }
+                        match chars.next() {
+                            Some('t') => text.push('\t'),
+                            Some('n') => text.push('\n'),
+                            Some('r') => text.push('\r'),
+                            Some('b') => text.push('\u{8}'),
+                            Some('f') => text.push('\u{c}'),
+                            Some('v') => text.push('\u{b}'),
+                            Some(other) => text.push(other),
+                            None => text.push('\\'),
+                        }
+                    }
+                    Some(text)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// rows of comma separated fields in CSV format, quoted fields could contain commas and new lines
+fn csv_rows(data: &str) -> Vec<Vec<Option<String>>> {
+    let mut rows = vec![];
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = data.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let row_start = fields.is_empty() && field.is_empty() && !quoted;
+        if row_start && data[index..].lines().next() == Some(END_OF_DATA) {
+            return rows;
+        }
+        match c {
+            '"' if in_quotes => {
+                if matches!(chars.peek(), Some((_, '"'))) {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            c if in_quotes => field.push(c),
+            '"' => {
+                in_quotes = true;
+                quoted = true;
+            }
+            ',' => fields.push(csv_field(&mut field, &mut quoted)),
+            '\r' if matches!(chars.peek(), Some((_, '\n'))) => {}
+            '\n' => {
+                fields.push(csv_field(&mut field, &mut quoted));
+                rows.push(mem::take(&mut fields));
+            }
+            c => field.push(c),
+        }
+    }
+    if !fields.is_empty() || !field.is_empty() || quoted {
+        fields.push(csv_field(&mut field, &mut quoted));
+        rows.push(fields);
+    }
+    rows
+}
+
+// an empty value is NULL unless it is quoted
+fn csv_field(field: &mut String, quoted: &mut bool) -> Option<String> {
+    let quoted = mem::replace(quoted, false);
+    let text = mem::take(field);
+    if text.is_empty() && !quoted {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn text_row(fields: Vec<Option<String>>) -> String {
+    let mut row = fields
+        .into_iter()
+        .map(|field| match field {
+            None => NULL.to_owned(),
+            Some(text) => text
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r"),
+        })
+        .collect::<Vec<_>>()
+        .join("\t");
+    row.push('\n');
+    row
+}
+
+fn csv_row(fields: Vec<Option<String>>) -> String {
+    let mut row = fields
+        .into_iter()
+        .map(|field| match field {
+            None => String::new(),
+            Some(text) if text.is_empty() || text == END_OF_DATA || text.contains(&[',', '"', '\n', '\r'][..]) => {
+                format!("\"{}\"", text.replace('"', "\"\""))
+            }
+            Some(text) => text,
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push('\n');
+    row
+}
+
+// literal of the text that satisfies the column type
+fn text_value(text: String, sql_type: &SqlType) -> Value {
+    match sql_type {
+        SqlType::SmallInt(_) | SqlType::Integer(_) | SqlType::BigInt(_) => {
+            Value::Number(BigDecimal::from_str(text.as_str()).expect("validated integer"))
+        }
+        SqlType::Bool => Value::Boolean(sql_type.serializer().ser(text.as_str()) == [1u8]),
+        _ => Value::SingleQuotedString(text),
+    }
+}
@@ -12,62 +12,328 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use data_manager::DataManager;
+use crate::dml::{
+    check_referenced_keys, equality_filter,
+    relation::{ColumnReferenceError, Frame, RelationColumn, Scope},
+    select::{expr_name, expr_type, expr_type_modifier, SelectCommand},
+    trigger::{Operation, RowTriggers},
+    update::from_clause_query,
+    ExprResult, ExpressionEvaluation,
+};
+use data_manager::{DataManager, LockMode, Privilege, SessionId, TableChange};
 use kernel::SystemResult;
 use protocol::{
-    results::{QueryError, QueryEvent},
+    results::{Description, QueryError, QueryEvent},
     Sender,
 };
-use sqlparser::ast::ObjectName;
+use representation::unpack_raw;
+use sqlparser::ast::{Expr, ObjectName, SelectItem, TableWithJoins};
 use std::sync::Arc;
 
-pub(crate) struct DeleteCommand {
+pub(crate) struct DeleteCommand<'dc> {
+    raw_sql_query: &'dc str,
     name: ObjectName,
+    using: Vec<TableWithJoins>,
+    selection: Option<Expr>,
+    returning: Vec<SelectItem>,
+    session_id: SessionId,
     storage: Arc<DataManager>,
     sender: Arc<dyn Sender>,
 }
 
-impl DeleteCommand {
-    pub(crate) fn new(name: ObjectName, storage: Arc<DataManager>, sender: Arc<dyn Sender>) -> DeleteCommand {
-        DeleteCommand { name, storage, sender }
+enum Returned<'r> {
+    Column(usize),
+    Expr(&'r Expr),
+}
+
+impl<'dc> DeleteCommand<'dc> {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        raw_sql_query: &'dc str,
+        name: ObjectName,
+        using: Vec<TableWithJoins>,
+        selection: Option<Expr>,
+        returning: Vec<SelectItem>,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> DeleteCommand<'dc> {
+        DeleteCommand {
+            raw_sql_query,
+            name,
+            using,
+            selection,
+            returning,
+            session_id,
+            storage,
+            sender,
+        }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        self.run(false)
+    }
+
+    // resolves the table and evaluates the predicate against its rows, nothing is deleted
+    pub(crate) fn validate(&mut self) -> SystemResult<()> {
+        self.run(true)
+    }
+
+    fn run(&mut self, dry_run: bool) -> SystemResult<()> {
         let schema_name = self.name.0[0].to_string();
         let table_name = self.name.0[1].to_string();
 
-        match self.storage.table_exists(&schema_name, &table_name) {
-            None => self
-                .sender
-                .send(Err(QueryError::schema_does_not_exist(schema_name)))
-                .expect("To Send Result to Client"),
-            Some((_, None)) => self
-                .sender
-                .send(Err(QueryError::table_does_not_exist(
-                    schema_name + "." + table_name.as_str(),
-                )))
-                .expect("To Send Result to Client"),
-            Some((schema_id, Some(table_id))) => {
-                match self.storage.full_scan(schema_id, table_id) {
-                    Err(e) => return Err(e),
-                    Ok(reads) => {
-                        let keys = reads
-                            .map(Result::unwrap)
-                            .map(Result::unwrap)
-                            .map(|(key, _)| key)
-                            .collect();
+        let (schema_id, table_id) = match self
+            .storage
+            .session_table_exists(self.session_id, &schema_name, &table_name)
+        {
+            None => {
+                self.sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((_, None)) => {
+                self.sender
+                    .send(Err(QueryError::table_does_not_exist(
+                        schema_name + "." + table_name.as_str(),
+                    )))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((schema_id, Some(table_id)))
+                if !self
+                    .storage
+                    .has_privilege(self.session_id, schema_id, table_id, Privilege::Delete) =>
+            {
+                self.sender
+                    .send(Err(QueryError::permission_denied(format!(
+                        "permission denied for table {}",
+                        table_name
+                    ))))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((schema_id, Some(table_id))) if self.storage.partition_key(schema_id, table_id).is_some() => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(
+                        "DELETE on partitioned table".to_owned(),
+                    )))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        };
+        self.storage
+            .lock_table(self.session_id, schema_id, table_id, LockMode::RowExclusive);
 
-                        match self.storage.delete_from(schema_id, table_id, keys) {
-                            Err(e) => return Err(e),
-                            Ok(records_number) => self
-                                .sender
-                                .send(Ok(QueryEvent::RecordsDeleted(records_number)))
-                                .expect("To Send Query Result to Client"),
+        let definitions = self.storage.table_columns(schema_id, table_id)?;
+        let mut columns: Vec<RelationColumn> = definitions
+            .iter()
+            .map(|column| {
+                RelationColumn::qualified(
+                    Some(schema_name.clone()),
+                    Some(table_name.clone()),
+                    column.name(),
+                    (&column.sql_type()).into(),
+                )
+                .with_type_modifier(column.sql_type().type_modifier())
+            })
+            .collect();
+        // without USING clause every row of the table is matched against a single empty row
+        let source_rows = if self.using.is_empty() {
+            vec![vec![]]
+        } else {
+            let query = Box::new(from_clause_query(self.using.clone()));
+            match SelectCommand::new(
+                self.raw_sql_query,
+                query,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+                None,
+            )
+            .source()?
+            {
+                Some(relation) => {
+                    columns.extend(relation.columns);
+                    relation.rows
+                }
+                None => return Ok(()),
+            }
+        };
+
+        let header = Scope::new(Frame::header(&columns), None);
+        if let Some(predicate) = &self.selection {
+            let error = match header.check(predicate) {
+                Ok(()) => None,
+                Err(ColumnReferenceError::Missing(columns)) => Some(QueryError::column_does_not_exist(columns)),
+                Err(ColumnReferenceError::Ambiguous(column)) => Some(QueryError::ambiguous_column(column)),
+            };
+            if let Some(error) = error {
+                self.sender.send(Err(error)).expect("To Send Result to Client");
+                return Ok(());
+            }
+        }
+        let (description, returned) = match self.returning(&columns) {
+            Ok(returning) => returning,
+            Err(error) => {
+                self.sender.send(Err(error)).expect("To Send Result to Client");
+                return Ok(());
+            }
+        };
+
+        let triggers = RowTriggers::new(
+            schema_id,
+            table_id,
+            Operation::Delete,
+            &definitions,
+            self.session_id,
+            self.storage.clone(),
+            self.sender.clone(),
+        );
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let mut removed = vec![];
+        // rows that AFTER triggers fire for
+        let mut deleted = vec![];
+        let mut returned_rows = vec![];
+        let pushed_down = self
+            .selection
+            .as_ref()
+            .and_then(|predicate| equality_filter(predicate, &header, &definitions));
+        let cursor = match pushed_down {
+            Some((column, value)) => self
+                .storage
+                .scan_where(self.session_id, schema_id, table_id, column, &value)?,
+            None => self.storage.full_scan(self.session_id, schema_id, table_id)?,
+        };
+        for (key, stored) in cursor.map(Result::unwrap).map(Result::unwrap) {
+            let target_row: Vec<ExprResult> = unpack_raw(stored.to_bytes())
+                .into_iter()
+                .map(ExprResult::from)
+                .collect();
+            // a row is deleted once even if several joined rows satisfy WHERE clause
+            for source_row in source_rows.iter() {
+                let mut row = target_row.clone();
+                row.extend(source_row.iter().cloned());
+                let scope = Scope::new(Frame::new(&columns, &row), None);
+                let satisfied = match &self.selection {
+                    None => true,
+                    Some(predicate) => match evaluation.eval_in(predicate, &scope) {
+                        Ok(ExprResult::Bool(value)) => value,
+                        Ok(ExprResult::Null) => false,
+                        Ok(_) => {
+                            self.sender
+                                .send(Err(QueryError::syntax_error(predicate.to_string())))
+                                .expect("To Send Query Result to Client");
+                            return Ok(());
                         }
-                    }
+                        Err(()) => return Ok(()),
+                    },
                 };
+                if satisfied {
+                    let mut values = vec![];
+                    for item in returned.iter() {
+                        match item {
+                            Returned::Column(index) => values.push(row[*index].to_text()),
+                            Returned::Expr(expr) => match evaluation.eval_in(expr, &scope) {
+                                Ok(value) => values.push(value.to_text()),
+                                Err(()) => return Ok(()),
+                            },
+                        }
+                    }
+                    if !dry_run && triggers.has_before() && !triggers.before(Some(&target_row), None)? {
+                        return Ok(());
+                    }
+                    if triggers.has_after() {
+                        deleted.push(target_row.clone());
+                    }
+                    removed.push((key, stored));
+                    returned_rows.push(values);
+                    break;
+                }
+            }
+        }
+
+        if !check_referenced_keys(
+            &self.storage,
+            self.session_id,
+            schema_id,
+            table_id,
+            &table_name,
+            &removed,
+            &[],
+            self.sender.as_ref(),
+        )? {
+            return Ok(());
+        }
+        if dry_run {
+            return Ok(());
+        }
+
+        let keys = removed.into_iter().map(|(key, _values)| key).collect();
+        let records_number = self.storage.delete_from(self.session_id, schema_id, table_id, keys)?;
+        self.storage
+            .count_changes(schema_id, table_id, TableChange::Deleted, records_number);
+        for old in deleted {
+            if !triggers.after(Some(&old), None)? {
+                return Ok(());
             }
         }
+        let event = if self.returning.is_empty() {
+            QueryEvent::RecordsDeleted(records_number)
+        } else {
+            QueryEvent::RecordsDeletedReturning((description, returned_rows))
+        };
+        self.sender.send(Ok(event)).expect("To Send Query Result to Client");
         Ok(())
     }
+
+    // resolves items of RETURNING clause against columns of deleted and joined rows
+    fn returning(&self, columns: &[RelationColumn]) -> Result<(Description, Vec<Returned<'_>>), QueryError> {
+        let scope = Scope::new(Frame::header(columns), None);
+        let mut description = vec![];
+        let mut returned = vec![];
+        let mut non_existing_columns = vec![];
+        for item in self.returning.iter() {
+            let (expr, name) = match item {
+                SelectItem::Wildcard => {
+                    for (index, column) in columns.iter().enumerate() {
+                        description.push((column.name().to_owned(), column.sql_type(), column.type_modifier()));
+                        returned.push(Returned::Column(index));
+                    }
+                    continue;
+                }
+                SelectItem::QualifiedWildcard(qualifier) => {
+                    let len = returned.len();
+                    for (index, column) in columns.iter().enumerate() {
+                        if column.belongs_to(&qualifier.0) {
+                            description.push((column.name().to_owned(), column.sql_type(), column.type_modifier()));
+                            returned.push(Returned::Column(index));
+                        }
+                    }
+                    if returned.len() == len {
+                        return Err(QueryError::table_does_not_exist(qualifier.to_string()));
+                    }
+                    continue;
+                }
+                SelectItem::UnnamedExpr(expr) => (expr, expr_name(expr)),
+                SelectItem::ExprWithAlias { expr, alias } => (expr, alias.value.clone()),
+            };
+            match scope.check(expr) {
+                Ok(()) => {
+                    description.push((name, expr_type(expr, &scope), expr_type_modifier(expr, &scope)));
+                    returned.push(Returned::Expr(expr));
+                }
+                Err(ColumnReferenceError::Missing(mut columns)) => non_existing_columns.append(&mut columns),
+                Err(ColumnReferenceError::Ambiguous(column)) => return Err(QueryError::ambiguous_column(column)),
+            }
+        }
+        if !non_existing_columns.is_empty() {
+            return Err(QueryError::column_does_not_exist(non_existing_columns));
+        }
+        Ok((description, returned))
+    }
 }
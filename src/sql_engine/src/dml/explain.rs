@@ -0,0 +1,316 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dml::select::SelectCommand;
+use data_manager::{DataManager, SessionId};
+use kernel::SystemResult;
+use protocol::{results::QueryEvent, sql_types::PostgreSqlType, Sender};
+use sqlparser::ast::{BinaryOperator, Expr, Query, UnaryOperator};
+use std::{
+    cell::RefCell,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// selectivities of predicates that are used when column statistics are not taken into account,
+// the same defaults as PostgreSQL has
+const EQUALITY_SELECTIVITY: f64 = 0.005;
+const INEQUALITY_SELECTIVITY: f64 = 1.0 / 3.0;
+const DEFAULT_SELECTIVITY: f64 = 0.5;
+
+pub(crate) struct ExplainCommand<'ec> {
+    raw_sql_query: &'ec str,
+    query: Box<Query>,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl<'ec> ExplainCommand<'ec> {
+    pub(crate) fn new(
+        raw_sql_query: &'ec str,
+        query: Box<Query>,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> ExplainCommand<'ec> {
+        ExplainCommand {
+            raw_sql_query,
+            query,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    // runs the query and sends its plan with estimated and actual rows of every node instead of its rows
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let started = Instant::now();
+        let profile = match self.select().analyze()? {
+            Some(profile) => profile,
+            None => return Ok(()),
+        };
+        let mut plan = profile.lines();
+        plan.push(format!("Execution Time: {:.3} ms", millis(started.elapsed())));
+
+        self.sender
+            .send(Ok(QueryEvent::RecordsSelected((
+                vec![("QUERY PLAN".to_owned(), PostgreSqlType::VarChar, -1)],
+                plan.into_iter().map(|line| vec![Some(line)]).collect(),
+            ))))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    pub(crate) fn validate(&mut self) -> SystemResult<()> {
+        self.select().validate()
+    }
+
+    fn select(&self) -> SelectCommand<'ec> {
+        SelectCommand::new(
+            self.raw_sql_query,
+            self.query.clone(),
+            self.session_id,
+            self.storage.clone(),
+            self.sender.clone(),
+            None,
+        )
+    }
+}
+
+/// What a plan node does with rows of its children, used to estimate its rows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Operation {
+    /// reads all rows stored in a table
+    Scan { stored: usize },
+    /// produces rows without reading any table, e.g. `generate_series`
+    Function { rows: usize },
+    /// matches rows of two children, selectivity `1.0` is a cartesian product
+    Join { selectivity: f64 },
+    /// filters, aggregates and limits rows of its first child
+    Select {
+        selectivity: f64,
+        aggregation: Aggregation,
+        limit: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Aggregation {
+    None,
+    All,
+    Grouped,
+}
+
+struct PlanNode {
+    name: String,
+    parent: Option<usize>,
+    operation: Option<Operation>,
+    filter: Option<String>,
+    removed: usize,
+    loops: usize,
+    rows: usize,
+    time: Duration,
+}
+
+/// Nodes of an executed query with rows they produced and time they took.
+/// A node that is run several times, e.g. a LATERAL subquery for every row of
+/// the left side of a join, is kept once with the number of its loops.
+#[derive(Default)]
+pub(crate) struct Profile {
+    nodes: RefCell<Vec<PlanNode>>,
+    // nodes that are being run with the number of children entered in their current loop,
+    // the last one is the parent of a node that is entered
+    running: RefCell<Vec<(usize, usize)>>,
+}
+
+impl Profile {
+    pub(crate) fn enter(&self, name: String) -> usize {
+        let mut nodes = self.nodes.borrow_mut();
+        let mut running = self.running.borrow_mut();
+        // children are entered in the same order in every loop of their parent
+        let (parent, entered) = match running.last_mut() {
+            Some((parent, entered)) => {
+                *entered += 1;
+                (Some(*parent), *entered - 1)
+            }
+            None => (None, 0),
+        };
+        let index = match nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.parent == parent)
+            .map(|(index, _)| index)
+            .nth(entered)
+        {
+            Some(index) => index,
+            None => {
+                nodes.push(PlanNode {
+                    name,
+                    parent,
+                    operation: None,
+                    filter: None,
+                    removed: 0,
+                    loops: 0,
+                    rows: 0,
+                    time: Duration::default(),
+                });
+                nodes.len() - 1
+            }
+        };
+        nodes[index].loops += 1;
+        running.push((index, 0));
+        index
+    }
+
+    /// operation of the running node
+    pub(crate) fn operation(&self, operation: Operation) {
+        if let Some((index, _)) = self.running.borrow().last() {
+            self.nodes.borrow_mut()[*index].operation = Some(operation);
+        }
+    }
+
+    pub(crate) fn leave(&self, index: usize, rows: usize, time: Duration) {
+        self.running.borrow_mut().pop();
+        let node = &mut self.nodes.borrow_mut()[index];
+        node.rows += rows;
+        node.time += time;
+    }
+
+    /// predicate of the running node and the number of rows it has not satisfied
+    pub(crate) fn filter(&self, predicate: &Expr, removed: usize) {
+        if let Some((index, _)) = self.running.borrow().last() {
+            let node = &mut self.nodes.borrow_mut()[*index];
+            node.filter = Some(predicate.to_string());
+            node.removed += removed;
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let nodes = self.nodes.borrow();
+        let mut lines = vec![];
+        self.describe(&nodes, None, 0, &mut lines);
+        lines
+    }
+
+    fn describe(&self, nodes: &[PlanNode], parent: Option<usize>, depth: usize, lines: &mut Vec<String>) {
+        for (index, node) in nodes.iter().enumerate().filter(|(_, node)| node.parent == parent) {
+            let (indent, details) = if depth == 0 {
+                (String::new(), "  ".to_owned())
+            } else {
+                let indent = " ".repeat(6 * (depth - 1) + 2);
+                (format!("{}->  ", indent), format!("{}      ", indent))
+            };
+            lines.push(format!(
+                "{}{}  (estimated rows={}) (actual time={:.3} ms rows={} loops={})",
+                indent,
+                node.name,
+                estimated_rows(nodes, index).round().max(1.0),
+                millis(node.time) / node.loops as f64,
+                node.rows / node.loops,
+                node.loops
+            ));
+            if let Some(filter) = &node.filter {
+                let label = match node.operation {
+                    Some(Operation::Join { .. }) => "Join Filter",
+                    _ => "Filter",
+                };
+                lines.push(format!("{}{}: {}", details, label, filter));
+                lines.push(format!(
+                    "{}Rows Removed by {}: {}",
+                    details,
+                    label,
+                    node.removed / node.loops
+                ));
+            }
+            self.describe(nodes, Some(index), depth + 1, lines);
+        }
+    }
+}
+
+// rows of a single loop of the node
+fn estimated_rows(nodes: &[PlanNode], index: usize) -> f64 {
+    let children = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.parent == Some(index))
+        .map(|(child, _)| estimated_rows(nodes, child))
+        .collect::<Vec<_>>();
+    match nodes[index].operation {
+        None => 0.0,
+        Some(Operation::Scan { stored }) => stored as f64,
+        Some(Operation::Function { rows }) => rows as f64,
+        Some(Operation::Join { selectivity }) => children.iter().product::<f64>() * selectivity,
+        Some(Operation::Select {
+            selectivity,
+            aggregation,
+            limit,
+        }) => {
+            let rows = match aggregation {
+                Aggregation::All => 1.0,
+                Aggregation::None | Aggregation::Grouped => children.first().copied().unwrap_or(1.0) * selectivity,
+            };
+            match limit {
+                Some(limit) => rows.min(limit as f64),
+                None => rows,
+            }
+        }
+    }
+}
+
+/// fraction of rows that satisfy the predicate
+pub(crate) fn selectivity(predicate: &Expr) -> f64 {
+    match predicate {
+        Expr::Nested(expr) => selectivity(expr),
+        Expr::BinaryOp { left, op, right } => match op {
+            BinaryOperator::And => selectivity(left) * selectivity(right),
+            BinaryOperator::Or => {
+                let (left, right) = (selectivity(left), selectivity(right));
+                left + right - left * right
+            }
+            BinaryOperator::Eq => EQUALITY_SELECTIVITY,
+            BinaryOperator::NotEq => 1.0 - EQUALITY_SELECTIVITY,
+            BinaryOperator::Lt | BinaryOperator::LtEq | BinaryOperator::Gt | BinaryOperator::GtEq => {
+                INEQUALITY_SELECTIVITY
+            }
+            _ => DEFAULT_SELECTIVITY,
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => 1.0 - selectivity(expr),
+        Expr::IsNull(_) => EQUALITY_SELECTIVITY,
+        Expr::IsNotNull(_) => 1.0 - EQUALITY_SELECTIVITY,
+        Expr::Between { negated: false, .. } => EQUALITY_SELECTIVITY,
+        Expr::Between { negated: true, .. } => 1.0 - EQUALITY_SELECTIVITY,
+        Expr::InList { list, negated, .. } => {
+            let selectivity = (EQUALITY_SELECTIVITY * list.len() as f64).min(1.0);
+            if *negated {
+                1.0 - selectivity
+            } else {
+                selectivity
+            }
+        }
+        _ => DEFAULT_SELECTIVITY,
+    }
+}
+
+/// fraction of rows that have equal values of all `columns` compared by NATURAL or USING join
+pub(crate) fn selectivity_of_common(columns: usize) -> f64 {
+    EQUALITY_SELECTIVITY.powi(columns as i32)
+}
+
+fn millis(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000.0
+}
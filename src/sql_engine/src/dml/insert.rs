@@ -12,41 +12,85 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{dml::ExpressionEvaluation, query::plan::TableInserts};
-use data_manager::{DataManager, Row};
+use crate::{
+    dml::{
+        assign_value, check_foreign_keys, column_datum, constraint_error, default_expressions, default_values,
+        fit_char, generate_values, generation_expressions, is_default, not_null_violation,
+        relation::{ColumnReferenceError, Frame, RelationColumn, Scope},
+        trigger::{Operation, RowTriggers},
+        ExprResult, ExpressionEvaluation,
+    },
+    query::plan::{OnConflict, TableInserts},
+};
+use data_manager::{ColumnDefinition, DataManager, LockMode, Privilege, RecordId, Row, SessionId, TableChange};
 use kernel::SystemResult;
 use protocol::{
     results::{QueryError, QueryEvent},
     Sender,
 };
-use representation::{Binary, Datum};
+use representation::{unpack_raw, Binary, Datum};
 use sql_types::ConstraintError;
-use sqlparser::ast::{DataType, Expr, Query, SetExpr, UnaryOperator, Value};
-use std::{convert::TryFrom, str::FromStr, sync::Arc};
+use sqlparser::ast::{Assignment, DataType, Expr, Query, SetExpr, Value};
+use std::{collections::BTreeSet, str::FromStr, sync::Arc};
+
+/// How rows that violate column constraints are handled by a multi row INSERT
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum InsertMode {
+    /// nothing is inserted if any row violates a constraint
+    #[default]
+    Strict,
+    /// valid rows are inserted, violations of the other rows are reported as warnings
+    BestEffort,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum OperationOnTableError {
+    /// zero based index of a row with the violated constraint of its column
+    PartialConstraintViolations(Vec<(usize, ConstraintError, ColumnDefinition)>),
+}
 
 pub(crate) struct InsertCommand<'ic> {
     raw_sql_query: &'ic str,
     table_inserts: TableInserts,
+    session_id: SessionId,
     storage: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    mode: InsertMode,
 }
 
 impl<'ic> InsertCommand<'ic> {
     pub(crate) fn new(
         raw_sql_query: &'ic str,
         table_inserts: TableInserts,
+        session_id: SessionId,
         storage: Arc<DataManager>,
         sender: Arc<dyn Sender>,
     ) -> InsertCommand<'ic> {
         InsertCommand {
             raw_sql_query,
             table_inserts,
+            session_id,
             storage,
             sender,
+            mode: InsertMode::default(),
         }
     }
 
+    pub(crate) fn with_mode(mut self, mode: InsertMode) -> InsertCommand<'ic> {
+        self.mode = mode;
+        self
+    }
+
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        self.run(false)
+    }
+
+    // checks inserted values against column constraints, nothing is written and no keys are generated
+    pub(crate) fn validate(&mut self) -> SystemResult<()> {
+        self.run(true)
+    }
+
+    fn run(&mut self, dry_run: bool) -> SystemResult<()> {
         let table_name = self.table_inserts.table_id.name();
         let schema_name = self.table_inserts.table_id.schema_name();
         let Query { body, .. } = &*self.table_inserts.input;
@@ -68,11 +112,17 @@ impl<'ic> InsertCommand<'ic> {
                         .collect()
                 };
 
-                let mut evaluation = ExpressionEvaluation::new(self.sender.clone());
+                let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+                    .with_functions(&self.storage)
+                    .with_session(self.session_id, self.storage.clone());
                 let mut rows = vec![];
                 for line in values {
                     let mut row = vec![];
                     for col in line {
+                        if is_default(col) {
+                            row.push(None);
+                            continue;
+                        }
                         let v = match col {
                             Expr::Value(value) => value.clone(),
                             Expr::Cast { expr, data_type } => match (&**expr, data_type) {
@@ -80,31 +130,17 @@ impl<'ic> InsertCommand<'ic> {
                                 (Expr::Value(Value::SingleQuotedString(v)), DataType::Boolean) => {
                                     Value::Boolean(bool::from_str(v).unwrap())
                                 }
-                                _ => {
-                                    self.sender
-                                        .send(Err(QueryError::syntax_error(format!(
-                                            "Cast from {:?} to {:?} is not currently supported",
-                                            expr, data_type
-                                        ))))
-                                        .expect("To Send Query Result to Client");
-                                    return Ok(());
-                                }
+                                _ => match evaluation.eval(col) {
+                                    Ok(expr_result) => expr_result,
+                                    Err(()) => return Ok(()),
+                                },
                             },
-                            Expr::UnaryOp { op, expr } => match (op, &**expr) {
-                                (UnaryOperator::Minus, Expr::Value(Value::Number(v))) => Value::Number(-v),
-                                (op, expr) => {
-                                    self.sender
-                                        .send(Err(QueryError::syntax_error(
-                                            op.to_string() + expr.to_string().as_str(),
-                                        )))
-                                        .expect("To Send Query Result to Client");
-                                    return Ok(());
+                            expr @ Expr::UnaryOp { .. } | expr @ Expr::BinaryOp { .. } | expr @ Expr::Function(_) => {
+                                match evaluation.eval(expr) {
+                                    Ok(expr_result) => expr_result,
+                                    Err(()) => return Ok(()),
                                 }
-                            },
-                            expr @ Expr::BinaryOp { .. } => match evaluation.eval(expr) {
-                                Ok(expr_result) => expr_result,
-                                Err(()) => return Ok(()),
-                            },
+                            }
                             expr => {
                                 self.sender
                                     .send(Err(QueryError::syntax_error(expr.to_string())))
@@ -112,12 +148,15 @@ impl<'ic> InsertCommand<'ic> {
                                 return Ok(());
                             }
                         };
-                        row.push(v);
+                        row.push(Some(v));
                     }
                     rows.push(row);
                 }
 
-                match self.storage.table_exists(&schema_name, &table_name) {
+                match self
+                    .storage
+                    .session_table_exists(self.session_id, &schema_name, &table_name)
+                {
                     None => self
                         .sender
                         .send(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
@@ -128,7 +167,21 @@ impl<'ic> InsertCommand<'ic> {
                             schema_name.to_owned() + "." + table_name,
                         )))
                         .expect("To Send Result to Client"),
+                    Some((schema_id, Some(table_id)))
+                        if !self
+                            .storage
+                            .has_privilege(self.session_id, schema_id, table_id, Privilege::Insert) =>
+                    {
+                        self.sender
+                            .send(Err(QueryError::permission_denied(format!(
+                                "permission denied for table {}",
+                                table_name
+                            ))))
+                            .expect("To Send Result to Client")
+                    }
                     Some((schema_id, Some(table_id))) => {
+                        self.storage
+                            .lock_table(self.session_id, schema_id, table_id, LockMode::RowExclusive);
                         let column_names = columns;
                         let all_columns = self.storage.table_columns(schema_id, table_id)?;
                         let index_columns = if column_names.is_empty() {
@@ -166,8 +219,22 @@ impl<'ic> InsertCommand<'ic> {
                             index_cols
                         };
 
+                        let generated = generation_expressions(&all_columns);
+                        let defaults = default_expressions(&all_columns);
+                        let triggers = RowTriggers::new(
+                            schema_id,
+                            table_id,
+                            Operation::Insert,
+                            &all_columns,
+                            self.session_id,
+                            self.storage.clone(),
+                            self.sender.clone(),
+                        );
+                        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+                            .with_functions(&self.storage)
+                            .with_session(self.session_id, self.storage.clone());
                         let mut to_write: Vec<Row> = vec![];
-                        let mut errors = Vec::new();
+                        let mut violations = vec![];
 
                         for (row_index, row) in rows.iter().enumerate() {
                             if row.len() > all_columns.len() {
@@ -176,66 +243,211 @@ impl<'ic> InsertCommand<'ic> {
                                     .expect("To Send Result to Client");
                                 return Ok(());
                             }
+                            if let Some(((_, column_definition), _)) =
+                                index_columns
+                                    .iter()
+                                    .zip(row.iter())
+                                    .find(|((_, column_definition), item)| {
+                                        item.is_some() && column_definition.generated().is_some()
+                                    })
+                            {
+                                self.sender
+                                    .send(Err(QueryError::generated_column_cannot_be_written(
+                                        column_definition.name(),
+                                    )))
+                                    .expect("To Send Result to Client");
+                                return Ok(());
+                            }
 
-                            let key = self.storage.next_key_id(schema_id, table_id).to_be_bytes().to_vec();
-
-                            // TODO: The default value or NULL should be initialized for SQL types of all columns.
+                            let mut errors = vec![];
+                            // columns without a value or with DEFAULT get their default values,
+                            // generated columns are computed below
                             let mut record = vec![Datum::from_null(); all_columns.len()];
+                            let assigned: Vec<usize> = row
+                                .iter()
+                                .zip(index_columns.iter())
+                                .filter(|(item, _)| item.is_some())
+                                .map(|(_, (index, _))| *index)
+                                .collect();
+                            if default_values(
+                                &mut evaluation,
+                                &defaults,
+                                |index| !assigned.contains(&index) && all_columns[index].generated().is_none(),
+                                &all_columns,
+                                &mut record,
+                                row_index,
+                                self.sender.as_ref(),
+                            )
+                            .is_err()
+                            {
+                                return Ok(());
+                            }
                             for (item, (index, column_definition)) in row.iter().zip(index_columns.iter()) {
+                                let item = match item {
+                                    Some(item) => item,
+                                    None => continue,
+                                };
+                                let item = &fit_char(item.clone(), column_definition, row_index, self.sender.as_ref());
                                 let v = match item.clone() {
                                     Value::Number(v) => v.to_string(),
                                     Value::SingleQuotedString(v) => v.to_string(),
                                     Value::Boolean(v) => v.to_string(),
-                                    _ => unimplemented!("other types not implemented"),
+                                    // the record is NULL until a value is assigned
+                                    Value::Null => continue,
+                                    value => {
+                                        self.sender
+                                            .send(Err(QueryError::feature_not_supported(format!(
+                                                "{} value in INSERT",
+                                                value
+                                            ))))
+                                            .expect("To Send Query Result to Client");
+                                        return Ok(());
+                                    }
                                 };
                                 match column_definition.sql_type().constraint().validate(v.as_str()) {
                                     Ok(()) => {
-                                        record[*index] = Datum::try_from(item).unwrap();
+                                        record[*index] = column_datum(item, &column_definition.sql_type());
                                     }
                                     Err(e) => {
-                                        errors.push((e, column_definition.clone()));
+                                        errors.push((row_index, e, column_definition.clone()));
                                     }
                                 }
                             }
 
-                            // if there was an error then exit the loop.
+                            // in strict mode the first row with an error stops the insert
                             if !errors.is_empty() {
-                                for (error, column_definition) in errors {
-                                    let error_to_send = match error {
-                                        ConstraintError::OutOfRange => QueryError::out_of_range(
-                                            (&column_definition.sql_type()).into(),
-                                            column_definition.name(),
-                                            row_index + 1,
-                                        ),
-                                        ConstraintError::TypeMismatch(value) => QueryError::type_mismatch(
-                                            &value,
-                                            (&column_definition.sql_type()).into(),
-                                            column_definition.name(),
-                                            row_index + 1,
-                                        ),
-                                        ConstraintError::ValueTooLong(len) => QueryError::string_length_mismatch(
-                                            (&column_definition.sql_type()).into(),
-                                            len,
-                                            column_definition.name(),
-                                            row_index + 1,
-                                        ),
-                                    };
-                                    self.sender
-                                        .send(Err(error_to_send))
-                                        .expect("To Send Query Result to Client");
+                                if self.mode == InsertMode::Strict {
+                                    for (row_index, error, column_definition) in errors {
+                                        self.sender
+                                            .send(Err(constraint_error(error, &column_definition, row_index)))
+                                            .expect("To Send Query Result to Client");
+                                    }
+                                    return Ok(());
                                 }
+                                violations.extend(errors);
+                                continue;
+                            }
+                            if !dry_run && triggers.has_before() {
+                                let mut new = record.iter().cloned().map(ExprResult::from).collect();
+                                if !triggers.before(None, Some(&mut new))? {
+                                    return Ok(());
+                                }
+                                for (index, value) in new.into_iter().enumerate() {
+                                    if assign_value(
+                                        &mut record,
+                                        index,
+                                        Value::from(value),
+                                        &all_columns[index],
+                                        row_index,
+                                        self.sender.as_ref(),
+                                    )
+                                    .is_err()
+                                    {
+                                        return Ok(());
+                                    }
+                                }
+                            }
+                            if generate_values(
+                                &mut evaluation,
+                                &generated,
+                                &all_columns,
+                                &mut record,
+                                row_index,
+                                self.sender.as_ref(),
+                            )
+                            .is_err()
+                            {
                                 return Ok(());
                             }
+                            if let Some(error) = not_null_violation(&all_columns, &record) {
+                                if self.mode == InsertMode::Strict {
+                                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                                    return Ok(());
+                                }
+                                self.sender
+                                    .send(Ok(error.into_warning()))
+                                    .expect("To Send Query Result to Client");
+                                continue;
+                            }
+                            let key = if dry_run {
+                                vec![]
+                            } else {
+                                self.storage.next_key_id(schema_id, table_id).to_be_bytes().to_vec()
+                            };
                             to_write.push((Binary::with_data(key), Binary::pack(&record)));
                         }
+                        if !violations.is_empty() {
+                            self.report(OperationOnTableError::PartialConstraintViolations(violations));
+                        }
+                        if !check_foreign_keys(
+                            &self.storage,
+                            self.session_id,
+                            schema_id,
+                            table_id,
+                            table_name,
+                            &to_write,
+                            self.sender.as_ref(),
+                        )? {
+                            return Ok(());
+                        }
+                        if !dry_run && self.storage.partition_key(schema_id, table_id).is_some() {
+                            return self.insert_into_partitions(schema_id, table_id, table_name, to_write);
+                        }
+                        // rows that AFTER triggers fire for
+                        let mut inserted: Vec<Vec<ExprResult>> = vec![];
+                        if !dry_run && triggers.has_after() {
+                            let conflicts = match self.table_inserts.on_conflict {
+                                OnConflict::DoNothing => {
+                                    self.storage.unique_conflicts(schema_id, table_id, &to_write)?
+                                }
+                                _ => vec![None; to_write.len()],
+                            };
+                            for ((_, values), conflict) in to_write.iter().zip(conflicts) {
+                                if conflict.is_none() {
+                                    inserted.push(
+                                        unpack_raw(values.to_bytes())
+                                            .into_iter()
+                                            .map(ExprResult::from)
+                                            .collect(),
+                                    );
+                                }
+                            }
+                        }
 
-                        match self.storage.write_into(schema_id, table_id, to_write) {
-                            Err(error) => return Err(error),
-                            Ok(size) => self
-                                .sender
-                                .send(Ok(QueryEvent::RecordsInserted(size)))
-                                .expect("To Send Result to Client"),
+                        let written = match self.table_inserts.on_conflict {
+                            OnConflict::DoUpdate { .. } => {
+                                return self.upsert(schema_id, table_id, &all_columns, to_write, dry_run)
+                            }
+                            _ if dry_run => return Ok(()),
+                            OnConflict::DoNothing => {
+                                self.storage
+                                    .insert_or_ignore(self.session_id, schema_id, table_id, to_write)
+                            }
+                            OnConflict::Error => {
+                                let conflicts = self.storage.unique_conflicts(schema_id, table_id, &to_write)?;
+                                if let Some(column) = conflicts.into_iter().flatten().next() {
+                                    self.sender
+                                        .send(Err(QueryError::unique_violation(format!(
+                                            "{}_{}_key",
+                                            table_name, column
+                                        ))))
+                                        .expect("To Send Result to Client");
+                                    return Ok(());
+                                }
+                                self.storage.write_into(self.session_id, schema_id, table_id, to_write)
+                            }
+                        };
+                        let size = written?;
+                        self.storage
+                            .count_changes(schema_id, table_id, TableChange::Inserted, size);
+                        for new in inserted {
+                            if !triggers.after(None, Some(&new))? {
+                                return Ok(());
+                            }
                         }
+                        self.sender
+                            .send(Ok(QueryEvent::RecordsInserted(size)))
+                            .expect("To Send Result to Client");
                     }
                 }
                 Ok(())
@@ -248,4 +460,402 @@ impl<'ic> InsertCommand<'ic> {
             }
         }
     }
+
+    fn report(&self, error: OperationOnTableError) {
+        match error {
+            OperationOnTableError::PartialConstraintViolations(violations) => {
+                for (row_index, error, column_definition) in violations {
+                    self.sender
+                        .send(Ok(constraint_error(error, &column_definition, row_index).into_warning()))
+                        .expect("To Send Query Result to Client");
+                }
+            }
+        }
+    }
+
+    // rows of a partitioned table are stored in its partitions, nothing is inserted when
+    // one of the rows doesn't belong to any partition
+    fn insert_into_partitions(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+        table_name: &str,
+        rows: Vec<Row>,
+    ) -> SystemResult<()> {
+        if self.table_inserts.on_conflict != OnConflict::Error {
+            self.sender
+                .send(Err(QueryError::feature_not_supported(
+                    "ON CONFLICT on partitioned table".to_owned(),
+                )))
+                .expect("To Send Query Result to Client");
+            return Ok(());
+        }
+        let mut partitions: Vec<((RecordId, RecordId), Vec<Row>)> = vec![];
+        for (_key, values) in rows {
+            let partition = match self.storage.partition_of(schema_id, table_id, &values) {
+                Some(partition) => partition,
+                None => {
+                    self.sender
+                        .send(Err(QueryError::no_partition_for_row(table_name.to_owned())))
+                        .expect("To Send Query Result to Client");
+                    return Ok(());
+                }
+            };
+            let key = self
+                .storage
+                .next_key_id(partition.0, partition.1)
+                .to_be_bytes()
+                .to_vec();
+            let row = (Binary::with_data(key), values);
+            match partitions.iter_mut().find(|(table, _rows)| *table == partition) {
+                Some((_table, rows)) => rows.push(row),
+                None => partitions.push((partition, vec![row])),
+            }
+        }
+        for ((partition_schema_id, partition_table_id), rows) in partitions.iter() {
+            let conflicts = self
+                .storage
+                .unique_conflicts(*partition_schema_id, *partition_table_id, rows)?;
+            if let Some(column) = conflicts.into_iter().flatten().next() {
+                self.sender
+                    .send(Err(QueryError::unique_violation(format!(
+                        "{}_{}_key",
+                        self.storage
+                            .table_name(*partition_schema_id, *partition_table_id)
+                            .unwrap_or_default(),
+                        column
+                    ))))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        }
+        let mut inserted = 0;
+        for ((partition_schema_id, partition_table_id), rows) in partitions {
+            let written = self
+                .storage
+                .write_into(self.session_id, partition_schema_id, partition_table_id, rows)?;
+            self.storage
+                .count_changes(partition_schema_id, partition_table_id, TableChange::Inserted, written);
+            inserted += written;
+        }
+        self.sender
+            .send(Ok(QueryEvent::RecordsInserted(inserted)))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    // rows are processed in the order they are proposed, a row inserted by the statement
+    // could conflict with the following proposed rows as well as already stored rows
+    fn upsert(
+        &self,
+        schema_id: RecordId,
+        table_id: RecordId,
+        all_columns: &[ColumnDefinition],
+        proposed: Vec<Row>,
+        dry_run: bool,
+    ) -> SystemResult<()> {
+        let (target, assignments, selection) = match &self.table_inserts.on_conflict {
+            OnConflict::DoUpdate {
+                target,
+                assignments,
+                selection,
+            } => (target, assignments, selection),
+            _ => unreachable!(),
+        };
+        let table_name = self.table_inserts.table_id.name();
+        let schema_name = self.table_inserts.table_id.schema_name();
+
+        let mut target_indices = vec![];
+        for column in target {
+            match all_columns
+                .iter()
+                .position(|column_definition| column_definition.has_name(&column.value))
+            {
+                Some(index) => target_indices.push(index),
+                None => {
+                    self.sender
+                        .send(Err(QueryError::column_does_not_exist(vec![column.value.clone()])))
+                        .expect("To Send Result to Client");
+                    return Ok(());
+                }
+            }
+        }
+        // only single column unique constraints could be defined
+        let target_index = match target_indices.as_slice() {
+            [index] if all_columns[*index].is_unique() => *index,
+            _ => {
+                self.sender
+                    .send(Err(QueryError::invalid_column_reference(
+                        "there is no unique or exclusion constraint matching the ON CONFLICT specification".to_owned(),
+                    )))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+        };
+
+        let mut to_update = vec![];
+        let mut non_existing_columns = BTreeSet::new();
+        for Assignment { id, value } in assignments.iter() {
+            match all_columns.iter().position(|column| column.has_name(&id.value)) {
+                Some(index) if all_columns[index].generated().is_some() => {
+                    self.sender
+                        .send(Err(QueryError::generated_column_cannot_be_written(
+                            all_columns[index].name(),
+                        )))
+                        .expect("To Send Result to Client");
+                    return Ok(());
+                }
+                Some(index) => to_update.push((index, value)),
+                None => {
+                    non_existing_columns.insert(id.value.clone());
+                }
+            }
+        }
+        if !non_existing_columns.is_empty() {
+            self.sender
+                .send(Err(QueryError::column_does_not_exist(
+                    non_existing_columns.into_iter().collect(),
+                )))
+                .expect("To Send Result to Client");
+            return Ok(());
+        }
+
+        let columns: Vec<RelationColumn> = all_columns
+            .iter()
+            .map(|column| {
+                RelationColumn::qualified(
+                    Some(schema_name.to_owned()),
+                    Some(table_name.to_owned()),
+                    column.name(),
+                    (&column.sql_type()).into(),
+                )
+            })
+            .collect();
+        // `excluded` columns are resolved only when a column of the table does not match
+        let excluded: Vec<RelationColumn> = all_columns
+            .iter()
+            .map(|column| {
+                RelationColumn::qualified(
+                    None,
+                    Some("excluded".to_owned()),
+                    column.name(),
+                    (&column.sql_type()).into(),
+                )
+            })
+            .collect();
+        let header = Scope::new(Frame::header(&columns), Some(Frame::header(&excluded)));
+        for expr in selection
+            .iter()
+            .map(|expr| &**expr)
+            .chain(to_update.iter().map(|(_, expr)| *expr).filter(|expr| !is_default(expr)))
+        {
+            let error = match header.check(expr) {
+                Ok(()) => continue,
+                Err(ColumnReferenceError::Missing(columns)) => QueryError::column_does_not_exist(columns),
+                Err(ColumnReferenceError::Ambiguous(column)) => QueryError::ambiguous_column(column),
+            };
+            self.sender.send(Err(error)).expect("To Send Result to Client");
+            return Ok(());
+        }
+        if dry_run {
+            return Ok(());
+        }
+
+        // stored and inserted rows with a flag whether a row was affected by the statement
+        let mut rows: Vec<(Binary, Binary, bool)> = self
+            .storage
+            .full_scan(self.session_id, schema_id, table_id)?
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .map(|(key, values)| (key, values, false))
+            .collect();
+
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let generated = generation_expressions(all_columns);
+        let defaults = default_expressions(all_columns);
+        let mut affected = 0;
+        let mut inserted = 0;
+        for (row_index, (key, values)) in proposed.into_iter().enumerate() {
+            let proposed_datums = unpack_raw(values.to_bytes());
+            let conflict = if proposed_datums[target_index] == Datum::from_null() {
+                None
+            } else {
+                rows.iter().position(|(_, stored, _)| {
+                    unpack_raw(stored.to_bytes())[target_index] == proposed_datums[target_index]
+                })
+            };
+
+            let position = match conflict {
+                Some(position) => position,
+                None => {
+                    if let Some(column) = unique_conflict(all_columns, &rows, None, &proposed_datums) {
+                        self.sender
+                            .send(Err(QueryError::unique_violation(format!(
+                                "{}_{}_key",
+                                table_name, column
+                            ))))
+                            .expect("To Send Result to Client");
+                        return Ok(());
+                    }
+                    rows.push((key, values, true));
+                    affected += 1;
+                    inserted += 1;
+                    continue;
+                }
+            };
+
+            if rows[position].2 {
+                self.sender
+                    .send(Err(QueryError::cardinality_violation(
+                        "ON CONFLICT DO UPDATE command cannot affect row a second time".to_owned(),
+                    )))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+
+            let stored = rows[position].1.clone();
+            let mut datums = unpack_raw(stored.to_bytes());
+            let row: Vec<ExprResult> = datums.iter().cloned().map(ExprResult::from).collect();
+            let excluded_row: Vec<ExprResult> = proposed_datums.iter().cloned().map(ExprResult::from).collect();
+            let scope = Scope::new(Frame::new(&columns, &row), Some(Frame::new(&excluded, &excluded_row)));
+            let satisfied = match selection {
+                None => true,
+                Some(predicate) => match evaluation.eval_in(predicate, &scope) {
+                    Ok(ExprResult::Bool(value)) => value,
+                    Ok(ExprResult::Null) => false,
+                    Ok(_) => {
+                        self.sender
+                            .send(Err(QueryError::syntax_error(predicate.to_string())))
+                            .expect("To Send Query Result to Client");
+                        return Ok(());
+                    }
+                    Err(()) => return Ok(()),
+                },
+            };
+            if !satisfied {
+                continue;
+            }
+
+            let mut new_values = vec![];
+            for (index, expr) in to_update.iter() {
+                let default = defaults
+                    .iter()
+                    .find(|(column, _)| column == index)
+                    .map(|(_, expr)| expr);
+                let value = match default {
+                    Some(default) if is_default(expr) => evaluation.eval(default),
+                    None if is_default(expr) => Ok(Value::Null),
+                    _ => evaluation.eval_in(expr, &scope).map(Value::from),
+                };
+                match value {
+                    Ok(value) => new_values.push((*index, value)),
+                    Err(()) => return Ok(()),
+                }
+            }
+
+            let mut errors = vec![];
+            for (index, value) in new_values {
+                let column_definition = &all_columns[index];
+                let value = fit_char(value, column_definition, row_index, self.sender.as_ref());
+                let v = match &value {
+                    Value::Number(v) => v.to_string(),
+                    Value::SingleQuotedString(v) => v.to_string(),
+                    Value::Boolean(v) => v.to_string(),
+                    Value::Null => {
+                        datums[index] = Datum::from_null();
+                        continue;
+                    }
+                    _ => unimplemented!("other types not implemented"),
+                };
+                match column_definition.sql_type().constraint().validate(v.as_str()) {
+                    Ok(()) => datums[index] = column_datum(&value, &column_definition.sql_type()),
+                    Err(e) => errors.push((e, column_definition.clone())),
+                }
+            }
+            if !errors.is_empty() {
+                for (error, column_definition) in errors {
+                    self.sender
+                        .send(Err(constraint_error(error, &column_definition, row_index)))
+                        .expect("To Send Query Result to Client");
+                }
+                return Ok(());
+            }
+            if generate_values(
+                &mut evaluation,
+                &generated,
+                all_columns,
+                &mut datums,
+                row_index,
+                self.sender.as_ref(),
+            )
+            .is_err()
+            {
+                return Ok(());
+            }
+            if let Some(error) = not_null_violation(all_columns, &datums) {
+                self.sender.send(Err(error)).expect("To Send Result to Client");
+                return Ok(());
+            }
+
+            if let Some(column) = unique_conflict(all_columns, &rows, Some(position), &datums) {
+                self.sender
+                    .send(Err(QueryError::unique_violation(format!(
+                        "{}_{}_key",
+                        table_name, column
+                    ))))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            rows[position].1 = Binary::pack(&datums);
+            rows[position].2 = true;
+            affected += 1;
+        }
+
+        let affected_rows = rows
+            .into_iter()
+            .filter(|(_, _, affected)| *affected)
+            .map(|(key, values, _)| (key, values))
+            .collect();
+        match self
+            .storage
+            .write_into(self.session_id, schema_id, table_id, affected_rows)
+        {
+            Err(error) => Err(error),
+            Ok(_) => {
+                self.storage
+                    .count_changes(schema_id, table_id, TableChange::Inserted, inserted);
+                self.storage
+                    .count_changes(schema_id, table_id, TableChange::Updated, affected - inserted);
+                self.sender
+                    .send(Ok(QueryEvent::RecordsInserted(affected)))
+                    .expect("To Send Result to Client");
+                Ok(())
+            }
+        }
+    }
+}
+
+// name of the first unique column which value is already taken by other row
+fn unique_conflict(
+    all_columns: &[ColumnDefinition],
+    rows: &[(Binary, Binary, bool)],
+    except: Option<usize>,
+    datums: &[Datum],
+) -> Option<String> {
+    for (index, column_definition) in all_columns.iter().enumerate() {
+        if !column_definition.is_unique() || datums[index] == Datum::from_null() {
+            continue;
+        }
+        let taken = rows
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| Some(*position) != except)
+            .any(|(_, (_, stored, _))| unpack_raw(stored.to_bytes())[index] == datums[index]);
+        if taken {
+            return Some(column_definition.name());
+        }
+    }
+    None
 }
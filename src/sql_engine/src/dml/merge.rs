@@ -0,0 +1,559 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dml::{
+    column_datum, constraint_error, default_expressions, default_values, fit_char, generate_values,
+    generation_expressions, not_null_violation,
+    relation::{ColumnReferenceError, Frame, RelationColumn, Scope},
+    select::SelectCommand,
+    update::from_clause_query,
+    ExprResult, ExpressionEvaluation,
+};
+use data_manager::{ColumnDefinition, DataManager, LockMode, Privilege, Row, SessionId, TableChange};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use representation::{unpack_raw, Binary, Datum};
+use sql_types::ConstraintError;
+use sqlparser::ast::{Assignment, Expr, Ident, ObjectName, TableFactor, TableWithJoins, Value};
+use std::sync::Arc;
+
+/// Rows of the target table or the source a `WHEN` clause of MERGE applies to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MergeKind {
+    /// target rows that are joined with a source row
+    Matched,
+    /// source rows without target rows to join
+    NotMatchedByTarget,
+    /// target rows without source rows to join
+    NotMatchedBySource,
+}
+
+/// What is done with a row by a `WHEN` clause of MERGE
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MergeAction {
+    Update(Vec<Assignment>),
+    Delete,
+    Insert { columns: Vec<Ident>, values: Vec<Expr> },
+}
+
+/// `WHEN [NOT] MATCHED [BY { TARGET | SOURCE }] [AND <predicate>] THEN <action>`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MergeClause {
+    pub(crate) kind: MergeKind,
+    pub(crate) predicate: Option<Expr>,
+    pub(crate) action: MergeAction,
+}
+
+pub(crate) struct MergeCommand<'mc> {
+    raw_sql_query: &'mc str,
+    name: ObjectName,
+    alias: Option<Ident>,
+    source: TableFactor,
+    on: Expr,
+    clauses: Vec<MergeClause>,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl<'mc> MergeCommand<'mc> {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        raw_sql_query: &'mc str,
+        name: ObjectName,
+        alias: Option<Ident>,
+        source: TableFactor,
+        on: Expr,
+        clauses: Vec<MergeClause>,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> MergeCommand<'mc> {
+        MergeCommand {
+            raw_sql_query,
+            name,
+            alias,
+            source,
+            on,
+            clauses,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        self.run(false)
+    }
+
+    // joins target and source rows and evaluates actions of the clauses, nothing is written
+    pub(crate) fn validate(&mut self) -> SystemResult<()> {
+        self.run(true)
+    }
+
+    fn run(&mut self, dry_run: bool) -> SystemResult<()> {
+        let schema_name = self.name.0[0].to_string();
+        let table_name = self.name.0[1].to_string();
+
+        let (schema_id, table_id) = match self
+            .storage
+            .session_table_exists(self.session_id, &schema_name, &table_name)
+        {
+            None => {
+                self.sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((_, None)) => {
+                self.sender
+                    .send(Err(QueryError::table_does_not_exist(
+                        schema_name + "." + table_name.as_str(),
+                    )))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        };
+        for clause in self.clauses.iter() {
+            let privilege = match clause.action {
+                MergeAction::Update(_) => Privilege::Update,
+                MergeAction::Delete => Privilege::Delete,
+                MergeAction::Insert { .. } => Privilege::Insert,
+            };
+            if !self
+                .storage
+                .has_privilege(self.session_id, schema_id, table_id, privilege)
+            {
+                self.sender
+                    .send(Err(QueryError::permission_denied(format!(
+                        "permission denied for table {}",
+                        table_name
+                    ))))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+        }
+        self.storage
+            .lock_table(self.session_id, schema_id, table_id, LockMode::RowExclusive);
+
+        let all_columns = self.storage.table_columns(schema_id, table_id)?;
+        let (qualifier_schema, qualifier_table) = match &self.alias {
+            Some(alias) => (None, alias.value.clone()),
+            None => (Some(schema_name.clone()), table_name.clone()),
+        };
+        let mut columns: Vec<RelationColumn> = all_columns
+            .iter()
+            .map(|column| {
+                RelationColumn::qualified(
+                    qualifier_schema.clone(),
+                    Some(qualifier_table.clone()),
+                    column.name(),
+                    (&column.sql_type()).into(),
+                )
+            })
+            .collect();
+        let source = TableWithJoins {
+            relation: self.source.clone(),
+            joins: vec![],
+        };
+        let relation = match SelectCommand::new(
+            self.raw_sql_query,
+            Box::new(from_clause_query(vec![source])),
+            self.session_id,
+            self.storage.clone(),
+            self.sender.clone(),
+            None,
+        )
+        .source()?
+        {
+            Some(relation) => relation,
+            None => return Ok(()),
+        };
+        let source_columns = relation.columns;
+        let source_rows = relation.rows;
+        columns.extend(source_columns.iter().cloned());
+
+        let header = Scope::new(Frame::header(&columns), None);
+        // values of inserted rows can reference only columns of the source
+        let source_header = Scope::new(Frame::header(&source_columns), None);
+        // indexes of the columns that are assigned by each clause
+        let mut targets = vec![];
+        let mut checked = vec![&self.on];
+        for clause in self.clauses.iter() {
+            checked.extend(clause.predicate.iter());
+            match &clause.action {
+                MergeAction::Update(assignments) => {
+                    let mut indexes = vec![];
+                    for Assignment { id, value } in assignments {
+                        match all_columns.iter().position(|column| column.has_name(&id.value)) {
+                            Some(index) if all_columns[index].generated().is_some() => {
+                                self.sender
+                                    .send(Err(QueryError::generated_column_cannot_be_written(
+                                        all_columns[index].name(),
+                                    )))
+                                    .expect("To Send Result to Client");
+                                return Ok(());
+                            }
+                            Some(index) => indexes.push(index),
+                            None => {
+                                self.sender
+                                    .send(Err(QueryError::column_does_not_exist(vec![id.value.clone()])))
+                                    .expect("To Send Result to Client");
+                                return Ok(());
+                            }
+                        }
+                        checked.push(value);
+                    }
+                    targets.push(indexes);
+                }
+                MergeAction::Delete => targets.push(vec![]),
+                MergeAction::Insert { columns, values } => {
+                    let indexes = if columns.is_empty() {
+                        (0..all_columns.len()).collect()
+                    } else {
+                        let mut indexes = vec![];
+                        for column in columns {
+                            match all_columns
+                                .iter()
+                                .position(|definition| definition.has_name(&column.value))
+                            {
+                                Some(index) => indexes.push(index),
+                                None => {
+                                    self.sender
+                                        .send(Err(QueryError::column_does_not_exist(vec![column.value.clone()])))
+                                        .expect("To Send Result to Client");
+                                    return Ok(());
+                                }
+                            }
+                        }
+                        indexes
+                    };
+                    if values.len() > indexes.len() {
+                        self.sender
+                            .send(Err(QueryError::too_many_insert_expressions()))
+                            .expect("To Send Result to Client");
+                        return Ok(());
+                    }
+                    if let Some(index) = indexes
+                        .iter()
+                        .take(values.len())
+                        .find(|index| all_columns[**index].generated().is_some())
+                    {
+                        self.sender
+                            .send(Err(QueryError::generated_column_cannot_be_written(
+                                all_columns[*index].name(),
+                            )))
+                            .expect("To Send Result to Client");
+                        return Ok(());
+                    }
+                    for value in values {
+                        if !self.check(&source_header, value) {
+                            return Ok(());
+                        }
+                    }
+                    targets.push(indexes);
+                }
+            }
+        }
+        for expr in checked {
+            if !self.check(&header, expr) {
+                return Ok(());
+            }
+        }
+
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let generated = generation_expressions(&all_columns);
+        let defaults = default_expressions(&all_columns);
+        let records: Vec<Row> = self
+            .storage
+            .full_scan(self.session_id, schema_id, table_id)?
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .collect();
+        let mut source_matched = vec![false; source_rows.len()];
+        let mut updated = vec![];
+        let mut deleted = vec![];
+        let mut inserted = vec![];
+        for (row_index, (key, values)) in records.into_iter().enumerate() {
+            let mut datums = unpack_raw(values.to_bytes());
+            let target_row: Vec<ExprResult> = datums.iter().cloned().map(ExprResult::from).collect();
+
+            let mut matched = None;
+            for (source_index, source_row) in source_rows.iter().enumerate() {
+                let mut row = target_row.clone();
+                row.extend(source_row.iter().cloned());
+                match self.satisfied(&mut evaluation, &self.on, &columns, &row) {
+                    Ok(true) if matched.is_some() => {
+                        self.sender
+                            .send(Err(QueryError::cardinality_violation(
+                                "MERGE command cannot affect row a second time".to_owned(),
+                            )))
+                            .expect("To Send Result to Client");
+                        return Ok(());
+                    }
+                    Ok(true) => {
+                        source_matched[source_index] = true;
+                        matched = Some(row);
+                    }
+                    Ok(false) => {}
+                    Err(()) => return Ok(()),
+                }
+            }
+            let (kind, row) = match matched {
+                Some(row) => (MergeKind::Matched, row),
+                None => {
+                    let mut row = target_row;
+                    row.extend(source_columns.iter().map(|_| ExprResult::Null));
+                    (MergeKind::NotMatchedBySource, row)
+                }
+            };
+
+            let index = match self.applied(&mut evaluation, kind, &columns, &row) {
+                Ok(Some(index)) => index,
+                Ok(None) => continue,
+                Err(()) => return Ok(()),
+            };
+            match &self.clauses[index].action {
+                MergeAction::Update(assignments) => {
+                    let scope = Scope::new(Frame::new(&columns, &row), None);
+                    let mut new_values = vec![];
+                    for (Assignment { value, .. }, column) in assignments.iter().zip(targets[index].iter()) {
+                        match evaluation.eval_in(value, &scope) {
+                            Ok(value) => new_values.push((*column, Value::from(value))),
+                            Err(()) => return Ok(()),
+                        }
+                    }
+                    if !self.assign(&mut datums, new_values, &all_columns, row_index) {
+                        return Ok(());
+                    }
+                    if generate_values(
+                        &mut evaluation,
+                        &generated,
+                        &all_columns,
+                        &mut datums,
+                        row_index,
+                        self.sender.as_ref(),
+                    )
+                    .is_err()
+                    {
+                        return Ok(());
+                    }
+                    if let Some(error) = not_null_violation(&all_columns, &datums) {
+                        self.sender.send(Err(error)).expect("To Send Result to Client");
+                        return Ok(());
+                    }
+                    updated.push((key, Binary::pack(&datums)));
+                }
+                MergeAction::Delete => deleted.push(key),
+                MergeAction::Insert { .. } => {}
+            }
+        }
+
+        for (row_index, source_row) in source_rows.iter().enumerate() {
+            if source_matched[row_index] {
+                continue;
+            }
+            let mut row: Vec<ExprResult> = all_columns.iter().map(|_| ExprResult::Null).collect();
+            row.extend(source_row.iter().cloned());
+            let index = match self.applied(&mut evaluation, MergeKind::NotMatchedByTarget, &columns, &row) {
+                Ok(Some(index)) => index,
+                Ok(None) => continue,
+                Err(()) => return Ok(()),
+            };
+            if let MergeAction::Insert { values, .. } = &self.clauses[index].action {
+                let scope = Scope::new(Frame::new(&source_columns, source_row), None);
+                let mut new_values = vec![];
+                for (value, column) in values.iter().zip(targets[index].iter()) {
+                    match evaluation.eval_in(value, &scope) {
+                        Ok(value) => new_values.push((*column, Value::from(value))),
+                        Err(()) => return Ok(()),
+                    }
+                }
+                let mut record = vec![Datum::from_null(); all_columns.len()];
+                if !self.assign(&mut record, new_values, &all_columns, row_index) {
+                    return Ok(());
+                }
+                let assigned = &targets[index][..values.len()];
+                let omitted = |column: usize| !assigned.contains(&column) && all_columns[column].generated().is_none();
+                if default_values(
+                    &mut evaluation,
+                    &defaults,
+                    omitted,
+                    &all_columns,
+                    &mut record,
+                    row_index,
+                    self.sender.as_ref(),
+                )
+                .is_err()
+                {
+                    return Ok(());
+                }
+                if generate_values(
+                    &mut evaluation,
+                    &generated,
+                    &all_columns,
+                    &mut record,
+                    row_index,
+                    self.sender.as_ref(),
+                )
+                .is_err()
+                {
+                    return Ok(());
+                }
+                if let Some(error) = not_null_violation(&all_columns, &record) {
+                    self.sender.send(Err(error)).expect("To Send Result to Client");
+                    return Ok(());
+                }
+                let key = if dry_run {
+                    vec![]
+                } else {
+                    self.storage.next_key_id(schema_id, table_id).to_be_bytes().to_vec()
+                };
+                inserted.push((Binary::with_data(key), Binary::pack(&record)));
+            }
+        }
+
+        if dry_run {
+            return Ok(());
+        }
+
+        let conflicts = self.storage.unique_conflicts(schema_id, table_id, &inserted)?;
+        if let Some(column) = conflicts.into_iter().flatten().next() {
+            self.sender
+                .send(Err(QueryError::unique_violation(format!(
+                    "{}_{}_key",
+                    table_name, column
+                ))))
+                .expect("To Send Result to Client");
+            return Ok(());
+        }
+        let deleted = self
+            .storage
+            .delete_from(self.session_id, schema_id, table_id, deleted)?;
+        let updated = self.storage.write_into(self.session_id, schema_id, table_id, updated)?;
+        let inserted = self
+            .storage
+            .write_into(self.session_id, schema_id, table_id, inserted)?;
+        self.storage
+            .count_changes(schema_id, table_id, TableChange::Deleted, deleted);
+        self.storage
+            .count_changes(schema_id, table_id, TableChange::Updated, updated);
+        self.storage
+            .count_changes(schema_id, table_id, TableChange::Inserted, inserted);
+        let merged = deleted + updated + inserted;
+        self.sender
+            .send(Ok(QueryEvent::RecordsMerged(merged)))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    // reports references to missing or ambiguous columns
+    fn check(&self, scope: &Scope, expr: &Expr) -> bool {
+        let error = match scope.check(expr) {
+            Ok(()) => return true,
+            Err(ColumnReferenceError::Missing(columns)) => QueryError::column_does_not_exist(columns),
+            Err(ColumnReferenceError::Ambiguous(column)) => QueryError::ambiguous_column(column),
+        };
+        self.sender.send(Err(error)).expect("To Send Result to Client");
+        false
+    }
+
+    // NULL is not satisfied, values that aren't boolean are reported
+    fn satisfied(
+        &self,
+        evaluation: &mut ExpressionEvaluation,
+        predicate: &Expr,
+        columns: &[RelationColumn],
+        row: &[ExprResult],
+    ) -> Result<bool, ()> {
+        match evaluation.eval_in(predicate, &Scope::new(Frame::new(columns, row), None))? {
+            ExprResult::Bool(value) => Ok(value),
+            ExprResult::Null => Ok(false),
+            _ => {
+                self.sender
+                    .send(Err(QueryError::syntax_error(predicate.to_string())))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    // index of the first clause of the `kind` whose predicate is satisfied by the row
+    fn applied(
+        &self,
+        evaluation: &mut ExpressionEvaluation,
+        kind: MergeKind,
+        columns: &[RelationColumn],
+        row: &[ExprResult],
+    ) -> Result<Option<usize>, ()> {
+        for (index, clause) in self.clauses.iter().enumerate() {
+            if clause.kind != kind {
+                continue;
+            }
+            match &clause.predicate {
+                Some(predicate) if !self.satisfied(evaluation, predicate, columns, row)? => {}
+                _ => return Ok(Some(index)),
+            }
+        }
+        Ok(None)
+    }
+
+    // checks constraints of the new values, violations are reported
+    fn assign(
+        &self,
+        datums: &mut [Datum],
+        values: Vec<(usize, Value)>,
+        all_columns: &[ColumnDefinition],
+        row_index: usize,
+    ) -> bool {
+        let mut errors = vec![];
+        for (index, value) in values {
+            let column_definition = &all_columns[index];
+            let value = fit_char(value, column_definition, row_index, self.sender.as_ref());
+            let v = match &value {
+                Value::Number(v) => v.to_string(),
+                Value::SingleQuotedString(v) => v.to_string(),
+                Value::Boolean(v) => v.to_string(),
+                Value::Null => {
+                    datums[index] = Datum::from_null();
+                    continue;
+                }
+                other => {
+                    errors.push((
+                        ConstraintError::TypeMismatch(other.to_string()),
+                        column_definition.clone(),
+                    ));
+                    continue;
+                }
+            };
+            match column_definition.sql_type().constraint().validate(v.as_str()) {
+                Ok(()) => datums[index] = column_datum(&value, &column_definition.sql_type()),
+                Err(error) => errors.push((error, column_definition.clone())),
+            }
+        }
+        let valid = errors.is_empty();
+        for (error, column_definition) in errors {
+            self.sender
+                .send(Err(constraint_error(error, &column_definition, row_index)))
+                .expect("To Send Query Result to Client");
+        }
+        valid
+    }
+}
@@ -12,121 +12,1255 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use bigdecimal::BigDecimal;
-use protocol::{results::QueryError, Sender};
-use sqlparser::ast::{BinaryOperator, Expr, Value};
-use std::{ops::Deref, sync::Arc};
+use crate::dml::relation::{reference_name, Frame, RelationColumn, Resolution, Scope};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use data_manager::{
+    compare_keys, ColumnDefinition, DataManager, ForeignKeyDefinition, PartitionBounds, PartitionDefinition, RecordId,
+    ReturnType, Row, SessionId,
+};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent, QueryResult},
+    sql_types::PostgreSqlType,
+    Sender,
+};
+use representation::Datum;
+use sql_types::{array_literal, ConstraintError, SqlType};
+use sqlparser::{
+    ast::{BinaryOperator, DataType, Expr, Function, Ident, ObjectName, UnaryOperator, Value},
+    dialect::PostgreSqlDialect,
+    parser::Parser,
+    tokenizer::Tokenizer,
+};
+use std::{
+    cmp,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    io,
+    ops::Deref,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
+pub(crate) mod catalog;
+pub(crate) mod copy;
 pub(crate) mod delete;
+pub(crate) mod explain;
 pub(crate) mod insert;
+pub(crate) mod merge;
+pub(crate) mod relation;
 pub(crate) mod select;
+pub(crate) mod trigger;
 pub(crate) mod update;
 
+// calls of user functions that are nested deeper fail instead of overflowing the stack
+const MAX_FUNCTION_DEPTH: usize = 100;
+
+/// sends only errors and notices of nested statements, e.g. the ones that triggers execute,
+/// the client receives results of the statement that runs them
+pub(crate) struct NestedSender {
+    sender: Arc<dyn Sender>,
+    failed: AtomicBool,
+}
+
+impl NestedSender {
+    pub(crate) fn new(sender: Arc<dyn Sender>) -> NestedSender {
+        NestedSender {
+            sender,
+            failed: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn failed(&self) -> bool {
+        self.failed.load(Ordering::SeqCst)
+    }
+}
+
+impl Sender for NestedSender {
+    fn flush(&self) -> io::Result<()> {
+        self.sender.flush()
+    }
+
+    fn send(&self, query_result: QueryResult) -> io::Result<()> {
+        match query_result {
+            Err(error) => {
+                self.failed.store(true, Ordering::SeqCst);
+                self.sender.send(Err(error))
+            }
+            Ok(notice @ QueryEvent::Notice { .. }) => self.sender.send(Ok(notice)),
+            Ok(_) => Ok(()),
+        }
+    }
+}
+
+/// function created with `CREATE FUNCTION`, its body is evaluated against values of the arguments
+struct UserFunction {
+    schema: String,
+    name: String,
+    args: Vec<RelationColumn>,
+    body: Expr,
+}
+
 pub(crate) struct ExpressionEvaluation {
     session: Arc<dyn Sender>,
+    functions: Vec<Rc<UserFunction>>,
+    backend: Option<(SessionId, Arc<DataManager>)>,
+    depth: usize,
 }
 
 impl ExpressionEvaluation {
     pub(crate) fn new(session: Arc<dyn Sender>) -> ExpressionEvaluation {
-        ExpressionEvaluation { session }
+        ExpressionEvaluation {
+            session,
+            functions: vec![],
+            backend: None,
+            depth: 0,
+        }
+    }
+
+    /// the session that evaluates expressions, its id is the pid of the backend
+    pub(crate) fn with_session(mut self, session_id: SessionId, storage: Arc<DataManager>) -> ExpressionEvaluation {
+        self.backend = Some((session_id, storage));
+        self
+    }
+
+    /// makes functions that users created callable by their qualified names
+    pub(crate) fn with_functions(mut self, storage: &DataManager) -> ExpressionEvaluation {
+        self.functions = storage
+            .functions()
+            .into_iter()
+            .filter(|(_, function)| function.return_type() != ReturnType::Trigger)
+            .filter_map(|(schema, function)| {
+                let tokens = Tokenizer::new(&PostgreSqlDialect {}, function.body()).tokenize().ok()?;
+                let body = Parser::new(tokens).parse_expr().ok()?;
+                Some(Rc::new(UserFunction {
+                    schema,
+                    name: function.name().to_owned(),
+                    args: function
+                        .args()
+                        .iter()
+                        .map(|(name, sql_type)| RelationColumn::new(name.clone(), sql_type.into()))
+                        .collect(),
+                    body,
+                }))
+            })
+            .collect();
+        self
+    }
+
+    fn user_function(&self, name: &ObjectName) -> Option<Rc<UserFunction>> {
+        match name.0.as_slice() {
+            [schema, function_name] => self
+                .functions
+                .iter()
+                .find(|function| function.schema == schema.value && function.name == function_name.value)
+                .cloned(),
+            _ => None,
+        }
     }
 
     pub(crate) fn eval(&mut self, expr: &Expr) -> Result<Value, ()> {
-        match self.inner_eval(expr)? {
-            ExprResult::Number(v) => Ok(Value::Number(v)),
-            ExprResult::String(v) => Ok(Value::SingleQuotedString(v)),
-        }
-    }
-
-    fn inner_eval(&mut self, expr: &Expr) -> Result<ExprResult, ()> {
-        if let Expr::BinaryOp { op, left, right } = expr {
-            let left = self.inner_eval(left.deref())?;
-            let right = self.inner_eval(right.deref())?;
-            match (left, right) {
-                (ExprResult::Number(left), ExprResult::Number(right)) => match op {
-                    BinaryOperator::Plus => Ok(ExprResult::Number(left + right)),
-                    BinaryOperator::Minus => Ok(ExprResult::Number(left - right)),
-                    BinaryOperator::Multiply => Ok(ExprResult::Number(left * right)),
-                    BinaryOperator::Divide => Ok(ExprResult::Number(left / right)),
-                    BinaryOperator::Modulus => Ok(ExprResult::Number(left % right)),
-                    BinaryOperator::BitwiseAnd => {
-                        let (left, _) = left.as_bigint_and_exponent();
-                        let (right, _) = right.as_bigint_and_exponent();
-                        Ok(ExprResult::Number(BigDecimal::from(left & &right)))
-                    }
-                    BinaryOperator::BitwiseOr => {
-                        let (left, _) = left.as_bigint_and_exponent();
-                        let (right, _) = right.as_bigint_and_exponent();
-                        Ok(ExprResult::Number(BigDecimal::from(left | &right)))
-                    }
-                    operator => {
+        self.inner_eval(expr, None).map(Value::from)
+    }
+
+    pub(crate) fn eval_in(&mut self, expr: &Expr, scope: &Scope) -> Result<ExprResult, ()> {
+        self.inner_eval(expr, Some(scope))
+    }
+
+    fn inner_eval(&mut self, expr: &Expr, scope: Option<&Scope>) -> Result<ExprResult, ()> {
+        match (expr, scope) {
+            (Expr::BinaryOp { op, left, right }, _) => {
+                let left = self.inner_eval(left.deref(), scope)?;
+                if let Some((all, elements)) = quantified(right) {
+                    return self.quantified_op(op, left, all, elements, scope);
+                }
+                let right = self.inner_eval(right.deref(), scope)?;
+                self.binary_op(op, left, right)
+            }
+            (Expr::UnaryOp { op, expr }, _) => {
+                let operand = self.inner_eval(expr.deref(), scope)?;
+                match (op, operand) {
+                    (_, ExprResult::Null) => Ok(ExprResult::Null),
+                    (UnaryOperator::Minus, ExprResult::Number(v)) => Ok(ExprResult::Number(-v)),
+                    (UnaryOperator::Plus, ExprResult::Number(v)) => Ok(ExprResult::Number(v)),
+                    (UnaryOperator::Not, ExprResult::Bool(v)) => Ok(ExprResult::Bool(!v)),
+                    (op, operand) => {
                         self.session
                             .send(Err(QueryError::undefined_function(
-                                operator.to_string(),
-                                "NUMBER".to_owned(),
-                                "NUMBER".to_owned(),
+                                op.to_string(),
+                                "".to_owned(),
+                                operand.type_name().to_owned(),
                             )))
                             .expect("To Send Query Result to Client");
                         Err(())
                     }
+                }
+            }
+            (Expr::Nested(expr), _) => self.inner_eval(expr.deref(), scope),
+            (Expr::IsNull(expr), _) => Ok(ExprResult::Bool(self.inner_eval(expr.deref(), scope)?.is_null())),
+            (Expr::IsNotNull(expr), _) => Ok(ExprResult::Bool(!self.inner_eval(expr.deref(), scope)?.is_null())),
+            (Expr::Identifier(ident), Some(scope)) => self.column_value(std::slice::from_ref(ident), scope),
+            (Expr::CompoundIdentifier(idents), Some(scope)) => self.column_value(idents, scope),
+            (Expr::Value(Value::Number(v)), _) => Ok(ExprResult::Number(v.clone())),
+            (Expr::Value(Value::SingleQuotedString(v)), _) => Ok(ExprResult::String(v.clone())),
+            (Expr::Value(Value::Boolean(v)), _) => Ok(ExprResult::Bool(*v)),
+            (Expr::Value(Value::Null), _) => Ok(ExprResult::Null),
+            (
+                Expr::Cast {
+                    expr: operand,
+                    data_type,
                 },
-                (ExprResult::String(left), ExprResult::String(right)) => match op {
-                    BinaryOperator::StringConcat => Ok(ExprResult::String(left + right.as_str())),
-                    operator => {
+                _,
+            ) => {
+                let value = self.inner_eval(operand.deref(), scope)?;
+                self.cast(value, data_type, expr)
+            }
+            // window functions are not implemented
+            (Expr::Function(Function { over: Some(_), .. }), _) => {
+                self.session
+                    .send(Err(QueryError::feature_not_supported(expr.to_string())))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            (Expr::Function(Function { name, args, .. }), _) if name.to_string().eq_ignore_ascii_case("array") => {
+                let mut elements = vec![];
+                for arg in args {
+                    elements.push(match self.inner_eval(arg, scope)? {
+                        ExprResult::Null => None,
+                        element => Some(element.to_string()),
+                    });
+                }
+                Ok(ExprResult::String(array_literal(elements)))
+            }
+            (Expr::Function(Function { name, args, .. }), _) if distinct_predicate(name).is_some() => {
+                let negated = distinct_predicate(name) == Some(true);
+                match args.as_slice() {
+                    [left, right] => {
+                        let left = self.inner_eval(left, scope)?;
+                        let right = self.inner_eval(right, scope)?;
+                        // NULL is not distinct from NULL and distinct from any other value
+                        let distinct = match (left, right) {
+                            (ExprResult::Null, ExprResult::Null) => false,
+                            (ExprResult::Null, _) | (_, ExprResult::Null) => true,
+                            (left, right) => {
+                                self.binary_op(&BinaryOperator::NotEq, left, right)? == ExprResult::Bool(true)
+                            }
+                        };
+                        Ok(ExprResult::Bool(distinct != negated))
+                    }
+                    _ => {
                         self.session
-                            .send(Err(QueryError::undefined_function(
-                                operator.to_string(),
-                                "STRING".to_owned(),
-                                "STRING".to_owned(),
-                            )))
+                            .send(Err(QueryError::syntax_error(expr.to_string())))
                             .expect("To Send Query Result to Client");
                         Err(())
                     }
-                },
-                (ExprResult::Number(left), ExprResult::String(right)) => match op {
-                    BinaryOperator::StringConcat => Ok(ExprResult::String(left.to_string() + right.as_str())),
-                    operator => {
+                }
+            }
+            (Expr::Function(Function { name, args, .. }), _) if self.user_function(name).is_some() => {
+                let function = self.user_function(name).expect("function exists");
+                let mut values = vec![];
+                for arg in args {
+                    values.push(self.inner_eval(arg, scope)?);
+                }
+                if values.len() != function.args.len() {
+                    self.session
+                        .send(Err(QueryError::function_does_not_exist(format!(
+                            "{}({})",
+                            name,
+                            values
+                                .iter()
+                                .map(ExprResult::type_name)
+                                .collect::<Vec<&str>>()
+                                .join(", ")
+                        ))))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+                if self.depth == MAX_FUNCTION_DEPTH {
+                    self.session
+                        .send(Err(QueryError::stack_depth_limit_exceeded()))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+                self.depth += 1;
+                let result = self.inner_eval(
+                    &function.body,
+                    Some(&Scope::new(Frame::new(&function.args, &values), None)),
+                );
+                self.depth -= 1;
+                result
+            }
+            (Expr::Function(Function { name, args, .. }), _)
+                if name.to_string().eq_ignore_ascii_case("pg_backend_pid") && self.backend.is_some() =>
+            {
+                match (args.as_slice(), &self.backend) {
+                    ([], Some((session_id, _))) => Ok(ExprResult::Number(BigDecimal::from(*session_id))),
+                    _ => {
                         self.session
-                            .send(Err(QueryError::undefined_function(
-                                operator.to_string(),
-                                "NUMBER".to_owned(),
-                                "STRING".to_owned(),
-                            )))
+                            .send(Err(QueryError::function_does_not_exist(expr.to_string())))
                             .expect("To Send Query Result to Client");
                         Err(())
                     }
-                },
-                (ExprResult::String(left), ExprResult::Number(right)) => match op {
-                    BinaryOperator::StringConcat => Ok(ExprResult::String(left + right.to_string().as_str())),
-                    operator => {
+                }
+            }
+            (Expr::Function(Function { name, args, .. }), _)
+                if name.to_string().eq_ignore_ascii_case("pg_cancel_backend") && self.backend.is_some() =>
+            {
+                let pid = match args.as_slice() {
+                    [arg] => self.inner_eval(arg, scope)?,
+                    _ => {
+                        self.session
+                            .send(Err(QueryError::function_does_not_exist(expr.to_string())))
+                            .expect("To Send Query Result to Client");
+                        return Err(());
+                    }
+                };
+                match pid {
+                    ExprResult::Null => Ok(ExprResult::Null),
+                    ExprResult::Number(pid) => {
+                        let (_, storage) = self.backend.as_ref().expect("backend is set");
+                        let canceled = match pid.to_u64() {
+                            Some(session_id) => storage.cancel_session(session_id),
+                            None => false,
+                        };
+                        if !canceled {
+                            self.session
+                                .send(Ok(QueryEvent::Notice {
+                                    severity: "WARNING".to_owned(),
+                                    message: format!("PID {} is not a PostgreSQL backend process", pid),
+                                }))
+                                .expect("To Send Query Result to Client");
+                        }
+                        Ok(ExprResult::Bool(canceled))
+                    }
+                    value => {
                         self.session
                             .send(Err(QueryError::undefined_function(
-                                operator.to_string(),
-                                "STRING".to_owned(),
-                                "NUMBER".to_owned(),
+                                "pg_cancel_backend".to_owned(),
+                                "".to_owned(),
+                                value.type_name().to_owned(),
                             )))
                             .expect("To Send Query Result to Client");
                         Err(())
                     }
-                },
+                }
             }
-        } else {
-            match expr {
-                Expr::Value(Value::Number(v)) => Ok(ExprResult::Number(v.clone())),
-                Expr::Value(Value::SingleQuotedString(v)) => Ok(ExprResult::String(v.clone())),
-                e => {
+            (Expr::Function(Function { name, args, .. }), _) if rounding_function(name).is_some() => {
+                let round = rounding_function(name).expect("rounding function");
+                match args.as_slice() {
+                    [arg] => match self.inner_eval(arg, scope)? {
+                        ExprResult::Null => Ok(ExprResult::Null),
+                        ExprResult::Number(value) => Ok(ExprResult::Number(round(&value))),
+                        value => {
+                            self.session
+                                .send(Err(QueryError::undefined_function(
+                                    name.to_string().to_lowercase(),
+                                    "".to_owned(),
+                                    value.type_name().to_owned(),
+                                )))
+                                .expect("To Send Query Result to Client");
+                            Err(())
+                        }
+                    },
+                    _ => {
+                        self.session
+                            .send(Err(QueryError::syntax_error(expr.to_string())))
+                            .expect("To Send Query Result to Client");
+                        Err(())
+                    }
+                }
+            }
+            (Expr::Function(Function { name, args, .. }), _)
+                if name.to_string().eq_ignore_ascii_case("upper") || name.to_string().eq_ignore_ascii_case("lower") =>
+            {
+                let name = name.to_string().to_lowercase();
+                match args.as_slice() {
+                    [arg] => match self.inner_eval(arg, scope)? {
+                        ExprResult::Null => Ok(ExprResult::Null),
+                        ExprResult::String(value) if name == "upper" => Ok(ExprResult::String(value.to_uppercase())),
+                        ExprResult::String(value) => Ok(ExprResult::String(value.to_lowercase())),
+                        value => {
+                            self.session
+                                .send(Err(QueryError::undefined_function(
+                                    name,
+                                    "".to_owned(),
+                                    value.type_name().to_owned(),
+                                )))
+                                .expect("To Send Query Result to Client");
+                            Err(())
+                        }
+                    },
+                    _ => {
+                        self.session
+                            .send(Err(QueryError::syntax_error(expr.to_string())))
+                            .expect("To Send Query Result to Client");
+                        Err(())
+                    }
+                }
+            }
+            (e, _) => {
+                self.session
+                    .send(Err(QueryError::syntax_error(e.to_string())))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    // explicit conversion of the `value` to `data_type`, numbers converted to integer types are rounded
+    // half to even, `trunc`, `floor` and `ceil` functions are used for the other ways of rounding
+    fn cast(&mut self, value: ExprResult, data_type: &DataType, expr: &Expr) -> Result<ExprResult, ()> {
+        match (data_type, value) {
+            (_, ExprResult::Null) => Ok(ExprResult::Null),
+            (DataType::SmallInt, value) => self.integer_cast(
+                value,
+                PostgreSqlType::SmallInt,
+                i64::from(i16::MIN),
+                i64::from(i16::MAX),
+                expr,
+            ),
+            (DataType::Int, value) => self.integer_cast(
+                value,
+                PostgreSqlType::Integer,
+                i64::from(i32::MIN),
+                i64::from(i32::MAX),
+                expr,
+            ),
+            (DataType::BigInt, value) => self.integer_cast(value, PostgreSqlType::BigInt, i64::MIN, i64::MAX, expr),
+            (DataType::Decimal(..), ExprResult::Number(value))
+            | (DataType::Float(_), ExprResult::Number(value))
+            | (DataType::Real, ExprResult::Number(value))
+            | (DataType::Double, ExprResult::Number(value)) => Ok(ExprResult::Number(value)),
+            (DataType::Decimal(..), ExprResult::String(value))
+            | (DataType::Float(_), ExprResult::String(value))
+            | (DataType::Real, ExprResult::String(value))
+            | (DataType::Double, ExprResult::String(value)) => match value.trim().parse::<BigDecimal>() {
+                Ok(number) => Ok(ExprResult::Number(number)),
+                Err(_) => {
                     self.session
-                        .send(Err(QueryError::syntax_error(e.to_string())))
+                        .send(Err(QueryError::invalid_text_representation(
+                            PostgreSqlType::Decimal,
+                            value,
+                        )))
                         .expect("To Send Query Result to Client");
                     Err(())
                 }
+            },
+            (DataType::Char(_), value) | (DataType::Varchar(_), value) | (DataType::Text, value) => {
+                Ok(ExprResult::String(value.to_string()))
+            }
+            (DataType::Boolean, ExprResult::Bool(value)) => Ok(ExprResult::Bool(value)),
+            _ => {
+                self.session
+                    .send(Err(QueryError::feature_not_supported(expr.to_string())))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    fn integer_cast(
+        &mut self,
+        value: ExprResult,
+        pg_type: PostgreSqlType,
+        min: i64,
+        max: i64,
+        expr: &Expr,
+    ) -> Result<ExprResult, ()> {
+        let number = match value {
+            ExprResult::Number(number) => round_half_even(&number).to_i64(),
+            // text has to be an integer, it is never rounded
+            ExprResult::String(text) => match text.trim().parse::<i64>() {
+                Ok(number) => Some(number),
+                Err(_) => {
+                    self.session
+                        .send(Err(QueryError::invalid_text_representation(pg_type, text)))
+                        .expect("To Send Query Result to Client");
+                    return Err(());
+                }
+            },
+            _ => {
+                self.session
+                    .send(Err(QueryError::feature_not_supported(expr.to_string())))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        match number {
+            Some(number) if min <= number && number <= max => Ok(ExprResult::Number(BigDecimal::from(number))),
+            _ => {
+                self.session
+                    .send(Err(QueryError::value_out_of_range(pg_type)))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    fn column_value(&mut self, idents: &[Ident], scope: &Scope) -> Result<ExprResult, ()> {
+        match scope.resolve(idents) {
+            Resolution::Found { outer, index } => Ok(scope.value(outer, index).clone()),
+            Resolution::Missing => {
+                let missing = vec![reference_name(idents)];
+                let hint = scope.column_hint(&missing);
+                let error = QueryError::column_does_not_exist(missing);
+                self.session
+                    .send(Err(match hint {
+                        Some(hint) => error.with_hint(hint),
+                        None => error,
+                    }))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            Resolution::Ambiguous => {
+                self.session
+                    .send(Err(QueryError::ambiguous_column(reference_name(idents))))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    fn binary_op(&mut self, op: &BinaryOperator, left: ExprResult, right: ExprResult) -> Result<ExprResult, ()> {
+        if let BinaryOperator::And | BinaryOperator::Or = op {
+            return self.logical_op(op, left, right);
+        }
+        match (left, right) {
+            (ExprResult::Null, _) | (_, ExprResult::Null) => Ok(ExprResult::Null),
+            (ExprResult::Number(left), ExprResult::Number(right)) => match op {
+                BinaryOperator::Plus => Ok(ExprResult::Number(left + right)),
+                BinaryOperator::Minus => Ok(ExprResult::Number(left - right)),
+                BinaryOperator::Multiply => Ok(ExprResult::Number(left * right)),
+                BinaryOperator::Divide => Ok(ExprResult::Number(left / right)),
+                BinaryOperator::Modulus => Ok(ExprResult::Number(left % right)),
+                BinaryOperator::BitwiseAnd => {
+                    let (left, _) = left.as_bigint_and_exponent();
+                    let (right, _) = right.as_bigint_and_exponent();
+                    Ok(ExprResult::Number(BigDecimal::from(left & &right)))
+                }
+                BinaryOperator::BitwiseOr => {
+                    let (left, _) = left.as_bigint_and_exponent();
+                    let (right, _) = right.as_bigint_and_exponent();
+                    Ok(ExprResult::Number(BigDecimal::from(left | &right)))
+                }
+                operator => match compare(operator, &left, &right) {
+                    Some(result) => Ok(ExprResult::Bool(result)),
+                    None => self.undefined_function(operator, "NUMBER", "NUMBER"),
+                },
+            },
+            (ExprResult::String(left), ExprResult::String(right)) => match op {
+                BinaryOperator::StringConcat => Ok(ExprResult::String(left + right.as_str())),
+                operator => match compare(operator, &left, &right) {
+                    Some(result) => Ok(ExprResult::Bool(result)),
+                    None => self.undefined_function(operator, "STRING", "STRING"),
+                },
+            },
+            (ExprResult::Bool(left), ExprResult::Bool(right)) => match compare(op, &left, &right) {
+                Some(result) => Ok(ExprResult::Bool(result)),
+                None => self.undefined_function(op, "BOOL", "BOOL"),
+            },
+            // numbers are converted to text only to be concatenated, see `assignment_cast` for the others
+            (ExprResult::Number(left), ExprResult::String(right)) => match op {
+                BinaryOperator::StringConcat => Ok(ExprResult::String(left.to_string() + right.as_str())),
+                operator => self.undefined_function(operator, "NUMBER", "STRING"),
+            },
+            (ExprResult::String(left), ExprResult::Number(right)) => match op {
+                BinaryOperator::StringConcat => Ok(ExprResult::String(left + right.to_string().as_str())),
+                operator => self.undefined_function(operator, "STRING", "NUMBER"),
+            },
+            (left, right) => self.undefined_function(op, left.type_name(), right.type_name()),
+        }
+    }
+
+    // `<left> <op> ANY (ARRAY[...])` is true when comparison is true for some of the elements and
+    // `<left> <op> ALL (ARRAY[...])` when it is true for all of them, NULL when it is not known
+    fn quantified_op(
+        &mut self,
+        op: &BinaryOperator,
+        left: ExprResult,
+        all: bool,
+        elements: &[Expr],
+        scope: Option<&Scope>,
+    ) -> Result<ExprResult, ()> {
+        let mut values = vec![];
+        for element in elements {
+            values.push(self.inner_eval(element, scope)?);
+        }
+        let expected = values
+            .iter()
+            .chain(std::iter::once(&left))
+            .find(|value| !value.is_null());
+        if let Some(expected) = expected {
+            let left_type = if left.is_null() {
+                expected.type_name()
+            } else {
+                left.type_name()
+            };
+            if let Some(mismatched) = values
+                .iter()
+                .find(|value| !value.is_null() && value.type_name() != left_type)
+            {
+                return self.undefined_function(op, left_type, mismatched.type_name());
+            }
+        }
+        let mut result = Some(all);
+        for value in values {
+            match self.binary_op(op, left.clone(), value)? {
+                ExprResult::Bool(matched) if matched != all => return Ok(ExprResult::Bool(matched)),
+                ExprResult::Bool(_) => {}
+                ExprResult::Null => result = None,
+                _ => return self.undefined_function(op, left.type_name(), "ARRAY"),
+            }
+        }
+        Ok(result.map_or(ExprResult::Null, ExprResult::Bool))
+    }
+
+    // AND and OR follow three-valued logic, e.g. `NULL AND false` is `false`
+    fn logical_op(&mut self, op: &BinaryOperator, left: ExprResult, right: ExprResult) -> Result<ExprResult, ()> {
+        let (left, right) = match (left, right) {
+            (ExprResult::Bool(left), ExprResult::Bool(right)) => (Some(left), Some(right)),
+            (ExprResult::Bool(left), ExprResult::Null) => (Some(left), None),
+            (ExprResult::Null, ExprResult::Bool(right)) => (None, Some(right)),
+            (ExprResult::Null, ExprResult::Null) => (None, None),
+            (left, right) => return self.undefined_function(op, left.type_name(), right.type_name()),
+        };
+        let result = match (op, left, right) {
+            (BinaryOperator::And, Some(false), _) | (BinaryOperator::And, _, Some(false)) => Some(false),
+            (BinaryOperator::And, Some(true), Some(true)) => Some(true),
+            (BinaryOperator::Or, Some(true), _) | (BinaryOperator::Or, _, Some(true)) => Some(true),
+            (BinaryOperator::Or, Some(false), Some(false)) => Some(false),
+            _ => None,
+        };
+        Ok(result.map_or(ExprResult::Null, ExprResult::Bool))
+    }
+
+    fn undefined_function(
+        &mut self,
+        operator: &BinaryOperator,
+        left_type: &str,
+        right_type: &str,
+    ) -> Result<ExprResult, ()> {
+        self.session
+            .send(Err(QueryError::undefined_function(
+                operator.to_string(),
+                left_type.to_owned(),
+                right_type.to_owned(),
+            )))
+            .expect("To Send Query Result to Client");
+        Err(())
+    }
+}
+
+/// maps violation of column type constraint to the error reported for the `row_index` (zero based) row
+pub(crate) fn constraint_error(
+    error: ConstraintError,
+    column_definition: &ColumnDefinition,
+    row_index: usize,
+) -> QueryError {
+    match error {
+        ConstraintError::OutOfRange => QueryError::out_of_range(
+            (&column_definition.sql_type()).into(),
+            column_definition.name(),
+            row_index + 1,
+        ),
+        ConstraintError::TypeMismatch(value) => QueryError::type_mismatch(
+            &value,
+            (&column_definition.sql_type()).into(),
+            column_definition.name(),
+            row_index + 1,
+        ),
+        ConstraintError::ValueTooLong(len) => QueryError::string_length_mismatch(
+            (&column_definition.sql_type()).into(),
+            len,
+            column_definition.name(),
+            row_index + 1,
+        ),
+    }
+}
+
+/// datum of the `value` that satisfies column `sql_type`, arrays, JSON documents and binary strings are
+/// kept in their canonical text form
+pub(crate) fn column_datum(value: &Value, sql_type: &SqlType) -> Datum<'static> {
+    match (sql_type, value) {
+        (SqlType::Array(_), Value::SingleQuotedString(v))
+        | (SqlType::Json, Value::SingleQuotedString(v))
+        | (SqlType::Bytea, Value::SingleQuotedString(v))
+        | (SqlType::Timestamp, Value::SingleQuotedString(v)) => {
+            let serializer = sql_type.serializer();
+            Datum::from_string(serializer.des(&serializer.ser(v)))
+        }
+        _ => Datum::try_from(value).unwrap(),
+    }
+}
+
+/// `column = literal` conjunct of the `predicate` that the storage could check against stored values while
+/// scanning the table with `definitions` of its columns, the predicate has to be evaluated anyway for the
+/// rows the storage returns
+pub(crate) fn equality_filter(
+    predicate: &Expr,
+    scope: &Scope,
+    definitions: &[ColumnDefinition],
+) -> Option<(usize, Datum<'static>)> {
+    match predicate {
+        Expr::Nested(expr) => equality_filter(expr, scope, definitions),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => equality_filter(left, scope, definitions).or_else(|| equality_filter(right, scope, definitions)),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq,
+            right,
+        } => {
+            let (column, value) = match (left.deref(), right.deref()) {
+                (Expr::Identifier(ident), Expr::Value(value)) | (Expr::Value(value), Expr::Identifier(ident)) => {
+                    (vec![ident.clone()], value)
+                }
+                (Expr::CompoundIdentifier(idents), Expr::Value(value))
+                | (Expr::Value(value), Expr::CompoundIdentifier(idents)) => (idents.clone(), value),
+                _ => return None,
+            };
+            let index = match scope.resolve(&column) {
+                Resolution::Found { outer: false, index } if index < definitions.len() => index,
+                _ => return None,
+            };
+            match (definitions[index].sql_type(), value) {
+                (SqlType::SmallInt(_), Value::Number(number))
+                | (SqlType::Integer(_), Value::Number(number))
+                | (SqlType::BigInt(_), Value::Number(number))
+                    if number.is_integer() =>
+                {
+                    Datum::try_from(value).ok().map(|datum| (index, datum))
+                }
+                (SqlType::VarChar(_), Value::SingleQuotedString(_)) => {
+                    Datum::try_from(value).ok().map(|datum| (index, datum))
+                }
+                _ => None,
             }
         }
+        _ => None,
+    }
+}
+
+/// whether the `predicate` has a `key op literal` conjunct that no row of the `partition` could satisfy,
+/// `key` is index of the partition key column among `definitions`
+pub(crate) fn excludes_partition(
+    predicate: &Expr,
+    scope: &Scope,
+    definitions: &[ColumnDefinition],
+    key: usize,
+    partition: &PartitionDefinition,
+) -> bool {
+    let (column, op, value) = match predicate {
+        Expr::Nested(expr) => return excludes_partition(expr, scope, definitions, key, partition),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            return excludes_partition(left, scope, definitions, key, partition)
+                || excludes_partition(right, scope, definitions, key, partition)
+        }
+        Expr::BinaryOp { left, op, right } => match (left.deref(), right.deref()) {
+            (Expr::Identifier(ident), Expr::Value(value)) => (vec![ident.clone()], op.clone(), value),
+            (Expr::CompoundIdentifier(idents), Expr::Value(value)) => (idents.clone(), op.clone(), value),
+            (Expr::Value(value), Expr::Identifier(ident)) => (vec![ident.clone()], flipped(op), value),
+            (Expr::Value(value), Expr::CompoundIdentifier(idents)) => (idents.clone(), flipped(op), value),
+            _ => return false,
+        },
+        _ => return false,
+    };
+    match scope.resolve(&column) {
+        Resolution::Found { outer: false, index } if index == key => {}
+        _ => return false,
+    }
+    let sql_type = definitions[key].sql_type();
+    let text = match value {
+        Value::Number(number) => number.to_string(),
+        Value::SingleQuotedString(text) => text.clone(),
+        _ => return false,
+    };
+    if sql_type.constraint().validate(text.as_str()).is_err() {
+        return false;
+    }
+    let value = column_datum(value, &sql_type);
+    match partition.bounds() {
+        PartitionBounds::Range { lower, upper } => {
+            let lower = compare_keys(lower, &value);
+            let upper = compare_keys(upper, &value);
+            match op {
+                BinaryOperator::Gt | BinaryOperator::GtEq => {
+                    matches!(upper, Some(cmp::Ordering::Less) | Some(cmp::Ordering::Equal))
+                }
+                BinaryOperator::Lt => matches!(lower, Some(cmp::Ordering::Greater) | Some(cmp::Ordering::Equal)),
+                BinaryOperator::LtEq => lower == Some(cmp::Ordering::Greater),
+                BinaryOperator::Eq => lower.is_some() && upper.is_some() && !partition.contains(&value),
+                _ => false,
+            }
+        }
+        PartitionBounds::List(values) => {
+            op == BinaryOperator::Eq
+                && values.iter().all(|listed| {
+                    matches!(
+                        compare_keys(listed, &value),
+                        Some(cmp::Ordering::Less) | Some(cmp::Ordering::Greater)
+                    )
+                })
+        }
+        // rows of the default partition can have any key
+        PartitionBounds::Default => false,
+    }
+}
+
+// operator that gives the same result when operands are swapped
+fn flipped(op: &BinaryOperator) -> BinaryOperator {
+    match op {
+        BinaryOperator::Gt => BinaryOperator::Lt,
+        BinaryOperator::GtEq => BinaryOperator::LtEq,
+        BinaryOperator::Lt => BinaryOperator::Gt,
+        BinaryOperator::LtEq => BinaryOperator::GtEq,
+        op => op.clone(),
+    }
+}
+
+/// expressions of generated columns with indexes of the columns, the expressions are stored as text
+pub(crate) fn generation_expressions(definitions: &[ColumnDefinition]) -> Vec<(usize, Expr)> {
+    parse_expressions(definitions, ColumnDefinition::generated)
+}
+
+/// `DEFAULT` expressions of columns with indexes of the columns, columns without one default to NULL
+pub(crate) fn default_expressions(definitions: &[ColumnDefinition]) -> Vec<(usize, Expr)> {
+    parse_expressions(definitions, ColumnDefinition::default_expression)
+}
+
+fn parse_expressions<'d>(
+    definitions: &'d [ColumnDefinition],
+    text: impl Fn(&'d ColumnDefinition) -> Option<&'d str>,
+) -> Vec<(usize, Expr)> {
+    definitions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, column)| {
+            let tokens = Tokenizer::new(&PostgreSqlDialect {}, text(column)?).tokenize().ok()?;
+            Parser::new(tokens).parse_expr().ok().map(|expr| (index, expr))
+        })
+        .collect()
+}
+
+/// reports the first row that references a key missing in the referenced table,
+/// `false` when the rows violate foreign keys of the table
+pub(crate) fn check_foreign_keys(
+    storage: &DataManager,
+    session_id: SessionId,
+    schema_id: RecordId,
+    table_id: RecordId,
+    table_name: &str,
+    rows: &[Row],
+    sender: &dyn Sender,
+) -> SystemResult<bool> {
+    match storage
+        .foreign_key_violations(session_id, schema_id, table_id, rows)?
+        .into_iter()
+        .flatten()
+        .next()
+    {
+        Some((foreign_key, key)) => {
+            sender
+                .send(Err(foreign_key_violation(storage, table_name, &foreign_key, &key)))
+                .expect("To Send Query Result to Client");
+            Ok(false)
+        }
+        None => Ok(true),
+    }
+}
+
+/// reports the first key of `removed` rows that is still referenced by rows of another table once they are
+/// replaced by `added` rows, `false` when the change violates a foreign key that references the table
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn check_referenced_keys(
+    storage: &DataManager,
+    session_id: SessionId,
+    schema_id: RecordId,
+    table_id: RecordId,
+    table_name: &str,
+    removed: &[Row],
+    added: &[Row],
+    sender: &dyn Sender,
+) -> SystemResult<bool> {
+    match storage.referenced_key_violation(session_id, schema_id, table_id, removed, added)? {
+        Some((referencing_schema_id, referencing_table_id, foreign_key, key)) => {
+            let referencing_table = storage
+                .table_name(referencing_schema_id, referencing_table_id)
+                .unwrap_or_default();
+            let error = QueryError::referenced_key_violation(
+                table_name.to_owned(),
+                foreign_key.name().to_owned(),
+                referencing_table.clone(),
+            )
+            .with_detail(format!(
+                "Key ({})=({}) is still referenced from table \"{}\".",
+                foreign_key.referenced_columns().join(", "),
+                key.join(", "),
+                referencing_table
+            ));
+            sender.send(Err(error)).expect("To Send Query Result to Client");
+            Ok(false)
+        }
+        None => Ok(true),
+    }
+}
+
+/// checks foreign keys that were deferred in the transaction against all rows of their tables,
+/// `false` when a violation was sent to the client
+pub(crate) fn check_deferred_foreign_keys(
+    storage: &DataManager,
+    session_id: SessionId,
+    sender: &dyn Sender,
+) -> SystemResult<bool> {
+    for (schema_id, table_id, foreign_key) in storage.deferred_foreign_keys(session_id) {
+        let rows = storage
+            .full_scan(session_id, schema_id, table_id)?
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        let missing = storage
+            .missing_references(schema_id, table_id, &foreign_key, &rows)?
+            .into_iter()
+            .flatten()
+            .next();
+        if let Some(key) = missing {
+            let table_name = storage.table_name(schema_id, table_id).unwrap_or_default();
+            sender
+                .send(Err(foreign_key_violation(storage, &table_name, &foreign_key, &key)))
+                .expect("To Send Query Result to Client");
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+pub(crate) fn foreign_key_violation(
+    storage: &DataManager,
+    table_name: &str,
+    foreign_key: &ForeignKeyDefinition,
+    key: &[String],
+) -> QueryError {
+    let (schema_id, table_id) = foreign_key.referenced_table();
+    QueryError::foreign_key_violation(table_name.to_owned(), foreign_key.name().to_owned()).with_detail(format!(
+        "Key ({})=({}) is not present in table \"{}\".",
+        foreign_key.columns().join(", "),
+        key.join(", "),
+        storage.table_name(schema_id, table_id).unwrap_or_default()
+    ))
+}
+
+/// computes values of generated columns from the other values of the row, violations of column
+/// constraints are reported
+pub(crate) fn generate_values(
+    evaluation: &mut ExpressionEvaluation,
+    generated: &[(usize, Expr)],
+    definitions: &[ColumnDefinition],
+    datums: &mut [Datum],
+    row_index: usize,
+    sender: &dyn Sender,
+) -> Result<(), ()> {
+    if generated.is_empty() {
+        return Ok(());
+    }
+    let columns: Vec<RelationColumn> = definitions
+        .iter()
+        .map(|column| RelationColumn::new(column.name(), (&column.sql_type()).into()))
+        .collect();
+    let row: Vec<ExprResult> = datums.iter().cloned().map(ExprResult::from).collect();
+    let scope = Scope::new(Frame::new(&columns, &row), None);
+    for (index, expr) in generated {
+        let value = Value::from(evaluation.eval_in(expr, &scope)?);
+        assign_value(datums, *index, value, &definitions[*index], row_index, sender)?;
+    }
+    Ok(())
+}
+
+/// stores default values of the `defaulted` columns, the columns without `DEFAULT` expression are NULL
+pub(crate) fn default_values(
+    evaluation: &mut ExpressionEvaluation,
+    defaults: &[(usize, Expr)],
+    defaulted: impl Fn(usize) -> bool,
+    definitions: &[ColumnDefinition],
+    datums: &mut [Datum],
+    row_index: usize,
+    sender: &dyn Sender,
+) -> Result<(), ()> {
+    for (index, expr) in defaults.iter().filter(|(index, _)| defaulted(*index)) {
+        let value = evaluation.eval(expr)?;
+        assign_value(datums, *index, value, &definitions[*index], row_index, sender)?;
+    }
+    Ok(())
+}
+
+/// the violation of the first column with NOT NULL constraint that has NULL value in the row
+pub(crate) fn not_null_violation(definitions: &[ColumnDefinition], datums: &[Datum]) -> Option<QueryError> {
+    definitions
+        .iter()
+        .zip(datums.iter())
+        .find(|(column, datum)| column.is_not_null() && **datum == Datum::from_null())
+        .map(|(column, _)| QueryError::not_null_violation(column.name()))
+}
+
+/// stores the value of the `index` column if it satisfies the column constraint, the violation
+/// is reported otherwise
+pub(crate) fn assign_value(
+    datums: &mut [Datum],
+    index: usize,
+    value: Value,
+    column_definition: &ColumnDefinition,
+    row_index: usize,
+    sender: &dyn Sender,
+) -> Result<(), ()> {
+    let value = fit_char(value, column_definition, row_index, sender);
+    let v = match &value {
+        Value::Number(v) => v.to_string(),
+        Value::SingleQuotedString(v) => v.to_string(),
+        Value::Boolean(v) => v.to_string(),
+        _ => {
+            datums[index] = Datum::from_null();
+            return Ok(());
+        }
+    };
+    match column_definition.sql_type().constraint().validate(v.as_str()) {
+        Ok(()) => {
+            datums[index] = column_datum(&value, &column_definition.sql_type());
+            Ok(())
+        }
+        Err(error) => {
+            sender
+                .send(Err(constraint_error(error, column_definition, row_index)))
+                .expect("To Send Query Result to Client");
+            Err(())
+        }
+    }
+}
+
+/// implicit conversion of the `value` assigned to a column of `sql_type`, as in PostgreSQL numbers are
+/// converted to their text form when they are stored into `char` or `varchar` columns, other values are
+/// kept as is and checked by the column constraint
+///
+/// the other implicit conversion is done by `||` operator that concatenates a number with a string,
+/// comparison and arithmetic operators never convert numbers to strings and report undefined function
+fn assignment_cast(value: Value, sql_type: &SqlType) -> Value {
+    match (sql_type, value) {
+        (SqlType::Char(_), Value::Number(number)) | (SqlType::VarChar(_), Value::Number(number)) => {
+            Value::SingleQuotedString(number.to_string())
+        }
+        (_, value) => value,
+    }
+}
+
+/// converts the `value` to the type of the column and truncates strings that are longer than `char` column
+pub(crate) fn fit_char(
+    value: Value,
+    column_definition: &ColumnDefinition,
+    row_index: usize,
+    sender: &dyn Sender,
+) -> Value {
+    let sql_type = column_definition.sql_type();
+    match (&sql_type, assignment_cast(value, &sql_type)) {
+        (SqlType::Char(len), Value::SingleQuotedString(s)) if s.trim_end().chars().count() > *len as usize => {
+            sender
+                .send(Ok(QueryEvent::Notice {
+                    severity: "NOTICE".to_owned(),
+                    message: format!(
+                        "value truncated to type {}({}) for column '{}' at row {}",
+                        Into::<PostgreSqlType>::into(&sql_type),
+                        len,
+                        column_definition.name(),
+                        row_index + 1
+                    ),
+                }))
+                .expect("To Send Query Result to Client");
+            Value::SingleQuotedString(s.chars().take(*len as usize).collect())
+        }
+        (_, value) => value,
+    }
+}
+
+/// whether the expression is `DEFAULT` keyword in place of a value of INSERT or UPDATE,
+/// it stands for the default value of the column or a value of a generated column
+pub(crate) fn is_default(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(Ident { value, quote_style: None }) if value.eq_ignore_ascii_case("default"))
+}
+
+/// whether the function is rewritten `IS DISTINCT FROM` predicate, `Some(true)` for `IS NOT DISTINCT FROM`
+pub(crate) fn distinct_predicate(name: &ObjectName) -> Option<bool> {
+    let name = name.to_string();
+    if name.eq_ignore_ascii_case("is_distinct_from") {
+        Some(false)
+    } else if name.eq_ignore_ascii_case("is_not_distinct_from") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// `trunc`, `floor` and `ceil` functions that round a number to an integer towards zero, down and up
+pub(crate) fn rounding_function(name: &ObjectName) -> Option<fn(&BigDecimal) -> BigDecimal> {
+    let name = name.to_string();
+    if name.eq_ignore_ascii_case("trunc") {
+        Some(trunc)
+    } else if name.eq_ignore_ascii_case("floor") {
+        Some(floor)
+    } else if name.eq_ignore_ascii_case("ceil") || name.eq_ignore_ascii_case("ceiling") {
+        Some(ceil)
+    } else {
+        None
+    }
+}
+
+fn trunc(number: &BigDecimal) -> BigDecimal {
+    number.with_scale(0)
+}
+
+fn floor(number: &BigDecimal) -> BigDecimal {
+    let truncated = trunc(number);
+    if &truncated > number {
+        truncated - BigDecimal::from(1)
+    } else {
+        truncated
+    }
+}
+
+fn ceil(number: &BigDecimal) -> BigDecimal {
+    let truncated = trunc(number);
+    if &truncated < number {
+        truncated + BigDecimal::from(1)
+    } else {
+        truncated
+    }
+}
+
+// rounds to the nearest integer, halves are rounded to the even one, e.g. 2.5 to 2 and 3.5 to 4
+fn round_half_even(number: &BigDecimal) -> BigDecimal {
+    let truncated = trunc(number);
+    let twice_fraction = (number - &truncated).abs().double();
+    let away_from_zero = match twice_fraction.cmp(&BigDecimal::from(1)) {
+        cmp::Ordering::Less => false,
+        cmp::Ordering::Greater => true,
+        cmp::Ordering::Equal => truncated.clone() % BigDecimal::from(2) != BigDecimal::from(0),
+    };
+    if !away_from_zero {
+        truncated
+    } else if number < &BigDecimal::from(0) {
+        truncated - BigDecimal::from(1)
+    } else {
+        truncated + BigDecimal::from(1)
+    }
+}
+
+// `ANY (ARRAY[...])`, `SOME (ARRAY[...])` or `ALL (ARRAY[...])` with the flag whether it is ALL
+fn quantified(expr: &Expr) -> Option<(bool, &[Expr])> {
+    let (name, args) = match expr {
+        Expr::Function(Function { name, args, .. }) => (name.to_string(), args),
+        _ => return None,
+    };
+    let all = if name.eq_ignore_ascii_case("all") {
+        true
+    } else if name.eq_ignore_ascii_case("any") || name.eq_ignore_ascii_case("some") {
+        false
+    } else {
+        return None;
+    };
+    match args.as_slice() {
+        [Expr::Function(Function { name, args, .. })] if name.to_string().eq_ignore_ascii_case("array") => {
+            Some((all, args.as_slice()))
+        }
+        _ => None,
+    }
+}
+
+fn compare<T: PartialOrd>(op: &BinaryOperator, left: &T, right: &T) -> Option<bool> {
+    match op {
+        BinaryOperator::Eq => Some(left == right),
+        BinaryOperator::NotEq => Some(left != right),
+        BinaryOperator::Lt => Some(left < right),
+        BinaryOperator::LtEq => Some(left <= right),
+        BinaryOperator::Gt => Some(left > right),
+        BinaryOperator::GtEq => Some(left >= right),
+        _ => None,
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum ExprResult {
     Number(BigDecimal),
     String(String),
+    Bool(bool),
+    Null,
+}
+
+impl ExprResult {
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(self, ExprResult::Null)
+    }
+
+    /// text representation that is sent to the client, `None` for NULL
+    pub(crate) fn to_text(&self) -> Option<String> {
+        match self {
+            ExprResult::Null => None,
+            value => Some(value.to_string()),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            ExprResult::Number(_) => "NUMBER",
+            ExprResult::String(_) => "STRING",
+            ExprResult::Bool(_) => "BOOL",
+            ExprResult::Null => "NULL",
+        }
+    }
+}
+
+impl From<Datum<'_>> for ExprResult {
+    fn from(datum: Datum) -> ExprResult {
+        match datum {
+            Datum::Null => ExprResult::Null,
+            Datum::True => ExprResult::Bool(true),
+            Datum::False => ExprResult::Bool(false),
+            Datum::Int16(v) => ExprResult::Number(BigDecimal::from(v)),
+            Datum::Int32(v) => ExprResult::Number(BigDecimal::from(v)),
+            Datum::Int64(v) => ExprResult::Number(BigDecimal::from(v)),
+            Datum::UInt64(v) => ExprResult::Number(BigDecimal::from(v)),
+            Datum::Float32(v) => BigDecimal::from_f32(v.into_inner()).map_or(ExprResult::Null, ExprResult::Number),
+            Datum::Float64(v) => BigDecimal::from_f64(v.into_inner()).map_or(ExprResult::Null, ExprResult::Number),
+            Datum::String(v) => ExprResult::String(v.to_owned()),
+            Datum::OwnedString(v) => ExprResult::String(v),
+            Datum::SqlType(v) => ExprResult::String(v.to_string()),
+        }
+    }
+}
+
+impl From<ExprResult> for Value {
+    fn from(result: ExprResult) -> Value {
+        match result {
+            ExprResult::Number(v) => Value::Number(v),
+            ExprResult::String(v) => Value::SingleQuotedString(v),
+            ExprResult::Bool(v) => Value::Boolean(v),
+            ExprResult::Null => Value::Null,
+        }
+    }
+}
+
+impl Display for ExprResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprResult::Number(v) => write!(f, "{}", v),
+            ExprResult::String(v) => write!(f, "{}", v),
+            ExprResult::Bool(true) => write!(f, "t"),
+            ExprResult::Bool(false) => write!(f, "f"),
+            ExprResult::Null => write!(f, "NULL"),
+        }
+    }
 }
@@ -0,0 +1,325 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dml::ExprResult;
+use protocol::sql_types::PostgreSqlType;
+use sqlparser::ast::{Expr, Ident};
+
+/// Column of an intermediate result set produced while a query is executed.
+/// `schema` and `table` are used to resolve qualified column references, e.g.
+/// `t1.id` or `schema_name.t1.id`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RelationColumn {
+    schema: Option<String>,
+    table: Option<String>,
+    name: String,
+    sql_type: PostgreSqlType,
+    type_modifier: i32,
+}
+
+impl RelationColumn {
+    pub(crate) fn new(name: String, sql_type: PostgreSqlType) -> RelationColumn {
+        RelationColumn {
+            schema: None,
+            table: None,
+            name,
+            sql_type,
+            type_modifier: -1,
+        }
+    }
+
+    pub(crate) fn qualified(
+        schema: Option<String>,
+        table: Option<String>,
+        name: String,
+        sql_type: PostgreSqlType,
+    ) -> RelationColumn {
+        RelationColumn {
+            schema,
+            table,
+            name,
+            sql_type,
+            type_modifier: -1,
+        }
+    }
+
+    /// declared length or precision of the column type, see `SqlType::type_modifier`
+    pub(crate) fn with_type_modifier(self, type_modifier: i32) -> RelationColumn {
+        RelationColumn { type_modifier, ..self }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub(crate) fn sql_type(&self) -> PostgreSqlType {
+        self.sql_type
+    }
+
+    pub(crate) fn type_modifier(&self) -> i32 {
+        self.type_modifier
+    }
+
+    /// whether the column is of the table referenced by `qualifier`, e.g. `t1` or `schema_name.t1`
+    pub(crate) fn belongs_to(&self, qualifier: &[Ident]) -> bool {
+        match qualifier {
+            [table] => self.table.as_ref() == Some(&table.value),
+            [schema, table] => self.schema.as_ref() == Some(&schema.value) && self.table.as_ref() == Some(&table.value),
+            _ => false,
+        }
+    }
+
+    fn matches(&self, idents: &[Ident]) -> bool {
+        match idents {
+            [name] => self.name == name.value,
+            [table, name] => self.table.as_ref() == Some(&table.value) && self.name == name.value,
+            [schema, table, name] => {
+                self.schema.as_ref() == Some(&schema.value)
+                    && self.table.as_ref() == Some(&table.value)
+                    && self.name == name.value
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Materialized rows together with their columns
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Relation {
+    pub(crate) columns: Vec<RelationColumn>,
+    pub(crate) rows: Vec<Vec<ExprResult>>,
+}
+
+impl Relation {
+    pub(crate) fn new(columns: Vec<RelationColumn>, rows: Vec<Vec<ExprResult>>) -> Relation {
+        Relation { columns, rows }
+    }
+}
+
+/// Columns and values of a single row that expressions are evaluated against
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Frame<'f> {
+    columns: &'f [RelationColumn],
+    values: &'f [ExprResult],
+}
+
+impl<'f> Frame<'f> {
+    pub(crate) fn new(columns: &'f [RelationColumn], values: &'f [ExprResult]) -> Frame<'f> {
+        Frame { columns, values }
+    }
+
+    /// frame that is used to check column references before any row is read
+    pub(crate) fn header(columns: &'f [RelationColumn]) -> Frame<'f> {
+        Frame { columns, values: &[] }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Resolution {
+    Found { outer: bool, index: usize },
+    Missing,
+    Ambiguous,
+}
+
+/// Visible columns for an expression. Column references are looked up in the
+/// current row first and then in the row of the enclosing query, which is how
+/// `LATERAL` subqueries see the `FROM` items to their left. Only one enclosing
+/// level is visible.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Scope<'s> {
+    current: Frame<'s>,
+    outer: Option<Frame<'s>>,
+}
+
+impl<'s> Scope<'s> {
+    pub(crate) fn new(current: Frame<'s>, outer: Option<Frame<'s>>) -> Scope<'s> {
+        Scope { current, outer }
+    }
+
+    pub(crate) fn resolve(&self, idents: &[Ident]) -> Resolution {
+        match Self::find(self.current.columns, idents) {
+            Resolution::Missing => match self.outer.map(|outer| Self::find(outer.columns, idents)) {
+                Some(Resolution::Found { index, .. }) => Resolution::Found { outer: true, index },
+                Some(resolution) => resolution,
+                None => Resolution::Missing,
+            },
+            resolution => resolution,
+        }
+    }
+
+    pub(crate) fn column(&self, outer: bool, index: usize) -> &'s RelationColumn {
+        match (outer, self.outer) {
+            (true, Some(frame)) => &frame.columns[index],
+            _ => &self.current.columns[index],
+        }
+    }
+
+    pub(crate) fn value(&self, outer: bool, index: usize) -> &'s ExprResult {
+        match (outer, self.outer) {
+            (true, Some(frame)) => &frame.values[index],
+            _ => &self.current.values[index],
+        }
+    }
+
+    /// Checks that every column referenced by `expr` can be resolved.
+    /// Returns names of the missing columns or an ambiguous reference.
+    pub(crate) fn check(&self, expr: &Expr) -> Result<(), ColumnReferenceError> {
+        let mut references = vec![];
+        column_references(expr, &mut references);
+        let mut missing = vec![];
+        for idents in references {
+            match self.resolve(&idents) {
+                Resolution::Found { .. } => {}
+                Resolution::Missing => missing.push(reference_name(&idents)),
+                Resolution::Ambiguous => return Err(ColumnReferenceError::Ambiguous(reference_name(&idents))),
+            }
+        }
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ColumnReferenceError::Missing(missing))
+        }
+    }
+
+    /// "did you mean" hint with visible columns whose names are the closest to names of the `missing`
+    /// column references, `None` when no column name is close enough
+    pub(crate) fn column_hint(&self, missing: &[String]) -> Option<String> {
+        let mut suggestions: Vec<&str> = vec![];
+        for reference in missing {
+            let name = reference.rsplit('.').next().unwrap_or_default();
+            for suggestion in self.closest_columns(name) {
+                if !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        match suggestions.as_slice() {
+            [] => None,
+            [column] => Some(format!("Perhaps you meant to reference the column \"{}\".", column)),
+            columns => Some(format!(
+                "Perhaps you meant to reference one of the columns {}.",
+                columns
+                    .iter()
+                    .map(|column| format!("\"{}\"", column))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    // names of columns with the least edit distance to the `name`, a name that differs in more than
+    // a half of its characters is not a suggestion
+    fn closest_columns(&self, name: &str) -> Vec<&'s str> {
+        let max_distance = name.chars().count() / 2;
+        let mut closest = vec![];
+        let mut min_distance = usize::MAX;
+        let columns = self
+            .current
+            .columns
+            .iter()
+            .chain(self.outer.map(|outer| outer.columns).unwrap_or_default());
+        for column in columns {
+            let distance = edit_distance(name, column.name());
+            if distance > max_distance || distance > min_distance {
+                continue;
+            }
+            if distance < min_distance {
+                min_distance = distance;
+                closest.clear();
+            }
+            if !closest.contains(&column.name()) {
+                closest.push(column.name());
+            }
+        }
+        closest
+    }
+
+    fn find(columns: &[RelationColumn], idents: &[Ident]) -> Resolution {
+        let mut found = None;
+        for (index, column) in columns.iter().enumerate() {
+            if column.matches(idents) {
+                if found.is_some() {
+                    return Resolution::Ambiguous;
+                }
+                found = Some(index);
+            }
+        }
+        match found {
+            Some(index) => Resolution::Found { outer: false, index },
+            None => Resolution::Missing,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ColumnReferenceError {
+    Missing(Vec<String>),
+    Ambiguous(String),
+}
+
+// Levenshtein distance, the number of inserted, deleted or substituted characters
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + if left_char == *right_char { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+pub(crate) fn reference_name(idents: &[Ident]) -> String {
+    idents
+        .iter()
+        .map(|ident| ident.value.as_str())
+        .collect::<Vec<&str>>()
+        .join(".")
+}
+
+fn column_references(expr: &Expr, references: &mut Vec<Vec<Ident>>) {
+    match expr {
+        Expr::Identifier(ident) => references.push(vec![ident.clone()]),
+        Expr::CompoundIdentifier(idents) => references.push(idents.clone()),
+        Expr::BinaryOp { left, right, .. } => {
+            column_references(left, references);
+            column_references(right, references);
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Cast { expr, .. } => column_references(expr, references),
+        Expr::Between { expr, low, high, .. } => {
+            column_references(expr, references);
+            column_references(low, references);
+            column_references(high, references);
+        }
+        Expr::InList { expr, list, .. } => {
+            column_references(expr, references);
+            for item in list {
+                column_references(item, references);
+            }
+        }
+        Expr::Function(function) => {
+            for arg in function.args.iter() {
+                column_references(arg, references);
+            }
+        }
+        _ => {}
+    }
+}
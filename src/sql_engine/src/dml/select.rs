@@ -12,266 +12,1948 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use data_manager::DataManager;
-use kernel::{Object, Operation, SystemError, SystemResult};
+use crate::{
+    cache::{CacheKey, QueryCache, ReadTable},
+    dml::{
+        catalog::CatalogTable,
+        distinct_predicate, equality_filter, excludes_partition,
+        explain::{selectivity, selectivity_of_common, Aggregation, Operation, Profile},
+        relation::{ColumnReferenceError, Frame, Relation, RelationColumn, Resolution, Scope},
+        rounding_function, ExprResult, ExpressionEvaluation,
+    },
+    query::{TableId, TableNamingError},
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use data_manager::{DataManager, LockMode, Privilege, RecordId, ReturnType, SessionId};
+use kernel::{SystemError, SystemResult};
 use protocol::{
-    results::{Description, QueryError, QueryEvent},
+    results::{Description, Projection, QueryError, QueryEvent},
+    sql_types::PostgreSqlType,
     Sender,
 };
-use sqlparser::ast::{Expr, Ident, Query, Select, SelectItem, SetExpr, TableFactor, TableWithJoins};
-use std::{ops::Deref, sync::Arc};
+use representation::unpack_raw;
+use sqlparser::ast::{
+    BinaryOperator, DataType, Expr, Function, Ident, Join, JoinConstraint, JoinOperator, ObjectName, OrderByExpr,
+    Query, Select, SelectItem, SetExpr, TableAlias, TableFactor, TableWithJoins, UnaryOperator, Value,
+};
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    convert::TryFrom,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 pub(crate) struct SelectCommand<'sc> {
     raw_sql_query: &'sc str,
     query: Box<Query>,
+    session_id: SessionId,
     storage: Arc<DataManager>,
     sender: Arc<dyn Sender>,
+    max_rows: Option<usize>,
+    cache: Option<Arc<QueryCache>>,
+    read_tables: RefCell<Vec<ReadTable>>,
+    profile: Option<Profile>,
+    deadline: Option<Instant>,
+}
+
+enum Failure {
+    // error was already sent to the client, message describes it for the caller
+    Reported(&'static str),
+    System(SystemError),
+}
+
+impl From<SystemError> for Failure {
+    fn from(error: SystemError) -> Failure {
+        Failure::System(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+}
+
+enum JoinCondition<'c> {
+    Always,
+    On(&'c Expr),
+    Natural,
+    Using(&'c [Ident]),
+}
+
+#[derive(Clone, Copy)]
+enum Projected<'p> {
+    Column(usize),
+    Expr(&'p Expr),
+}
+
+struct SortBy {
+    // index of a projected item
+    index: usize,
+    descending: bool,
+    nulls_first: bool,
 }
 
 impl<'sc> SelectCommand<'sc> {
     pub(crate) fn new(
         raw_sql_query: &'sc str,
         query: Box<Query>,
+        session_id: SessionId,
         storage: Arc<DataManager>,
         sender: Arc<dyn Sender>,
+        max_rows: Option<usize>,
     ) -> SelectCommand<'sc> {
         SelectCommand {
             raw_sql_query,
             query,
+            session_id,
             storage,
             sender,
+            max_rows,
+            cache: None,
+            read_tables: RefCell::default(),
+            profile: None,
+            deadline: None,
         }
     }
 
+    pub(crate) fn with_cache(mut self, cache: Option<Arc<QueryCache>>) -> SelectCommand<'sc> {
+        self.cache = cache;
+        self
+    }
+
+    /// the query is canceled when it runs longer than the `timeout`, the time is counted from now
+    pub(crate) fn with_timeout(mut self, timeout: Option<Duration>) -> SelectCommand<'sc> {
+        self.deadline = timeout.map(|timeout| Instant::now() + timeout);
+        self
+    }
+
     pub(crate) fn describe(&mut self) -> SystemResult<Description> {
-        let input = self.parse_select_input()?;
-
-        match self.storage.table_exists(&input.schema_name, &input.table_name) {
-            None => {
-                self.sender
-                    .send(Err(QueryError::schema_does_not_exist(input.schema_name.clone())))
-                    .expect("To Send Result to Client");
-                Err(SystemError::bug_in_sql_engine(
-                    Operation::Access,
-                    Object::Schema(&input.schema_name),
-                ))
+        match self.query_columns(&self.query, None) {
+            Ok(columns) => Ok(columns
+                .into_iter()
+                .map(|column| (column.name().to_owned(), column.sql_type(), column.type_modifier()))
+                .collect()),
+            Err(Failure::Reported(message)) => Err(SystemError::runtime_check_failure(message.to_owned())),
+            Err(Failure::System(error)) => Err(error),
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let cache = match &self.cache {
+            // result limited by a session setting is not shared with other sessions
+            Some(cache) if self.max_rows.is_none() && is_cacheable(&self.query) => Some((
+                cache.clone(),
+                CacheKey::new(self.query.to_string(), self.storage.schema_version()),
+            )),
+            _ => None,
+        };
+        if let Some((cache, key)) = &cache {
+            if let Some(cached) = cache.lookup(key) {
+                if self.can_read(&cached.tables) {
+                    if self.is_up_to_date(&cached.tables) {
+                        cache.hit();
+                        self.sender
+                            .send(Ok(QueryEvent::RecordsSelected(cached.projection)))
+                            .expect("To Send Query Result to Client");
+                        return Ok(());
+                    }
+                    cache.invalidate(key, &cached);
+                }
             }
-            Some((_, None)) => {
-                self.sender
-                    .send(Err(QueryError::table_does_not_exist(
-                        input.schema_name.clone() + "." + input.table_name.as_str(),
-                    )))
-                    .expect("To Send Result to Client");
-                Err(SystemError::bug_in_sql_engine(
-                    Operation::Access,
-                    Object::Table(&input.schema_name, &input.table_name),
-                ))
+        }
+
+        // rows of a cached result have to be collected, as well as the ones that are limited by a session setting
+        if cache.is_none() && self.max_rows.is_none() {
+            match self.stream() {
+                Ok(false) => {}
+                Ok(true) | Err(Failure::Reported(_)) => return Ok(()),
+                Err(Failure::System(error)) => return Err(error),
             }
-            Some((schema_id, Some(table_id))) => {
-                let all_columns = self.storage.table_columns(schema_id, table_id)?;
-                let mut column_definitions = vec![];
-                let mut non_existing_columns = vec![];
-                for column_name in &input.selected_columns {
-                    let mut found = None;
-                    for column_definition in &all_columns {
-                        if column_definition.has_name(&column_name) {
-                            found = Some(column_definition);
-                            break;
-                        }
-                    }
+        }
+
+        let relation = match self.run_query(&self.query, None, self.max_rows) {
+            Ok(relation) => relation,
+            Err(Failure::Reported(_)) => return Ok(()),
+            Err(Failure::System(error)) => return Err(error),
+        };
+
+        let projection = projection(&relation);
+        if let Some((cache, key)) = cache {
+            let tables = self.read_tables.take();
+            if self.is_shared(&tables) {
+                cache.put(key, tables, projection.clone());
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::RecordsSelected(projection)))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+
+    // rows of a read of table columns, e.g. `SELECT a, b FROM t LIMIT 10`, are sent to the client as they are
+    // read from the storage, `false` when the query has to be run as a whole
+    fn stream(&self) -> Result<bool, Failure> {
+        let select = match streamed(&self.query) {
+            Some(select) => select,
+            None => return Ok(false),
+        };
+        let (name, alias) = match select.from.as_slice() {
+            [TableWithJoins {
+                relation: TableFactor::Table { name, alias, args, .. },
+                joins,
+            }] if args.is_empty() && joins.is_empty() && CatalogTable::resolve(name).is_none() => (name, alias),
+            _ => return Ok(false),
+        };
+        let (schema_id, table_id, columns) = self.table(name)?;
+        if self.storage.partition_key(schema_id, table_id).is_some() {
+            return Ok(false);
+        }
+        let columns = qualify(columns, alias);
+        let (projected, items) = self.projection(&select.projection, &columns, None)?;
+        let scope = Scope::new(Frame::header(&columns), None);
+        let indexes = match items
+            .iter()
+            .map(|item| match item {
+                Projected::Column(index) => Some(*index),
+                Projected::Expr(expr) => local_column(&scope, expr),
+            })
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(indexes) => indexes,
+            None => return Ok(false),
+        };
+        let limit = match &self.query.limit {
+            Some(limit) => self.row_count(limit, "LIMIT", QueryError::invalid_row_count_in_limit_clause())?,
+            None => None,
+        };
+        let table_columns = self.storage.table_columns(schema_id, table_id)?;
+        let names = indexes
+            .iter()
+            .map(|index| table_columns[*index].name())
+            .collect::<Vec<_>>();
+        let table = match TableId::try_from(name.clone()) {
+            Ok(table) => table,
+            Err(TableNamingError(error)) => return Err(self.report(error, "Table Naming Error")),
+        };
+        let description = projected
+            .iter()
+            .map(|column| (column.name().to_owned(), column.sql_type(), column.type_modifier()))
+            .collect();
+        let mut failure = None;
+        let mut rows = self
+            .storage
+            .select_scan(self.session_id, table.schema_name(), table.name(), &names)?
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|row| match (self.interrupt(), row) {
+                (Some(error), _) => Err(error),
+                (None, Ok(row)) => Ok(row),
+                (None, Err(error)) => {
+                    let message = format!("could not read table \"{}\": {}", table.name(), error.message());
+                    failure = Some(error);
+                    Err(QueryError::io_error(message))
+                }
+            });
+        self.sender
+            .send_rows(description, &mut rows)
+            .expect("To Send Query Result to Client");
+        drop(rows);
+        match failure {
+            Some(error) => Err(Failure::System(error)),
+            None => Ok(true),
+        }
+    }
+
+    // runs the query without sending its rows to the client, `None` when an error was sent instead
+    pub(crate) fn collect(&mut self) -> SystemResult<Option<Projection>> {
+        match self.run_query(&self.query, None, self.max_rows) {
+            Ok(relation) => Ok(Some(projection(&relation))),
+            Err(Failure::Reported(_)) => Ok(None),
+            Err(Failure::System(error)) => Err(error),
+        }
+    }
+
+    // privileges are checked before cached result is reused, otherwise the query reports the error
+    fn can_read(&self, tables: &[ReadTable]) -> bool {
+        tables.iter().all(|table| {
+            self.storage
+                .has_privilege(self.session_id, table.schema_id, table.table_id, Privilege::Select)
+        })
+    }
+
+    // the same tables are locked as if the query was run, none of them could be changed, replaced
+    // or shadowed by a temporary table since the result was cached
+    fn is_up_to_date(&self, tables: &[ReadTable]) -> bool {
+        tables.iter().all(|table| {
+            self.storage
+                .lock_table(self.session_id, table.schema_id, table.table_id, LockMode::AccessShare);
+            self.storage
+                .session_table_exists(self.session_id, &table.schema_name, &table.table_name)
+                == Some((table.schema_id, Some(table.table_id)))
+                && self.storage.table_version(table.schema_id, table.table_id) == table.version
+        }) && self.is_shared(tables)
+    }
+
+    // rows read by a snapshot that doesn't see some changes of the tables are not the same for all sessions
+    fn is_shared(&self, tables: &[ReadTable]) -> bool {
+        tables
+            .iter()
+            .all(|table| self.storage.is_latest(table.schema_id, table.table_id))
+    }
+
+    // runs the query to check names and types of its expressions, rows are not sent to the client
+    pub(crate) fn validate(&mut self) -> SystemResult<()> {
+        match self.run_query(&self.query, None, None) {
+            Ok(_) | Err(Failure::Reported(_)) => Ok(()),
+            Err(Failure::System(error)) => Err(error),
+        }
+    }
+
+    // runs the query recording rows and time of its plan nodes, `None` when an error was sent to the client
+    pub(crate) fn analyze(mut self) -> SystemResult<Option<Profile>> {
+        self.profile = Some(Profile::default());
+        match self.run_query(&self.query, None, None) {
+            Ok(_) => Ok(self.profile.take()),
+            Err(Failure::Reported(_)) => Ok(None),
+            Err(Failure::System(error)) => Err(error),
+        }
+    }
+
+    // rows of FROM clause before they are filtered and projected
+    pub(crate) fn source(&mut self) -> SystemResult<Option<Relation>> {
+        match self.select(&self.query).and_then(|select| self.run_from(&select.from)) {
+            Ok(relation) => Ok(Some(relation)),
+            Err(Failure::Reported(_)) => Ok(None),
+            Err(Failure::System(error)) => Err(error),
+        }
+    }
+
+    // `max_rows` limits only rows of the result, rows of subqueries are not counted
+    fn run_query(&self, query: &Query, outer: Option<Frame>, max_rows: Option<usize>) -> Result<Relation, Failure> {
+        let node = self.enter(|| "Select".to_owned());
+        let relation = self.run_select(query, outer, max_rows)?;
+        self.leave(node, &relation);
+        Ok(relation)
+    }
+
+    fn run_select(&self, query: &Query, outer: Option<Frame>, max_rows: Option<usize>) -> Result<Relation, Failure> {
+        let select = self.select(query)?;
+        let offset = match &query.offset {
+            Some(offset) => self.row_count(
+                &offset.value,
+                "OFFSET",
+                QueryError::invalid_row_count_in_result_offset_clause(),
+            )?,
+            None => None,
+        };
+        let limit = match &query.limit {
+            Some(limit) => self.row_count(limit, "LIMIT", QueryError::invalid_row_count_in_limit_clause())?,
+            None => None,
+        };
+        self.operation(|| Operation::Select {
+            selectivity: select.selection.as_ref().map_or(1.0, selectivity),
+            aggregation: match (is_aggregation(select), select.group_by.is_empty()) {
+                (false, _) => Aggregation::None,
+                (true, true) => Aggregation::All,
+                (true, false) => Aggregation::Grouped,
+            },
+            limit,
+        });
+        let source = match select.from.as_slice() {
+            [TableWithJoins {
+                relation: TableFactor::Table { name, alias, args, .. },
+                joins,
+            }] if args.is_empty() && joins.is_empty() => self.scan_table(
+                name,
+                alias,
+                select.selection.as_ref().map(|predicate| (predicate, outer)),
+            )?,
+            from => self.run_from(from)?,
+        };
 
-                    if let Some(column_definition) = found {
-                        column_definitions.push(column_definition);
+        let rows = match &select.selection {
+            None => source.rows,
+            Some(predicate) => {
+                self.check_columns(predicate, &Scope::new(Frame::header(&source.columns), outer))?;
+                let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+                    .with_functions(&self.storage)
+                    .with_session(self.session_id, self.storage.clone());
+                let mut rows = vec![];
+                let mut removed = 0;
+                for row in source.rows {
+                    self.check_interrupts()?;
+                    let scope = Scope::new(Frame::new(&source.columns, &row), outer);
+                    if self.satisfies(&mut evaluation, predicate, &scope)? {
+                        rows.push(row);
                     } else {
-                        non_existing_columns.push(column_name.clone());
+                        removed += 1;
                     }
                 }
-
-                if !non_existing_columns.is_empty() {
-                    self.sender
-                        .send(Err(QueryError::column_does_not_exist(non_existing_columns)))
-                        .expect("To Send Result to Client");
-                    return Err(SystemError::runtime_check_failure("Column Does Not Exist".to_owned()));
+                if let Some(profile) = &self.profile {
+                    profile.filter(predicate, removed);
+                }
+                rows
+            }
+        };
+        let (columns, mut items) = self.projection(&select.projection, &source.columns, outer)?;
+        let width = items.len();
+        let group_by = self.group_by(&select.group_by, &items)?;
+        let sort_by = self.sort_by(
+            &query.order_by,
+            &columns,
+            &mut items,
+            &Scope::new(Frame::header(&source.columns), outer),
+        )?;
+        if is_aggregation(select) || !sort_by.is_empty() {
+            let mut rows = if is_aggregation(select) {
+                self.aggregate(&group_by, &items, &source.columns, rows, outer)?
+            } else {
+                let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+                    .with_functions(&self.storage)
+                    .with_session(self.session_id, self.storage.clone());
+                let mut projected_rows = vec![];
+                for row in rows {
+                    self.check_interrupts()?;
+                    let scope = Scope::new(Frame::new(&source.columns, &row), outer);
+                    projected_rows.push(self.project(&mut evaluation, &items, &row, &scope)?);
                 }
+                projected_rows
+            };
+            rows.sort_by(|left, right| compare_rows(&sort_by, left, right));
+            let rows = rows
+                .into_iter()
+                .skip(offset.unwrap_or(0))
+                .take(limit.unwrap_or(usize::MAX))
+                .map(|mut row| {
+                    row.truncate(width);
+                    row
+                })
+                .collect::<Vec<_>>();
+            if let Some(max_rows) = max_rows {
+                if rows.len() > max_rows {
+                    return Err(self.max_rows_exceeded(max_rows));
+                }
+            }
+            return Ok(Relation::new(columns, rows));
+        }
 
-                let description = column_definitions
-                    .into_iter()
-                    .map(|column_definition| (column_definition.name(), (&column_definition.sql_type()).into()))
-                    .collect();
+        let rows = rows
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX));
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let mut projected_rows = vec![];
+        for row in rows {
+            if Some(projected_rows.len()) == max_rows {
+                return Err(self.max_rows_exceeded(projected_rows.len()));
+            }
+            self.check_interrupts()?;
+            let scope = Scope::new(Frame::new(&source.columns, &row), outer);
+            projected_rows.push(self.project(&mut evaluation, &items, &row, &scope)?);
+        }
+        // the query could cancel itself while its rows are projected
+        self.check_interrupts()?;
+
+        Ok(Relation::new(columns, projected_rows))
+    }
 
-                Ok(description)
+    fn project(
+        &self,
+        evaluation: &mut ExpressionEvaluation,
+        items: &[Projected],
+        row: &[ExprResult],
+        scope: &Scope,
+    ) -> Result<Vec<ExprResult>, Failure> {
+        let mut projected = vec![];
+        for item in items.iter() {
+            match item {
+                Projected::Column(index) => projected.push(row[*index].clone()),
+                Projected::Expr(expr) => match evaluation.eval_in(expr, scope) {
+                    Ok(value) => projected.push(value),
+                    Err(()) => return Err(Failure::Reported("Expression Evaluation Failure")),
+                },
             }
         }
+        Ok(projected)
     }
 
-    pub(crate) fn execute(&mut self) -> SystemResult<()> {
-        let input = match self.parse_select_input() {
-            Ok(input) => input,
-            Err(_) => return Ok(()),
-        };
+    // ORDER BY item is an output column name, a 1-based position in the select list or
+    // an expression; expressions that are not in the select list are appended to `items`
+    // and have to be truncated from the result rows after sorting
+    fn sort_by<'q>(
+        &self,
+        order_by: &'q [OrderByExpr],
+        columns: &[RelationColumn],
+        items: &mut Vec<Projected<'q>>,
+        scope: &Scope,
+    ) -> Result<Vec<SortBy>, Failure> {
+        let mut sort_by = vec![];
+        for OrderByExpr { expr, asc, nulls_first } in order_by {
+            let index = match expr {
+                Expr::Value(Value::Number(position)) if position.is_integer() => match position.to_usize() {
+                    Some(position) if position >= 1 && position <= columns.len() => position - 1,
+                    _ => {
+                        return Err(self.report(
+                            QueryError::invalid_column_reference(format!(
+                                "ORDER BY position {} is not in select list",
+                                position
+                            )),
+                            "Invalid Column Reference",
+                        ))
+                    }
+                },
+                _ => match output_column(columns, items, expr) {
+                    Some(index) => index,
+                    None => {
+                        self.check_columns(expr, scope)?;
+                        items.push(Projected::Expr(expr));
+                        items.len() - 1
+                    }
+                },
+            };
+            let descending = *asc == Some(false);
+            sort_by.push(SortBy {
+                index,
+                descending,
+                nulls_first: nulls_first.unwrap_or(descending),
+            });
+        }
+        Ok(sort_by)
+    }
 
-        match self.storage.table_exists(&input.schema_name, &input.table_name) {
-            None => {
-                self.sender
-                    .send(Err(QueryError::schema_does_not_exist(input.schema_name)))
-                    .expect("To Send Result to Client");
-                Err(SystemError::runtime_check_failure("Schema Does Not Exist".to_owned()))
-            }
-            Some((_, None)) => {
-                self.sender
-                    .send(Err(QueryError::table_does_not_exist(
-                        input.schema_name + "." + input.table_name.as_str(),
-                    )))
-                    .expect("To Send Result to Client");
-                Err(SystemError::runtime_check_failure("Table Does Not Exist".to_owned()))
-            }
-            Some((schema_id, Some(table_id))) => match self.storage.full_scan(schema_id, table_id) {
-                Err(error) => Err(error),
-                Ok(records) => {
-                    let all_columns = self.storage.table_columns(schema_id, table_id)?;
-                    let mut description = vec![];
-                    let mut column_indexes = vec![];
-                    let mut non_existing_columns = vec![];
-                    for column_name in input.selected_columns.iter() {
-                        let mut found = None;
-                        for (index, column_definition) in all_columns.iter().enumerate() {
-                            if column_definition.has_name(column_name) {
-                                found = Some((index, column_definition.clone()));
-                                break;
-                            }
-                        }
+    // GROUP BY item is an expression or a 1-based position in the select list
+    fn group_by<'q>(&self, group_by: &'q [Expr], items: &[Projected<'q>]) -> Result<Vec<Projected<'q>>, Failure> {
+        let mut grouped = vec![];
+        for expr in group_by {
+            match expr {
+                Expr::Value(Value::Number(position)) if position.is_integer() => match position.to_usize() {
+                    Some(position) if position >= 1 && position <= items.len() => grouped.push(items[position - 1]),
+                    _ => {
+                        return Err(self.report(
+                            QueryError::invalid_column_reference(format!(
+                                "GROUP BY position {} is not in select list",
+                                position
+                            )),
+                            "Invalid Column Reference",
+                        ))
+                    }
+                },
+                _ => grouped.push(Projected::Expr(expr)),
+            }
+        }
+        Ok(grouped)
+    }
 
-                        if let Some((index, column_definition)) = found {
-                            column_indexes.push(index);
-                            description.push(column_definition);
-                        } else {
-                            non_existing_columns.push(column_name.clone());
+    // rows are split into groups by values of GROUP BY expressions and every group produces
+    // a single row, without GROUP BY all rows form one group
+    fn aggregate(
+        &self,
+        group_by: &[Projected],
+        items: &[Projected],
+        columns: &[RelationColumn],
+        rows: Vec<Vec<ExprResult>>,
+        outer: Option<Frame>,
+    ) -> Result<Vec<Vec<ExprResult>>, Failure> {
+        let header = Scope::new(Frame::header(columns), outer);
+        let mut grouped = vec![];
+        for item in group_by {
+            match item {
+                Projected::Column(index) => grouped.push(*index),
+                Projected::Expr(expr) => {
+                    self.check_columns(expr, &header)?;
+                    if let Some(index) = local_column(&header, expr) {
+                        grouped.push(index);
+                    }
+                }
+            }
+        }
+        for item in items {
+            let ungrouped = match item {
+                Projected::Column(index) if !grouped.contains(index) => Some(columns[*index].name().to_owned()),
+                Projected::Column(_) => None,
+                Projected::Expr(expr) => {
+                    let is_grouped = aggregate(expr).is_some()
+                        || group_by
+                            .iter()
+                            .any(|item| matches!(item, Projected::Expr(grouped) if grouped == expr))
+                        || match local_column(&header, expr) {
+                            Some(index) => grouped.contains(&index),
+                            None => Scope::new(Frame::header(&[]), None).check(expr).is_ok(),
+                        };
+                    if is_grouped {
+                        None
+                    } else {
+                        Some(expr.to_string())
+                    }
+                }
+            };
+            if let Some(column) = ungrouped {
+                return Err(self.report(QueryError::grouping_error(column), "Grouping Error"));
+            }
+        }
+
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let mut groups: Vec<(Vec<ExprResult>, Vec<Vec<ExprResult>>)> = vec![];
+        for row in rows {
+            let scope = Scope::new(Frame::new(columns, &row), outer);
+            let mut key = vec![];
+            for item in group_by {
+                match item {
+                    Projected::Column(index) => key.push(row[*index].clone()),
+                    Projected::Expr(expr) => match evaluation.eval_in(expr, &scope) {
+                        Ok(value) => key.push(value),
+                        Err(()) => return Err(Failure::Reported("Expression Evaluation Failure")),
+                    },
+                }
+            }
+            match groups.iter_mut().find(|(group, _)| *group == key) {
+                Some((_, group_rows)) => group_rows.push(row),
+                None => groups.push((key, vec![row])),
+            }
+        }
+        if groups.is_empty() && group_by.is_empty() {
+            groups.push((vec![], vec![]));
+        }
+
+        let mut aggregated = vec![];
+        for (_, group_rows) in groups {
+            let first = match group_rows.first() {
+                Some(row) => row.clone(),
+                None => vec![ExprResult::Null; columns.len()],
+            };
+            let mut result = vec![];
+            for item in items {
+                match item {
+                    Projected::Column(index) => result.push(first[*index].clone()),
+                    Projected::Expr(expr) => {
+                        let value = match aggregate(expr) {
+                            Some(aggregate) => {
+                                self.accumulate(&mut evaluation, &aggregate, columns, &group_rows, outer)
+                            }
+                            None => evaluation.eval_in(expr, &Scope::new(Frame::new(columns, &first), outer)),
+                        };
+                        match value {
+                            Ok(value) => result.push(value),
+                            Err(()) => return Err(Failure::Reported("Expression Evaluation Failure")),
                         }
                     }
+                }
+            }
+            aggregated.push(result);
+        }
+        Ok(aggregated)
+    }
 
-                    if !non_existing_columns.is_empty() {
-                        self.sender
-                            .send(Err(QueryError::column_does_not_exist(non_existing_columns)))
-                            .expect("To Send Result to Client");
-                        return Ok(());
+    // NULL values are not counted, DISTINCT counts equal values only once
+    fn accumulate(
+        &self,
+        evaluation: &mut ExpressionEvaluation,
+        aggregate: &Aggregate,
+        columns: &[RelationColumn],
+        rows: &[Vec<ExprResult>],
+        outer: Option<Frame>,
+    ) -> Result<ExprResult, ()> {
+        let count = match aggregate {
+            Aggregate::CountRows => rows.len(),
+            Aggregate::Count { arg, distinct } => {
+                let mut counted: Vec<ExprResult> = vec![];
+                let mut count = 0;
+                for row in rows {
+                    let value = evaluation.eval_in(arg, &Scope::new(Frame::new(columns, row), outer))?;
+                    if value.is_null() || (*distinct && counted.contains(&value)) {
+                        continue;
+                    }
+                    if *distinct {
+                        counted.push(value);
                     }
+                    count += 1;
+                }
+                count
+            }
+        };
+        Ok(ExprResult::Number(BigDecimal::from(count as u64)))
+    }
 
-                    let values: Vec<Vec<String>> = records
-                        .map(Result::unwrap)
-                        .map(Result::unwrap)
-                        .map(|(_key, values)| {
-                            let row: Vec<String> = values.unpack().into_iter().map(|datum| datum.to_string()).collect();
-
-                            let mut values = vec![];
-                            for origin in column_indexes.iter() {
-                                for (index, value) in row.iter().enumerate() {
-                                    if index == *origin {
-                                        values.push(value.clone())
-                                    }
-                                }
-                            }
-                            log::debug!("{:#?}", values);
-                            values
-                        })
-                        .collect();
-
-                    let projection = (
-                        description
-                            .into_iter()
-                            .map(|column| (column.name(), (&column.sql_type()).into()))
-                            .collect(),
-                        values,
+    fn run_from(&self, from: &[TableWithJoins]) -> Result<Relation, Failure> {
+        // every FROM item after the first one is joined with the result of preceding items
+        let mut nodes = from
+            .iter()
+            .skip(1)
+            .map(|_| self.enter(|| "Nested Loop".to_owned()))
+            .collect::<Vec<_>>();
+        let mut result: Option<Relation> = None;
+        for table_with_joins in from {
+            let joined = result.is_some();
+            result = Some(match result.take() {
+                None => self.run_table_with_joins(table_with_joins, None)?,
+                // LATERAL item sees FROM items that precede it
+                Some(preceding) if is_lateral(&table_with_joins.relation) => {
+                    self.run_table_with_joins(table_with_joins, Some(preceding))?
+                }
+                Some(preceding) => {
+                    let relation = self.run_table_with_joins(table_with_joins, None)?;
+                    self.cartesian_product(preceding, relation)?
+                }
+            });
+            if joined {
+                self.operation(|| Operation::Join { selectivity: 1.0 });
+                if let Some(relation) = &result {
+                    self.leave(nodes.pop().flatten(), relation);
+                }
+            }
+        }
+        match result {
+            Some(relation) => Ok(relation),
+            // projection of a query without FROM clause is evaluated once
+            None => Ok(Relation::new(vec![], vec![vec![]])),
+        }
+    }
+
+    fn run_table_with_joins(
+        &self,
+        table_with_joins: &TableWithJoins,
+        preceding: Option<Relation>,
+    ) -> Result<Relation, Failure> {
+        let TableWithJoins { relation, joins } = table_with_joins;
+        // the last join is the root of joined relations
+        let mut nodes = joins
+            .iter()
+            .map(|join| self.enter(|| join_name(&join.join_operator)))
+            .collect::<Vec<_>>();
+        let mut result = match preceding {
+            Some(preceding) => self.join(preceding, relation, JoinKind::Inner, JoinCondition::Always)?,
+            None => self.run_factor(relation)?,
+        };
+        for join in joins {
+            let (kind, condition) = match &join.join_operator {
+                JoinOperator::Inner(constraint) => (JoinKind::Inner, join_condition(constraint)),
+                JoinOperator::LeftOuter(constraint) => (JoinKind::Left, join_condition(constraint)),
+                JoinOperator::RightOuter(constraint) => (JoinKind::Right, join_condition(constraint)),
+                JoinOperator::FullOuter(constraint) => (JoinKind::Full, join_condition(constraint)),
+                JoinOperator::CrossJoin => (JoinKind::Inner, JoinCondition::Always),
+                _ => return Err(self.feature_not_supported()),
+            };
+            result = self.join(result, &join.relation, kind, condition)?;
+            self.leave(nodes.pop().flatten(), &result);
+        }
+        Ok(result)
+    }
+
+    fn run_factor(&self, factor: &TableFactor) -> Result<Relation, Failure> {
+        match factor {
+            TableFactor::Table { name, alias, args, .. } if is_generate_series(name, args) => {
+                let node = self.enter(|| "Function Scan on generate_series".to_owned());
+                let rows = self
+                    .generate_series(args)?
+                    .into_iter()
+                    .map(|value| vec![ExprResult::Number(BigDecimal::from(value))])
+                    .collect::<Vec<_>>();
+                self.operation(|| Operation::Function { rows: rows.len() });
+                let relation = Relation::new(qualify(series_columns(), alias), rows);
+                self.leave(node, &relation);
+                Ok(relation)
+            }
+            TableFactor::Table { name, alias, args, .. } if args.is_empty() => self.scan_table(name, alias, None),
+            TableFactor::Derived { subquery, alias, .. } => {
+                let relation = self.run_query(subquery, None, None)?;
+                Ok(Relation::new(qualify(relation.columns, alias), relation.rows))
+            }
+            TableFactor::NestedJoin(table_with_joins) => self.run_table_with_joins(table_with_joins, None),
+            _ => Err(self.feature_not_supported()),
+        }
+    }
+
+    // reads the table skipping rows that could not satisfy the `filter` predicate
+    fn scan_table(
+        &self,
+        name: &ObjectName,
+        alias: &Option<TableAlias>,
+        filter: Option<(&Expr, Option<Frame>)>,
+    ) -> Result<Relation, Failure> {
+        if let Some(catalog_table) = CatalogTable::resolve(name) {
+            let node = self.enter(|| format!("Seq Scan on {}", name));
+            let relation = Relation::new(
+                qualify(catalog_table.columns(), alias),
+                catalog_table.rows(&self.storage)?,
+            );
+            self.operation(|| Operation::Scan {
+                stored: relation.rows.len(),
+            });
+            self.leave(node, &relation);
+            return Ok(relation);
+        }
+        let (schema_id, table_id, columns) = self.table(name)?;
+        let columns = qualify(columns, alias);
+        if let Some(key) = self.storage.partition_key(schema_id, table_id) {
+            return self.scan_partitions(name, schema_id, table_id, key, columns, filter);
+        }
+        let pushed_down = match filter {
+            Some((predicate, outer)) => equality_filter(
+                predicate,
+                &Scope::new(Frame::header(&columns), outer),
+                &self.storage.table_columns(schema_id, table_id)?,
+            ),
+            None => None,
+        };
+        // rows that are skipped by pushed down predicate are counted before the table is read
+        let stored = match (&self.profile, &pushed_down) {
+            (Some(_), Some(_)) => Some(self.storage.full_scan(self.session_id, schema_id, table_id)?.count()),
+            _ => None,
+        };
+        let node = self.enter(|| format!("Seq Scan on {}", name));
+        let cursor = match pushed_down {
+            Some((column, value)) => self
+                .storage
+                .scan_where(self.session_id, schema_id, table_id, column, &value)?,
+            None => self.storage.full_scan(self.session_id, schema_id, table_id)?,
+        };
+        let rows = cursor
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .map(|(_key, values)| {
+                unpack_raw(values.to_bytes())
+                    .into_iter()
+                    .map(ExprResult::from)
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        self.check_interrupts()?;
+        let stored = stored.unwrap_or(rows.len());
+        self.operation(|| Operation::Scan { stored });
+        if let (Some(profile), Some((predicate, _outer))) = (&self.profile, filter) {
+            if stored > rows.len() {
+                profile.filter(predicate, stored - rows.len());
+            }
+        }
+        let relation = Relation::new(columns, rows);
+        self.leave(node, &relation);
+        Ok(relation)
+    }
+
+    // partitioned table keeps no rows itself, rows of its partitions are appended skipping partitions
+    // which range can't contain rows that satisfy the `filter` predicate
+    fn scan_partitions(
+        &self,
+        name: &ObjectName,
+        schema_id: RecordId,
+        table_id: RecordId,
+        key: usize,
+        columns: Vec<RelationColumn>,
+        filter: Option<(&Expr, Option<Frame>)>,
+    ) -> Result<Relation, Failure> {
+        let definitions = self.storage.table_columns(schema_id, table_id)?;
+        let node = self.enter(|| format!("Append on {}", name));
+        let mut rows = vec![];
+        for partition in self.storage.partitions(schema_id, table_id) {
+            if let Some((predicate, outer)) = filter {
+                if excludes_partition(
+                    predicate,
+                    &Scope::new(Frame::header(&columns), outer),
+                    &definitions,
+                    key,
+                    &partition,
+                ) {
+                    continue;
+                }
+            }
+            let (partition_schema_id, partition_table_id) = partition.table();
+            let (schema_name, table_name) = match (
+                self.storage.schema_name(partition_schema_id),
+                self.storage.table_name(partition_schema_id, partition_table_id),
+            ) {
+                (Some(schema_name), Some(table_name)) => (schema_name, table_name),
+                _ => continue,
+            };
+            self.storage.lock_table(
+                self.session_id,
+                partition_schema_id,
+                partition_table_id,
+                LockMode::AccessShare,
+            );
+            self.read_tables.borrow_mut().push(ReadTable {
+                schema_name: schema_name.clone(),
+                table_name: table_name.clone(),
+                schema_id: partition_schema_id,
+                table_id: partition_table_id,
+                version: self.storage.table_version(partition_schema_id, partition_table_id),
+            });
+            let partition_node = self.enter(|| format!("Seq Scan on {}.{}", schema_name, table_name));
+            let partition_rows = self
+                .storage
+                .full_scan(self.session_id, partition_schema_id, partition_table_id)?
+                .map(Result::unwrap)
+                .map(Result::unwrap)
+                .map(|(_key, values)| {
+                    unpack_raw(values.to_bytes())
+                        .into_iter()
+                        .map(ExprResult::from)
+                        .collect()
+                })
+                .collect::<Vec<_>>();
+            self.check_interrupts()?;
+            self.operation(|| Operation::Scan {
+                stored: partition_rows.len(),
+            });
+            let relation = Relation::new(columns.clone(), partition_rows);
+            self.leave(partition_node, &relation);
+            rows.extend(relation.rows);
+        }
+        let relation = Relation::new(columns, rows);
+        self.leave(node, &relation);
+        Ok(relation)
+    }
+
+    fn join(
+        &self,
+        left: Relation,
+        factor: &TableFactor,
+        kind: JoinKind,
+        condition: JoinCondition,
+    ) -> Result<Relation, Failure> {
+        let (right_columns, right_rows) = match factor {
+            TableFactor::Derived {
+                lateral: true,
+                subquery,
+                alias,
+            } => {
+                if kind == JoinKind::Right || kind == JoinKind::Full {
+                    return Err(self.feature_not_supported());
+                }
+                let columns = qualify(self.query_columns(subquery, Some(&left.columns))?, alias);
+                let mut rows = vec![];
+                for left_row in left.rows.iter() {
+                    rows.push(
+                        self.run_query(subquery, Some(Frame::new(&left.columns, left_row)), None)?
+                            .rows,
                     );
-                    self.sender
-                        .send(Ok(QueryEvent::RecordsSelected(projection)))
-                        .expect("To Send Query Result to Client");
-                    Ok(())
                 }
+                (columns, RightRows::PerLeftRow(rows))
+            }
+            factor => {
+                let relation = self.run_factor(factor)?;
+                (relation.columns, RightRows::Shared(relation.rows))
+            }
+        };
+
+        let left_width = left.columns.len();
+        let right_width = right_columns.len();
+        let common = self.common_columns(&left.columns, &right_columns, &condition)?;
+        let mut columns = left.columns;
+        columns.extend(right_columns);
+        let on = match condition {
+            JoinCondition::On(on) => {
+                self.check_columns(on, &Scope::new(Frame::header(&columns), None))?;
+                Some(on)
+            }
+            _ => None,
+        };
+
+        self.operation(|| Operation::Join {
+            selectivity: match on {
+                Some(on) => selectivity(on),
+                None => selectivity_of_common(common.len()),
             },
+        });
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let mut rows = vec![];
+        let mut removed = 0;
+        let mut right_matched = vec![false; right_rows.shared_len()];
+        for (left_index, left_row) in left.rows.into_iter().enumerate() {
+            self.check_interrupts()?;
+            let mut matched = false;
+            for (right_index, right_row) in right_rows.for_left_row(left_index).iter().enumerate() {
+                let mut row = left_row.clone();
+                row.extend(right_row.iter().cloned());
+                let joined = match on {
+                    None => common.iter().all(|(left_index, right_index)| {
+                        !left_row[*left_index].is_null() && left_row[*left_index] == right_row[*right_index]
+                    }),
+                    Some(on) => self.satisfies(&mut evaluation, on, &Scope::new(Frame::new(&columns, &row), None))?,
+                };
+                if joined {
+                    matched = true;
+                    if let Some(right_matched) = right_matched.get_mut(right_index) {
+                        *right_matched = true;
+                    }
+                    rows.push(row);
+                } else {
+                    removed += 1;
+                }
+            }
+            if !matched && (kind == JoinKind::Left || kind == JoinKind::Full) {
+                let mut row = left_row;
+                row.extend(vec![ExprResult::Null; right_width]);
+                rows.push(row);
+            }
+        }
+        if let (JoinKind::Right, RightRows::Shared(right_rows)) | (JoinKind::Full, RightRows::Shared(right_rows)) =
+            (kind, right_rows)
+        {
+            for (right_row, matched) in right_rows.into_iter().zip(right_matched) {
+                if !matched {
+                    let mut row = vec![ExprResult::Null; left_width];
+                    row.extend(right_row);
+                    rows.push(row);
+                }
+            }
+        }
+
+        if let (Some(profile), Some(on)) = (&self.profile, on) {
+            profile.filter(on, removed);
+        }
+        if common.is_empty() {
+            Ok(Relation::new(columns, rows))
+        } else {
+            Ok(merge_common_columns(Relation::new(columns, rows), left_width, &common))
         }
     }
 
-    fn parse_select_input(&self) -> SystemResult<SelectInput> {
-        let Query { body, .. } = &*self.query;
-        if let SetExpr::Select(select) = body {
-            let Select { projection, from, .. } = select.deref();
-            let TableWithJoins { relation, .. } = &from[0];
-            let (schema_name, table_name) = match relation {
-                TableFactor::Table { name, .. } => {
-                    let table_name = name.0[1].to_string();
-                    let schema_name = name.0[0].to_string();
-                    (schema_name, table_name)
+    // pairs of left and right column indexes that NATURAL or USING join compares for equality
+    fn common_columns(
+        &self,
+        left: &[RelationColumn],
+        right: &[RelationColumn],
+        condition: &JoinCondition,
+    ) -> Result<Vec<(usize, usize)>, Failure> {
+        let names: Vec<String> = match condition {
+            JoinCondition::Always | JoinCondition::On(_) => return Ok(vec![]),
+            JoinCondition::Natural => left
+                .iter()
+                .map(|column| column.name().to_owned())
+                .filter(|name| right.iter().any(|column| column.name() == name))
+                .collect(),
+            JoinCondition::Using(idents) => idents.iter().map(|ident| ident.value.clone()).collect(),
+        };
+        let mut common = vec![];
+        for name in names {
+            if common
+                .iter()
+                .any(|(left_index, _): &(usize, usize)| left[*left_index].name() == name)
+            {
+                continue;
+            }
+            let find = |columns: &[RelationColumn]| {
+                let found: Vec<usize> = columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, column)| column.name() == name)
+                    .map(|(index, _)| index)
+                    .collect();
+                found
+            };
+            match (find(left).as_slice(), find(right).as_slice()) {
+                ([left_index], [right_index]) => common.push((*left_index, *right_index)),
+                ([], _) | (_, []) => {
+                    return Err(self.report(QueryError::column_does_not_exist(vec![name]), "Column Does Not Exist"))
                 }
-                _ => {
-                    self.sender
-                        .send(Err(QueryError::feature_not_supported(self.raw_sql_query.to_owned())))
-                        .expect("To Send Query Result to Client");
-                    return Err(SystemError::runtime_check_failure("Feature Not Supported".to_owned()));
+                _ => return Err(self.report(QueryError::ambiguous_column(name), "Ambiguous Column")),
+            }
+        }
+        Ok(common)
+    }
+
+    fn cartesian_product(&self, left: Relation, right: Relation) -> Result<Relation, Failure> {
+        let mut columns = left.columns;
+        columns.extend(right.columns);
+        let mut rows = vec![];
+        for left_row in left.rows.iter() {
+            self.check_interrupts()?;
+            for right_row in right.rows.iter() {
+                let mut row = left_row.clone();
+                row.extend(right_row.iter().cloned());
+                rows.push(row);
+            }
+        }
+        Ok(Relation::new(columns, rows))
+    }
+
+    fn query_columns(&self, query: &Query, outer: Option<&[RelationColumn]>) -> Result<Vec<RelationColumn>, Failure> {
+        let select = self.select(query)?;
+        let mut columns: Vec<RelationColumn> = vec![];
+        for TableWithJoins { relation, joins } in select.from.iter() {
+            if is_lateral(relation) {
+                columns.extend(self.factor_columns(relation, Some(&columns))?);
+                for join in joins {
+                    columns = self.joined_columns(columns, join)?;
                 }
-            };
+            } else {
+                let mut local = self.factor_columns(relation, None)?;
+                for join in joins {
+                    local = self.joined_columns(local, join)?;
+                }
+                columns.extend(local);
+            }
+        }
+        let (columns, _) = self.projection(&select.projection, &columns, outer.map(Frame::header))?;
+        Ok(columns)
+    }
 
-            match self.storage.table_exists(&schema_name, &table_name) {
+    fn factor_columns(
+        &self,
+        factor: &TableFactor,
+        preceding: Option<&[RelationColumn]>,
+    ) -> Result<Vec<RelationColumn>, Failure> {
+        match factor {
+            TableFactor::Table { name, alias, args, .. } if is_generate_series(name, args) => {
+                Ok(qualify(series_columns(), alias))
+            }
+            TableFactor::Table { name, alias, args, .. } if args.is_empty() => match CatalogTable::resolve(name) {
+                Some(catalog_table) => Ok(qualify(catalog_table.columns(), alias)),
                 None => {
-                    self.sender
-                        .send(Err(QueryError::schema_does_not_exist(schema_name)))
-                        .expect("To Send Result to Client");
-                    Err(SystemError::runtime_check_failure("Schema Does Not Exist".to_owned()))
-                }
-                Some((_, None)) => {
-                    self.sender
-                        .send(Err(QueryError::table_does_not_exist(
-                            schema_name + "." + table_name.as_str(),
-                        )))
-                        .expect("To Send Result to Client");
-                    Err(SystemError::runtime_check_failure("Table Does Not Exist".to_owned()))
-                }
-                Some((schema_id, Some(table_id))) => {
-                    let selected_columns = {
-                        let projection = projection.clone();
-                        let mut columns: Vec<String> = vec![];
-                        for item in projection {
-                            match item {
-                                SelectItem::Wildcard => {
-                                    let all_columns = self.storage.table_columns(schema_id, table_id)?;
-                                    columns.extend(
-                                        all_columns
-                                            .into_iter()
-                                            .map(|column_definition| column_definition.name())
-                                            .collect::<Vec<String>>(),
-                                    )
-                                }
-                                SelectItem::UnnamedExpr(Expr::Identifier(Ident { value, .. })) => {
-                                    columns.push(value.clone())
-                                }
-                                _ => {
-                                    self.sender
-                                        .send(Err(QueryError::feature_not_supported(self.raw_sql_query.to_owned())))
-                                        .expect("To Send Query Result to Client");
-                                    return Err(SystemError::runtime_check_failure("Feature Not Supported".to_owned()));
-                                }
-                            }
+                    let (_, _, columns) = self.table(name)?;
+                    Ok(qualify(columns, alias))
+                }
+            },
+            TableFactor::Derived {
+                lateral,
+                subquery,
+                alias,
+            } => {
+                let outer = if *lateral { preceding } else { None };
+                Ok(qualify(self.query_columns(subquery, outer)?, alias))
+            }
+            TableFactor::NestedJoin(table_with_joins) => {
+                let mut columns = self.factor_columns(&table_with_joins.relation, None)?;
+                for join in table_with_joins.joins.iter() {
+                    columns = self.joined_columns(columns, join)?;
+                }
+                Ok(columns)
+            }
+            _ => Err(self.feature_not_supported()),
+        }
+    }
+
+    fn joined_columns(&self, left: Vec<RelationColumn>, join: &Join) -> Result<Vec<RelationColumn>, Failure> {
+        let right = self.factor_columns(&join.relation, Some(&left))?;
+        let condition = match &join.join_operator {
+            JoinOperator::Inner(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => join_condition(constraint),
+            _ => JoinCondition::Always,
+        };
+        let common = self.common_columns(&left, &right, &condition)?;
+        let left_width = left.len();
+        let mut columns = left;
+        columns.extend(right);
+        if common.is_empty() {
+            Ok(columns)
+        } else {
+            Ok(merge_common_columns(Relation::new(columns, vec![]), left_width, &common).columns)
+        }
+    }
+
+    fn projection<'p>(
+        &self,
+        projection: &'p [SelectItem],
+        columns: &[RelationColumn],
+        outer: Option<Frame>,
+    ) -> Result<(Vec<RelationColumn>, Vec<Projected<'p>>), Failure> {
+        let scope = Scope::new(Frame::header(columns), outer);
+        let mut projected_columns = vec![];
+        let mut items = vec![];
+        let mut non_existing_columns = vec![];
+        for item in projection {
+            let (expr, name) = match item {
+                SelectItem::Wildcard => {
+                    for (index, column) in columns.iter().enumerate() {
+                        projected_columns.push(
+                            RelationColumn::new(column.name().to_owned(), column.sql_type())
+                                .with_type_modifier(column.type_modifier()),
+                        );
+                        items.push(Projected::Column(index));
+                    }
+                    continue;
+                }
+                SelectItem::UnnamedExpr(expr) => (expr, expr_name(expr)),
+                SelectItem::ExprWithAlias { expr, alias } => (expr, alias.value.clone()),
+                SelectItem::QualifiedWildcard(qualifier) => {
+                    let len = items.len();
+                    for (index, column) in columns.iter().enumerate() {
+                        if column.belongs_to(&qualifier.0) {
+                            projected_columns.push(
+                                RelationColumn::new(column.name().to_owned(), column.sql_type())
+                                    .with_type_modifier(column.type_modifier()),
+                            );
+                            items.push(Projected::Column(index));
                         }
-                        columns
-                    };
+                    }
+                    if items.len() == len {
+                        return Err(self.report(
+                            QueryError::table_does_not_exist(qualifier.to_string()),
+                            "Table Does Not Exist",
+                        ));
+                    }
+                    continue;
+                }
+            };
+            match scope.check(expr) {
+                Ok(()) => {
+                    let sql_type = self.function_type(expr).unwrap_or_else(|| expr_type(expr, &scope));
+                    projected_columns
+                        .push(RelationColumn::new(name, sql_type).with_type_modifier(expr_type_modifier(expr, &scope)));
+                    items.push(Projected::Expr(expr));
+                }
+                Err(ColumnReferenceError::Missing(mut columns)) => non_existing_columns.append(&mut columns),
+                Err(ColumnReferenceError::Ambiguous(column)) => {
+                    return Err(self.report(QueryError::ambiguous_column(column), "Ambiguous Column"))
+                }
+            }
+        }
+
+        if !non_existing_columns.is_empty() {
+            return Err(self.column_does_not_exist(non_existing_columns, &scope));
+        }
 
-                    Ok(SelectInput {
-                        schema_name,
-                        table_name,
-                        selected_columns,
+        Ok((projected_columns, items))
+    }
+
+    fn select<'q>(&self, query: &'q Query) -> Result<&'q Select, Failure> {
+        let Query { body, ctes, fetch, .. } = query;
+        if !ctes.is_empty() || fetch.is_some() {
+            return Err(self.feature_not_supported());
+        }
+        match body {
+            SetExpr::Select(select) => {
+                let Select {
+                    distinct, top, having, ..
+                } = &**select;
+                if *distinct || top.is_some() || having.is_some() {
+                    Err(self.feature_not_supported())
+                } else {
+                    Ok(select)
+                }
+            }
+            SetExpr::Query(query) => self.select(query),
+            _ => Err(self.feature_not_supported()),
+        }
+    }
+
+    fn table(&self, name: &ObjectName) -> Result<(RecordId, RecordId, Vec<RelationColumn>), Failure> {
+        let table = match TableId::try_from(name.clone()) {
+            Ok(table) => table,
+            Err(TableNamingError(error)) => return Err(self.report(error, "Table Naming Error")),
+        };
+        let schema_name = table.schema_name();
+        let table_name = table.name();
+        match self
+            .storage
+            .session_table_exists(self.session_id, schema_name, table_name)
+        {
+            None => Err(self.report(
+                QueryError::schema_does_not_exist(schema_name.to_owned()),
+                "Schema Does Not Exist",
+            )),
+            Some((_, None)) => Err(self.report(
+                QueryError::table_does_not_exist(schema_name.to_owned() + "." + table_name),
+                "Table Does Not Exist",
+            )),
+            Some((schema_id, Some(table_id)))
+                if !self
+                    .storage
+                    .has_privilege(self.session_id, schema_id, table_id, Privilege::Select) =>
+            {
+                Err(self.report(
+                    QueryError::permission_denied(format!("permission denied for table {}", table_name)),
+                    "Permission Denied",
+                ))
+            }
+            Some((schema_id, Some(table_id))) => {
+                self.storage
+                    .lock_table(self.session_id, schema_id, table_id, LockMode::AccessShare);
+                self.read_tables.borrow_mut().push(ReadTable {
+                    schema_name: schema_name.to_owned(),
+                    table_name: table_name.to_owned(),
+                    schema_id,
+                    table_id,
+                    version: self.storage.table_version(schema_id, table_id),
+                });
+                let columns = self
+                    .storage
+                    .table_columns(schema_id, table_id)?
+                    .into_iter()
+                    .map(|column| {
+                        RelationColumn::qualified(
+                            Some(schema_name.to_owned()),
+                            Some(table_name.to_owned()),
+                            column.name(),
+                            (&column.sql_type()).into(),
+                        )
+                        .with_type_modifier(column.sql_type().type_modifier())
                     })
+                    .collect();
+                Ok((schema_id, table_id, columns))
+            }
+        }
+    }
+
+    // values of `generate_series(start, stop [, step])` from `start` up to `stop` inclusive
+    fn generate_series(&self, args: &[Expr]) -> Result<Vec<i32>, Failure> {
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let mut values = vec![];
+        for arg in args {
+            match evaluation.eval(arg) {
+                Ok(Value::Number(value)) if value.is_integer() && value.to_i32().is_some() => {
+                    values.push(value.to_i32().unwrap())
+                }
+                Ok(Value::Number(value)) if value.is_integer() => {
+                    return Err(self.report(
+                        QueryError::value_out_of_range(PostgreSqlType::Integer),
+                        "Argument Out Of Range",
+                    ))
                 }
+                Ok(value) => {
+                    return Err(self.report(
+                        QueryError::invalid_parameter_value(format!(
+                            "generate_series argument {} is not an integer",
+                            value
+                        )),
+                        "Non Integer Argument",
+                    ))
+                }
+                Err(()) => return Err(Failure::Reported("Expression Evaluation Failure")),
             }
-        } else {
-            self.sender
-                .send(Err(QueryError::feature_not_supported(self.raw_sql_query.to_owned())))
-                .expect("To Send Query Result to Client");
-            Err(SystemError::runtime_check_failure("Feature Not Supported".to_owned()))
+        }
+        let (start, stop, step) = match values.as_slice() {
+            [start, stop] => (*start, *stop, 1),
+            [start, stop, step] => (*start, *stop, *step),
+            _ => {
+                return Err(self.report(
+                    QueryError::invalid_parameter_value(
+                        "generate_series takes start, stop and optional step arguments".to_owned(),
+                    ),
+                    "Wrong Number Of Arguments",
+                ))
+            }
+        };
+        if step == 0 {
+            return Err(self.report(
+                QueryError::invalid_parameter_value("step size cannot equal zero".to_owned()),
+                "Zero Step",
+            ));
+        }
+        let mut series = vec![];
+        let mut value = Some(start);
+        while let Some(current) =
+            value.filter(|current| (step > 0 && *current <= stop) || (step < 0 && *current >= stop))
+        {
+            series.push(current);
+            value = current.checked_add(step);
+        }
+        Ok(series)
+    }
+
+    // `None` when LIMIT or OFFSET is NULL which means no limit or no offset
+    fn row_count(&self, expr: &Expr, clause: &str, negative: QueryError) -> Result<Option<usize>, Failure> {
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        match evaluation.eval(expr) {
+            Ok(Value::Null) => Ok(None),
+            Ok(Value::Number(count)) if count.is_integer() => {
+                if count < BigDecimal::from(0) {
+                    Err(self.report(negative, "Negative Row Count"))
+                } else {
+                    Ok(Some(count.to_usize().unwrap_or(usize::MAX)))
+                }
+            }
+            Ok(value) => Err(self.report(
+                QueryError::non_integer_row_count(clause.to_owned(), value.to_string()),
+                "Non Integer Row Count",
+            )),
+            Err(()) => Err(Failure::Reported("Expression Evaluation Failure")),
+        }
+    }
+
+    fn check_columns(&self, expr: &Expr, scope: &Scope) -> Result<(), Failure> {
+        match scope.check(expr) {
+            Ok(()) => Ok(()),
+            Err(ColumnReferenceError::Missing(columns)) => Err(self.column_does_not_exist(columns, scope)),
+            Err(ColumnReferenceError::Ambiguous(column)) => {
+                Err(self.report(QueryError::ambiguous_column(column), "Ambiguous Column"))
+            }
+        }
+    }
+
+    // rows that evaluate predicate to NULL are filtered out the same way as to `false`
+    fn satisfies(
+        &self,
+        evaluation: &mut ExpressionEvaluation,
+        predicate: &Expr,
+        scope: &Scope,
+    ) -> Result<bool, Failure> {
+        match evaluation.eval_in(predicate, scope) {
+            Ok(ExprResult::Bool(value)) => Ok(value),
+            Ok(ExprResult::Null) => Ok(false),
+            Ok(_) => Err(self.report(
+                QueryError::syntax_error(predicate.to_string()),
+                "Predicate Is Not Boolean",
+            )),
+            Err(()) => Err(Failure::Reported("Expression Evaluation Failure")),
+        }
+    }
+
+    // starts a node of the analyzed plan, `None` when the query is not analyzed
+    fn enter(&self, name: impl FnOnce() -> String) -> Option<(usize, Instant)> {
+        self.profile
+            .as_ref()
+            .map(|profile| (profile.enter(name()), Instant::now()))
+    }
+
+    fn operation(&self, operation: impl FnOnce() -> Operation) {
+        if let Some(profile) = &self.profile {
+            profile.operation(operation());
+        }
+    }
+
+    fn leave(&self, node: Option<(usize, Instant)>, relation: &Relation) {
+        if let (Some(profile), Some((index, started))) = (&self.profile, node) {
+            profile.leave(index, relation.rows.len(), started.elapsed());
+        }
+    }
+
+    fn max_rows_exceeded(&self, max_rows: usize) -> Failure {
+        self.report(
+            QueryError::program_limit_exceeded(format!(
+                "query returns more than {} rows allowed by statement_max_rows",
+                max_rows
+            )),
+            "statement_max_rows is exceeded",
+        )
+    }
+
+    fn feature_not_supported(&self) -> Failure {
+        self.report(
+            QueryError::feature_not_supported(self.raw_sql_query.to_owned()),
+            "Feature Not Supported",
+        )
+    }
+
+    // return type of a call to a function that was created by a user
+    fn function_type(&self, expr: &Expr) -> Option<PostgreSqlType> {
+        match expr {
+            Expr::Function(Function { name, .. }) => match name.0.as_slice() {
+                [schema, function_name] => self
+                    .storage
+                    .functions()
+                    .into_iter()
+                    .find(|(schema_name, function)| {
+                        *schema_name == schema.value && function.name() == function_name.value
+                    })
+                    .and_then(|(_, function)| match function.return_type() {
+                        ReturnType::Value(sql_type) => Some((&sql_type).into()),
+                        ReturnType::Trigger => None,
+                    }),
+                _ => None,
+            },
+            Expr::Nested(expr) => self.function_type(expr),
+            _ => None,
+        }
+    }
+
+    // rows are processed until the query runs out of its time or another session cancels it
+    fn check_interrupts(&self) -> Result<(), Failure> {
+        match self.interrupt() {
+            Some(error) => Err(self.report(error, "Query Interrupted")),
+            None => Ok(()),
+        }
+    }
+
+    fn interrupt(&self) -> Option<QueryError> {
+        match self.deadline {
+            Some(deadline) if Instant::now() > deadline => Some(QueryError::query_timeout()),
+            _ if self.storage.is_canceled(self.session_id) => Some(QueryError::canceled_by_user()),
+            _ => None,
+        }
+    }
+
+    // names of the closest visible columns are suggested in the hint
+    fn column_does_not_exist(&self, columns: Vec<String>, scope: &Scope) -> Failure {
+        let hint = scope.column_hint(&columns);
+        let error = QueryError::column_does_not_exist(columns);
+        let error = match hint {
+            Some(hint) => error.with_hint(hint),
+            None => error,
+        };
+        self.report(error, "Column Does Not Exist")
+    }
+
+    fn report(&self, error: QueryError, message: &'static str) -> Failure {
+        self.sender.send(Err(error)).expect("To Send Query Result to Client");
+        Failure::Reported(message)
+    }
+}
+
+// query that only reads columns of a single table, the rows it returns are not sorted, grouped or filtered
+fn streamed(query: &Query) -> Option<&Select> {
+    if !query.ctes.is_empty() || !query.order_by.is_empty() || query.offset.is_some() || query.fetch.is_some() {
+        return None;
+    }
+    match &query.body {
+        SetExpr::Select(select)
+            if !select.distinct
+                && select.top.is_none()
+                && select.selection.is_none()
+                && select.group_by.is_empty()
+                && select.having.is_none()
+                && !is_aggregation(select) =>
+        {
+            Some(select)
+        }
+        _ => None,
+    }
+}
+
+// names, types and text values of the relation as they are sent to the client
+fn projection(relation: &Relation) -> Projection {
+    (
+        relation
+            .columns
+            .iter()
+            .map(|column| (column.name().to_owned(), column.sql_type(), column.type_modifier()))
+            .collect(),
+        relation
+            .rows
+            .iter()
+            .map(|row| row.iter().map(ExprResult::to_text).collect())
+            .collect(),
+    )
+}
+
+enum Aggregate<'a> {
+    // COUNT(*)
+    CountRows,
+    Count { arg: &'a Expr, distinct: bool },
+}
+
+fn aggregate(expr: &Expr) -> Option<Aggregate<'_>> {
+    match expr {
+        Expr::Function(Function {
+            name,
+            args,
+            over: None,
+            distinct,
+        }) if name.to_string().eq_ignore_ascii_case("count") => match args.as_slice() {
+            [Expr::Wildcard] if !distinct => Some(Aggregate::CountRows),
+            [arg] => Some(Aggregate::Count {
+                arg,
+                distinct: *distinct,
+            }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_aggregation(select: &Select) -> bool {
+    !select.group_by.is_empty()
+        || select.projection.iter().any(|item| match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => aggregate(expr).is_some(),
+            _ => false,
+        })
+}
+
+// index of a column in the current relation when expression is just a reference to it
+fn local_column(scope: &Scope, expr: &Expr) -> Option<usize> {
+    let resolution = match expr {
+        Expr::Identifier(ident) => scope.resolve(std::slice::from_ref(ident)),
+        Expr::CompoundIdentifier(idents) => scope.resolve(idents),
+        _ => return None,
+    };
+    match resolution {
+        Resolution::Found { outer: false, index } => Some(index),
+        _ => None,
+    }
+}
+
+// output column with the same name as identifier or select list item with the same expression
+fn output_column(columns: &[RelationColumn], items: &[Projected], expr: &Expr) -> Option<usize> {
+    if let Expr::Identifier(ident) = expr {
+        if let Some(index) = columns.iter().position(|column| column.name() == ident.value) {
+            return Some(index);
+        }
+    }
+    items[..columns.len()].iter().position(|item| match item {
+        Projected::Expr(projected) => *projected == expr,
+        Projected::Column(_) => false,
+    })
+}
+
+// NULLs are larger than any other value, thus they are last in ascending order by default
+fn compare_rows(sort_by: &[SortBy], left: &[ExprResult], right: &[ExprResult]) -> Ordering {
+    for SortBy {
+        index,
+        descending,
+        nulls_first,
+    } in sort_by
+    {
+        let ordering = match (&left[*index], &right[*index]) {
+            (ExprResult::Null, ExprResult::Null) => Ordering::Equal,
+            (ExprResult::Null, _) if *nulls_first => Ordering::Less,
+            (ExprResult::Null, _) => Ordering::Greater,
+            (_, ExprResult::Null) if *nulls_first => Ordering::Greater,
+            (_, ExprResult::Null) => Ordering::Less,
+            (left, right) => {
+                let ordering = match (left, right) {
+                    (ExprResult::Number(left), ExprResult::Number(right)) => left.cmp(right),
+                    (ExprResult::String(left), ExprResult::String(right)) => left.cmp(right),
+                    (ExprResult::Bool(left), ExprResult::Bool(right)) => left.cmp(right),
+                    (left, right) => left.to_string().cmp(&right.to_string()),
+                };
+                if *descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
         }
     }
+    Ordering::Equal
+}
+
+enum RightRows {
+    Shared(Vec<Vec<ExprResult>>),
+    // LATERAL subquery produces its own rows for every row on the left side
+    PerLeftRow(Vec<Vec<Vec<ExprResult>>>),
+}
+
+impl RightRows {
+    fn for_left_row(&self, index: usize) -> &[Vec<ExprResult>] {
+        match self {
+            RightRows::Shared(rows) => rows,
+            RightRows::PerLeftRow(rows) => &rows[index],
+        }
+    }
+
+    fn shared_len(&self) -> usize {
+        match self {
+            RightRows::Shared(rows) => rows.len(),
+            RightRows::PerLeftRow(_) => 0,
+        }
+    }
+}
+
+fn join_name(operator: &JoinOperator) -> String {
+    match operator {
+        JoinOperator::Inner(_) => "Nested Loop Inner Join",
+        JoinOperator::LeftOuter(_) => "Nested Loop Left Join",
+        JoinOperator::RightOuter(_) => "Nested Loop Right Join",
+        JoinOperator::FullOuter(_) => "Nested Loop Full Join",
+        _ => "Nested Loop",
+    }
+    .to_owned()
+}
+
+fn join_condition(constraint: &JoinConstraint) -> JoinCondition<'_> {
+    match constraint {
+        JoinConstraint::On(expr) => JoinCondition::On(expr),
+        JoinConstraint::Natural => JoinCondition::Natural,
+        JoinConstraint::Using(idents) => JoinCondition::Using(idents),
+    }
+}
+
+// common columns of NATURAL and USING join go first and only once followed by the rest of
+// left and right columns, value of a common column is taken from the side that is not NULL
+fn merge_common_columns(relation: Relation, left_width: usize, common: &[(usize, usize)]) -> Relation {
+    let right_index = |index: usize| left_width + index;
+    let rest: Vec<usize> = (0..relation.columns.len())
+        .filter(|index| {
+            !common
+                .iter()
+                .any(|(left, right)| *index == *left || *index == right_index(*right))
+        })
+        .collect();
+    let columns = common
+        .iter()
+        .map(|(left, _)| relation.columns[*left].clone())
+        .chain(rest.iter().map(|index| relation.columns[*index].clone()))
+        .collect();
+    let rows = relation
+        .rows
+        .into_iter()
+        .map(|row| {
+            common
+                .iter()
+                .map(|(left, right)| {
+                    if row[*left].is_null() {
+                        row[right_index(*right)].clone()
+                    } else {
+                        row[*left].clone()
+                    }
+                })
+                .chain(rest.iter().map(|index| row[*index].clone()))
+                .collect()
+        })
+        .collect();
+    Relation::new(columns, rows)
 }
 
-struct SelectInput {
-    schema_name: String,
-    table_name: String,
-    selected_columns: Vec<String>,
+// functions that return the same value whenever they are called with the same arguments
+const IMMUTABLE_FUNCTIONS: [&str; 14] = [
+    "all",
+    "any",
+    "array",
+    "ceil",
+    "ceiling",
+    "count",
+    "floor",
+    "generate_series",
+    "is_distinct_from",
+    "is_not_distinct_from",
+    "lower",
+    "some",
+    "trunc",
+    "upper",
+];
+
+// result of the query is the same for all sessions until tables that it reads are changed only when
+// all of its expressions are immutable, user functions and catalog tables depend on the server state
+fn is_cacheable(query: &Query) -> bool {
+    query.ctes.iter().all(|cte| is_cacheable(&cte.query))
+        && is_cacheable_set(&query.body)
+        && query.order_by.iter().all(|order_by| is_immutable(&order_by.expr))
+        && query.limit.iter().all(is_immutable)
+        && query.offset.iter().all(|offset| is_immutable(&offset.value))
+        && query.fetch.iter().all(|fetch| fetch.quantity.iter().all(is_immutable))
+}
+
+fn is_cacheable_set(set: &SetExpr) -> bool {
+    match set {
+        SetExpr::Select(select) => {
+            select.projection.iter().all(|item| match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => is_immutable(expr),
+                SelectItem::QualifiedWildcard(_) | SelectItem::Wildcard => true,
+            }) && select.from.iter().all(is_cacheable_from)
+                && select.selection.iter().all(is_immutable)
+                && select.group_by.iter().all(is_immutable)
+                && select.having.iter().all(is_immutable)
+        }
+        SetExpr::Query(query) => is_cacheable(query),
+        SetExpr::SetOperation { left, right, .. } => is_cacheable_set(left) && is_cacheable_set(right),
+        SetExpr::Values(values) => values.0.iter().flatten().all(is_immutable),
+    }
+}
+
+fn is_cacheable_from(table: &TableWithJoins) -> bool {
+    is_cacheable_factor(&table.relation)
+        && table.joins.iter().all(|join| {
+            is_cacheable_factor(&join.relation)
+                && match &join.join_operator {
+                    JoinOperator::Inner(JoinConstraint::On(expr))
+                    | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+                    | JoinOperator::RightOuter(JoinConstraint::On(expr))
+                    | JoinOperator::FullOuter(JoinConstraint::On(expr)) => is_immutable(expr),
+                    _ => true,
+                }
+        })
+}
+
+fn is_cacheable_factor(factor: &TableFactor) -> bool {
+    match factor {
+        TableFactor::Table { name, args, .. } if args.is_empty() => CatalogTable::resolve(name).is_none(),
+        TableFactor::Table { name, args, .. } => is_generate_series(name, args) && args.iter().all(is_immutable),
+        TableFactor::Derived { subquery, .. } => is_cacheable(subquery),
+        TableFactor::NestedJoin(table) => is_cacheable_from(table),
+    }
+}
+
+fn is_immutable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Identifier(_)
+        | Expr::CompoundIdentifier(_)
+        | Expr::Wildcard
+        | Expr::QualifiedWildcard(_)
+        | Expr::Value(_)
+        | Expr::TypedString { .. } => true,
+        Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::Collate { expr, .. }
+        | Expr::Nested(expr) => is_immutable(expr),
+        Expr::BinaryOp { left, right, .. } => is_immutable(left) && is_immutable(right),
+        Expr::Between { expr, low, high, .. } => is_immutable(expr) && is_immutable(low) && is_immutable(high),
+        Expr::InList { expr, list, .. } => is_immutable(expr) && list.iter().all(is_immutable),
+        Expr::InSubquery { expr, subquery, .. } => is_immutable(expr) && is_cacheable(subquery),
+        Expr::Exists(query) | Expr::Subquery(query) => is_cacheable(query),
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            operand.iter().all(|operand| is_immutable(operand))
+                && conditions.iter().chain(results.iter()).all(is_immutable)
+                && else_result.iter().all(|else_result| is_immutable(else_result))
+        }
+        Expr::Function(Function { name, args, .. }) => {
+            IMMUTABLE_FUNCTIONS
+                .iter()
+                .any(|function| name.to_string().eq_ignore_ascii_case(function))
+                && args.iter().all(is_immutable)
+        }
+        _ => false,
+    }
+}
+
+fn is_generate_series(name: &ObjectName, args: &[Expr]) -> bool {
+    !args.is_empty() && name.to_string().eq_ignore_ascii_case("generate_series")
+}
+
+fn series_columns() -> Vec<RelationColumn> {
+    vec![RelationColumn::new("value".to_owned(), PostgreSqlType::Integer)]
+}
+
+fn is_lateral(factor: &TableFactor) -> bool {
+    matches!(factor, TableFactor::Derived { lateral: true, .. })
+}
+
+fn qualify(columns: Vec<RelationColumn>, alias: &Option<TableAlias>) -> Vec<RelationColumn> {
+    match alias {
+        None => columns,
+        Some(TableAlias { name, columns: renames }) => columns
+            .into_iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let column_name = renames
+                    .get(index)
+                    .map(|rename| rename.value.clone())
+                    .unwrap_or_else(|| column.name().to_owned());
+                RelationColumn::qualified(None, Some(name.value.clone()), column_name, column.sql_type())
+                    .with_type_modifier(column.type_modifier())
+            })
+            .collect(),
+    }
+}
+
+pub(crate) fn expr_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident.value.clone(),
+        Expr::CompoundIdentifier(idents) => idents.last().map(|ident| ident.value.clone()).unwrap_or_default(),
+        Expr::Nested(expr) | Expr::Cast { expr, .. } => expr_name(expr),
+        Expr::Function(function) if distinct_predicate(&function.name).is_some() => "?column?".to_owned(),
+        Expr::Function(function) => function
+            .name
+            .0
+            .last()
+            .map(|ident| ident.value.to_lowercase())
+            .unwrap_or_default(),
+        _ => "?column?".to_owned(),
+    }
+}
+
+pub(crate) fn expr_type(expr: &Expr, scope: &Scope) -> PostgreSqlType {
+    match expr {
+        Expr::Identifier(ident) => column_type(scope, std::slice::from_ref(ident)),
+        Expr::CompoundIdentifier(idents) => column_type(scope, idents),
+        Expr::Value(Value::Number(number)) if number.is_integer() => PostgreSqlType::Integer,
+        Expr::Value(Value::Number(_)) => PostgreSqlType::Decimal,
+        Expr::Value(Value::Boolean(_)) => PostgreSqlType::Bool,
+        Expr::BinaryOp { op, left, right } => match op {
+            BinaryOperator::Plus
+            | BinaryOperator::Minus
+            | BinaryOperator::Multiply
+            | BinaryOperator::Divide
+            | BinaryOperator::Modulus
+            | BinaryOperator::BitwiseAnd
+            | BinaryOperator::BitwiseOr
+            | BinaryOperator::BitwiseXor => wider(expr_type(left, scope), expr_type(right, scope)),
+            BinaryOperator::StringConcat => PostgreSqlType::VarChar,
+            _ => PostgreSqlType::Bool,
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Not, ..
+        } => PostgreSqlType::Bool,
+        Expr::UnaryOp { expr, .. } | Expr::Nested(expr) => expr_type(expr, scope),
+        Expr::IsNull(_) | Expr::IsNotNull(_) | Expr::Between { .. } | Expr::InList { .. } => PostgreSqlType::Bool,
+        Expr::Function(_) if aggregate(expr).is_some() => PostgreSqlType::BigInt,
+        Expr::Function(Function { name, .. }) if distinct_predicate(name).is_some() => PostgreSqlType::Bool,
+        Expr::Function(Function { name, .. }) if name.to_string().eq_ignore_ascii_case("pg_backend_pid") => {
+            PostgreSqlType::Integer
+        }
+        Expr::Function(Function { name, .. }) if name.to_string().eq_ignore_ascii_case("pg_cancel_backend") => {
+            PostgreSqlType::Bool
+        }
+        Expr::Function(Function { name, args, .. }) if rounding_function(name).is_some() => match args.as_slice() {
+            [arg] => match expr_type(arg, scope) {
+                sql_type @ PostgreSqlType::Real | sql_type @ PostgreSqlType::DoublePrecision => sql_type,
+                _ => PostgreSqlType::Decimal,
+            },
+            _ => PostgreSqlType::Decimal,
+        },
+        Expr::Cast { data_type, .. } => cast_type(data_type),
+        _ => PostgreSqlType::VarChar,
+    }
+}
+
+fn cast_type(data_type: &DataType) -> PostgreSqlType {
+    match data_type {
+        DataType::SmallInt => PostgreSqlType::SmallInt,
+        DataType::Int => PostgreSqlType::Integer,
+        DataType::BigInt => PostgreSqlType::BigInt,
+        DataType::Decimal(..) => PostgreSqlType::Decimal,
+        DataType::Real => PostgreSqlType::Real,
+        DataType::Float(_) | DataType::Double => PostgreSqlType::DoublePrecision,
+        DataType::Boolean => PostgreSqlType::Bool,
+        DataType::Char(_) => PostgreSqlType::Char,
+        _ => PostgreSqlType::VarChar,
+    }
+}
+
+/// type modifier of a column that is referenced as is, results of other expressions have none
+pub(crate) fn expr_type_modifier(expr: &Expr, scope: &Scope) -> i32 {
+    let idents = match expr {
+        Expr::Identifier(ident) => std::slice::from_ref(ident),
+        Expr::CompoundIdentifier(idents) => idents.as_slice(),
+        Expr::Nested(expr) => return expr_type_modifier(expr, scope),
+        _ => return -1,
+    };
+    match scope.resolve(idents) {
+        Resolution::Found { outer, index } => scope.column(outer, index).type_modifier(),
+        _ => -1,
+    }
+}
+
+fn column_type(scope: &Scope, idents: &[Ident]) -> PostgreSqlType {
+    match scope.resolve(idents) {
+        Resolution::Found { outer, index } => scope.column(outer, index).sql_type(),
+        _ => PostgreSqlType::VarChar,
+    }
+}
+
+// result type of arithmetic over two numeric types, e.g. smallint * integer is integer
+fn wider(left: PostgreSqlType, right: PostgreSqlType) -> PostgreSqlType {
+    fn rank(sql_type: PostgreSqlType) -> Option<u8> {
+        match sql_type {
+            PostgreSqlType::SmallInt => Some(0),
+            PostgreSqlType::Integer => Some(1),
+            PostgreSqlType::BigInt => Some(2),
+            PostgreSqlType::Decimal => Some(3),
+            PostgreSqlType::Real => Some(4),
+            PostgreSqlType::DoublePrecision => Some(5),
+            _ => None,
+        }
+    }
+
+    match (rank(left), rank(right)) {
+        (Some(left_rank), Some(right_rank)) if left_rank >= right_rank => left,
+        (Some(_), Some(_)) | (None, Some(_)) => right,
+        (Some(_), None) => left,
+        (None, None) => PostgreSqlType::Decimal,
+    }
 }
@@ -0,0 +1,413 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    dml::{
+        delete::DeleteCommand,
+        insert::InsertCommand,
+        relation::{Frame, Scope},
+        update::UpdateCommand,
+        ExprResult, ExpressionEvaluation, NestedSender,
+    },
+    query::{
+        plan::Plan,
+        process::QueryProcessor,
+        {TableId, TableNamingError},
+    },
+};
+use data_manager::{ColumnDefinition, DataManager, RecordId, SessionId, TriggerEvent, TriggerTiming};
+use kernel::SystemResult;
+use protocol::{results::QueryError, Sender};
+use sqlparser::{
+    ast::{Expr, Ident, ObjectName, Query, SelectItem, SetExpr, Statement, Value},
+    dialect::PostgreSqlDialect,
+    parser::Parser,
+    tokenizer::Tokenizer,
+};
+use std::{convert::TryFrom, sync::Arc};
+
+/// Modification of a table that fires its triggers
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Operation<'o> {
+    Insert,
+    /// indexes of the assigned columns
+    Update(&'o [usize]),
+    Delete,
+}
+
+/// Row level triggers of a table that fire on an operation. Each trigger executes a statement of its
+/// function where `NEW.<column>` and `OLD.<column>` are replaced with values of the modified row.
+pub(crate) struct RowTriggers {
+    before: Vec<Statement>,
+    after: Vec<Statement>,
+    columns: Vec<ColumnDefinition>,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl RowTriggers {
+    pub(crate) fn new(
+        schema_id: RecordId,
+        table_id: RecordId,
+        operation: Operation,
+        columns: &[ColumnDefinition],
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> RowTriggers {
+        let mut triggers = storage.triggers(schema_id, table_id);
+        // triggers of the same timing fire in alphabetical order of their names
+        triggers.sort_by(|left, right| left.name().cmp(right.name()));
+        let mut before = vec![];
+        let mut after = vec![];
+        for trigger in triggers.iter().filter(|trigger| trigger.is_enabled()) {
+            let fires = trigger.events().iter().any(|event| match (event, operation) {
+                (TriggerEvent::Insert, Operation::Insert) | (TriggerEvent::Delete, Operation::Delete) => true,
+                (TriggerEvent::Update(names), Operation::Update(assigned)) => {
+                    names.is_empty()
+                        || assigned
+                            .iter()
+                            .any(|index| names.iter().any(|name| columns[*index].has_name(name)))
+                }
+                _ => false,
+            });
+            if !fires {
+                continue;
+            }
+            let (function_schema_id, function_name) = trigger.function();
+            let statement = storage
+                .function(function_schema_id, function_name)
+                .and_then(|function| {
+                    let tokens = Tokenizer::new(&PostgreSqlDialect {}, function.body()).tokenize().ok()?;
+                    Parser::new(tokens).parse_statement().ok()
+                });
+            if let Some(statement) = statement {
+                match trigger.timing() {
+                    TriggerTiming::Before => before.push(statement),
+                    TriggerTiming::After => after.push(statement),
+                }
+            }
+        }
+        RowTriggers {
+            before,
+            after,
+            columns: columns.to_vec(),
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn has_before(&self) -> bool {
+        !self.before.is_empty()
+    }
+
+    pub(crate) fn has_after(&self) -> bool {
+        !self.after.is_empty()
+    }
+
+    /// fires BEFORE triggers, a trigger whose function is a SELECT replaces values of the NEW row.
+    /// Returns `false` when an error was sent to the client
+    pub(crate) fn before(
+        &self,
+        old: Option<&[ExprResult]>,
+        mut new: Option<&mut Vec<ExprResult>>,
+    ) -> SystemResult<bool> {
+        for statement in self.before.iter() {
+            let row = new.as_deref().map(Vec::as_slice);
+            match self.fire(statement, old, row)? {
+                Fired::Failed => return Ok(false),
+                Fired::Row(values) => {
+                    if let Some(new) = new.as_mut() {
+                        **new = values;
+                    }
+                }
+                Fired::Executed => {}
+            }
+        }
+        Ok(true)
+    }
+
+    /// fires AFTER triggers, returns `false` when an error was sent to the client
+    pub(crate) fn after(&self, old: Option<&[ExprResult]>, new: Option<&[ExprResult]>) -> SystemResult<bool> {
+        for statement in self.after.iter() {
+            if let Fired::Failed = self.fire(statement, old, new)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn fire(
+        &self,
+        statement: &Statement,
+        old: Option<&[ExprResult]>,
+        new: Option<&[ExprResult]>,
+    ) -> SystemResult<Fired> {
+        let rows = Rows {
+            columns: &self.columns,
+            old,
+            new,
+        };
+        let mut statement = statement.clone();
+        if let Err(error) = rows.substitute_statement(&mut statement) {
+            self.sender.send(Err(error)).expect("To Send Query Result to Client");
+            return Ok(Fired::Failed);
+        }
+        let sender = Arc::new(NestedSender::new(self.sender.clone()));
+        let raw_sql_query = statement.to_string();
+        match statement {
+            Statement::Query(query) => return Ok(self.returned_row(&query, &rows)),
+            Statement::Update {
+                table_name,
+                assignments,
+                selection,
+            } => {
+                if let Some(table_name) = self.qualified(table_name) {
+                    UpdateCommand::new(
+                        &raw_sql_query,
+                        table_name,
+                        assignments,
+                        vec![],
+                        selection,
+                        self.session_id,
+                        self.storage.clone(),
+                        sender.clone(),
+                    )
+                    .execute()?;
+                } else {
+                    return Ok(Fired::Failed);
+                }
+            }
+            Statement::Delete { table_name, selection } => {
+                if let Some(table_name) = self.qualified(table_name) {
+                    DeleteCommand::new(
+                        &raw_sql_query,
+                        table_name,
+                        vec![],
+                        selection,
+                        vec![],
+                        self.session_id,
+                        self.storage.clone(),
+                        sender.clone(),
+                    )
+                    .execute()?;
+                } else {
+                    return Ok(Fired::Failed);
+                }
+            }
+            statement => {
+                match QueryProcessor::new(self.session_id, self.storage.clone(), sender.clone()).process(statement) {
+                    Ok(Plan::Insert(table_inserts)) => InsertCommand::new(
+                        &raw_sql_query,
+                        table_inserts,
+                        self.session_id,
+                        self.storage.clone(),
+                        sender.clone(),
+                    )
+                    .execute()?,
+                    Ok(_) => {
+                        self.sender
+                            .send(Err(QueryError::feature_not_supported(raw_sql_query)))
+                            .expect("To Send Query Result to Client");
+                        return Ok(Fired::Failed);
+                    }
+                    Err(()) => return Ok(Fired::Failed),
+                }
+            }
+        }
+        if sender.failed() {
+            Ok(Fired::Failed)
+        } else {
+            Ok(Fired::Executed)
+        }
+    }
+
+    // values of `SELECT` items are the NEW row, the statement of AFTER trigger is not evaluated
+    fn returned_row(&self, query: &Query, rows: &Rows) -> Fired {
+        let items = match (&query.body, rows.new) {
+            (SetExpr::Select(select), Some(_)) if select.from.is_empty() => &select.projection,
+            (_, None) => return Fired::Executed,
+            (body, _) => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(body.to_string())))
+                    .expect("To Send Query Result to Client");
+                return Fired::Failed;
+            }
+        };
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let no_columns = Scope::new(Frame::header(&[]), None);
+        let mut values = vec![];
+        for item in items {
+            match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    match evaluation.eval_in(expr, &no_columns) {
+                        Ok(value) => values.push(value),
+                        Err(()) => return Fired::Failed,
+                    }
+                }
+                SelectItem::QualifiedWildcard(ObjectName(qualifier)) => match rows.row(qualifier) {
+                    Some(row) => values.extend(row.iter().cloned()),
+                    None => {
+                        self.sender
+                            .send(Err(QueryError::feature_not_supported(item.to_string())))
+                            .expect("To Send Query Result to Client");
+                        return Fired::Failed;
+                    }
+                },
+                SelectItem::Wildcard => {
+                    self.sender
+                        .send(Err(QueryError::feature_not_supported(item.to_string())))
+                        .expect("To Send Query Result to Client");
+                    return Fired::Failed;
+                }
+            }
+        }
+        if values.len() != self.columns.len() {
+            self.sender
+                .send(Err(QueryError::invalid_object_definition(
+                    "returned row structure does not match the structure of the triggering table".to_owned(),
+                )))
+                .expect("To Send Query Result to Client");
+            return Fired::Failed;
+        }
+        Fired::Row(values)
+    }
+
+    fn qualified(&self, table_name: ObjectName) -> Option<ObjectName> {
+        match TableId::try_from(table_name.clone()) {
+            Ok(_) => Some(table_name),
+            Err(TableNamingError(error)) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                None
+            }
+        }
+    }
+}
+
+enum Fired {
+    Executed,
+    /// values of NEW row returned by BEFORE trigger
+    Row(Vec<ExprResult>),
+    Failed,
+}
+
+/// rows that `NEW` and `OLD` refer to, references to a missing row are NULL
+struct Rows<'r> {
+    columns: &'r [ColumnDefinition],
+    old: Option<&'r [ExprResult]>,
+    new: Option<&'r [ExprResult]>,
+}
+
+impl<'r> Rows<'r> {
+    fn row(&self, qualifier: &[Ident]) -> Option<&'r [ExprResult]> {
+        match qualifier {
+            [name] if name.value.eq_ignore_ascii_case("new") => self.new,
+            [name] if name.value.eq_ignore_ascii_case("old") => self.old,
+            _ => None,
+        }
+    }
+
+    fn substitute_statement(&self, statement: &mut Statement) -> Result<(), QueryError> {
+        match statement {
+            Statement::Insert { source, .. } => {
+                if let SetExpr::Values(values) = &mut source.body {
+                    for expr in values.0.iter_mut().flatten() {
+                        self.substitute(expr)?;
+                    }
+                }
+            }
+            Statement::Update {
+                assignments, selection, ..
+            } => {
+                for assignment in assignments.iter_mut() {
+                    self.substitute(&mut assignment.value)?;
+                }
+                if let Some(selection) = selection {
+                    self.substitute(selection)?;
+                }
+            }
+            Statement::Delete {
+                selection: Some(selection),
+                ..
+            } => self.substitute(selection)?,
+            Statement::Query(query) => {
+                if let SetExpr::Select(select) = &mut query.body {
+                    for item in select.projection.iter_mut() {
+                        if let SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } = item {
+                            self.substitute(expr)?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn substitute(&self, expr: &mut Expr) -> Result<(), QueryError> {
+        match expr {
+            Expr::CompoundIdentifier(idents) if idents.len() == 2 && self.is_row(&idents[0]) => {
+                let value = match self.row(&idents[..1]) {
+                    None => Value::Null,
+                    Some(row) => match self.columns.iter().position(|column| column.has_name(&idents[1].value)) {
+                        Some(index) => Value::from(row[index].clone()),
+                        None => {
+                            return Err(QueryError::column_does_not_exist(vec![format!(
+                                "{}.{}",
+                                idents[0].value.to_lowercase(),
+                                idents[1].value
+                            )]))
+                        }
+                    },
+                };
+                *expr = Expr::Value(value);
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                self.substitute(left)?;
+                self.substitute(right)?;
+            }
+            Expr::UnaryOp { expr, .. }
+            | Expr::Nested(expr)
+            | Expr::IsNull(expr)
+            | Expr::IsNotNull(expr)
+            | Expr::Cast { expr, .. } => self.substitute(expr)?,
+            Expr::Between { expr, low, high, .. } => {
+                self.substitute(expr)?;
+                self.substitute(low)?;
+                self.substitute(high)?;
+            }
+            Expr::InList { expr, list, .. } => {
+                self.substitute(expr)?;
+                for item in list.iter_mut() {
+                    self.substitute(item)?;
+                }
+            }
+            Expr::Function(function) => {
+                for arg in function.args.iter_mut() {
+                    self.substitute(arg)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn is_row(&self, ident: &Ident) -> bool {
+        ident.value.eq_ignore_ascii_case("new") || ident.value.eq_ignore_ascii_case("old")
+    }
+}
@@ -12,186 +12,430 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dml::ExpressionEvaluation;
-use data_manager::{DataManager, Row};
+use crate::dml::{
+    assign_value, check_foreign_keys, check_referenced_keys, column_datum, constraint_error, default_expressions,
+    equality_filter, fit_char, generate_values, generation_expressions, is_default, not_null_violation,
+    relation::{ColumnReferenceError, Frame, RelationColumn, Scope},
+    select::SelectCommand,
+    trigger::{Operation, RowTriggers},
+    ExprResult, ExpressionEvaluation,
+};
+use data_manager::{DataManager, LockMode, Privilege, Row, SessionId, TableChange};
 use kernel::SystemResult;
 use protocol::{
     results::{QueryError, QueryEvent},
     Sender,
 };
 use representation::{unpack_raw, Binary, Datum};
-use sql_types::ConstraintError;
-use sqlparser::ast::{Assignment, Expr, Ident, ObjectName, UnaryOperator, Value};
-use std::{collections::BTreeSet, convert::TryFrom, sync::Arc};
+use sqlparser::ast::{Assignment, Expr, ObjectName, Query, Select, SelectItem, SetExpr, TableWithJoins, Value};
+use std::{collections::BTreeSet, sync::Arc};
 
-pub(crate) struct UpdateCommand {
+pub(crate) struct UpdateCommand<'uc> {
+    raw_sql_query: &'uc str,
     name: ObjectName,
     assignments: Vec<Assignment>,
+    from: Vec<TableWithJoins>,
+    selection: Option<Expr>,
+    session_id: SessionId,
     storage: Arc<DataManager>,
     sender: Arc<dyn Sender>,
 }
 
-impl UpdateCommand {
+impl<'uc> UpdateCommand<'uc> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
+        raw_sql_query: &'uc str,
         name: ObjectName,
         assignments: Vec<Assignment>,
+        from: Vec<TableWithJoins>,
+        selection: Option<Expr>,
+        session_id: SessionId,
         storage: Arc<DataManager>,
         sender: Arc<dyn Sender>,
-    ) -> UpdateCommand {
+    ) -> UpdateCommand<'uc> {
         UpdateCommand {
+            raw_sql_query,
             name,
             assignments,
+            from,
+            selection,
+            session_id,
             storage,
             sender,
         }
     }
 
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        self.run(false)
+    }
+
+    // evaluates new values and checks their constraints, nothing is written
+    pub(crate) fn validate(&mut self) -> SystemResult<()> {
+        self.run(true)
+    }
+
+    fn run(&mut self, dry_run: bool) -> SystemResult<()> {
         let schema_name = self.name.0[0].to_string();
         let table_name = self.name.0[1].to_string();
-        let mut to_update = vec![];
 
-        let mut evaluation = ExpressionEvaluation::new(self.sender.clone());
-
-        for item in self.assignments.iter() {
-            let Assignment { id, value } = &item;
-            let Ident { value: column, .. } = id;
-            let value = match value {
-                Expr::Value(value) => value.clone(),
-                Expr::UnaryOp { op, expr } => match (op, &**expr) {
-                    (UnaryOperator::Minus, Expr::Value(Value::Number(v))) => Value::Number(-v),
-                    (op, expr) => {
-                        self.sender
-                            .send(Err(QueryError::syntax_error(
-                                op.to_string() + expr.to_string().as_str(),
-                            )))
-                            .expect("To Send Query Result to Client");
-                        return Ok(());
-                    }
-                },
-                expr @ Expr::BinaryOp { .. } => match evaluation.eval(expr) {
-                    Ok(expr_result) => expr_result,
+        // values that do not reference any column are evaluated once for all rows
+        let mut evaluation = ExpressionEvaluation::new(self.sender.clone())
+            .with_functions(&self.storage)
+            .with_session(self.session_id, self.storage.clone());
+        let no_columns = Scope::new(Frame::header(&[]), None);
+        let mut constants = vec![];
+        for Assignment { value, .. } in self.assignments.iter() {
+            if is_default(value) {
+                // default values of columns are evaluated when the columns are known
+                constants.push(None);
+            } else if no_columns.check(value).is_ok() {
+                match evaluation.eval(value) {
+                    Ok(value) => constants.push(Some(value)),
                     Err(()) => return Ok(()),
-                },
-                expr => {
+                }
+            } else {
+                constants.push(None);
+            }
+        }
+
+        let (schema_id, table_id) = match self
+            .storage
+            .session_table_exists(self.session_id, &schema_name, &table_name)
+        {
+            None => {
+                self.sender
+                    .send(Err(QueryError::schema_does_not_exist(schema_name)))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((_, None)) => {
+                self.sender
+                    .send(Err(QueryError::table_does_not_exist(
+                        schema_name + "." + table_name.as_str(),
+                    )))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((schema_id, Some(table_id)))
+                if !self
+                    .storage
+                    .has_privilege(self.session_id, schema_id, table_id, Privilege::Update) =>
+            {
+                self.sender
+                    .send(Err(QueryError::permission_denied(format!(
+                        "permission denied for table {}",
+                        table_name
+                    ))))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((schema_id, Some(table_id))) if self.storage.partition_key(schema_id, table_id).is_some() => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(
+                        "UPDATE on partitioned table".to_owned(),
+                    )))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+            Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        };
+        self.storage
+            .lock_table(self.session_id, schema_id, table_id, LockMode::RowExclusive);
+
+        let all_columns = self.storage.table_columns(schema_id, table_id)?;
+        let defaults = default_expressions(&all_columns);
+        let mut to_update = vec![];
+        let mut non_existing_columns = BTreeSet::new();
+        for (Assignment { id, value }, constant) in self.assignments.iter().zip(constants) {
+            match all_columns.iter().position(|column| column.has_name(&id.value)) {
+                // generated columns are computed again for every updated row
+                Some(index) if all_columns[index].generated().is_some() && is_default(value) => {}
+                Some(index) if all_columns[index].generated().is_some() => {
                     self.sender
-                        .send(Err(QueryError::syntax_error(expr.to_string())))
-                        .expect("To Send Query Result to Client");
+                        .send(Err(QueryError::generated_column_cannot_be_written(
+                            all_columns[index].name(),
+                        )))
+                        .expect("To Send Result to Client");
                     return Ok(());
                 }
-            };
-
-            to_update.push((column.to_owned(), value))
+                Some(index) if is_default(value) => {
+                    let default = match defaults.iter().find(|(column, _)| *column == index) {
+                        Some((_, expr)) => match evaluation.eval(expr) {
+                            Ok(value) => value,
+                            Err(()) => return Ok(()),
+                        },
+                        None => Value::Null,
+                    };
+                    to_update.push((index, value, Some(default)))
+                }
+                Some(index) => to_update.push((index, value, constant)),
+                None => {
+                    non_existing_columns.insert(id.value.clone());
+                }
+            }
         }
 
-        match self.storage.table_exists(&schema_name, &table_name) {
-            None => self
-                .sender
-                .send(Err(QueryError::schema_does_not_exist(schema_name)))
-                .expect("To Send Result to Client"),
-            Some((_, None)) => self
-                .sender
-                .send(Err(QueryError::table_does_not_exist(
-                    schema_name + "." + table_name.as_str(),
+        if !non_existing_columns.is_empty() {
+            self.sender
+                .send(Err(QueryError::column_does_not_exist(
+                    non_existing_columns.into_iter().collect(),
                 )))
-                .expect("To Send Result to Client"),
-            Some((schema_id, Some(table_id))) => {
-                let all_columns = self.storage.table_columns(schema_id, table_id)?;
-                let mut errors = Vec::new();
-                let mut index_value_pairs = Vec::new();
-                let mut non_existing_columns = BTreeSet::new();
-                let mut column_exists = false;
-
-                for (column_name, value) in to_update {
-                    for (index, column_definition) in all_columns.iter().enumerate() {
-                        if column_definition.has_name(&column_name) {
-                            let v = match value.clone() {
-                                Value::Number(v) => v.to_string(),
-                                Value::SingleQuotedString(v) => v.to_string(),
-                                Value::Boolean(v) => v.to_string(),
-                                _ => unimplemented!("other types not implemented"),
-                            };
-                            match column_definition.sql_type().constraint().validate(v.as_str()) {
-                                Ok(()) => {
-                                    index_value_pairs.push((index, Datum::try_from(&value).unwrap()));
-                                }
-                                Err(e) => {
-                                    errors.push((e, column_definition.clone()));
-                                }
-                            }
-
-                            column_exists = true;
-
-                            break;
+                .expect("To Send Result to Client");
+            return Ok(());
+        }
+
+        let mut columns: Vec<RelationColumn> = all_columns
+            .iter()
+            .map(|column| {
+                RelationColumn::qualified(
+                    Some(schema_name.clone()),
+                    Some(table_name.clone()),
+                    column.name(),
+                    (&column.sql_type()).into(),
+                )
+            })
+            .collect();
+        // without FROM clause every row of the table is matched against a single empty row
+        let source_rows = if self.from.is_empty() {
+            vec![vec![]]
+        } else {
+            let query = Box::new(from_clause_query(self.from.clone()));
+            match SelectCommand::new(
+                self.raw_sql_query,
+                query,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+                None,
+            )
+            .source()?
+            {
+                Some(relation) => {
+                    columns.extend(relation.columns);
+                    relation.rows
+                }
+                None => return Ok(()),
+            }
+        };
+
+        let header = Scope::new(Frame::header(&columns), None);
+        let evaluated = to_update
+            .iter()
+            .filter(|(_, _, constant)| constant.is_none())
+            .map(|(_, expr, _)| *expr);
+        for expr in self.selection.iter().chain(evaluated) {
+            let error = match header.check(expr) {
+                Ok(()) => continue,
+                Err(ColumnReferenceError::Missing(columns)) => QueryError::column_does_not_exist(columns),
+                Err(ColumnReferenceError::Ambiguous(column)) => QueryError::ambiguous_column(column),
+            };
+            self.sender.send(Err(error)).expect("To Send Result to Client");
+            return Ok(());
+        }
+
+        let pushed_down = self
+            .selection
+            .as_ref()
+            .and_then(|predicate| equality_filter(predicate, &header, &all_columns));
+        let cursor = match pushed_down {
+            Some((column, value)) => self
+                .storage
+                .scan_where(self.session_id, schema_id, table_id, column, &value)?,
+            None => self.storage.full_scan(self.session_id, schema_id, table_id)?,
+        };
+        let records: Vec<Row> = cursor.map(Result::unwrap).map(Result::unwrap).collect();
+
+        let generated = generation_expressions(&all_columns);
+        let assigned: Vec<usize> = to_update.iter().map(|(index, _, _)| *index).collect();
+        let triggers = RowTriggers::new(
+            schema_id,
+            table_id,
+            Operation::Update(&assigned),
+            &all_columns,
+            self.session_id,
+            self.storage.clone(),
+            self.sender.clone(),
+        );
+        let mut updated = vec![];
+        // stored versions of updated rows
+        let mut replaced = vec![];
+        // old and new values of rows that AFTER triggers fire for
+        let mut changed = vec![];
+        for (row_index, (key, values)) in records.into_iter().enumerate() {
+            let mut datums = unpack_raw(values.to_bytes());
+            let target_row: Vec<ExprResult> = datums.iter().cloned().map(ExprResult::from).collect();
+
+            // the first joined row that satisfies WHERE clause provides values for the update
+            let mut new_values = None;
+            for source_row in source_rows.iter() {
+                let mut row = target_row.clone();
+                row.extend(source_row.iter().cloned());
+                let scope = Scope::new(Frame::new(&columns, &row), None);
+                let satisfied = match &self.selection {
+                    None => true,
+                    Some(predicate) => match evaluation.eval_in(predicate, &scope) {
+                        Ok(ExprResult::Bool(value)) => value,
+                        Ok(ExprResult::Null) => false,
+                        Ok(_) => {
+                            self.sender
+                                .send(Err(QueryError::syntax_error(predicate.to_string())))
+                                .expect("To Send Query Result to Client");
+                            return Ok(());
+                        }
+                        Err(()) => return Ok(()),
+                    },
+                };
+                if satisfied {
+                    let mut values = vec![];
+                    for (index, expr, constant) in to_update.iter() {
+                        match constant {
+                            Some(value) => values.push((*index, value.clone())),
+                            None => match evaluation.eval_in(expr, &scope) {
+                                Ok(value) => values.push((*index, Value::from(value))),
+                                Err(()) => return Ok(()),
+                            },
                         }
                     }
+                    new_values = Some(values);
+                    break;
+                }
+            }
+
+            let new_values = match new_values {
+                Some(new_values) => new_values,
+                None => continue,
+            };
 
-                    if !column_exists {
-                        non_existing_columns.insert(column_name.clone());
+            let mut errors = vec![];
+            for (index, value) in new_values {
+                let column_definition = &all_columns[index];
+                let value = fit_char(value, column_definition, row_index, self.sender.as_ref());
+                let v = match &value {
+                    Value::Number(v) => v.to_string(),
+                    Value::SingleQuotedString(v) => v.to_string(),
+                    Value::Boolean(v) => v.to_string(),
+                    Value::Null => {
+                        datums[index] = Datum::from_null();
+                        continue;
                     }
+                    _ => unimplemented!("other types not implemented"),
+                };
+                match column_definition.sql_type().constraint().validate(v.as_str()) {
+                    Ok(()) => datums[index] = column_datum(&value, &column_definition.sql_type()),
+                    Err(e) => errors.push((e, column_definition.clone())),
                 }
+            }
 
-                if !non_existing_columns.is_empty() {
+            if !errors.is_empty() {
+                for (error, column_definition) in errors {
                     self.sender
-                        .send(Err(QueryError::column_does_not_exist(
-                            non_existing_columns.into_iter().collect(),
-                        )))
-                        .expect("To Send Result to Client");
+                        .send(Err(constraint_error(error, &column_definition, row_index)))
+                        .expect("To Send Query Result to Client");
+                }
+                return Ok(());
+            }
+            if !dry_run && triggers.has_before() {
+                let mut new = datums.iter().cloned().map(ExprResult::from).collect();
+                if !triggers.before(Some(&target_row), Some(&mut new))? {
                     return Ok(());
                 }
-                if !errors.is_empty() {
-                    for (error, column_definition) in errors {
-                        let error_to_send = match error {
-                            ConstraintError::OutOfRange => QueryError::out_of_range(
-                                (&column_definition.sql_type()).into(),
-                                column_definition.name(),
-                                1,
-                            ),
-                            ConstraintError::TypeMismatch(value) => QueryError::type_mismatch(
-                                &value,
-                                (&column_definition.sql_type()).into(),
-                                column_definition.name(),
-                                1,
-                            ),
-                            ConstraintError::ValueTooLong(len) => QueryError::string_length_mismatch(
-                                (&column_definition.sql_type()).into(),
-                                len,
-                                column_definition.name(),
-                                1,
-                            ),
-                        };
-                        self.sender
-                            .send(Err(error_to_send))
-                            .expect("To Send Query Result to Client");
+                for (index, value) in new.into_iter().enumerate() {
+                    if assign_value(
+                        &mut datums,
+                        index,
+                        Value::from(value),
+                        &all_columns[index],
+                        row_index,
+                        self.sender.as_ref(),
+                    )
+                    .is_err()
+                    {
+                        return Ok(());
                     }
-                    return Ok(());
                 }
+            }
+            if generate_values(
+                &mut evaluation,
+                &generated,
+                &all_columns,
+                &mut datums,
+                row_index,
+                self.sender.as_ref(),
+            )
+            .is_err()
+            {
+                return Ok(());
+            }
+            if let Some(error) = not_null_violation(&all_columns, &datums) {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                return Ok(());
+            }
 
-                let to_update: Vec<Row> = match self.storage.full_scan(schema_id, table_id) {
-                    Err(error) => return Err(error),
-                    Ok(reads) => reads
-                        .map(Result::unwrap)
-                        .map(Result::unwrap)
-                        .map(|(key, values)| {
-                            let mut values = unpack_raw(values.to_bytes());
-                            for (idx, data) in index_value_pairs.as_slice() {
-                                values[*idx] = data.clone();
-                            }
-                            (key, Binary::pack(&values))
-                        })
-                        .collect(),
-                };
+            if triggers.has_after() {
+                let new: Vec<ExprResult> = datums.iter().cloned().map(ExprResult::from).collect();
+                changed.push((target_row, new));
+            }
+            updated.push((key.clone(), Binary::pack(&datums)));
+            replaced.push((key, values));
+        }
 
-                match self.storage.write_into(schema_id, table_id, to_update) {
-                    Err(error) => return Err(error),
-                    Ok(records_number) => {
-                        self.sender
-                            .send(Ok(QueryEvent::RecordsUpdated(records_number)))
-                            .expect("To Send Query Result to Client");
-                    }
-                }
+        if !check_foreign_keys(
+            &self.storage,
+            self.session_id,
+            schema_id,
+            table_id,
+            &table_name,
+            &updated,
+            self.sender.as_ref(),
+        )? {
+            return Ok(());
+        }
+        if !check_referenced_keys(
+            &self.storage,
+            self.session_id,
+            schema_id,
+            table_id,
+            &table_name,
+            &replaced,
+            &updated,
+            self.sender.as_ref(),
+        )? {
+            return Ok(());
+        }
+        if dry_run {
+            return Ok(());
+        }
+
+        let records_number = self.storage.write_into(self.session_id, schema_id, table_id, updated)?;
+        self.storage
+            .count_changes(schema_id, table_id, TableChange::Updated, records_number);
+        for (old, new) in changed {
+            if !triggers.after(Some(&old), Some(&new))? {
+                return Ok(());
             }
         }
+        self.sender
+            .send(Ok(QueryEvent::RecordsUpdated(records_number)))
+            .expect("To Send Query Result to Client");
         Ok(())
     }
 }
+
+pub(crate) fn from_clause_query(from: Vec<TableWithJoins>) -> Query {
+    Query {
+        ctes: vec![],
+        body: SetExpr::Select(Box::new(Select {
+            distinct: false,
+            top: None,
+            projection: vec![SelectItem::Wildcard],
+            from,
+            selection: None,
+            group_by: vec![],
+            having: None,
+        })),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        fetch: None,
+    }
+}
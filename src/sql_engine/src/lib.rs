@@ -15,15 +15,38 @@
 extern crate bigdecimal;
 extern crate log;
 
+pub use crate::{cache::QueryCache, dml::insert::InsertMode, notification::Notifications};
+
 use crate::{
+    dcl::{create_user::CreateUserCommand, drop_user::DropUserCommand, grant::GrantCommand, revoke::RevokeCommand},
     ddl::{
-        create_schema::CreateSchemaCommand, create_table::CreateTableCommand, drop_schema::DropSchemaCommand,
-        drop_table::DropTableCommand,
+        alter_schema::AlterSchemaCommand, alter_table::AlterTableCommand, attach_partition::AttachPartitionCommand,
+        copy_table::CopyTableCommand, create_function::CreateFunctionCommand, create_partition::CreatePartitionCommand,
+        create_schema::CreateSchemaCommand, create_table::CreateTableCommand, create_trigger::CreateTriggerCommand,
+        detach_partition::DetachPartitionCommand, drop_function::DropFunctionCommand, drop_schema::DropSchemaCommand,
+        drop_table::DropTableCommand, drop_trigger::DropTriggerCommand, enable_trigger::EnableTriggerCommand,
+    },
+    dml::{
+        check_deferred_foreign_keys,
+        copy::{CopyFromCommand, CopyIn, CopyToCommand},
+        delete::DeleteCommand,
+        explain::ExplainCommand,
+        insert::InsertCommand,
+        merge::MergeCommand,
+        select::SelectCommand,
+        update::UpdateCommand,
+        NestedSender,
+    },
+    maintenance::{analyze::AnalyzeCommand, cluster::ClusterCommand, vacuum::VacuumCommand},
+    parser::Parsed,
+    query::{
+        bind::ParamBinder,
+        plan::{Plan, TableInserts},
+        process::QueryProcessor,
     },
-    dml::{delete::DeleteCommand, insert::InsertCommand, select::SelectCommand, update::UpdateCommand},
-    query::{bind::ParamBinder, plan::Plan, process::QueryProcessor},
+    tcl::lock_table::LockTableCommand,
 };
-use data_manager::DataManager;
+use data_manager::{DataManager, SessionId};
 use itertools::izip;
 use kernel::SystemResult;
 use protocol::{
@@ -32,46 +55,574 @@ use protocol::{
     sql_formats::PostgreSqlFormat,
     sql_types::PostgreSqlType,
     sql_values::PostgreSqlValue,
-    statement::PreparedStatement,
+    statement::{Cursor, PreparedStatement},
     Sender,
 };
 use sqlparser::{
-    ast::Statement,
+    ast::{AlterTableOperation, SetVariableValue, Statement, Value},
     dialect::{Dialect, PostgreSqlDialect},
     parser::Parser,
 };
-use std::{iter, sync::Arc};
+use std::{
+    future::Future,
+    iter, mem,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+const STATEMENT_MAX_ROWS: &str = "statement_max_rows";
+const STATEMENT_TIMEOUT: &str = "statement_timeout";
+const INSERT_MODE: &str = "insert_mode";
 
+mod cache;
+mod dcl;
 mod ddl;
 mod dml;
+mod maintenance;
+mod notification;
+mod parser;
 mod query;
+mod tcl;
 
 pub struct QueryExecutor {
+    session_id: SessionId,
     storage: Arc<DataManager>,
     sender: Arc<dyn Sender>,
     session: Session<Statement>,
     processor: QueryProcessor,
     param_binder: ParamBinder,
+    cache: Option<Arc<QueryCache>>,
+    notifications: Arc<Notifications>,
+    insert_mode: InsertMode,
+    copy_in: Option<CopyIn>,
+    copy_directory: Option<PathBuf>,
+    query_timeout: Option<Duration>,
 }
 
 impl QueryExecutor {
     pub fn new(storage: Arc<DataManager>, sender: Arc<dyn Sender>) -> Self {
+        let session_id = storage.open_session();
         Self {
+            session_id,
             storage: storage.clone(),
             sender: sender.clone(),
             session: Session::default(),
-            processor: QueryProcessor::new(storage, sender.clone()),
+            processor: QueryProcessor::new(session_id, storage, sender.clone()),
             param_binder: ParamBinder::new(sender),
+            cache: None,
+            notifications: Arc::new(Notifications::default()),
+            insert_mode: InsertMode::default(),
+            copy_in: None,
+            copy_directory: None,
+            query_timeout: None,
         }
     }
 
+    /// the mode is used by INSERTs of the session until it is changed by `SET insert_mode`
+    pub fn set_insert_mode(&mut self, mode: InsertMode) {
+        self.insert_mode = mode;
+    }
+
+    /// results of read only queries are shared through the `cache` with other sessions that use it
+    pub fn set_query_cache(&mut self, cache: Arc<QueryCache>) {
+        self.cache = Some(cache);
+    }
+
+    /// notifications are delivered between sessions that share the `notifications` registry
+    pub fn set_notifications(&mut self, notifications: Arc<Notifications>) {
+        self.notifications.unlisten(None, self.session_id);
+        self.notifications = notifications;
+    }
+
+    /// `COPY FROM` a file on the server reads only files inside of the `directory`
+    pub fn set_copy_directory(&mut self, directory: PathBuf) {
+        self.copy_directory = Some(directory);
+    }
+
+    /// queries of the connection are canceled when they run longer than the `timeout`, zero duration
+    /// removes the limit, `SET statement_timeout` overrides it until it is set to `DEFAULT`
+    pub fn set_query_timeout(&mut self, timeout: Duration) {
+        self.query_timeout = Some(timeout).filter(|timeout| !timeout.is_zero());
+        self.session.set_statement_timeout(self.query_timeout);
+    }
+
+    pub fn set_session_user(&mut self, user_name: &str) {
+        self.storage.set_session_user(self.session_id, user_name);
+    }
+
+    /// errors of the query are sent to the client, `Err` is returned only when the database fails
     #[allow(clippy::match_wild_err_arm)]
     pub fn execute(&mut self, raw_sql_query: &str) -> SystemResult<()> {
-        let statement = match Parser::parse_sql(&PostgreSqlDialect {}, raw_sql_query) {
-            Ok(mut statements) => {
-                log::info!("stmts: {:#?}", statements);
-                statements.pop().unwrap()
+        // the previous query could be canceled after it was completed
+        self.storage.start_query(self.session_id);
+        let parsed = match parser::parse(&PostgreSqlDialect {}, raw_sql_query) {
+            Ok(parsed) => parsed,
+            // query is completed with the error, the client could send the next one
+            Err(e) => {
+                log::error!("{:?} can't be parsed. Error: {:?}", raw_sql_query, e);
+                self.sender
+                    .send(Err(QueryError::syntax_error(format!(
+                        "{:?} can't be parsed",
+                        raw_sql_query
+                    ))))
+                    .expect("To Send Query Result to Client");
+                self.sender
+                    .send(Ok(QueryEvent::QueryComplete))
+                    .expect("To Send Query Complete Event to Client");
+                return Ok(());
+            }
+        };
+
+        match parsed {
+            Parsed::Statement(statement) => self.process_statement(raw_sql_query, statement)?,
+            Parsed::UpdateFrom {
+                table_name,
+                assignments,
+                from,
+                selection,
+            } => UpdateCommand::new(
+                raw_sql_query,
+                table_name,
+                assignments,
+                from,
+                selection,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::DeleteUsing {
+                table_name,
+                using,
+                selection,
+                returning,
+            } => DeleteCommand::new(
+                raw_sql_query,
+                table_name,
+                using,
+                selection,
+                returning,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::Merge {
+                table_name,
+                alias,
+                source,
+                on,
+                clauses,
+            } => MergeCommand::new(
+                raw_sql_query,
+                table_name,
+                alias,
+                source,
+                on,
+                clauses,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::CreateTemporaryTable(statement) => {
+                if let Ok(Plan::CreateTable(creation_info)) = self.processor.process_temporary_table(statement) {
+                    CreateTableCommand::new(
+                        creation_info,
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .execute()?;
+                }
+            }
+            Parsed::CreatePartitionedTable {
+                statement,
+                strategy,
+                key,
+            } => match self.processor.process(statement) {
+                Ok(Plan::CreateTable(creation_info)) => {
+                    CreateTableCommand::new(
+                        creation_info,
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .with_partition_key(strategy, key)
+                    .execute()?;
+                }
+                Ok(_) => self
+                    .sender
+                    .send(Err(QueryError::feature_not_supported(
+                        "partitioned CREATE TABLE AS".to_owned(),
+                    )))
+                    .expect("To Send Query Result to Client"),
+                Err(()) => {}
+            },
+            Parsed::CreatePartition {
+                table_name,
+                parent,
+                bound,
+            } => CreatePartitionCommand::new(
+                table_name,
+                parent,
+                bound,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::AttachPartition {
+                table_name,
+                partition,
+                bound,
+            } => AttachPartitionCommand::new(
+                table_name,
+                partition,
+                bound,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::DetachPartition { table_name, partition } => DetachPartitionCommand::new(
+                table_name,
+                partition,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::InsertOnConflict { statement, on_conflict } => {
+                if let Ok(Plan::Insert(table_inserts)) = self.processor.process(statement) {
+                    InsertCommand::new(
+                        raw_sql_query,
+                        TableInserts {
+                            on_conflict,
+                            ..table_inserts
+                        },
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .with_mode(self.insert_mode)
+                    .execute()?;
+                }
+            }
+            Parsed::CreateUser { name, superuser } => CreateUserCommand::new(
+                name,
+                superuser,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::DropUser { name, if_exists } => DropUserCommand::new(
+                name,
+                if_exists,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::Grant {
+                privileges,
+                table_name,
+                grantee,
+                with_grant_option,
+            } => GrantCommand::new(
+                privileges,
+                table_name,
+                grantee,
+                with_grant_option,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::Revoke {
+                privileges,
+                table_name,
+                grantee,
+            } => RevokeCommand::new(
+                privileges,
+                table_name,
+                grantee,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::CreateFunction {
+                name,
+                args,
+                return_type,
+                body,
+            } => CreateFunctionCommand::new(name, args, return_type, body, self.storage.clone(), self.sender.clone())
+                .execute()?,
+            Parsed::DropFunction { names, if_exists } => {
+                DropFunctionCommand::new(names, if_exists, self.storage.clone(), self.sender.clone()).execute()?
+            }
+            Parsed::CreateTrigger {
+                name,
+                timing,
+                events,
+                table_name,
+                function,
+            } => CreateTriggerCommand::new(
+                name,
+                timing,
+                events,
+                table_name,
+                function,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::DropTrigger {
+                name,
+                table_name,
+                if_exists,
+            } => DropTriggerCommand::new(
+                name,
+                table_name,
+                if_exists,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::EnableTrigger {
+                table_name,
+                name,
+                enabled,
+            } => EnableTriggerCommand::new(
+                table_name,
+                name,
+                enabled,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::ExplainSyntax { query } => self.explain_syntax(&query)?,
+            Parsed::Listen { channel } => {
+                self.notifications.listen(channel, self.session_id, self.sender.clone());
+                self.sender
+                    .send(Ok(QueryEvent::ChannelListened))
+                    .expect("To Send Query Result to Client");
+            }
+            Parsed::Unlisten { channel } => {
+                self.notifications.unlisten(channel.as_deref(), self.session_id);
+                self.sender
+                    .send(Ok(QueryEvent::ChannelUnlistened))
+                    .expect("To Send Query Result to Client");
             }
+            Parsed::Notify { channel, payload } => {
+                self.sender
+                    .send(Ok(QueryEvent::NotificationSent))
+                    .expect("To Send Query Result to Client");
+                self.notifications.notify(self.session_id, &channel, &payload);
+            }
+            Parsed::DeclareCursor { name, query } => {
+                if self.session.get_cursor_mut(&name).is_some() {
+                    self.sender
+                        .send(Err(QueryError::cursor_already_exists(name)))
+                        .expect("To Send Query Result to Client");
+                } else if let Some(projection) = SelectCommand::new(
+                    raw_sql_query,
+                    query,
+                    self.session_id,
+                    self.storage.clone(),
+                    self.sender.clone(),
+                    None,
+                )
+                .with_timeout(self.session.statement_timeout())
+                .collect()?
+                {
+                    // rows are read once thus the cursor is not affected by later changes of the tables
+                    self.session.declare_cursor(name, Cursor::new(projection));
+                    self.sender
+                        .send(Ok(QueryEvent::CursorDeclared))
+                        .expect("To Send Query Result to Client");
+                }
+            }
+            Parsed::Fetch { name, count } => match self.session.get_cursor_mut(&name) {
+                Some(cursor) => {
+                    let projection = cursor.fetch(count.map(|count| count as usize));
+                    self.sender
+                        .send(Ok(QueryEvent::RecordsFetched(projection)))
+                        .expect("To Send Query Result to Client");
+                }
+                None => self
+                    .sender
+                    .send(Err(QueryError::cursor_does_not_exist(name)))
+                    .expect("To Send Query Result to Client"),
+            },
+            Parsed::CloseCursor { name: None } => {
+                self.session.close_all_cursors();
+                self.sender
+                    .send(Ok(QueryEvent::CursorClosed))
+                    .expect("To Send Query Result to Client");
+            }
+            Parsed::CloseCursor { name: Some(name) } => {
+                if self.session.close_cursor(&name) {
+                    self.sender
+                        .send(Ok(QueryEvent::CursorClosed))
+                        .expect("To Send Query Result to Client");
+                } else {
+                    self.sender
+                        .send(Err(QueryError::cursor_does_not_exist(name)))
+                        .expect("To Send Query Result to Client");
+                }
+            }
+            Parsed::RenameSchema { schema_name, new_name } => {
+                AlterSchemaCommand::new(schema_name, new_name, self.storage.clone(), self.sender.clone()).execute()?
+            }
+            Parsed::AddDeferrableForeignKey {
+                table_name,
+                constraint,
+                initially_deferred,
+            } => AlterTableCommand::new(
+                raw_sql_query,
+                table_name,
+                AlterTableOperation::AddConstraint(constraint),
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .with_deferrable(initially_deferred)
+            .execute()?,
+            Parsed::SetConstraints { deferred } => {
+                self.storage.set_constraints_deferred(self.session_id, deferred);
+                // postponed checks are run right away when constraints become immediate
+                if deferred || check_deferred_foreign_keys(&self.storage, self.session_id, self.sender.as_ref())? {
+                    self.sender
+                        .send(Ok(QueryEvent::ConstraintsSet))
+                        .expect("To Send Query Result to Client");
+                }
+            }
+            Parsed::LockTable { tables, mode, nowait } => LockTableCommand::new(
+                tables,
+                mode,
+                nowait,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::Analyze { table_name } => {
+                AnalyzeCommand::new(table_name, self.session_id, self.storage.clone(), self.sender.clone()).execute()?
+            }
+            Parsed::Cluster { table_name, index_name } => ClusterCommand::new(
+                table_name,
+                index_name,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::Vacuum {
+                table_name,
+                full,
+                analyze,
+            } => VacuumCommand::new(
+                table_name,
+                full,
+                analyze,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::CopyFrom {
+                table_name,
+                columns,
+                file: Some(file),
+                format,
+                on_error,
+                header,
+            } => CopyFromCommand::new(
+                table_name,
+                columns,
+                format,
+                on_error,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .with_header(header)
+            .execute_file(&file, self.copy_directory.as_deref())?,
+            Parsed::CopyFrom {
+                table_name,
+                columns,
+                file: None,
+                format,
+                on_error,
+                header,
+            } => {
+                self.copy_in = CopyFromCommand::new(
+                    table_name,
+                    columns,
+                    format,
+                    on_error,
+                    self.session_id,
+                    self.storage.clone(),
+                    self.sender.clone(),
+                )
+                .with_header(header)
+                .execute()?;
+                // the query is complete when the client sends all rows
+                if self.copy_in.is_some() {
+                    return Ok(());
+                }
+            }
+            Parsed::CopyTo {
+                table_name,
+                columns,
+                format,
+                header,
+            } => CopyToCommand::new(
+                table_name,
+                columns,
+                format,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .with_header(header)
+            .execute()?,
+            Parsed::ExplainAnalyze { query } => ExplainCommand::new(
+                raw_sql_query,
+                query,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .execute()?,
+            Parsed::Empty => {
+                self.sender
+                    .send(Ok(QueryEvent::EmptyQueryResponse))
+                    .expect("To Send Query Result to Client");
+            }
+        }
+        self.release_statement_locks();
+
+        self.sender
+            .send(Ok(QueryEvent::QueryComplete))
+            .expect("To Send Query Complete Event to Client");
+
+        Ok(())
+    }
+
+    /// checks names and types used by the query the same way `execute` does,
+    /// however storage is not modified and only errors are sent to the client
+    #[allow(clippy::match_wild_err_arm)]
+    pub fn validate(&mut self, raw_sql_query: &str) -> SystemResult<()> {
+        let parsed = match parser::parse(&PostgreSqlDialect {}, raw_sql_query) {
+            Ok(parsed) => parsed,
             Err(e) => {
                 log::error!("{:?} can't be parsed. Error: {:?}", raw_sql_query, e);
                 self.sender
@@ -84,7 +635,198 @@ impl QueryExecutor {
             }
         };
 
-        self.process_statement(raw_sql_query, statement)?;
+        match parsed {
+            Parsed::Statement(statement) => self.validate_statement(raw_sql_query, statement)?,
+            Parsed::UpdateFrom {
+                table_name,
+                assignments,
+                from,
+                selection,
+            } => UpdateCommand::new(
+                raw_sql_query,
+                table_name,
+                assignments,
+                from,
+                selection,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .validate()?,
+            Parsed::DeleteUsing {
+                table_name,
+                using,
+                selection,
+                returning,
+            } => DeleteCommand::new(
+                raw_sql_query,
+                table_name,
+                using,
+                selection,
+                returning,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .validate()?,
+            Parsed::Merge {
+                table_name,
+                alias,
+                source,
+                on,
+                clauses,
+            } => MergeCommand::new(
+                raw_sql_query,
+                table_name,
+                alias,
+                source,
+                on,
+                clauses,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .validate()?,
+            Parsed::CreateTemporaryTable(statement) => {
+                let _plan = self.processor.process_temporary_table(statement);
+            }
+            Parsed::CreatePartitionedTable { statement, .. } => {
+                let _plan = self.processor.process(statement);
+            }
+            Parsed::InsertOnConflict { statement, on_conflict } => {
+                if let Ok(Plan::Insert(table_inserts)) = self.processor.process(statement) {
+                    InsertCommand::new(
+                        raw_sql_query,
+                        TableInserts {
+                            on_conflict,
+                            ..table_inserts
+                        },
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .with_mode(self.insert_mode)
+                    .validate()?;
+                }
+            }
+            Parsed::CopyFrom {
+                table_name,
+                columns,
+                format,
+                on_error,
+                ..
+            } => CopyFromCommand::new(
+                table_name,
+                columns,
+                format,
+                on_error,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .validate()?,
+            Parsed::CopyTo {
+                table_name,
+                columns,
+                format,
+                ..
+            } => CopyToCommand::new(
+                table_name,
+                columns,
+                format,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .validate()?,
+            Parsed::ExplainAnalyze { query } => ExplainCommand::new(
+                raw_sql_query,
+                query,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+            )
+            .validate()?,
+            Parsed::DeclareCursor { query, .. } => SelectCommand::new(
+                raw_sql_query,
+                query,
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+                None,
+            )
+            .validate()?,
+            Parsed::CreateUser { .. }
+            | Parsed::DropUser { .. }
+            | Parsed::Grant { .. }
+            | Parsed::Revoke { .. }
+            | Parsed::CreateFunction { .. }
+            | Parsed::DropFunction { .. }
+            | Parsed::CreateTrigger { .. }
+            | Parsed::DropTrigger { .. }
+            | Parsed::EnableTrigger { .. }
+            | Parsed::ExplainSyntax { .. }
+            | Parsed::Listen { .. }
+            | Parsed::Unlisten { .. }
+            | Parsed::Notify { .. }
+            | Parsed::Fetch { .. }
+            | Parsed::CloseCursor { .. }
+            | Parsed::LockTable { .. }
+            | Parsed::RenameSchema { .. }
+            | Parsed::CreatePartition { .. }
+            | Parsed::AttachPartition { .. }
+            | Parsed::DetachPartition { .. }
+            | Parsed::AddDeferrableForeignKey { .. }
+            | Parsed::SetConstraints { .. }
+            | Parsed::Analyze { .. }
+            | Parsed::Cluster { .. }
+            | Parsed::Vacuum { .. } => {}
+            Parsed::Empty => {}
+        }
+        self.release_statement_locks();
+
+        self.sender
+            .send(Ok(QueryEvent::QueryComplete))
+            .expect("To Send Query Complete Event to Client");
+
+        Ok(())
+    }
+
+    /// buffers data of `COPY FROM STDIN` that is in progress, otherwise data is ignored
+    pub fn copy_data(&mut self, data: &[u8]) {
+        if let Some(copy_in) = self.copy_in.as_mut() {
+            copy_in.receive(data);
+        }
+    }
+
+    /// writes rows received by `COPY FROM STDIN` into the table and completes the query
+    pub fn copy_done(&mut self) -> SystemResult<()> {
+        let copy_in = match self.copy_in.take() {
+            Some(copy_in) => copy_in,
+            None => return Ok(()),
+        };
+        copy_in.finish()?;
+        self.release_statement_locks();
+
+        self.sender
+            .send(Ok(QueryEvent::QueryComplete))
+            .expect("To Send Query Complete Event to Client");
+
+        Ok(())
+    }
+
+    /// aborts `COPY FROM STDIN`, none of the received rows is written
+    pub fn copy_fail(&mut self, message: &str) -> SystemResult<()> {
+        if self.copy_in.take().is_none() {
+            return Ok(());
+        }
+        self.sender
+            .send(Err(QueryError::query_canceled(format!(
+                "COPY from stdin failed: {}",
+                message
+            ))))
+            .expect("To Send Query Result to Client");
+        self.release_statement_locks();
 
         self.sender
             .send(Ok(QueryEvent::QueryComplete))
@@ -93,6 +835,34 @@ impl QueryExecutor {
         Ok(())
     }
 
+    /// executes query on a thread of the blocking pool, thus storage I/O doesn't block async executor.
+    /// Queries sent to the same executor are executed one after another
+    pub fn execute_async(
+        executor: Arc<Mutex<QueryExecutor>>,
+        raw_sql_query: String,
+    ) -> impl Future<Output = SystemResult<()>> {
+        blocking::unblock(move || {
+            executor
+                .lock()
+                .expect("to acquire query executor lock")
+                .execute(raw_sql_query.as_str())
+        })
+    }
+
+    /// executes semicolon separated statements one by one, an error in a statement
+    /// is reported to the client and doesn't prevent execution of the next statements.
+    /// Query string without any statement is responded as an empty query
+    pub fn execute_batch(&mut self, raw_sql: &str) -> SystemResult<()> {
+        let statements = parser::split_statements(raw_sql);
+        if statements.is_empty() {
+            return self.execute(raw_sql);
+        }
+        for raw_sql_query in statements {
+            self.execute(raw_sql_query.trim())?;
+        }
+        Ok(())
+    }
+
     pub fn parse_prepared_statement(
         &mut self,
         statement_name: &str,
@@ -117,10 +887,15 @@ impl QueryExecutor {
         };
 
         let description = match &statement {
-            Statement::Query(query) => {
-                SelectCommand::new(raw_sql_query, query.clone(), self.storage.clone(), self.sender.clone())
-                    .describe()?
-            }
+            Statement::Query(query) => SelectCommand::new(
+                raw_sql_query,
+                query.clone(),
+                self.session_id,
+                self.storage.clone(),
+                self.sender.clone(),
+                None,
+            )
+            .describe()?,
             _ => vec![],
         };
 
@@ -247,6 +1022,7 @@ impl QueryExecutor {
 
     // TODO: Parameter `max_rows` should be handled.
     pub fn execute_portal(&mut self, portal_name: &str, _max_rows: i32) -> SystemResult<()> {
+        self.storage.start_query(self.session_id);
         let portal = match self.session.get_portal(portal_name) {
             Some(portal) => portal,
             None => {
@@ -259,7 +1035,16 @@ impl QueryExecutor {
 
         let statement = portal.stmt();
         let raw_sql_query = format!("{}", statement);
-        self.process_statement(&raw_sql_query, statement.clone())
+        self.process_statement(&raw_sql_query, statement.clone())?;
+        self.release_statement_locks();
+        Ok(())
+    }
+
+    // locks acquired outside of a transaction block are held only while a statement is executed
+    fn release_statement_locks(&self) {
+        if !self.session.in_transaction() {
+            self.storage.release_locks(self.session_id);
+        }
     }
 
     pub fn flush(&self) {
@@ -271,14 +1056,24 @@ impl QueryExecutor {
         };
     }
 
-    fn process_statement(&self, raw_sql_query: &str, statement: Statement) -> SystemResult<()> {
+    fn process_statement(&mut self, raw_sql_query: &str, statement: Statement) -> SystemResult<()> {
         log::debug!("STATEMENT = {:?}", statement);
         match self.processor.process(statement) {
             Ok(Plan::CreateSchema(creation_info)) => {
                 CreateSchemaCommand::new(creation_info, self.storage.clone(), self.sender.clone()).execute()?;
             }
             Ok(Plan::CreateTable(creation_info)) => {
-                CreateTableCommand::new(creation_info, self.storage.clone(), self.sender.clone()).execute()?;
+                CreateTableCommand::new(
+                    creation_info,
+                    self.session_id,
+                    self.storage.clone(),
+                    self.sender.clone(),
+                )
+                .execute()?;
+            }
+            Ok(Plan::CopyTable(copy_info)) => {
+                CopyTableCommand::new(copy_info, self.session_id, self.storage.clone(), self.sender.clone())
+                    .execute()?;
             }
             Ok(Plan::DropSchemas(schemas)) => {
                 for (schema, cascade) in schemas {
@@ -286,20 +1081,74 @@ impl QueryExecutor {
                 }
             }
             Ok(Plan::DropTables(tables)) => {
-                for table in tables {
-                    DropTableCommand::new(table, self.storage.clone(), self.sender.clone()).execute()?;
+                for (table, cascade) in tables {
+                    DropTableCommand::new(
+                        table,
+                        cascade,
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .execute()?;
                 }
             }
             Ok(Plan::Insert(table_insert)) => {
-                InsertCommand::new(raw_sql_query, table_insert, self.storage.clone(), self.sender.clone()).execute()?;
+                InsertCommand::new(
+                    raw_sql_query,
+                    table_insert,
+                    self.session_id,
+                    self.storage.clone(),
+                    self.sender.clone(),
+                )
+                .with_mode(self.insert_mode)
+                .execute()?;
             }
             Ok(Plan::NotProcessed(statement)) => match *statement {
                 Statement::StartTransaction { .. } => {
+                    self.session.set_in_transaction(true);
+                    self.storage.begin_transaction(self.session_id);
                     self.sender
                         .send(Ok(QueryEvent::TransactionStarted))
                         .expect("To Send Query Result to Client");
                 }
-                Statement::SetVariable { .. } => {
+                Statement::Commit { .. } => {
+                    // the transaction is kept open when its deferred foreign keys are violated
+                    if !check_deferred_foreign_keys(&self.storage, self.session_id, self.sender.as_ref())? {
+                        return Ok(());
+                    }
+                    self.session.set_in_transaction(false);
+                    self.storage.commit_transaction(self.session_id);
+                    self.storage.release_locks(self.session_id);
+                    self.sender
+                        .send(Ok(QueryEvent::TransactionCommitted))
+                        .expect("To Send Query Result to Client");
+                }
+                Statement::SetVariable { variable, value, .. } => {
+                    if variable.value.to_lowercase() == STATEMENT_MAX_ROWS {
+                        match statement_max_rows(&value) {
+                            Ok(max_rows) => self.session.set_statement_max_rows(max_rows),
+                            Err(error) => {
+                                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                                return Ok(());
+                            }
+                        }
+                    } else if variable.value.to_lowercase() == STATEMENT_TIMEOUT {
+                        match statement_timeout(&value, self.query_timeout) {
+                            Ok(timeout) => self.session.set_statement_timeout(timeout),
+                            Err(error) => {
+                                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                                return Ok(());
+                            }
+                        }
+                    } else if variable.value.to_lowercase() == INSERT_MODE {
+                        match insert_mode(&value) {
+                            Ok(mode) => self.insert_mode = mode,
+                            Err(error) => {
+                                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                                return Ok(());
+                            }
+                        }
+                    }
                     self.sender
                         .send(Ok(QueryEvent::VariableSet))
                         .expect("To Send Query Result to Client");
@@ -309,18 +1158,59 @@ impl QueryExecutor {
                         .send(Err(QueryError::feature_not_supported(raw_sql_query.to_owned())))
                         .expect("To Send Query Result to Client");
                 }
+                Statement::AlterTable { name, operation } => {
+                    AlterTableCommand::new(
+                        raw_sql_query,
+                        name,
+                        operation,
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .execute()?;
+                }
                 Statement::Query(query) => {
-                    SelectCommand::new(raw_sql_query, query, self.storage.clone(), self.sender.clone()).execute()?;
+                    SelectCommand::new(
+                        raw_sql_query,
+                        query,
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                        self.session.statement_max_rows(),
+                    )
+                    .with_cache(self.cache.clone())
+                    .with_timeout(self.session.statement_timeout())
+                    .execute()?;
                 }
                 Statement::Update {
                     table_name,
                     assignments,
-                    ..
+                    selection,
                 } => {
-                    UpdateCommand::new(table_name, assignments, self.storage.clone(), self.sender.clone()).execute()?;
+                    UpdateCommand::new(
+                        raw_sql_query,
+                        table_name,
+                        assignments,
+                        vec![],
+                        selection,
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .execute()?;
                 }
-                Statement::Delete { table_name, .. } => {
-                    DeleteCommand::new(table_name, self.storage.clone(), self.sender.clone()).execute()?;
+                Statement::Delete { table_name, selection } => {
+                    DeleteCommand::new(
+                        raw_sql_query,
+                        table_name,
+                        vec![],
+                        selection,
+                        vec![],
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .execute()?;
                 }
                 _ => {
                     self.sender
@@ -333,6 +1223,109 @@ impl QueryExecutor {
 
         Ok(())
     }
+
+    // definitions are checked while a statement is planned, thus DDL is not executed at all
+    // the query is validated with a sender that records whether errors were reported to the client
+    fn explain_syntax(&mut self, raw_sql_query: &str) -> SystemResult<()> {
+        let checking = Arc::new(NestedSender::new(self.sender.clone()));
+        let sender = mem::replace(&mut self.sender, checking.clone());
+        self.processor = QueryProcessor::new(self.session_id, self.storage.clone(), checking.clone());
+        let validated = self.validate(raw_sql_query);
+        self.sender = sender;
+        self.processor = QueryProcessor::new(self.session_id, self.storage.clone(), self.sender.clone());
+        validated?;
+
+        if !checking.failed() {
+            self.sender
+                .send(Ok(QueryEvent::SyntaxValid))
+                .expect("To Send Query Result to Client");
+        }
+        Ok(())
+    }
+
+    fn validate_statement(&self, raw_sql_query: &str, statement: Statement) -> SystemResult<()> {
+        match self.processor.process(statement) {
+            Ok(Plan::Insert(table_insert)) => {
+                InsertCommand::new(
+                    raw_sql_query,
+                    table_insert,
+                    self.session_id,
+                    self.storage.clone(),
+                    self.sender.clone(),
+                )
+                .with_mode(self.insert_mode)
+                .validate()?;
+            }
+            Ok(Plan::NotProcessed(statement)) => match *statement {
+                Statement::StartTransaction { .. }
+                | Statement::Commit { .. }
+                | Statement::SetVariable { .. }
+                | Statement::AlterTable { .. } => {}
+                Statement::Query(query) => {
+                    SelectCommand::new(
+                        raw_sql_query,
+                        query,
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                        None,
+                    )
+                    .validate()?;
+                }
+                Statement::Update {
+                    table_name,
+                    assignments,
+                    selection,
+                } => {
+                    UpdateCommand::new(
+                        raw_sql_query,
+                        table_name,
+                        assignments,
+                        vec![],
+                        selection,
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .validate()?;
+                }
+                Statement::Delete { table_name, selection } => {
+                    DeleteCommand::new(
+                        raw_sql_query,
+                        table_name,
+                        vec![],
+                        selection,
+                        vec![],
+                        self.session_id,
+                        self.storage.clone(),
+                        self.sender.clone(),
+                    )
+                    .validate()?;
+                }
+                _ => {
+                    self.sender
+                        .send(Err(QueryError::feature_not_supported(raw_sql_query.to_owned())))
+                        .expect("To Send Query Result to Client");
+                }
+            },
+            Ok(_) | Err(()) => {}
+        };
+
+        Ok(())
+    }
+}
+
+impl Drop for QueryExecutor {
+    fn drop(&mut self) {
+        self.notifications.unlisten(None, self.session_id);
+        if let Err(error) = self.storage.close_session(self.session_id) {
+            log::error!(
+                "temporary tables of session {} were not dropped: {:?}",
+                self.session_id,
+                error
+            );
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -348,6 +1341,77 @@ impl Dialect for PreparedStatementDialect {
     }
 }
 
+// `0` or `DEFAULT` removes the limit
+fn statement_max_rows(value: &SetVariableValue) -> Result<Option<usize>, QueryError> {
+    match value {
+        SetVariableValue::Ident(ident) if ident.value.to_lowercase() == "default" => Ok(None),
+        SetVariableValue::Literal(Value::Number(number)) => match number.to_string().parse::<usize>() {
+            Ok(0) => Ok(None),
+            Ok(max_rows) => Ok(Some(max_rows)),
+            Err(_) => Err(QueryError::invalid_parameter_value(format!(
+                "invalid value for parameter \"{}\": \"{}\"",
+                STATEMENT_MAX_ROWS, number
+            ))),
+        },
+        value => Err(QueryError::invalid_parameter_value(format!(
+            "invalid value for parameter \"{}\": \"{}\"",
+            STATEMENT_MAX_ROWS, value
+        ))),
+    }
+}
+
+// a number is in milliseconds, a string could have one of `ms`, `s`, `min`, `h` or `d` units,
+// `0` removes the limit and `DEFAULT` restores the timeout of the connection
+fn statement_timeout(value: &SetVariableValue, default: Option<Duration>) -> Result<Option<Duration>, QueryError> {
+    let invalid = || {
+        QueryError::invalid_parameter_value(format!(
+            "invalid value for parameter \"{}\": \"{}\"",
+            STATEMENT_TIMEOUT, value
+        ))
+    };
+    let (number, unit) = match value {
+        SetVariableValue::Ident(ident) if ident.value.to_lowercase() == "default" => return Ok(default),
+        SetVariableValue::Literal(Value::Number(number)) => (number.to_string(), "ms".to_owned()),
+        SetVariableValue::Literal(Value::SingleQuotedString(timeout)) => {
+            let timeout = timeout.trim();
+            let split = timeout.find(|c: char| !c.is_ascii_digit()).unwrap_or(timeout.len());
+            let (number, unit) = timeout.split_at(split);
+            (number.to_owned(), unit.trim().to_lowercase())
+        }
+        _ => return Err(invalid()),
+    };
+    let number = number.parse::<u64>().map_err(|_| invalid())?;
+    let millis = match unit.as_str() {
+        "" | "ms" => 1,
+        "s" => 1_000,
+        "min" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        _ => return Err(invalid()),
+    };
+    match number.checked_mul(millis) {
+        Some(0) => Ok(None),
+        Some(timeout) => Ok(Some(Duration::from_millis(timeout))),
+        None => Err(invalid()),
+    }
+}
+
+fn insert_mode(value: &SetVariableValue) -> Result<InsertMode, QueryError> {
+    let mode = match value {
+        SetVariableValue::Ident(ident) => ident.value.to_lowercase(),
+        SetVariableValue::Literal(Value::SingleQuotedString(mode)) => mode.to_lowercase(),
+        value => value.to_string(),
+    };
+    match mode.as_str() {
+        "default" | "strict" => Ok(InsertMode::Strict),
+        "best_effort" => Ok(InsertMode::BestEffort),
+        _ => Err(QueryError::invalid_parameter_value(format!(
+            "invalid value for parameter \"{}\": \"{}\"",
+            INSERT_MODE, value
+        ))),
+    }
+}
+
 fn pad_formats(formats: &[PostgreSqlFormat], param_len: usize) -> Result<Vec<PostgreSqlFormat>, String> {
     match (formats.len(), param_len) {
         (0, n) => Ok(vec![PostgreSqlFormat::Text; n]),
@@ -0,0 +1,106 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::maintenance::maintained_table;
+use data_manager::{ColumnStatistics, DataManager, LockMode, RecordId, SessionId};
+use kernel::SystemResult;
+use protocol::{results::QueryEvent, Sender};
+use representation::{unpack_raw, Datum};
+use sqlparser::ast::ObjectName;
+use std::{collections::BTreeSet, sync::Arc};
+
+pub(crate) struct AnalyzeCommand {
+    table_name: ObjectName,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl AnalyzeCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> AnalyzeCommand {
+        AnalyzeCommand {
+            table_name,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, table_id) =
+            match maintained_table(&self.table_name, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        self.storage
+            .lock_table(self.session_id, schema_id, table_id, LockMode::AccessShare);
+        collect_statistics(&self.storage, self.session_id, schema_id, table_id)?;
+
+        self.sender
+            .send(Ok(QueryEvent::AnalyzeComplete))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
+
+// previously collected statistics of the table are replaced
+pub(crate) fn collect_statistics(
+    storage: &DataManager,
+    session_id: SessionId,
+    schema_id: RecordId,
+    table_id: RecordId,
+) -> SystemResult<()> {
+    let columns = storage.table_columns(schema_id, table_id)?;
+    let rows = storage
+        .full_scan(session_id, schema_id, table_id)?
+        .map(Result::unwrap)
+        .map(Result::unwrap)
+        .map(|(_key, values)| values)
+        .collect::<Vec<_>>();
+
+    let mut values = vec![BTreeSet::new(); columns.len()];
+    let mut nulls = vec![0; columns.len()];
+    for row in rows.iter() {
+        for (index, datum) in unpack_raw(row.to_bytes()).into_iter().enumerate() {
+            match datum {
+                Datum::Null => nulls[index] += 1,
+                datum => {
+                    values[index].insert(datum);
+                }
+            }
+        }
+    }
+
+    let statistics = values
+        .into_iter()
+        .zip(nulls)
+        .map(|(values, nulls)| ColumnStatistics {
+            distinct: values.len(),
+            min: values.iter().next().map(Datum::to_string),
+            max: values.iter().next_back().map(Datum::to_string),
+            null_fraction: if rows.is_empty() {
+                0.0
+            } else {
+                nulls as f64 / rows.len() as f64
+            },
+        })
+        .collect();
+    storage.set_table_statistics(schema_id, table_id, statistics);
+    Ok(())
+}
@@ -0,0 +1,123 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::maintenance::maintained_table;
+use data_manager::{DataManager, LockMode, SessionId};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use representation::{unpack_raw, Binary, Datum};
+use sqlparser::ast::{Ident, ObjectName};
+use std::{cmp::Ordering, sync::Arc};
+
+pub(crate) struct ClusterCommand {
+    table_name: ObjectName,
+    index_name: Ident,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl ClusterCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        index_name: Ident,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> ClusterCommand {
+        ClusterCommand {
+            table_name,
+            index_name,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    // rows are written again with new keys in the order of the index, rows that are
+    // inserted later get greater keys and are not kept in the order
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, table_id) =
+            match maintained_table(&self.table_name, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        let table_name = self
+            .table_name
+            .0
+            .last()
+            .map(|ident| ident.value.clone())
+            .unwrap_or_default();
+        // unique columns are the only indexes of a table, they are named the same way as in PostgreSQL
+        let index = self
+            .storage
+            .table_columns(schema_id, table_id)?
+            .iter()
+            .position(|column| {
+                column.is_unique() && format!("{}_{}_key", table_name, column.name()) == self.index_name.value
+            });
+        let index = match index {
+            Some(index) => index,
+            None => {
+                self.sender
+                    .send(Err(QueryError::index_does_not_exist(
+                        self.index_name.value.clone(),
+                        table_name,
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        };
+        self.storage
+            .lock_table(self.session_id, schema_id, table_id, LockMode::AccessExclusive);
+
+        let mut rows = self
+            .storage
+            .full_scan(self.session_id, schema_id, table_id)?
+            .map(Result::unwrap)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        // NULLs are placed after other values the same way as in ascending index
+        rows.sort_by(|(_, left), (_, right)| {
+            match (
+                &unpack_raw(left.to_bytes())[index],
+                &unpack_raw(right.to_bytes())[index],
+            ) {
+                (Datum::Null, Datum::Null) => Ordering::Equal,
+                (Datum::Null, _) => Ordering::Greater,
+                (_, Datum::Null) => Ordering::Less,
+                (left, right) => left.cmp(right),
+            }
+        });
+
+        let keys = rows.iter().map(|(key, _)| key.clone()).collect();
+        self.storage.delete_from(self.session_id, schema_id, table_id, keys)?;
+        let rows = rows
+            .into_iter()
+            .map(|(_, values)| {
+                let key = self.storage.next_key_id(schema_id, table_id).to_be_bytes().to_vec();
+                (Binary::with_data(key), values)
+            })
+            .collect();
+        self.storage.write_into(self.session_id, schema_id, table_id, rows)?;
+
+        self.sender
+            .send(Ok(QueryEvent::TableClustered))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::query::{TableId, TableNamingError};
+use data_manager::{DataManager, RecordId, SessionId};
+use protocol::{results::QueryError, Sender};
+use sqlparser::ast::ObjectName;
+use std::convert::TryFrom;
+
+pub(crate) mod analyze;
+pub(crate) mod cluster;
+pub(crate) mod vacuum;
+
+// ids of the table visible in the session, sends an error when the table does not exist
+fn maintained_table(
+    table_name: &ObjectName,
+    session_id: SessionId,
+    storage: &DataManager,
+    sender: &dyn Sender,
+) -> Option<(RecordId, RecordId)> {
+    let table = match TableId::try_from(table_name.clone()) {
+        Ok(table) => table,
+        Err(TableNamingError(error)) => {
+            sender.send(Err(error)).expect("To Send Query Result to Client");
+            return None;
+        }
+    };
+    match storage.session_table_exists(session_id, table.schema_name(), table.name()) {
+        None => {
+            sender
+                .send(Err(QueryError::schema_does_not_exist(table.schema_name().to_owned())))
+                .expect("To Send Query Result to Client");
+            None
+        }
+        Some((_, None)) => {
+            sender
+                .send(Err(QueryError::table_does_not_exist(
+                    table.schema_name().to_owned() + "." + table.name(),
+                )))
+                .expect("To Send Query Result to Client");
+            None
+        }
+        Some((schema_id, Some(table_id))) => Some((schema_id, table_id)),
+    }
+}
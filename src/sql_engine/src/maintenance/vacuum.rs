@@ -0,0 +1,72 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::maintenance::{analyze::collect_statistics, maintained_table};
+use data_manager::{DataManager, LockMode, SessionId};
+use kernel::SystemResult;
+use protocol::{results::QueryEvent, Sender};
+use sqlparser::ast::ObjectName;
+use std::sync::Arc;
+
+pub(crate) struct VacuumCommand {
+    table_name: ObjectName,
+    full: bool,
+    analyze: bool,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl VacuumCommand {
+    pub(crate) fn new(
+        table_name: ObjectName,
+        full: bool,
+        analyze: bool,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> VacuumCommand {
+        VacuumCommand {
+            table_name,
+            full,
+            analyze,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    // DELETE removes rows from storage right away, thus there are no dead rows to reclaim yet
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let (schema_id, table_id) =
+            match maintained_table(&self.table_name, self.session_id, &self.storage, self.sender.as_ref()) {
+                Some(table) => table,
+                None => return Ok(()),
+            };
+        let mode = if self.full {
+            LockMode::AccessExclusive
+        } else {
+            LockMode::AccessShare
+        };
+        self.storage.lock_table(self.session_id, schema_id, table_id, mode);
+        if self.analyze {
+            collect_statistics(&self.storage, self.session_id, schema_id, table_id)?;
+        }
+
+        self.sender
+            .send(Ok(QueryEvent::VacuumComplete { rows_removed: 0 }))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use data_manager::SessionId;
+use protocol::{results::QueryEvent, Sender};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+type Listeners = HashMap<SessionId, Arc<dyn Sender>>;
+
+/// Sessions that listen to notification channels. Sessions that share the registry
+/// receive notifications sent by each other
+#[derive(Default)]
+pub struct Notifications {
+    channels: RwLock<HashMap<String, Listeners>>,
+}
+
+impl Notifications {
+    pub(crate) fn listen(&self, channel: String, session_id: SessionId, sender: Arc<dyn Sender>) {
+        self.channels
+            .write()
+            .expect("to acquire write lock")
+            .entry(channel)
+            .or_default()
+            .insert(session_id, sender);
+    }
+
+    /// stops listening to the channel, or to all channels when it is not specified
+    pub(crate) fn unlisten(&self, channel: Option<&str>, session_id: SessionId) {
+        let mut channels = self.channels.write().expect("to acquire write lock");
+        for (name, listeners) in channels.iter_mut() {
+            if channel.map(|channel| channel == name).unwrap_or(true) {
+                listeners.remove(&session_id);
+            }
+        }
+        channels.retain(|_, listeners| !listeners.is_empty());
+    }
+
+    /// sends the notification to every session that listens to the channel
+    pub(crate) fn notify(&self, session_id: SessionId, channel: &str, payload: &str) {
+        let channels = self.channels.read().expect("to acquire read lock");
+        if let Some(listeners) = channels.get(channel) {
+            for sender in listeners.values() {
+                sender
+                    .send(Ok(QueryEvent::Notification {
+                        pid: session_id as u32,
+                        channel: channel.to_owned(),
+                        payload: payload.to_owned(),
+                    }))
+                    .expect("To Send Notification to Client");
+                // listener could be idle and waiting for its next query
+                if let Err(error) = sender.flush() {
+                    log::error!("Flush error: {:?}", error);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,2263 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    ddl::{create_function::FunctionBody, create_partition::PartitionBound},
+    dml::{
+        copy::{CopyFormat, OnError},
+        merge::{MergeAction, MergeClause, MergeKind},
+    },
+    query::plan::OnConflict,
+};
+use data_manager::{LockMode, PartitionStrategy, Privilege, TriggerEvent, TriggerTiming};
+use sqlparser::{
+    ast::{
+        AlterTableOperation, Assignment, ColumnOption, DataType, Expr, Ident, ObjectName, Offset, OffsetRows, Query,
+        SelectItem, Statement, TableConstraint, TableFactor, TableWithJoins,
+    },
+    dialect::{
+        keywords::{self, Keyword},
+        Dialect,
+    },
+    parser::{IsOptional, Parser, ParserError},
+    tokenizer::{Token, Tokenizer, Whitespace},
+};
+
+/// Result of parsing. Besides everything that `sqlparser` understands it includes
+/// PostgreSQL statements that `sqlparser` is not able to parse yet.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Parsed {
+    Statement(Statement),
+    UpdateFrom {
+        table_name: ObjectName,
+        assignments: Vec<Assignment>,
+        from: Vec<TableWithJoins>,
+        selection: Option<Expr>,
+    },
+    DeleteUsing {
+        table_name: ObjectName,
+        using: Vec<TableWithJoins>,
+        selection: Option<Expr>,
+        returning: Vec<SelectItem>,
+    },
+    Merge {
+        table_name: ObjectName,
+        alias: Option<Ident>,
+        source: TableFactor,
+        on: Expr,
+        clauses: Vec<MergeClause>,
+    },
+    InsertOnConflict {
+        statement: Statement,
+        on_conflict: OnConflict,
+    },
+    CreateTemporaryTable(Statement),
+    /// rows of the table are stored in its partitions by ranges or lists of `key` column values
+    CreatePartitionedTable {
+        statement: Statement,
+        strategy: PartitionStrategy,
+        key: Ident,
+    },
+    /// partition stores rows whose key is within `bound`
+    CreatePartition {
+        table_name: ObjectName,
+        parent: ObjectName,
+        bound: PartitionBound,
+    },
+    AttachPartition {
+        table_name: ObjectName,
+        partition: ObjectName,
+        bound: PartitionBound,
+    },
+    DetachPartition {
+        table_name: ObjectName,
+        partition: ObjectName,
+    },
+    CreateUser {
+        name: String,
+        superuser: bool,
+    },
+    DropUser {
+        name: String,
+        if_exists: bool,
+    },
+    CreateFunction {
+        name: ObjectName,
+        args: Vec<(Ident, DataType)>,
+        return_type: DataType,
+        body: FunctionBody,
+    },
+    DropFunction {
+        names: Vec<ObjectName>,
+        if_exists: bool,
+    },
+    CreateTrigger {
+        name: Ident,
+        timing: TriggerTiming,
+        events: Vec<TriggerEvent>,
+        table_name: ObjectName,
+        function: ObjectName,
+    },
+    DropTrigger {
+        name: Ident,
+        table_name: ObjectName,
+        if_exists: bool,
+    },
+    EnableTrigger {
+        table_name: ObjectName,
+        name: Ident,
+        enabled: bool,
+    },
+    Grant {
+        privileges: Vec<Privilege>,
+        table_name: ObjectName,
+        grantee: String,
+        with_grant_option: bool,
+    },
+    Revoke {
+        privileges: Vec<Privilege>,
+        table_name: ObjectName,
+        grantee: String,
+    },
+    LockTable {
+        tables: Vec<ObjectName>,
+        mode: LockMode,
+        nowait: bool,
+    },
+    RenameSchema {
+        schema_name: ObjectName,
+        new_name: ObjectName,
+    },
+    AddDeferrableForeignKey {
+        table_name: ObjectName,
+        constraint: TableConstraint,
+        initially_deferred: bool,
+    },
+    SetConstraints {
+        deferred: bool,
+    },
+    Analyze {
+        table_name: ObjectName,
+    },
+    Cluster {
+        table_name: ObjectName,
+        index_name: Ident,
+    },
+    Vacuum {
+        table_name: ObjectName,
+        full: bool,
+        analyze: bool,
+    },
+    /// `None` file means that rows are sent by the client
+    CopyFrom {
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+        file: Option<String>,
+        format: CopyFormat,
+        on_error: OnError,
+        header: bool,
+    },
+    CopyTo {
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+        format: CopyFormat,
+        header: bool,
+    },
+    ExplainAnalyze {
+        query: Box<Query>,
+    },
+    /// text of the query that is checked without being executed, its parameters are replaced with NULLs
+    ExplainSyntax {
+        query: String,
+    },
+    Listen {
+        channel: String,
+    },
+    /// `None` channel stops listening to all channels
+    Unlisten {
+        channel: Option<String>,
+    },
+    Notify {
+        channel: String,
+        payload: String,
+    },
+    DeclareCursor {
+        name: String,
+        query: Box<Query>,
+    },
+    /// `None` count fetches all remaining rows of the cursor
+    Fetch {
+        name: String,
+        count: Option<u64>,
+    },
+    /// `None` name closes all cursors of the session
+    CloseCursor {
+        name: Option<String>,
+    },
+    /// query string that contains only whitespaces, comments or semicolons
+    Empty,
+}
+
+/// splits semicolon separated statements, semicolons inside of quoted strings,
+/// `$$` quoted function bodies, quoted identifiers and `--` comments are not treated as separators.
+/// Statements that consist only of whitespaces are skipped.
+pub(crate) fn split_statements(raw_sql: &str) -> Vec<&str> {
+    let mut statements = vec![];
+    let mut start = 0;
+    let mut quote = None;
+    let mut comment = false;
+    let mut chars = raw_sql.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match (c, quote) {
+            ('\n', _) if comment => comment = false,
+            (_, _) if comment => {}
+            ('$', None) | ('$', Some('$')) if matches!(chars.peek(), Some((_, '$'))) => {
+                chars.next();
+                quote = if quote.is_none() { Some('$') } else { None };
+            }
+            ('$', Some('$')) => {}
+            (c, Some(opened)) if c == opened => quote = None,
+            (_, Some(_)) => {}
+            ('\'', None) | ('"', None) => quote = Some(c),
+            ('-', None) if matches!(chars.peek(), Some((_, '-'))) => comment = true,
+            (';', None) => {
+                statements.push(&raw_sql[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&raw_sql[start..]);
+    statements
+        .into_iter()
+        .filter(|statement| !statement.trim().is_empty())
+        .collect()
+}
+
+pub(crate) fn parse(dialect: &dyn Dialect, raw_sql_query: &str) -> Result<Parsed, ParserError> {
+    if is_empty(dialect, raw_sql_query) {
+        return Ok(Parsed::Empty);
+    }
+    let rewritten = array_constructors(dialect, raw_sql_query);
+    let raw_sql_query = rewritten.as_deref().unwrap_or(raw_sql_query);
+    let rewritten = distinct_predicates(dialect, raw_sql_query);
+    let raw_sql_query = rewritten.as_deref().unwrap_or(raw_sql_query);
+    let (rewritten, generated) = match generated_columns(dialect, raw_sql_query) {
+        Some((raw_sql_query, generated)) => (Some(raw_sql_query), generated),
+        None => (None, vec![]),
+    };
+    let raw_sql_query = rewritten.as_deref().unwrap_or(raw_sql_query);
+    let parsed = match array_column_types(dialect, raw_sql_query) {
+        Some((raw_sql_query, columns)) => {
+            parse_statement(dialect, &raw_sql_query).map(|parsed| with_array_columns(parsed, &columns))
+        }
+        None => parse_statement(dialect, raw_sql_query),
+    };
+    parsed.map(|parsed| with_generated_columns(parsed, &generated))
+}
+
+fn parse_statement(dialect: &dyn Dialect, raw_sql_query: &str) -> Result<Parsed, ParserError> {
+    if let Some(parsed) = update_from(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = delete_using(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = merge(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = create_temporary_table(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = create_partition(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = partitioned_table(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = partition_management(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = user_management(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = function_management(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = trigger_management(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = rename_schema(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = drop_constraint(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = deferrable_constraint(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = set_constraints(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = lock_table(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = analyze(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = cluster(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = vacuum(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = copy(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = explain_syntax(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = explain(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = notifications(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = cursors(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = insert_on_conflict(dialect, raw_sql_query) {
+        return parsed;
+    }
+    if let Some(parsed) = query_with_row_count(dialect, raw_sql_query) {
+        return parsed;
+    }
+    let mut statements = Parser::parse_sql(dialect, raw_sql_query)?;
+    log::info!("stmts: {:#?}", statements);
+    Ok(Parsed::Statement(statements.pop().unwrap()))
+}
+
+fn is_empty(dialect: &dyn Dialect, raw_sql_query: &str) -> bool {
+    match Tokenizer::new(dialect, raw_sql_query).tokenize() {
+        Ok(tokens) => tokens
+            .iter()
+            .all(|token| matches!(token, Token::Whitespace(_) | Token::SemiColon)),
+        Err(_) => false,
+    }
+}
+
+// `sqlparser` can't parse `ARRAY[<elements>]`, thus it is rewritten as `ARRAY(<elements>)`
+// function call. returns `None` when query doesn't construct arrays
+fn array_constructors(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<String> {
+    let mut tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut brackets = vec![];
+    let mut rewritten = false;
+    for index in 0..tokens.len() {
+        match &tokens[index] {
+            Token::LBracket => {
+                let is_array = index > 0
+                    && matches!(&tokens[index - 1], Token::Word(word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case("ARRAY"));
+                if is_array {
+                    tokens[index] = Token::LParen;
+                    rewritten = true;
+                }
+                brackets.push(is_array);
+            }
+            // closing bracket is matched with the last opened one
+            Token::RBracket if brackets.pop() == Some(true) => tokens[index] = Token::RParen,
+            _ => {}
+        }
+    }
+    if !rewritten {
+        return None;
+    }
+    Some(to_query(&tokens))
+}
+
+// keywords that end an operand of `IS [NOT] DISTINCT FROM`, operators with lower precedence
+// and clauses around the predicate
+const OPERAND_BOUNDARIES: &[Keyword] = &[
+    Keyword::AND,
+    Keyword::OR,
+    Keyword::NOT,
+    Keyword::IS,
+    Keyword::SELECT,
+    Keyword::FROM,
+    Keyword::WHERE,
+    Keyword::ON,
+    Keyword::USING,
+    Keyword::JOIN,
+    Keyword::SET,
+    Keyword::GROUP,
+    Keyword::HAVING,
+    Keyword::ORDER,
+    Keyword::LIMIT,
+    Keyword::OFFSET,
+    Keyword::AS,
+    Keyword::ASC,
+    Keyword::DESC,
+    Keyword::CASE,
+    Keyword::WHEN,
+    Keyword::THEN,
+    Keyword::ELSE,
+    Keyword::END,
+    Keyword::UNION,
+    Keyword::EXCEPT,
+    Keyword::INTERSECT,
+];
+
+// `sqlparser` can't parse `<left> IS [NOT] DISTINCT FROM <right>`, thus it is rewritten as
+// `IS_DISTINCT_FROM(<left>, <right>)` or `IS_NOT_DISTINCT_FROM(<left>, <right>)` function call.
+// returns `None` when query doesn't have such predicates
+fn distinct_predicates(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<String> {
+    let mut tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let is_boundary = |token: &Token| match token {
+        Token::Comma | Token::SemiColon | Token::EOF => true,
+        Token::Word(word) => word.quote_style.is_none() && OPERAND_BOUNDARIES.contains(&word.keyword),
+        _ => false,
+    };
+    let mut rewritten = false;
+    loop {
+        let words: Vec<(usize, &Token)> = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_index, token)| !matches!(token, Token::Whitespace(_)))
+            .collect();
+        let found = words.windows(4).find_map(|window| {
+            let keywords: Vec<Keyword> = window
+                .iter()
+                .map(|(_, token)| match token {
+                    Token::Word(word) if word.quote_style.is_none() => word.keyword,
+                    _ => Keyword::NoKeyword,
+                })
+                .collect();
+            match keywords.as_slice() {
+                [Keyword::IS, Keyword::DISTINCT, Keyword::FROM, _] => Some((window[0].0, window[2].0, false)),
+                [Keyword::IS, Keyword::NOT, Keyword::DISTINCT, Keyword::FROM] => Some((window[0].0, window[3].0, true)),
+                _ => None,
+            }
+        });
+        let (is, from, negated) = match found {
+            Some(found) => found,
+            None => break,
+        };
+
+        let mut start = is;
+        let mut depth = 0;
+        while start > 0 {
+            match &tokens[start - 1] {
+                Token::RParen => depth += 1,
+                Token::LParen if depth == 0 => break,
+                Token::LParen => depth -= 1,
+                token if depth == 0 && is_boundary(token) => break,
+                _ => {}
+            }
+            start -= 1;
+        }
+        let mut end = from + 1;
+        let mut depth = 0;
+        while end < tokens.len() {
+            match &tokens[end] {
+                Token::LParen => depth += 1,
+                Token::RParen if depth == 0 => break,
+                Token::RParen => depth -= 1,
+                token if depth == 0 && is_boundary(token) => break,
+                _ => {}
+            }
+            end += 1;
+        }
+
+        let name = if negated {
+            "IS_NOT_DISTINCT_FROM"
+        } else {
+            "IS_DISTINCT_FROM"
+        };
+        let mut call = vec![
+            Token::Whitespace(Whitespace::Space),
+            Token::make_word(name, None),
+            Token::LParen,
+        ];
+        call.extend(tokens[start..is].iter().cloned());
+        call.push(Token::Comma);
+        call.extend(tokens[from + 1..end].iter().cloned());
+        call.push(Token::RParen);
+        call.push(Token::Whitespace(Whitespace::Space));
+        tokens.splice(start..end, call);
+        rewritten = true;
+    }
+    if !rewritten {
+        return None;
+    }
+    Some(to_query(&tokens))
+}
+
+// `sqlparser` can't parse `<type>[]` column types, thus brackets are removed from `CREATE TABLE`
+// query and names of array columns are returned. returns `None` when there are no array columns
+fn array_column_types(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<(String, Vec<String>)> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    match tokens.iter().find(|token| !matches!(token, Token::Whitespace(_))) {
+        Some(Token::Word(word)) if word.keyword == Keyword::CREATE => {}
+        _ => return None,
+    }
+    let mut kept = vec![];
+    let mut columns = vec![];
+    let mut column = None;
+    let mut depth = 0;
+    let mut index = 0;
+    while index < tokens.len() {
+        match &tokens[index] {
+            Token::LParen => {
+                depth += 1;
+                if depth == 1 {
+                    column = None;
+                }
+            }
+            Token::RParen => depth -= 1,
+            Token::Comma if depth == 1 => column = None,
+            Token::Word(word) if depth == 1 && column.is_none() => column = Some(word.value.clone()),
+            Token::LBracket if depth == 1 => {
+                let closing = (index + 1..tokens.len()).find(|index| !matches!(tokens[*index], Token::Whitespace(_)));
+                if let (Some(closing), Some(column)) = (closing, &column) {
+                    if tokens[closing] == Token::RBracket {
+                        columns.push(column.clone());
+                        index = closing + 1;
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+        kept.push(tokens[index].clone());
+        index += 1;
+    }
+    if columns.is_empty() {
+        return None;
+    }
+    Some((to_query(&kept), columns))
+}
+
+fn with_array_columns(parsed: Parsed, array_columns: &[String]) -> Parsed {
+    let wrap = |mut statement: Statement| {
+        if let Statement::CreateTable { columns, .. } = &mut statement {
+            for name in array_columns {
+                if let Some(column) = columns.iter_mut().find(|column| &column.name.value == name) {
+                    column.data_type = DataType::Array(Box::new(column.data_type.clone()));
+                }
+            }
+        }
+        statement
+    };
+    match parsed {
+        Parsed::Statement(statement) => Parsed::Statement(wrap(statement)),
+        Parsed::CreateTemporaryTable(statement) => Parsed::CreateTemporaryTable(wrap(statement)),
+        Parsed::CreatePartitionedTable {
+            statement,
+            strategy,
+            key,
+        } => Parsed::CreatePartitionedTable {
+            statement: wrap(statement),
+            strategy,
+            key,
+        },
+        parsed => parsed,
+    }
+}
+
+/// name of `CHECK` column option that holds expression of a generated column
+pub(crate) const GENERATED_COLUMN: &str = "GENERATED ALWAYS";
+
+// `sqlparser` can't parse `GENERATED ALWAYS AS (<expr>) STORED` column option, thus it is rewritten
+// as `CHECK (<expr>)` and names of generated columns are returned. returns `None` when there are no
+// generated columns
+fn generated_columns(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<(String, Vec<String>)> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    match tokens.iter().find(|token| !matches!(token, Token::Whitespace(_))) {
+        Some(Token::Word(word)) if word.keyword == Keyword::CREATE => {}
+        _ => return None,
+    }
+    let is_word = |index: usize, expected: &str| matches!(&tokens[index], Token::Word(word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case(expected));
+    let next_word =
+        |index: usize| (index + 1..tokens.len()).find(|index| !matches!(tokens[*index], Token::Whitespace(_)));
+    let mut kept = vec![];
+    let mut columns = vec![];
+    let mut column = None;
+    let mut depth = 0;
+    // depth of parentheses that enclose the expression and whether STORED is expected after them
+    let mut expression = None;
+    let mut index = 0;
+    while index < tokens.len() {
+        match &tokens[index] {
+            Token::LParen => {
+                depth += 1;
+                if depth == 1 {
+                    column = None;
+                }
+            }
+            Token::RParen => {
+                depth -= 1;
+                if expression == Some(depth) {
+                    expression = None;
+                    if let Some(stored) = next_word(index).filter(|stored| is_word(*stored, "STORED")) {
+                        kept.push(Token::RParen);
+                        index = stored + 1;
+                        continue;
+                    }
+                }
+            }
+            Token::Comma if depth == 1 => column = None,
+            Token::Word(word) if depth == 1 && column.is_none() => column = Some(word.value.clone()),
+            Token::Word(_) if depth == 1 && is_word(index, "GENERATED") => {
+                let always = next_word(index).filter(|always| is_word(*always, "ALWAYS"));
+                let as_ = always.and_then(next_word).filter(|as_| is_word(*as_, "AS"));
+                if let (Some(as_), Some(column)) = (as_, &column) {
+                    columns.push(column.clone());
+                    kept.push(Token::make_keyword("CHECK"));
+                    expression = Some(depth);
+                    index = as_ + 1;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        kept.push(tokens[index].clone());
+        index += 1;
+    }
+    if columns.is_empty() {
+        return None;
+    }
+    Some((to_query(&kept), columns))
+}
+
+fn with_generated_columns(parsed: Parsed, generated_columns: &[String]) -> Parsed {
+    let wrap = |mut statement: Statement| {
+        if let Statement::CreateTable { columns, .. } = &mut statement {
+            for name in generated_columns {
+                let column = columns.iter_mut().find(|column| &column.name.value == name);
+                let option = column.and_then(|column| {
+                    column
+                        .options
+                        .iter_mut()
+                        .rev()
+                        .find(|option| matches!(option.option, ColumnOption::Check(_)))
+                });
+                if let Some(option) = option {
+                    option.name = Some(Ident::new(GENERATED_COLUMN));
+                }
+            }
+        }
+        statement
+    };
+    match parsed {
+        Parsed::Statement(statement) => Parsed::Statement(wrap(statement)),
+        Parsed::CreateTemporaryTable(statement) => Parsed::CreateTemporaryTable(wrap(statement)),
+        Parsed::CreatePartitionedTable {
+            statement,
+            strategy,
+            key,
+        } => Parsed::CreatePartitionedTable {
+            statement: wrap(statement),
+            strategy,
+            key,
+        },
+        parsed => parsed,
+    }
+}
+
+fn to_query(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::SingleQuotedString(value) => format!("'{}'", value.replace('\'', "''")),
+            token => token.to_string(),
+        })
+        .collect()
+}
+
+// `UPDATE <table> SET <assignments> FROM <from items> [WHERE <predicate>]`
+// returns `None` when query is not an UPDATE with FROM clause
+fn update_from(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keyword(Keyword::UPDATE) {
+        return None;
+    }
+    let table_name = parser.parse_object_name().ok()?;
+    if !parser.parse_keyword(Keyword::SET) {
+        return None;
+    }
+    let assignments = parser.parse_comma_separated(Parser::parse_assignment).ok()?;
+    if !parser.parse_keyword(Keyword::FROM) {
+        return None;
+    }
+
+    let from = match parser.parse_comma_separated(Parser::parse_table_and_joins) {
+        Ok(from) => from,
+        Err(error) => return Some(Err(error)),
+    };
+    let selection = if parser.parse_keyword(Keyword::WHERE) {
+        match parser.parse_expr() {
+            Ok(expr) => Some(expr),
+            Err(error) => return Some(Err(error)),
+        }
+    } else {
+        None
+    };
+    while parser.consume_token(&Token::SemiColon) {}
+    if parser.peek_token() != Token::EOF {
+        return Some(Err(ParserError::ParserError(format!(
+            "Expected end of statement, found: {}",
+            parser.peek_token()
+        ))));
+    }
+
+    Some(Ok(Parsed::UpdateFrom {
+        table_name,
+        assignments,
+        from,
+        selection,
+    }))
+}
+
+// `DELETE FROM <table> [USING <from items>] [WHERE <predicate>] [RETURNING <items>]`
+// `sqlparser` doesn't know RETURNING, thus the clause is split off and parsed as a select list
+// returns `None` when query is not a DELETE with USING or RETURNING clause
+fn delete_using(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut depth = 0;
+    let mut split = None;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            Token::Word(word)
+                if depth == 0 && word.quote_style.is_none() && word.value.eq_ignore_ascii_case("RETURNING") =>
+            {
+                split = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let mut head = tokens[..split.unwrap_or(tokens.len())].to_vec();
+    head.push(Token::EOF);
+    let mut parser = Parser::new(head);
+    if !parser.parse_keywords(&[Keyword::DELETE, Keyword::FROM]) {
+        return None;
+    }
+    let table_name = parser.parse_object_name().ok()?;
+    let using = if parser.parse_keyword(Keyword::USING) {
+        match parser.parse_comma_separated(Parser::parse_table_and_joins) {
+            Ok(using) => using,
+            Err(error) => return Some(Err(error)),
+        }
+    } else if split.is_none() {
+        return None;
+    } else {
+        vec![]
+    };
+    let selection = if parser.parse_keyword(Keyword::WHERE) {
+        match parser.parse_expr() {
+            Ok(expr) => Some(expr),
+            Err(error) => return Some(Err(error)),
+        }
+    } else {
+        None
+    };
+    while parser.consume_token(&Token::SemiColon) {}
+    if parser.peek_token() != Token::EOF {
+        return Some(Err(ParserError::ParserError(format!(
+            "Expected end of statement, found: {}",
+            parser.peek_token()
+        ))));
+    }
+
+    let returning = match split {
+        None => vec![],
+        Some(split) => {
+            let mut parser = Parser::new(tokens[split + 1..].to_vec());
+            let returning = match parser.parse_comma_separated(Parser::parse_select_item) {
+                Ok(returning) => returning,
+                Err(error) => return Some(Err(error)),
+            };
+            while parser.consume_token(&Token::SemiColon) {}
+            if parser.peek_token() != Token::EOF {
+                return Some(Err(ParserError::ParserError(format!(
+                    "Expected end of statement, found: {}",
+                    parser.peek_token()
+                ))));
+            }
+            returning
+        }
+    };
+
+    Some(Ok(Parsed::DeleteUsing {
+        table_name,
+        using,
+        selection,
+        returning,
+    }))
+}
+
+// `MERGE INTO <table> [[AS] <alias>] USING <source> ON <condition> <when clauses>`
+// returns `None` when query is not a MERGE
+fn merge(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keyword(Keyword::MERGE) {
+        return None;
+    }
+    let result = (|| {
+        parser.expect_keyword(Keyword::INTO)?;
+        let table_name = parser.parse_object_name()?;
+        let alias = parser.parse_optional_alias(keywords::RESERVED_FOR_TABLE_ALIAS)?;
+        parser.expect_keyword(Keyword::USING)?;
+        let source = parser.parse_table_factor()?;
+        parser.expect_keyword(Keyword::ON)?;
+        let on = parser.parse_expr()?;
+        let mut clauses = vec![];
+        while parser.parse_keyword(Keyword::WHEN) {
+            clauses.push(merge_clause(&mut parser)?);
+        }
+        if clauses.is_empty() {
+            return Err(ParserError::ParserError(format!(
+                "Expected WHEN, found: {}",
+                parser.peek_token()
+            )));
+        }
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::Merge {
+            table_name,
+            alias,
+            source,
+            on,
+            clauses,
+        })
+    })();
+    Some(result)
+}
+
+// `[NOT] MATCHED [BY { TARGET | SOURCE }] [AND <predicate>] THEN <action>` after WHEN keyword,
+// only source rows that are not matched by the target could be inserted
+fn merge_clause(parser: &mut Parser) -> Result<MergeClause, ParserError> {
+    let not = parser.parse_keyword(Keyword::NOT);
+    expect_word(parser, "MATCHED")?;
+    let kind = if !not {
+        MergeKind::Matched
+    } else if parser.parse_keyword(Keyword::BY) {
+        if parse_word(parser, "SOURCE") {
+            MergeKind::NotMatchedBySource
+        } else {
+            expect_word(parser, "TARGET")?;
+            MergeKind::NotMatchedByTarget
+        }
+    } else {
+        MergeKind::NotMatchedByTarget
+    };
+    let predicate = if parser.parse_keyword(Keyword::AND) {
+        Some(parser.parse_expr()?)
+    } else {
+        None
+    };
+    parser.expect_keyword(Keyword::THEN)?;
+    let found = parser.peek_token();
+    let action = match parser.parse_one_of_keywords(&[Keyword::UPDATE, Keyword::DELETE, Keyword::INSERT]) {
+        Some(Keyword::UPDATE) if kind != MergeKind::NotMatchedByTarget => {
+            parser.expect_keyword(Keyword::SET)?;
+            MergeAction::Update(parser.parse_comma_separated(Parser::parse_assignment)?)
+        }
+        Some(Keyword::DELETE) if kind != MergeKind::NotMatchedByTarget => MergeAction::Delete,
+        Some(Keyword::INSERT) if kind == MergeKind::NotMatchedByTarget => {
+            let columns = parser.parse_parenthesized_column_list(IsOptional::Optional)?;
+            parser.expect_keyword(Keyword::VALUES)?;
+            parser.expect_token(&Token::LParen)?;
+            let values = parser.parse_comma_separated(Parser::parse_expr)?;
+            parser.expect_token(&Token::RParen)?;
+            MergeAction::Insert { columns, values }
+        }
+        _ => {
+            let expected = if kind == MergeKind::NotMatchedByTarget {
+                "INSERT"
+            } else {
+                "UPDATE or DELETE"
+            };
+            return Err(ParserError::ParserError(format!(
+                "Expected {}, found: {}",
+                expected, found
+            )));
+        }
+    };
+    Ok(MergeClause {
+        kind,
+        predicate,
+        action,
+    })
+}
+
+// `CREATE { TEMP | TEMPORARY } TABLE <table> (<columns>)`
+// returns `None` when query doesn't create a temporary table
+fn create_temporary_table(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let mut tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut words = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_index, token)| !matches!(token, Token::Whitespace(_)));
+    match words.next() {
+        Some((_, Token::Word(word))) if word.keyword == Keyword::CREATE => {}
+        _ => return None,
+    }
+    let temporary = match words.next() {
+        Some((index, Token::Word(word)))
+            if word.value.eq_ignore_ascii_case("TEMP") || word.value.eq_ignore_ascii_case("TEMPORARY") =>
+        {
+            index
+        }
+        _ => return None,
+    };
+    tokens.remove(temporary);
+    let mut parser = Parser::new(tokens);
+    let result = (|| {
+        let statement = parser.parse_statement()?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        match statement {
+            statement @ Statement::CreateTable { .. } => Ok(statement),
+            _ => Err(ParserError::ParserError("Expected TABLE after TEMPORARY".to_owned())),
+        }
+    })();
+
+    Some(result.map(Parsed::CreateTemporaryTable))
+}
+
+// `CREATE TABLE <table> PARTITION OF <parent> <bound>`
+// returns `None` when query doesn't create a partition
+fn create_partition(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keywords(&[Keyword::CREATE, Keyword::TABLE]) {
+        return None;
+    }
+    let table_name = parser.parse_object_name().ok()?;
+    if !parser.parse_keywords(&[Keyword::PARTITION, Keyword::OF]) {
+        return None;
+    }
+    let result = (|| {
+        let parent = parser.parse_object_name()?;
+        let bound = parse_partition_bound(&mut parser)?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::CreatePartition {
+            table_name,
+            parent,
+            bound,
+        })
+    })();
+
+    Some(result)
+}
+
+// `ALTER TABLE <table> ATTACH PARTITION <partition> <bound>` or `ALTER TABLE <table> DETACH PARTITION <partition>`
+// returns `None` when query doesn't attach or detach a partition
+fn partition_management(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keywords(&[Keyword::ALTER, Keyword::TABLE]) {
+        return None;
+    }
+    let table_name = parser.parse_object_name().ok()?;
+    let attach = match parser.next_token() {
+        Token::Word(word) if word.value.eq_ignore_ascii_case("ATTACH") => true,
+        Token::Word(word) if word.value.eq_ignore_ascii_case("DETACH") => false,
+        _ => return None,
+    };
+    let result = (|| {
+        parser.expect_keyword(Keyword::PARTITION)?;
+        let partition = parser.parse_object_name()?;
+        let parsed = if attach {
+            let bound = parse_partition_bound(&mut parser)?;
+            Parsed::AttachPartition {
+                table_name,
+                partition,
+                bound,
+            }
+        } else {
+            Parsed::DetachPartition { table_name, partition }
+        };
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(parsed)
+    })();
+
+    Some(result)
+}
+
+// `DEFAULT` or `FOR VALUES FROM (<value>) TO (<value>)` or `FOR VALUES IN (<values>)`
+fn parse_partition_bound(parser: &mut Parser) -> Result<PartitionBound, ParserError> {
+    if parser.parse_keyword(Keyword::DEFAULT) {
+        return Ok(PartitionBound::Default);
+    }
+    parser.expect_keywords(&[Keyword::FOR, Keyword::VALUES])?;
+    if parser.parse_keyword(Keyword::IN) {
+        parser.expect_token(&Token::LParen)?;
+        let values = parser.parse_comma_separated(Parser::parse_expr)?;
+        parser.expect_token(&Token::RParen)?;
+        return Ok(PartitionBound::List(values));
+    }
+    parser.expect_keyword(Keyword::FROM)?;
+    parser.expect_token(&Token::LParen)?;
+    let lower = parser.parse_expr()?;
+    parser.expect_token(&Token::RParen)?;
+    parser.expect_keyword(Keyword::TO)?;
+    parser.expect_token(&Token::LParen)?;
+    let upper = parser.parse_expr()?;
+    parser.expect_token(&Token::RParen)?;
+    Ok(PartitionBound::Range {
+        lower: Box::new(lower),
+        upper: Box::new(upper),
+    })
+}
+
+// `CREATE TABLE <table> (<columns>) PARTITION BY { RANGE | LIST } (<column>)`, `sqlparser` can't parse PARTITION BY
+// clause, thus it is cut off before the rest of the statement is parsed
+// returns `None` when query doesn't create a partitioned table
+fn partitioned_table(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    match tokens.iter().find(|token| !matches!(token, Token::Whitespace(_))) {
+        Some(Token::Word(word)) if word.keyword == Keyword::CREATE => {}
+        _ => return None,
+    }
+    let mut depth = 0;
+    let partition = tokens.iter().position(|token| {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            Token::Word(word) if depth == 0 && word.keyword == Keyword::PARTITION => return true,
+            _ => {}
+        }
+        false
+    })?;
+    let mut parser = Parser::new(tokens[partition..].to_vec());
+    parser.expect_keyword(Keyword::PARTITION).ok()?;
+    if !parser.parse_keyword(Keyword::BY) {
+        return None;
+    }
+    let result = (|| {
+        let strategy = match parser.next_token() {
+            Token::Word(word) if word.keyword == Keyword::RANGE => PartitionStrategy::Range,
+            Token::Word(word) if word.value.eq_ignore_ascii_case("LIST") => PartitionStrategy::List,
+            found => {
+                return Err(ParserError::ParserError(format!(
+                    "Expected RANGE or LIST, found: {}",
+                    found
+                )))
+            }
+        };
+        parser.expect_token(&Token::LParen)?;
+        let key = parser.parse_identifier()?;
+        parser.expect_token(&Token::RParen)?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        match Parser::parse_sql(dialect, &to_query(&tokens[..partition]))?.pop() {
+            Some(statement @ Statement::CreateTable { .. }) => Ok(Parsed::CreatePartitionedTable {
+                statement,
+                strategy,
+                key,
+            }),
+            _ => Err(ParserError::ParserError(
+                "Expected CREATE TABLE before PARTITION BY".to_owned(),
+            )),
+        }
+    })();
+
+    Some(result)
+}
+
+// `INSERT INTO <table> ... ON CONFLICT [ (<columns>) ] DO NOTHING` or
+// `INSERT INTO <table> ... ON CONFLICT (<columns>) DO UPDATE SET <assignments> [WHERE <predicate>]`
+// conflict target of DO NOTHING is accepted but conflicts on any unique column are skipped
+// returns `None` when query is not an INSERT with ON CONFLICT clause
+fn insert_on_conflict(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut depth = 0;
+    let mut split = None;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            Token::Word(word) if depth == 0 && word.keyword == Keyword::ON => {
+                let next = tokens[index + 1..]
+                    .iter()
+                    .find(|token| !matches!(token, Token::Whitespace(_)));
+                if let Some(Token::Word(next)) = next {
+                    if next.value.eq_ignore_ascii_case("CONFLICT") {
+                        split = Some(index);
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let split = split?;
+    let mut head = tokens[..split].to_vec();
+    head.push(Token::EOF);
+    let mut parser = Parser::new(head);
+    if !parser.parse_keyword(Keyword::INSERT) {
+        return None;
+    }
+    parser.prev_token();
+    let statement = match parser.parse_statement() {
+        Ok(statement) => statement,
+        Err(error) => return Some(Err(error)),
+    };
+
+    // PostgreSQL folds unquoted identifiers to lower case, `EXCLUDED.val` has to match `excluded` pseudo-table
+    let tail = tokens[split..]
+        .iter()
+        .cloned()
+        .map(|token| match token {
+            Token::Word(mut word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case("EXCLUDED") => {
+                word.value = word.value.to_lowercase();
+                Token::Word(word)
+            }
+            token => token,
+        })
+        .collect();
+    let mut parser = Parser::new(tail);
+    let result = (|| {
+        parser.expect_keyword(Keyword::ON)?;
+        expect_word(&mut parser, "CONFLICT")?;
+        let target = parser.parse_parenthesized_column_list(IsOptional::Optional)?;
+        expect_word(&mut parser, "DO")?;
+        let on_conflict = if parser.parse_keyword(Keyword::UPDATE) {
+            if target.is_empty() {
+                return Err(ParserError::ParserError(
+                    "ON CONFLICT DO UPDATE requires inference specification or constraint name".to_owned(),
+                ));
+            }
+            parser.expect_keyword(Keyword::SET)?;
+            let assignments = parser.parse_comma_separated(Parser::parse_assignment)?;
+            let selection = if parser.parse_keyword(Keyword::WHERE) {
+                Some(Box::new(parser.parse_expr()?))
+            } else {
+                None
+            };
+            OnConflict::DoUpdate {
+                target,
+                assignments,
+                selection,
+            }
+        } else {
+            expect_word(&mut parser, "NOTHING")?;
+            OnConflict::DoNothing
+        };
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(on_conflict)
+    })();
+
+    Some(result.map(|on_conflict| Parsed::InsertOnConflict { statement, on_conflict }))
+}
+
+// `CREATE USER <name> [ [ WITH ] { SUPERUSER | NOSUPERUSER | PASSWORD '<password>' } ... ]`,
+// the password is not kept, clients are authenticated by rules of the authentication configuration
+// `DROP USER [ IF EXISTS ] <name>`
+// `GRANT <privileges> ON [ TABLE ] <table> TO <name> [ WITH GRANT OPTION ]`
+// `REVOKE <privileges> ON [ TABLE ] <table> FROM <name>`
+// returns `None` when query doesn't manage users or their privileges
+fn user_management(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    let statement = if parser.parse_keywords(&[Keyword::CREATE, Keyword::USER]) {
+        Keyword::CREATE
+    } else if parser.parse_keywords(&[Keyword::DROP, Keyword::USER]) {
+        Keyword::DROP
+    } else if parser.parse_keyword(Keyword::GRANT) {
+        Keyword::GRANT
+    } else if parser.parse_keyword(Keyword::REVOKE) {
+        Keyword::REVOKE
+    } else {
+        return None;
+    };
+    let result = (|| {
+        let parsed = match statement {
+            Keyword::CREATE => {
+                let name = parser.parse_identifier()?.value;
+                let _ = parser.parse_keyword(Keyword::WITH);
+                let mut superuser = false;
+                loop {
+                    match parser.peek_token() {
+                        Token::Word(word) if word.value.eq_ignore_ascii_case("PASSWORD") => {
+                            parser.next_token();
+                            parser.parse_literal_string()?;
+                        }
+                        Token::Word(word) if word.value.eq_ignore_ascii_case("SUPERUSER") => {
+                            parser.next_token();
+                            superuser = true;
+                        }
+                        Token::Word(word) if word.value.eq_ignore_ascii_case("NOSUPERUSER") => {
+                            parser.next_token();
+                            superuser = false;
+                        }
+                        _ => break,
+                    }
+                }
+                Parsed::CreateUser { name, superuser }
+            }
+            Keyword::DROP => {
+                let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+                let name = parser.parse_identifier()?.value;
+                Parsed::DropUser { name, if_exists }
+            }
+            _ => {
+                let privileges = privileges(&mut parser)?;
+                parser.expect_keyword(Keyword::ON)?;
+                let _ = parser.parse_keyword(Keyword::TABLE);
+                let table_name = parser.parse_object_name()?;
+                if statement == Keyword::GRANT {
+                    parser.expect_keyword(Keyword::TO)?;
+                    let grantee = parser.parse_identifier()?.value;
+                    let with_grant_option = parser.parse_keywords(&[Keyword::WITH, Keyword::GRANT]);
+                    if with_grant_option {
+                        expect_word(&mut parser, "OPTION")?;
+                    }
+                    Parsed::Grant {
+                        privileges,
+                        table_name,
+                        grantee,
+                        with_grant_option,
+                    }
+                } else {
+                    parser.expect_keyword(Keyword::FROM)?;
+                    let grantee = parser.parse_identifier()?.value;
+                    Parsed::Revoke {
+                        privileges,
+                        table_name,
+                        grantee,
+                    }
+                }
+            }
+        };
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(parsed)
+    })();
+
+    Some(result)
+}
+
+// `CREATE FUNCTION <name> ( [ <arg> <type> [, ...] ] ) RETURNS <type> AS <body> [ LANGUAGE SQL ]`
+// `DROP FUNCTION [ IF EXISTS ] <name> [ ( [ <type> [, ...] ] ) ] [, ...]`
+// body is a string or `$$` quoted text of a single expression or `SELECT <expression>`,
+// body of a function that `RETURNS TRIGGER` is a single INSERT, UPDATE, DELETE or SELECT statement
+// returns `None` when query doesn't create or drop a function
+fn function_management(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(dollar_quoted(tokens));
+    let create = if parser.parse_keywords(&[Keyword::CREATE, Keyword::FUNCTION]) {
+        true
+    } else if parser.parse_keywords(&[Keyword::DROP, Keyword::FUNCTION]) {
+        false
+    } else {
+        return None;
+    };
+    let result = (|| {
+        let parsed = if create {
+            let name = parser.parse_object_name()?;
+            parser.expect_token(&Token::LParen)?;
+            let args = if parser.consume_token(&Token::RParen) {
+                vec![]
+            } else {
+                let args = parser.parse_comma_separated(|parser| {
+                    let arg = parser.parse_identifier()?;
+                    Ok((arg, parser.parse_data_type()?))
+                })?;
+                parser.expect_token(&Token::RParen)?;
+                args
+            };
+            parser.expect_keyword(Keyword::RETURNS)?;
+            let trigger = parse_word(&mut parser, "TRIGGER");
+            let return_type = if trigger {
+                DataType::Custom(ObjectName(vec![Ident::new("trigger")]))
+            } else {
+                parser.parse_data_type()?
+            };
+            let mut body = None;
+            loop {
+                if parser.parse_keyword(Keyword::AS) {
+                    let text = parser.parse_literal_string()?;
+                    body = Some(if trigger {
+                        FunctionBody::Statement(trigger_body(dialect, &text)?)
+                    } else {
+                        FunctionBody::Expr(function_body(dialect, &text)?)
+                    });
+                } else if parser.parse_keyword(Keyword::LANGUAGE) {
+                    parser.expect_keyword(Keyword::SQL)?;
+                } else {
+                    break;
+                }
+            }
+            match body {
+                Some(body) => Parsed::CreateFunction {
+                    name,
+                    args,
+                    return_type,
+                    body,
+                },
+                None => {
+                    return Err(ParserError::ParserError(format!(
+                        "Expected AS, found: {}",
+                        parser.peek_token()
+                    )))
+                }
+            }
+        } else {
+            let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let names = parser.parse_comma_separated(|parser| {
+                let name = parser.parse_object_name()?;
+                // functions can't be overloaded, thus argument types are not needed to find the function
+                if parser.consume_token(&Token::LParen) && !parser.consume_token(&Token::RParen) {
+                    parser.parse_comma_separated(Parser::parse_data_type)?;
+                    parser.expect_token(&Token::RParen)?;
+                }
+                Ok(name)
+            })?;
+            Parsed::DropFunction { names, if_exists }
+        };
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(parsed)
+    })();
+
+    Some(result)
+}
+
+// `sqlparser` doesn't support dollar quoting, text between `$$` pairs is replaced with a string literal
+fn dollar_quoted(tokens: Vec<Token>) -> Vec<Token> {
+    let mut result = vec![];
+    let mut quoted: Option<Vec<Token>> = None;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if token == Token::Char('$') && tokens.peek() == Some(&Token::Char('$')) {
+            tokens.next();
+            match quoted.take() {
+                Some(text) => result.push(Token::SingleQuotedString(to_query(&text))),
+                None => quoted = Some(vec![]),
+            }
+            continue;
+        }
+        match quoted.as_mut() {
+            Some(text) => text.push(token),
+            None => result.push(token),
+        }
+    }
+    result
+}
+
+// body of a function is either an expression or `SELECT` of a single expression without FROM clause
+fn function_body(dialect: &dyn Dialect, body: &str) -> Result<Expr, ParserError> {
+    let tokens = Tokenizer::new(dialect, body).tokenize()?;
+    let mut parser = Parser::new(tokens);
+    let expr = if parser.parse_keyword(Keyword::SELECT) {
+        match parser.parse_select_item()? {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => expr,
+            item => {
+                return Err(ParserError::ParserError(format!(
+                    "Expected an expression, found: {}",
+                    item
+                )))
+            }
+        }
+    } else {
+        parser.parse_expr()?
+    };
+    while parser.consume_token(&Token::SemiColon) {}
+    if parser.peek_token() != Token::EOF {
+        return Err(ParserError::ParserError(format!(
+            "Expected end of function body, found: {}",
+            parser.peek_token()
+        )));
+    }
+    Ok(expr)
+}
+
+// body of a trigger function is a statement that is executed for each modified row
+fn trigger_body(dialect: &dyn Dialect, body: &str) -> Result<Statement, ParserError> {
+    let tokens = Tokenizer::new(dialect, body).tokenize()?;
+    let mut parser = Parser::new(tokens);
+    let statement = parser.parse_statement()?;
+    match statement {
+        Statement::Insert { .. } | Statement::Update { .. } | Statement::Delete { .. } | Statement::Query(_) => {}
+        statement => {
+            return Err(ParserError::ParserError(format!(
+                "Expected INSERT, UPDATE, DELETE or SELECT, found: {}",
+                statement
+            )))
+        }
+    }
+    while parser.consume_token(&Token::SemiColon) {}
+    if parser.peek_token() != Token::EOF {
+        return Err(ParserError::ParserError(format!(
+            "Expected end of function body, found: {}",
+            parser.peek_token()
+        )));
+    }
+    Ok(statement)
+}
+
+// `CREATE TRIGGER <name> { BEFORE | AFTER } <event> [ OR ... ] ON <table> [ FOR [ EACH ] ROW ]
+//      EXECUTE { FUNCTION | PROCEDURE } <function> ( )`
+// where <event> is INSERT, DELETE or `UPDATE [ OF <column> [, ...] ]`
+// `DROP TRIGGER [ IF EXISTS ] <name> ON <table>`
+// `ALTER TABLE <table> { ENABLE | DISABLE } TRIGGER <name>`
+// returns `None` when query doesn't manage a trigger
+fn trigger_management(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens.clone());
+    let statement = if parser.parse_keywords(&[Keyword::CREATE, Keyword::TRIGGER]) {
+        Keyword::CREATE
+    } else if parser.parse_keywords(&[Keyword::DROP, Keyword::TRIGGER]) {
+        Keyword::DROP
+    } else if parser.parse_keywords(&[Keyword::ALTER, Keyword::TABLE]) {
+        Keyword::ALTER
+    } else {
+        return None;
+    };
+    if statement == Keyword::ALTER {
+        // other ALTER TABLE statements are parsed by `sqlparser`
+        let mut lookahead = Parser::new(tokens);
+        let _ = lookahead.parse_keywords(&[Keyword::ALTER, Keyword::TABLE]);
+        if lookahead.parse_object_name().is_err()
+            || !(parse_word(&mut lookahead, "ENABLE") || parse_word(&mut lookahead, "DISABLE"))
+        {
+            return None;
+        }
+    }
+    let result = (|| {
+        let parsed = match statement {
+            Keyword::CREATE => {
+                let name = parser.parse_identifier()?;
+                let timing = if parse_word(&mut parser, "BEFORE") {
+                    TriggerTiming::Before
+                } else {
+                    expect_word(&mut parser, "AFTER")?;
+                    TriggerTiming::After
+                };
+                let mut events = vec![];
+                loop {
+                    events.push(if parser.parse_keyword(Keyword::INSERT) {
+                        TriggerEvent::Insert
+                    } else if parser.parse_keyword(Keyword::DELETE) {
+                        TriggerEvent::Delete
+                    } else {
+                        parser.expect_keyword(Keyword::UPDATE)?;
+                        if parser.parse_keyword(Keyword::OF) {
+                            let columns = parser.parse_comma_separated(Parser::parse_identifier)?;
+                            TriggerEvent::Update(columns.into_iter().map(|column| column.value).collect())
+                        } else {
+                            TriggerEvent::Update(vec![])
+                        }
+                    });
+                    if !parser.parse_keyword(Keyword::OR) {
+                        break;
+                    }
+                }
+                parser.expect_keyword(Keyword::ON)?;
+                let table_name = parser.parse_object_name()?;
+                if parser.parse_keyword(Keyword::FOR) {
+                    let _ = parser.parse_keyword(Keyword::EACH);
+                    parser.expect_keyword(Keyword::ROW)?;
+                }
+                parser.expect_keyword(Keyword::EXECUTE)?;
+                if !parser.parse_keyword(Keyword::FUNCTION) {
+                    parser.expect_keyword(Keyword::PROCEDURE)?;
+                }
+                let function = parser.parse_object_name()?;
+                parser.expect_token(&Token::LParen)?;
+                parser.expect_token(&Token::RParen)?;
+                Parsed::CreateTrigger {
+                    name,
+                    timing,
+                    events,
+                    table_name,
+                    function,
+                }
+            }
+            Keyword::DROP => {
+                let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+                let name = parser.parse_identifier()?;
+                parser.expect_keyword(Keyword::ON)?;
+                let table_name = parser.parse_object_name()?;
+                Parsed::DropTrigger {
+                    name,
+                    table_name,
+                    if_exists,
+                }
+            }
+            _ => {
+                let table_name = parser.parse_object_name()?;
+                let enabled = parse_word(&mut parser, "ENABLE");
+                if !enabled {
+                    expect_word(&mut parser, "DISABLE")?;
+                }
+                parser.expect_keyword(Keyword::TRIGGER)?;
+                let name = parser.parse_identifier()?;
+                Parsed::EnableTrigger {
+                    table_name,
+                    name,
+                    enabled,
+                }
+            }
+        };
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(parsed)
+    })();
+
+    Some(result)
+}
+
+// `LOCK [ TABLE ] <table> [, ...] [ IN <lock mode> MODE ] [ NOWAIT ]`
+// returns `None` when query is not a LOCK
+fn lock_table(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parse_word(&mut parser, "LOCK") {
+        return None;
+    }
+    let result = (|| {
+        let _ = parser.parse_keyword(Keyword::TABLE);
+        let tables = parser.parse_comma_separated(Parser::parse_object_name)?;
+        let mode = if parser.parse_keyword(Keyword::IN) {
+            let mode = if parse_word(&mut parser, "ACCESS") {
+                if parse_word(&mut parser, "SHARE") {
+                    LockMode::AccessShare
+                } else {
+                    expect_word(&mut parser, "EXCLUSIVE")?;
+                    LockMode::AccessExclusive
+                }
+            } else if parser.parse_keyword(Keyword::ROW) {
+                expect_word(&mut parser, "EXCLUSIVE")?;
+                LockMode::RowExclusive
+            } else {
+                expect_word(&mut parser, "EXCLUSIVE")?;
+                LockMode::Exclusive
+            };
+            expect_word(&mut parser, "MODE")?;
+            mode
+        } else {
+            LockMode::AccessExclusive
+        };
+        let nowait = parse_word(&mut parser, "NOWAIT");
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::LockTable { tables, mode, nowait })
+    })();
+
+    Some(result)
+}
+
+// `CLUSTER <table> USING <index>`
+// returns `None` when query is not CLUSTER
+fn cluster(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parse_word(&mut parser, "CLUSTER") {
+        return None;
+    }
+    let result = (|| {
+        let table_name = parser.parse_object_name()?;
+        parser.expect_keyword(Keyword::USING)?;
+        let index_name = parser.parse_identifier()?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::Cluster { table_name, index_name })
+    })();
+
+    Some(result)
+}
+
+// `LISTEN <channel>`, `UNLISTEN { <channel> | * }` and `NOTIFY <channel> [ , '<payload>' ]`
+// returns `None` when query is none of them
+fn notifications(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    let result = if parse_word(&mut parser, "LISTEN") {
+        (|| {
+            let channel = case_folded_name(&mut parser)?;
+            Ok(Parsed::Listen { channel })
+        })()
+    } else if parse_word(&mut parser, "UNLISTEN") {
+        (|| {
+            let channel = if parser.consume_token(&Token::Mult) {
+                None
+            } else {
+                Some(case_folded_name(&mut parser)?)
+            };
+            Ok(Parsed::Unlisten { channel })
+        })()
+    } else if parse_word(&mut parser, "NOTIFY") {
+        (|| {
+            let channel = case_folded_name(&mut parser)?;
+            let payload = if parser.consume_token(&Token::Comma) {
+                parser.parse_literal_string()?
+            } else {
+                String::new()
+            };
+            Ok(Parsed::Notify { channel, payload })
+        })()
+    } else {
+        return None;
+    };
+    let result = result.and_then(|parsed| {
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(parsed)
+    });
+
+    Some(result)
+}
+
+// names of channels and cursors are case insensitive unless they are quoted
+fn case_folded_name(parser: &mut Parser) -> Result<String, ParserError> {
+    let ident = parser.parse_identifier()?;
+    Ok(match ident.quote_style {
+        Some(_) => ident.value,
+        None => ident.value.to_lowercase(),
+    })
+}
+
+// `DECLARE <name> CURSOR FOR <query>`, `CLOSE { <name> | ALL }` and
+// `FETCH [ NEXT | ALL | <count> | FORWARD [ <count> | ALL ] ] [ FROM | IN ] <name>`
+// returns `None` when query is none of them
+fn cursors(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    let result = if parser.parse_keyword(Keyword::DECLARE) {
+        (|| {
+            let name = case_folded_name(&mut parser)?;
+            parser.expect_keywords(&[Keyword::CURSOR, Keyword::FOR])?;
+            let query = parser.parse_query()?;
+            Ok(Parsed::DeclareCursor {
+                name,
+                query: Box::new(query),
+            })
+        })()
+    } else if parser.parse_keyword(Keyword::FETCH) {
+        (|| {
+            let count = if parser.parse_keyword(Keyword::NEXT) {
+                Some(1)
+            } else if parser.parse_keyword(Keyword::ALL) {
+                None
+            } else if parse_word(&mut parser, "FORWARD") {
+                if parser.parse_keyword(Keyword::ALL) {
+                    None
+                } else if let Token::Number(_) = parser.peek_token() {
+                    Some(parser.parse_literal_uint()?)
+                } else {
+                    Some(1)
+                }
+            } else if let Token::Number(_) = parser.peek_token() {
+                Some(parser.parse_literal_uint()?)
+            } else {
+                Some(1)
+            };
+            let _ = parser.parse_one_of_keywords(&[Keyword::FROM, Keyword::IN]);
+            let name = case_folded_name(&mut parser)?;
+            Ok(Parsed::Fetch { name, count })
+        })()
+    } else if parser.parse_keyword(Keyword::CLOSE) {
+        (|| {
+            let name = if parser.parse_keyword(Keyword::ALL) {
+                None
+            } else {
+                Some(case_folded_name(&mut parser)?)
+            };
+            Ok(Parsed::CloseCursor { name })
+        })()
+    } else {
+        return None;
+    };
+    let result = result.and_then(|parsed| {
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(parsed)
+    });
+
+    Some(result)
+}
+
+// `ALTER SCHEMA <name> RENAME TO <new_name>`
+// returns `None` when query is not ALTER SCHEMA
+fn rename_schema(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keywords(&[Keyword::ALTER, Keyword::SCHEMA]) {
+        return None;
+    }
+    let result = (|| {
+        let schema_name = parser.parse_object_name()?;
+        parser.expect_keywords(&[Keyword::RENAME, Keyword::TO])?;
+        let new_name = parser.parse_object_name()?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::RenameSchema { schema_name, new_name })
+    })();
+
+    Some(result)
+}
+
+// `ALTER TABLE [ ONLY ] <table> DROP CONSTRAINT <name>`, `sqlparser` takes CONSTRAINT as a column name
+// returns `None` when query doesn't drop a constraint
+fn drop_constraint(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keywords(&[Keyword::ALTER, Keyword::TABLE]) {
+        return None;
+    }
+    let _ = parser.parse_keyword(Keyword::ONLY);
+    let table_name = parser.parse_object_name().ok()?;
+    if !parser.parse_keywords(&[Keyword::DROP, Keyword::CONSTRAINT]) {
+        return None;
+    }
+    let result = (|| {
+        let name = parser.parse_identifier()?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::Statement(Statement::AlterTable {
+            name: table_name,
+            operation: AlterTableOperation::DropConstraint { name },
+        }))
+    })();
+
+    Some(result)
+}
+
+// `sqlparser` can't parse `[NOT] DEFERRABLE [INITIALLY { DEFERRED | IMMEDIATE }]` of a foreign key that
+// ALTER TABLE adds, thus they are split off and the rest of the query is parsed as usual.
+// returns `None` when query is not ALTER TABLE ADD FOREIGN KEY that declares when the key is checked
+fn deferrable_constraint(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut words = tokens.iter().filter(|token| !matches!(token, Token::Whitespace(_)));
+    match (words.next(), words.next()) {
+        (Some(Token::Word(alter)), Some(Token::Word(table)))
+            if alter.keyword == Keyword::ALTER && table.keyword == Keyword::TABLE => {}
+        _ => return None,
+    }
+    let is_word = |index: usize, expected: &str| matches!(&tokens[index], Token::Word(word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case(expected));
+    let next_word =
+        |index: usize| (index + 1..tokens.len()).find(|index| !matches!(tokens[*index], Token::Whitespace(_)));
+    let mut kept = vec![];
+    let mut deferrable = None;
+    let mut initially_deferred = None;
+    let mut index = 0;
+    while index < tokens.len() {
+        if is_word(index, "DEFERRABLE") {
+            deferrable = Some(true);
+            index += 1;
+            continue;
+        }
+        if is_word(index, "NOT") {
+            if let Some(next) = next_word(index).filter(|next| is_word(*next, "DEFERRABLE")) {
+                deferrable = Some(false);
+                index = next + 1;
+                continue;
+            }
+        }
+        if is_word(index, "INITIALLY") {
+            match next_word(index) {
+                Some(next) if is_word(next, "DEFERRED") || is_word(next, "IMMEDIATE") => {
+                    initially_deferred = Some(is_word(next, "DEFERRED"));
+                    index = next + 1;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        kept.push(tokens[index].clone());
+        index += 1;
+    }
+    if deferrable.is_none() && initially_deferred.is_none() {
+        return None;
+    }
+    let initially_deferred = initially_deferred.unwrap_or(false);
+    // INITIALLY DEFERRED implies DEFERRABLE
+    let deferrable = deferrable.unwrap_or(initially_deferred);
+    let statement = match Parser::parse_sql(dialect, &to_query(&kept)) {
+        Ok(mut statements) => statements.pop()?,
+        Err(error) => return Some(Err(error)),
+    };
+    match statement {
+        Statement::AlterTable {
+            operation: AlterTableOperation::AddConstraint(TableConstraint::ForeignKey { .. }),
+            ..
+        } if initially_deferred && !deferrable => Some(Err(ParserError::ParserError(
+            "constraint declared INITIALLY DEFERRED must be DEFERRABLE".to_owned(),
+        ))),
+        Statement::AlterTable {
+            name,
+            operation: AlterTableOperation::AddConstraint(constraint @ TableConstraint::ForeignKey { .. }),
+        } if deferrable => Some(Ok(Parsed::AddDeferrableForeignKey {
+            table_name: name,
+            constraint,
+            initially_deferred,
+        })),
+        statement @ Statement::AlterTable {
+            operation: AlterTableOperation::AddConstraint(TableConstraint::ForeignKey { .. }),
+            ..
+        } => Some(Ok(Parsed::Statement(statement))),
+        _ => None,
+    }
+}
+
+// `SET CONSTRAINTS ALL { DEFERRED | IMMEDIATE }`
+// returns `None` when query is not SET CONSTRAINTS
+fn set_constraints(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keyword(Keyword::SET) || !parse_word(&mut parser, "CONSTRAINTS") {
+        return None;
+    }
+    let result = (|| {
+        parser.expect_keyword(Keyword::ALL)?;
+        let deferred = if parse_word(&mut parser, "DEFERRED") {
+            true
+        } else {
+            expect_word(&mut parser, "IMMEDIATE")?;
+            false
+        };
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::SetConstraints { deferred })
+    })();
+
+    Some(result)
+}
+
+// `ANALYZE [ TABLE ] <table>`
+// returns `None` when query is not ANALYZE
+fn analyze(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parse_word(&mut parser, "ANALYZE") {
+        return None;
+    }
+    let result = (|| {
+        let _ = parser.parse_keyword(Keyword::TABLE);
+        let table_name = parser.parse_object_name()?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::Analyze { table_name })
+    })();
+
+    Some(result)
+}
+
+// `EXPLAIN ANALYZE <query>`, the query is run to collect actual rows and time of its plan
+// returns `None` when query is not EXPLAIN
+fn explain(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parse_word(&mut parser, "EXPLAIN") {
+        return None;
+    }
+    let result = (|| {
+        expect_word(&mut parser, "ANALYZE")?;
+        let query = parser.parse_query()?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::ExplainAnalyze { query: Box::new(query) })
+    })();
+
+    Some(result)
+}
+
+// `EXPLAIN SYNTAX <query>`, the query is parsed later thus its syntax errors are reported as the result
+// returns `None` when query is not EXPLAIN SYNTAX
+fn explain_syntax(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut words = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_index, token)| !matches!(token, Token::Whitespace(_)));
+    let start = match (words.next(), words.next()) {
+        (Some((_, Token::Word(explain))), Some((index, Token::Word(syntax))))
+            if explain.quote_style.is_none()
+                && explain.value.eq_ignore_ascii_case("EXPLAIN")
+                && syntax.quote_style.is_none()
+                && syntax.value.eq_ignore_ascii_case("SYNTAX") =>
+        {
+            index + 1
+        }
+        _ => return None,
+    };
+    // values of `$n` parameters are not known until they are bound
+    let mut query = vec![];
+    let mut tokens = tokens[start..].iter().peekable();
+    while let Some(token) = tokens.next() {
+        match (token, tokens.peek()) {
+            (Token::Char('$'), Some(Token::Number(_))) => {
+                tokens.next();
+                query.push(Token::make_keyword("NULL"));
+            }
+            (token, _) => query.push(token.clone()),
+        }
+    }
+
+    Some(Ok(Parsed::ExplainSyntax {
+        query: to_query(&query).trim().to_owned(),
+    }))
+}
+
+// `VACUUM [ FULL ] [ ANALYZE ] <table>`
+// returns `None` when query is not VACUUM
+fn vacuum(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parse_word(&mut parser, "VACUUM") {
+        return None;
+    }
+    let result = (|| {
+        let full = parser.parse_keyword(Keyword::FULL);
+        let analyze = parse_word(&mut parser, "ANALYZE");
+        let table_name = parser.parse_object_name()?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(Parsed::Vacuum {
+            table_name,
+            full,
+            analyze,
+        })
+    })();
+
+    Some(result)
+}
+
+// `COPY <table> [ (<columns>) ] { FROM STDIN | TO STDOUT } [ CSV ] [ [ WITH ] ( <options> ) ]`
+// `sqlparser` expects rows to follow the statement in the same query string, however
+// clients send them with CopyData messages. returns `None` when query is not COPY
+fn copy(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut parser = Parser::new(tokens);
+    if !parser.parse_keyword(Keyword::COPY) {
+        return None;
+    }
+    let result = (|| {
+        let table_name = parser.parse_object_name()?;
+        let columns = parser.parse_parenthesized_column_list(IsOptional::Optional)?;
+        let from = match parser.expect_one_of_keywords(&[Keyword::FROM, Keyword::TO])? {
+            Keyword::FROM => match parser.peek_token() {
+                Token::SingleQuotedString(_) => Some(Some(parser.parse_literal_string()?)),
+                _ => {
+                    parser.expect_keyword(Keyword::STDIN)?;
+                    Some(None)
+                }
+            },
+            _ => {
+                expect_word(&mut parser, "STDOUT")?;
+                None
+            }
+        };
+        let (format, on_error, header) = copy_options(&mut parser)?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        if let Some(file) = from {
+            Ok(Parsed::CopyFrom {
+                table_name,
+                columns,
+                file,
+                format,
+                on_error,
+                header,
+            })
+        } else if on_error == OnError::Ignore {
+            Err(ParserError::ParserError(
+                "COPY ON_ERROR cannot be used with COPY TO".to_owned(),
+            ))
+        } else {
+            Ok(Parsed::CopyTo {
+                table_name,
+                columns,
+                format,
+                header,
+            })
+        }
+    })();
+
+    Some(result)
+}
+
+// `[ CSV [ HEADER ] ] [ [ WITH ] ( { FORMAT { TEXT | CSV } | ON_ERROR { STOP | IGNORE } | HEADER [ <boolean> ] } [, ...] ) ]`,
+// by default rows are in text format without a header line and the first invalid row stops COPY
+fn copy_options(parser: &mut Parser) -> Result<(CopyFormat, OnError, bool), ParserError> {
+    let mut format = CopyFormat::Text;
+    let mut on_error = OnError::Stop;
+    let mut header = false;
+    if parser.parse_keyword(Keyword::CSV) {
+        format = CopyFormat::Csv;
+        header = parser.parse_keyword(Keyword::HEADER);
+    }
+    if !parser.parse_keyword(Keyword::WITH) && parser.peek_token() != Token::LParen {
+        return Ok((format, on_error, header));
+    }
+    parser.expect_token(&Token::LParen)?;
+    parser.parse_comma_separated(|parser| {
+        if parser.parse_keyword(Keyword::HEADER) {
+            header = !matches!(
+                parser.parse_one_of_keywords(&[Keyword::TRUE, Keyword::FALSE]),
+                Some(Keyword::FALSE)
+            );
+            return Ok(());
+        }
+        if parse_word(parser, "ON_ERROR") {
+            on_error = if parse_word(parser, "IGNORE") {
+                OnError::Ignore
+            } else {
+                expect_word(parser, "STOP")?;
+                OnError::Stop
+            };
+            return Ok(());
+        }
+        expect_word(parser, "FORMAT")?;
+        format = match parser.parse_one_of_keywords(&[Keyword::TEXT, Keyword::CSV]) {
+            Some(Keyword::TEXT) => CopyFormat::Text,
+            Some(_) => CopyFormat::Csv,
+            None => {
+                return Err(ParserError::ParserError(format!(
+                    "Expected TEXT or CSV, found: {}",
+                    parser.peek_token()
+                )))
+            }
+        };
+        Ok(())
+    })?;
+    parser.expect_token(&Token::RParen)?;
+    Ok((format, on_error, header))
+}
+
+// `ALL [ PRIVILEGES ]` or comma separated list of SELECT, INSERT, UPDATE and DELETE
+fn privileges(parser: &mut Parser) -> Result<Vec<Privilege>, ParserError> {
+    if parser.parse_keyword(Keyword::ALL) {
+        if let Token::Word(word) = parser.peek_token() {
+            if word.value.eq_ignore_ascii_case("PRIVILEGES") {
+                parser.next_token();
+            }
+        }
+        return Ok(vec![
+            Privilege::Select,
+            Privilege::Insert,
+            Privilege::Update,
+            Privilege::Delete,
+        ]);
+    }
+    parser.parse_comma_separated(|parser| {
+        match parser.parse_one_of_keywords(&[Keyword::SELECT, Keyword::INSERT, Keyword::UPDATE, Keyword::DELETE]) {
+            Some(Keyword::SELECT) => Ok(Privilege::Select),
+            Some(Keyword::INSERT) => Ok(Privilege::Insert),
+            Some(Keyword::UPDATE) => Ok(Privilege::Update),
+            Some(_) => Ok(Privilege::Delete),
+            None => Err(ParserError::ParserError(format!(
+                "Expected privilege, found: {}",
+                parser.peek_token()
+            ))),
+        }
+    })
+}
+
+// consumes the next token only when it is the expected word
+fn parse_word(parser: &mut Parser, expected: &str) -> bool {
+    match parser.peek_token() {
+        Token::Word(word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case(expected) => {
+            parser.next_token();
+            true
+        }
+        _ => false,
+    }
+}
+
+// `sqlparser` doesn't treat CONFLICT, DO, NOTHING, OPTION, STDOUT, FORMAT, ON_ERROR, ANALYZE, IMMEDIATE and lock modes
+// as keywords
+fn expect_word(parser: &mut Parser, expected: &str) -> Result<(), ParserError> {
+    match parser.next_token() {
+        Token::Word(word) if word.value.eq_ignore_ascii_case(expected) => Ok(()),
+        found => Err(ParserError::ParserError(format!(
+            "Expected {}, found: {}",
+            expected, found
+        ))),
+    }
+}
+
+// `sqlparser` accepts only number literals in LIMIT and OFFSET clauses, e.g. `LIMIT -1` or
+// `LIMIT 2 + 3` can't be parsed. Clauses of the top level query are split off and parsed as expressions.
+// returns `None` when query is not a SELECT with LIMIT or OFFSET clause
+fn query_with_row_count(dialect: &dyn Dialect, raw_sql_query: &str) -> Option<Result<Parsed, ParserError>> {
+    let tokens = Tokenizer::new(dialect, raw_sql_query).tokenize().ok()?;
+    let mut depth = 0;
+    let mut split = None;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            Token::Word(word) if depth == 0 && (word.keyword == Keyword::LIMIT || word.keyword == Keyword::OFFSET) => {
+                split = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let split = split?;
+    let mut head = tokens[..split].to_vec();
+    head.push(Token::EOF);
+    let mut parser = Parser::new(head);
+    if !parser.parse_keyword(Keyword::SELECT) {
+        return None;
+    }
+    parser.prev_token();
+    let mut query = match parser.parse_query() {
+        Ok(query) => query,
+        Err(error) => return Some(Err(error)),
+    };
+    if parser.peek_token() != Token::EOF {
+        return Some(Err(ParserError::ParserError(format!(
+            "Expected end of query, found: {}",
+            parser.peek_token()
+        ))));
+    }
+
+    let mut parser = Parser::new(tokens[split..].to_vec());
+    let result = (|| {
+        if parser.parse_keyword(Keyword::LIMIT) && !parser.parse_keyword(Keyword::ALL) {
+            query.limit = Some(parser.parse_expr()?);
+        }
+        if parser.parse_keyword(Keyword::OFFSET) {
+            let value = parser.parse_expr()?;
+            let rows = match parser.parse_one_of_keywords(&[Keyword::ROW, Keyword::ROWS]) {
+                Some(Keyword::ROW) => OffsetRows::Row,
+                Some(_) => OffsetRows::Rows,
+                None => OffsetRows::None,
+            };
+            query.offset = Some(Offset { value, rows });
+        }
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(ParserError::ParserError(format!(
+                "Expected end of statement, found: {}",
+                parser.peek_token()
+            )));
+        }
+        Ok(())
+    })();
+
+    Some(result.map(|()| Parsed::Statement(Statement::Query(Box::new(query)))))
+}
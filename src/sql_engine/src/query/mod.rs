@@ -18,10 +18,14 @@ pub mod bind;
 pub mod plan;
 pub mod process;
 
+use protocol::results::QueryError;
 use sql_types::SqlType;
 use sqlparser::ast::ObjectName;
 use std::convert::TryFrom;
 
+/// the same limit as PostgreSQL has for names of database objects
+const MAX_IDENTIFIER_LENGTH: usize = 63;
+
 /// A type of a column
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ColumnType {
@@ -52,24 +56,30 @@ impl TryFrom<ObjectName> for TableId {
 
     fn try_from(object: ObjectName) -> Result<Self, Self::Error> {
         if object.0.len() == 1 {
-            Err(TableNamingError(format!(
+            Err(TableNamingError(QueryError::syntax_error(format!(
                 "unsupported table name '{}'. All table names must be qualified",
                 object.to_string()
-            )))
+            ))))
         } else if object.0.len() != 2 {
-            Err(TableNamingError(format!(
+            Err(TableNamingError(QueryError::syntax_error(format!(
                 "unable to process table name '{}'",
                 object.to_string()
-            )))
+            ))))
         } else {
             let table_name = object.0.last().unwrap().value.clone();
-            let schema_name = object.0.first().unwrap().value.clone();
-            Ok(TableId(SchemaId(schema_name), table_name))
+            let schema_id = SchemaId::new(&object.0.first().unwrap().value)?;
+            Ok(TableId(schema_id, table_name))
         }
     }
 }
 
-pub struct TableNamingError(String);
+pub struct TableNamingError(pub(crate) QueryError);
+
+impl From<InvalidIdentifier> for TableNamingError {
+    fn from(InvalidIdentifier(name): InvalidIdentifier) -> TableNamingError {
+        TableNamingError(QueryError::invalid_identifier(name))
+    }
+}
 
 /// represents a schema uniquely
 ///
@@ -78,6 +88,25 @@ pub struct TableNamingError(String);
 pub struct SchemaId(String);
 
 impl SchemaId {
+    /// schema names are used to name storage objects, thus only ASCII letters, digits
+    /// and underscores are allowed and a name can't start with a digit
+    pub fn new(name: &str) -> Result<SchemaId, InvalidIdentifier> {
+        let mut chars = name.chars();
+        let valid = match chars.next() {
+            Some(first) => {
+                (first.is_ascii_alphabetic() || first == '_')
+                    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    && name.len() <= MAX_IDENTIFIER_LENGTH
+            }
+            None => false,
+        };
+        if valid {
+            Ok(SchemaId(name.to_owned()))
+        } else {
+            Err(InvalidIdentifier(name.to_owned()))
+        }
+    }
+
     pub fn name(&self) -> &str {
         self.0.as_str()
     }
@@ -88,14 +117,24 @@ impl TryFrom<ObjectName> for SchemaId {
 
     fn try_from(object: ObjectName) -> Result<Self, Self::Error> {
         if object.0.len() != 1 {
-            Err(SchemaNamingError(format!(
+            Err(SchemaNamingError(QueryError::syntax_error(format!(
                 "only unqualified schema names are supported, '{}'",
                 object
-            )))
+            ))))
         } else {
-            Ok(SchemaId(object.to_string()))
+            Ok(SchemaId::new(&object.0[0].value)?)
         }
     }
 }
 
-pub struct SchemaNamingError(String);
+pub struct SchemaNamingError(pub(crate) QueryError);
+
+impl From<InvalidIdentifier> for SchemaNamingError {
+    fn from(InvalidIdentifier(name): InvalidIdentifier) -> SchemaNamingError {
+        SchemaNamingError(QueryError::invalid_identifier(name))
+    }
+}
+
+/// name that can't be used to identify a database object
+#[derive(Debug, PartialEq)]
+pub struct InvalidIdentifier(pub(crate) String);
@@ -14,14 +14,23 @@
 
 ///! represents a plan to be executed by the engine.
 use crate::query::{SchemaId, TableId};
-use data_manager::ColumnDefinition;
-use sqlparser::ast::{Ident, Query, Statement};
+use data_manager::{ColumnDefinition, StorageLayout};
+use sqlparser::ast::{Assignment, Expr, Ident, Query, Statement};
 
 #[derive(Debug, Clone)]
 pub struct TableCreationInfo {
     pub schema_name: String,
     pub table_name: String,
     pub columns: Vec<ColumnDefinition>, // pub table_constraints: Vec<TableConstraints> ??
+    pub temporary: bool,
+    pub layout: StorageLayout,
+}
+
+/// `CREATE TABLE target AS SELECT * FROM source`
+#[derive(Debug, Clone)]
+pub struct TableCopyInfo {
+    pub source: TableId,
+    pub target: TableId,
 }
 
 #[derive(Debug, Clone)]
@@ -34,13 +43,30 @@ pub struct TableInserts {
     pub table_id: TableId,
     pub column_indices: Vec<Ident>,
     pub input: Box<Query>,
+    pub on_conflict: OnConflict,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OnConflict {
+    /// unique constraint violation is reported to the client
+    Error,
+    /// rows that violate unique constraint are skipped
+    DoNothing,
+    /// existing row that has the same value in `target` column is updated,
+    /// `EXCLUDED` pseudo-table refers to values proposed for insertion
+    DoUpdate {
+        target: Vec<Ident>,
+        assignments: Vec<Assignment>,
+        selection: Option<Box<Expr>>,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub enum Plan {
     CreateTable(TableCreationInfo),
+    CopyTable(TableCopyInfo),
     CreateSchema(SchemaCreationInfo),
-    DropTables(Vec<TableId>),
+    DropTables(Vec<(TableId, bool)>),
     DropSchemas(Vec<(SchemaId, bool)>),
     Insert(TableInserts),
     NotProcessed(Box<Statement>),
@@ -13,36 +13,78 @@
 // limitations under the License.
 
 ///! Module for transforming the input Query AST into representation the engine can process.
-use crate::query::plan::{Plan, SchemaCreationInfo, TableCreationInfo, TableInserts};
+use crate::dml::relation::{ColumnReferenceError, Frame, RelationColumn, Scope};
+use crate::parser::GENERATED_COLUMN;
+use crate::query::plan::{OnConflict, Plan, SchemaCreationInfo, TableCopyInfo, TableCreationInfo, TableInserts};
 use crate::query::{SchemaId, SchemaNamingError, TableId, TableNamingError};
-use data_manager::{ColumnDefinition, DataManager};
+use data_manager::{ColumnDefinition, DataManager, SessionId, StorageLayout};
 use protocol::{results::QueryError, Sender};
-use sql_types::SqlType;
-use sqlparser::ast::{ColumnDef, DataType, ObjectName, ObjectType, Statement};
+use sql_types::{array_of, SqlType};
+use sqlparser::ast::{
+    ColumnDef, ColumnOption, DataType, Ident, ObjectName, ObjectType, Query, SelectItem, SetExpr, SqlOption, Statement,
+    TableConstraint, TableFactor, Value,
+};
 use std::{convert::TryFrom, sync::Arc};
 
 type Result<T> = std::result::Result<T, ()>;
 
 pub(crate) struct QueryProcessor {
+    session_id: SessionId,
     storage: Arc<DataManager>,
     sender: Arc<dyn Sender>,
 }
 
 impl<'qp> QueryProcessor {
-    pub fn new(storage: Arc<DataManager>, sender: Arc<dyn Sender>) -> Self {
-        Self { storage, sender }
+    pub fn new(session_id: SessionId, storage: Arc<DataManager>, sender: Arc<dyn Sender>) -> Self {
+        Self {
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    /// plans `CREATE TEMPORARY TABLE` that the parser turned into a regular `CREATE TABLE`
+    pub fn process_temporary_table(&self, stmt: Statement) -> Result<Plan> {
+        match stmt {
+            Statement::CreateTable { query: Some(_), .. } => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(
+                        "CREATE TEMPORARY TABLE AS".to_owned(),
+                    )))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                with_options,
+                ..
+            } => self.handle_create_table(name, &columns, &constraints, &with_options, true),
+            _ => Ok(Plan::NotProcessed(Box::new(stmt))),
+        }
     }
 
     pub fn process(&self, stmt: Statement) -> Result<Plan> {
         match stmt {
-            Statement::CreateTable { name, columns, .. } => self.handle_create_table(name, &columns),
+            Statement::CreateTable {
+                name,
+                columns,
+                query: Some(query),
+                ..
+            } if columns.is_empty() => self.handle_create_table_as(name, *query),
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                with_options,
+                ..
+            } => self.handle_create_table(name, &columns, &constraints, &with_options, false),
             Statement::CreateSchema { schema_name, .. } => {
                 let schema_id = match SchemaId::try_from(schema_name) {
                     Ok(schema_id) => schema_id,
-                    Err(SchemaNamingError(message)) => {
-                        self.sender
-                            .send(Err(QueryError::syntax_error(message)))
-                            .expect("To Send Query Result to Client");
+                    Err(SchemaNamingError(error)) => {
+                        self.sender.send(Err(error)).expect("To Send Query Result to Client");
                         return Err(());
                     }
                 };
@@ -73,11 +115,10 @@ impl<'qp> QueryProcessor {
                     table_id,
                     column_indices: columns,
                     input: source,
+                    on_conflict: OnConflict::Error,
                 })),
-                Err(TableNamingError(message)) => {
-                    self.sender
-                        .send(Err(QueryError::syntax_error(message)))
-                        .expect("To Send Query Result to Client");
+                Err(TableNamingError(error)) => {
+                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
                     Err(())
                 }
             },
@@ -86,66 +127,112 @@ impl<'qp> QueryProcessor {
     }
 
     fn sql_type_from_datatype(&self, datatype: &DataType) -> Result<SqlType> {
-        match datatype {
-            DataType::SmallInt => Ok(SqlType::SmallInt(i16::min_value())),
-            DataType::Int => Ok(SqlType::Integer(i32::min_value())),
-            DataType::BigInt => Ok(SqlType::BigInt(i64::min_value())),
-            DataType::Char(len) => Ok(SqlType::Char(len.unwrap_or(255))),
-            DataType::Varchar(len) => Ok(SqlType::VarChar(len.unwrap_or(255))),
-            DataType::Boolean => Ok(SqlType::Bool),
-            DataType::Custom(name) => {
-                let name = name.to_string();
-                match name.as_str() {
-                    "serial" => Ok(SqlType::Integer(1)),
-                    "smallserial" => Ok(SqlType::SmallInt(1)),
-                    "bigserial" => Ok(SqlType::BigInt(1)),
-                    other_type => {
-                        self.sender
-                            .send(Err(QueryError::feature_not_supported(format!(
-                                "{} type is not supported",
-                                other_type
-                            ))))
-                            .expect("To Send Query Result to Client");
-                        Err(())
-                    }
-                }
-            }
-            other_type => {
-                self.sender
-                    .send(Err(QueryError::feature_not_supported(format!(
-                        "{} type is not supported",
-                        other_type
-                    ))))
-                    .expect("To Send Query Result to Client");
-                Err(())
-            }
-        }
+        sql_type(datatype).map_err(|error| {
+            self.sender.send(Err(error)).expect("To Send Query Result to Client");
+        })
     }
 
-    fn resolve_column_definitions(&self, columns: &[ColumnDef]) -> Result<Vec<ColumnDefinition>> {
+    fn resolve_column_definitions(
+        &self,
+        columns: &[ColumnDef],
+        constraints: &[TableConstraint],
+    ) -> Result<Vec<ColumnDefinition>> {
         let mut column_defs = Vec::new();
+        let mut generated_exprs = vec![];
         for column in columns {
             let sql_type = self.sql_type_from_datatype(&column.data_type)?;
+            let unique = column
+                .options
+                .iter()
+                .any(|option| matches!(option.option, ColumnOption::Unique { .. }))
+                || constraints.iter().any(|constraint| match constraint {
+                    TableConstraint::Unique { columns, .. } => columns.as_slice() == std::slice::from_ref(&column.name),
+                    _ => false,
+                });
             // maybe a different type should be used to represent this instead of the storage's representation.
-            let column_definition = ColumnDefinition::new(column.name.value.as_str(), sql_type);
-            column_defs.push(column_definition);
+            let mut column_definition = if unique {
+                ColumnDefinition::unique(column.name.value.as_str(), sql_type)
+            } else {
+                ColumnDefinition::new(column.name.value.as_str(), sql_type)
+            };
+            for option in column.options.iter() {
+                match &option.option {
+                    ColumnOption::Default(expr) => {
+                        // default values are computed before the row exists, it has no columns to reference
+                        if Scope::new(Frame::header(&[]), None).check(expr).is_err() {
+                            self.sender
+                                .send(Err(QueryError::feature_not_supported(format!(
+                                    "column reference in DEFAULT expression of column \"{}\"",
+                                    column.name
+                                ))))
+                                .expect("To Send Query Result to Client");
+                            return Err(());
+                        }
+                        column_definition = column_definition.with_default(expr.to_string());
+                    }
+                    ColumnOption::NotNull => column_definition = column_definition.with_not_null(),
+                    _ => {}
+                }
+            }
+            let generated = column.options.iter().find_map(|option| match &option.option {
+                ColumnOption::Check(expr) if option.name == Some(Ident::new(GENERATED_COLUMN)) => Some(expr),
+                _ => None,
+            });
+            match generated {
+                Some(expr) => {
+                    generated_exprs.push(expr);
+                    column_defs.push(column_definition.with_generated(expr.to_string()));
+                }
+                None => column_defs.push(column_definition),
+            }
+        }
+
+        // generated columns are computed from the other columns that are not generated
+        let base_columns: Vec<RelationColumn> = column_defs
+            .iter()
+            .filter(|column| column.generated().is_none())
+            .map(|column| RelationColumn::new(column.name(), (&column.sql_type()).into()))
+            .collect();
+        let scope = Scope::new(Frame::header(&base_columns), None);
+        for expr in generated_exprs {
+            let error = match scope.check(expr) {
+                Ok(()) => continue,
+                Err(ColumnReferenceError::Missing(columns)) => QueryError::column_does_not_exist(columns),
+                Err(ColumnReferenceError::Ambiguous(column)) => QueryError::ambiguous_column(column),
+            };
+            self.sender.send(Err(error)).expect("To Send Query Result to Client");
+            return Err(());
         }
         Ok(column_defs)
     }
 
-    fn handle_create_table(&self, name: ObjectName, columns: &[ColumnDef]) -> Result<Plan> {
+    fn handle_create_table(
+        &self,
+        name: ObjectName,
+        columns: &[ColumnDef],
+        constraints: &[TableConstraint],
+        options: &[SqlOption],
+        temporary: bool,
+    ) -> Result<Plan> {
         let table_id = match TableId::try_from(name) {
             Ok(table_id) => table_id,
-            Err(TableNamingError(message)) => {
-                self.sender
-                    .send(Err(QueryError::syntax_error(message)))
-                    .expect("To Send Query Result to Client");
+            Err(TableNamingError(error)) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
                 return Err(());
             }
         };
         let schema_name = table_id.schema_name();
         let table_name = table_id.name();
-        match self.storage.table_exists(&schema_name, &table_name) {
+        let table = match self.storage.table_exists(&schema_name, &table_name) {
+            Some((schema_id, _)) if temporary => Some((
+                schema_id,
+                self.storage
+                    .temp_table_exists(self.session_id, schema_name, table_name)
+                    .map(|(_, table_id)| table_id),
+            )),
+            table => table,
+        };
+        match table {
             None => {
                 self.sender
                     .send(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
@@ -162,17 +249,99 @@ impl<'qp> QueryProcessor {
                 Err(())
             }
             Some((_, None)) => {
-                let columns = self.resolve_column_definitions(columns)?;
+                let columns = self.resolve_column_definitions(columns, constraints)?;
+                let layout = self.resolve_storage_layout(options)?;
                 let table_info = TableCreationInfo {
                     schema_name: schema_name.to_owned(),
                     table_name: table_name.to_owned(),
                     columns,
+                    temporary,
+                    layout,
                 };
                 Ok(Plan::CreateTable(table_info))
             }
         }
     }
 
+    /// only a copy of a whole table is supported, i.e. `CREATE TABLE target AS SELECT * FROM source`
+    fn handle_create_table_as(&self, name: ObjectName, query: Query) -> Result<Plan> {
+        let source = match Self::copied_table(query) {
+            Some(source) => source,
+            None => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(
+                        "CREATE TABLE AS with a query other than SELECT * FROM table".to_owned(),
+                    )))
+                    .expect("To Send Query Result to Client");
+                return Err(());
+            }
+        };
+        match (TableId::try_from(name), TableId::try_from(source)) {
+            (Ok(target), Ok(source)) => Ok(Plan::CopyTable(TableCopyInfo { source, target })),
+            (Err(TableNamingError(error)), _) | (_, Err(TableNamingError(error))) => {
+                self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+
+    fn copied_table(query: Query) -> Option<ObjectName> {
+        if !query.ctes.is_empty()
+            || !query.order_by.is_empty()
+            || query.limit.is_some()
+            || query.offset.is_some()
+            || query.fetch.is_some()
+        {
+            return None;
+        }
+        let select = match query.body {
+            SetExpr::Select(select) => select,
+            _ => return None,
+        };
+        if select.distinct
+            || select.top.is_some()
+            || select.selection.is_some()
+            || !select.group_by.is_empty()
+            || select.having.is_some()
+            || select.projection != vec![SelectItem::Wildcard]
+            || select.from.len() != 1
+            || !select.from[0].joins.is_empty()
+        {
+            return None;
+        }
+        match select.from.into_iter().next().map(|table| table.relation) {
+            Some(TableFactor::Table { name, args, .. }) if args.is_empty() => Some(name),
+            _ => None,
+        }
+    }
+
+    /// `WITH (storage_layout = 'columnar')` stores each column of the table separately
+    fn resolve_storage_layout(&self, options: &[SqlOption]) -> Result<StorageLayout> {
+        let mut layout = StorageLayout::Row;
+        for option in options {
+            let error = match (option.name.value.to_lowercase().as_str(), &option.value) {
+                ("storage_layout", Value::SingleQuotedString(value)) => match value.to_lowercase().as_str() {
+                    "row" => {
+                        layout = StorageLayout::Row;
+                        continue;
+                    }
+                    "columnar" => {
+                        layout = StorageLayout::Columnar;
+                        continue;
+                    }
+                    _ => format!("invalid value for parameter \"storage_layout\": \"{}\"", value),
+                },
+                ("storage_layout", value) => format!("invalid value for parameter \"storage_layout\": \"{}\"", value),
+                (name, _) => format!("unrecognized parameter \"{}\"", name),
+            };
+            self.sender
+                .send(Err(QueryError::invalid_parameter_value(error)))
+                .expect("To Send Query Result to Client");
+            return Err(());
+        }
+        Ok(layout)
+    }
+
     fn handle_drop(&self, object_type: &ObjectType, names: &[ObjectName], cascade: bool) -> Result<Plan> {
         match object_type {
             ObjectType::Table => {
@@ -182,16 +351,17 @@ impl<'qp> QueryProcessor {
                     // this check for us and can be reused else where. ideally this function could handle aliasing as well.
                     let table_id = match TableId::try_from(name.clone()) {
                         Ok(table_id) => table_id,
-                        Err(TableNamingError(message)) => {
-                            self.sender
-                                .send(Err(QueryError::syntax_error(message)))
-                                .expect("To Send Query Result to Client");
+                        Err(TableNamingError(error)) => {
+                            self.sender.send(Err(error)).expect("To Send Query Result to Client");
                             return Err(());
                         }
                     };
                     let schema_name = table_id.schema_name();
                     let table_name = table_id.name();
-                    match self.storage.table_exists(&schema_name, &table_name) {
+                    match self
+                        .storage
+                        .session_table_exists(self.session_id, &schema_name, &table_name)
+                    {
                         None => {
                             self.sender
                                 .send(Err(QueryError::schema_does_not_exist(schema_name.to_owned())))
@@ -207,7 +377,7 @@ impl<'qp> QueryProcessor {
                                 .expect("To Send Query Result to Client");
                             return Err(());
                         }
-                        Some((_, Some(_))) => table_names.push(table_id),
+                        Some((_, Some(_))) => table_names.push((table_id, cascade)),
                     }
                 }
                 Ok(Plan::DropTables(table_names))
@@ -217,10 +387,8 @@ impl<'qp> QueryProcessor {
                 for name in names {
                     let schema_id = match SchemaId::try_from(name.clone()) {
                         Ok(schema_id) => schema_id,
-                        Err(SchemaNamingError(message)) => {
-                            self.sender
-                                .send(Err(QueryError::syntax_error(message)))
-                                .expect("To Send Query Result to Client");
+                        Err(SchemaNamingError(error)) => {
+                            self.sender.send(Err(error)).expect("To Send Query Result to Client");
                             return Err(());
                         }
                     };
@@ -236,7 +404,53 @@ impl<'qp> QueryProcessor {
                 }
                 Ok(Plan::DropSchemas(schema_names))
             }
-            _ => unimplemented!(),
+            object_type => {
+                self.sender
+                    .send(Err(QueryError::feature_not_supported(format!("DROP {}", object_type))))
+                    .expect("To Send Query Result to Client");
+                Err(())
+            }
+        }
+    }
+}
+
+/// SQL type of columns, arguments or results of functions with the data type
+pub(crate) fn sql_type(datatype: &DataType) -> std::result::Result<SqlType, QueryError> {
+    match datatype {
+        DataType::SmallInt => Ok(SqlType::SmallInt(i16::min_value())),
+        DataType::Int => Ok(SqlType::Integer(i32::min_value())),
+        DataType::BigInt => Ok(SqlType::BigInt(i64::min_value())),
+        DataType::Char(len) => Ok(SqlType::Char(len.unwrap_or(255))),
+        DataType::Varchar(len) => Ok(SqlType::VarChar(len.unwrap_or(255))),
+        // text has no length limit
+        DataType::Text => Ok(SqlType::VarChar(u64::MAX)),
+        DataType::Boolean => Ok(SqlType::Bool),
+        DataType::Bytea => Ok(SqlType::Bytea),
+        DataType::Timestamp => Ok(SqlType::Timestamp),
+        // only arrays of booleans, strings and integers could be stored
+        DataType::Array(element) if !matches!(**element, DataType::Array(_)) => {
+            let element = sql_type(element)?;
+            match array_of((&element).into()) {
+                Some(_) => Ok(SqlType::Array(Box::new(element))),
+                None => Err(QueryError::feature_not_supported(format!(
+                    "{} type is not supported",
+                    datatype
+                ))),
+            }
         }
+        DataType::Custom(name) => match name.to_string().as_str() {
+            "serial" => Ok(SqlType::Integer(1)),
+            "smallserial" => Ok(SqlType::SmallInt(1)),
+            "bigserial" => Ok(SqlType::BigInt(1)),
+            "json" | "jsonb" => Ok(SqlType::Json),
+            other_type => Err(QueryError::feature_not_supported(format!(
+                "{} type is not supported",
+                other_type
+            ))),
+        },
+        other_type => Err(QueryError::feature_not_supported(format!(
+            "{} type is not supported",
+            other_type
+        ))),
     }
 }
@@ -0,0 +1,104 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::query::{TableId, TableNamingError};
+use data_manager::{DataManager, LockMode, SessionId};
+use kernel::SystemResult;
+use protocol::{
+    results::{QueryError, QueryEvent},
+    Sender,
+};
+use sqlparser::ast::ObjectName;
+use std::{convert::TryFrom, sync::Arc};
+
+pub(crate) struct LockTableCommand {
+    tables: Vec<ObjectName>,
+    mode: LockMode,
+    nowait: bool,
+    session_id: SessionId,
+    storage: Arc<DataManager>,
+    sender: Arc<dyn Sender>,
+}
+
+impl LockTableCommand {
+    pub(crate) fn new(
+        tables: Vec<ObjectName>,
+        mode: LockMode,
+        nowait: bool,
+        session_id: SessionId,
+        storage: Arc<DataManager>,
+        sender: Arc<dyn Sender>,
+    ) -> LockTableCommand {
+        LockTableCommand {
+            tables,
+            mode,
+            nowait,
+            session_id,
+            storage,
+            sender,
+        }
+    }
+
+    // all tables are checked before any of them is locked
+    pub(crate) fn execute(&mut self) -> SystemResult<()> {
+        let mut tables = vec![];
+        for name in self.tables.iter() {
+            let table = match TableId::try_from(name.clone()) {
+                Ok(table) => table,
+                Err(TableNamingError(error)) => {
+                    self.sender.send(Err(error)).expect("To Send Query Result to Client");
+                    return Ok(());
+                }
+            };
+            match self
+                .storage
+                .session_table_exists(self.session_id, table.schema_name(), table.name())
+            {
+                None => {
+                    self.sender
+                        .send(Err(QueryError::schema_does_not_exist(table.schema_name().to_owned())))
+                        .expect("To Send Query Result to Client");
+                    return Ok(());
+                }
+                Some((_, None)) => {
+                    self.sender
+                        .send(Err(QueryError::table_does_not_exist(
+                            table.schema_name().to_owned() + "." + table.name(),
+                        )))
+                        .expect("To Send Query Result to Client");
+                    return Ok(());
+                }
+                Some((schema_id, Some(table_id))) => tables.push((table.name().to_owned(), schema_id, table_id)),
+            }
+        }
+
+        for (table_name, schema_id, table_id) in tables {
+            if !self.nowait {
+                self.storage.lock_table(self.session_id, schema_id, table_id, self.mode);
+            } else if !self
+                .storage
+                .try_lock_table(self.session_id, schema_id, table_id, self.mode)
+            {
+                self.sender
+                    .send(Err(QueryError::lock_not_available(table_name)))
+                    .expect("To Send Query Result to Client");
+                return Ok(());
+            }
+        }
+        self.sender
+            .send(Ok(QueryEvent::TableLocked))
+            .expect("To Send Query Result to Client");
+        Ok(())
+    }
+}
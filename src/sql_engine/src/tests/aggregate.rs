@@ -0,0 +1,234 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (category varchar(5), column_1 smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values ('a', 1), ('a', 1), ('a', 2), ('b', 3), ('b', 3);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name (category) values ('a'), ('b');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(5)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn rows(rows: Vec<Vec<&str>>) -> Vec<Vec<Option<String>>> {
+    rows.into_iter()
+        .map(|row| row.into_iter().map(text).collect())
+        .collect()
+}
+
+#[rstest::rstest]
+fn count_all_rows(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select count(*) from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+            rows(vec![vec!["7"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn count_skips_nulls(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select count(column_1) from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+            rows(vec![vec!["5"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn count_distinct(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select count(distinct column_1) from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+            rows(vec![vec!["3"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn count_distinct_of_empty_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint);")
+        .expect("no system errors");
+    engine
+        .execute("select count(distinct column_1) from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+            rows(vec![vec!["0"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn count_distinct_per_group(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(
+            "select category, count(distinct column_1) as distinct_values, count(*) \
+             from schema_name.table_name group by category;",
+        )
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("category".to_owned(), PostgreSqlType::VarChar, 9),
+                ("distinct_values".to_owned(), PostgreSqlType::BigInt, -1),
+                ("count".to_owned(), PostgreSqlType::BigInt, -1),
+            ],
+            rows(vec![vec!["a", "2", "4"], vec!["b", "1", "3"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn column_not_in_group_by(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select column_1, count(*) from schema_name.table_name group by category;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::grouping_error("column_1".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn group_by_position(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select category, count(*) from schema_name.table_name group by 1;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("category".to_owned(), PostgreSqlType::VarChar, 9),
+                ("count".to_owned(), PostgreSqlType::BigInt, -1),
+            ],
+            rows(vec![vec!["a", "4"], vec!["b", "3"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn group_by_position_of_expression(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select count(*), column_1 + 1 from schema_name.table_name where column_1 > 1 group by 2;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("count".to_owned(), PostgreSqlType::BigInt, -1),
+                ("?column?".to_owned(), PostgreSqlType::Integer, -1),
+            ],
+            rows(vec![vec!["1", "3"], vec!["2", "4"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest(position, case::zero("0"), case::out_of_select_list("3"))]
+fn group_by_position_is_not_in_select_list(with_table: (QueryExecutor, ResultCollector), position: &str) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(
+            format!(
+                "select category, count(*) from schema_name.table_name group by {};",
+                position
+            )
+            .as_str(),
+        )
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::invalid_column_reference(format!(
+            "GROUP BY position {} is not in select list",
+            position
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
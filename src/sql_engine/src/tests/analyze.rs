@@ -0,0 +1,184 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use data_manager::ColumnStatistics;
+
+fn with_table() -> (QueryExecutor, ResultCollector, Arc<DataManager>) {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage.clone(), collector.clone());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_si smallint, column_vc varchar(10));")
+        .expect("no system errors");
+
+    (engine, collector, storage)
+}
+
+fn statistics(storage: &DataManager) -> Option<Vec<ColumnStatistics>> {
+    let (schema_id, table_id) = match storage.table_exists("schema_name", "table_name") {
+        Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        _ => return None,
+    };
+    storage.table_statistics(schema_id, table_id)
+}
+
+#[test]
+fn analyze_empty_table() {
+    let (mut engine, collector, storage) = with_table();
+    engine
+        .execute("analyze schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::AnalyzeComplete),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    let empty = ColumnStatistics {
+        distinct: 0,
+        min: None,
+        max: None,
+        null_fraction: 0.0,
+    };
+    assert_eq!(statistics(&storage), Some(vec![empty.clone(), empty]));
+}
+
+#[test]
+fn analyze_records_min_and_max() {
+    let (mut engine, collector, storage) = with_table();
+    engine
+        .execute("insert into schema_name.table_name values (3, 'b'), (-1, 'a'), (5, 'c'), (3, 'b');")
+        .expect("no system errors");
+    engine
+        .execute("analyze table schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::AnalyzeComplete),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    assert_eq!(
+        statistics(&storage),
+        Some(vec![
+            ColumnStatistics {
+                distinct: 3,
+                min: Some("-1".to_owned()),
+                max: Some("5".to_owned()),
+                null_fraction: 0.0,
+            },
+            ColumnStatistics {
+                distinct: 3,
+                min: Some("a".to_owned()),
+                max: Some("c".to_owned()),
+                null_fraction: 0.0,
+            }
+        ])
+    );
+}
+
+#[test]
+fn analyze_computes_null_fraction() {
+    let (mut engine, _collector, storage) = with_table();
+    engine
+        .execute("insert into schema_name.table_name values (1, 'a'), (2, 'b');")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name (column_si) values (3), (4);")
+        .expect("no system errors");
+    engine
+        .execute("analyze schema_name.table_name;")
+        .expect("no system errors");
+
+    assert_eq!(
+        statistics(&storage).map(|columns| columns[1].clone()),
+        Some(ColumnStatistics {
+            distinct: 2,
+            min: Some("a".to_owned()),
+            max: Some("b".to_owned()),
+            null_fraction: 0.5,
+        })
+    );
+}
+
+#[test]
+fn analyze_updates_stale_statistics() {
+    let (mut engine, _collector, storage) = with_table();
+    engine
+        .execute("insert into schema_name.table_name values (1, 'a');")
+        .expect("no system errors");
+    engine
+        .execute("analyze schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (10, 'b');")
+        .expect("no system errors");
+
+    assert_eq!(
+        statistics(&storage).and_then(|columns| columns[0].max.clone()),
+        Some("1".to_owned())
+    );
+
+    engine
+        .execute("analyze schema_name.table_name;")
+        .expect("no system errors");
+
+    assert_eq!(
+        statistics(&storage).map(|columns| columns[0].clone()),
+        Some(ColumnStatistics {
+            distinct: 2,
+            min: Some("1".to_owned()),
+            max: Some("10".to_owned()),
+            null_fraction: 0.0,
+        })
+    );
+}
+
+#[rstest::rstest]
+fn analyze_non_existent_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("analyze schema_name.non_existent;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn analyze_table_of_non_existent_schema(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("analyze non_existent.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::schema_does_not_exist("non_existent".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
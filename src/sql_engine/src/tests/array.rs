@@ -0,0 +1,209 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (tags integer[], names varchar(5)[]);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::rstest]
+fn insert_and_select_arrays(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values (array[1, 2, 3], array['a', 'b c', null]);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("tags".to_owned(), PostgreSqlType::IntegerArray, -1),
+                ("names".to_owned(), PostgreSqlType::VarCharArray, 9),
+            ],
+            vec![vec![Some("{1,2,3}".to_owned()), Some("{a,\"b c\",NULL}".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn insert_array_literal(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values ('{ 1, 02 }', '{}');")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("tags".to_owned(), PostgreSqlType::IntegerArray, -1),
+                ("names".to_owned(), PostgreSqlType::VarCharArray, 9),
+            ],
+            vec![vec![Some("{1,2}".to_owned()), Some("{}".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_array(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values (array[1], array['a']);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set tags = array[4, 5];")
+        .expect("no system errors");
+    engine
+        .execute("select tags from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("tags".to_owned(), PostgreSqlType::IntegerArray, -1)],
+            vec![vec![Some("{4,5}".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn element_type_mismatch(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values (array[1, 'abc'], array['a']);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch(
+            "abc",
+            PostgreSqlType::IntegerArray,
+            "tags".to_owned(),
+            1,
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn not_an_array(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.table_name values (1, array['a']);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch(
+            "1",
+            PostgreSqlType::IntegerArray,
+            "tags".to_owned(),
+            1,
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn multidimensional_arrays_are_not_supported(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (matrix integer[][]);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::feature_not_supported(
+            "INT[][] type is not supported".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+fn assert_unsupported_array(
+    engine_with_collector: (QueryExecutor, ResultCollector),
+    column_type: &str,
+    type_name: &str,
+) {
+    let (mut engine, collector) = engine_with_collector;
+    engine
+        .execute(format!("create table schema_name.table_name (column_test {});", column_type).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::feature_not_supported(format!(
+            "{} type is not supported",
+            type_name
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn arrays_of_bytea_are_not_supported(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    assert_unsupported_array(sql_engine_with_schema, "bytea[]", "BYTEA[]");
+}
+
+#[rstest::rstest]
+fn arrays_of_timestamps_are_not_supported(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    assert_unsupported_array(sql_engine_with_schema, "timestamp[]", "TIMESTAMP[]");
+}
+
+#[rstest::rstest]
+fn arrays_of_json_are_not_supported(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    assert_unsupported_array(sql_engine_with_schema, "json[]", "json[]");
+}
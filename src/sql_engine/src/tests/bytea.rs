@@ -0,0 +1,133 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, data bytea);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::rstest]
+fn insert_and_select_hex_format(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(r"insert into schema_name.table_name values (1, '\x48656C6C6F'), (2, '\x');")
+        .expect("no system errors");
+    engine
+        .execute("select data from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("data".to_owned(), PostgreSqlType::Bytea, -1)],
+            vec![vec![Some(r"\x48656c6c6f".to_owned())], vec![Some(r"\x".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn insert_escape_format(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(r"insert into schema_name.table_name values (1, 'Hi\\\000');")
+        .expect("no system errors");
+    engine
+        .execute("select data from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("data".to_owned(), PostgreSqlType::Bytea, -1)],
+            vec![vec![Some(r"\x48695c00".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn insert_invalid_escape_sequence(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(r"insert into schema_name.table_name values (1, '\q');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch(
+            r"\q",
+            PostgreSqlType::Bytea,
+            "data".to_owned(),
+            1,
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_with_invalid_hex(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(r"insert into schema_name.table_name values (1, '\x00');")
+        .expect("no system errors");
+    engine
+        .execute(r"update schema_name.table_name set data = '\x0';")
+        .expect("no system errors");
+    engine
+        .execute("select data from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch(
+            r"\x0",
+            PostgreSqlType::Bytea,
+            "data".to_owned(),
+            1,
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("data".to_owned(), PostgreSqlType::Bytea, -1)],
+            vec![vec![Some(r"\x00".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
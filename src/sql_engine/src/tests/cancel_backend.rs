@@ -0,0 +1,190 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+use std::thread;
+
+type Session = (QueryExecutor, ResultCollector);
+
+// sessions are numbered from zero in the order they are opened
+const FIRST_PID: &str = "0";
+
+const LONG_QUERY: &str = "select count(*) from generate_series(1, 2000) as a, generate_series(1, 2000) as b;";
+
+#[rstest::fixture]
+fn two_sessions() -> (Session, Session) {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let first_collector = Collector::new();
+    let first = QueryExecutor::new(storage.clone(), first_collector.clone());
+    let second_collector = Collector::new();
+    let second = QueryExecutor::new(storage, second_collector.clone());
+
+    ((first, first_collector), (second, second_collector))
+}
+
+fn canceled(result: &str) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![("pg_cancel_backend".to_owned(), PostgreSqlType::Bool, -1)],
+        vec![vec![text(result)]],
+    )))
+}
+
+// executes query on other thread and cancels it from the other session until it is finished
+fn canceled_by(canceled: Session, query: &'static str, canceling: &mut QueryExecutor) -> Session {
+    let (mut executor, collector) = canceled;
+    let running = thread::spawn(move || {
+        executor.execute(query).expect("no system errors");
+        executor
+    });
+    while !running.is_finished() {
+        canceling
+            .execute(format!("select pg_cancel_backend({});", FIRST_PID).as_str())
+            .expect("no system errors");
+    }
+    (running.join().expect("to finish"), collector)
+}
+
+#[rstest::rstest]
+fn backend_pid(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select pg_backend_pid();").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("pg_backend_pid".to_owned(), PostgreSqlType::Integer, -1)],
+            vec![vec![text(FIRST_PID)]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn cancel_query_of_other_session(two_sessions: (Session, Session)) {
+    let (first, (mut second, _second_collector)) = two_sessions;
+    let (_first, first_collector) = canceled_by(first, LONG_QUERY, &mut second);
+
+    first_collector
+        .assert_content_for_single_queries(vec![Err(QueryError::canceled_by_user()), Ok(QueryEvent::QueryComplete)]);
+}
+
+#[rstest::rstest]
+fn cancel_own_query(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select pg_cancel_backend(pg_backend_pid());")
+        .expect("no system errors");
+    engine
+        .execute("select count(*) from generate_series(1, 3);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::canceled_by_user()),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+            vec![vec![text("3")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn cancel_not_existing_backend(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select pg_cancel_backend(42);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::Notice {
+            severity: "WARNING".to_owned(),
+            message: "PID 42 is not a PostgreSQL backend process".to_owned(),
+        }),
+        canceled("f"),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn cancel_closed_backend(two_sessions: (Session, Session)) {
+    let ((first, _first_collector), (mut second, second_collector)) = two_sessions;
+    drop(first);
+    second
+        .execute(format!("select pg_cancel_backend({});", FIRST_PID).as_str())
+        .expect("no system errors");
+
+    second_collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::Notice {
+            severity: "WARNING".to_owned(),
+            message: format!("PID {} is not a PostgreSQL backend process", FIRST_PID),
+        }),
+        canceled("f"),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn cancel_null_backend(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select pg_cancel_backend(null);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("pg_cancel_backend".to_owned(), PostgreSqlType::Bool, -1)],
+            vec![vec![None]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn canceled_update_changes_nothing(two_sessions: (Session, Session)) {
+    let ((mut first, first_collector), (mut second, _second_collector)) = two_sessions;
+    first.execute("create schema schema_name;").expect("no system errors");
+    first
+        .execute("create table schema_name.table_name (column_i integer);")
+        .expect("no system errors");
+    first
+        .execute("insert into schema_name.table_name values (1), (2);")
+        .expect("no system errors");
+
+    let (mut first, first_collector) = canceled_by(
+        (first, first_collector),
+        "update schema_name.table_name set column_i = a.a \
+         from generate_series(1, 2000) as a, generate_series(1, 2000) as b;",
+        &mut second,
+    );
+    first
+        .execute("select column_i from schema_name.table_name;")
+        .expect("no system errors");
+
+    first_collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::canceled_by_user()),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_i".to_owned(), PostgreSqlType::Integer, -1)],
+            vec![vec![text("1")], vec![text("2")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
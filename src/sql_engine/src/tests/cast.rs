@@ -0,0 +1,199 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::rstest(
+    value,
+    rounded,
+    case::half_rounded_down_to_even("2.5", "2"),
+    case::half_rounded_up_to_even("3.5", "4"),
+    case::above_half("2.7", "3"),
+    case::below_half("2.4", "2"),
+    case::zero_and_half("0.5", "0"),
+    case::negative_half_rounded_up_to_even("-2.5", "-2"),
+    case::negative_half_rounded_down_to_even("-3.5", "-4"),
+    case::negative_above_half("-2.7", "-3"),
+    case::negative_below_half("-2.4", "-2"),
+    case::integer("7", "7")
+)]
+fn number_cast_to_integer_is_rounded_half_to_even(
+    sql_engine: (QueryExecutor, ResultCollector),
+    value: &str,
+    rounded: &str,
+) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute(format!("select cast({} as int), {}::smallint;", value, value).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("?column?".to_owned(), PostgreSqlType::Integer, -1),
+            ("?column?".to_owned(), PostgreSqlType::SmallInt, -1),
+        ],
+        vec![vec![text(rounded), text(rounded)]],
+    )))]);
+}
+
+#[rstest::rstest(
+    value,
+    truncated,
+    floor,
+    ceil,
+    case::half("2.5", "2", "2", "3"),
+    case::above_half("2.7", "2", "2", "3"),
+    case::negative_half("-2.5", "-2", "-3", "-2"),
+    case::negative_above_half("-2.7", "-2", "-3", "-2"),
+    case::integer("-3", "-3", "-3", "-3")
+)]
+fn explicit_rounding(
+    sql_engine: (QueryExecutor, ResultCollector),
+    value: &str,
+    truncated: &str,
+    floor: &str,
+    ceil: &str,
+) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute(format!("select trunc({}), floor({}), ceil({});", value, value, value).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("trunc".to_owned(), PostgreSqlType::Decimal, -1),
+            ("floor".to_owned(), PostgreSqlType::Decimal, -1),
+            ("ceil".to_owned(), PostgreSqlType::Decimal, -1),
+        ],
+        vec![vec![text(truncated), text(floor), text(ceil)]],
+    )))]);
+}
+
+#[rstest::rstest]
+fn rounding_of_text(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select floor('2.5');").expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![Err(QueryError::undefined_function(
+        "floor".to_owned(),
+        "".to_owned(),
+        "STRING".to_owned(),
+    ))]);
+}
+
+#[rstest::rstest]
+fn rounded_value_out_of_range(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select cast(32766.5 as smallint);")
+        .expect("no system errors");
+    engine
+        .execute("select cast(32767.5 as smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("?column?".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("32766")]],
+        ))),
+        Err(QueryError::value_out_of_range(PostgreSqlType::SmallInt)),
+    ]);
+}
+
+#[rstest::rstest]
+fn text_cast_to_integer_is_not_rounded(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("select cast('42' as int);").expect("no system errors");
+    engine.execute("select cast('2.5' as int);").expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("?column?".to_owned(), PostgreSqlType::Integer, -1)],
+            vec![vec![text("42")]],
+        ))),
+        Err(QueryError::invalid_text_representation(
+            PostgreSqlType::Integer,
+            "2.5".to_owned(),
+        )),
+    ]);
+}
+
+#[rstest::rstest]
+fn null_cast(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select cast(null as bigint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::RecordsSelected((
+        vec![("?column?".to_owned(), PostgreSqlType::BigInt, -1)],
+        vec![vec![None]],
+    )))]);
+}
+
+#[rstest::rstest]
+fn insert_rounded_values(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "insert into schema_name.table_name values \
+             (cast(2.5 as smallint)), (cast(3.5 as smallint)), (cast(-2.5 as smallint)), (trunc(-2.7));",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("id".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("2")], vec![text("4")], vec![text("-2")], vec![text("-2")]],
+        ))),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_with_rounded_value(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set id = cast(id * 2.5 as smallint);")
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("id".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("2")]],
+        ))),
+    ]);
+}
@@ -0,0 +1,400 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::rstest]
+fn select_functions(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select proname, pronargs, proargtypes from pg_catalog.pg_proc;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("proname".to_owned(), PostgreSqlType::VarChar, -1),
+                ("pronargs".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("proargtypes".to_owned(), PostgreSqlType::VarChar, -1),
+            ],
+            vec![
+                vec![text("lower"), text("1"), text("1043")],
+                vec![text("upper"), text("1"), text("1043")],
+                vec![text("generate_series"), text("3"), text("23 23 23")],
+                vec![text("generate_series"), text("2"), text("23 23")],
+                vec![text("pg_backend_pid"), text("0"), text("")],
+                vec![text("count"), text("1"), text("2276")],
+                vec![text("pg_cancel_backend"), text("1"), text("23")],
+                vec![text("count"), text("0"), text("")],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn select_function_by_name(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select * from pg_catalog.pg_proc where proname = 'upper';")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("oid".to_owned(), PostgreSqlType::Integer, -1),
+                ("proname".to_owned(), PostgreSqlType::VarChar, -1),
+                ("pronamespace".to_owned(), PostgreSqlType::Integer, -1),
+                ("pronargs".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("proargtypes".to_owned(), PostgreSqlType::VarChar, -1),
+                ("prorettype".to_owned(), PostgreSqlType::Integer, -1),
+            ],
+            vec![vec![
+                text("871"),
+                text("upper"),
+                text("11"),
+                text("1"),
+                text("1043"),
+                text("1043"),
+            ]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn count_overloaded_functions(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select count(*) from pg_catalog.pg_proc where proname = 'generate_series';")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+            vec![vec![text("2")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn join_return_types(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute(
+            "select p.proname, t.typname from pg_catalog.pg_proc as p \
+             join pg_catalog.pg_type as t on p.prorettype = t.oid \
+             where p.pronargs = 1;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("proname".to_owned(), PostgreSqlType::VarChar, -1),
+                ("typname".to_owned(), PostgreSqlType::VarChar, -1),
+            ],
+            vec![
+                vec![text("lower"), text("varchar")],
+                vec![text("upper"), text("varchar")],
+                vec![text("count"), text("int8")],
+                vec![text("pg_cancel_backend"), text("bool")],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn non_existent_catalog_table(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select * from pg_catalog.pg_non_existent;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::schema_does_not_exist("pg_catalog".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[cfg(test)]
+mod stat_user_tables {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (column_si smallint);")
+            .expect("no system errors");
+        (engine, collector)
+    }
+
+    #[rstest::rstest]
+    fn all_counters(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name values (1), (2), (3);")
+            .expect("no system errors");
+        engine
+            .execute("update schema_name.table_name set column_si = 4 where column_si = 3;")
+            .expect("no system errors");
+        engine
+            .execute("delete from schema_name.table_name where column_si = 1;")
+            .expect("no system errors");
+        engine
+            .execute("select * from pg_catalog.pg_stat_user_tables;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(3)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsUpdated(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsDeleted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("schemaname".to_owned(), PostgreSqlType::VarChar, -1),
+                    ("tablename".to_owned(), PostgreSqlType::VarChar, -1),
+                    ("n_live_tup".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("n_dead_tup".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("seq_scan".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("seq_tup_read".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("idx_scan".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("idx_tup_fetch".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("n_tup_ins".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("n_tup_upd".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("n_tup_del".to_owned(), PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec![
+                    text("schema_name"),
+                    text("table_name"),
+                    text("2"),
+                    text("0"),
+                    text("2"),
+                    text("2"),
+                    None,
+                    None,
+                    text("3"),
+                    text("1"),
+                    text("1"),
+                ]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn insert_increments_inserted_rows(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name values (1), (2);")
+            .expect("no system errors");
+        engine
+            .execute("select n_tup_ins, n_live_tup from pg_catalog.pg_stat_user_tables;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("n_tup_ins".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("n_live_tup".to_owned(), PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec![text("2"), text("2")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn delete_increments_deleted_rows(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name values (1), (2);")
+            .expect("no system errors");
+        engine
+            .execute("delete from schema_name.table_name;")
+            .expect("no system errors");
+        engine
+            .execute("select n_tup_del, n_live_tup from pg_catalog.pg_stat_user_tables;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsDeleted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("n_tup_del".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("n_live_tup".to_owned(), PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec![text("2"), text("0")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn select_increments_sequential_scans(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name values (1), (2);")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+        engine
+            .execute("select seq_scan, seq_tup_read from pg_catalog.pg_stat_user_tables;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name where column_si = 2;")
+            .expect("no system errors");
+        engine
+            .execute("select seq_scan, seq_tup_read from pg_catalog.pg_stat_user_tables;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                vec![vec![text("1")], vec![text("2")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("seq_scan".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("seq_tup_read".to_owned(), PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec![text("1"), text("2")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                vec![vec![text("2")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("seq_scan".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("seq_tup_read".to_owned(), PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec![text("2"), text("3")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn limit_stops_sequential_scan(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name values (1), (2), (3);")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name limit 1;")
+            .expect("no system errors");
+        engine
+            .execute("select seq_scan, seq_tup_read from pg_catalog.pg_stat_user_tables;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(3)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                vec![vec![text("1")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("seq_scan".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("seq_tup_read".to_owned(), PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec![text("1"), text("1")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn counters_are_kept_between_queries(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name values (1);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (2), (3);")
+            .expect("no system errors");
+        engine
+            .execute("update schema_name.table_name set column_si = 5;")
+            .expect("no system errors");
+        engine
+            .execute("update schema_name.table_name set column_si = 6 where column_si = 5;")
+            .expect("no system errors");
+        engine
+            .execute("select n_tup_ins, n_tup_upd from pg_catalog.pg_stat_user_tables;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsUpdated(3)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsUpdated(3)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("n_tup_ins".to_owned(), PostgreSqlType::BigInt, -1),
+                    ("n_tup_upd".to_owned(), PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec![text("3"), text("6")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint unique, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3, 'three'), (1, 'one'), (4, 'four'), (2, 'two');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(4)),
+    ]
+}
+
+fn selected(rows: Vec<(&str, &str)>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+            ("name".to_owned(), PostgreSqlType::VarChar, 14),
+        ],
+        rows.into_iter().map(|(id, name)| vec![text(id), text(name)]).collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn rows_are_returned_in_index_order(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("cluster schema_name.table_name using table_name_id_key;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableClustered),
+        selected(vec![("1", "one"), ("2", "two"), ("3", "three"), ("4", "four")]),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn data_is_preserved(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("cluster schema_name.table_name using table_name_id_key;")
+        .expect("no system errors");
+    engine
+        .execute("select count(*) from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select name from schema_name.table_name where id = 3;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableClustered),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+            vec![vec![text("4")]],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("name".to_owned(), PostgreSqlType::VarChar, 14)],
+            vec![vec![text("three")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn rows_inserted_after_cluster_are_not_ordered(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("cluster schema_name.table_name using table_name_id_key;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (0, 'zero');")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableClustered),
+        Ok(QueryEvent::RecordsInserted(1)),
+        selected(vec![
+            ("1", "one"),
+            ("2", "two"),
+            ("3", "three"),
+            ("4", "four"),
+            ("0", "zero"),
+        ]),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn table_without_index(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint);")
+        .expect("no system errors");
+    engine
+        .execute("cluster schema_name.table_name using table_name_id_key;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Err(QueryError::index_does_not_exist(
+            "table_name_id_key".to_owned(),
+            "table_name".to_owned(),
+        )),
+    ]);
+}
+
+#[rstest::rstest]
+fn non_existent_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("cluster schema_name.non_existent using non_existent_id_key;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+    ]);
+}
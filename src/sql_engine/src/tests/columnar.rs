@@ -0,0 +1,231 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+use std::time::{Duration, Instant};
+
+#[rstest::fixture]
+fn with_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.row_table (id smallint, name varchar(10), flag boolean);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.columnar_table (id smallint, name varchar(10), flag boolean) \
+             with (storage_layout = 'columnar');",
+        )
+        .expect("no system errors");
+    for table in &["row_table", "columnar_table"] {
+        engine
+            .execute(
+                format!(
+                    "insert into schema_name.{} values (1, 'one', true), (2, 'two', false), (3, 'three', true);",
+                    table
+                )
+                .as_str(),
+            )
+            .expect("no system errors");
+    }
+
+    (engine, collector)
+}
+
+fn tables_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn rows(rows: Vec<Vec<&str>>) -> Vec<Vec<Option<String>>> {
+    rows.into_iter()
+        .map(|row| row.into_iter().map(text).collect())
+        .collect()
+}
+
+#[rstest::rstest]
+fn select_the_same_as_from_row_layout(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    for table in &["row_table", "columnar_table"] {
+        engine
+            .execute(format!("select * from schema_name.{};", table).as_str())
+            .expect("no system errors");
+        engine
+            .execute(format!("select flag, id from schema_name.{} where id > 1;", table).as_str())
+            .expect("no system errors");
+    }
+
+    let all = Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+            ("name".to_owned(), PostgreSqlType::VarChar, 14),
+            ("flag".to_owned(), PostgreSqlType::Bool, -1),
+        ],
+        rows(vec![
+            vec!["1", "one", "t"],
+            vec!["2", "two", "f"],
+            vec!["3", "three", "t"],
+        ]),
+    )));
+    let filtered = Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("flag".to_owned(), PostgreSqlType::Bool, -1),
+            ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+        ],
+        rows(vec![vec!["f", "2"], vec!["t", "3"]]),
+    )));
+    let mut expected = tables_created();
+    for _ in 0..2 {
+        expected.extend(vec![
+            all.clone(),
+            Ok(QueryEvent::QueryComplete),
+            filtered.clone(),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_and_delete(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("update schema_name.columnar_table set name = 'updated' where id = 2;")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.columnar_table where id = 1;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.columnar_table;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("name".to_owned(), PostgreSqlType::VarChar, 14),
+                ("flag".to_owned(), PostgreSqlType::Bool, -1),
+            ],
+            rows(vec![vec!["2", "updated", "f"], vec!["3", "three", "t"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn join_tables_with_different_layouts(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute(
+            "select r.id, c.name from schema_name.row_table as r \
+             join schema_name.columnar_table as c on r.id = c.id where c.flag;",
+        )
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("name".to_owned(), PostgreSqlType::VarChar, 14),
+            ],
+            rows(vec![vec!["1", "one"], vec!["3", "three"]]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn unknown_storage_layout(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint) with (storage_layout = 'diagonal');")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (id smallint) with (fill_factor = 10);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value(
+            "invalid value for parameter \"storage_layout\": \"diagonal\"".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_parameter_value(
+            "unrecognized parameter \"fill_factor\"".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn select_all_is_not_much_slower_than_from_row_layout(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, _collector) = sql_engine_with_schema;
+    for (table, options) in &[
+        ("row_table", ""),
+        ("columnar_table", "with (storage_layout = 'columnar')"),
+    ] {
+        engine
+            .execute(
+                format!(
+                    "create table schema_name.{} (c1 integer, c2 integer, c3 integer, c4 integer, c5 integer) {};",
+                    table, options
+                )
+                .as_str(),
+            )
+            .expect("no system errors");
+        let values = (1..=2000)
+            .map(|value| format!("({0}, {0}, {0}, {0}, {0})", value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        engine
+            .execute(format!("insert into schema_name.{} values {};", table, values).as_str())
+            .expect("no system errors");
+    }
+
+    let mut elapsed = |table: &str| {
+        let start = Instant::now();
+        engine
+            .execute(format!("select * from schema_name.{};", table).as_str())
+            .expect("no system errors");
+        start.elapsed()
+    };
+    // scans are interleaved so that both layouts are measured under the same load
+    let mut row = Duration::MAX;
+    let mut columnar = Duration::MAX;
+    for _ in 0..7 {
+        row = row.min(elapsed("row_table"));
+        columnar = columnar.min(elapsed("columnar_table"));
+    }
+
+    assert!(columnar <= row * 3 / 2, "columnar {:?} row {:?}", columnar, row);
+}
@@ -0,0 +1,780 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::{results::QueryEvent, sql_types::PostgreSqlType};
+use std::fs;
+use tempfile::TempDir;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, val varchar(5), flag boolean);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn rows(values: Vec<Vec<&str>>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+            ("val".to_owned(), PostgreSqlType::VarChar, 9),
+            ("flag".to_owned(), PostgreSqlType::Bool, -1),
+        ],
+        values
+            .into_iter()
+            .map(|row| row.into_iter().map(text).collect())
+            .collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn copy_rows_from_stdin(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\ta\tt\n2\t\\N\tf\n");
+    engine.copy_data(b"3\tc\\td\ttrue\n\\.\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![
+            vec!["1", "a", "t"],
+            vec!["2", "NULL", "f"],
+            vec!["3", "c\td", "t"],
+        ]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn empty_string_and_null_are_selected_differently(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\t\tt\n2\t\\N\tf\n3\tNULL\tt\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select val from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::VarChar, 9)],
+            vec![vec![Some("".to_owned())], vec![None], vec![Some("NULL".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_listed_columns(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name (val, id) from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"a\t1\nb\t2\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(2)),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![vec!["1", "a", "NULL"], vec!["2", "b", "NULL"]]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn columns_that_are_not_copied_get_default(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint not null, v smallint default 7);")
+        .expect("no system errors");
+    engine
+        .execute("copy schema_name.table_name (id) from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\n2\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("copy schema_name.table_name (v) from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"3\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(1)),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::CopyInStarted(1)),
+        Err(QueryError::not_null_violation("id".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![vec![text("1"), text("7")], vec![text("2"), text("7")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_row_fails_whole_copy(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\ta\tt\ntwo\tb\tf\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Err(QueryError::type_mismatch(
+            "two",
+            PostgreSqlType::SmallInt,
+            "id".to_owned(),
+            2,
+        )),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn row_with_missing_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\ta\n");
+    engine.copy_done().expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Err(QueryError::bad_copy_file_format(
+            "missing data for column \"flag\"".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn row_with_extra_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\ta\tt\textra\n");
+    engine.copy_done().expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Err(QueryError::bad_copy_file_format(
+            "extra data after last expected column".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn client_aborts_copy(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\ta\tt\n");
+    engine.copy_fail("file is corrupted").expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Err(QueryError::query_canceled(
+            "COPY from stdin failed: file is corrupted".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_into_non_existent_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("copy schema_name.non_existent from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\n");
+    engine.copy_done().expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn copy_into_non_existent_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name (id, missing) from stdin;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::column_does_not_exist(vec!["missing".to_owned()])),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_csv_rows_from_stdin(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin with (format csv);")
+        .expect("no system errors");
+    engine.copy_data(b"1,\"a,b\",t\n2,,f\n3,\"\",true\n\\.\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![vec!["1", "a,b", "t"], vec!["2", "NULL", "f"], vec!["3", "", "t"]]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_rows_to_stdout(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin;")
+        .expect("no system errors");
+    engine.copy_data(b"1\ta\tt\n2\t\\N\tf\n3\tc\\td\t\\N\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("copy schema_name.table_name to stdout;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsCopied {
+            columns: 3,
+            rows: vec![
+                "1\ta\tt\n".to_owned(),
+                "2\t\\N\tf\n".to_owned(),
+                "3\tc\\td\t\\N\n".to_owned(),
+            ],
+        }),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_listed_columns_to_stdout_in_csv(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin with (format csv);")
+        .expect("no system errors");
+    engine.copy_data(b"1,\"a,b\",t\n2,,f\n3,\"\",\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("copy schema_name.table_name (val, id) to stdout with (format csv);")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsCopied {
+            columns: 2,
+            rows: vec!["\"a,b\",1\n".to_owned(), ",2\n".to_owned(), "\"\",3\n".to_owned()],
+        }),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_from_non_existent_table_to_stdout(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("copy schema_name.non_existent to stdout;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn ignore_invalid_row(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin csv with (on_error ignore);")
+        .expect("no system errors");
+    engine.copy_data(b"1,a,t\ntwo,b,f\n3,c,true\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Ok(QueryEvent::Notice {
+            severity: "NOTICE".to_owned(),
+            message: format!(
+                "skipping row 2: {}",
+                QueryError::type_mismatch("two", PostgreSqlType::SmallInt, "id".to_owned(), 2)
+            ),
+        }),
+        Ok(QueryEvent::Notice {
+            severity: "NOTICE".to_owned(),
+            message: "1 row was skipped".to_owned(),
+        }),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![vec!["1", "a", "t"], vec!["3", "c", "t"]]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn ignore_all_invalid_rows(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin csv with (on_error ignore);")
+        .expect("no system errors");
+    engine.copy_data(b"1,a\n2,b,t,extra\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Ok(QueryEvent::Notice {
+            severity: "NOTICE".to_owned(),
+            message: "skipping row 1: missing data for column \"flag\"".to_owned(),
+        }),
+        Ok(QueryEvent::Notice {
+            severity: "NOTICE".to_owned(),
+            message: "skipping row 2: extra data after last expected column".to_owned(),
+        }),
+        Ok(QueryEvent::Notice {
+            severity: "NOTICE".to_owned(),
+            message: "2 rows were skipped".to_owned(),
+        }),
+        Ok(QueryEvent::RecordsInserted(0)),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn stop_on_invalid_row(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin csv with (on_error stop);")
+        .expect("no system errors");
+    engine.copy_data(b"1,a,t\ntwo,b,f\nthree,c,t\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Err(QueryError::type_mismatch(
+            "two",
+            PostgreSqlType::SmallInt,
+            "id".to_owned(),
+            2,
+        )),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn on_error_is_not_allowed_for_copy_to(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name to stdout with (on_error ignore);")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::syntax_error(
+            "\"copy schema_name.table_name to stdout with (on_error ignore);\" can't be parsed".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::fixture]
+fn copy_directory() -> TempDir {
+    tempfile::tempdir().expect("to create temporary folder")
+}
+
+#[rstest::rstest]
+fn copy_csv_file_with_header(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    fs::write(
+        copy_directory.path().join("data.csv"),
+        "id,val,flag\n1,\"a\nb\",t\n2,,f\n",
+    )
+    .expect("to write file");
+    engine.set_copy_directory(copy_directory.path().to_path_buf());
+    engine
+        .execute("copy schema_name.table_name from 'data.csv' csv header;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![vec!["1", "a\nb", "t"], vec!["2", "NULL", "f"]]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_text_file_by_absolute_path(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    let path = copy_directory.path().join("data.txt");
+    fs::write(&path, "1\ta\tt\n2\t\\N\tf\n\\.\n3\tc\tt\n").expect("to write file");
+    engine.set_copy_directory(copy_directory.path().to_path_buf());
+    engine
+        .execute(format!("copy schema_name.table_name from '{}';", path.display()).as_str())
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![vec!["1", "a", "t"], vec!["2", "NULL", "f"]]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_file_with_byte_order_mark(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    fs::write(copy_directory.path().join("data.csv"), "\u{feff}1,a,t\n").expect("to write file");
+    engine.set_copy_directory(copy_directory.path().to_path_buf());
+    engine
+        .execute("copy schema_name.table_name from 'data.csv' with (format csv);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![vec!["1", "a", "t"]]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_file_with_wrong_number_of_columns(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    fs::write(copy_directory.path().join("data.csv"), "1,a,t\n2,b\n").expect("to write file");
+    engine.set_copy_directory(copy_directory.path().to_path_buf());
+    engine
+        .execute("copy schema_name.table_name from 'data.csv' csv;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::bad_copy_file_format(
+            "missing data for column \"flag\"".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        rows(vec![]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+fn count_rows(count: usize) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+        vec![vec![text(count.to_string().as_str())]],
+    )))
+}
+
+#[rstest::rstest]
+fn copy_file_with_more_rows_than_batch(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    let data = (0..2500).map(|id| format!("{},a,t\n", id)).collect::<String>();
+    fs::write(copy_directory.path().join("data.csv"), data).expect("to write file");
+    engine.set_copy_directory(copy_directory.path().to_path_buf());
+    engine
+        .execute("copy schema_name.table_name from 'data.csv' csv;")
+        .expect("no system errors");
+    engine
+        .execute("select count(*) from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2500)),
+        Ok(QueryEvent::QueryComplete),
+        count_rows(2500),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn invalid_row_removes_rows_of_written_batches(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    let mut data = (0..2500).map(|id| format!("{},a,t\n", id)).collect::<String>();
+    data.push_str("2500,b\n");
+    fs::write(copy_directory.path().join("data.csv"), data).expect("to write file");
+    engine.set_copy_directory(copy_directory.path().to_path_buf());
+    engine
+        .execute("copy schema_name.table_name from 'data.csv' csv;")
+        .expect("no system errors");
+    engine
+        .execute("select count(*) from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::bad_copy_file_format(
+            "missing data for column \"flag\"".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        count_rows(0),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_non_existent_file(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    engine.set_copy_directory(copy_directory.path().to_path_buf());
+    engine
+        .execute("copy schema_name.table_name from 'non_existent.csv' csv;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::undefined_file("non_existent.csv".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_file_outside_of_copy_directory(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    let allowed = copy_directory.path().join("allowed");
+    fs::create_dir(&allowed).expect("to create folder");
+    fs::write(copy_directory.path().join("data.csv"), "1,a,t\n").expect("to write file");
+    engine.set_copy_directory(allowed);
+    engine
+        .execute("copy schema_name.table_name from '../data.csv' csv;")
+        .expect("no system errors");
+    engine
+        .execute(
+            format!(
+                "copy schema_name.table_name from '{}' csv;",
+                copy_directory.path().join("data.csv").display()
+            )
+            .as_str(),
+        )
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::permission_denied(
+            "could not open file \"../data.csv\" for reading: it is outside of the copy directory".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied(format!(
+            "could not open file \"{}\" for reading: it is outside of the copy directory",
+            copy_directory.path().join("data.csv").display()
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_missing_file_outside_of_copy_directory(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    let allowed = copy_directory.path().join("allowed");
+    fs::create_dir(&allowed).expect("to create folder");
+    engine.set_copy_directory(allowed);
+    engine
+        .execute("copy schema_name.table_name from '../missing.csv' csv;")
+        .expect("no system errors");
+    engine
+        .execute("copy schema_name.table_name from '/missing/data.csv' csv;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::permission_denied(
+            "could not open file \"../missing.csv\" for reading: it is outside of the copy directory".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::permission_denied(
+            "could not open file \"/missing/data.csv\" for reading: it is outside of the copy directory".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_file_without_copy_directory(with_table: (QueryExecutor, ResultCollector), copy_directory: TempDir) {
+    let (mut engine, collector) = with_table;
+    let path = copy_directory.path().join("data.csv");
+    fs::write(&path, "1,a,t\n").expect("to write file");
+    engine
+        .execute(format!("copy schema_name.table_name from '{}' csv;", path.display()).as_str())
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::permission_denied(
+            "permission denied to COPY from a file".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn copy_with_header_from_stdin_and_to_stdout(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("copy schema_name.table_name from stdin with (format csv, header true);")
+        .expect("no system errors");
+    engine.copy_data(b"id,val,flag\n1,a,t\n");
+    engine.copy_done().expect("no system errors");
+    engine
+        .execute("copy schema_name.table_name (val, id) to stdout with (header);")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::CopyInStarted(3)),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsCopied {
+            columns: 2,
+            rows: vec!["val\tid\n".to_owned(), "a\t1\n".to_owned()],
+        }),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
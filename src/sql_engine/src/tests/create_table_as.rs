@@ -0,0 +1,185 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 'one'), (2, 'two');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(2)),
+    ]
+}
+
+fn columns() -> Vec<(String, PostgreSqlType, i32)> {
+    vec![
+        ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+        ("name".to_owned(), PostgreSqlType::VarChar, 14),
+    ]
+}
+
+#[rstest::rstest]
+fn copied_table_has_the_same_columns_and_rows(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("create table schema_name.copy_name as select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.copy_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![vec![text("1"), text("one")], vec![text("2"), text("two")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn modifying_copy_does_not_affect_source(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("create table schema_name.copy_name as select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.copy_name values (3, 'three');")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.copy_name set name = 'uno' where id = 1;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.copy_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![
+                vec![text("1"), text("uno")],
+                vec![text("2"), text("two")],
+                vec![text("3"), text("three")],
+            ],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![vec![text("1"), text("one")], vec![text("2"), text("two")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn copy_to_existing_table(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("create table schema_name.copy_name as select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.copy_name as select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Err(QueryError::table_already_exists("schema_name.copy_name".to_owned())),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn copy_of_non_existent_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.copy_name as select * from schema_name.non_existent;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+    ]);
+}
+
+#[rstest::rstest]
+fn copy_across_schemas(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine.execute("create schema other_schema;").expect("no system errors");
+    engine
+        .execute("create table other_schema.table_name as select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from other_schema.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![vec![text("1"), text("one")], vec![text("2"), text("two")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn copy_into_non_existent_schema(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("create table non_existent.table_name as select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![Err(QueryError::schema_does_not_exist("non_existent".to_owned()))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn query_other_than_whole_table(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("create table schema_name.copy_name as select id from schema_name.table_name where id = 1;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![Err(QueryError::feature_not_supported(
+        "CREATE TABLE AS with a query other than SELECT * FROM table".to_owned(),
+    ))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
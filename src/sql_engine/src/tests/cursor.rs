@@ -0,0 +1,275 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+type Session = (QueryExecutor, ResultCollector);
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: Session) -> Session {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (2), (3), (4), (5);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_filled() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(5)),
+    ]
+}
+
+fn fetched(values: Vec<&str>) -> QueryResult {
+    Ok(QueryEvent::RecordsFetched((
+        vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+        values.into_iter().map(|value| vec![text(value)]).collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn fetch_advances_position(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare cur cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("fetch 2 from cur;").expect("no system errors");
+    engine.execute("fetch forward 2 in cur;").expect("no system errors");
+    engine.execute("fetch next from cur;").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorDeclared),
+        fetched(vec!["1", "2"]),
+        fetched(vec!["3", "4"]),
+        fetched(vec!["5"]),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn fetch_beyond_the_end_returns_no_rows(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare cur cursor for select * from schema_name.table_name where column_test > 3;")
+        .expect("no system errors");
+    engine.execute("fetch all from cur;").expect("no system errors");
+    engine.execute("fetch 10 from cur;").expect("no system errors");
+    engine.execute("fetch cur;").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorDeclared),
+        fetched(vec!["4", "5"]),
+        fetched(vec![]),
+        fetched(vec![]),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn fetch_from_closed_cursor(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare cur cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("close cur;").expect("no system errors");
+    engine.execute("fetch 1 from cur;").expect("no system errors");
+    engine.execute("close cur;").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorDeclared),
+        Ok(QueryEvent::CursorClosed),
+        Err(QueryError::cursor_does_not_exist("cur".to_owned())),
+        Err(QueryError::cursor_does_not_exist("cur".to_owned())),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn close_all_cursors(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare first cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("declare second cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("close all;").expect("no system errors");
+    engine.execute("fetch first;").expect("no system errors");
+    engine.execute("fetch second;").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorDeclared),
+        Ok(QueryEvent::CursorDeclared),
+        Ok(QueryEvent::CursorClosed),
+        Err(QueryError::cursor_does_not_exist("first".to_owned())),
+        Err(QueryError::cursor_does_not_exist("second".to_owned())),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn cursors_on_same_table_are_independent(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare first cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("declare second cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("fetch 3 from first;").expect("no system errors");
+    engine.execute("fetch 1 from second;").expect("no system errors");
+    engine.execute("close second;").expect("no system errors");
+    engine.execute("fetch all from first;").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorDeclared),
+        Ok(QueryEvent::CursorDeclared),
+        fetched(vec!["1", "2", "3"]),
+        fetched(vec!["1"]),
+        Ok(QueryEvent::CursorClosed),
+        fetched(vec!["4", "5"]),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn cursor_names_are_case_insensitive(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare Cur cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("fetch 1 from CUR;").expect("no system errors");
+    engine.execute("fetch 1 from \"CUR\";").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorDeclared),
+        fetched(vec!["1"]),
+        Err(QueryError::cursor_does_not_exist("CUR".to_owned())),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn declare_cursor_with_existing_name(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare cur cursor for select * from schema_name.table_name where column_test = 1;")
+        .expect("no system errors");
+    engine
+        .execute("declare cur cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("fetch all from cur;").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorDeclared),
+        Err(QueryError::cursor_already_exists("cur".to_owned())),
+        fetched(vec!["1"]),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn declare_cursor_for_non_existent_table(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare cur cursor for select * from schema_name.non_existent;")
+        .expect("no system errors");
+    engine.execute("fetch 1 from cur;").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+        Err(QueryError::cursor_does_not_exist("cur".to_owned())),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn cursor_sees_rows_at_declare_time(with_table: Session) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("declare cur cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    engine.execute("fetch 1 from cur;").expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where column_test = 2;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (6);")
+        .expect("no system errors");
+    engine.execute("fetch all from cur;").expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::CursorDeclared),
+        fetched(vec!["1"]),
+        Ok(QueryEvent::RecordsDeleted(1)),
+        Ok(QueryEvent::RecordsInserted(1)),
+        fetched(vec!["2", "3", "4", "5"]),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn cursor_in_transaction_sees_snapshot_at_declare_time() {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let first_collector = Collector::new();
+    let mut first = QueryExecutor::new(storage.clone(), first_collector.clone());
+    let second_collector = Collector::new();
+    let mut second = QueryExecutor::new(storage, second_collector);
+    first.execute("create schema schema_name;").expect("no system errors");
+    first
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    first
+        .execute("insert into schema_name.table_name values (1), (2);")
+        .expect("no system errors");
+
+    first.execute("begin;").expect("no system errors");
+    first
+        .execute("declare cur cursor for select * from schema_name.table_name;")
+        .expect("no system errors");
+    second
+        .execute("insert into schema_name.table_name values (3);")
+        .expect("no system errors");
+    second
+        .execute("update schema_name.table_name set column_test = 10 where column_test = 1;")
+        .expect("no system errors");
+    first.execute("fetch all from cur;").expect("no system errors");
+    first.execute("commit;").expect("no system errors");
+
+    first_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::CursorDeclared),
+        fetched(vec!["1", "2"]),
+        Ok(QueryEvent::TransactionCommitted),
+    ]);
+}
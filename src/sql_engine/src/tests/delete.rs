@@ -63,16 +63,293 @@ fn delete_all_records(sql_engine_with_schema: (QueryExecutor, ResultCollector))
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![vec!["123".to_owned()], vec!["456".to_owned()]],
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("123".to_owned())], vec![Some("456".to_owned())]],
         ))),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsDeleted(2)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
             vec![],
         ))),
         Ok(QueryEvent::QueryComplete),
     ])
 }
+
+#[rstest::rstest]
+fn delete_records_matching_where_clause(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, v smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 10), (2, 20), (3, 30);")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where id > 1;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![vec![Some("1".to_owned()), Some("10".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn delete_where_clause_matches_no_records(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, v smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 10), (2, 20);")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where id = 9999;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(0)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![
+                vec![Some("1".to_owned()), Some("10".to_owned())],
+                vec![Some("2".to_owned()), Some("20".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn delete_with_non_existent_column_in_where_clause(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint);")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where non_existent = 1;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::column_does_not_exist(vec!["non_existent".to_owned()])),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+mod using_clause {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.orders (id smallint, customer_id smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values (1, 1), (2, 2), (3, 1), (4, 3);")
+            .expect("no system errors");
+        engine
+            .execute("create table schema_name.customers (id smallint, region_id smallint, active boolean);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.customers values (1, 1, false), (2, 2, true), (3, 2, false);")
+            .expect("no system errors");
+        engine
+            .execute("create table schema_name.regions (id smallint, closed boolean);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.regions values (1, false), (2, true);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn tables_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(4)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(3)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    fn orders(rows: Vec<(&str, &str)>) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("customer_id".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            rows.into_iter()
+                .map(|(id, customer_id)| vec![text(id), text(customer_id)])
+                .collect(),
+        )))
+    }
+
+    #[rstest::rstest]
+    fn one_table(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "delete from schema_name.orders using schema_name.customers \
+                 where orders.customer_id = customers.id and customers.active = false;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.orders;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsDeleted(3)),
+            Ok(QueryEvent::QueryComplete),
+            orders(vec![("2", "2")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn two_tables(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "delete from schema_name.orders using schema_name.customers, schema_name.regions \
+                 where orders.customer_id = customers.id and customers.region_id = regions.id and regions.closed;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.orders;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsDeleted(2)),
+            Ok(QueryEvent::QueryComplete),
+            orders(vec![("1", "1"), ("3", "1")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn non_existent_table(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "delete from schema_name.orders using schema_name.non_existent \
+                 where orders.customer_id = non_existent.id;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.orders;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+            Ok(QueryEvent::QueryComplete),
+            orders(vec![("1", "1"), ("2", "2"), ("3", "1"), ("4", "3")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn no_matches(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "delete from schema_name.orders using schema_name.customers \
+                 where orders.customer_id = customers.id and customers.id > 5;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.orders;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsDeleted(0)),
+            Ok(QueryEvent::QueryComplete),
+            orders(vec![("1", "1"), ("2", "2"), ("3", "1"), ("4", "3")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn returning_deleted_rows(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "delete from schema_name.orders using schema_name.customers \
+                 where orders.customer_id = customers.id and customers.active = false \
+                 returning orders.*, customers.region_id as region;",
+            )
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsDeletedReturning((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("customer_id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("region".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                vec![
+                    vec![text("1"), text("1"), text("1")],
+                    vec![text("3"), text("1"), text("1")],
+                    vec![text("4"), text("3"), text("2")],
+                ],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
@@ -40,8 +40,8 @@ fn describe_select_statement(sql_engine_with_schema: (QueryExecutor, ResultColle
         Ok(QueryEvent::PreparedStatementDescribed(
             vec![PostgreSqlType::SmallInt, PostgreSqlType::SmallInt],
             vec![
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
         )),
     ]);
@@ -0,0 +1,43 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::QueryExecutor;
+use protocol::results::QueryEvent;
+
+#[rstest::rstest(
+    query,
+    case::comment("-- a comment\n;"),
+    case::only_comment("-- a comment"),
+    case::multiline_comment("/* a comment */"),
+    case::semicolon(";"),
+    case::whitespaces(" \t\n")
+)]
+fn empty_query(sql_engine: (QueryExecutor, ResultCollector), query: &str) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute(query).expect("no system errors");
+
+    collector
+        .assert_content_for_single_queries(vec![Ok(QueryEvent::EmptyQueryResponse), Ok(QueryEvent::QueryComplete)]);
+}
+
+#[rstest::rstest]
+fn comment_before_statement(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("-- a comment\ncreate schema schema_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![Ok(QueryEvent::SchemaCreated), Ok(QueryEvent::QueryComplete)]);
+}
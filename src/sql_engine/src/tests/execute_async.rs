@@ -0,0 +1,118 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use futures_lite::future::{block_on, zip};
+use protocol::sql_types::PostgreSqlType;
+use std::sync::Mutex;
+
+fn count(rows: &str) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+        vec![vec![text(rows)]],
+    )))
+}
+
+#[rstest::rstest]
+fn concurrent_queries_to_the_same_executor(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    let engine = Arc::new(Mutex::new(engine));
+
+    block_on(async {
+        let (first, second) = zip(
+            QueryExecutor::execute_async(
+                engine.clone(),
+                "insert into schema_name.table_name values (1);".to_owned(),
+            ),
+            QueryExecutor::execute_async(
+                engine.clone(),
+                "insert into schema_name.table_name values (2), (3);".to_owned(),
+            ),
+        )
+        .await;
+        first.expect("no system errors");
+        second.expect("no system errors");
+
+        QueryExecutor::execute_async(
+            engine.clone(),
+            "select count(*) from schema_name.table_name;".to_owned(),
+        )
+        .await
+        .expect("no system errors");
+    });
+
+    collector.assert_content_unordered(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        count("3"),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[test]
+fn concurrent_queries_from_many_executors() {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage.clone(), collector.clone());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+
+    let executors: Vec<(Arc<Mutex<QueryExecutor>>, ResultCollector)> = (0..4)
+        .map(|_| {
+            let collector = Collector::new();
+            let executor = QueryExecutor::new(storage.clone(), collector.clone());
+            (Arc::new(Mutex::new(executor)), collector)
+        })
+        .collect();
+
+    block_on(async {
+        let insert = |index: usize| {
+            QueryExecutor::execute_async(
+                executors[index].0.clone(),
+                format!("insert into schema_name.table_name values ({});", index),
+            )
+        };
+        let ((first, second), (third, fourth)) = zip(zip(insert(0), insert(1)), zip(insert(2), insert(3))).await;
+        for result in [first, second, third, fourth] {
+            result.expect("no system errors");
+        }
+    });
+    engine
+        .execute("select count(*) from schema_name.table_name;")
+        .expect("no system errors");
+
+    for (_executor, collector) in executors {
+        collector
+            .assert_content_for_single_queries(vec![Ok(QueryEvent::RecordsInserted(1)), Ok(QueryEvent::QueryComplete)]);
+    }
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        count("4"),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
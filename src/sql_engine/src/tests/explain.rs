@@ -0,0 +1,150 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.t1 (id smallint, col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.t1 values (1, 3), (2, 6), (3, 7), (4, 9);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.t2 (id smallint, col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.t2 values (1, 100), (3, 300), (5, 500);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+// lines of the last sent plan
+fn plan(collector: &ResultCollector) -> Vec<String> {
+    match collector
+        .content()
+        .into_iter()
+        .rev()
+        .find(|event| matches!(event, Ok(QueryEvent::RecordsSelected(_))))
+    {
+        Some(Ok(QueryEvent::RecordsSelected((columns, rows)))) => {
+            assert_eq!(columns, vec![("QUERY PLAN".to_owned(), PostgreSqlType::VarChar, -1)]);
+            rows.into_iter()
+                .map(|mut row| row.remove(0).expect("plan line is not NULL"))
+                .collect()
+        }
+        _ => panic!("plan was not sent"),
+    }
+}
+
+// milliseconds that are reported after `time=` or `Time: `
+fn times(line: &str) -> Vec<f64> {
+    line.split(['=', ' '])
+        .zip(line.split(['=', ' ']).skip(1))
+        .filter(|(_, next)| next.parse::<f64>().is_ok())
+        .filter(|(previous, _)| *previous == "time" || *previous == "Time:")
+        .map(|(_, time)| time.parse().expect("time is a number"))
+        .collect()
+}
+
+// plan lines with time replaced by `_`, thus they could be compared
+fn without_times(plan: &[String]) -> Vec<String> {
+    plan.iter()
+        .map(|line| {
+            times(line).into_iter().fold(line.clone(), |line, time| {
+                line.replacen(&format!("{:.3} ms", time), "_ ms", 1)
+            })
+        })
+        .collect()
+}
+
+#[rstest::rstest]
+fn explain_analyze_table_scan(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("explain analyze select * from schema_name.t1 where col > 5;")
+        .expect("no system errors");
+
+    assert_eq!(
+        without_times(&plan(&collector)),
+        vec![
+            "Select  (estimated rows=1) (actual time=_ ms rows=3 loops=1)",
+            "  Filter: col > 5",
+            "  Rows Removed by Filter: 1",
+            "  ->  Seq Scan on schema_name.t1  (estimated rows=4) (actual time=_ ms rows=4 loops=1)",
+            "Execution Time: _ ms",
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn explain_analyze_join(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("explain analyze select * from schema_name.t1 join schema_name.t2 on t1.id = t2.id;")
+        .expect("no system errors");
+
+    assert_eq!(
+        without_times(&plan(&collector)),
+        vec![
+            "Select  (estimated rows=1) (actual time=_ ms rows=2 loops=1)",
+            "  ->  Nested Loop Inner Join  (estimated rows=1) (actual time=_ ms rows=2 loops=1)",
+            "        Join Filter: t1.id = t2.id",
+            "        Rows Removed by Join Filter: 10",
+            "        ->  Seq Scan on schema_name.t1  (estimated rows=4) (actual time=_ ms rows=4 loops=1)",
+            "        ->  Seq Scan on schema_name.t2  (estimated rows=3) (actual time=_ ms rows=3 loops=1)",
+            "Execution Time: _ ms",
+        ]
+    );
+}
+
+#[rstest::rstest]
+fn timing_is_not_negative(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("explain analyze select t1.col, t2.col from schema_name.t1 left join schema_name.t2 on t1.id = t2.id where t1.col > 5;")
+        .expect("no system errors");
+
+    let plan = plan(&collector);
+    let times = plan.iter().flat_map(|line| times(line)).collect::<Vec<_>>();
+    assert_eq!(times.len(), plan.iter().filter(|line| line.contains(" ms")).count());
+    assert!(times.iter().all(|time| *time >= 0.0), "{:#?}", plan);
+}
+
+#[rstest::rstest]
+fn actual_rows_match_query_result(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("select * from schema_name.t1 where col > 5 or id = 1;")
+        .expect("no system errors");
+    let selected = match collector.content().into_iter().rev().nth(1) {
+        Some(Ok(QueryEvent::RecordsSelected((_columns, rows)))) => rows.len(),
+        _ => panic!("rows were not selected"),
+    };
+
+    engine
+        .execute("explain analyze select * from schema_name.t1 where col > 5 or id = 1;")
+        .expect("no system errors");
+
+    assert_eq!(selected, 4);
+    assert!(
+        plan(&collector)[0].ends_with(&format!("rows={} loops=1)", selected)),
+        "{:?}",
+        plan(&collector)
+    );
+}
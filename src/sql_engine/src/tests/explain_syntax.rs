@@ -0,0 +1,116 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+    ]
+}
+
+#[rstest::rstest(
+    query,
+    case::select("select * from schema_name.table_name where col = 1"),
+    case::parameters("select * from schema_name.table_name where col = $1"),
+    case::update("update schema_name.table_name set col = $1 where col = $2"),
+    case::delete("delete from schema_name.table_name where col = 1")
+)]
+fn valid_query(with_table: (QueryExecutor, ResultCollector), query: &str) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(format!("explain syntax {};", query).as_str())
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.push(Ok(QueryEvent::SyntaxValid));
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn query_is_not_executed(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("explain syntax delete from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::SyntaxValid),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("col".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("1")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn undefined_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("explain syntax select * from schema_name.table_name where other_col = $1;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.push(Err(QueryError::column_does_not_exist(vec!["other_col".to_owned()])));
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn syntax_error(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("explain syntax select * from where col = 1;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.push(Err(QueryError::syntax_error(
+        "\"select * from where col = 1;\" can't be parsed".to_owned(),
+    )));
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn missing_table(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("explain syntax select * from schema_name.non_existent where col = $1;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.push(Err(QueryError::table_does_not_exist(
+        "schema_name.non_existent".to_owned(),
+    )));
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
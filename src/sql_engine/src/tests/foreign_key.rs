@@ -0,0 +1,585 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[rstest::fixture]
+fn with_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.customers (id integer primary key, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders (id integer, customer_id integer);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.customers values (1, 'alice'), (2, 'bob');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn tables_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn missing_customer(customer_id: &str) -> QueryResult {
+    Err(
+        QueryError::foreign_key_violation("orders".to_owned(), "orders_customer_id_fkey".to_owned()).with_detail(
+            format!(
+                "Key (customer_id)=({}) is not present in table \"customers\".",
+                customer_id
+            ),
+        ),
+    )
+}
+
+#[rstest::rstest]
+fn add_foreign_key_to_valid_data(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("insert into schema_name.orders values (1, 1), (2, 2);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "alter table schema_name.orders add constraint orders_customer_id_fkey \
+             foreign key (customer_id) references schema_name.customers (id);",
+        )
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableAltered),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content(expected);
+}
+
+#[rstest::rstest]
+fn add_foreign_key_to_violating_rows(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("insert into schema_name.orders values (1, 1), (2, 3);")
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.orders add foreign key (customer_id) references schema_name.customers (id);")
+        .expect("no system errors");
+    // constraint was not added
+    engine
+        .execute("insert into schema_name.orders values (3, 4);")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        missing_customer("3"),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content(expected);
+}
+
+#[rstest::rstest]
+fn referenced_column_is_not_unique(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute(
+            "alter table schema_name.orders add foreign key (customer_id) references schema_name.customers (name);",
+        )
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Err(QueryError::invalid_foreign_key(
+            "there is no unique constraint matching given keys for referenced table \"customers\"".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content(expected);
+}
+
+#[rstest::rstest]
+fn constraint_is_kept_for_other_sessions() {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let mut first = QueryExecutor::new(storage.clone(), Collector::new());
+    first.execute("create schema schema_name;").expect("no system errors");
+    first
+        .execute("create table schema_name.customers (id integer primary key);")
+        .expect("no system errors");
+    first
+        .execute("create table schema_name.orders (id integer, customer_id integer);")
+        .expect("no system errors");
+    first
+        .execute("alter table schema_name.orders add foreign key (customer_id) references schema_name.customers (id);")
+        .expect("no system errors");
+
+    let collector = Collector::new();
+    let mut second = QueryExecutor::new(storage, collector.clone());
+    second
+        .execute("alter table schema_name.orders add foreign key (customer_id) references schema_name.customers (id);")
+        .expect("no system errors");
+    second
+        .execute("insert into schema_name.orders values (1, 10);")
+        .expect("no system errors");
+
+    collector.assert_content(vec![
+        Err(QueryError::constraint_already_exists(
+            "orders_customer_id_fkey".to_owned(),
+            "orders".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        missing_customer("10"),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[cfg(test)]
+mod with_foreign_key {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_foreign_key(with_tables: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "alter table schema_name.orders add foreign key (customer_id) references schema_name.customers (id);",
+            )
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn foreign_key_added() -> Vec<QueryResult> {
+        let mut expected = tables_created();
+        expected.extend(vec![Ok(QueryEvent::TableAltered), Ok(QueryEvent::QueryComplete)]);
+        expected
+    }
+
+    #[rstest::rstest]
+    fn insert_violating_row(with_foreign_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_foreign_key;
+        engine
+            .execute("insert into schema_name.orders values (1, 1), (2, 5);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values (3, 2);")
+            .expect("no system errors");
+        // missing key is NULL
+        engine
+            .execute("insert into schema_name.orders (id) values (4);")
+            .expect("no system errors");
+
+        let mut expected = foreign_key_added();
+        expected.extend(vec![
+            missing_customer("5"),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn update_to_violating_value(with_foreign_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_foreign_key;
+        engine
+            .execute("insert into schema_name.orders values (1, 1);")
+            .expect("no system errors");
+        engine
+            .execute("update schema_name.orders set customer_id = 7;")
+            .expect("no system errors");
+
+        let mut expected = foreign_key_added();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            missing_customer("7"),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn drop_constraint(with_foreign_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_foreign_key;
+        engine
+            .execute("alter table schema_name.orders drop constraint orders_customer_id_fkey;")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values (1, 5);")
+            .expect("no system errors");
+
+        let mut expected = foreign_key_added();
+        expected.extend(vec![
+            Ok(QueryEvent::TableAltered),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn drop_non_existent_constraint(with_foreign_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_foreign_key;
+        engine
+            .execute("alter table schema_name.orders drop constraint orders_id_fkey;")
+            .expect("no system errors");
+
+        let mut expected = foreign_key_added();
+        expected.extend(vec![
+            Err(QueryError::constraint_does_not_exist(
+                "orders_id_fkey".to_owned(),
+                "orders".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    fn referenced_customer(customer_id: &str) -> QueryResult {
+        Err(QueryError::referenced_key_violation(
+            "customers".to_owned(),
+            "orders_customer_id_fkey".to_owned(),
+            "orders".to_owned(),
+        )
+        .with_detail(format!(
+            "Key (id)=({}) is still referenced from table \"orders\".",
+            customer_id
+        )))
+    }
+
+    #[rstest::rstest]
+    fn delete_referenced_row(with_foreign_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_foreign_key;
+        engine
+            .execute("insert into schema_name.orders values (1, 1);")
+            .expect("no system errors");
+        engine
+            .execute("delete from schema_name.customers where id = 1;")
+            .expect("no system errors");
+        engine
+            .execute("delete from schema_name.customers where id = 2;")
+            .expect("no system errors");
+
+        let mut expected = foreign_key_added();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            referenced_customer("1"),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsDeleted(1)),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn update_referenced_key(with_foreign_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_foreign_key;
+        engine
+            .execute("insert into schema_name.orders values (1, 1);")
+            .expect("no system errors");
+        engine
+            .execute("update schema_name.customers set id = 5 where id = 1;")
+            .expect("no system errors");
+        // key that is not changed is still referenced
+        engine
+            .execute("update schema_name.customers set name = 'carol' where id = 1;")
+            .expect("no system errors");
+
+        let mut expected = foreign_key_added();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            referenced_customer("1"),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsUpdated(1)),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn drop_referenced_table(with_foreign_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_foreign_key;
+        engine
+            .execute("drop table schema_name.customers;")
+            .expect("no system errors");
+        engine
+            .execute("drop table schema_name.customers cascade;")
+            .expect("no system errors");
+        // constraint was dropped with the referenced table
+        engine
+            .execute("insert into schema_name.orders values (1, 5);")
+            .expect("no system errors");
+
+        let mut expected = foreign_key_added();
+        expected.extend(vec![
+            Err(
+                QueryError::table_has_dependent_objects("customers".to_owned()).with_detail(
+                    "constraint orders_customer_id_fkey on table orders depends on table customers".to_owned(),
+                ),
+            ),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableDropped),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn constraint_survives_restart() {
+        let root_path = tempfile::tempdir().expect("to create temporary folder");
+        let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+        let mut engine = QueryExecutor::new(storage, Collector::new());
+        engine.execute("create schema schema_name;").expect("no system errors");
+        engine
+            .execute("create table schema_name.customers (id integer primary key, name varchar(10));")
+            .expect("no system errors");
+        engine
+            .execute("create table schema_name.orders (id integer, customer_id integer);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.customers values (1, 'alice');")
+            .expect("no system errors");
+        engine
+            .execute(
+                "alter table schema_name.orders add foreign key (customer_id) references schema_name.customers (id);",
+            )
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values (1, 1);")
+            .expect("no system errors");
+        drop(engine);
+
+        let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+        let collector = Collector::new();
+        let mut engine = QueryExecutor::new(storage, collector.clone());
+        engine
+            .execute("update schema_name.orders set customer_id = 5;")
+            .expect("no system errors");
+        engine
+            .execute("delete from schema_name.customers where id = 1;")
+            .expect("no system errors");
+
+        collector
+            .assert_content_for_single_queries_ignoring_complete(vec![missing_customer("5"), referenced_customer("1")]);
+    }
+}
+
+#[cfg(test)]
+mod deferrable {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_deferred_key(with_tables: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "alter table schema_name.orders add foreign key (customer_id) \
+                 references schema_name.customers (id) deferrable initially deferred;",
+            )
+            .expect("no system errors");
+        engine.execute("begin;").expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn transaction_started() -> Vec<QueryResult> {
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableAltered),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TransactionStarted),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        expected
+    }
+
+    #[rstest::rstest]
+    fn temporarily_invalid_rows(with_deferred_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_deferred_key;
+        engine
+            .execute("insert into schema_name.orders values (1, 3);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.customers values (3, 'carol');")
+            .expect("no system errors");
+        engine.execute("commit;").expect("no system errors");
+
+        let mut expected = transaction_started();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TransactionCommitted),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn commit_with_violated_key(with_deferred_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_deferred_key;
+        engine
+            .execute("insert into schema_name.orders values (1, 3);")
+            .expect("no system errors");
+        engine.execute("commit;").expect("no system errors");
+
+        let mut expected = transaction_started();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            missing_customer("3"),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn set_constraints_immediate(with_deferred_key: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_deferred_key;
+        engine
+            .execute("insert into schema_name.orders values (1, 3);")
+            .expect("no system errors");
+        engine
+            .execute("set constraints all immediate;")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values (2, 4);")
+            .expect("no system errors");
+
+        let mut expected = transaction_started();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            missing_customer("3"),
+            Ok(QueryEvent::QueryComplete),
+            missing_customer("4"),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn set_constraints_deferred(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "alter table schema_name.orders add foreign key (customer_id) \
+                 references schema_name.customers (id) deferrable;",
+            )
+            .expect("no system errors");
+        engine.execute("begin;").expect("no system errors");
+        engine
+            .execute("set constraints all deferred;")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values (1, 3);")
+            .expect("no system errors");
+
+        let mut expected = transaction_started();
+        expected.extend(vec![
+            Ok(QueryEvent::ConstraintsSet),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn not_deferrable_key(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "alter table schema_name.orders add foreign key (customer_id) \
+                 references schema_name.customers (id) not deferrable;",
+            )
+            .expect("no system errors");
+        engine.execute("begin;").expect("no system errors");
+        engine
+            .execute("set constraints all deferred;")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values (1, 3);")
+            .expect("no system errors");
+
+        let mut expected = transaction_started();
+        expected.extend(vec![
+            Ok(QueryEvent::ConstraintsSet),
+            Ok(QueryEvent::QueryComplete),
+            missing_customer("3"),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn deferred_key_outside_of_transaction(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "alter table schema_name.orders add foreign key (customer_id) \
+                 references schema_name.customers (id) initially deferred;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values (1, 3);")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableAltered),
+            Ok(QueryEvent::QueryComplete),
+            missing_customer("3"),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+
+    #[rstest::rstest]
+    fn initially_deferred_key_is_not_deferrable(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        let query = "alter table schema_name.orders add foreign key (customer_id) \
+                     references schema_name.customers (id) not deferrable initially deferred;";
+        engine.execute(query).expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Err(QueryError::syntax_error(format!("{:?} can't be parsed", query))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content(expected);
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_function(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create function schema_name.double_it(n smallint) returns smallint \
+             as $$ select n * 2 $$ language sql;",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::fixture]
+fn with_table(with_function: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = with_function;
+    engine
+        .execute("create table schema_name.items (id smallint, price smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 10), (2, 20), (3, 30);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn function_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn table_filled() -> Vec<QueryResult> {
+    let mut expected = function_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    expected
+}
+
+#[rstest::rstest]
+fn scalar_function(with_function: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_function;
+    engine
+        .execute("select schema_name.double_it(21);")
+        .expect("no system errors");
+
+    let mut expected = function_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("double_it".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("42")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_in_select(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select id, schema_name.double_it(price) as doubled from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("doubled".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![
+                vec![text("1"), text("20")],
+                vec![text("2"), text("40")],
+                vec![text("3"), text("60")],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_in_where(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select id from schema_name.items where schema_name.double_it(price) > 30;")
+        .expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("id".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("2")], vec![text("3")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_in_update_set(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("update schema_name.items set price = schema_name.double_it(price) where id = 2;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = table_filled();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("price".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![
+                vec![text("1"), text("10")],
+                vec![text("2"), text("40")],
+                vec![text("3"), text("30")],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn wrong_number_of_arguments(with_function: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_function;
+    engine
+        .execute("select schema_name.double_it(1, 2);")
+        .expect("no system errors");
+
+    let mut expected = function_created();
+    expected.extend(vec![
+        Err(QueryError::function_does_not_exist(
+            "schema_name.double_it(NUMBER, NUMBER)".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn create_existing_function(with_function: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_function;
+    engine
+        .execute("create function schema_name.double_it(n smallint) returns smallint as $$ n + n $$ language sql;")
+        .expect("no system errors");
+
+    let mut expected = function_created();
+    expected.extend(vec![
+        Err(QueryError::function_already_exists("schema_name.double_it".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn body_references_unknown_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create function schema_name.add(n smallint) returns smallint as $$ n + m $$ language sql;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::column_does_not_exist(vec!["m".to_owned()])),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn drop_function(with_function: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_function;
+    engine
+        .execute("drop function schema_name.double_it(smallint);")
+        .expect("no system errors");
+    engine
+        .execute("drop function schema_name.double_it;")
+        .expect("no system errors");
+    engine
+        .execute("drop function if exists schema_name.double_it;")
+        .expect("no system errors");
+
+    let mut expected = function_created();
+    expected.extend(vec![
+        Ok(QueryEvent::FunctionDropped),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::function_does_not_exist("schema_name.double_it".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionDropped),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn infinite_recursion(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create function schema_name.forever(n smallint) returns smallint \
+             as $$ select schema_name.forever(n + 1) $$ language sql;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select schema_name.forever(1);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::stack_depth_limit_exceeded()),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn function_survives_restart() {
+    let root_path = tempfile::tempdir().expect("to create temporary folder");
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let mut engine = QueryExecutor::new(storage, Collector::new());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute(
+            "create function schema_name.double_it(n smallint) returns smallint \
+             as $$ select n * 2 $$ language sql;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("create function schema_name.dropped(n smallint) returns smallint as $$ n $$ language sql;")
+        .expect("no system errors");
+    engine
+        .execute("drop function schema_name.dropped;")
+        .expect("no system errors");
+    drop(engine);
+
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage, collector.clone());
+    engine
+        .execute("select schema_name.double_it(21);")
+        .expect("no system errors");
+    engine
+        .execute("drop function schema_name.dropped;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("double_it".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("42")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::function_does_not_exist("schema_name.dropped".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,140 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+fn series(values: &[i32]) -> Vec<Vec<Option<String>>> {
+    values.iter().map(|value| vec![Some(value.to_string())]).collect()
+}
+
+#[rstest::rstest]
+fn series_of_integers(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select * from generate_series(1, 5);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("value".to_owned(), PostgreSqlType::Integer, -1)],
+            series(&[1, 2, 3, 4, 5]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn series_with_step(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select * from generate_series(1, 10, 2);")
+        .expect("no system errors");
+    engine
+        .execute("select * from generate_series(5, 1, -2);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("value".to_owned(), PostgreSqlType::Integer, -1)],
+            series(&[1, 3, 5, 7, 9]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("value".to_owned(), PostgreSqlType::Integer, -1)],
+            series(&[5, 3, 1]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn empty_series(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select * from generate_series(5, 1);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("value".to_owned(), PostgreSqlType::Integer, -1)],
+            vec![],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn series_with_alias_in_expressions(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select s.n * 10 as tens from generate_series(1, 3) as s (n) where s.n > 1;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("tens".to_owned(), PostgreSqlType::Integer, -1)],
+            series(&[20, 30]),
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn zero_step(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select * from generate_series(1, 5, 0);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::invalid_parameter_value(
+            "step size cannot equal zero".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn non_integer_argument(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select * from generate_series(1, 'a');")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::invalid_parameter_value(
+            "generate_series argument 'a' is not an integer".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn argument_out_of_range(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("select * from generate_series(1, 3000000000);")
+        .expect("no system errors");
+    engine
+        .execute("select * from generate_series(-9223372036854775808, 1);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::value_out_of_range(PostgreSqlType::Integer)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::value_out_of_range(PostgreSqlType::Integer)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,249 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.items (\
+             price integer, \
+             quantity integer, \
+             total integer generated always as (price * quantity) stored\
+             );",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn selected(rows: Vec<(&str, &str, &str)>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("price".to_owned(), PostgreSqlType::Integer, -1),
+            ("quantity".to_owned(), PostgreSqlType::Integer, -1),
+            ("total".to_owned(), PostgreSqlType::Integer, -1),
+        ],
+        rows.into_iter()
+            .map(|(price, quantity, total)| vec![text(price), text(quantity), text(total)])
+            .collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn insert_computes_generated_value(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.items (price, quantity) values (10, 3), (5, 4);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (7, 2);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("10", "3", "30"), ("5", "4", "20"), ("7", "2", "14")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn generated_value_of_null_operand_is_null(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.items (price) values (10);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("10", "NULL", "NULL")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_of_base_column_recomputes_generated_value(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.items (price, quantity) values (10, 3), (5, 4);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.items set quantity = 6 where price = 10;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("10", "6", "60"), ("5", "4", "20")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn generated_column_in_where_clause(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.items (price, quantity) values (10, 3), (5, 4), (1, 1);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items where total >= 20;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("10", "3", "30"), ("5", "4", "20")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn insert_value_into_generated_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.items (price, quantity, total) values (10, 3, 100);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (10, 3, 100);")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::generated_column_cannot_be_written("total".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::generated_column_cannot_be_written("total".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_generated_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("update schema_name.items set total = 100;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Err(QueryError::generated_column_cannot_be_written("total".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn insert_default_values(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.items values (10, 3, default), (5, default, DEFAULT);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items (quantity, price) values (default, 7);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("10", "3", "30"), ("5", "NULL", "NULL"), ("7", "NULL", "NULL")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_to_default_values(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("insert into schema_name.items (price, quantity) values (10, 3), (5, 4);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.items set quantity = default where price = 10;")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.items set price = 2, total = default where price = 5;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("10", "NULL", "NULL"), ("2", "4", "8")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn generation_expression_of_non_existing_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.items (price integer, total integer generated always as (price * amount) stored);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::column_does_not_exist(vec!["amount".to_owned()])),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
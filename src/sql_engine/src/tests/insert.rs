@@ -72,8 +72,8 @@ fn insert_and_select_single_row(sql_engine_with_schema: (QueryExecutor, ResultCo
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![vec!["123".to_owned()]],
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("123".to_owned())]],
         ))),
         Ok(QueryEvent::QueryComplete),
     ]);
@@ -107,15 +107,15 @@ fn insert_and_select_multiple_rows(sql_engine_with_schema: (QueryExecutor, Resul
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![vec!["123".to_owned()]],
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("123".to_owned())]],
         ))),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![vec!["123".to_owned()], vec!["456".to_owned()]],
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("123".to_owned())], vec![Some("456".to_owned())]],
         ))),
         Ok(QueryEvent::QueryComplete),
     ]);
@@ -143,13 +143,13 @@ fn insert_and_select_named_columns(sql_engine_with_schema: (QueryExecutor, Resul
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("col1".to_owned(), PostgreSqlType::SmallInt),
-                ("col2".to_owned(), PostgreSqlType::SmallInt),
-                ("col3".to_owned(), PostgreSqlType::SmallInt),
+                ("col1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("col2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("col3".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["3".to_owned(), "1".to_owned(), "2".to_owned()],
-                vec!["6".to_owned(), "4".to_owned(), "5".to_owned()],
+                vec![Some("3".to_owned()), Some("1".to_owned()), Some("2".to_owned())],
+                vec![Some("6".to_owned()), Some("4".to_owned()), Some("5".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -178,14 +178,14 @@ fn insert_multiple_rows(sql_engine_with_schema: (QueryExecutor, ResultCollector)
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["1".to_owned(), "4".to_owned(), "7".to_owned()],
-                vec!["2".to_owned(), "5".to_owned(), "8".to_owned()],
-                vec!["3".to_owned(), "6".to_owned(), "9".to_owned()],
+                vec![Some("1".to_owned()), Some("4".to_owned()), Some("7".to_owned())],
+                vec![Some("2".to_owned()), Some("5".to_owned()), Some("8".to_owned())],
+                vec![Some("3".to_owned()), Some("6".to_owned()), Some("9".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -219,23 +219,23 @@ fn insert_and_select_different_integer_types(sql_engine_with_schema: (QueryExecu
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_si".to_owned(), PostgreSqlType::SmallInt),
-                ("column_i".to_owned(), PostgreSqlType::Integer),
-                ("column_bi".to_owned(), PostgreSqlType::BigInt),
-                ("column_serial".to_owned(), PostgreSqlType::Integer),
+                ("column_si".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_i".to_owned(), PostgreSqlType::Integer, -1),
+                ("column_bi".to_owned(), PostgreSqlType::BigInt, -1),
+                ("column_serial".to_owned(), PostgreSqlType::Integer, -1),
             ],
             vec![
                 vec![
-                    "-32768".to_owned(),
-                    "-2147483648".to_owned(),
-                    "-9223372036854775808".to_owned(),
-                    "1".to_owned(),
+                    Some("-32768".to_owned()),
+                    Some("-2147483648".to_owned()),
+                    Some("-9223372036854775808".to_owned()),
+                    Some("1".to_owned()),
                 ],
                 vec![
-                    "32767".to_owned(),
-                    "2147483647".to_owned(),
-                    "9223372036854775807".to_owned(),
-                    "1".to_owned(),
+                    Some("32767".to_owned()),
+                    Some("2147483647".to_owned()),
+                    Some("9223372036854775807".to_owned()),
+                    Some("1".to_owned()),
                 ],
             ],
         ))),
@@ -243,6 +243,37 @@ fn insert_and_select_different_integer_types(sql_engine_with_schema: (QueryExecu
     ]);
 }
 
+#[rstest::rstest(
+    value,
+    expected,
+    case::negative("-5", Ok(QueryEvent::RecordsInserted(1))),
+    case::lower_bound("-32768", Ok(QueryEvent::RecordsInserted(1))),
+    case::out_of_range("-32769", Err(QueryError::out_of_range(PostgreSqlType::SmallInt, "column_si".to_owned(), 1))),
+    case::negated_expression("-(2 + 3)", Ok(QueryEvent::RecordsInserted(1)))
+)]
+fn insert_negative_literals(
+    sql_engine_with_schema: (QueryExecutor, ResultCollector),
+    value: &str,
+    expected: QueryResult,
+) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_si smallint);")
+        .expect("no system errors");
+    engine
+        .execute(format!("insert into schema_name.table_name values ({});", value).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        expected,
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
 #[rstest::rstest]
 fn insert_and_select_different_character_types(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
     let (mut engine, collector) = sql_engine_with_schema;
@@ -270,12 +301,12 @@ fn insert_and_select_different_character_types(sql_engine_with_schema: (QueryExe
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_c".to_owned(), PostgreSqlType::Char),
-                ("column_vc".to_owned(), PostgreSqlType::VarChar),
+                ("column_c".to_owned(), PostgreSqlType::Char, 14),
+                ("column_vc".to_owned(), PostgreSqlType::VarChar, 14),
             ],
             vec![
-                vec!["12345abcde".to_owned(), "12345abcde".to_owned()],
-                vec!["12345abcde".to_owned(), "abcde".to_owned()],
+                vec![Some("12345abcde".to_owned()), Some("12345abcde".to_owned())],
+                vec![Some("12345abcde".to_owned()), Some("abcde".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -354,8 +385,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["3".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("3".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -379,8 +410,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["-1".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("-1".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -404,8 +435,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["6".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("6".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -429,8 +460,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["4".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("4".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -454,8 +485,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["0".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("0".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -482,8 +513,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["64".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("64".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -509,8 +540,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["4".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("4".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -536,8 +567,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["2".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("2".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -563,8 +594,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["120".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("120".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -590,8 +621,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["120".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("120".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -617,8 +648,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["5".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("5".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -642,8 +673,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["1".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("1".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -667,8 +698,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["7".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("7".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -694,8 +725,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["-2".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("-2".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -721,8 +752,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["16".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("16".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -748,8 +779,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["2".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("2".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -773,8 +804,8 @@ mod operators {
                     Ok(QueryEvent::RecordsInserted(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["5".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("5".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -814,8 +845,8 @@ mod operators {
                 Ok(QueryEvent::RecordsInserted(1)),
                 Ok(QueryEvent::QueryComplete),
                 Ok(QueryEvent::RecordsSelected((
-                    vec![("strings".to_owned(), PostgreSqlType::Char)],
-                    vec![vec!["12345".to_owned()]],
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("12345".to_owned())]],
                 ))),
                 Ok(QueryEvent::QueryComplete),
             ]);
@@ -844,13 +875,68 @@ mod operators {
                 Ok(QueryEvent::RecordsInserted(1)),
                 Ok(QueryEvent::QueryComplete),
                 Ok(QueryEvent::RecordsSelected((
-                    vec![("strings".to_owned(), PostgreSqlType::Char)],
-                    vec![vec!["145".to_owned()], vec!["451".to_owned()]],
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("145".to_owned())], vec![Some("451".to_owned())]],
+                ))),
+                Ok(QueryEvent::QueryComplete),
+            ]);
+        }
+
+        #[rstest::rstest]
+        fn number_assigned_as_text(with_table: (QueryExecutor, ResultCollector)) {
+            let (mut engine, collector) = with_table;
+            engine
+                .execute("insert into schema_name.table_name values (42), (1234567);")
+                .expect("no system errors");
+            engine
+                .execute("select * from schema_name.table_name where strings = '42';")
+                .expect("no system errors");
+
+            collector.assert_content_for_single_queries(vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::Notice {
+                    severity: "NOTICE".to_owned(),
+                    message: "value truncated to type character(5) for column 'strings' at row 2".to_owned(),
+                }),
+                Ok(QueryEvent::RecordsInserted(2)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsSelected((
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("42".to_owned())]],
                 ))),
                 Ok(QueryEvent::QueryComplete),
             ]);
         }
 
+        #[rstest::rstest]
+        fn number_not_compared_with_text(with_table: (QueryExecutor, ResultCollector)) {
+            let (mut engine, collector) = with_table;
+            engine
+                .execute("insert into schema_name.table_name values (42);")
+                .expect("no system errors");
+            engine
+                .execute("select * from schema_name.table_name where strings = 42;")
+                .expect("no system errors");
+
+            collector.assert_content_for_single_queries(vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+                Err(QueryError::undefined_function(
+                    "=".to_owned(),
+                    "STRING".to_owned(),
+                    "NUMBER".to_owned(),
+                )),
+                Ok(QueryEvent::QueryComplete),
+            ]);
+        }
+
         #[rstest::rstest]
         fn non_string_concatenation_not_supported(with_table: (QueryExecutor, ResultCollector)) {
             let (mut engine, collector) = with_table;
@@ -872,5 +958,624 @@ mod operators {
                 Ok(QueryEvent::QueryComplete),
             ]);
         }
+
+        #[rstest::rstest]
+        fn truncated_to_fit_char(with_table: (QueryExecutor, ResultCollector)) {
+            let (mut engine, collector) = with_table;
+            engine
+                .execute("insert into schema_name.table_name values ('1234567');")
+                .expect("no system errors");
+            engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors");
+
+            collector.assert_content_for_single_queries(vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::Notice {
+                    severity: "NOTICE".to_owned(),
+                    message: "value truncated to type character(5) for column 'strings' at row 1".to_owned(),
+                }),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsSelected((
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("12345".to_owned())]],
+                ))),
+                Ok(QueryEvent::QueryComplete),
+            ]);
+        }
+
+        #[rstest::rstest]
+        fn notice_for_each_truncated_row(with_table: (QueryExecutor, ResultCollector)) {
+            let (mut engine, collector) = with_table;
+            engine
+                .execute("insert into schema_name.table_name values ('abc'), ('abcdefg'), ('abcde   ');")
+                .expect("no system errors");
+            engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors");
+
+            collector.assert_content_for_single_queries(vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::Notice {
+                    severity: "NOTICE".to_owned(),
+                    message: "value truncated to type character(5) for column 'strings' at row 2".to_owned(),
+                }),
+                Ok(QueryEvent::RecordsInserted(3)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsSelected((
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![
+                        vec![Some("abc".to_owned())],
+                        vec![Some("abcde".to_owned())],
+                        vec![Some("abcde".to_owned())],
+                    ],
+                ))),
+                Ok(QueryEvent::QueryComplete),
+            ]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod on_conflict {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (id smallint primary key, val varchar(5) unique);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1, 'a');")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn rows(values: Vec<(&str, &str)>) -> Result<QueryEvent, QueryError> {
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("val".to_owned(), PostgreSqlType::VarChar, 9),
+            ],
+            values.into_iter().map(|(id, val)| vec![text(id), text(val)]).collect(),
+        )))
+    }
+
+    #[rstest::rstest]
+    fn conflict_on_primary_key(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name (id, val) values (1, 'b') on conflict do nothing;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(0)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn conflict_on_unique_column(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name (id, val) values (2, 'a') on conflict (val) do nothing;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(0)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn no_conflict(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name (id, val) values (2, 'b') on conflict do nothing;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a"), ("2", "b")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn some_rows_conflict(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name (id, val) values (1, 'x'), (2, 'b'), (3, 'a'), (2, 'c') \
+                 on conflict do nothing;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a"), ("2", "b")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn conflict_without_clause_is_an_error(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name (id, val) values (2, 'b'), (1, 'c');")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::unique_violation("table_name_id_key".to_owned())),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod on_conflict_do_update {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (id smallint primary key, val varchar(5), hits smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1, 'a', 1), (2, 'b', 1);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn rows(values: Vec<(&str, &str, &str)>) -> Result<QueryEvent, QueryError> {
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("val".to_owned(), PostgreSqlType::VarChar, 9),
+                ("hits".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            values
+                .into_iter()
+                .map(|(id, val, hits)| vec![text(id), text(val), text(hits)])
+                .collect(),
+        )))
+    }
+
+    #[rstest::rstest]
+    fn inserts_row_without_conflict(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name values (3, 'c', 1) \
+                 on conflict (id) do update set val = excluded.val;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a", "1"), ("2", "b", "1"), ("3", "c", "1")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn updates_conflicting_row(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name (id, val) values (1, 'new') \
+                 on conflict (id) do update set val = EXCLUDED.val;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "new", "1"), ("2", "b", "1")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn some_rows_conflict(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name values (3, 'c', 1), (2, 'x', 1), (4, 'd', 1) \
+                 on conflict (id) do update set val = excluded.val;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(3)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a", "1"), ("2", "x", "1"), ("3", "c", "1"), ("4", "d", "1")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn update_with_computed_value(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name values (1, 'a', 5) \
+                 on conflict (id) do update set hits = hits + excluded.hits * 2;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a", "11"), ("2", "b", "1")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn update_only_rows_that_satisfy_condition(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name values (1, 'x', 1), (2, 'y', 1) \
+                 on conflict (id) do update set val = excluded.val where table_name.val = 'b';",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a", "1"), ("2", "y", "1")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn same_row_affected_twice(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name values (1, 'x', 1), (1, 'y', 1) \
+                 on conflict (id) do update set val = excluded.val;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::cardinality_violation(
+                "ON CONFLICT DO UPDATE command cannot affect row a second time".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a", "1"), ("2", "b", "1")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn conflict_target_is_not_unique(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name values (1, 'x', 1) \
+                 on conflict (val) do update set val = excluded.val;",
+            )
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::invalid_column_reference(
+                "there is no unique or exclusion constraint matching the ON CONFLICT specification".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod best_effort {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (id smallint, val varchar(5));")
+            .expect("no system errors");
+        engine
+            .execute("set insert_mode = best_effort;")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    fn rows(values: Vec<(&str, &str)>) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("val".to_owned(), PostgreSqlType::VarChar, 9),
+            ],
+            values.into_iter().map(|(id, val)| vec![text(id), text(val)]).collect(),
+        )))
+    }
+
+    #[rstest::rstest]
+    fn valid_rows_are_inserted(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "insert into schema_name.table_name values (1, 'a'), ('two', 'b'), (3, 'c'), (4, 'd'), (5, 'too long');",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryError::type_mismatch("two", PostgreSqlType::SmallInt, "id".to_owned(), 2).into_warning()),
+            Ok(QueryError::string_length_mismatch(PostgreSqlType::VarChar, 5, "val".to_owned(), 5).into_warning()),
+            Ok(QueryEvent::RecordsInserted(3)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a"), ("3", "c"), ("4", "d")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn all_rows_violate_constraints(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name values ('one', 'a'), (2, 'too long');")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryError::type_mismatch("one", PostgreSqlType::SmallInt, "id".to_owned(), 1).into_warning()),
+            Ok(QueryError::string_length_mismatch(PostgreSqlType::VarChar, 5, "val".to_owned(), 2).into_warning()),
+            Ok(QueryEvent::RecordsInserted(0)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn rows_with_null_in_not_null_column_are_skipped(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (id smallint not null, val varchar(5));")
+            .expect("no system errors");
+        engine
+            .execute("set insert_mode = best_effort;")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1, 'a'), (null, 'b'), (3, 'c');")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryError::not_null_violation("id".to_owned()).into_warning()),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a"), ("3", "c")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn no_row_violates_constraints(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("insert into schema_name.table_name values (1, 'a'), (2, 'b');")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a"), ("2", "b")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn strict_mode_inserts_nothing(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine.execute("set insert_mode = strict;").expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1, 'a'), ('two', 'b'), (3, 'c');")
+            .expect("no system errors");
+        engine
+            .execute("set insert_mode = best_effort;")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1, 'a'), ('two', 'b'), (3, 'c');")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::type_mismatch(
+                "two",
+                PostgreSqlType::SmallInt,
+                "id".to_owned(),
+                2,
+            )),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryError::type_mismatch("two", PostgreSqlType::SmallInt, "id".to_owned(), 2).into_warning()),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            rows(vec![("1", "a"), ("3", "c")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn unknown_insert_mode(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine.execute("set insert_mode = sloppy;").expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Err(QueryError::invalid_parameter_value(
+                "invalid value for parameter \"insert_mode\": \"sloppy\"".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
     }
 }
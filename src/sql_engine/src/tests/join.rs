@@ -0,0 +1,553 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.t1 (id smallint, col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.t1 values (1, 10), (2, 20), (3, 30);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.t2 (id smallint, col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.t2 values (1, 100), (3, 300), (4, 400);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn tables_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn joined_columns() -> Vec<(String, PostgreSqlType, i32)> {
+    vec![
+        ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+        ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+        ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+        ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+    ]
+}
+
+fn rows(rows: Vec<Vec<&str>>) -> Vec<Vec<Option<String>>> {
+    rows.into_iter()
+        .map(|row| row.into_iter().map(text).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod full_outer {
+    use super::*;
+
+    #[rstest::rstest]
+    fn partial_overlap(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select * from schema_name.t1 full outer join schema_name.t2 on t1.id = t2.id;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                joined_columns(),
+                rows(vec![
+                    vec!["1", "10", "1", "100"],
+                    vec!["2", "20", "NULL", "NULL"],
+                    vec!["3", "30", "3", "300"],
+                    vec!["NULL", "NULL", "4", "400"],
+                ]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn no_overlap(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select * from schema_name.t1 full join schema_name.t2 on t1.id = t2.id + 100;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                joined_columns(),
+                rows(vec![
+                    vec!["1", "10", "NULL", "NULL"],
+                    vec!["2", "20", "NULL", "NULL"],
+                    vec!["3", "30", "NULL", "NULL"],
+                    vec!["NULL", "NULL", "1", "100"],
+                    vec!["NULL", "NULL", "3", "300"],
+                    vec!["NULL", "NULL", "4", "400"],
+                ]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn all_rows_match(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select * from schema_name.t1 as a full join schema_name.t1 as b on a.id = b.id;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.t1 as a inner join schema_name.t1 as b on a.id = b.id;")
+            .expect("no system errors");
+
+        let selected = rows(vec![
+            vec!["1", "10", "1", "10"],
+            vec!["2", "20", "2", "20"],
+            vec!["3", "30", "3", "30"],
+        ]);
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((joined_columns(), selected.clone()))),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((joined_columns(), selected))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn complex_predicate(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select * from schema_name.t1 full join schema_name.t2 on t1.id = t2.id and t2.col > 200;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                joined_columns(),
+                rows(vec![
+                    vec!["1", "10", "NULL", "NULL"],
+                    vec!["2", "20", "NULL", "NULL"],
+                    vec!["3", "30", "3", "300"],
+                    vec!["NULL", "NULL", "1", "100"],
+                    vec!["NULL", "NULL", "4", "400"],
+                ]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod cross {
+    use super::*;
+
+    #[rstest::rstest]
+    fn produces_cartesian_product(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("insert into schema_name.t2 values (5, 500);")
+            .expect("no system errors");
+        engine
+            .execute("select t1.id, t2.id from schema_name.t1 cross join schema_name.t2;")
+            .expect("no system errors");
+
+        let mut selected = vec![];
+        for left in &["1", "2", "3"] {
+            for right in &["1", "3", "4", "5"] {
+                selected.push(vec![Some(left.to_string()), Some(right.to_string())]);
+            }
+        }
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                selected,
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn with_empty_table(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("create table schema_name.empty (id smallint);")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.t1 cross join schema_name.empty;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                vec![],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn implicit_comma_join_with_where_is_inner_join(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select * from schema_name.t1, schema_name.t2 where t1.id = t2.id;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.t1 inner join schema_name.t2 on t1.id = t2.id;")
+            .expect("no system errors");
+
+        let selected = rows(vec![vec!["1", "10", "1", "100"], vec!["3", "30", "3", "300"]]);
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((joined_columns(), selected.clone()))),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((joined_columns(), selected))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn three_tables(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("create table schema_name.t3 (flag boolean);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.t3 values (true), (false);")
+            .expect("no system errors");
+        engine
+            .execute(
+                "select t1.id, t2.id, t3.flag from schema_name.t1 \
+                 cross join schema_name.t2 cross join schema_name.t3 where t1.id = 1;",
+            )
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("flag".to_owned(), PostgreSqlType::Bool, -1),
+                ],
+                rows(vec![
+                    vec!["1", "1", "t"],
+                    vec!["1", "1", "f"],
+                    vec!["1", "3", "t"],
+                    vec!["1", "3", "f"],
+                    vec!["1", "4", "t"],
+                    vec!["1", "4", "f"],
+                ]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod natural {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_names(with_tables: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("create table schema_name.names (id smallint, name varchar(10));")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.names values (1, 'one'), (3, 'three');")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn names_created() -> Vec<QueryResult> {
+        let mut events = tables_created();
+        events.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        events
+    }
+
+    fn named_columns() -> Vec<(String, PostgreSqlType, i32)> {
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+            ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+            ("name".to_owned(), PostgreSqlType::VarChar, 14),
+        ]
+    }
+
+    #[rstest::rstest]
+    fn one_common_column(with_names: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_names;
+        engine
+            .execute("select * from schema_name.t1 natural join schema_name.names;")
+            .expect("no system errors");
+
+        let mut expected = names_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                named_columns(),
+                rows(vec![vec!["1", "10", "one"], vec!["3", "30", "three"]]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn two_common_columns(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("create table schema_name.t3 (col smallint, id smallint, extra varchar(10));")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.t3 values (10, 1, 'x'), (99, 2, 'y');")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.t1 natural join schema_name.t3;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("extra".to_owned(), PostgreSqlType::VarChar, 14),
+                ],
+                rows(vec![vec!["1", "10", "x"]]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn no_common_columns_is_cross_join(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("create table schema_name.flags (flag boolean);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.flags values (true), (false);")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.t1 natural join schema_name.flags;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("flag".to_owned(), PostgreSqlType::Bool, -1),
+                ],
+                rows(vec![
+                    vec!["1", "10", "t"],
+                    vec!["1", "10", "f"],
+                    vec!["2", "20", "t"],
+                    vec!["2", "20", "f"],
+                    vec!["3", "30", "t"],
+                    vec!["3", "30", "f"],
+                ]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn disjoint_values_of_common_column(with_names: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_names;
+        engine
+            .execute("select * from (select id + 10 as id, col from schema_name.t1) a natural join schema_name.names;")
+            .expect("no system errors");
+
+        let mut expected = names_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::Integer, -1),
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("name".to_owned(), PostgreSqlType::VarChar, 14),
+                ],
+                vec![],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn natural_left_join(with_names: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_names;
+        engine
+            .execute("select * from schema_name.t1 natural left join schema_name.names;")
+            .expect("no system errors");
+
+        let mut expected = names_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                named_columns(),
+                rows(vec![
+                    vec!["1", "10", "one"],
+                    vec!["2", "20", "NULL"],
+                    vec!["3", "30", "three"],
+                ]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn join_using_column_list(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select * from schema_name.t1 right join schema_name.t2 using (id);")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                rows(vec![
+                    vec!["1", "10", "100"],
+                    vec!["3", "30", "300"],
+                    vec!["4", "NULL", "400"],
+                ]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod qualified_wildcard {
+    use super::*;
+
+    #[rstest::rstest]
+    fn expands_only_columns_of_aliased_table(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select a.* from schema_name.t1 as a join schema_name.t2 as b on a.id = b.id;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                rows(vec![vec!["1", "10"], vec!["3", "30"]]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn mixed_with_other_columns(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select t2.col, schema_name.t1.* from schema_name.t1 join schema_name.t2 on t1.id = t2.id;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                rows(vec![vec!["100", "1", "10"], vec!["300", "3", "30"]]),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn unknown_alias(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("select c.* from schema_name.t1 as a join schema_name.t2 as b on a.id = b.id;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Err(QueryError::table_does_not_exist("c".to_owned())),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
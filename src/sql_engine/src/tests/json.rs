@@ -0,0 +1,147 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, doc json);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::rstest]
+fn insert_and_select_documents(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(r#"insert into schema_name.table_name values (1, '{ "tags" : ["a", "b"], "count": 2 }'), (2, '3');"#)
+        .expect("no system errors");
+    engine
+        .execute("select doc from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("doc".to_owned(), PostgreSqlType::Json, -1)],
+            vec![
+                vec![Some(r#"{"tags":["a","b"],"count":2}"#.to_owned())],
+                vec![Some("3".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn jsonb_is_json(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (doc jsonb);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values ('[true, null]');")
+        .expect("no system errors");
+    engine
+        .execute("select doc from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("doc".to_owned(), PostgreSqlType::Json, -1)],
+            vec![vec![Some("[true,null]".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn insert_malformed_document(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(r#"insert into schema_name.table_name values (1, '{"a": 1'), (2, '{}');"#)
+        .expect("no system errors");
+    engine
+        .execute("select doc from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch(
+            r#"{"a": 1"#,
+            PostgreSqlType::Json,
+            "doc".to_owned(),
+            1,
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("doc".to_owned(), PostgreSqlType::Json, -1)],
+            vec![],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_with_malformed_document(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(r#"insert into schema_name.table_name values (1, '{"a": 1}');"#)
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set doc = '[1, 2';")
+        .expect("no system errors");
+    engine
+        .execute("select doc from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::type_mismatch(
+            "[1, 2",
+            PostgreSqlType::Json,
+            "doc".to_owned(),
+            1,
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("doc".to_owned(), PostgreSqlType::Json, -1)],
+            vec![vec![Some(r#"{"a":1}"#.to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
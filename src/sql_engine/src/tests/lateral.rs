@@ -0,0 +1,140 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.t1 (id smallint, col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.t1 values (1, 10), (2, 20), (3, 30);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.t2 (id smallint, col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.t2 values (1, 100), (3, 300);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn tables_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+#[rstest::rstest]
+fn lateral_subquery_is_executed_for_every_outer_row(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute(
+            "select t1.col, sub.val from schema_name.t1, \
+             lateral (select col * 2 as val from schema_name.t2 where t2.id = t1.id) sub;",
+        )
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("col".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("val".to_owned(), PostgreSqlType::Integer, -1),
+            ],
+            vec![
+                vec![Some("10".to_owned()), Some("200".to_owned())],
+                vec![Some("30".to_owned()), Some("600".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn left_join_lateral_keeps_outer_rows_without_subquery_rows(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute(
+            "select t1.id, sub.val from schema_name.t1 \
+             left join lateral (select col as val from schema_name.t2 where t2.id = t1.id) sub on true;",
+        )
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("val".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![
+                vec![Some("1".to_owned()), Some("100".to_owned())],
+                vec![Some("2".to_owned()), None],
+                vec![Some("3".to_owned()), Some("300".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn lateral_subquery_can_not_reference_column_two_levels_up(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute(
+            "select * from schema_name.t1, \
+             lateral (select * from schema_name.t2, lateral (select t1.id as x from schema_name.t2 as t3) s2) s1;",
+        )
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Err(QueryError::column_does_not_exist(vec!["t1.id".to_owned()])
+            .with_hint("Perhaps you meant to reference the column \"id\".".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn not_lateral_subquery_can_not_reference_preceding_from_items(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("select * from schema_name.t1, (select col from schema_name.t2 where t2.id = t1.id) sub;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Err(QueryError::column_does_not_exist(vec!["t1.id".to_owned()])
+            .with_hint("Perhaps you meant to reference the column \"id\".".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
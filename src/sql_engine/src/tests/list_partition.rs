@@ -0,0 +1,568 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_partitions(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.orders (region text, val smallint) partition by list (region);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_us partition of schema_name.orders for values in ('US', 'CA');")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_eu partition of schema_name.orders for values in ('DE', 'FR');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn partitions_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::TableCreated),
+    ]
+}
+
+fn columns() -> Vec<(String, PostgreSqlType, i32)> {
+    vec![
+        ("region".to_owned(), PostgreSqlType::VarChar, -1),
+        ("val".to_owned(), PostgreSqlType::SmallInt, -1),
+    ]
+}
+
+#[rstest::rstest]
+fn insert_routes_rows_to_partitions(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.orders values ('US', 1), ('DE', 2), ('CA', 3), ('FR', 4);")
+        .expect("no system errors");
+    engine
+        .execute("select region, val from schema_name.orders_us;")
+        .expect("no system errors");
+    engine
+        .execute("select region, val from schema_name.orders_eu;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![vec![text("US"), text("1")], vec![text("CA"), text("3")]],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![vec![text("DE"), text("2")], vec![text("FR"), text("4")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn value_that_is_not_listed(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.orders values ('JP', 1);")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![Err(QueryError::no_partition_for_row("orders".to_owned()))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn default_partition_receives_values_that_are_not_listed(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("create table schema_name.orders_other partition of schema_name.orders default;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values ('JP', 1), ('US', 2);")
+        .expect("no system errors");
+    engine
+        .execute("select region, val from schema_name.orders_other;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![vec![text("JP"), text("1")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn select_from_partitioned_table_sees_rows_of_all_partitions(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.orders values ('FR', 3), ('US', 1), ('DE', 2);")
+        .expect("no system errors");
+    engine
+        .execute("select region, val from schema_name.orders order by val;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![
+                vec![text("US"), text("1")],
+                vec![text("DE"), text("2")],
+                vec![text("FR"), text("3")],
+            ],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn equality_filter_scans_single_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.orders values ('US', 1), ('DE', 2);")
+        .expect("no system errors");
+    engine
+        .execute("select val from schema_name.orders where region = 'DE';")
+        .expect("no system errors");
+    engine
+        .execute("select tablename, seq_scan from pg_catalog.pg_stat_user_tables;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("2")]],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("tablename".to_owned(), PostgreSqlType::VarChar, -1),
+                ("seq_scan".to_owned(), PostgreSqlType::BigInt, -1),
+            ],
+            vec![
+                vec![text("orders"), text("0")],
+                vec![text("orders_eu"), text("1")],
+                vec![text("orders_us"), text("0")],
+            ],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn dropping_partition_removes_its_rows(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.orders values ('US', 1), ('DE', 2);")
+        .expect("no system errors");
+    engine
+        .execute("drop table schema_name.orders_us;")
+        .expect("no system errors");
+    engine
+        .execute("select region, val from schema_name.orders;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values ('US', 3);")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::TableDropped),
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![vec![text("DE"), text("2")]],
+        ))),
+        Err(QueryError::no_partition_for_row("orders".to_owned())),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn overlapping_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("create table schema_name.orders_na partition of schema_name.orders for values in ('MX', 'CA');")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![Err(QueryError::invalid_object_definition(
+        "partition \"orders_na\" would overlap partition \"orders_us\"".to_owned(),
+    ))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn range_bound_of_list_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute(
+            "create table schema_name.orders_range partition of schema_name.orders \
+             for values from ('A') to ('C');",
+        )
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![Err(QueryError::invalid_object_definition(
+        "invalid bound specification for a list partition".to_owned(),
+    ))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn partition_for_rows_of_default_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("create table schema_name.orders_other partition of schema_name.orders default;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values ('JP', 1);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_asia partition of schema_name.orders for values in ('JP', 'KR');")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_kr partition of schema_name.orders for values in ('KR');")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Err(QueryError::check_violation(
+            "updated partition constraint for default partition \"orders_other\" would be violated by some row"
+                .to_owned(),
+        )),
+        Ok(QueryEvent::TableCreated),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[cfg(test)]
+mod attach {
+    use super::*;
+
+    #[rstest::rstest]
+    fn table_becomes_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_partitions;
+        engine
+            .execute("create table schema_name.orders_asia (region text, val smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders_asia values ('JP', 1);")
+            .expect("no system errors");
+        engine
+            .execute(
+                "alter table schema_name.orders attach partition schema_name.orders_asia for values in ('JP', 'KR');",
+            )
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values ('KR', 2), ('US', 3);")
+            .expect("no system errors");
+        engine
+            .execute("select region, val from schema_name.orders order by val;")
+            .expect("no system errors");
+        engine
+            .execute("select region, val from schema_name.orders_asia;")
+            .expect("no system errors");
+
+        let mut expected = partitions_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::TableAltered),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::RecordsSelected((
+                columns(),
+                vec![
+                    vec![text("JP"), text("1")],
+                    vec![text("KR"), text("2")],
+                    vec![text("US"), text("3")],
+                ],
+            ))),
+            Ok(QueryEvent::RecordsSelected((
+                columns(),
+                vec![vec![text("JP"), text("1")], vec![text("KR"), text("2")]],
+            ))),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn table_with_rows_out_of_bound(with_partitions: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_partitions;
+        engine
+            .execute("create table schema_name.orders_asia (region text, val smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders_asia values ('JP', 1), ('CN', 2);")
+            .expect("no system errors");
+        engine
+            .execute(
+                "alter table schema_name.orders attach partition schema_name.orders_asia for values in ('JP', 'KR');",
+            )
+            .expect("no system errors");
+
+        let mut expected = partitions_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Err(QueryError::check_violation(
+                "partition constraint of relation \"orders_asia\" is violated by some row".to_owned(),
+            )),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn default_partition_with_rows_of_other_partitions(with_partitions: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_partitions;
+        engine
+            .execute("create table schema_name.orders_other (region text, val smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders_other values ('JP', 1), ('US', 2);")
+            .expect("no system errors");
+        engine
+            .execute("alter table schema_name.orders attach partition schema_name.orders_other default;")
+            .expect("no system errors");
+
+        let mut expected = partitions_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Err(QueryError::check_violation(
+                "partition constraint of relation \"orders_other\" is violated by some row".to_owned(),
+            )),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn table_that_is_already_a_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_partitions;
+        engine
+            .execute("alter table schema_name.orders attach partition schema_name.orders_us for values in ('MX');")
+            .expect("no system errors");
+
+        let mut expected = partitions_created();
+        expected.extend(vec![Err(QueryError::already_a_partition("orders_us".to_owned()))]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn table_with_missing_column(with_partitions: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_partitions;
+        engine
+            .execute("create table schema_name.orders_asia (region text);")
+            .expect("no system errors");
+        engine
+            .execute("alter table schema_name.orders attach partition schema_name.orders_asia for values in ('JP');")
+            .expect("no system errors");
+
+        let mut expected = partitions_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Err(QueryError::invalid_object_definition(
+                "child table is missing column \"val\"".to_owned(),
+            )),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn table_with_different_column_type(with_partitions: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_partitions;
+        engine
+            .execute("create table schema_name.orders_asia (region text, val integer);")
+            .expect("no system errors");
+        engine
+            .execute("alter table schema_name.orders attach partition schema_name.orders_asia for values in ('JP');")
+            .expect("no system errors");
+
+        let mut expected = partitions_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Err(QueryError::invalid_object_definition(
+                "child table \"orders_asia\" has different type for column \"val\"".to_owned(),
+            )),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn to_not_partitioned_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (id smallint);")
+            .expect("no system errors");
+        engine
+            .execute("create table schema_name.partition_name (id smallint);")
+            .expect("no system errors");
+        engine
+            .execute("alter table schema_name.table_name attach partition schema_name.partition_name default;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries_ignoring_complete(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::TableCreated),
+            Err(QueryError::table_is_not_partitioned("table_name".to_owned())),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod detach {
+    use super::*;
+
+    #[rstest::rstest]
+    fn partition_keeps_its_rows(with_partitions: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_partitions;
+        engine
+            .execute("insert into schema_name.orders values ('US', 1), ('DE', 2);")
+            .expect("no system errors");
+        engine
+            .execute("alter table schema_name.orders detach partition schema_name.orders_us;")
+            .expect("no system errors");
+        engine
+            .execute("select region, val from schema_name.orders;")
+            .expect("no system errors");
+        engine
+            .execute("select region, val from schema_name.orders_us;")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.orders values ('US', 3);")
+            .expect("no system errors");
+
+        let mut expected = partitions_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::TableAltered),
+            Ok(QueryEvent::RecordsSelected((
+                columns(),
+                vec![vec![text("DE"), text("2")]],
+            ))),
+            Ok(QueryEvent::RecordsSelected((
+                columns(),
+                vec![vec![text("US"), text("1")]],
+            ))),
+            Err(QueryError::no_partition_for_row("orders".to_owned())),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn table_that_is_not_a_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_partitions;
+        engine
+            .execute("create table schema_name.orders_asia (region text, val smallint);")
+            .expect("no system errors");
+        engine
+            .execute("alter table schema_name.orders detach partition schema_name.orders_asia;")
+            .expect("no system errors");
+
+        let mut expected = partitions_created();
+        expected.extend(vec![
+            Ok(QueryEvent::TableCreated),
+            Err(QueryError::not_a_partition(
+                "orders_asia".to_owned(),
+                "orders".to_owned(),
+            )),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+}
+
+#[rstest::rstest]
+fn partitions_survive_restart() {
+    let root_path = tempfile::tempdir().expect("to create temporary folder");
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let mut engine = QueryExecutor::new(storage, Collector::new());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.orders (region text, val smallint) partition by list (region);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_us partition of schema_name.orders for values in ('US', 'CA');")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_eu partition of schema_name.orders for values in ('DE', 'FR');")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_other partition of schema_name.orders default;")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_asia (region text, val smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders_asia values ('JP', 3);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values ('US', 1), ('DE', 2), ('BR', 4);")
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.orders attach partition schema_name.orders_asia for values in ('JP', 'KR');")
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.orders detach partition schema_name.orders_eu;")
+        .expect("no system errors");
+    drop(engine);
+
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage, collector.clone());
+    engine
+        .execute("select region, val from schema_name.orders order by val;")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_na partition of schema_name.orders for values in ('MX', 'CA');")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders_rest partition of schema_name.orders default;")
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.orders detach partition schema_name.orders_eu;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::RecordsSelected((
+            columns(),
+            vec![
+                vec![text("US"), text("1")],
+                vec![text("JP"), text("3")],
+                vec![text("BR"), text("4")],
+            ],
+        ))),
+        Err(QueryError::invalid_object_definition(
+            "partition \"orders_na\" would overlap partition \"orders_us\"".to_owned(),
+        )),
+        Err(QueryError::invalid_object_definition(
+            "partition \"orders_rest\" would overlap partition \"orders_other\"".to_owned(),
+        )),
+        Err(QueryError::not_a_partition("orders_eu".to_owned(), "orders".to_owned())),
+    ]);
+}
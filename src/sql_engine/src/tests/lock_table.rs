@@ -0,0 +1,194 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+use std::{sync::mpsc, thread, time::Duration};
+
+type Session = (QueryExecutor, ResultCollector);
+
+#[rstest::fixture]
+fn two_sessions() -> (Session, Session) {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let first_collector = Collector::new();
+    let mut first = QueryExecutor::new(storage.clone(), first_collector.clone());
+    first.execute("create schema schema_name;").expect("no system errors");
+    first
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    let second_collector = Collector::new();
+    let second = QueryExecutor::new(storage, second_collector.clone());
+
+    ((first, first_collector), (second, second_collector))
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![Ok(QueryEvent::SchemaCreated), Ok(QueryEvent::TableCreated)]
+}
+
+// executes query on other thread and checks that it waits until `release` query is executed
+fn blocked_until(
+    blocked: Session,
+    query: &'static str,
+    releasing: &mut QueryExecutor,
+    release: &str,
+) -> ResultCollector {
+    let (mut executor, collector) = blocked;
+    let (done_sender, done) = mpsc::channel();
+    let blocked = thread::spawn(move || {
+        executor.execute(query).expect("no system errors");
+        done_sender.send(()).expect("to notify");
+    });
+    assert!(done.recv_timeout(Duration::from_millis(100)).is_err());
+
+    releasing.execute(release).expect("no system errors");
+    blocked.join().expect("to finish");
+    collector
+}
+
+#[rstest::rstest]
+fn two_exclusive_locks(two_sessions: (Session, Session)) {
+    let ((mut first, first_collector), second) = two_sessions;
+    first.execute("begin;").expect("no system errors");
+    first
+        .execute("lock table schema_name.table_name in exclusive mode;")
+        .expect("no system errors");
+
+    let second_collector = blocked_until(
+        second,
+        "lock table schema_name.table_name in exclusive mode;",
+        &mut first,
+        "commit;",
+    );
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::TableLocked),
+        Ok(QueryEvent::TransactionCommitted),
+    ]);
+    first_collector.assert_content_for_single_queries_ignoring_complete(expected);
+    second_collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::TableLocked)]);
+}
+
+#[rstest::rstest]
+fn exclusive_lock_blocks_modification(two_sessions: (Session, Session)) {
+    let ((mut first, _first_collector), second) = two_sessions;
+    first.execute("begin;").expect("no system errors");
+    first
+        .execute("lock table schema_name.table_name in exclusive mode;")
+        .expect("no system errors");
+
+    let second_collector = blocked_until(
+        second,
+        "insert into schema_name.table_name values (1);",
+        &mut first,
+        "commit;",
+    );
+
+    second_collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::RecordsInserted(1))]);
+}
+
+#[rstest::rstest]
+fn shared_lock_allows_readers(two_sessions: (Session, Session)) {
+    let ((mut first, _first_collector), (mut second, second_collector)) = two_sessions;
+    first.execute("begin;").expect("no system errors");
+    first
+        .execute("lock table schema_name.table_name in access share mode;")
+        .expect("no system errors");
+
+    second
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    second
+        .execute("lock table schema_name.table_name in row exclusive mode nowait;")
+        .expect("no system errors");
+
+    second_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![],
+        ))),
+        Ok(QueryEvent::TableLocked),
+    ]);
+}
+
+#[rstest::rstest]
+fn lock_outside_of_transaction_is_released(two_sessions: (Session, Session)) {
+    let ((mut first, _first_collector), (mut second, second_collector)) = two_sessions;
+    first
+        .execute("lock table schema_name.table_name in exclusive mode;")
+        .expect("no system errors");
+
+    second
+        .execute("lock table schema_name.table_name in exclusive mode nowait;")
+        .expect("no system errors");
+
+    second_collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::TableLocked)]);
+}
+
+#[rstest::rstest]
+fn nowait(two_sessions: (Session, Session)) {
+    let ((mut first, _first_collector), (mut second, second_collector)) = two_sessions;
+    first.execute("begin;").expect("no system errors");
+    first
+        .execute("lock table schema_name.table_name;")
+        .expect("no system errors");
+
+    second
+        .execute("lock table schema_name.table_name in access share mode nowait;")
+        .expect("no system errors");
+
+    second_collector.assert_content_for_single_queries_ignoring_complete(vec![Err(QueryError::lock_not_available(
+        "table_name".to_owned(),
+    ))]);
+}
+
+#[rstest::rstest]
+fn lock_non_existent_table(two_sessions: (Session, Session)) {
+    let (_first, (mut second, second_collector)) = two_sessions;
+
+    second
+        .execute("lock table schema_name.non_existent in exclusive mode;")
+        .expect("no system errors");
+
+    second_collector.assert_content_for_single_queries_ignoring_complete(vec![Err(QueryError::table_does_not_exist(
+        "schema_name.non_existent".to_owned(),
+    ))]);
+}
+
+#[rstest::rstest]
+fn vacuum_full_takes_exclusive_lock(two_sessions: (Session, Session)) {
+    let ((mut first, first_collector), second) = two_sessions;
+    first.execute("begin;").expect("no system errors");
+    first
+        .execute("vacuum full schema_name.table_name;")
+        .expect("no system errors");
+
+    let second_collector = blocked_until(
+        second,
+        "insert into schema_name.table_name values (1);",
+        &mut first,
+        "commit;",
+    );
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::VacuumComplete { rows_removed: 0 }),
+        Ok(QueryEvent::TransactionCommitted),
+    ]);
+    first_collector.assert_content_for_single_queries_ignoring_complete(expected);
+    second_collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::RecordsInserted(1))]);
+}
@@ -0,0 +1,277 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.target (id smallint, v smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.target values (1, 10), (2, 20), (3, 30);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.source (id smallint, v smallint);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn tables_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn selected(rows: Vec<(&str, &str)>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+            ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+        ],
+        rows.into_iter().map(|(id, v)| vec![text(id), text(v)]).collect(),
+    )))
+}
+
+const UPSERT: &str = "merge into schema_name.target as t using schema_name.source as s on t.id = s.id \
+                      when matched then update set v = s.v \
+                      when not matched then insert (id, v) values (s.id, s.v);";
+
+#[rstest::rstest]
+fn all_matched_rows_are_updated(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("insert into schema_name.source values (1, 100), (3, 300);")
+        .expect("no system errors");
+    engine.execute(UPSERT).expect("no system errors");
+    engine
+        .execute("select * from schema_name.target;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsMerged(2)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("1", "100"), ("2", "20"), ("3", "300")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn all_unmatched_rows_are_inserted(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("insert into schema_name.source values (4, 400), (5, 500);")
+        .expect("no system errors");
+    engine.execute(UPSERT).expect("no system errors");
+    engine
+        .execute("select * from schema_name.target;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsMerged(2)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("1", "10"), ("2", "20"), ("3", "30"), ("4", "400"), ("5", "500")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn mixed_batch(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("insert into schema_name.source values (2, 200), (4, 400);")
+        .expect("no system errors");
+    engine.execute(UPSERT).expect("no system errors");
+    engine
+        .execute("select * from schema_name.target;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsMerged(2)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("1", "10"), ("2", "200"), ("3", "30"), ("4", "400")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn not_matched_by_source_rows_are_deleted(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("insert into schema_name.source values (2, 200), (4, 400);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "merge into schema_name.target as t using schema_name.source as s on t.id = s.id \
+             when matched then update set v = s.v \
+             when not matched by target then insert values (s.id, s.v) \
+             when not matched by source then delete;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.target;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsMerged(4)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("2", "200"), ("4", "400")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn subquery_as_source(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("insert into schema_name.source values (1, 100), (4, 400), (5, 500);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "merge into schema_name.target using (select id, v + 1 as v from schema_name.source where id < 5) as s \
+             on target.id = s.id \
+             when matched and s.v > 100 then update set v = s.v \
+             when not matched then insert (id, v) values (s.id, s.v);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.target;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsMerged(2)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("1", "101"), ("2", "20"), ("3", "30"), ("4", "401")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn inserted_columns_without_value_get_default(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.target (id smallint, v smallint default 7, w smallint not null);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.source (id smallint, w smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.source values (1, 10), (2, null);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "merge into schema_name.target as t using schema_name.source as s on t.id = s.id \
+             when not matched and s.id = 1 then insert (id, w) values (s.id, s.w);",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "merge into schema_name.target as t using schema_name.source as s on t.id = s.id \
+             when not matched then insert (id, w) values (s.id, s.w);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select id, v from schema_name.target;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsMerged(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::not_null_violation("w".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("1", "7")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn target_row_matched_twice(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute("insert into schema_name.source values (1, 100), (1, 101);")
+        .expect("no system errors");
+    engine.execute(UPSERT).expect("no system errors");
+    engine
+        .execute("select * from schema_name.target;")
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::cardinality_violation(
+            "MERGE command cannot affect row a second time".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("1", "10"), ("2", "20"), ("3", "30")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn non_existent_source(with_tables: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_tables;
+    engine
+        .execute(
+            "merge into schema_name.target using schema_name.non_existent as s on target.id = s.id \
+             when matched then delete;",
+        )
+        .expect("no system errors");
+
+    let mut expected = tables_created();
+    expected.extend(vec![
+        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
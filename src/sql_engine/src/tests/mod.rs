@@ -12,75 +12,127 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(test)]
+mod aggregate;
+#[cfg(test)]
+mod analyze;
+#[cfg(test)]
+mod array;
+#[cfg(test)]
+mod batch;
 #[cfg(test)]
 mod bind;
 #[cfg(test)]
 mod bind_prepared_statement_to_portal;
 #[cfg(test)]
+mod bytea;
+#[cfg(test)]
+mod cancel_backend;
+#[cfg(test)]
+mod cast;
+#[cfg(test)]
+mod catalog;
+#[cfg(test)]
+mod cluster;
+#[cfg(test)]
+mod columnar;
+#[cfg(test)]
+mod copy;
+#[cfg(test)]
+mod create_table_as;
+#[cfg(test)]
+mod cursor;
+#[cfg(test)]
 mod delete;
 #[cfg(test)]
 mod describe_prepared_statement;
 #[cfg(test)]
+mod empty_query;
+#[cfg(test)]
+mod execute_async;
+#[cfg(test)]
 mod execute_portal;
 #[cfg(test)]
+mod explain;
+#[cfg(test)]
+mod explain_syntax;
+#[cfg(test)]
+mod foreign_key;
+#[cfg(test)]
+mod function;
+#[cfg(test)]
+mod generate_series;
+#[cfg(test)]
+mod generated_column;
+#[cfg(test)]
 mod insert;
 #[cfg(test)]
+mod join;
+#[cfg(test)]
+mod json;
+#[cfg(test)]
+mod lateral;
+#[cfg(test)]
+mod list_partition;
+#[cfg(test)]
+mod lock_table;
+#[cfg(test)]
+mod merge;
+#[cfg(test)]
+mod notification;
+#[cfg(test)]
 mod parse_prepared_statement;
 #[cfg(test)]
+mod partition;
+#[cfg(test)]
+mod pushdown;
+#[cfg(test)]
+mod query_cache;
+#[cfg(test)]
+mod rename_column;
+#[cfg(test)]
 mod schema;
 #[cfg(test)]
 mod select;
 #[cfg(test)]
+mod syntax_error;
+#[cfg(test)]
 mod table;
 #[cfg(test)]
+mod temporary_table;
+#[cfg(test)]
+mod test_utils;
+#[cfg(test)]
+mod transaction;
+#[cfg(test)]
+mod trigger;
+#[cfg(test)]
 mod type_constraints;
 #[cfg(test)]
+mod unsupported;
+#[cfg(test)]
 mod update;
+#[cfg(test)]
+mod users;
+#[cfg(test)]
+mod vacuum;
+#[cfg(test)]
+mod validate;
 
 use super::*;
 use crate::QueryExecutor;
 use protocol::results::{QueryError, QueryResult};
-use std::{
-    io,
-    ops::Deref,
-    sync::{Arc, Mutex},
-};
-
-struct Collector(Mutex<Vec<QueryResult>>);
-
-impl Sender for Collector {
-    fn flush(&self) -> io::Result<()> {
-        Ok(())
-    }
-
-    fn send(&self, query_result: QueryResult) -> io::Result<()> {
-        self.0.lock().expect("locked").push(query_result);
-        Ok(())
-    }
-}
-
-impl Collector {
-    fn assert_content(&self, expected: Vec<QueryResult>) {
-        let result = self.0.lock().expect("locked");
-        assert_eq!(result.deref(), &expected)
-    }
-
-    fn assert_content_for_single_queries(&self, expected: Vec<QueryResult>) {
-        let actual = self.0.lock().expect("locked");
-        assert_eq!(actual.deref(), &expected)
-    }
-}
-
-type ResultCollector = Arc<Collector>;
+use std::sync::Arc;
+use test_utils::{text, Collector, ResultCollector};
 
 #[rstest::fixture]
 fn sender() -> ResultCollector {
-    Arc::new(Collector(Mutex::new(vec![])))
+    Collector::new()
 }
 
 #[rstest::fixture]
 fn sql_engine() -> (QueryExecutor, ResultCollector) {
-    let collector = Arc::new(Collector(Mutex::new(vec![])));
+    let collector = Collector::new();
     (
         QueryExecutor::new(
             Arc::new(DataManager::in_memory().expect("to create data manager")),
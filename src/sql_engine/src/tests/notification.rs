@@ -0,0 +1,181 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::Notifications;
+
+type Session = (QueryExecutor, ResultCollector);
+
+fn sessions(number: usize) -> Vec<Session> {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let notifications = Arc::new(Notifications::default());
+    (0..number)
+        .map(|_| {
+            let collector = Collector::new();
+            let mut executor = QueryExecutor::new(storage.clone(), collector.clone());
+            executor.set_notifications(notifications.clone());
+            (executor, collector)
+        })
+        .collect()
+}
+
+fn notification(notifier: &QueryExecutor, channel: &str, payload: &str) -> QueryResult {
+    Ok(QueryEvent::Notification {
+        pid: notifier.session_id as u32,
+        channel: channel.to_owned(),
+        payload: payload.to_owned(),
+    })
+}
+
+#[rstest::rstest]
+fn listener_receives_notification() {
+    let mut sessions = sessions(2);
+    let (mut notifier, notifier_collector) = sessions.pop().unwrap();
+    let (mut listener, listener_collector) = sessions.pop().unwrap();
+
+    listener.execute("listen channel_name;").expect("no system errors");
+    notifier
+        .execute("notify channel_name, 'payload';")
+        .expect("no system errors");
+
+    listener_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::ChannelListened),
+        notification(&notifier, "channel_name", "payload"),
+    ]);
+    notifier_collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::NotificationSent)]);
+}
+
+#[rstest::rstest]
+fn multiple_listeners_receive_same_notification() {
+    let mut sessions = sessions(3);
+    let (mut notifier, _notifier_collector) = sessions.pop().unwrap();
+    let (mut second, second_collector) = sessions.pop().unwrap();
+    let (mut first, first_collector) = sessions.pop().unwrap();
+
+    first.execute("listen channel_name;").expect("no system errors");
+    second.execute("listen channel_name;").expect("no system errors");
+    notifier
+        .execute("notify channel_name, 'payload';")
+        .expect("no system errors");
+
+    let expected = vec![
+        Ok(QueryEvent::ChannelListened),
+        notification(&notifier, "channel_name", "payload"),
+    ];
+    first_collector.assert_content_for_single_queries_ignoring_complete(expected.clone());
+    second_collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn unlisten_stops_delivery() {
+    let mut sessions = sessions(2);
+    let (mut notifier, _notifier_collector) = sessions.pop().unwrap();
+    let (mut listener, listener_collector) = sessions.pop().unwrap();
+
+    listener.execute("listen channel_name;").expect("no system errors");
+    listener.execute("unlisten channel_name;").expect("no system errors");
+    notifier
+        .execute("notify channel_name, 'payload';")
+        .expect("no system errors");
+
+    listener_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::ChannelListened),
+        Ok(QueryEvent::ChannelUnlistened),
+    ]);
+}
+
+#[rstest::rstest]
+fn unlisten_all_channels() {
+    let mut sessions = sessions(2);
+    let (mut notifier, _notifier_collector) = sessions.pop().unwrap();
+    let (mut listener, listener_collector) = sessions.pop().unwrap();
+
+    listener.execute("listen first_channel;").expect("no system errors");
+    listener.execute("listen second_channel;").expect("no system errors");
+    listener.execute("unlisten *;").expect("no system errors");
+    notifier.execute("notify first_channel;").expect("no system errors");
+    notifier.execute("notify second_channel;").expect("no system errors");
+
+    listener_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::ChannelListened),
+        Ok(QueryEvent::ChannelListened),
+        Ok(QueryEvent::ChannelUnlistened),
+    ]);
+}
+
+#[rstest::rstest]
+fn notification_sent_before_listen_is_not_delivered() {
+    let mut sessions = sessions(2);
+    let (mut notifier, _notifier_collector) = sessions.pop().unwrap();
+    let (mut listener, listener_collector) = sessions.pop().unwrap();
+
+    notifier
+        .execute("notify channel_name, 'before';")
+        .expect("no system errors");
+    listener.execute("listen channel_name;").expect("no system errors");
+    notifier
+        .execute("notify channel_name, 'after';")
+        .expect("no system errors");
+
+    listener_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::ChannelListened),
+        notification(&notifier, "channel_name", "after"),
+    ]);
+}
+
+#[rstest::rstest]
+fn payload_is_optional() {
+    let mut sessions = sessions(2);
+    let (mut notifier, _notifier_collector) = sessions.pop().unwrap();
+    let (mut listener, listener_collector) = sessions.pop().unwrap();
+
+    listener.execute("listen channel_name;").expect("no system errors");
+    notifier.execute("notify channel_name;").expect("no system errors");
+
+    listener_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::ChannelListened),
+        notification(&notifier, "channel_name", ""),
+    ]);
+}
+
+#[rstest::rstest]
+fn other_channels_are_not_delivered() {
+    let mut sessions = sessions(2);
+    let (mut notifier, _notifier_collector) = sessions.pop().unwrap();
+    let (mut listener, listener_collector) = sessions.pop().unwrap();
+
+    listener.execute("listen channel_name;").expect("no system errors");
+    notifier
+        .execute("notify other_channel, 'payload';")
+        .expect("no system errors");
+
+    listener_collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::ChannelListened)]);
+}
+
+#[rstest::rstest]
+fn session_receives_own_notification() {
+    let mut sessions = sessions(1);
+    let (mut session, collector) = sessions.pop().unwrap();
+
+    session.execute("listen channel_name;").expect("no system errors");
+    session
+        .execute("notify Channel_Name, 'payload';")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::ChannelListened),
+        Ok(QueryEvent::NotificationSent),
+        notification(&session, "channel_name", "payload"),
+    ]);
+}
@@ -0,0 +1,457 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_partitions(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.measurements (ts timestamp, val smallint) partition by range (ts);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.measurements_2023 partition of schema_name.measurements \
+             for values from ('2023-01-01') to ('2024-01-01');",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.measurements_2024 partition of schema_name.measurements \
+             for values from ('2024-01-01') to ('2025-01-01');",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn partitions_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::TableCreated),
+    ]
+}
+
+#[rstest::rstest]
+fn insert_routes_rows_to_partitions(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute(
+            "insert into schema_name.measurements values \
+             ('2023-03-15 10:00:00', 1), ('2024-07-01 12:30:00', 2), ('2023-11-30', 3);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select val from schema_name.measurements_2023;")
+        .expect("no system errors");
+    engine
+        .execute("select ts, val from schema_name.measurements_2024;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("1")], vec![text("3")]],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("ts".to_owned(), PostgreSqlType::Timestamp, -1),
+                ("val".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![vec![text("2024-07-01 12:30:00"), text("2")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn select_from_partitioned_table_sees_rows_of_all_partitions(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.measurements values ('2024-02-01', 1), ('2023-02-01', 2);")
+        .expect("no system errors");
+    engine
+        .execute("select val from schema_name.measurements order by val;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("1")], vec![text("2")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn lower_bound_is_inclusive_upper_bound_is_exclusive(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.measurements values ('2024-01-01 00:00:00', 1);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.measurements values ('2023-12-31 23:59:59', 2);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.measurements values ('2025-01-01', 3);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.measurements values ('2022-12-31 23:59:59', 4);")
+        .expect("no system errors");
+    engine
+        .execute("select val from schema_name.measurements_2024;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Err(QueryError::no_partition_for_row("measurements".to_owned())),
+        Err(QueryError::no_partition_for_row("measurements".to_owned())),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("1")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn null_partition_key_is_not_routed(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.measurements (val) values (1);")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![Err(QueryError::no_partition_for_row("measurements".to_owned()))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn partitions_out_of_filtered_range_are_not_scanned(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute(
+            "insert into schema_name.measurements values \
+             ('2023-05-01 08:00:00', 1), ('2024-03-01 08:00:00', 2), ('2024-08-01 08:00:00', 3);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("select val from schema_name.measurements where ts > '2024-06-01';")
+        .expect("no system errors");
+    engine
+        .execute("select tablename, seq_scan from pg_catalog.pg_stat_user_tables;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("3")]],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("tablename".to_owned(), PostgreSqlType::VarChar, -1),
+                ("seq_scan".to_owned(), PostgreSqlType::BigInt, -1),
+            ],
+            vec![
+                vec![text("measurements"), text("0")],
+                vec![text("measurements_2023"), text("0")],
+                vec![text("measurements_2024"), text("1")],
+            ],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn equality_filter_scans_single_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("insert into schema_name.measurements values ('2023-05-01 08:00:00', 1), ('2024-03-01 08:00:00', 2);")
+        .expect("no system errors");
+    engine
+        .execute("select val from schema_name.measurements where '2023-05-01 08:00:00' = ts;")
+        .expect("no system errors");
+    engine
+        .execute("select tablename, seq_scan from pg_catalog.pg_stat_user_tables;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("1")]],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("tablename".to_owned(), PostgreSqlType::VarChar, -1),
+                ("seq_scan".to_owned(), PostgreSqlType::BigInt, -1),
+            ],
+            vec![
+                vec![text("measurements"), text("0")],
+                vec![text("measurements_2023"), text("1")],
+                vec![text("measurements_2024"), text("0")],
+            ],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn overlapping_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute(
+            "create table schema_name.measurements_mid partition of schema_name.measurements \
+             for values from ('2023-06-01') to ('2024-06-01');",
+        )
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![Err(QueryError::invalid_object_definition(
+        "partition \"measurements_mid\" would overlap partition \"measurements_2023\"".to_owned(),
+    ))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn empty_partition_range(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute(
+            "create table schema_name.measurements_2026 partition of schema_name.measurements \
+             for values from ('2026-01-01') to ('2026-01-01');",
+        )
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![Err(QueryError::invalid_object_definition(
+        "empty range bound specified for partition \"measurements_2026\"".to_owned(),
+    ))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn partition_of_not_partitioned_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.partition_name partition of schema_name.table_name \
+             for values from (1) to (10);",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Err(QueryError::table_is_not_partitioned("table_name".to_owned())),
+    ]);
+}
+
+#[rstest::rstest]
+fn partition_key_is_not_a_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint) partition by range (non_existent);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Err(QueryError::column_does_not_exist(vec!["non_existent".to_owned()])),
+    ]);
+}
+
+#[rstest::rstest]
+fn integer_partition_key(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint) partition by range (id);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.negative partition of schema_name.table_name \
+             for values from (-100) to (0);",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.positive partition of schema_name.table_name \
+             for values from (0) to (100);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (-5), (0), (99);")
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.positive;")
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.table_name where id < 0;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("id".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("0")], vec![text("99")]],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("id".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("-5")]],
+        ))),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_partitioned_table(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("update schema_name.measurements set val = 1;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![Err(QueryError::feature_not_supported(
+        "UPDATE on partitioned table".to_owned(),
+    ))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn dropping_partitioned_table_drops_partitions(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("drop table schema_name.measurements;")
+        .expect("no system errors");
+    engine
+        .execute("select val from schema_name.measurements_2024;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableDropped),
+        Err(QueryError::table_does_not_exist(
+            "schema_name.measurements_2024".to_owned(),
+        )),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn attach_table_as_range_partition(with_partitions: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_partitions;
+    engine
+        .execute("create table schema_name.measurements_2025 (ts timestamp, val smallint);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "alter table schema_name.measurements attach partition schema_name.measurements_2025 \
+             for values from ('2024-06-01') to ('2026-01-01');",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "alter table schema_name.measurements attach partition schema_name.measurements_2025 \
+             for values from ('2025-01-01') to ('2026-01-01');",
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.measurements values ('2025-02-01', 1);")
+        .expect("no system errors");
+    engine
+        .execute("select val from schema_name.measurements_2025;")
+        .expect("no system errors");
+
+    let mut expected = partitions_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Err(QueryError::invalid_object_definition(
+            "partition \"measurements_2025\" would overlap partition \"measurements_2024\"".to_owned(),
+        )),
+        Ok(QueryEvent::TableAltered),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("1")]],
+        ))),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn partitions_survive_restart() {
+    let root_path = tempfile::tempdir().expect("to create temporary folder");
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let mut engine = QueryExecutor::new(storage, Collector::new());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.measurements (ts timestamp, val smallint) partition by range (ts);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.measurements_2023 partition of schema_name.measurements \
+             for values from ('2023-01-01') to ('2024-01-01');",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.measurements_2024 partition of schema_name.measurements \
+             for values from ('2024-01-01') to ('2025-01-01');",
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.measurements values ('2024-07-01', 2), ('2023-03-15', 1);")
+        .expect("no system errors");
+    drop(engine);
+
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage, collector.clone());
+    engine
+        .execute("select val from schema_name.measurements order by val;")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create table schema_name.measurements_half partition of schema_name.measurements \
+             for values from ('2023-06-01') to ('2023-07-01');",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::RecordsSelected((
+            vec![("val".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("1")], vec![text("2")]],
+        ))),
+        Err(QueryError::invalid_object_definition(
+            "partition \"measurements_half\" would overlap partition \"measurements_2023\"".to_owned(),
+        )),
+    ]);
+}
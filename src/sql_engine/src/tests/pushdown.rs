@@ -0,0 +1,162 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+use std::time::{Duration, Instant};
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id integer, name varchar(10), amount smallint);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "insert into schema_name.table_name values (1, 'one', 10), (2, 'two', 20), (3, 'three', 20), (4, 'four', 40);",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn selected(columns: Vec<(&str, PostgreSqlType, i32)>, rows: Vec<Vec<&str>>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        columns
+            .into_iter()
+            .map(|(name, sql_type, type_modifier)| (name.to_owned(), sql_type, type_modifier))
+            .collect(),
+        rows.into_iter()
+            .map(|row| row.into_iter().map(text).collect())
+            .collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn select_with_equality_on_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("select id from schema_name.table_name where amount = 20;")
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.table_name where 20 = amount and id > 2;")
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.table_name where (name = 'three' or id = 1);")
+        .expect("no system errors");
+    engine
+        .execute("select t.id from schema_name.table_name as t where (t.name = 'two');")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        selected(vec![("id", PostgreSqlType::Integer, -1)], vec![vec!["2"], vec!["3"]]),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("id", PostgreSqlType::Integer, -1)], vec![vec!["3"]]),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("id", PostgreSqlType::Integer, -1)], vec![vec!["1"], vec!["3"]]),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec![("id", PostgreSqlType::Integer, -1)], vec![vec!["2"]]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_and_delete_with_equality_on_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("update schema_name.table_name set amount = 30 where amount = 20 and name = 'three';")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where name = 'one';")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(
+            vec![
+                ("id", PostgreSqlType::Integer, -1),
+                ("name", PostgreSqlType::VarChar, 14),
+                ("amount", PostgreSqlType::SmallInt, -1),
+            ],
+            vec![
+                vec!["2", "two", "20"],
+                vec!["3", "three", "30"],
+                vec!["4", "four", "40"],
+            ],
+        ),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn selective_equality_on_wide_table_is_faster_than_full_scan(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, _collector) = sql_engine_with_schema;
+    let columns = (1..=20)
+        .map(|index| format!("c{} integer", index))
+        .collect::<Vec<_>>()
+        .join(", ");
+    engine
+        .execute(format!("create table schema_name.wide_table ({});", columns).as_str())
+        .expect("no system errors");
+    let values = (1..=2000)
+        .map(|value| format!("({})", vec![value.to_string(); 20].join(", ")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    engine
+        .execute(format!("insert into schema_name.wide_table values {};", values).as_str())
+        .expect("no system errors");
+
+    let mut elapsed = |predicate: &str| {
+        let start = Instant::now();
+        engine
+            .execute(format!("select * from schema_name.wide_table where {};", predicate).as_str())
+            .expect("no system errors");
+        start.elapsed()
+    };
+    let mut pushed_down = Duration::MAX;
+    let mut evaluated = Duration::MAX;
+    for _ in 0..7 {
+        pushed_down = pushed_down.min(elapsed("c1 = 1000"));
+        // arithmetic keeps the predicate from being checked by the storage
+        evaluated = evaluated.min(elapsed("c1 + 0 = 1000"));
+    }
+
+    assert!(
+        pushed_down <= evaluated / 2,
+        "pushed down {:?} evaluated {:?}",
+        pushed_down,
+        evaluated
+    );
+}
@@ -0,0 +1,338 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::QueryCache;
+use protocol::sql_types::PostgreSqlType;
+use std::{thread, time::Duration};
+
+const CACHE_SIZE_BYTES: usize = 1024 * 1024;
+const TTL: Duration = Duration::from_secs(60);
+
+fn cached_engine(storage: Arc<DataManager>, cache: Arc<QueryCache>) -> (QueryExecutor, ResultCollector) {
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage, collector.clone());
+    engine.set_query_cache(cache);
+    (engine, collector)
+}
+
+fn with_table(cache: Arc<QueryCache>) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = cached_engine(
+        Arc::new(DataManager::in_memory().expect("to create data manager")),
+        cache,
+    );
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (id smallint, name varchar(1000));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 'one'), (2, 'two');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn ids(ids: Vec<&str>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![("id".to_owned(), PostgreSqlType::SmallInt, -1)],
+        ids.into_iter().map(|id| vec![text(id)]).collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn repeated_select_is_answered_from_cache() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, TTL));
+    let (mut engine, collector) = with_table(cache.clone());
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+    assert_eq!(cache.hits(), 0);
+    engine
+        .execute("SELECT id\n  FROM schema_name.table_name;")
+        .expect("no system errors");
+    assert_eq!(cache.hits(), 1);
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        ids(vec!["1", "2"]),
+        Ok(QueryEvent::QueryComplete),
+        ids(vec!["1", "2"]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn result_is_shared_between_sessions() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, TTL));
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let (mut first, _first_collector) = cached_engine(storage.clone(), cache.clone());
+    let (mut second, second_collector) = cached_engine(storage, cache.clone());
+    first.execute("create schema schema_name;").expect("no system errors");
+    first
+        .execute("create table schema_name.table_name (id smallint);")
+        .expect("no system errors");
+    first
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    first
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+    second
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+
+    assert_eq!(cache.hits(), 1);
+    second_collector.assert_content_for_single_queries(vec![ids(vec!["1"]), Ok(QueryEvent::QueryComplete)]);
+}
+
+#[rstest::rstest]
+fn insert_invalidates_cached_result() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, TTL));
+    let (mut engine, collector) = with_table(cache.clone());
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3, 'three');")
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where id = 1;")
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+
+    assert_eq!(cache.hits(), 0);
+    let mut expected = table_created();
+    expected.extend(vec![
+        ids(vec!["1", "2"]),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        ids(vec!["1", "2", "3"]),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(1)),
+        Ok(QueryEvent::QueryComplete),
+        ids(vec!["2", "3"]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn temporary_table_shadows_cached_result() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, TTL));
+    let (mut engine, collector) = with_table(cache.clone());
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("create temporary table schema_name.table_name (id smallint);")
+        .expect("no system errors");
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+
+    assert_eq!(cache.hits(), 0);
+    let mut expected = table_created();
+    expected.extend(vec![
+        ids(vec!["1", "2"]),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        ids(vec![]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn least_recently_used_result_is_evicted_when_cache_is_full() {
+    // every result holds a value of 1000 bytes, thus only two of them fit
+    let cache = Arc::new(QueryCache::new(3000, TTL));
+    let (mut engine, _collector) = with_table(cache.clone());
+    engine
+        .execute("insert into schema_name.table_name values (3, 'three');")
+        .expect("no system errors");
+    engine
+        .execute(format!("update schema_name.table_name set name = '{}';", "x".repeat(1000)).as_str())
+        .expect("no system errors");
+    let mut select = |id: u8| {
+        engine
+            .execute(format!("select * from schema_name.table_name where id = {};", id).as_str())
+            .expect("no system errors");
+    };
+    select(1);
+    select(2);
+    select(1);
+    assert_eq!(cache.hits(), 1);
+    select(3);
+    assert_eq!(cache.len(), 2);
+    select(1);
+    assert_eq!(cache.hits(), 2);
+    select(2);
+    assert_eq!(cache.hits(), 2);
+}
+
+#[rstest::rstest]
+fn result_larger_than_cache_is_not_cached() {
+    let cache = Arc::new(QueryCache::new(100, TTL));
+    let (mut engine, _collector) = with_table(cache.clone());
+    engine
+        .execute(format!("update schema_name.table_name set name = '{}';", "x".repeat(1000)).as_str())
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    assert!(cache.is_empty());
+}
+
+#[rstest::rstest]
+fn cached_result_expires() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, Duration::from_millis(50)));
+    let (mut engine, _collector) = with_table(cache.clone());
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+    thread::sleep(Duration::from_millis(100));
+    engine
+        .execute("select id from schema_name.table_name;")
+        .expect("no system errors");
+
+    assert_eq!(cache.hits(), 0);
+}
+
+#[rstest::rstest]
+fn immutable_query_without_tables_is_cached() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, TTL));
+    let (mut engine, _collector) = cached_engine(
+        Arc::new(DataManager::in_memory().expect("to create data manager")),
+        cache.clone(),
+    );
+    engine.execute("select upper('one');").expect("no system errors");
+    engine.execute("select upper('one');").expect("no system errors");
+
+    assert_eq!(cache.hits(), 1);
+}
+
+#[rstest::rstest]
+fn query_with_user_function_is_not_cached() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, TTL));
+    let (mut engine, _collector) = with_table(cache.clone());
+    engine
+        .execute("create function schema_name.next(n smallint) returns smallint as $$ n + 1 $$ language sql;")
+        .expect("no system errors");
+    engine
+        .execute("select schema_name.next(id) from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select schema_name.next(id) from schema_name.table_name;")
+        .expect("no system errors");
+
+    assert_eq!(cache.hits(), 0);
+    assert!(cache.is_empty());
+}
+
+#[rstest::rstest]
+fn catalog_table_is_not_cached() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, TTL));
+    let (mut engine, collector) = with_table(cache.clone());
+    engine
+        .execute("select n_live_tup from pg_catalog.pg_stat_user_tables;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3, 'three');")
+        .expect("no system errors");
+    engine
+        .execute("select n_live_tup from pg_catalog.pg_stat_user_tables;")
+        .expect("no system errors");
+
+    assert_eq!(cache.hits(), 0);
+    let live_rows = |count: &str| {
+        Ok(QueryEvent::RecordsSelected((
+            vec![("n_live_tup".to_owned(), PostgreSqlType::BigInt, -1)],
+            vec![vec![text(count)]],
+        )))
+    };
+    let mut expected = table_created();
+    expected.extend(vec![
+        live_rows("2"),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        live_rows("3"),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn backend_functions_are_not_cached() {
+    let cache = Arc::new(QueryCache::new(CACHE_SIZE_BYTES, TTL));
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let (mut first, first_collector) = cached_engine(storage.clone(), cache.clone());
+    let (mut second, second_collector) = cached_engine(storage, cache.clone());
+    first.execute("select pg_backend_pid();").expect("no system errors");
+    second.execute("select pg_backend_pid();").expect("no system errors");
+    first
+        .execute("select pg_cancel_backend(10);")
+        .expect("no system errors");
+    second
+        .execute("select pg_cancel_backend(10);")
+        .expect("no system errors");
+
+    assert_eq!(cache.hits(), 0);
+    assert!(cache.is_empty());
+    let pid = |pid: &str| {
+        Ok(QueryEvent::RecordsSelected((
+            vec![("pg_backend_pid".to_owned(), PostgreSqlType::Integer, -1)],
+            vec![vec![text(pid)]],
+        )))
+    };
+    let not_canceled = || {
+        vec![
+            Ok(QueryEvent::Notice {
+                severity: "WARNING".to_owned(),
+                message: "PID 10 is not a PostgreSQL backend process".to_owned(),
+            }),
+            Ok(QueryEvent::RecordsSelected((
+                vec![("pg_cancel_backend".to_owned(), PostgreSqlType::Bool, -1)],
+                vec![vec![text("f")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    };
+    let mut expected = vec![pid("0"), Ok(QueryEvent::QueryComplete)];
+    expected.extend(not_canceled());
+    first_collector.assert_content_for_single_queries(expected);
+    let mut expected = vec![pid("1"), Ok(QueryEvent::QueryComplete)];
+    expected.extend(not_canceled());
+    second_collector.assert_content_for_single_queries(expected);
+}
@@ -0,0 +1,190 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint unique, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3, 'three'), (1, 'one'), (2, 'two');")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(3)),
+    ]
+}
+
+#[rstest::rstest]
+fn unique_column_is_looked_up_by_new_name(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("alter table schema_name.table_name rename column id to key;")
+        .expect("no system errors");
+    engine
+        .execute("select name from schema_name.table_name where key = 2;")
+        .expect("no system errors");
+    // only the matching row is read
+    engine
+        .execute("select seq_tup_read from pg_catalog.pg_stat_user_tables;")
+        .expect("no system errors");
+    engine
+        .execute("cluster schema_name.table_name using table_name_key_key;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableAltered),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("name".to_owned(), PostgreSqlType::VarChar, 14)],
+            vec![vec![text("two")]],
+        ))),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("seq_tup_read".to_owned(), PostgreSqlType::BigInt, -1)],
+            vec![vec![text("1")]],
+        ))),
+        Ok(QueryEvent::TableClustered),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn old_name_is_not_available(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("alter table schema_name.table_name rename column id to key;")
+        .expect("no system errors");
+    engine
+        .execute("select name from schema_name.table_name where id = 2;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableAltered),
+        Err(QueryError::column_does_not_exist(vec!["id".to_owned()])),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn rename_non_existent_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("alter table schema_name.table_name rename column non_existent to key;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![Err(QueryError::column_does_not_exist(vec![
+        "non_existent".to_owned()
+    ]))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn rename_to_existing_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("alter table schema_name.table_name rename column id to name;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![Err(QueryError::column_already_exists(
+        "name".to_owned(),
+        "table_name".to_owned(),
+    ))]);
+    collector.assert_content_for_single_queries_ignoring_complete(expected);
+}
+
+#[rstest::rstest]
+fn foreign_key_follows_renamed_columns(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.customers (id integer primary key);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_name.orders (id integer, customer_id integer);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.customers values (1);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "alter table schema_name.orders add constraint orders_customer_fkey \
+             foreign key (customer_id) references schema_name.customers (id);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.customers rename column id to customer_key;")
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.orders rename column customer_id to customer;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values (1, 1);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.orders values (2, 5);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::TableAltered),
+        Ok(QueryEvent::TableAltered),
+        Ok(QueryEvent::TableAltered),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Err(
+            QueryError::foreign_key_violation("orders".to_owned(), "orders_customer_fkey".to_owned())
+                .with_detail("Key (customer)=(5) is not present in table \"customers\".".to_owned()),
+        ),
+    ]);
+}
+
+#[rstest::rstest]
+fn column_that_generated_column_depends_on(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute(
+            "create table schema_name.items (price smallint, quantity smallint, \
+             total smallint generated always as (price * quantity) stored);",
+        )
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.items rename column price to cost;")
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.items rename column total to amount;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Err(QueryError::feature_not_supported(
+            "renaming of column \"price\" that generated columns depend on".to_owned(),
+        )),
+        Ok(QueryEvent::TableAltered),
+    ]);
+}
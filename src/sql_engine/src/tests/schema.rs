@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use super::*;
+use protocol::sql_types::PostgreSqlType;
 
 #[rstest::rstest]
 fn create_schema(sql_engine: (QueryExecutor, ResultCollector)) {
@@ -127,3 +128,243 @@ fn delete_from_table_in_nonexistent_schema(sql_engine: (QueryExecutor, ResultCol
         Ok(QueryEvent::QueryComplete),
     ]);
 }
+
+#[rstest::rstest(
+    schema_name,
+    case::lower_case("schema_name"),
+    case::upper_case("SCHEMA"),
+    case::starts_with_underscore("_schema"),
+    case::with_digits("schema_1"),
+    case::max_length("s23456789012345678901234567890123456789012345678901234567890123")
+)]
+fn create_schema_with_valid_name(sql_engine: (QueryExecutor, ResultCollector), schema_name: &str) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute(format!("create schema \"{}\";", schema_name).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![Ok(QueryEvent::SchemaCreated), Ok(QueryEvent::QueryComplete)]);
+}
+
+#[rstest::rstest(
+    schema_name,
+    case::parent_directory(".."),
+    case::path_traversal("../etc"),
+    case::path_separator("schema/name"),
+    case::empty(""),
+    case::too_long("s234567890123456789012345678901234567890123456789012345678901234"),
+    case::starts_with_digit("1schema"),
+    case::dash("schema-name"),
+    case::space("schema name"),
+    case::non_ascii("схема")
+)]
+fn create_schema_with_invalid_name(sql_engine: (QueryExecutor, ResultCollector), schema_name: &str) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute(format!("create schema \"{}\";", schema_name).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::invalid_identifier(schema_name.to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn create_table_in_schema_with_invalid_name(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine
+        .execute("create table \"../etc\".table_name (column_test smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::invalid_identifier("../etc".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn same_table_name_in_different_schemas(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("create schema schema_a;").expect("no system errors");
+    engine.execute("create schema schema_b;").expect("no system errors");
+    engine
+        .execute("create table schema_a.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("create table schema_b.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_a.table_name values (1), (2);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_b.table_name values (3);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_a.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_b.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("drop table schema_a.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_a.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_b.table_name;")
+        .expect("no system errors");
+
+    let selected = |values: Vec<&str>| {
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            values.into_iter().map(|value| vec![text(value)]).collect(),
+        )))
+    };
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec!["1", "2"]),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec!["3"]),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableDropped),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_a.table_name".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec!["3"]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[cfg(test)]
+mod rename {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (column_test smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1), (2);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::RecordsInserted(2)),
+        ]
+    }
+
+    fn selected() -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![text("1")], vec![text("2")]],
+        )))
+    }
+
+    #[rstest::rstest]
+    fn tables_are_accessible_under_new_name(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("alter schema schema_name rename to new_schema_name;")
+            .expect("no system errors");
+        engine
+            .execute("select * from new_schema_name.table_name;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::SchemaAltered),
+            selected(),
+            Err(QueryError::schema_does_not_exist("schema_name".to_owned())),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn rename_to_existing_schema(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine.execute("create schema other_schema;").expect("no system errors");
+        engine
+            .execute("alter schema schema_name rename to other_schema;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Err(QueryError::schema_already_exists("other_schema".to_owned())),
+            selected(),
+        ]);
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn rename_non_existent_schema(sql_engine: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine;
+        engine
+            .execute("alter schema non_existent rename to new_schema_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Err(QueryError::schema_does_not_exist("non_existent".to_owned())),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn renamed_schema_survives_restart() {
+        let root_path = tempfile::tempdir().expect("to create temporary folder");
+        let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+        let mut engine = QueryExecutor::new(storage, Collector::new());
+        engine.execute("create schema schema_name;").expect("no system errors");
+        engine
+            .execute("create table schema_name.table_name (column_test smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1), (2);")
+            .expect("no system errors");
+        engine
+            .execute("alter schema schema_name rename to new_schema_name;")
+            .expect("no system errors");
+        drop(engine);
+
+        let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+        let collector = Collector::new();
+        let mut engine = QueryExecutor::new(storage, collector.clone());
+        engine
+            .execute("select * from new_schema_name.table_name;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries_ignoring_complete(vec![
+            selected(),
+            Err(QueryError::schema_does_not_exist("schema_name".to_owned())),
+        ]);
+    }
+}
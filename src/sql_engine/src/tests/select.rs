@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use super::*;
+use data_manager::ColumnDefinition;
 use protocol::sql_types::PostgreSqlType;
+use sql_types::SqlType;
 
 #[rstest::rstest]
 fn select_from_not_existed_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
@@ -67,11 +69,15 @@ fn select_all_from_table_with_multiple_columns(sql_engine_with_schema: (QueryExe
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
-            vec![vec!["123".to_owned(), "456".to_owned(), "789".to_owned()]],
+            vec![vec![
+                Some("123".to_owned()),
+                Some("456".to_owned()),
+                Some("789".to_owned()),
+            ]],
         ))),
         Ok(QueryEvent::QueryComplete),
     ]);
@@ -99,13 +105,13 @@ fn select_not_all_columns(sql_engine_with_schema: (QueryExecutor, ResultCollecto
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["7".to_owned(), "4".to_owned()],
-                vec!["8".to_owned(), "5".to_owned()],
-                vec!["9".to_owned(), "6".to_owned()],
+                vec![Some("7".to_owned()), Some("4".to_owned())],
+                vec![Some("8".to_owned()), Some("5".to_owned())],
+                vec![Some("9".to_owned()), Some("6".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -130,7 +136,8 @@ fn select_non_existing_columns_from_table(sql_engine_with_schema: (QueryExecutor
         Err(QueryError::column_does_not_exist(vec![
             "column_not_in_table1".to_owned(),
             "column_not_in_table2".to_owned(),
-        ])),
+        ])
+        .with_hint("Perhaps you meant to reference the column \"column_in_table\".".to_owned())),
         Ok(QueryEvent::QueryComplete),
     ]);
 }
@@ -170,13 +177,13 @@ fn select_first_and_last_columns_from_table_with_multiple_columns(
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["3".to_owned(), "1".to_owned()],
-                vec!["6".to_owned(), "4".to_owned()],
-                vec!["9".to_owned(), "7".to_owned()],
+                vec![Some("3".to_owned()), Some("1".to_owned())],
+                vec![Some("6".to_owned()), Some("4".to_owned())],
+                vec![Some("9".to_owned()), Some("7".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -219,14 +226,14 @@ fn select_all_columns_reordered_from_table_with_multiple_columns(
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["3".to_owned(), "1".to_owned(), "2".to_owned()],
-                vec!["6".to_owned(), "4".to_owned(), "5".to_owned()],
-                vec!["9".to_owned(), "7".to_owned(), "8".to_owned()],
+                vec![Some("3".to_owned()), Some("1".to_owned()), Some("2".to_owned())],
+                vec![Some("6".to_owned()), Some("4".to_owned()), Some("5".to_owned())],
+                vec![Some("9".to_owned()), Some("7".to_owned()), Some("8".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -266,33 +273,33 @@ fn select_with_column_name_duplication(sql_engine_with_schema: (QueryExecutor, R
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
                 vec![
-                    "3".to_owned(),
-                    "2".to_owned(),
-                    "1".to_owned(),
-                    "3".to_owned(),
-                    "2".to_owned(),
+                    Some("3".to_owned()),
+                    Some("2".to_owned()),
+                    Some("1".to_owned()),
+                    Some("3".to_owned()),
+                    Some("2".to_owned()),
                 ],
                 vec![
-                    "6".to_owned(),
-                    "5".to_owned(),
-                    "4".to_owned(),
-                    "6".to_owned(),
-                    "5".to_owned(),
+                    Some("6".to_owned()),
+                    Some("5".to_owned()),
+                    Some("4".to_owned()),
+                    Some("6".to_owned()),
+                    Some("5".to_owned()),
                 ],
                 vec![
-                    "9".to_owned(),
-                    "8".to_owned(),
-                    "7".to_owned(),
-                    "9".to_owned(),
-                    "8".to_owned(),
+                    Some("9".to_owned()),
+                    Some("8".to_owned()),
+                    Some("7".to_owned()),
+                    Some("9".to_owned()),
+                    Some("8".to_owned()),
                 ],
             ],
         ))),
@@ -335,14 +342,26 @@ fn select_different_integer_types(sql_engine_with_schema: (QueryExecutor, Result
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_si".to_owned(), PostgreSqlType::SmallInt),
-                ("column_i".to_owned(), PostgreSqlType::Integer),
-                ("column_bi".to_owned(), PostgreSqlType::BigInt),
+                ("column_si".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_i".to_owned(), PostgreSqlType::Integer, -1),
+                ("column_bi".to_owned(), PostgreSqlType::BigInt, -1),
             ],
             vec![
-                vec!["1000".to_owned(), "2000000".to_owned(), "3000000000".to_owned()],
-                vec!["4000".to_owned(), "5000000".to_owned(), "6000000000".to_owned()],
-                vec!["7000".to_owned(), "8000000".to_owned(), "9000000000".to_owned()],
+                vec![
+                    Some("1000".to_owned()),
+                    Some("2000000".to_owned()),
+                    Some("3000000000".to_owned()),
+                ],
+                vec![
+                    Some("4000".to_owned()),
+                    Some("5000000".to_owned()),
+                    Some("6000000000".to_owned()),
+                ],
+                vec![
+                    Some("7000".to_owned()),
+                    Some("8000000".to_owned()),
+                    Some("9000000000".to_owned()),
+                ],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -384,15 +403,1381 @@ fn select_different_character_strings_types(sql_engine_with_schema: (QueryExecut
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("char_10".to_owned(), PostgreSqlType::Char),
-                ("var_char_20".to_owned(), PostgreSqlType::VarChar),
+                ("char_10".to_owned(), PostgreSqlType::Char, 14),
+                ("var_char_20".to_owned(), PostgreSqlType::VarChar, 24),
+            ],
+            vec![
+                vec![Some("1234567890".to_owned()), Some("12345678901234567890".to_owned())],
+                vec![Some("12345".to_owned()), Some("1234567890".to_owned())],
+                vec![Some("12345".to_owned()), Some("1234567890".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn type_modifiers_of_selected_columns(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+
+    engine
+        .execute("create table schema_name.table_name (char_5 char(5), var_char_10 varchar(10), number integer);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "select char_5, t.var_char_10 as renamed, var_char_10 || 'a', number from schema_name.table_name as t;",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("char_5".to_owned(), PostgreSqlType::Char, 9),
+                ("renamed".to_owned(), PostgreSqlType::VarChar, 14),
+                ("?column?".to_owned(), PostgreSqlType::VarChar, -1),
+                ("number".to_owned(), PostgreSqlType::Integer, -1),
+            ],
+            vec![],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[test]
+fn columns_in_ordinal_position_order() {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage.clone(), collector.clone());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    let schema_id = storage.schema_exists("schema_name").expect("schema exists");
+    storage
+        .create_table(
+            schema_id,
+            "table_name",
+            &[
+                ColumnDefinition::new("column_3", SqlType::SmallInt(i16::MIN)).with_ordinal_position(3),
+                ColumnDefinition::new("column_1", SqlType::SmallInt(i16::MIN)).with_ordinal_position(1),
+                ColumnDefinition::new("column_2", SqlType::SmallInt(i16::MIN)).with_ordinal_position(2),
             ],
+        )
+        .expect("table is created");
+    engine
+        .execute("insert into schema_name.table_name values (1, 2, 3);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
             vec![
-                vec!["1234567890".to_owned(), "12345678901234567890".to_owned()],
-                vec!["12345".to_owned(), "1234567890".to_owned()],
-                vec!["12345".to_owned(), "1234567890".to_owned()],
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
+            vec![vec![Some("1".to_owned()), Some("2".to_owned()), Some("3".to_owned())]],
         ))),
         Ok(QueryEvent::QueryComplete),
     ]);
 }
+
+#[rstest::rstest]
+fn select_all_in_declaration_order(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (z_column smallint, a_column smallint, m_column smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name (m_column, a_column, z_column) values (3, 2, 1);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("z_column".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("a_column".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("m_column".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![vec![Some("1".to_owned()), Some("2".to_owned()), Some("3".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[cfg(test)]
+mod limit {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (column_1 smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1), (2), (3), (4), (5);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(5)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    fn selected(values: Vec<&str>) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_1".to_owned(), PostgreSqlType::SmallInt, -1)],
+            values.into_iter().map(|value| vec![text(value)]).collect(),
+        )))
+    }
+
+    #[rstest::rstest]
+    fn limit_and_offset(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select * from schema_name.table_name limit 2 offset 1;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![selected(vec!["2", "3"]), Ok(QueryEvent::QueryComplete)]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn zero_limit(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select * from schema_name.table_name limit 0;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![selected(vec![]), Ok(QueryEvent::QueryComplete)]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn limit_expression(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select * from schema_name.table_name limit 1 + 2;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name limit null;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            selected(vec!["1", "2", "3"]),
+            Ok(QueryEvent::QueryComplete),
+            selected(vec!["1", "2", "3", "4", "5"]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn negative_limit(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select * from schema_name.table_name limit -1;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name offset 2 - 3;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Err(QueryError::invalid_row_count_in_limit_clause()),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::invalid_row_count_in_result_offset_clause()),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn non_integer_limit(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select * from schema_name.table_name limit 2.5;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name limit 'abc';")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Err(QueryError::non_integer_row_count("LIMIT".to_owned(), "2.5".to_owned())),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::non_integer_row_count(
+                "LIMIT".to_owned(),
+                "'abc'".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod statement_max_rows {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (column_1 smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1), (2), (3), (4), (5);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(5)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    fn selected(values: Vec<&str>) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_1".to_owned(), PostgreSqlType::SmallInt, -1)],
+            values.into_iter().map(|value| vec![text(value)]).collect(),
+        )))
+    }
+
+    #[rstest::rstest]
+    fn query_exceeds_limit(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine.execute("set statement_max_rows = 3;").expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name limit 3;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::program_limit_exceeded(
+                "query returns more than 3 rows allowed by statement_max_rows".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+            selected(vec!["1", "2", "3"]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn rows_of_subqueries_are_not_limited(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine.execute("set statement_max_rows = 1;").expect("no system errors");
+        engine
+            .execute("select count(*) from (select * from schema_name.table_name) as t;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsSelected((
+                vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+                vec![vec![Some("5".to_owned())]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest(value, case::zero("0"), case::default("default"))]
+    fn limit_is_removed(with_table: (QueryExecutor, ResultCollector), value: &str) {
+        let (mut engine, collector) = with_table;
+        engine.execute("set statement_max_rows = 1;").expect("no system errors");
+        engine
+            .execute(format!("set statement_max_rows = {};", value).as_str())
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            selected(vec!["1", "2", "3", "4", "5"]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn invalid_value(sql_engine: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine;
+        engine
+            .execute("set statement_max_rows = 'many';")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Err(QueryError::invalid_parameter_value(
+                "invalid value for parameter \"statement_max_rows\": \"'many'\"".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod statement_timeout {
+    use super::*;
+    use std::time::Duration;
+
+    const LONG_QUERY: &str = "select count(*) from generate_series(1, 2000) as a, generate_series(1, 2000) as b;";
+
+    fn counted(count: &str) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![("count".to_owned(), PostgreSqlType::BigInt, -1)],
+            vec![vec![text(count)]],
+        )))
+    }
+
+    #[rstest::rstest]
+    fn query_is_canceled(sql_engine: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine;
+        engine.execute("set statement_timeout = 1;").expect("no system errors");
+        engine.execute(LONG_QUERY).expect("no system errors");
+        engine
+            .execute("select count(*) from generate_series(1, 3);")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::query_timeout()),
+            Ok(QueryEvent::QueryComplete),
+            counted("3"),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest(value, case::seconds("'5s'"), case::minutes("'1 min'"), case::hours("'2h'"))]
+    fn timeout_with_units(sql_engine: (QueryExecutor, ResultCollector), value: &str) {
+        let (mut engine, collector) = sql_engine;
+        engine
+            .execute(format!("set statement_timeout = {};", value).as_str())
+            .expect("no system errors");
+        engine
+            .execute("select count(*) from generate_series(1, 3);")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            counted("3"),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest(value, case::zero("0"), case::default("default"))]
+    fn timeout_is_removed(sql_engine: (QueryExecutor, ResultCollector), value: &str) {
+        let (mut engine, collector) = sql_engine;
+        engine.execute("set statement_timeout = 1;").expect("no system errors");
+        engine
+            .execute(format!("set statement_timeout = {};", value).as_str())
+            .expect("no system errors");
+        engine
+            .execute("select count(*) from generate_series(1, 200) as a, generate_series(1, 200) as b;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            counted("40000"),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::fixture]
+    fn with_large_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (column_i integer);")
+            .expect("no system errors");
+        let values = (1..=300).map(|value| format!("({})", value)).collect::<Vec<_>>();
+        engine
+            .execute(format!("insert into schema_name.table_name values {};", values.join(", ")).as_str())
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn large_table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(300)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    const LARGE_TABLE_SCAN: &str =
+        "select count(*) from schema_name.table_name as a, schema_name.table_name as b where a.column_i = b.column_i;";
+
+    #[rstest::rstest]
+    fn connection_timeout_cancels_scan(with_large_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_large_table;
+        engine.set_query_timeout(Duration::from_millis(1));
+        engine.execute(LARGE_TABLE_SCAN).expect("no system errors");
+
+        let mut expected = large_table_created();
+        expected.extend(vec![Err(QueryError::query_timeout()), Ok(QueryEvent::QueryComplete)]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn query_completes_within_connection_timeout(with_large_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_large_table;
+        engine.set_query_timeout(Duration::from_secs(60));
+        engine
+            .execute("select count(*) from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = large_table_created();
+        expected.extend(vec![counted("300"), Ok(QueryEvent::QueryComplete)]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn session_overrides_connection_timeout(with_large_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_large_table;
+        engine.set_query_timeout(Duration::from_millis(1));
+        engine
+            .execute("set statement_timeout = '5s';")
+            .expect("no system errors");
+        engine.execute(LARGE_TABLE_SCAN).expect("no system errors");
+        engine
+            .execute("set statement_timeout to default;")
+            .expect("no system errors");
+        engine.execute(LARGE_TABLE_SCAN).expect("no system errors");
+
+        let mut expected = large_table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            counted("300"),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::VariableSet),
+            Ok(QueryEvent::QueryComplete),
+            Err(QueryError::query_timeout()),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn zero_connection_timeout_is_unlimited(with_large_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_large_table;
+        engine.set_query_timeout(Duration::from_millis(0));
+        engine.execute(LARGE_TABLE_SCAN).expect("no system errors");
+
+        let mut expected = large_table_created();
+        expected.extend(vec![counted("300"), Ok(QueryEvent::QueryComplete)]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest(
+        value,
+        case::unknown_unit("'5 years'"),
+        case::negative("'-5s'"),
+        case::not_a_number("fast")
+    )]
+    fn invalid_value(sql_engine: (QueryExecutor, ResultCollector), value: &str) {
+        let (mut engine, collector) = sql_engine;
+        engine
+            .execute(format!("set statement_timeout = {};", value).as_str())
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Err(QueryError::invalid_parameter_value(format!(
+                "invalid value for parameter \"statement_timeout\": \"{}\"",
+                value
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod order_by {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute(
+                "create table schema_name.table_name (column_1 smallint, column_2 varchar(10), column_3 smallint);",
+            )
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (2, 'b', 1), (3, 'a', 2), (1, 'c', 1);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(3)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    fn selected(columns: Vec<(&str, PostgreSqlType, i32)>, rows: Vec<Vec<&str>>) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            columns
+                .into_iter()
+                .map(|(name, sql_type, type_modifier)| (name.to_owned(), sql_type, type_modifier))
+                .collect(),
+            rows.into_iter()
+                .map(|row| row.into_iter().map(text).collect())
+                .collect(),
+        )))
+    }
+
+    fn two_columns(rows: Vec<Vec<&str>>) -> QueryResult {
+        selected(
+            vec![
+                ("column_1", PostgreSqlType::SmallInt, -1),
+                ("column_2", PostgreSqlType::VarChar, 14),
+            ],
+            rows,
+        )
+    }
+
+    #[rstest::rstest(
+        query,
+        rows,
+        case::position("select column_1, column_2 from schema_name.table_name order by 1;", vec![vec!["1", "c"], vec!["2", "b"], vec!["3", "a"]]),
+        case::second_position("select column_1, column_2 from schema_name.table_name order by 2;", vec![vec!["3", "a"], vec!["2", "b"], vec!["1", "c"]]),
+        case::descending_position("select column_1, column_2 from schema_name.table_name order by 1 desc;", vec![vec!["3", "a"], vec!["2", "b"], vec!["1", "c"]]),
+        case::column_name("select column_1, column_2 from schema_name.table_name order by column_2;", vec![vec!["3", "a"], vec!["2", "b"], vec!["1", "c"]]),
+        case::column_not_in_select_list("select column_1, column_2 from schema_name.table_name order by column_3, column_1 desc;", vec![vec!["2", "b"], vec!["1", "c"], vec!["3", "a"]]),
+        case::position_and_name("select column_1, column_2 from schema_name.table_name order by column_3 desc, 2;", vec![vec!["3", "a"], vec!["2", "b"], vec!["1", "c"]]),
+        case::expression("select column_1, column_2 from schema_name.table_name order by column_1 * -1;", vec![vec!["3", "a"], vec!["2", "b"], vec!["1", "c"]]),
+        case::wildcard("select column_1, column_2 from (select * from schema_name.table_name order by 3, 1) as t;", vec![vec!["1", "c"], vec!["2", "b"], vec!["3", "a"]]),
+        case::with_limit("select column_1, column_2 from schema_name.table_name order by 1 limit 2 offset 1;", vec![vec!["2", "b"], vec!["3", "a"]]),
+    )]
+    fn sorted(with_table: (QueryExecutor, ResultCollector), query: &str, rows: Vec<Vec<&str>>) {
+        let (mut engine, collector) = with_table;
+        engine.execute(query).expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![two_columns(rows), Ok(QueryEvent::QueryComplete)]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn alias(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select column_2 as name from schema_name.table_name order by name desc;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            selected(
+                vec![("name", PostgreSqlType::VarChar, 14)],
+                vec![vec!["c"], vec!["b"], vec!["a"]],
+            ),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn aggregation(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select column_3, count(*) from schema_name.table_name group by column_3 order by 2 desc;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            selected(
+                vec![
+                    ("column_3", PostgreSqlType::SmallInt, -1),
+                    ("count", PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec!["1", "2"], vec!["2", "1"]],
+            ),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest(position, case::zero("0"), case::out_of_range("5"))]
+    fn position_is_not_in_select_list(with_table: (QueryExecutor, ResultCollector), position: &str) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                format!(
+                    "select column_1, column_2, column_3 from schema_name.table_name order by {};",
+                    position
+                )
+                .as_str(),
+            )
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Err(QueryError::invalid_column_reference(format!(
+                "ORDER BY position {} is not in select list",
+                position
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn expression_of_not_selected_columns(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select column_1 from schema_name.table_name order by column_3 * -1, column_2;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            selected(
+                vec![("column_1", PostgreSqlType::SmallInt, -1)],
+                vec![vec!["3"], vec!["2"], vec!["1"]],
+            ),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest(sort_key, case::column("column_4"), case::expression("column_1 * column_4"))]
+    fn non_existent_column(with_table: (QueryExecutor, ResultCollector), sort_key: &str) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(format!("select column_1 from schema_name.table_name order by {};", sort_key).as_str())
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Err(
+                QueryError::column_does_not_exist(vec!["column_4".to_owned()]).with_hint(
+                    "Perhaps you meant to reference one of the columns \"column_1\", \"column_2\", \"column_3\"."
+                        .to_owned(),
+                ),
+            ),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod quantified_comparison {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (column_1 smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1), (2), (3), (4), (5);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(5)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    fn selected(values: Vec<&str>) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_1".to_owned(), PostgreSqlType::SmallInt, -1)],
+            values.into_iter().map(|value| vec![text(value)]).collect(),
+        )))
+    }
+
+    #[rstest::rstest(
+        predicate,
+        values,
+        case::equal_to_any("column_1 = any(array[1, 3, 7])", vec!["1", "3"]),
+        case::some("column_1 = some(array[2])", vec!["2"]),
+        case::greater_than_all("column_1 > all(array[1, 3])", vec!["4", "5"]),
+        case::not_equal_to_all("column_1 <> all(array[2, 4])", vec!["1", "3", "5"]),
+        case::expressions("column_1 = any(array[column_1 + 1, 6 - column_1])", vec!["3"]),
+        case::any_of_empty_array("column_1 = any(array[])", vec![]),
+        case::all_of_empty_array("column_1 = all(array[])", vec!["1", "2", "3", "4", "5"]),
+        case::any_with_null("column_1 = any(array[null, 1])", vec!["1"]),
+        case::all_with_null("column_1 < all(array[null, 3])", vec![])
+    )]
+    fn filtered(with_table: (QueryExecutor, ResultCollector), predicate: &str, values: Vec<&str>) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(format!("select * from schema_name.table_name where {};", predicate).as_str())
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![selected(values), Ok(QueryEvent::QueryComplete)]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn type_mismatch(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select * from schema_name.table_name where column_1 = any(array[1, 'two']);")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Err(QueryError::undefined_function(
+                "=".to_owned(),
+                "NUMBER".to_owned(),
+                "STRING".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[rstest::rstest(
+    predicate,
+    values,
+    case::equal("column_1 = -5", vec!["-5"]),
+    case::greater_than("column_1 > -3", vec!["-2", "1"]),
+    case::negated_column("-column_1 = 2", vec!["-2"])
+)]
+fn where_with_negative_literals(
+    sql_engine_with_schema: (QueryExecutor, ResultCollector),
+    predicate: &str,
+    values: Vec<&str>,
+) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (-5), (-2), (1);")
+        .expect("no system errors");
+    engine
+        .execute(format!("select * from schema_name.table_name where {};", predicate).as_str())
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_1".to_owned(), PostgreSqlType::SmallInt, -1)],
+            values.into_iter().map(|value| vec![text(value)]).collect(),
+        ))),
+    ]);
+}
+
+#[cfg(test)]
+mod grouped_conditions {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (a smallint, b smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (1, 3), (1, 4), (2, 3), (2, 4), (3, 3);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(5)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    fn selected(rows: Vec<(&str, &str)>) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("a".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("b".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            rows.into_iter().map(|(a, b)| vec![text(a), text(b)]).collect(),
+        )))
+    }
+
+    #[rstest::rstest(
+        predicate,
+        rows,
+        case::parenthesized_or("(a = 1 or a = 2) and b = 3", vec![("1", "3"), ("2", "3")]),
+        case::default_precedence("a = 1 or a = 2 and b = 3", vec![("1", "3"), ("1", "4"), ("2", "3")]),
+        case::parenthesized_and("a = 1 or (a = 2 and b = 3)", vec![("1", "3"), ("1", "4"), ("2", "3")]),
+        case::nested_groups("((a = 1) or (a = 3)) and (b = 3)", vec![("1", "3"), ("3", "3")]),
+        case::negated_group("not (a = 1 or b = 3)", vec![("2", "4")])
+    )]
+    fn filtered(with_table: (QueryExecutor, ResultCollector), predicate: &str, rows: Vec<(&str, &str)>) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(format!("select * from schema_name.table_name where {};", predicate).as_str())
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![selected(rows), Ok(QueryEvent::QueryComplete)]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn parentheses_change_result(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select * from schema_name.table_name where (a = 1 or a = 2) and b = 4;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.table_name where a = 1 or a = 2 and b = 4;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            selected(vec![("1", "4"), ("2", "4")]),
+            Ok(QueryEvent::QueryComplete),
+            selected(vec![("1", "3"), ("1", "4"), ("2", "4")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod without_from {
+    use super::*;
+
+    #[rstest::rstest]
+    fn arithmetic(sql_engine: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine;
+        engine.execute("select 1 + 1;").expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![("?column?".to_owned(), PostgreSqlType::Integer, -1)],
+                vec![vec![Some("2".to_owned())]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn functions(sql_engine: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine;
+        engine
+            .execute("select upper('abc'), lower('ABC') as name, 1;")
+            .expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("upper".to_owned(), PostgreSqlType::VarChar, -1),
+                    ("name".to_owned(), PostgreSqlType::VarChar, -1),
+                    ("?column?".to_owned(), PostgreSqlType::Integer, -1),
+                ],
+                vec![vec![
+                    Some("ABC".to_owned()),
+                    Some("abc".to_owned()),
+                    Some("1".to_owned()),
+                ]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn column_reference(sql_engine: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine;
+        engine.execute("select col;").expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Err(QueryError::column_does_not_exist(vec!["col".to_owned()])),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+
+    #[rstest::rstest]
+    fn function_of_number(sql_engine: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine;
+        engine.execute("select upper(1);").expect("no system errors");
+
+        collector.assert_content_for_single_queries(vec![
+            Err(QueryError::undefined_function(
+                "upper".to_owned(),
+                "".to_owned(),
+                "NUMBER".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod numeric_comparison {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (column_si smallint, column_vc varchar(10));")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.table_name values (9, '9'), (10, '10'), (100, '100'), (2, '2');")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(4)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    #[rstest::rstest(
+        column,
+        predicate,
+        values,
+        case::integers_are_compared_as_numbers("column_si", "column_si > 9", vec!["10", "100"]),
+        case::literal_on_the_left("column_si", "10 > column_si", vec!["9", "2"]),
+        case::strings_are_compared_as_text("column_vc", "column_vc > '9'", vec![]),
+        case::strings_less_than("column_vc", "column_vc < '2'", vec!["10", "100"])
+    )]
+    fn filtered(with_table: (QueryExecutor, ResultCollector), column: &str, predicate: &str, values: Vec<&str>) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(format!("select {} from schema_name.table_name where {};", column, predicate).as_str())
+            .expect("no system errors");
+
+        let (sql_type, type_modifier) = if column == "column_si" {
+            (PostgreSqlType::SmallInt, -1)
+        } else {
+            (PostgreSqlType::VarChar, 14)
+        };
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![(column.to_owned(), sql_type, type_modifier)],
+                values.into_iter().map(|value| vec![text(value)]).collect(),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn join_condition(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "select l.column_si, r.column_si from schema_name.table_name as l \
+                 join schema_name.table_name as r on l.column_si > r.column_si where r.column_si = 9;",
+            )
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("column_si".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("column_si".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                vec![vec![text("10"), text("9")], vec![text("100"), text("9")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod distinct_predicate {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (a smallint, b smallint);")
+            .expect("no system errors");
+        engine
+            .execute("copy schema_name.table_name from stdin;")
+            .expect("no system errors");
+        engine.copy_data(b"1\t1\n1\t2\n1\t\\N\n\\N\t1\n\\N\t\\N\n");
+        engine.copy_done().expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::CopyInStarted(2)),
+            Ok(QueryEvent::RecordsInserted(5)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    #[rstest::rstest(
+        predicate,
+        rows,
+        case::distinct("a is distinct from b", vec![("1", "2"), ("1", "NULL"), ("NULL", "1")]),
+        case::not_distinct("a is not distinct from b", vec![("1", "1"), ("NULL", "NULL")]),
+        case::from_expression("a + 1 is distinct from b + 1 and a = 1", vec![("1", "2"), ("1", "NULL")]),
+        case::negated("not (a is distinct from b)", vec![("1", "1"), ("NULL", "NULL")])
+    )]
+    fn filtered(with_table: (QueryExecutor, ResultCollector), predicate: &str, rows: Vec<(&str, &str)>) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(format!("select * from schema_name.table_name where {};", predicate).as_str())
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("a".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("b".to_owned(), PostgreSqlType::SmallInt, -1),
+                ],
+                rows.into_iter().map(|(a, b)| vec![text(a), text(b)]).collect(),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn never_null(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select a is distinct from b, a is not distinct from b from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("?column?".to_owned(), PostgreSqlType::Bool, -1),
+                    ("?column?".to_owned(), PostgreSqlType::Bool, -1),
+                ],
+                vec![
+                    vec![text("f"), text("t")],
+                    vec![text("t"), text("f")],
+                    vec![text("t"), text("f")],
+                    vec![text("t"), text("f")],
+                    vec![text("f"), text("t")],
+                ],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn type_mismatch(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select * from schema_name.table_name where a is distinct from 'one';")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Err(QueryError::undefined_function(
+                "<>".to_owned(),
+                "NUMBER".to_owned(),
+                "STRING".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod mixed_integer_widths {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute(
+                "create table schema_name.table_name \
+                 (column_si smallint, column_i integer, column_bi bigint, column_c char(5));",
+            )
+            .expect("no system errors");
+        engine
+            .execute(
+                "insert into schema_name.table_name values \
+                 (1, 1, 1, '1'), (2, 3, 2, '2'), (5, 4, 10, '5'), (-7, -7, 5000000000, '-7');",
+            )
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(4)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    #[rstest::rstest(
+        predicate,
+        values,
+        case::smallint_equals_integer("column_si = column_i", vec!["1", "-7"]),
+        case::integer_equals_smallint("column_i = column_si", vec!["1", "-7"]),
+        case::smallint_less_than_integer("column_si < column_i", vec!["2"]),
+        case::integer_equals_bigint("column_i = column_bi", vec!["1"]),
+        case::bigint_greater_than_integer("column_bi > column_i", vec!["5", "-7"]),
+        case::bigint_out_of_integer_range("column_bi > 2147483647", vec!["-7"]),
+        case::smallint_not_equal_to_bigint("column_si <> column_bi", vec!["5", "-7"])
+    )]
+    fn compared_as_numbers(with_table: (QueryExecutor, ResultCollector), predicate: &str, values: Vec<&str>) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(format!("select column_si from schema_name.table_name where {};", predicate).as_str())
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                values.into_iter().map(|value| vec![text(value)]).collect(),
+            ))),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn join_condition(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute(
+                "select l.column_si, r.column_bi from schema_name.table_name as l \
+                 join schema_name.table_name as r on l.column_i = r.column_bi;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("delete from schema_name.table_name where column_bi = column_si;")
+            .expect("no system errors");
+        engine
+            .execute("update schema_name.table_name set column_si = 0 where column_i < column_bi;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsSelected((
+                vec![
+                    ("column_si".to_owned(), PostgreSqlType::SmallInt, -1),
+                    ("column_bi".to_owned(), PostgreSqlType::BigInt, -1),
+                ],
+                vec![vec![text("1"), text("1")]],
+            ))),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsDeleted(2)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsUpdated(2)),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn integer_and_char_columns(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select column_si from schema_name.table_name where column_i = column_c;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.extend(vec![
+            Err(QueryError::undefined_function(
+                "=".to_owned(),
+                "NUMBER".to_owned(),
+                "STRING".to_owned(),
+            )),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
+
+#[cfg(test)]
+mod column_suggestions {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (id smallint, name varchar(10), amount smallint);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn table_created() -> Vec<QueryResult> {
+        vec![Ok(QueryEvent::SchemaCreated), Ok(QueryEvent::TableCreated)]
+    }
+
+    #[rstest::rstest(
+        query,
+        missing,
+        hint,
+        case::projection("select nmae from schema_name.table_name;", "nmae", "name"),
+        case::predicate("select id from schema_name.table_name where amout > 1;", "amout", "amount"),
+        case::qualified("select t.naem from schema_name.table_name as t;", "t.naem", "name"),
+        case::order_by("select id from schema_name.table_name order by ids;", "ids", "id")
+    )]
+    fn closest_column_is_suggested(
+        with_table: (QueryExecutor, ResultCollector),
+        query: &str,
+        missing: &str,
+        hint: &str,
+    ) {
+        let (mut engine, collector) = with_table;
+        engine.execute(query).expect("no system errors");
+
+        let mut expected = table_created();
+        expected.push(Err(QueryError::column_does_not_exist(vec![missing.to_owned()])
+            .with_hint(format!("Perhaps you meant to reference the column \"{}\".", hint))));
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn equally_close_columns_are_suggested(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.table_name (value_1 smallint, value_2 smallint);")
+            .expect("no system errors");
+        engine
+            .execute("select value_3 from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.push(Err(QueryError::column_does_not_exist(vec!["value_3".to_owned()])
+            .with_hint(
+                "Perhaps you meant to reference one of the columns \"value_1\", \"value_2\".".to_owned(),
+            )));
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn suggestions_for_several_columns(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select nam, amont from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.push(Err(QueryError::column_does_not_exist(vec![
+            "nam".to_owned(),
+            "amont".to_owned(),
+        ])
+        .with_hint(
+            "Perhaps you meant to reference one of the columns \"name\", \"amount\".".to_owned(),
+        )));
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+
+    #[rstest::rstest]
+    fn no_suggestion_for_unrelated_name(with_table: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_table;
+        engine
+            .execute("select price from schema_name.table_name;")
+            .expect("no system errors");
+
+        let mut expected = table_created();
+        expected.push(Err(QueryError::column_does_not_exist(vec!["price".to_owned()])));
+        collector.assert_content_for_single_queries_ignoring_complete(expected);
+    }
+}
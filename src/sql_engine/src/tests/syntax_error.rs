@@ -0,0 +1,47 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[rstest::rstest(
+    query,
+    case::misspelled_keyword("selec 1;"),
+    case::missing_table_name("select * from;"),
+    case::unbalanced_parentheses("select (1 + 2;")
+)]
+fn malformed_query(sql_engine: (QueryExecutor, ResultCollector), query: &str) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute(query).expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::syntax_error(format!("{:?} can't be parsed", query))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn query_after_malformed_one(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.execute("create schema;").expect("no system errors");
+    engine.execute("create schema schema_name;").expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::syntax_error(
+            "\"create schema;\" can't be parsed".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -82,6 +82,114 @@ fn create_same_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
     ]);
 }
 
+#[rstest::rstest]
+fn column_with_default(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (v smallint, w smallint default 7);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, default), (2, 3);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name (v) values (4);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set w = default where v = 2;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("w".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![
+                vec![Some("1".to_owned()), Some("7".to_owned())],
+                vec![Some("2".to_owned()), Some("7".to_owned())],
+                vec![Some("4".to_owned()), Some("7".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn column_reference_in_default(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (v smallint, w smallint default v);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::feature_not_supported(
+            "column reference in DEFAULT expression of column \"w\"".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn not_null_column(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (v smallint, w smallint not null);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 2);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (3, null);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name (v) values (4);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set w = default;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::not_null_violation("w".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::not_null_violation("w".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::not_null_violation("w".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("w".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![vec![Some("1".to_owned()), Some("2".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
 #[rstest::rstest]
 fn drop_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
     let (mut engine, collector) = sql_engine_with_schema;
@@ -0,0 +1,154 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+type Session = (QueryExecutor, ResultCollector);
+
+#[rstest::fixture]
+fn storage() -> Arc<DataManager> {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    storage.create_schema("schema_name").expect("schema is created");
+    storage
+}
+
+fn session(storage: &Arc<DataManager>) -> Session {
+    let collector = Collector::new();
+    (QueryExecutor::new(storage.clone(), collector.clone()), collector)
+}
+
+fn selected(values: Vec<&str>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+        values.into_iter().map(|value| vec![text(value)]).collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn create_insert_and_select(storage: Arc<DataManager>) {
+    let (mut engine, collector) = session(&storage);
+    engine
+        .execute("create temp table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec!["1"]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn create_twice(storage: Arc<DataManager>) {
+    let (mut engine, collector) = session(&storage);
+    engine
+        .execute("create temporary table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("create temporary table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_already_exists("schema_name.table_name".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn not_visible_from_other_session(storage: Arc<DataManager>) {
+    let (mut engine, collector) = session(&storage);
+    let (mut other_engine, other_collector) = session(&storage);
+    engine
+        .execute("create temp table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    other_engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![Ok(QueryEvent::TableCreated), Ok(QueryEvent::QueryComplete)]);
+    other_collector.assert_content_for_single_queries(vec![
+        Err(QueryError::table_does_not_exist("schema_name.table_name".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn shadows_permanent_table(storage: Arc<DataManager>) {
+    let (mut engine, collector) = session(&storage);
+    let (mut other_engine, other_collector) = session(&storage);
+    engine
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    engine
+        .execute("create temp table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (2);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    other_engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        selected(vec!["2"]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    other_collector.assert_content_for_single_queries(vec![selected(vec!["1"]), Ok(QueryEvent::QueryComplete)]);
+}
+
+#[rstest::rstest]
+fn dropped_when_session_ends(storage: Arc<DataManager>) {
+    let (mut engine, _collector) = session(&storage);
+    engine
+        .execute("create temp table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    drop(engine);
+
+    let (mut engine, collector) = session(&storage);
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Err(QueryError::table_does_not_exist("schema_name.table_name".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    assert_eq!(storage.schema_exists("pg_temp_0"), None);
+}
@@ -0,0 +1,170 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::{
+    results::{QueryEvent, QueryResult},
+    Sender,
+};
+use std::{
+    io,
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
+
+/// collects everything that query engine sends to a client
+pub(crate) struct Collector(Mutex<Vec<QueryResult>>);
+
+impl Sender for Collector {
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn send(&self, query_result: QueryResult) -> io::Result<()> {
+        self.0.lock().expect("locked").push(query_result);
+        Ok(())
+    }
+}
+
+impl Collector {
+    pub(crate) fn new() -> ResultCollector {
+        Arc::new(Collector(Mutex::new(vec![])))
+    }
+
+    pub(crate) fn assert_content(&self, expected: Vec<QueryResult>) {
+        let result = self.0.lock().expect("locked");
+        assert_eq!(result.deref(), &expected)
+    }
+
+    pub(crate) fn assert_content_for_single_queries(&self, expected: Vec<QueryResult>) {
+        let actual = self.0.lock().expect("locked");
+        assert_eq!(actual.deref(), &expected)
+    }
+
+    /// checks that the same events were sent, each of them the same number of times,
+    /// regardless of the order they were sent in
+    ///
+    /// ```
+    /// collector.assert_content_unordered(vec![
+    ///     Ok(QueryEvent::QueryComplete),
+    ///     Ok(QueryEvent::RecordsInserted(1)),
+    /// ]);
+    /// ```
+    pub(crate) fn assert_content_unordered(&self, expected: Vec<QueryResult>) {
+        let actual = self.0.lock().expect("locked");
+        let mut unmatched = actual.clone();
+        let mut missing = vec![];
+        for event in expected {
+            match unmatched.iter().position(|sent| sent == &event) {
+                Some(index) => {
+                    let _matched = unmatched.remove(index);
+                }
+                None => missing.push(event),
+            }
+        }
+        assert!(
+            missing.is_empty() && unmatched.is_empty(),
+            "sent events {:#?} don't match, missing: {:#?}, unexpected: {:#?}",
+            actual.deref(),
+            missing,
+            unmatched
+        )
+    }
+
+    /// the same as `assert_content_for_single_queries` but without `QueryEvent::QueryComplete` events
+    ///
+    /// ```
+    /// collector.assert_content_for_single_queries_ignoring_complete(vec![
+    ///     Ok(QueryEvent::SchemaCreated),
+    ///     Ok(QueryEvent::TableCreated),
+    /// ]);
+    /// ```
+    pub(crate) fn assert_content_for_single_queries_ignoring_complete(&self, expected: Vec<QueryResult>) {
+        let actual: Vec<QueryResult> = self
+            .0
+            .lock()
+            .expect("locked")
+            .iter()
+            .filter(|event| !matches!(event, Ok(QueryEvent::QueryComplete)))
+            .cloned()
+            .collect();
+        assert_eq!(actual, expected)
+    }
+
+    /// events that were sent so far, for checks that can't compare them as a whole
+    pub(crate) fn content(&self) -> Vec<QueryResult> {
+        self.0.lock().expect("locked").clone()
+    }
+}
+
+pub(crate) type ResultCollector = Arc<Collector>;
+
+/// text of a selected value, `"NULL"` stands for NULL value
+pub(crate) fn text(value: &str) -> Option<String> {
+    match value {
+        "NULL" => None,
+        value => Some(value.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::results::QueryError;
+
+    fn collector_with(events: Vec<QueryResult>) -> ResultCollector {
+        let collector = Collector::new();
+        for event in events {
+            collector.send(event).expect("sent");
+        }
+        collector
+    }
+
+    #[test]
+    fn unordered_events_match() {
+        let collector = collector_with(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Err(QueryError::schema_does_not_exist("schema_name".to_owned())),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+
+        collector.assert_content_unordered(vec![
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(1)),
+            Err(QueryError::schema_does_not_exist("schema_name".to_owned())),
+        ]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn unordered_events_are_counted() {
+        let collector = collector_with(vec![Ok(QueryEvent::QueryComplete), Ok(QueryEvent::QueryComplete)]);
+
+        collector.assert_content_unordered(vec![Ok(QueryEvent::QueryComplete)]);
+    }
+
+    #[test]
+    fn query_complete_events_are_ignored() {
+        let collector = collector_with(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+
+        collector.assert_content_for_single_queries_ignoring_complete(vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::TableCreated),
+        ]);
+    }
+}
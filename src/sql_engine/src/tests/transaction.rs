@@ -0,0 +1,113 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+type Session = (QueryExecutor, ResultCollector);
+
+#[rstest::fixture]
+fn two_sessions() -> (Session, Session) {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let first_collector = Collector::new();
+    let mut first = QueryExecutor::new(storage.clone(), first_collector.clone());
+    first.execute("create schema schema_name;").expect("no system errors");
+    first
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    first
+        .execute("insert into schema_name.table_name values (1), (2);")
+        .expect("no system errors");
+    let second_collector = Collector::new();
+    let second = QueryExecutor::new(storage, second_collector.clone());
+
+    ((first, first_collector), (second, second_collector))
+}
+
+fn rows(values: Vec<&str>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+        values.into_iter().map(|value| vec![text(value)]).collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn repeatable_read_in_transaction(two_sessions: (Session, Session)) {
+    let ((mut first, first_collector), (mut second, _second_collector)) = two_sessions;
+    first.execute("begin;").expect("no system errors");
+    first
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    second
+        .execute("insert into schema_name.table_name values (3);")
+        .expect("no system errors");
+    second
+        .execute("update schema_name.table_name set column_test = 10 where column_test = 1;")
+        .expect("no system errors");
+    second
+        .execute("delete from schema_name.table_name where column_test = 2;")
+        .expect("no system errors");
+    first
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    first.execute("commit;").expect("no system errors");
+    first
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    first_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::TransactionStarted),
+        rows(vec!["1", "2"]),
+        rows(vec!["1", "2"]),
+        Ok(QueryEvent::TransactionCommitted),
+        rows(vec!["10", "3"]),
+    ]);
+}
+
+#[rstest::rstest]
+fn uncommitted_changes_are_visible_only_to_own_transaction(two_sessions: (Session, Session)) {
+    let ((mut first, first_collector), (mut second, second_collector)) = two_sessions;
+    first.execute("begin;").expect("no system errors");
+    first
+        .execute("insert into schema_name.table_name values (3);")
+        .expect("no system errors");
+    first
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    second
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    first.execute("commit;").expect("no system errors");
+    second
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    first_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::TransactionStarted),
+        Ok(QueryEvent::RecordsInserted(1)),
+        rows(vec!["1", "2", "3"]),
+        Ok(QueryEvent::TransactionCommitted),
+    ]);
+    second_collector
+        .assert_content_for_single_queries_ignoring_complete(vec![rows(vec!["1", "2"]), rows(vec!["1", "2", "3"])]);
+}
@@ -0,0 +1,373 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.items (id smallint, name varchar(10), price smallint);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create function schema_name.upper_name() returns trigger \
+             as $$ select NEW.id, upper(NEW.name), NEW.price $$ language sql;",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::fixture]
+fn with_trigger(with_table: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute(
+            "create trigger tr_before_insert before insert on schema_name.items \
+             for each row execute function upper_name();",
+        )
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+fn table_created() -> Vec<QueryResult> {
+    vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+    ]
+}
+
+fn trigger_created() -> Vec<QueryResult> {
+    let mut expected = table_created();
+    expected.extend(vec![Ok(QueryEvent::TriggerCreated), Ok(QueryEvent::QueryComplete)]);
+    expected
+}
+
+fn items(rows: Vec<(&str, &str, &str)>) -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![
+            ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+            ("name".to_owned(), PostgreSqlType::VarChar, 14),
+            ("price".to_owned(), PostgreSqlType::SmallInt, -1),
+        ],
+        rows.into_iter()
+            .map(|(id, name, price)| vec![text(id), text(name), text(price)])
+            .collect(),
+    )))
+}
+
+#[rstest::rstest]
+fn before_insert_modifies_new_row(with_trigger: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_trigger;
+    engine
+        .execute("insert into schema_name.items values (1, 'apple', 10), (2, 'pear', 20);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = trigger_created();
+    expected.extend(vec![
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        items(vec![("1", "APPLE", "10"), ("2", "PEAR", "20")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn after_delete_logs_to_another_table(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("create table schema_name.deleted_items (id smallint, name varchar(10));")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create function schema_name.log_delete() returns trigger \
+             as $$ insert into schema_name.deleted_items values (OLD.id, OLD.name) $$ language sql;",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "create trigger tr_after_delete after delete on schema_name.items \
+             for each row execute function schema_name.log_delete();",
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 'apple', 10), (2, 'pear', 20), (3, 'plum', 30);")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.items where price > 15;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.deleted_items;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TriggerCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("name".to_owned(), PostgreSqlType::VarChar, 14),
+            ],
+            vec![vec![text("2"), text("pear")], vec![text("3"), text("plum")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn update_of_column(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("create table schema_name.price_changes (id smallint, old_price smallint, new_price smallint);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create function schema_name.log_price() returns trigger as $$ \
+             insert into schema_name.price_changes values (NEW.id, OLD.price, NEW.price) $$ language sql;",
+        )
+        .expect("no system errors");
+    engine
+        .execute(
+            "create trigger tr_price after update of price on schema_name.items \
+             for each row execute function log_price();",
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 'apple', 10);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.items set name = 'red apple';")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.items set price = price + 5;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.price_changes;")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TriggerCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("old_price".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("new_price".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![vec![text("1"), text("10"), text("15")]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn disable_trigger(with_trigger: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_trigger;
+    engine
+        .execute("alter table schema_name.items disable trigger tr_before_insert;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 'apple', 10);")
+        .expect("no system errors");
+    engine
+        .execute("alter table schema_name.items enable trigger tr_before_insert;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (2, 'pear', 20);")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = trigger_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TableAltered),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableAltered),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        items(vec![("1", "apple", "10"), ("2", "PEAR", "20")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn drop_trigger(with_trigger: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_trigger;
+    engine
+        .execute("drop trigger tr_before_insert on schema_name.items;")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 'apple', 10);")
+        .expect("no system errors");
+    engine
+        .execute("drop trigger tr_before_insert on schema_name.items;")
+        .expect("no system errors");
+    engine
+        .execute("drop trigger if exists tr_before_insert on schema_name.items;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+
+    let mut expected = trigger_created();
+    expected.extend(vec![
+        Ok(QueryEvent::TriggerDropped),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::trigger_does_not_exist(
+            "tr_before_insert".to_owned(),
+            "items".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TriggerDropped),
+        Ok(QueryEvent::QueryComplete),
+        items(vec![("1", "apple", "10")]),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn create_existing_trigger(with_trigger: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_trigger;
+    engine
+        .execute(
+            "create trigger tr_before_insert before insert on schema_name.items \
+             for each row execute function upper_name();",
+        )
+        .expect("no system errors");
+
+    let mut expected = trigger_created();
+    expected.extend(vec![
+        Err(QueryError::trigger_already_exists(
+            "tr_before_insert".to_owned(),
+            "items".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn function_does_not_return_trigger(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .execute("create function schema_name.one() returns smallint as $$ 1 $$ language sql;")
+        .expect("no system errors");
+    engine
+        .execute("create trigger tr before insert on schema_name.items execute function one();")
+        .expect("no system errors");
+
+    let mut expected = table_created();
+    expected.extend(vec![
+        Ok(QueryEvent::FunctionCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::invalid_object_definition(
+            "function schema_name.one must return type trigger".to_owned(),
+        )),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    collector.assert_content_for_single_queries(expected);
+}
+
+#[rstest::rstest]
+fn trigger_survives_restart() {
+    let root_path = tempfile::tempdir().expect("to create temporary folder");
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let mut engine = QueryExecutor::new(storage, Collector::new());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.items (id smallint, name varchar(10), price smallint);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create function schema_name.upper_name() returns trigger \
+             as $$ select NEW.id, upper(NEW.name), NEW.price $$ language sql;",
+        )
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.items values (1, 'apple', 10);")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create trigger tr_before_update before update on schema_name.items \
+             for each row execute function upper_name();",
+        )
+        .expect("no system errors");
+    drop(engine);
+
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage, collector.clone());
+    engine
+        .execute("update schema_name.items set name = 'kiwi';")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.items;")
+        .expect("no system errors");
+    engine
+        .execute(
+            "create trigger tr_before_update before update on schema_name.items \
+             for each row execute function upper_name();",
+        )
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::RecordsUpdated(1)),
+        items(vec![("1", "KIWI", "10")]),
+        Err(QueryError::trigger_already_exists(
+            "tr_before_update".to_owned(),
+            "items".to_owned(),
+        )),
+    ]);
+}
@@ -47,15 +47,15 @@ fn update_all_records(sql_engine_with_schema: (QueryExecutor, ResultCollector))
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![vec!["123".to_owned()], vec!["456".to_owned()]],
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("123".to_owned())], vec![Some("456".to_owned())]],
         ))),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsUpdated(2)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![vec!["789".to_owned()], vec!["789".to_owned()]],
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("789".to_owned())], vec![Some("789".to_owned())]],
         ))),
         Ok(QueryEvent::QueryComplete),
     ]);
@@ -94,12 +94,12 @@ fn update_single_column_of_all_records(sql_engine_with_schema: (QueryExecutor, R
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("col1".to_owned(), PostgreSqlType::SmallInt),
-                ("col2".to_owned(), PostgreSqlType::SmallInt),
+                ("col1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("col2".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["123".to_owned(), "789".to_owned()],
-                vec!["456".to_owned(), "789".to_owned()],
+                vec![Some("123".to_owned()), Some("789".to_owned())],
+                vec![Some("456".to_owned()), Some("789".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -107,12 +107,12 @@ fn update_single_column_of_all_records(sql_engine_with_schema: (QueryExecutor, R
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("col1".to_owned(), PostgreSqlType::SmallInt),
-                ("col2".to_owned(), PostgreSqlType::SmallInt),
+                ("col1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("col2".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["123".to_owned(), "357".to_owned()],
-                vec!["456".to_owned(), "357".to_owned()],
+                vec![Some("123".to_owned()), Some("357".to_owned())],
+                vec![Some("456".to_owned()), Some("357".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -152,13 +152,13 @@ fn update_multiple_columns_of_all_records(sql_engine_with_schema: (QueryExecutor
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("col1".to_owned(), PostgreSqlType::SmallInt),
-                ("col2".to_owned(), PostgreSqlType::SmallInt),
-                ("col3".to_owned(), PostgreSqlType::SmallInt),
+                ("col1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("col2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("col3".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["111".to_owned(), "222".to_owned(), "333".to_owned()],
-                vec!["444".to_owned(), "555".to_owned(), "666".to_owned()],
+                vec![Some("111".to_owned()), Some("222".to_owned()), Some("333".to_owned())],
+                vec![Some("444".to_owned()), Some("555".to_owned()), Some("666".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -166,13 +166,13 @@ fn update_multiple_columns_of_all_records(sql_engine_with_schema: (QueryExecutor
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("col1".to_owned(), PostgreSqlType::SmallInt),
-                ("col2".to_owned(), PostgreSqlType::SmallInt),
-                ("col3".to_owned(), PostgreSqlType::SmallInt),
+                ("col1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("col2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("col3".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["999".to_owned(), "222".to_owned(), "777".to_owned()],
-                vec!["999".to_owned(), "555".to_owned(), "777".to_owned()],
+                vec![Some("999".to_owned()), Some("222".to_owned()), Some("777".to_owned())],
+                vec![Some("999".to_owned()), Some("555".to_owned()), Some("777".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -207,14 +207,14 @@ fn update_all_records_in_multiple_columns(sql_engine_with_schema: (QueryExecutor
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["1".to_owned(), "2".to_owned(), "3".to_owned()],
-                vec!["4".to_owned(), "5".to_owned(), "6".to_owned()],
-                vec!["7".to_owned(), "8".to_owned(), "9".to_owned()],
+                vec![Some("1".to_owned()), Some("2".to_owned()), Some("3".to_owned())],
+                vec![Some("4".to_owned()), Some("5".to_owned()), Some("6".to_owned())],
+                vec![Some("7".to_owned()), Some("8".to_owned()), Some("9".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -222,14 +222,14 @@ fn update_all_records_in_multiple_columns(sql_engine_with_schema: (QueryExecutor
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
             vec![
-                ("column_1".to_owned(), PostgreSqlType::SmallInt),
-                ("column_2".to_owned(), PostgreSqlType::SmallInt),
-                ("column_3".to_owned(), PostgreSqlType::SmallInt),
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_3".to_owned(), PostgreSqlType::SmallInt, -1),
             ],
             vec![
-                vec!["10".to_owned(), "-20".to_owned(), "30".to_owned()],
-                vec!["10".to_owned(), "-20".to_owned(), "30".to_owned()],
-                vec!["10".to_owned(), "-20".to_owned(), "30".to_owned()],
+                vec![Some("10".to_owned()), Some("-20".to_owned()), Some("30".to_owned())],
+                vec![Some("10".to_owned()), Some("-20".to_owned()), Some("30".to_owned())],
+                vec![Some("10".to_owned()), Some("-20".to_owned()), Some("30".to_owned())],
             ],
         ))),
         Ok(QueryEvent::QueryComplete),
@@ -275,8 +275,8 @@ fn update_non_existent_columns_of_records(sql_engine_with_schema: (QueryExecutor
         Ok(QueryEvent::RecordsInserted(1)),
         Ok(QueryEvent::QueryComplete),
         Ok(QueryEvent::RecordsSelected((
-            vec![("column_test".to_owned(), PostgreSqlType::SmallInt)],
-            vec![vec!["123".to_owned()]],
+            vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("123".to_owned())]],
         ))),
         Ok(QueryEvent::QueryComplete),
         Err(QueryError::column_does_not_exist(vec![
@@ -335,8 +335,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["3".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("3".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -362,8 +362,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["-1".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("-1".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -389,8 +389,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["6".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("6".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -416,8 +416,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["4".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("4".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -443,8 +443,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["0".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("0".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -473,8 +473,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["64".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("64".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -502,8 +502,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["4".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("4".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -531,8 +531,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["2".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("2".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -560,8 +560,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["120".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("120".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -589,8 +589,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["120".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("120".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -618,8 +618,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["5".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("5".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -645,8 +645,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["1".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("1".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -672,8 +672,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["7".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("7".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -701,8 +701,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["-2".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("-2".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -730,8 +730,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["16".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("16".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -759,8 +759,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["2".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("2".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -786,8 +786,8 @@ mod operators {
                     Ok(QueryEvent::RecordsUpdated(1)),
                     Ok(QueryEvent::QueryComplete),
                     Ok(QueryEvent::RecordsSelected((
-                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt)],
-                        vec![vec!["5".to_owned()]],
+                        vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+                        vec![vec![Some("5".to_owned())]],
                     ))),
                     Ok(QueryEvent::QueryComplete),
                 ]);
@@ -833,8 +833,39 @@ mod operators {
                 Ok(QueryEvent::RecordsUpdated(1)),
                 Ok(QueryEvent::QueryComplete),
                 Ok(QueryEvent::RecordsSelected((
-                    vec![("strings".to_owned(), PostgreSqlType::Char)],
-                    vec![vec!["12345".to_owned()]],
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("12345".to_owned())]],
+                ))),
+                Ok(QueryEvent::QueryComplete),
+            ]);
+        }
+
+        #[rstest::rstest]
+        fn truncated_to_fit_char(with_table: (QueryExecutor, ResultCollector)) {
+            let (mut engine, collector) = with_table;
+            engine
+                .execute("update schema_name.table_name set strings = '1234567';")
+                .expect("no system errors");
+            engine
+                .execute("select * from schema_name.table_name;")
+                .expect("no system errors");
+
+            collector.assert_content_for_single_queries(vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::Notice {
+                    severity: "NOTICE".to_owned(),
+                    message: "value truncated to type character(5) for column 'strings' at row 1".to_owned(),
+                }),
+                Ok(QueryEvent::RecordsUpdated(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsSelected((
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("12345".to_owned())]],
                 ))),
                 Ok(QueryEvent::QueryComplete),
             ]);
@@ -866,15 +897,42 @@ mod operators {
                 Ok(QueryEvent::RecordsUpdated(1)),
                 Ok(QueryEvent::QueryComplete),
                 Ok(QueryEvent::RecordsSelected((
-                    vec![("strings".to_owned(), PostgreSqlType::Char)],
-                    vec![vec!["145".to_owned()]],
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("145".to_owned())]],
                 ))),
                 Ok(QueryEvent::QueryComplete),
                 Ok(QueryEvent::RecordsUpdated(1)),
                 Ok(QueryEvent::QueryComplete),
                 Ok(QueryEvent::RecordsSelected((
-                    vec![("strings".to_owned(), PostgreSqlType::Char)],
-                    vec![vec!["451".to_owned()]],
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("451".to_owned())]],
+                ))),
+                Ok(QueryEvent::QueryComplete),
+            ]);
+        }
+
+        #[rstest::rstest]
+        fn number_assigned_as_text(with_table: (QueryExecutor, ResultCollector)) {
+            let (mut engine, collector) = with_table;
+            engine
+                .execute("update schema_name.table_name set strings = 42;")
+                .expect("no system errors");
+            engine
+                .execute("select * from schema_name.table_name where strings = '42';")
+                .expect("no system errors");
+
+            collector.assert_content_for_single_queries(vec![
+                Ok(QueryEvent::SchemaCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::TableCreated),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsInserted(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsUpdated(1)),
+                Ok(QueryEvent::QueryComplete),
+                Ok(QueryEvent::RecordsSelected((
+                    vec![("strings".to_owned(), PostgreSqlType::Char, 9)],
+                    vec![vec![Some("42".to_owned())]],
                 ))),
                 Ok(QueryEvent::QueryComplete),
             ]);
@@ -904,3 +962,316 @@ mod operators {
         }
     }
 }
+
+#[rstest::rstest]
+fn update_records_matching_where_clause(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, v smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 10), (2, 20), (3, 30);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set v = v + 1 where id > 1;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![
+                vec![Some("1".to_owned()), Some("10".to_owned())],
+                vec![Some("2".to_owned()), Some("21".to_owned())],
+                vec![Some("3".to_owned()), Some("31".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_where_clause_matches_no_records(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, v smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1, 10), (2, 20);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set v = 1 where id = 9999;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(0)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            vec![
+                vec![Some("1".to_owned()), Some("10".to_owned())],
+                vec![Some("2".to_owned()), Some("20".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_records_of_empty_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (id smallint, v smallint);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set v = 1;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(0)),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn update_with_negative_literals(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_1 smallint, column_2 integer);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (-1, 1), (-2, 2);")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set column_2 = -2147483648 where column_1 = -1;")
+        .expect("no system errors");
+    engine
+        .execute("update schema_name.table_name set column_1 = -32769 where column_1 = -2;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsUpdated(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::out_of_range(
+            PostgreSqlType::SmallInt,
+            "column_1".to_owned(),
+            2,
+        )),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("column_1".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("column_2".to_owned(), PostgreSqlType::Integer, -1),
+            ],
+            vec![
+                vec![Some("-1".to_owned()), Some("-2147483648".to_owned())],
+                vec![Some("-2".to_owned()), Some("2".to_owned())],
+            ],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[cfg(test)]
+mod from_clause {
+    use super::*;
+
+    #[rstest::fixture]
+    fn with_tables(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+        let (mut engine, collector) = sql_engine_with_schema;
+        engine
+            .execute("create table schema_name.a (id smallint, v smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.a values (1, 10), (2, 20), (3, 30);")
+            .expect("no system errors");
+        engine
+            .execute("create table schema_name.b (id smallint, v smallint);")
+            .expect("no system errors");
+        engine
+            .execute("insert into schema_name.b values (1, 100), (3, 300);")
+            .expect("no system errors");
+
+        (engine, collector)
+    }
+
+    fn tables_created() -> Vec<QueryResult> {
+        vec![
+            Ok(QueryEvent::SchemaCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(3)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::TableCreated),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsInserted(2)),
+            Ok(QueryEvent::QueryComplete),
+        ]
+    }
+
+    fn selected(rows: Vec<(&str, &str)>) -> QueryResult {
+        Ok(QueryEvent::RecordsSelected((
+            vec![
+                ("id".to_owned(), PostgreSqlType::SmallInt, -1),
+                ("v".to_owned(), PostgreSqlType::SmallInt, -1),
+            ],
+            rows.into_iter().map(|(id, v)| vec![text(id), text(v)]).collect(),
+        )))
+    }
+
+    #[rstest::rstest]
+    fn rows_without_match_are_unchanged(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("update schema_name.a set v = b.v from schema_name.b where a.id = b.id;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.a;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsUpdated(2)),
+            Ok(QueryEvent::QueryComplete),
+            selected(vec![("1", "100"), ("2", "20"), ("3", "300")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn value_computed_from_both_tables(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("update schema_name.a set v = a.v + b.v from schema_name.b where a.id = b.id and b.v > 200;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.a;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsUpdated(1)),
+            Ok(QueryEvent::QueryComplete),
+            selected(vec![("1", "10"), ("2", "20"), ("3", "330")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn row_with_multiple_matches_is_updated_once(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("insert into schema_name.b values (1, 200);")
+            .expect("no system errors");
+        engine
+            .execute("update schema_name.a set v = a.v + 1 from schema_name.b where a.id = b.id;")
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.a;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsInserted(1)),
+            Ok(QueryEvent::QueryComplete),
+            Ok(QueryEvent::RecordsUpdated(2)),
+            Ok(QueryEvent::QueryComplete),
+            selected(vec![("1", "11"), ("2", "20"), ("3", "31")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn self_update_reads_rows_before_update(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute(
+                "update schema_name.a set v = previous.v from schema_name.a as previous where a.id = previous.id + 1;",
+            )
+            .expect("no system errors");
+        engine
+            .execute("select * from schema_name.a;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Ok(QueryEvent::RecordsUpdated(2)),
+            Ok(QueryEvent::QueryComplete),
+            selected(vec![("1", "10"), ("2", "10"), ("3", "20")]),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn ambiguous_column_in_predicate(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("update schema_name.a set v = b.v from schema_name.b where id = 1;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Err(QueryError::ambiguous_column("id".to_owned())),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+
+    #[rstest::rstest]
+    fn from_non_existent_table(with_tables: (QueryExecutor, ResultCollector)) {
+        let (mut engine, collector) = with_tables;
+        engine
+            .execute("update schema_name.a set v = c.v from schema_name.c where a.id = c.id;")
+            .expect("no system errors");
+
+        let mut expected = tables_created();
+        expected.extend(vec![
+            Err(QueryError::table_does_not_exist("schema_name.c".to_owned())),
+            Ok(QueryEvent::QueryComplete),
+        ]);
+        collector.assert_content_for_single_queries(expected);
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use protocol::sql_types::PostgreSqlType;
+
+type Admin = (QueryExecutor, ResultCollector);
+type User = (QueryExecutor, ResultCollector);
+
+#[rstest::fixture]
+fn admin_and_user() -> (Admin, User) {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let admin_collector = Collector::new();
+    let mut admin = QueryExecutor::new(storage.clone(), admin_collector.clone());
+    admin.execute("create schema schema_name;").expect("no system errors");
+    admin
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    admin
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    admin
+        .execute("create user user_name with password 'secret';")
+        .expect("no system errors");
+
+    let user_collector = Collector::new();
+    let mut user = QueryExecutor::new(storage, user_collector.clone());
+    user.set_session_user("user_name");
+
+    ((admin, admin_collector), (user, user_collector))
+}
+
+fn selected() -> QueryResult {
+    Ok(QueryEvent::RecordsSelected((
+        vec![("column_test".to_owned(), PostgreSqlType::SmallInt, -1)],
+        vec![vec![Some("1".to_owned())]],
+    )))
+}
+
+fn denied() -> QueryResult {
+    Err(QueryError::permission_denied(
+        "permission denied for table table_name".to_owned(),
+    ))
+}
+
+#[rstest::rstest]
+fn select_without_privilege(admin_and_user: (Admin, User)) {
+    let (_admin, (mut user, collector)) = admin_and_user;
+
+    user.execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![denied()]);
+}
+
+#[rstest::rstest]
+fn modify_without_privileges(admin_and_user: (Admin, User)) {
+    let (_admin, (mut user, collector)) = admin_and_user;
+
+    user.execute("insert into schema_name.table_name values (2);")
+        .expect("no system errors");
+    user.execute("update schema_name.table_name set column_test = 2;")
+        .expect("no system errors");
+    user.execute("delete from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![denied(), denied(), denied()]);
+}
+
+#[rstest::rstest]
+fn grant_then_revoke(admin_and_user: (Admin, User)) {
+    let ((mut admin, admin_collector), (mut user, collector)) = admin_and_user;
+
+    admin
+        .execute("grant select on schema_name.table_name to user_name;")
+        .expect("no system errors");
+    user.execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    admin
+        .execute("revoke select on schema_name.table_name from user_name;")
+        .expect("no system errors");
+    user.execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    admin_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::UserCreated),
+        Ok(QueryEvent::PrivilegesGranted),
+        Ok(QueryEvent::PrivilegesRevoked),
+    ]);
+    collector.assert_content_for_single_queries_ignoring_complete(vec![selected(), denied()]);
+}
+
+#[rstest::rstest]
+fn revoke_one_of_granted_privileges(admin_and_user: (Admin, User)) {
+    let ((mut admin, _admin_collector), (mut user, collector)) = admin_and_user;
+
+    admin
+        .execute("grant all privileges on table schema_name.table_name to user_name;")
+        .expect("no system errors");
+    admin
+        .execute("revoke insert on schema_name.table_name from user_name;")
+        .expect("no system errors");
+    user.execute("insert into schema_name.table_name values (2);")
+        .expect("no system errors");
+    user.execute("delete from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![denied(), Ok(QueryEvent::RecordsDeleted(1))]);
+}
+
+#[rstest::rstest]
+fn superuser_bypasses_checks(admin_and_user: (Admin, User)) {
+    let ((mut admin, _admin_collector), (_user, _collector)) = admin_and_user;
+    admin
+        .execute("create user super_name with superuser;")
+        .expect("no system errors");
+    let collector = Collector::new();
+    let mut superuser = QueryExecutor::new(admin.storage.clone(), collector.clone());
+    superuser.set_session_user("super_name");
+
+    superuser
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![selected()]);
+}
+
+#[rstest::rstest]
+fn grant_with_grant_option(admin_and_user: (Admin, User)) {
+    let ((mut admin, _admin_collector), (mut user, collector)) = admin_and_user;
+    admin.execute("create user other_name;").expect("no system errors");
+    admin
+        .execute("grant select on schema_name.table_name to user_name with grant option;")
+        .expect("no system errors");
+    let other_collector = Collector::new();
+    let mut other = QueryExecutor::new(admin.storage.clone(), other_collector.clone());
+    other.set_session_user("other_name");
+
+    user.execute("grant select on schema_name.table_name to other_name;")
+        .expect("no system errors");
+    other
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::PrivilegesGranted)]);
+    other_collector.assert_content_for_single_queries_ignoring_complete(vec![selected()]);
+}
+
+#[rstest::rstest]
+fn grant_without_grant_option(admin_and_user: (Admin, User)) {
+    let ((mut admin, _admin_collector), (mut user, collector)) = admin_and_user;
+    admin.execute("create user other_name;").expect("no system errors");
+    admin
+        .execute("grant select on schema_name.table_name to user_name;")
+        .expect("no system errors");
+
+    user.execute("grant select on schema_name.table_name to other_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![denied()]);
+}
+
+#[rstest::rstest]
+fn owner_has_all_privileges(admin_and_user: (Admin, User)) {
+    let ((mut admin, _admin_collector), (mut user, collector)) = admin_and_user;
+    admin.execute("create schema user_schema;").expect("no system errors");
+
+    user.execute("create table user_schema.table_name (column_test smallint);")
+        .expect("no system errors");
+    user.execute("insert into user_schema.table_name values (1);")
+        .expect("no system errors");
+    user.execute("select * from user_schema.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        selected(),
+    ]);
+}
+
+#[rstest::rstest]
+fn create_existing_user(admin_and_user: (Admin, User)) {
+    let ((mut admin, admin_collector), _user) = admin_and_user;
+
+    admin.execute("create user user_name;").expect("no system errors");
+
+    admin_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::UserCreated),
+        Err(QueryError::user_already_exists("user_name".to_owned())),
+    ]);
+}
+
+#[rstest::rstest]
+fn drop_user(admin_and_user: (Admin, User)) {
+    let ((mut admin, admin_collector), _user) = admin_and_user;
+
+    admin.execute("drop user user_name;").expect("no system errors");
+    admin.execute("drop user user_name;").expect("no system errors");
+    admin
+        .execute("drop user if exists user_name;")
+        .expect("no system errors");
+
+    admin_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::UserCreated),
+        Ok(QueryEvent::UserDropped),
+        Err(QueryError::user_does_not_exist("user_name".to_owned())),
+        Ok(QueryEvent::UserDropped),
+    ]);
+}
+
+#[rstest::rstest]
+fn user_can_not_create_users(admin_and_user: (Admin, User)) {
+    let (_admin, (mut user, collector)) = admin_and_user;
+
+    user.execute("create user other_name;").expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![Err(QueryError::permission_denied(
+        "permission denied to create role".to_owned(),
+    ))]);
+}
+
+#[rstest::rstest]
+fn grant_to_non_existent_user(admin_and_user: (Admin, User)) {
+    let ((mut admin, admin_collector), _user) = admin_and_user;
+
+    admin
+        .execute("grant select on schema_name.table_name to other_name;")
+        .expect("no system errors");
+
+    admin_collector.assert_content_for_single_queries_ignoring_complete(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::UserCreated),
+        Err(QueryError::user_does_not_exist("other_name".to_owned())),
+    ]);
+}
+
+#[rstest::rstest]
+fn unknown_user_has_no_privileges(admin_and_user: (Admin, User)) {
+    let ((admin, _admin_collector), _user) = admin_and_user;
+    let collector = Collector::new();
+    let mut unknown = QueryExecutor::new(admin.storage.clone(), collector.clone());
+    unknown.set_session_user("unknown_name");
+
+    unknown
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    unknown.execute("create user other_name;").expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        denied(),
+        Err(QueryError::permission_denied(
+            "permission denied to create role".to_owned(),
+        )),
+    ]);
+}
+
+#[rstest::rstest]
+fn dropped_user_has_no_privileges(admin_and_user: (Admin, User)) {
+    let ((mut admin, _admin_collector), (mut user, collector)) = admin_and_user;
+    admin
+        .execute("grant select on schema_name.table_name to user_name;")
+        .expect("no system errors");
+    admin.execute("drop user user_name;").expect("no system errors");
+
+    user.execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    user.execute("create user other_name;").expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![
+        denied(),
+        Err(QueryError::permission_denied(
+            "permission denied to create role".to_owned(),
+        )),
+    ]);
+}
+
+#[rstest::rstest]
+fn users_and_privileges_survive_restart() {
+    let root_path = tempfile::tempdir().expect("to create temporary folder");
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let mut admin = QueryExecutor::new(storage, Collector::new());
+    admin.execute("create schema schema_name;").expect("no system errors");
+    admin
+        .execute("create table schema_name.table_name (column_test smallint);")
+        .expect("no system errors");
+    admin
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+    admin
+        .execute("create user user_name with password 'secret';")
+        .expect("no system errors");
+    admin
+        .execute("create user super_name with superuser;")
+        .expect("no system errors");
+    admin
+        .execute("grant select on schema_name.table_name to user_name;")
+        .expect("no system errors");
+    drop(admin);
+
+    let storage = Arc::new(DataManager::persistent(root_path.path().into()).expect("to create data manager"));
+    let collector = Collector::new();
+    let mut user = QueryExecutor::new(storage.clone(), collector.clone());
+    user.set_session_user("user_name");
+    user.execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+    user.execute("delete from schema_name.table_name;")
+        .expect("no system errors");
+    let super_collector = Collector::new();
+    let mut superuser = QueryExecutor::new(storage, super_collector.clone());
+    superuser.set_session_user("super_name");
+    superuser.execute("drop user user_name;").expect("no system errors");
+
+    collector.assert_content_for_single_queries_ignoring_complete(vec![selected(), denied()]);
+    super_collector.assert_content_for_single_queries_ignoring_complete(vec![Ok(QueryEvent::UserDropped)]);
+}
@@ -0,0 +1,111 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use data_manager::ColumnStatistics;
+use protocol::sql_types::PostgreSqlType;
+
+#[rstest::rstest]
+fn vacuum_keeps_live_rows(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (column_si smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (2), (3), (4);")
+        .expect("no system errors");
+    engine
+        .execute("delete from schema_name.table_name where column_si > 2;")
+        .expect("no system errors");
+    engine
+        .execute("vacuum schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select * from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(4)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsDeleted(2)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VacuumComplete { rows_removed: 0 }),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("column_si".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("1".to_owned())], vec![Some("2".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[test]
+fn vacuum_analyze_collects_statistics() {
+    let storage = Arc::new(DataManager::in_memory().expect("to create data manager"));
+    let collector = Collector::new();
+    let mut engine = QueryExecutor::new(storage.clone(), collector.clone());
+    engine.execute("create schema schema_name;").expect("no system errors");
+    engine
+        .execute("create table schema_name.table_name (column_si smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1), (2), (2);")
+        .expect("no system errors");
+    engine
+        .execute("vacuum analyze schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(3)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::VacuumComplete { rows_removed: 0 }),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+    let (schema_id, table_id) = match storage.table_exists("schema_name", "table_name") {
+        Some((schema_id, Some(table_id))) => (schema_id, table_id),
+        _ => panic!("table should exist"),
+    };
+    assert_eq!(
+        storage.table_statistics(schema_id, table_id),
+        Some(vec![ColumnStatistics {
+            distinct: 2,
+            min: Some("1".to_owned()),
+            max: Some("2".to_owned()),
+            null_fraction: 0.0,
+        }])
+    );
+}
+
+#[rstest::rstest]
+fn vacuum_non_existent_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("vacuum full schema_name.non_existent;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::table_does_not_exist("schema_name.non_existent".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -0,0 +1,141 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::QueryExecutor;
+use protocol::{results::QueryEvent, sql_types::PostgreSqlType};
+
+#[rstest::fixture]
+fn with_table(sql_engine_with_schema: (QueryExecutor, ResultCollector)) -> (QueryExecutor, ResultCollector) {
+    let (mut engine, collector) = sql_engine_with_schema;
+    engine
+        .execute("create table schema_name.table_name (col smallint);")
+        .expect("no system errors");
+    engine
+        .execute("insert into schema_name.table_name values (1);")
+        .expect("no system errors");
+
+    (engine, collector)
+}
+
+#[rstest::rstest]
+fn valid_queries_do_not_modify_storage(with_table: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = with_table;
+    engine
+        .validate("insert into schema_name.table_name values (2);")
+        .expect("no system errors");
+    engine
+        .validate("update schema_name.table_name set col = col + 1;")
+        .expect("no system errors");
+    engine
+        .validate("delete from schema_name.table_name where col = 1;")
+        .expect("no system errors");
+    engine
+        .validate("drop table schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .validate("select col from schema_name.table_name;")
+        .expect("no system errors");
+    engine
+        .execute("select col from schema_name.table_name;")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsSelected((
+            vec![("col".to_owned(), PostgreSqlType::SmallInt, -1)],
+            vec![vec![Some("1".to_owned())]],
+        ))),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn create_schema_is_not_executed(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.validate("create schema schema_name;").expect("no system errors");
+    engine
+        .validate("create table schema_name.table_name (col smallint);")
+        .expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::QueryComplete),
+        Err(QueryError::schema_does_not_exist("schema_name".to_owned())),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
+
+#[rstest::rstest]
+fn syntax_error(sql_engine: (QueryExecutor, ResultCollector)) {
+    let (mut engine, collector) = sql_engine;
+    engine.validate("selec 1;").expect("no system errors");
+
+    collector.assert_content(vec![Err(QueryError::syntax_error(
+        "\"selec 1;\" can't be parsed".to_owned(),
+    ))]);
+}
+
+#[rstest::rstest(
+    query,
+    error,
+    case::non_existent_table(
+        "select col from schema_name.non_existent;",
+        QueryError::table_does_not_exist("schema_name.non_existent".to_owned())
+    ),
+    case::non_existent_column_in_select(
+        "select other from schema_name.table_name;",
+        QueryError::column_does_not_exist(vec!["other".to_owned()])
+    ),
+    case::non_existent_column_in_update(
+        "update schema_name.table_name set other = 1;",
+        QueryError::column_does_not_exist(vec!["other".to_owned()])
+    ),
+    case::non_existent_column_in_delete(
+        "delete from schema_name.table_name where other = 1;",
+        QueryError::column_does_not_exist(vec!["other".to_owned()])
+    ),
+    case::operator_types(
+        "update schema_name.table_name set col = 1 || 2;",
+        QueryError::undefined_function("||".to_owned(), "NUMBER".to_owned(), "NUMBER".to_owned())
+    ),
+    case::out_of_range(
+        "insert into schema_name.table_name values (32768);",
+        QueryError::out_of_range(PostgreSqlType::SmallInt, "col".to_owned(), 1)
+    )
+)]
+fn reports_errors_of_execution(with_table: (QueryExecutor, ResultCollector), query: &str, error: QueryError) {
+    let (mut engine, collector) = with_table;
+    engine.validate(query).expect("no system errors");
+
+    collector.assert_content_for_single_queries(vec![
+        Ok(QueryEvent::SchemaCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::TableCreated),
+        Ok(QueryEvent::QueryComplete),
+        Ok(QueryEvent::RecordsInserted(1)),
+        Ok(QueryEvent::QueryComplete),
+        Err(error),
+        Ok(QueryEvent::QueryComplete),
+    ]);
+}
@@ -14,9 +14,9 @@
 
 use protocol::sql_types::PostgreSqlType;
 use serde::{Deserialize, Serialize};
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
-#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Hash, Ord, PartialOrd)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, Hash, Ord, PartialOrd)]
 pub enum SqlType {
     Bool,
     Char(u64),
@@ -33,12 +33,20 @@ pub enum SqlType {
     TimestampWithTimeZone,
     Date,
     Interval,
+    Json,
+    Bytea,
+    Array(Box<SqlType>),
 }
 
 impl ToString for SqlType {
     fn to_string(&self) -> String {
-        let string: &'static str = self.into();
-        string.to_owned()
+        match self {
+            SqlType::Array(element) => format!("{}[]", element.to_string()),
+            sql_type => {
+                let string: &'static str = sql_type.into();
+                string.to_owned()
+            }
+        }
     }
 }
 
@@ -60,6 +68,9 @@ impl Into<&'static str> for &SqlType {
             SqlType::TimestampWithTimeZone => "timestamp with time zone",
             SqlType::Date => "date",
             SqlType::Interval => "interval",
+            SqlType::Json => "json",
+            SqlType::Bytea => "bytea",
+            SqlType::Array(_) => "array",
         }
     }
 }
@@ -77,7 +88,13 @@ impl SqlType {
             Self::Integer(min) => Box::new(IntegerSqlTypeConstraint { min }),
             Self::BigInt(min) => Box::new(BigIntTypeConstraint { min }),
             Self::Bool => Box::new(BoolSqlTypeConstraint),
-            sql_type => unimplemented!("Type constraint for {:?} is not currently implemented", sql_type),
+            Self::Json => Box::new(JsonSqlTypeConstraint),
+            Self::Bytea => Box::new(ByteaSqlTypeConstraint),
+            Self::Timestamp => Box::new(TimestampSqlTypeConstraint),
+            Self::Array(ref element) => Box::new(ArraySqlTypeConstraint {
+                element: element.constraint(),
+            }),
+            ref sql_type => unimplemented!("Type constraint for {:?} is not currently implemented", sql_type),
         }
     }
 
@@ -89,7 +106,26 @@ impl SqlType {
             Self::Integer(_min) => Box::new(IntegerSqlTypeSerializer),
             Self::BigInt(_min) => Box::new(BigIntTypeSerializer),
             Self::Bool => Box::new(BoolSqlTypeSerializer),
-            sql_type => unimplemented!("Type Serializer for {:?} is not currently implemented", sql_type),
+            Self::Json => Box::new(JsonSqlTypeSerializer),
+            Self::Bytea => Box::new(ByteaSqlTypeSerializer),
+            Self::Timestamp => Box::new(TimestampSqlTypeSerializer),
+            Self::Array(ref element) => Box::new(ArraySqlTypeSerializer {
+                element: element.serializer(),
+            }),
+            ref sql_type => unimplemented!("Type Serializer for {:?} is not currently implemented", sql_type),
+        }
+    }
+
+    /// type modifier that PostgreSQL reports for a column of the type, declared length
+    /// of character types includes 4 bytes of the value header, -1 if the type has no modifier
+    pub fn type_modifier(&self) -> i32 {
+        match self {
+            Self::Char(length) | Self::VarChar(length) => i32::try_from(*length)
+                .ok()
+                .and_then(|length| length.checked_add(4))
+                .unwrap_or(-1),
+            Self::Array(element) => element.type_modifier(),
+            _ => -1,
         }
     }
 
@@ -110,6 +146,9 @@ impl SqlType {
             Self::TimestampWithTimeZone => PostgreSqlType::TimestampWithTimeZone,
             Self::Date => PostgreSqlType::Date,
             Self::Interval => PostgreSqlType::Interval,
+            Self::Json => PostgreSqlType::Json,
+            Self::Bytea => PostgreSqlType::Bytea,
+            Self::Array(ref element) => array_type(element.to_pg_types()),
         }
     }
 }
@@ -132,6 +171,100 @@ impl Into<PostgreSqlType> for &SqlType {
             SqlType::TimestampWithTimeZone => PostgreSqlType::TimestampWithTimeZone,
             SqlType::Date => PostgreSqlType::Date,
             SqlType::Interval => PostgreSqlType::Interval,
+            SqlType::Json => PostgreSqlType::Json,
+            SqlType::Bytea => PostgreSqlType::Bytea,
+            SqlType::Array(element) => array_type(element.as_ref().into()),
+        }
+    }
+}
+
+/// type of arrays of the `element` type, `None` if such arrays could not be stored,
+/// multidimensional arrays have the same type as one dimensional ones
+pub fn array_of(element: PostgreSqlType) -> Option<PostgreSqlType> {
+    match element {
+        PostgreSqlType::Bool | PostgreSqlType::BoolArray => Some(PostgreSqlType::BoolArray),
+        PostgreSqlType::Char | PostgreSqlType::CharArray => Some(PostgreSqlType::CharArray),
+        PostgreSqlType::VarChar | PostgreSqlType::VarCharArray => Some(PostgreSqlType::VarCharArray),
+        PostgreSqlType::SmallInt | PostgreSqlType::SmallIntArray => Some(PostgreSqlType::SmallIntArray),
+        PostgreSqlType::Integer | PostgreSqlType::IntegerArray => Some(PostgreSqlType::IntegerArray),
+        PostgreSqlType::BigInt | PostgreSqlType::BigIntArray => Some(PostgreSqlType::BigIntArray),
+        _ => None,
+    }
+}
+
+// columns of arrays that could not be stored are rejected when they are defined,
+// the text representation of any other array is described as `varchar[]`
+fn array_type(element: PostgreSqlType) -> PostgreSqlType {
+    array_of(element).unwrap_or(PostgreSqlType::VarCharArray)
+}
+
+/// text representation of an array, e.g. `{1,2,NULL}`, where `None` elements are NULLs
+pub fn array_literal(elements: Vec<Option<String>>) -> String {
+    let elements = elements
+        .into_iter()
+        .map(|element| match element {
+            None => "NULL".to_owned(),
+            Some(element)
+                if element.is_empty()
+                    || element.eq_ignore_ascii_case("NULL")
+                    || element
+                        .chars()
+                        .any(|c| c.is_whitespace() || matches!(c, ',' | '{' | '}' | '"' | '\\')) =>
+            {
+                format!("\"{}\"", element.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            Some(element) => element,
+        })
+        .collect::<Vec<String>>();
+    format!("{{{}}}", elements.join(","))
+}
+
+// parses text representation of one dimensional array, `None` if it is malformed
+fn array_elements(in_value: &str) -> Option<Vec<Option<String>>> {
+    let inner = in_value.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let mut elements = vec![];
+    if inner.trim().is_empty() {
+        return Some(elements);
+    }
+    let mut chars = inner.chars().peekable();
+    loop {
+        while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut element = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => element.push(chars.next()?),
+                    c => element.push(c),
+                }
+            }
+            while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+                chars.next();
+            }
+            elements.push(Some(element));
+        } else {
+            let mut element = String::new();
+            while let Some(&c) = chars.peek() {
+                match c {
+                    ',' => break,
+                    '{' | '}' | '"' => return None,
+                    c => element.push(c),
+                }
+                chars.next();
+            }
+            match element.trim_end() {
+                "" => return None,
+                element if element.eq_ignore_ascii_case("NULL") => elements.push(None),
+                element => elements.push(Some(element.to_owned())),
+            }
+        }
+        match chars.next() {
+            Some(',') => {}
+            None => return Some(elements),
+            Some(_) => return None,
         }
     }
 }
@@ -359,6 +492,400 @@ impl Serializer for BoolSqlTypeSerializer {
     }
 }
 
+struct JsonSqlTypeConstraint;
+
+impl Constraint for JsonSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        match canonical_json(in_value) {
+            Some(_) => Ok(()),
+            None => Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        }
+    }
+}
+
+// documents are kept without insignificant whitespaces
+struct JsonSqlTypeSerializer;
+
+impl Serializer for JsonSqlTypeSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        canonical_json(in_value).unwrap_or_default().into_bytes()
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        String::from_utf8(out_value.to_vec()).unwrap()
+    }
+}
+
+struct ByteaSqlTypeConstraint;
+
+impl Constraint for ByteaSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        match bytea_bytes(in_value) {
+            Some(_) => Ok(()),
+            None => Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        }
+    }
+}
+
+// bytes are always output in the hex format
+struct ByteaSqlTypeSerializer;
+
+impl Serializer for ByteaSqlTypeSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        bytea_bytes(in_value).unwrap_or_default()
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        out_value
+            .iter()
+            .fold("\\x".to_owned(), |hex, byte| hex + format!("{:02x}", byte).as_str())
+    }
+}
+
+struct TimestampSqlTypeConstraint;
+
+impl Constraint for TimestampSqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        match canonical_timestamp(in_value) {
+            Some(_) => Ok(()),
+            None => Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        }
+    }
+}
+
+// timestamps are kept as `YYYY-MM-DD HH:MM:SS[.ffffff]`, thus their text is ordered as time
+struct TimestampSqlTypeSerializer;
+
+impl Serializer for TimestampSqlTypeSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        canonical_timestamp(in_value).unwrap_or_default().into_bytes()
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        String::from_utf8(out_value.to_vec()).unwrap()
+    }
+}
+
+// parses `YYYY-MM-DD[( |T)HH:MM[:SS[.ffffff]]]`, time is midnight when it is omitted, trailing zeros
+// of fractional seconds are dropped as PostgreSQL does. `None` when the value is not a valid timestamp
+fn canonical_timestamp(in_value: &str) -> Option<String> {
+    fn number(part: &str, digits: usize) -> Option<u32> {
+        if part.len() == digits && part.chars().all(|c| c.is_ascii_digit()) {
+            part.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    let in_value = in_value.trim();
+    let (date, time) = match in_value.find(&[' ', 'T'][..]) {
+        Some(index) => (&in_value[..index], Some(in_value[index + 1..].trim_start())),
+        None => (in_value, None),
+    };
+    let mut date_parts = date.split('-');
+    let year = number(date_parts.next()?, 4)?;
+    let month = number(date_parts.next()?, 2)?;
+    let day = number(date_parts.next()?, 2)?;
+    if date_parts.next().is_some() {
+        return None;
+    }
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if year == 0 || day == 0 || day > days {
+        return None;
+    }
+    let (hour, minute, second, fraction) = match time {
+        None => (0, 0, 0, ""),
+        Some(time) => {
+            let (time, fraction) = match time.find('.') {
+                Some(index) if index + 1 < time.len() => (&time[..index], &time[index + 1..]),
+                Some(_) => return None,
+                None => (time, ""),
+            };
+            let mut time_parts = time.split(':');
+            let hour = number(time_parts.next()?, 2)?;
+            let minute = number(time_parts.next()?, 2)?;
+            let second = match time_parts.next() {
+                Some(second) => number(second, 2)?,
+                None if fraction.is_empty() => 0,
+                None => return None,
+            };
+            if time_parts.next().is_some() || fraction.len() > 6 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            (hour, minute, second, fraction.trim_end_matches('0'))
+        }
+    };
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let mut timestamp = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    );
+    if !fraction.is_empty() {
+        timestamp.push('.');
+        timestamp.push_str(fraction);
+    }
+    Some(timestamp)
+}
+
+// decodes either hex (`\x48656c6c6f`) or escape (`Hello\000`) format, `None` when `in_value` has
+// invalid hex digits or escape sequences, see https://www.postgresql.org/docs/12/datatype-binary.html
+fn bytea_bytes(in_value: &str) -> Option<Vec<u8>> {
+    if let Some(hex) = in_value.strip_prefix("\\x") {
+        let digits = hex
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .map(|c| c.to_digit(16))
+            .collect::<Option<Vec<_>>>()?;
+        if digits.len() % 2 != 0 {
+            return None;
+        }
+        return Some(digits.chunks(2).map(|pair| (pair[0] * 16 + pair[1]) as u8).collect());
+    }
+    let mut bytes = vec![];
+    let mut chars = in_value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+        match chars.next()? {
+            '\\' => bytes.push(b'\\'),
+            first @ '0'..='3' => {
+                let second = chars.next()?.to_digit(8)?;
+                let third = chars.next()?.to_digit(8)?;
+                bytes.push((first.to_digit(8)? * 64 + second * 8 + third) as u8);
+            }
+            _ => return None,
+        }
+    }
+    Some(bytes)
+}
+
+// `None` if `in_value` is not a well-formed JSON, see https://tools.ietf.org/html/rfc8259
+fn canonical_json(in_value: &str) -> Option<String> {
+    let mut json = JsonText {
+        chars: in_value.chars().peekable(),
+        canonical: String::new(),
+    };
+    json.value()?;
+    json.whitespaces();
+    if json.chars.next().is_some() {
+        return None;
+    }
+    Some(json.canonical)
+}
+
+struct JsonText<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    canonical: String,
+}
+
+impl JsonText<'_> {
+    fn value(&mut self) -> Option<()> {
+        self.whitespaces();
+        match self.chars.peek()? {
+            '{' => self.members('}', |json| {
+                json.whitespaces();
+                json.string()?;
+                json.whitespaces();
+                json.expect(':')?;
+                json.value()
+            }),
+            '[' => self.members(']', JsonText::value),
+            '"' => self.string(),
+            't' => self.literal("true"),
+            'f' => self.literal("false"),
+            'n' => self.literal("null"),
+            '-' | '0'..='9' => self.number(),
+            _ => None,
+        }
+    }
+
+    // comma separated members of an object or elements of an array
+    fn members(&mut self, closing: char, member: impl Fn(&mut Self) -> Option<()>) -> Option<()> {
+        self.canonical.push(self.chars.next()?);
+        self.whitespaces();
+        if self.chars.peek() == Some(&closing) {
+            self.canonical.push(self.chars.next()?);
+            return Some(());
+        }
+        loop {
+            member(self)?;
+            self.whitespaces();
+            match self.chars.next()? {
+                ',' => self.canonical.push(','),
+                c if c == closing => {
+                    self.canonical.push(c);
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<()> {
+        self.expect('"')?;
+        loop {
+            match self.chars.next()? {
+                '"' => {
+                    self.canonical.push('"');
+                    return Some(());
+                }
+                '\\' => {
+                    self.canonical.push('\\');
+                    match self.chars.next()? {
+                        c @ '"' | c @ '\\' | c @ '/' | c @ 'b' | c @ 'f' | c @ 'n' | c @ 'r' | c @ 't' => {
+                            self.canonical.push(c)
+                        }
+                        'u' => {
+                            self.canonical.push('u');
+                            for _ in 0..4 {
+                                match self.chars.next()? {
+                                    c if c.is_ascii_hexdigit() => self.canonical.push(c),
+                                    _ => return None,
+                                }
+                            }
+                        }
+                        _ => return None,
+                    }
+                }
+                c if c < ' ' => return None,
+                c => self.canonical.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<()> {
+        if self.chars.peek() == Some(&'-') {
+            self.canonical.push('-');
+            self.chars.next();
+        }
+        match self.chars.next()? {
+            '0' => self.canonical.push('0'),
+            c @ '1'..='9' => {
+                self.canonical.push(c);
+                self.digits();
+            }
+            _ => return None,
+        }
+        if self.chars.peek() == Some(&'.') {
+            self.canonical.push('.');
+            self.chars.next();
+            if self.digits() == 0 {
+                return None;
+            }
+        }
+        if let Some(&c) = self.chars.peek().filter(|c| **c == 'e' || **c == 'E') {
+            self.canonical.push(c);
+            self.chars.next();
+            if let Some(&c) = self.chars.peek().filter(|c| **c == '+' || **c == '-') {
+                self.canonical.push(c);
+                self.chars.next();
+            }
+            if self.digits() == 0 {
+                return None;
+            }
+        }
+        Some(())
+    }
+
+    fn digits(&mut self) -> usize {
+        let mut count = 0;
+        while let Some(&c) = self.chars.peek().filter(|c| c.is_ascii_digit()) {
+            self.canonical.push(c);
+            self.chars.next();
+            count += 1;
+        }
+        count
+    }
+
+    fn literal(&mut self, literal: &str) -> Option<()> {
+        for expected in literal.chars() {
+            self.expect(expected)?;
+        }
+        Some(())
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        if self.chars.next()? == expected {
+            self.canonical.push(expected);
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn whitespaces(&mut self) {
+        while matches!(self.chars.peek(), Some(' ') | Some('\t') | Some('\n') | Some('\r')) {
+            self.chars.next();
+        }
+    }
+}
+
+struct ArraySqlTypeConstraint {
+    element: Box<dyn Constraint>,
+}
+
+impl Constraint for ArraySqlTypeConstraint {
+    fn validate(&self, in_value: &str) -> Result<(), ConstraintError> {
+        match array_elements(in_value) {
+            Some(elements) => elements
+                .iter()
+                .flatten()
+                .try_for_each(|element| self.element.validate(element)),
+            None => Err(ConstraintError::TypeMismatch(in_value.to_owned())),
+        }
+    }
+}
+
+// every element is prefixed with its length, which is -1 for NULLs
+struct ArraySqlTypeSerializer {
+    element: Box<dyn Serializer>,
+}
+
+impl Serializer for ArraySqlTypeSerializer {
+    fn ser(&self, in_value: &str) -> Vec<u8> {
+        let mut data = vec![];
+        for element in array_elements(in_value).unwrap_or_default() {
+            match element {
+                Some(element) => {
+                    let element = self.element.ser(element.as_str());
+                    data.extend_from_slice(&(element.len() as i32).to_be_bytes());
+                    data.extend_from_slice(&element);
+                }
+                None => data.extend_from_slice(&(-1i32).to_be_bytes()),
+            }
+        }
+        data
+    }
+
+    fn des(&self, out_value: &[u8]) -> String {
+        let mut elements = vec![];
+        let mut index = 0;
+        while index < out_value.len() {
+            let len = i32::from_be_bytes(out_value[index..index + 4].try_into().unwrap());
+            index += 4;
+            if len < 0 {
+                elements.push(None);
+            } else {
+                elements.push(Some(self.element.des(&out_value[index..index + len as usize])));
+                index += len as usize;
+            }
+        }
+        array_literal(elements)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -457,6 +984,52 @@ mod tests {
             let pg_type: PostgreSqlType = (&SqlType::Interval).into();
             assert_eq!(pg_type, PostgreSqlType::Interval);
         }
+
+        #[test]
+        fn json() {
+            let pg_type: PostgreSqlType = (&SqlType::Json).into();
+            assert_eq!(pg_type, PostgreSqlType::Json);
+        }
+
+        #[test]
+        fn bytea() {
+            let pg_type: PostgreSqlType = (&SqlType::Bytea).into();
+            assert_eq!(pg_type, PostgreSqlType::Bytea);
+        }
+
+        #[test]
+        fn array() {
+            let pg_type: PostgreSqlType = (&SqlType::Array(Box::new(SqlType::Integer(i32::MIN)))).into();
+            assert_eq!(pg_type, PostgreSqlType::IntegerArray);
+        }
+
+        #[test]
+        fn array_of_type_that_could_not_be_stored() {
+            assert_eq!(array_of(PostgreSqlType::Json), None);
+        }
+    }
+
+    #[cfg(test)]
+    mod type_modifier {
+        use super::*;
+
+        #[test]
+        fn character_types() {
+            assert_eq!(SqlType::Char(5).type_modifier(), 9);
+            assert_eq!(SqlType::VarChar(10).type_modifier(), 14);
+        }
+
+        #[test]
+        fn array_of_character_type() {
+            assert_eq!(SqlType::Array(Box::new(SqlType::VarChar(10))).type_modifier(), 14);
+        }
+
+        #[test]
+        fn types_without_modifier() {
+            assert_eq!(SqlType::SmallInt(i16::MIN).type_modifier(), -1);
+            assert_eq!(SqlType::Decimal.type_modifier(), -1);
+            assert_eq!(SqlType::Bool.type_modifier(), -1);
+        }
     }
 
     #[cfg(test)]
@@ -877,4 +1450,276 @@ mod tests {
             }
         }
     }
+
+    mod array {
+        use super::*;
+
+        #[cfg(test)]
+        mod serialization {
+            use super::*;
+
+            #[rstest::fixture]
+            fn serializer() -> Box<dyn Serializer> {
+                SqlType::Array(Box::new(SqlType::SmallInt(i16::MIN))).serializer()
+            }
+
+            #[rstest::rstest]
+            fn serialize(serializer: Box<dyn Serializer>) {
+                assert_eq!(serializer.ser("{1,NULL}"), vec![0, 0, 0, 2, 0, 1, 255, 255, 255, 255]);
+                assert_eq!(serializer.ser("{}"), Vec::<u8>::new());
+            }
+
+            #[rstest::rstest]
+            fn deserialize(serializer: Box<dyn Serializer>) {
+                assert_eq!(
+                    serializer.des(&[0, 0, 0, 2, 0, 1, 255, 255, 255, 255]),
+                    "{1,NULL}".to_owned()
+                );
+                assert_eq!(serializer.des(&[]), "{}".to_owned());
+            }
+
+            #[test]
+            fn quoted_strings() {
+                let serializer = SqlType::Array(Box::new(SqlType::VarChar(10))).serializer();
+                assert_eq!(
+                    serializer.des(&serializer.ser(r#"{ab, "c,d" ,"e\"f",""}"#)),
+                    r#"{ab,"c,d","e\"f",""}"#.to_owned()
+                );
+            }
+        }
+
+        #[cfg(test)]
+        mod validation {
+            use super::*;
+
+            #[rstest::fixture]
+            fn constraint() -> Box<dyn Constraint> {
+                SqlType::Array(Box::new(SqlType::SmallInt(i16::MIN))).constraint()
+            }
+
+            #[rstest::rstest]
+            fn elements_of_the_type(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("{1,2,3}"), Ok(()));
+                assert_eq!(constraint.validate("{ 1 , NULL }"), Ok(()));
+                assert_eq!(constraint.validate("{}"), Ok(()));
+            }
+
+            #[rstest::rstest]
+            fn element_of_other_type(constraint: Box<dyn Constraint>) {
+                assert_eq!(
+                    constraint.validate("{1,abc}"),
+                    Err(ConstraintError::TypeMismatch("abc".to_owned()))
+                );
+            }
+
+            #[rstest::rstest]
+            fn element_out_of_range(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("{32768}"), Err(ConstraintError::OutOfRange));
+            }
+
+            #[rstest::rstest]
+            fn not_an_array(constraint: Box<dyn Constraint>) {
+                assert_eq!(
+                    constraint.validate("1"),
+                    Err(ConstraintError::TypeMismatch("1".to_owned()))
+                );
+                assert_eq!(
+                    constraint.validate("{1,}"),
+                    Err(ConstraintError::TypeMismatch("{1,}".to_owned()))
+                );
+            }
+        }
+    }
+
+    mod json {
+        use super::*;
+
+        #[cfg(test)]
+        mod serialization {
+            use super::*;
+
+            #[rstest::fixture]
+            fn serializer() -> Box<dyn Serializer> {
+                SqlType::Json.serializer()
+            }
+
+            #[rstest::rstest]
+            fn serialize(serializer: Box<dyn Serializer>) {
+                assert_eq!(
+                    serializer.ser(r#" { "a" : [1, -2.5e+3, true, null] , "b c": "d e" } "#),
+                    br#"{"a":[1,-2.5e+3,true,null],"b c":"d e"}"#.to_vec()
+                );
+            }
+
+            #[rstest::rstest]
+            fn deserialize(serializer: Box<dyn Serializer>) {
+                assert_eq!(serializer.des(br#"{"a":1}"#), r#"{"a":1}"#.to_owned());
+            }
+        }
+
+        #[cfg(test)]
+        mod validation {
+            use super::*;
+
+            #[rstest::fixture]
+            fn constraint() -> Box<dyn Constraint> {
+                SqlType::Json.constraint()
+            }
+
+            #[rstest::rstest]
+            fn well_formed(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate(r#"{"a": {"b": []}}"#), Ok(()));
+                assert_eq!(constraint.validate(r#"["\"\\\u00e9", 0.5, {}]"#), Ok(()));
+                assert_eq!(constraint.validate("12"), Ok(()));
+                assert_eq!(constraint.validate("null"), Ok(()));
+            }
+
+            #[rstest::rstest]
+            fn malformed(constraint: Box<dyn Constraint>) {
+                for malformed in &[
+                    r#"{"a": 1"#,
+                    r#"{a: 1}"#,
+                    "[1,]",
+                    "01",
+                    "1.",
+                    "tru",
+                    r#""\x""#,
+                    "{} {}",
+                    "",
+                ] {
+                    assert_eq!(
+                        constraint.validate(malformed),
+                        Err(ConstraintError::TypeMismatch((*malformed).to_owned()))
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod timestamp {
+        use super::*;
+
+        #[cfg(test)]
+        mod serialization {
+            use super::*;
+
+            #[rstest::fixture]
+            fn serializer() -> Box<dyn Serializer> {
+                SqlType::Timestamp.serializer()
+            }
+
+            #[rstest::rstest]
+            fn date_only(serializer: Box<dyn Serializer>) {
+                assert_eq!(serializer.ser("2024-01-01"), b"2024-01-01 00:00:00".to_vec());
+            }
+
+            #[rstest::rstest]
+            fn date_and_time(serializer: Box<dyn Serializer>) {
+                assert_eq!(serializer.ser("2024-02-29T13:05"), b"2024-02-29 13:05:00".to_vec());
+                assert_eq!(
+                    serializer.ser(" 2024-12-31 23:59:59.250000 "),
+                    b"2024-12-31 23:59:59.25".to_vec()
+                );
+            }
+
+            #[rstest::rstest]
+            fn deserialize(serializer: Box<dyn Serializer>) {
+                assert_eq!(serializer.des(b"2024-01-01 00:00:00"), "2024-01-01 00:00:00".to_owned());
+            }
+        }
+
+        #[cfg(test)]
+        mod validation {
+            use super::*;
+
+            #[rstest::fixture]
+            fn constraint() -> Box<dyn Constraint> {
+                SqlType::Timestamp.constraint()
+            }
+
+            #[rstest::rstest]
+            fn valid(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate("2024-01-01"), Ok(()));
+                assert_eq!(constraint.validate("2000-02-29 00:00:00.000001"), Ok(()));
+            }
+
+            #[rstest::rstest]
+            fn invalid(constraint: Box<dyn Constraint>) {
+                for invalid in &[
+                    "2023-02-29",
+                    "2024-13-01",
+                    "2024-1-01",
+                    "2024-01-01 24:00",
+                    "2024-01-01 12:00:00.",
+                    "2024-01-01 12:00.5",
+                    "2024-01-01 12:00:00.1234567",
+                    "yesterday",
+                ] {
+                    assert_eq!(
+                        constraint.validate(invalid),
+                        Err(ConstraintError::TypeMismatch((*invalid).to_owned()))
+                    );
+                }
+            }
+        }
+    }
+
+    mod bytea {
+        use super::*;
+
+        #[cfg(test)]
+        mod serialization {
+            use super::*;
+
+            #[rstest::fixture]
+            fn serializer() -> Box<dyn Serializer> {
+                SqlType::Bytea.serializer()
+            }
+
+            #[rstest::rstest]
+            fn hex_format(serializer: Box<dyn Serializer>) {
+                assert_eq!(serializer.ser(r"\x48656C6c6f"), b"Hello".to_vec());
+                assert_eq!(serializer.ser(r"\x"), Vec::<u8>::new());
+            }
+
+            #[rstest::rstest]
+            fn escape_format(serializer: Box<dyn Serializer>) {
+                assert_eq!(serializer.ser(r"a\\b\000\377"), vec![b'a', b'\\', b'b', 0, 255]);
+            }
+
+            #[rstest::rstest]
+            fn deserialize(serializer: Box<dyn Serializer>) {
+                assert_eq!(serializer.des(&[0, 15, 255]), r"\x000fff".to_owned());
+                assert_eq!(serializer.des(&[]), r"\x".to_owned());
+            }
+        }
+
+        #[cfg(test)]
+        mod validation {
+            use super::*;
+
+            #[rstest::fixture]
+            fn constraint() -> Box<dyn Constraint> {
+                SqlType::Bytea.constraint()
+            }
+
+            #[rstest::rstest]
+            fn valid(constraint: Box<dyn Constraint>) {
+                assert_eq!(constraint.validate(r"\x4865 6c6c 6f"), Ok(()));
+                assert_eq!(constraint.validate(r"abc\\\001"), Ok(()));
+                assert_eq!(constraint.validate(""), Ok(()));
+            }
+
+            #[rstest::rstest]
+            fn invalid(constraint: Box<dyn Constraint>) {
+                for invalid in &[r"\x486", r"\xzz", r"\q", r"\400", r"\01", r"abc\"] {
+                    assert_eq!(
+                        constraint.validate(invalid),
+                        Err(ConstraintError::TypeMismatch((*invalid).to_owned()))
+                    );
+                }
+            }
+        }
+    }
 }